@@ -3,6 +3,9 @@
 //! Environment variables (set by CI or fall back to defaults):
 //! - UHC_VERSION: Version string (defaults to CARGO_PKG_VERSION)
 //! - UHC_GIT_SHA: Git commit SHA (defaults to "unknown" or git rev-parse)
+//!
+//! With the `grpc` feature, also generates the gRPC server code from
+//! `proto/unified_hifi.proto`.
 
 use std::process::Command;
 
@@ -23,6 +26,27 @@ fn main() {
     println!("cargo:rerun-if-env-changed=UHC_VERSION");
     println!("cargo:rerun-if-env-changed=UHC_GIT_SHA");
     println!("cargo:rerun-if-env-changed=GITHUB_SHA");
+
+    #[cfg(feature = "grpc")]
+    compile_protos();
+}
+
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    // Use the vendored protoc unless the caller supplies one
+    if std::env::var_os("PROTOC").is_none() {
+        if let Ok(protoc) = protoc_bin_vendored::protoc_bin_path() {
+            std::env::set_var("PROTOC", protoc);
+        }
+    }
+
+    println!("cargo:rerun-if-changed=proto/unified_hifi.proto");
+    if let Err(e) = tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/unified_hifi.proto"], &["proto"])
+    {
+        panic!("Failed to compile proto/unified_hifi.proto: {}", e);
+    }
 }
 
 fn get_git_sha() -> String {
@@ -15,6 +15,8 @@ const {
 const { version: VERSION } = require('../package.json');
 
 const BRIDGE_URL = process.env.HIFI_BRIDGE_URL || 'http://localhost:3000';
+// Admin token (or the remote access token), so locked zones stay controllable
+const BRIDGE_TOKEN = process.env.HIFI_BRIDGE_TOKEN;
 
 const SERVER_INSTRUCTIONS = `
 Unified Hi-Fi Control MCP Server - Control Your Music System
@@ -31,6 +33,7 @@ and HQPlayer Embedded audio processing. Use these tools when the user wants to:
 - **hifi_zones**: List all available playback zones. Start here to get zone IDs.
 - **hifi_now_playing**: Get current track, artist, album, play state, and volume for a zone.
 - **hifi_control**: Control playback (play, pause, next, previous) or adjust volume (volume_set, volume_up, volume_down).
+- **hifi_party**: Start party mode: the saved preset's zones are set to their volumes, grouped, and start playing together.
 
 ### Audio Pipeline (HQPlayer Embedded)
 - **hifi_hqplayer_status**: Check if HQPlayer is configured and get current pipeline settings.
@@ -38,6 +41,9 @@ and HQPlayer Embedded audio processing. Use these tools when the user wants to:
 - **hifi_hqplayer_load_profile**: Switch to a different profile (restarts HQPlayer).
 - **hifi_hqplayer_set_pipeline**: Change individual settings (filter, shaper, dither, etc).
 
+### Room Correction (MiniDSP)
+- **hifi_minidsp**: Show or change the preset, volume and mute of the MiniDSP linked to a zone.
+
 ### System Status
 - **hifi_status**: Get overall bridge status (Roon connection state, HQPlayer config).
 
@@ -46,7 +52,9 @@ and HQPlayer Embedded audio processing. Use these tools when the user wants to:
 1. **Starting a session**: Use \`hifi_zones\` to discover available zones, then \`hifi_now_playing\`
    to see what's playing. This gives context for subsequent commands.
 
-2. **Playback control**: Always use the zone_id from \`hifi_zones\`. Actions include:
+2. **Playback control**: Zone parameters take a zone_id from \`hifi_zones\` or the name the user
+   said ("kitchen", "the living room speaker"); names are matched against zone names, rooms and
+   aliases. If a name is ambiguous, the error lists the candidates to choose from. Actions include:
    play, pause, next, previous.
 
 3. **Volume adjustment**: Use \`hifi_control\` with these actions:
@@ -54,13 +62,24 @@ and HQPlayer Embedded audio processing. Use these tools when the user wants to:
    - \`volume_up\` with optional value: Increase volume (default +5)
    - \`volume_down\` with optional value: Decrease volume (default -5)
 
-4. **HQPlayer tweaking**: Check \`hifi_hqplayer_profiles\` for presets, or use
+4. **Party mode**: Use \`hifi_party\` when the user wants music everywhere. The zones, volumes
+   and playlist come from the preset saved in the bridge; pass \`playlist\` to play something else
+   on LMS players. Report any steps that failed.
+
+5. **HQPlayer tweaking**: Check \`hifi_hqplayer_profiles\` for presets, or use
    \`hifi_hqplayer_set_pipeline\` for fine-grained control of filters and shapers.
 
+6. **MiniDSP presets**: Call \`hifi_minidsp\` with just the zone to see the current preset, volume
+   and mute. Pass \`preset\` (0-3, so "preset 2" on the unit is 1), \`volume\` in dB (0 is full
+   scale, negative is quieter) or \`mute\` to change them. Zones without a linked MiniDSP fail
+   with NOT_LINKED.
+
 ## Prerequisites
 
 The unified-hifi-control bridge must be running (default: http://localhost:3000).
 Set HIFI_BRIDGE_URL environment variable if running elsewhere.
+Locked zones only take commands when HIFI_BRIDGE_TOKEN holds an admin token; otherwise
+control fails with ZONE_LOCKED, which you should pass on to the user.
 `.trim();
 
 async function apiFetch(path, options = {}) {
@@ -69,6 +88,7 @@ async function apiFetch(path, options = {}) {
     ...options,
     headers: {
       'Content-Type': 'application/json',
+      ...(BRIDGE_TOKEN ? { Authorization: `Bearer ${BRIDGE_TOKEN}` } : {}),
       ...options.headers,
     },
   });
@@ -79,6 +99,17 @@ async function apiFetch(path, options = {}) {
   return res.json();
 }
 
+// Accept a zone ID or a spoken name ("kitchen", "the living room speaker")
+async function resolveZone(zone) {
+  const data = await apiFetch(`/zones/resolve?name=${encodeURIComponent(zone)}`);
+  if (data.zone_id) return data.zone_id;
+  if (data.matches && data.matches.length > 0) {
+    const candidates = data.matches.map((m) => `${m.zone_name} (${m.zone_id})`).join(', ');
+    throw new Error(`"${zone}" matches more than one zone: ${candidates}`);
+  }
+  throw new Error(`No zone matches "${zone}"; use hifi_zones to list them`);
+}
+
 // Tool definitions
 const TOOLS = [
   {
@@ -92,7 +123,7 @@ const TOOLS = [
     inputSchema: {
       type: 'object',
       properties: {
-        zone_id: { type: 'string', description: 'The zone ID to query (get from hifi_zones)' },
+        zone_id: { type: 'string', description: 'Zone ID (from hifi_zones) or zone name, e.g. "kitchen"' },
       },
       required: ['zone_id'],
     },
@@ -103,7 +134,7 @@ const TOOLS = [
     inputSchema: {
       type: 'object',
       properties: {
-        zone_id: { type: 'string', description: 'The zone ID to control' },
+        zone_id: { type: 'string', description: 'Zone ID (from hifi_zones) or zone name, e.g. "kitchen"' },
         action: {
           type: 'string',
           description: 'Action: play (toggle play/pause), pause (toggle play/pause), next, previous, volume_set (absolute), volume_up (relative increase), volume_down (relative decrease)',
@@ -113,6 +144,17 @@ const TOOLS = [
       required: ['zone_id', 'action'],
     },
   },
+  {
+    name: 'hifi_party',
+    description: 'Start party mode: set the preset zones to their volumes, group them and start playback',
+    inputSchema: {
+      type: 'object',
+      properties: {
+        playlist: { type: 'string', description: 'Optional playlist for LMS players (URL, file path or playlist file), instead of the preset one' },
+      },
+      required: [],
+    },
+  },
   {
     name: 'hifi_hqplayer_status',
     description: 'Get HQPlayer Embedded status and current pipeline settings',
@@ -146,6 +188,20 @@ const TOOLS = [
       required: ['setting', 'value'],
     },
   },
+  {
+    name: 'hifi_minidsp',
+    description: 'Show or change the preset, volume and mute of the MiniDSP linked to a zone; omit the settings to just read them',
+    inputSchema: {
+      type: 'object',
+      properties: {
+        zone_id: { type: 'string', description: 'Zone ID (from hifi_zones) or zone name, e.g. "kitchen"' },
+        preset: { type: 'integer', minimum: 0, maximum: 3, description: 'Preset slot, 0-based (0-3)' },
+        volume: { type: 'number', maximum: 0, description: 'Master volume in dB (0 is full scale)' },
+        mute: { type: 'boolean', description: 'Mute or unmute the outputs' },
+      },
+      required: ['zone_id'],
+    },
+  },
   {
     name: 'hifi_status',
     description: 'Get overall bridge status (Roon connection, HQPlayer config)',
@@ -163,13 +219,14 @@ async function handleTool(name, args) {
       }
 
       case 'hifi_now_playing': {
-        const { zone_id } = args;
+        const zone_id = await resolveZone(args.zone_id);
         const data = await apiFetch(`/now_playing?zone_id=${encodeURIComponent(zone_id)}`);
         return { content: [{ type: 'text', text: JSON.stringify(data, null, 2) }] };
       }
 
       case 'hifi_control': {
-        const { zone_id, action, value } = args;
+        const { action, value } = args;
+        const zone_id = await resolveZone(args.zone_id);
         // Translate MCP actions to backend actions
         let backendAction = action;
         let backendValue = value;
@@ -209,6 +266,13 @@ async function handleTool(name, args) {
         return { content: [{ type: 'text', text: `Action "${action}" executed.\n\nCurrent state:\n${JSON.stringify(data, null, 2)}` }] };
       }
 
+      case 'hifi_party': {
+        const { playlist } = args;
+        const body = playlist ? { playlist } : {};
+        const data = await apiFetch('/scenes/party', { method: 'POST', body: JSON.stringify(body) });
+        return { content: [{ type: 'text', text: `Party mode started.\n\nSteps:\n${JSON.stringify(data, null, 2)}` }] };
+      }
+
       case 'hifi_hqplayer_status': {
         const [status, pipeline] = await Promise.all([
           apiFetch('/hqp/status'),
@@ -235,6 +299,20 @@ async function handleTool(name, args) {
         return { content: [{ type: 'text', text: `Setting "${setting}" updated to "${value}".\n\nCurrent pipeline:\n${JSON.stringify(pipeline, null, 2)}` }] };
       }
 
+      case 'hifi_minidsp': {
+        const { preset, volume, mute } = args;
+        const zone_id = await resolveZone(args.zone_id);
+        const path = `/minidsp/zones/${encodeURIComponent(zone_id)}`;
+        const body = {};
+        if (preset !== undefined) body.preset = Number(preset);
+        if (volume !== undefined) body.volume = Number(volume);
+        if (mute !== undefined) body.mute = Boolean(mute);
+        const data = Object.keys(body).length > 0
+          ? await apiFetch(path, { method: 'POST', body: JSON.stringify(body) })
+          : await apiFetch(path);
+        return { content: [{ type: 'text', text: JSON.stringify(data, null, 2) }] };
+      }
+
       case 'hifi_status': {
         const data = await apiFetch('/api/status');
         return { content: [{ type: 'text', text: JSON.stringify(data, null, 2) }] };
@@ -0,0 +1,286 @@
+//! Zone activity events
+//!
+//! Turns raw zone state changes into `zone_active` and `zone_idle` bus events
+//! for presence automations (lights, heating) that care whether someone is
+//! listening rather than about every pause or track change. A zone becomes
+//! active once it has played for `active_after_secs`, and idle once it has
+//! been stopped, paused or offline for `idle_after_secs`; playback resuming
+//! within the idle window cancels it. Both go out on the bus, so they reach
+//! `/events` and webhooks like any other event.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::bus::{BusEvent, PlaybackState, PrefixedZoneId, SharedBus};
+use crate::config::ActivityConfig;
+
+#[derive(Debug)]
+struct ZoneActivity {
+    zone_name: String,
+    title: Option<String>,
+    artist: Option<String>,
+    playing: bool,
+    /// When `playing` last changed
+    changed_at: Instant,
+    /// When the zone started playing, once it has been reported active
+    active_since: Option<Instant>,
+    removed: bool,
+}
+
+/// Debounced playing state per zone
+#[derive(Debug)]
+struct ActivityTracker {
+    active_after: Duration,
+    idle_after: Duration,
+    zones: HashMap<PrefixedZoneId, ZoneActivity>,
+}
+
+impl ActivityTracker {
+    fn new(config: &ActivityConfig) -> Self {
+        Self {
+            active_after: Duration::from_secs(config.active_after_secs),
+            idle_after: Duration::from_secs(config.idle_after_secs),
+            zones: HashMap::new(),
+        }
+    }
+
+    fn set_playing(
+        &mut self,
+        zone_id: &PrefixedZoneId,
+        zone_name: &str,
+        playing: bool,
+        now: Instant,
+    ) {
+        let zone = self
+            .zones
+            .entry(zone_id.clone())
+            .or_insert_with(|| ZoneActivity {
+                zone_name: String::new(),
+                title: None,
+                artist: None,
+                playing: false,
+                changed_at: now,
+                active_since: None,
+                removed: false,
+            });
+        zone.zone_name = zone_name.to_string();
+        zone.removed = false;
+        if zone.playing != playing {
+            zone.playing = playing;
+            zone.changed_at = now;
+        }
+    }
+
+    fn set_removed(&mut self, zone_id: &PrefixedZoneId, now: Instant) {
+        if let Some(zone) = self.zones.get_mut(zone_id) {
+            zone.removed = true;
+            if zone.playing {
+                zone.playing = false;
+                zone.changed_at = now;
+            }
+        }
+    }
+
+    /// Record the zone state carried by a bus event
+    fn observe(&mut self, event: &BusEvent, now: Instant) {
+        match event {
+            BusEvent::ZoneDiscovered { zone } => {
+                let Some(zone_id) = PrefixedZoneId::parse(&zone.zone_id) else {
+                    return;
+                };
+                let playing = zone.state == PlaybackState::Playing;
+                self.set_playing(&zone_id, &zone.zone_name, playing, now);
+                if let (Some(np), Some(activity)) =
+                    (&zone.now_playing, self.zones.get_mut(&zone_id))
+                {
+                    activity.title = Some(np.title.clone());
+                    activity.artist = Some(np.artist.clone());
+                }
+            }
+            BusEvent::ZoneUpdated {
+                zone_id,
+                display_name,
+                state,
+            } => {
+                let playing = PlaybackState::from(state.as_str()) == PlaybackState::Playing;
+                self.set_playing(zone_id, display_name, playing, now);
+            }
+            BusEvent::NowPlayingChanged {
+                zone_id,
+                title,
+                artist,
+                ..
+            } => {
+                if let Some(activity) = self.zones.get_mut(zone_id) {
+                    activity.title = title.clone();
+                    activity.artist = artist.clone();
+                }
+            }
+            BusEvent::ZoneRemoved { zone_id } => self.set_removed(zone_id, now),
+            BusEvent::ZonesFlushed { zone_ids, .. } => {
+                for zone_id in zone_ids.iter().filter_map(PrefixedZoneId::parse) {
+                    self.set_removed(&zone_id, now);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// When the next pending transition is due, if any
+    fn next_deadline(&self) -> Option<Instant> {
+        self.zones
+            .values()
+            .filter_map(|zone| match (zone.playing, zone.active_since) {
+                (true, None) => Some(zone.changed_at + self.active_after),
+                (false, Some(_)) => Some(zone.changed_at + self.idle_after),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Activity events for transitions due by `now`
+    fn due(&mut self, now: Instant) -> Vec<BusEvent> {
+        let mut events = Vec::new();
+        for (zone_id, zone) in &mut self.zones {
+            match (zone.playing, zone.active_since) {
+                (true, None) if now >= zone.changed_at + self.active_after => {
+                    zone.active_since = Some(zone.changed_at);
+                    events.push(BusEvent::ZoneActive {
+                        zone_id: zone_id.clone(),
+                        zone_name: zone.zone_name.clone(),
+                        title: zone.title.clone(),
+                        artist: zone.artist.clone(),
+                    });
+                }
+                (false, Some(since)) if now >= zone.changed_at + self.idle_after => {
+                    zone.active_since = None;
+                    events.push(BusEvent::ZoneIdle {
+                        zone_id: zone_id.clone(),
+                        zone_name: zone.zone_name.clone(),
+                        active_secs: zone.changed_at.duration_since(since).as_secs(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        self.zones
+            .retain(|_, zone| !zone.removed || zone.active_since.is_some());
+        events
+    }
+}
+
+/// Publish zone activity events until `shutdown`
+pub fn start(config: &ActivityConfig, bus: SharedBus, shutdown: CancellationToken) {
+    let mut tracker = ActivityTracker::new(config);
+    let mut rx = bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let deadline = tracker.next_deadline();
+            let expired = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = expired => {}
+                result = rx.recv() => match result {
+                    Ok(event) => tracker.observe(&event, Instant::now()),
+                    // The next state change for each zone corrects any missed
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                },
+            }
+            for event in tracker.due(Instant::now()) {
+                bus.publish(event);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> ActivityTracker {
+        ActivityTracker::new(&ActivityConfig {
+            active_after_secs: 10,
+            idle_after_secs: 60,
+        })
+    }
+
+    fn update(state: &str) -> BusEvent {
+        BusEvent::ZoneUpdated {
+            zone_id: PrefixedZoneId::lms("aa"),
+            display_name: "Kitchen".to_string(),
+            state: state.to_string(),
+        }
+    }
+
+    fn secs(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn zone_becomes_active_after_window() {
+        let start = Instant::now();
+        let mut tracker = tracker();
+        tracker.observe(&update("playing"), start);
+        assert_eq!(tracker.next_deadline(), Some(secs(start, 10)));
+        assert!(tracker.due(secs(start, 9)).is_empty());
+
+        let events = tracker.due(secs(start, 10));
+        assert!(matches!(
+            events.as_slice(),
+            [BusEvent::ZoneActive { zone_id, zone_name, .. }]
+                if zone_id.as_str() == "lms:aa" && zone_name == "Kitchen"
+        ));
+        assert!(tracker.due(secs(start, 20)).is_empty());
+        assert_eq!(tracker.next_deadline(), None);
+    }
+
+    #[test]
+    fn short_playback_and_brief_pauses_are_ignored() {
+        let start = Instant::now();
+        let mut tracker = tracker();
+        tracker.observe(&update("playing"), start);
+        tracker.observe(&update("stopped"), secs(start, 5));
+        assert!(tracker.due(secs(start, 30)).is_empty());
+
+        tracker.observe(&update("playing"), secs(start, 100));
+        assert_eq!(tracker.due(secs(start, 110)).len(), 1);
+        tracker.observe(&update("paused"), secs(start, 200));
+        tracker.observe(&update("playing"), secs(start, 230));
+        assert!(tracker.due(secs(start, 300)).is_empty());
+    }
+
+    #[test]
+    fn zone_goes_idle_after_window() {
+        let start = Instant::now();
+        let mut tracker = tracker();
+        tracker.observe(&update("playing"), start);
+        tracker.due(secs(start, 10));
+        tracker.observe(
+            &BusEvent::ZoneRemoved {
+                zone_id: PrefixedZoneId::lms("aa"),
+            },
+            secs(start, 100),
+        );
+        assert_eq!(tracker.next_deadline(), Some(secs(start, 160)));
+
+        let events = tracker.due(secs(start, 160));
+        assert!(matches!(
+            events.as_slice(),
+            [BusEvent::ZoneIdle {
+                active_secs: 100,
+                ..
+            }]
+        ));
+        assert!(tracker.zones.is_empty());
+    }
+}
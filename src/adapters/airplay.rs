@@ -0,0 +1,387 @@
+//! AirPlay adapter - shows AirPlay and AirPlay 2 receivers as zones
+//!
+//! Receivers are found over mDNS (`_airplay._tcp` and `_raop._tcp`) and
+//! listed as read-only zones, so knobs and the UI know they exist. These
+//! zones have no now playing, no volume control, and reject commands. Plain
+//! AirPlay speakers only report to the device streaming to them; Apple TV and
+//! HomePod also serve now playing and volume over the Media Remote Protocol,
+//! but that needs HomeKit pairing and an encrypted session the bridge doesn't
+//! implement.
+//!
+//! A receiver usually advertises both services. They are merged by device id:
+//! the `deviceid` TXT record of `_airplay._tcp` and the MAC prefix of the
+//! `_raop._tcp` instance name (`AABBCCDDEEFF@Living Room`).
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{BusEvent, PlaybackState, PrefixedZoneId, SharedBus, Zone, ZoneCapabilities};
+use crate::clock::unix_millis;
+
+const AIRPLAY_SERVICE: &str = "_airplay._tcp.local.";
+const RAOP_SERVICE: &str = "_raop._tcp.local.";
+
+/// AirPlay receiver, merged from its advertised services
+#[derive(Debug, Clone, Serialize)]
+pub struct AirPlayDevice {
+    /// Lowercase MAC-style id without separators, e.g. `aabbccddeeff`
+    pub id: String,
+    pub name: String,
+    /// Model from the TXT records, e.g. `AudioAccessory5,1`
+    pub model: Option<String>,
+    /// Advertised `_airplay._tcp` (AirPlay 2 receivers and Apple TVs do)
+    pub airplay2: bool,
+    /// Full mDNS names of the services this receiver advertises
+    #[serde(skip)]
+    services: BTreeSet<String>,
+}
+
+/// AirPlay adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct AirPlayStatus {
+    pub connected: bool,
+    pub device_count: usize,
+    pub devices: Vec<AirPlayDevice>,
+}
+
+#[derive(Default)]
+struct AirPlayState {
+    devices: HashMap<String, AirPlayDevice>,
+    running: bool,
+}
+
+/// AirPlay adapter for discovering AirPlay receivers
+#[derive(Clone)]
+pub struct AirPlayAdapter {
+    state: Arc<RwLock<AirPlayState>>,
+    bus: SharedBus,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+}
+
+impl AirPlayAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        Self {
+            state: Arc::new(RwLock::new(AirPlayState::default())),
+            bus,
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+        }
+    }
+
+    /// Start mDNS browsing (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("AirPlay adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+
+        let mut state = self.state.write().await;
+        state.running = false;
+        state.devices.clear();
+        tracing::info!("AirPlay adapter stopped");
+    }
+
+    /// Get adapter status
+    pub async fn get_status(&self) -> AirPlayStatus {
+        let state = self.state.read().await;
+        let mut devices: Vec<AirPlayDevice> = state.devices.values().cloned().collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        AirPlayStatus {
+            connected: !devices.is_empty(),
+            device_count: devices.len(),
+            devices,
+        }
+    }
+
+    /// Record a resolved service and announce its receiver
+    async fn service_resolved(&self, fullname: &str, txt: &HashMap<String, String>) {
+        let Some(found) = parse_service(fullname, txt) else {
+            return;
+        };
+        let zone = {
+            let mut state = self.state.write().await;
+            let device = state
+                .devices
+                .entry(found.id.clone())
+                .or_insert_with(|| AirPlayDevice {
+                    id: found.id.clone(),
+                    name: found.name.clone(),
+                    model: None,
+                    airplay2: false,
+                    services: BTreeSet::new(),
+                });
+            let before = device.clone();
+            device.services.insert(fullname.to_string());
+            // The AirPlay service carries the name set on the receiver; RAOP's
+            // is only used until that one shows up
+            if found.airplay2 {
+                device.name = found.name;
+                device.airplay2 = true;
+            }
+            device.model = device.model.take().or(found.model);
+            let changed = before.services.is_empty()
+                || before.name != device.name
+                || before.model != device.model;
+            changed.then(|| airplay_device_to_zone(device))
+        };
+        if let Some(zone) = zone {
+            tracing::info!(
+                "Discovered AirPlay receiver: {} ({})",
+                zone.zone_name,
+                zone.zone_id
+            );
+            self.bus.publish(BusEvent::ZoneDiscovered { zone });
+        }
+    }
+
+    /// Forget a service; the receiver goes once none of its services remain
+    async fn service_removed(&self, fullname: &str) {
+        let removed = {
+            let mut state = self.state.write().await;
+            let mut gone = None;
+            for device in state.devices.values_mut() {
+                if device.services.remove(fullname) {
+                    if device.services.is_empty() {
+                        gone = Some(device.id.clone());
+                    }
+                    break;
+                }
+            }
+            gone.and_then(|id| state.devices.remove(&id))
+        };
+        if let Some(device) = removed {
+            tracing::info!("AirPlay receiver gone: {}", device.name);
+            self.bus.publish(BusEvent::ZoneRemoved {
+                zone_id: PrefixedZoneId::airplay(&device.id),
+            });
+        }
+    }
+}
+
+crate::impl_startable!(AirPlayAdapter, "airplay");
+
+#[async_trait]
+impl AdapterLogic for AirPlayAdapter {
+    fn prefix(&self) -> &'static str {
+        "airplay"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        self.state.write().await.running = true;
+
+        let mdns = ServiceDaemon::new()?;
+        let airplay = mdns.browse(AIRPLAY_SERVICE)?;
+        let raop = mdns.browse(RAOP_SERVICE)?;
+
+        loop {
+            let event = tokio::select! {
+                _ = ctx.shutdown.cancelled() => break,
+                event = airplay.recv_async() => event,
+                event = raop.recv_async() => event,
+            };
+            let Ok(event) = event else {
+                // Daemon gone; let the handle restart us
+                let _ = mdns.shutdown();
+                return Err(anyhow!("mDNS browse ended"));
+            };
+            match event {
+                ServiceEvent::ServiceResolved(service) => {
+                    let txt: HashMap<String, String> = service
+                        .txt_properties
+                        .iter()
+                        .map(|p| (p.key().to_ascii_lowercase(), p.val_str().to_string()))
+                        .collect();
+                    self.service_resolved(&service.fullname, &txt).await;
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    self.service_removed(&fullname).await;
+                }
+                _ => {}
+            }
+        }
+
+        let _ = mdns.shutdown();
+        {
+            let mut state = self.state.write().await;
+            state.running = false;
+            state.devices.clear();
+        }
+        tracing::info!("AirPlay adapter stopped");
+        Ok(())
+    }
+
+    async fn handle_command(
+        &self,
+        _zone_id: &str,
+        _command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        Ok(AdapterCommandResponse {
+            success: false,
+            error: Some("AirPlay zones are read-only".to_string()),
+        })
+    }
+}
+
+/// What one advertised service says about its receiver
+#[derive(Debug, PartialEq)]
+struct FoundService {
+    id: String,
+    name: String,
+    model: Option<String>,
+    /// From `_airplay._tcp` rather than `_raop._tcp`
+    airplay2: bool,
+}
+
+/// Identify the receiver behind a resolved `_airplay._tcp` or `_raop._tcp`
+/// service; `None` for other services or ones without a usable id
+fn parse_service(fullname: &str, txt: &HashMap<String, String>) -> Option<FoundService> {
+    if let Some(instance) = fullname.strip_suffix(&format!(".{}", AIRPLAY_SERVICE)) {
+        let id = txt
+            .get("deviceid")
+            .map(|id| normalize_id(id))
+            .filter(|id| !id.is_empty())?;
+        return Some(FoundService {
+            id,
+            name: unescape(instance),
+            model: txt.get("model").cloned(),
+            airplay2: true,
+        });
+    }
+    let instance = fullname.strip_suffix(&format!(".{}", RAOP_SERVICE))?;
+    let (mac, name) = instance.split_once('@')?;
+    let id = normalize_id(mac);
+    if id.is_empty() {
+        return None;
+    }
+    Some(FoundService {
+        id,
+        name: unescape(name),
+        model: txt.get("am").cloned(),
+        airplay2: false,
+    })
+}
+
+/// `AA:BB:CC:DD:EE:FF` or `AABBCCDDEEFF` as `aabbccddeeff`
+fn normalize_id(id: &str) -> String {
+    id.chars()
+        .filter(char::is_ascii_hexdigit)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Instance names escape dots and backslashes (`Dave\.s Room`)
+fn unescape(name: &str) -> String {
+    name.replace("\\.", ".").replace("\\\\", "\\")
+}
+
+fn airplay_device_to_zone(device: &AirPlayDevice) -> Zone {
+    Zone {
+        zone_id: PrefixedZoneId::airplay(&device.id).to_string(),
+        zone_name: device.name.clone(),
+        state: PlaybackState::Unknown,
+        volume_control: None,
+        now_playing: None,
+        source: "airplay".to_string(),
+        is_controllable: false,
+        is_seekable: false,
+        capabilities: ZoneCapabilities::default(),
+        volume_leveling: None,
+        last_updated: unix_millis(),
+        is_play_allowed: false,
+        is_pause_allowed: false,
+        is_next_allowed: false,
+        is_previous_allowed: false,
+        metadata: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn txt(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_services_merge_by_id() {
+        let airplay = parse_service(
+            "Kitchen\\.Pod._airplay._tcp.local.",
+            &txt(&[
+                ("deviceid", "AA:BB:CC:DD:EE:FF"),
+                ("model", "AudioAccessory5,1"),
+            ]),
+        )
+        .unwrap();
+        let raop = parse_service(
+            "AABBCCDDEEFF@Kitchen.Pod._raop._tcp.local.",
+            &txt(&[("am", "AudioAccessory5,1")]),
+        )
+        .unwrap();
+        assert_eq!(airplay.id, "aabbccddeeff");
+        assert_eq!(airplay.id, raop.id);
+        assert_eq!(airplay.name, "Kitchen.Pod");
+        assert!(airplay.airplay2);
+        assert!(!raop.airplay2);
+        assert_eq!(raop.model.as_deref(), Some("AudioAccessory5,1"));
+
+        assert_eq!(
+            parse_service("Kitchen._airplay._tcp.local.", &txt(&[])),
+            None
+        );
+        assert_eq!(
+            parse_service("Kitchen._roonknob._tcp.local.", &txt(&[])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_zone_is_read_only() {
+        let device = AirPlayDevice {
+            id: "aabbccddeeff".to_string(),
+            name: "Kitchen".to_string(),
+            model: None,
+            airplay2: true,
+            services: BTreeSet::new(),
+        };
+        let zone = airplay_device_to_zone(&device);
+        assert_eq!(zone.zone_id, "airplay:aabbccddeeff");
+        assert!(!zone.is_controllable);
+        assert!(zone.volume_control.is_none());
+    }
+}
@@ -0,0 +1,419 @@
+//! CamillaDSP control over its WebSocket interface
+//!
+//! CamillaDSP isn't a zone source. As with HQPlayer, a zone is linked to the
+//! CamillaDSP instance that processes its audio, and the linked zone's DSP
+//! volume, mute and config (filter preset) are then controlled through
+//! `/camilladsp/zones/{zone_id}`. Instances, with the config files offered as
+//! presets, and zone links are kept in `camilladsp-config.json`.
+//!
+//! CamillaDSP must run with its websocket server enabled (`-p <port>`). Each
+//! operation opens a short connection, sends its commands in order and reads
+//! one reply per command.
+
+use anyhow::{anyhow, bail, Result};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::adapters::ops;
+use crate::config::{get_config_file_path, read_config_file};
+
+const CAMILLADSP_CONFIG_FILE: &str = "camilladsp-config.json";
+const DEFAULT_PORT: u16 = 1234;
+/// Connecting plus all replies of one operation
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// CamillaDSP's lowest volume
+pub const MIN_VOLUME_DB: f64 = -150.0;
+/// Volumes are capped at unity gain so a stray request can't boost the output
+pub const MAX_VOLUME_DB: f64 = 0.0;
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(CAMILLADSP_CONFIG_FILE)
+}
+
+/// A config file on the CamillaDSP host offered as a preset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CamillaPreset {
+    pub name: String,
+    /// Path of the config file, as CamillaDSP sees it
+    pub path: String,
+}
+
+/// A CamillaDSP websocket server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CamillaInstance {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub presets: Vec<CamillaPreset>,
+}
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SavedCamillaConfig {
+    #[serde(default)]
+    instances: Vec<CamillaInstance>,
+    /// zone_id -> instance name
+    #[serde(default)]
+    links: HashMap<String, String>,
+}
+
+/// Zone link info for API responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CamillaZoneLink {
+    pub zone_id: String,
+    pub instance: String,
+}
+
+/// State of a linked instance
+#[derive(Debug, Clone, Serialize)]
+pub struct CamillaStatus {
+    pub instance: String,
+    /// Processing state reported by CamillaDSP (`Running`, `Paused`, `Inactive`, ...)
+    pub state: String,
+    /// Main volume in dB
+    pub volume: f64,
+    pub mute: bool,
+    /// Config file in use
+    pub config_path: Option<String>,
+    /// Preset whose file is in use, if any
+    pub preset: Option<String>,
+    /// Names of the instance's presets
+    pub presets: Vec<String>,
+}
+
+/// Name of a websocket command (`"GetVolume"` or `{"SetVolume": -20.0}`)
+fn command_name(command: &Value) -> &str {
+    match command {
+        Value::String(name) => name,
+        Value::Object(map) => map.keys().next().map(String::as_str).unwrap_or_default(),
+        _ => "",
+    }
+}
+
+/// The `value` of a reply (`{"GetVolume": {"result": "Ok", "value": -20.0}}`)
+fn reply_value(name: &str, reply: &Value) -> Result<Value> {
+    let body = reply
+        .get(name)
+        .ok_or_else(|| anyhow!("Unexpected CamillaDSP reply to {}", name))?;
+    match body["result"].as_str() {
+        Some("Ok") => Ok(body.get("value").cloned().unwrap_or(Value::Null)),
+        Some(result) => bail!("CamillaDSP {} failed: {}", name, result),
+        None => bail!("Unexpected CamillaDSP reply to {}", name),
+    }
+}
+
+/// Send `commands` on one connection and return each reply's value
+async fn request(instance: &CamillaInstance, commands: &[Value]) -> Result<Vec<Value>> {
+    let url = format!("ws://{}:{}", instance.host, instance.port);
+    let exchange = async {
+        let (ws, _) = tokio_tungstenite::connect_async(&url).await?;
+        let (mut sink, mut stream) = ws.split();
+        let mut values = Vec::with_capacity(commands.len());
+        for command in commands {
+            sink.send(Message::text(command.to_string())).await?;
+            let text = loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => break text,
+                    Some(Ok(Message::Close(_))) | None => {
+                        bail!("CamillaDSP closed the connection")
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                }
+            };
+            let reply: Value = serde_json::from_str(&text)?;
+            values.push(reply_value(command_name(command), &reply)?);
+        }
+        let _ = sink.close().await;
+        Ok(values)
+    };
+    tokio::time::timeout(REQUEST_TIMEOUT, exchange)
+        .await
+        .map_err(|_| anyhow!("Timed out talking to CamillaDSP at {}", url))?
+}
+
+/// Service for CamillaDSP instances and the zones linked to them
+pub struct CamillaDspService {
+    config: Arc<RwLock<SavedCamillaConfig>>,
+}
+
+impl Default for CamillaDspService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CamillaDspService {
+    pub fn new() -> Self {
+        let service = Self {
+            config: Arc::new(RwLock::new(SavedCamillaConfig::default())),
+        };
+        service.load_config_sync();
+        service
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(CAMILLADSP_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedCamillaConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut config) = self.config.try_write() {
+                    tracing::info!(
+                        "Loaded {} CamillaDSP instance(s) and {} zone link(s) from disk",
+                        saved.instances.len(),
+                        saved.links.len()
+                    );
+                    *config = saved;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse CamillaDSP config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let config = self.config.read().await.clone();
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&config) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save CamillaDSP config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize CamillaDSP config: {}", e),
+        }
+    }
+
+    /// All configured instances
+    pub async fn list_instances(&self) -> Vec<CamillaInstance> {
+        self.config.read().await.instances.clone()
+    }
+
+    /// Add an instance, or replace the one with the same name
+    pub async fn set_instance(&self, instance: CamillaInstance) -> Result<()> {
+        if instance.name.trim().is_empty() || instance.host.trim().is_empty() {
+            bail!("name and host are required");
+        }
+        if instance
+            .presets
+            .iter()
+            .any(|p| p.name.trim().is_empty() || p.path.trim().is_empty())
+        {
+            bail!("every preset needs a name and a path");
+        }
+        {
+            let mut config = self.config.write().await;
+            match config
+                .instances
+                .iter_mut()
+                .find(|i| i.name == instance.name)
+            {
+                Some(existing) => *existing = instance,
+                None => config.instances.push(instance),
+            }
+        }
+        self.save_config().await;
+        Ok(())
+    }
+
+    /// Remove an instance and the links to it; false if there was none
+    pub async fn remove_instance(&self, name: &str) -> bool {
+        let removed = {
+            let mut config = self.config.write().await;
+            let before = config.instances.len();
+            config.instances.retain(|i| i.name != name);
+            let removed = config.instances.len() != before;
+            if removed {
+                config.links.retain(|_, instance| instance != name);
+            }
+            removed
+        };
+        if removed {
+            self.save_config().await;
+            tracing::info!("Removed CamillaDSP instance {}", name);
+        }
+        removed
+    }
+
+    /// Link a zone to an instance
+    pub async fn link_zone(&self, zone_id: String, instance_name: String) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            if !config.instances.iter().any(|i| i.name == instance_name) {
+                bail!("Unknown CamillaDSP instance: {}", instance_name);
+            }
+            config.links.insert(zone_id.clone(), instance_name.clone());
+        }
+        self.save_config().await;
+        tracing::info!(
+            "Zone {} linked to CamillaDSP instance {}",
+            zone_id,
+            instance_name
+        );
+        Ok(())
+    }
+
+    /// Unlink a zone; false if it wasn't linked
+    pub async fn unlink_zone(&self, zone_id: &str) -> bool {
+        let was_linked = self.config.write().await.links.remove(zone_id).is_some();
+        if was_linked {
+            self.save_config().await;
+            tracing::info!("Zone {} unlinked from CamillaDSP", zone_id);
+        }
+        was_linked
+    }
+
+    /// All zone links
+    pub async fn get_links(&self) -> Vec<CamillaZoneLink> {
+        let config = self.config.read().await;
+        let mut links: Vec<CamillaZoneLink> = config
+            .links
+            .iter()
+            .map(|(zone_id, instance)| CamillaZoneLink {
+                zone_id: zone_id.clone(),
+                instance: instance.clone(),
+            })
+            .collect();
+        links.sort_by(|a, b| a.zone_id.cmp(&b.zone_id));
+        links
+    }
+
+    /// The instance a zone is linked to
+    pub async fn instance_for_zone(&self, zone_id: &str) -> Option<CamillaInstance> {
+        let config = self.config.read().await;
+        let name = config.links.get(zone_id)?;
+        config.instances.iter().find(|i| &i.name == name).cloned()
+    }
+
+    /// Read an instance's state, volume, mute and config file
+    pub async fn status(&self, instance: &CamillaInstance) -> Result<CamillaStatus> {
+        let commands = [
+            json!("GetState"),
+            json!("GetVolume"),
+            json!("GetMute"),
+            json!("GetConfigFilePath"),
+        ];
+        let values = ops::timed(
+            "camilladsp",
+            "status",
+            Some(instance.name.as_str()),
+            request(instance, &commands),
+        )
+        .await?;
+        let config_path = values[3].as_str().map(str::to_string);
+        Ok(CamillaStatus {
+            instance: instance.name.clone(),
+            state: values[0].as_str().unwrap_or("Unknown").to_string(),
+            volume: values[1].as_f64().unwrap_or(MIN_VOLUME_DB),
+            mute: values[2].as_bool().unwrap_or(false),
+            preset: instance
+                .presets
+                .iter()
+                .find(|p| Some(&p.path) == config_path.as_ref())
+                .map(|p| p.name.clone()),
+            config_path,
+            presets: instance.presets.iter().map(|p| p.name.clone()).collect(),
+        })
+    }
+
+    /// Set the main volume in dB, clamped to `MIN_VOLUME_DB..=MAX_VOLUME_DB`
+    pub async fn set_volume(&self, instance: &CamillaInstance, db: f64) -> Result<()> {
+        let db = db.clamp(MIN_VOLUME_DB, MAX_VOLUME_DB);
+        ops::timed(
+            "camilladsp",
+            "volume",
+            Some(instance.name.as_str()),
+            request(instance, &[json!({ "SetVolume": db })]),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_mute(&self, instance: &CamillaInstance, mute: bool) -> Result<()> {
+        ops::timed(
+            "camilladsp",
+            "mute",
+            Some(instance.name.as_str()),
+            request(instance, &[json!({ "SetMute": mute })]),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Switch to a preset's config file and reload it
+    pub async fn set_preset(&self, instance: &CamillaInstance, preset: &str) -> Result<()> {
+        let preset = instance
+            .presets
+            .iter()
+            .find(|p| p.name == preset)
+            .ok_or_else(|| anyhow!("Unknown preset: {}", preset))?;
+        ops::timed(
+            "camilladsp",
+            "preset",
+            Some(instance.name.as_str()),
+            request(
+                instance,
+                &[json!({ "SetConfigFilePath": preset.path }), json!("Reload")],
+            ),
+        )
+        .await?;
+        tracing::info!(
+            "CamillaDSP instance {} switched to preset {}",
+            instance.name,
+            preset.name
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(command_name(&json!("GetVolume")), "GetVolume");
+        assert_eq!(command_name(&json!({ "SetVolume": -20.0 })), "SetVolume");
+    }
+
+    #[test]
+    fn test_reply_value() {
+        let reply = json!({ "GetVolume": { "result": "Ok", "value": -23.5 } });
+        assert_eq!(reply_value("GetVolume", &reply).unwrap(), json!(-23.5));
+
+        let reply = json!({ "SetMute": { "result": "Ok" } });
+        assert_eq!(reply_value("SetMute", &reply).unwrap(), Value::Null);
+
+        let reply = json!({ "SetConfigFilePath": { "result": "InvalidConfigError" } });
+        let err = reply_value("SetConfigFilePath", &reply).unwrap_err();
+        assert!(err.to_string().contains("InvalidConfigError"));
+
+        assert!(reply_value("GetMute", &json!({ "Invalid": {} })).is_err());
+    }
+
+    #[test]
+    fn test_instance_defaults() {
+        let instance: CamillaInstance =
+            serde_json::from_value(json!({ "name": "living", "host": "dsp.local" })).unwrap();
+        assert_eq!(instance.port, DEFAULT_PORT);
+        assert!(instance.presets.is_empty());
+    }
+}
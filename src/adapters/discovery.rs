@@ -0,0 +1,264 @@
+//! Multicast discovery on selected network interfaces
+//!
+//! On multi-homed hosts (e.g. Docker with a macvlan and a bridge network) the
+//! OS default route may not be the network the devices are on. When
+//! `discovery_interfaces` is configured, UPnP/OpenHome SSDP searches and
+//! HQPlayer discovery send from a socket bound to each listed interface's IPv4
+//! address, which makes Linux send the multicast out of that interface.
+//! Unset, a single socket on `0.0.0.0` is used as before.
+
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use tokio::net::UdpSocket;
+
+/// SSDP multicast group
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// Configured interfaces (names or addresses); empty means the OS default
+static INTERFACES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Set the discovery interfaces from a comma-separated list of interface names
+/// or IPv4 addresses. Only the first call takes effect.
+pub fn set_interfaces(spec: &str) {
+    let interfaces: Vec<String> = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect();
+    if !interfaces.is_empty() {
+        tracing::info!("Discovery interfaces: {}", interfaces.join(", "));
+    }
+    if INTERFACES.set(interfaces).is_err() {
+        tracing::warn!("Discovery interfaces already set, ignoring");
+    }
+}
+
+/// Configured discovery interfaces (empty when unset)
+pub fn configured_interfaces() -> &'static [String] {
+    INTERFACES.get().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// IPv4 addresses to send discovery from: one per configured interface that
+/// resolves, or `0.0.0.0` when none are configured or none resolve
+pub fn bind_addrs() -> Vec<Ipv4Addr> {
+    let configured = configured_interfaces();
+    let mut addrs = Vec::new();
+    for iface in configured {
+        match resolve_ipv4(iface) {
+            Ok(ip) if !addrs.contains(&ip) => addrs.push(ip),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Discovery interface {} unavailable: {}", iface, e),
+        }
+    }
+    if addrs.is_empty() {
+        if !configured.is_empty() {
+            tracing::warn!("No discovery interface resolved, using the default route");
+        }
+        addrs.push(Ipv4Addr::UNSPECIFIED);
+    }
+    addrs
+}
+
+fn resolve_ipv4(iface: &str) -> Result<Ipv4Addr> {
+    if let Ok(ip) = iface.parse::<Ipv4Addr>() {
+        return Ok(ip);
+    }
+    if_addrs::get_if_addrs()?
+        .into_iter()
+        .filter(|i| i.name == iface)
+        .find_map(|i| match i.ip() {
+            IpAddr::V4(v4) => Some(v4),
+            IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| anyhow!("no IPv4 address on {}", iface))
+}
+
+/// Bind one UDP socket per discovery address. Fails only if none could be bound.
+pub async fn bind_sockets() -> Result<Vec<UdpSocket>> {
+    let mut sockets = Vec::new();
+    let mut last_err = None;
+    for ip in bind_addrs() {
+        match UdpSocket::bind((ip, 0)).await {
+            Ok(socket) => {
+                if let Err(e) = socket.set_multicast_ttl_v4(2) {
+                    tracing::debug!("Failed to set multicast TTL on {}: {}", ip, e);
+                }
+                sockets.push(socket);
+            }
+            Err(e) => {
+                tracing::warn!("Cannot bind discovery socket on {}: {}", ip, e);
+                last_err = Some(e);
+            }
+        }
+    }
+    match (sockets.is_empty(), last_err) {
+        (true, Some(e)) => Err(e.into()),
+        _ => Ok(sockets),
+    }
+}
+
+/// Send `payload` to `dest` from every socket, then collect replies until
+/// `timeout`, returning (payload, sender) pairs
+pub async fn multicast_exchange(
+    sockets: &[UdpSocket],
+    dest: SocketAddrV4,
+    payload: &[u8],
+    timeout: Duration,
+) -> Vec<(String, IpAddr)> {
+    for socket in sockets {
+        if let Err(e) = socket.send_to(payload, dest).await {
+            tracing::debug!("Discovery send to {} failed: {}", dest, e);
+        }
+    }
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    let receivers = sockets.iter().map(|socket| async move {
+        let mut replies = Vec::new();
+        let mut buf = [0u8; 2048];
+        while let Ok(Ok((len, from))) =
+            tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+        {
+            replies.push((String::from_utf8_lossy(&buf[..len]).into_owned(), from.ip()));
+        }
+        replies
+    });
+    futures::future::join_all(receivers)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// A device's answer to an SSDP M-SEARCH
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsdpResponse {
+    pub location: String,
+    pub usn: String,
+    pub st: String,
+}
+
+/// SSDP M-SEARCH for `target` on every discovery interface, collecting unique
+/// responses for `timeout`
+pub async fn ssdp_search(target: &str, timeout: Duration, mx: u8) -> Result<Vec<SsdpResponse>> {
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: {}\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         MX: {}\r\n\
+         ST: {}\r\n\r\n",
+        SSDP_ADDR, mx, target
+    );
+    let sockets = bind_sockets().await?;
+    let replies = multicast_exchange(&sockets, SSDP_ADDR, request.as_bytes(), timeout).await;
+
+    let mut seen = HashSet::new();
+    Ok(replies
+        .iter()
+        .filter_map(|(reply, _)| parse_ssdp_response(reply))
+        .filter(|r| seen.insert((r.usn.clone(), r.location.clone())))
+        .collect())
+}
+
+/// Parse an SSDP search response; `None` unless it has LOCATION and USN
+fn parse_ssdp_response(reply: &str) -> Option<SsdpResponse> {
+    let mut lines = reply.lines();
+    if !lines.next()?.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+    let (mut location, mut usn, mut st) = (None, None, String::new());
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "location" => location = Some(value),
+            "usn" => usn = Some(value),
+            "st" => st = value,
+            _ => {}
+        }
+    }
+    Some(SsdpResponse {
+        location: location?,
+        usn: usn?,
+        st,
+    })
+}
+
+/// A detected network interface address
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub ip: IpAddr,
+    pub loopback: bool,
+    /// Discovery sends from this address
+    pub selected: bool,
+}
+
+/// Detected interface addresses, marking the ones discovery uses
+pub fn list_interfaces() -> Vec<NetworkInterface> {
+    let selected = bind_addrs();
+    match if_addrs::get_if_addrs() {
+        Ok(interfaces) => interfaces
+            .into_iter()
+            .map(|iface| {
+                let ip = iface.ip();
+                NetworkInterface {
+                    loopback: iface.is_loopback(),
+                    selected: matches!(ip, IpAddr::V4(v4) if selected.contains(&v4)),
+                    name: iface.name,
+                    ip,
+                }
+            })
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to list network interfaces: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ssdp_response() {
+        let reply = "HTTP/1.1 200 OK\r\n\
+                     CACHE-CONTROL: max-age=1800\r\n\
+                     Location: http://192.168.1.20:49152/description.xml\r\n\
+                     ST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\
+                     USN: uuid:abcd::urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n";
+        let response = parse_ssdp_response(reply).unwrap();
+        assert_eq!(
+            response.location,
+            "http://192.168.1.20:49152/description.xml"
+        );
+        assert_eq!(
+            response.usn,
+            "uuid:abcd::urn:schemas-upnp-org:device:MediaRenderer:1"
+        );
+        assert_eq!(response.st, "urn:schemas-upnp-org:device:MediaRenderer:1");
+    }
+
+    #[test]
+    fn test_parse_ssdp_rejects_notify_and_incomplete() {
+        assert!(parse_ssdp_response("NOTIFY * HTTP/1.1\r\nLOCATION: x\r\nUSN: y\r\n").is_none());
+        assert!(parse_ssdp_response("HTTP/1.1 200 OK\r\nUSN: uuid:x\r\n").is_none());
+    }
+
+    #[test]
+    fn test_resolve_ipv4_literal() {
+        assert_eq!(
+            resolve_ipv4("192.168.1.5").unwrap(),
+            Ipv4Addr::new(192, 168, 1, 5)
+        );
+        assert!(resolve_ipv4("no-such-interface0").is_err());
+    }
+}
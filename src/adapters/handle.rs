@@ -2,9 +2,14 @@
 //!
 //! Provides automatic retry with exponential backoff when adapters encounter errors.
 //! All retry logic is centralized here - adapters should NOT implement their own retry loops.
+//!
+//! Also provides [`CircuitBreaker`], which adapters put in front of their backend
+//! requests so API calls fail fast while a backend is down instead of each one
+//! burning a connect timeout.
 
 use anyhow::Result;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
@@ -46,6 +51,176 @@ impl RetryConfig {
     }
 }
 
+/// Circuit breaker configuration
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures before the circuit opens
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a probe request is let through
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            open_duration: Duration::from_secs(15),
+        }
+    }
+}
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests go through
+    #[default]
+    Closed,
+    /// Backend considered down; requests fail immediately
+    Open,
+    /// Open period elapsed; one probe request is let through
+    HalfOpen,
+}
+
+/// Returned instead of attempting a request while the circuit is open
+#[derive(Debug, Clone)]
+pub struct CircuitOpenError {
+    pub backend: &'static str,
+    /// Time until the next probe is allowed
+    pub retry_in: Duration,
+}
+
+impl std::fmt::Display for CircuitOpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} unavailable (retrying in {}s)",
+            self.backend,
+            self.retry_in.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpenError {}
+
+#[derive(Debug, Default)]
+struct CircuitInner {
+    consecutive_failures: u32,
+    /// Set while open (and half-open)
+    opened_at: Option<Instant>,
+    /// Set while a half-open probe is in flight
+    probe_started: Option<Instant>,
+}
+
+/// Per-backend circuit breaker
+///
+/// Closed until `failure_threshold` consecutive failures, then open for
+/// `open_duration`, during which [`CircuitBreaker::check`] fails immediately.
+/// After that a single probe is let through (half-open): success closes the
+/// circuit, failure re-opens it. Callers only report transport failures
+/// (connect errors, timeouts); a backend rejecting a command is still "up".
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    backend: &'static str,
+    config: CircuitBreakerConfig,
+    inner: Mutex<CircuitInner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(backend: &'static str, config: CircuitBreakerConfig) -> Self {
+        Self {
+            backend,
+            config,
+            inner: Mutex::new(CircuitInner::default()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CircuitInner> {
+        // State is a few counters, always consistent, so a poisoned lock is still usable
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Current state
+    pub fn state(&self) -> CircuitState {
+        let inner = self.lock();
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.config.open_duration => {
+                CircuitState::Open
+            }
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Whether a request may be attempted now.
+    ///
+    /// In the half-open state only one caller gets through; a probe that never
+    /// reports back (e.g. its request was cancelled) is replaced after another
+    /// `open_duration`.
+    pub fn check(&self) -> std::result::Result<(), CircuitOpenError> {
+        let mut inner = self.lock();
+        let Some(opened_at) = inner.opened_at else {
+            return Ok(());
+        };
+
+        let open_for = opened_at.elapsed();
+        if open_for < self.config.open_duration {
+            return Err(self.open_error(self.config.open_duration - open_for));
+        }
+
+        match inner.probe_started {
+            Some(started) if started.elapsed() < self.config.open_duration => {
+                Err(self.open_error(self.config.open_duration - started.elapsed()))
+            }
+            _ => {
+                inner.probe_started = Some(Instant::now());
+                tracing::debug!("{}: circuit half-open, probing", self.backend);
+                Ok(())
+            }
+        }
+    }
+
+    /// Report a successful request
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        if inner.opened_at.is_some() {
+            info!("{}: backend recovered, circuit closed", self.backend);
+        }
+        *inner = CircuitInner::default();
+    }
+
+    /// Report a failed request
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        if inner.probe_started.take().is_some() {
+            // Failed probe: stay open for another full period
+            inner.opened_at = Some(Instant::now());
+            tracing::debug!("{}: probe failed, circuit re-opened", self.backend);
+        } else if inner.opened_at.is_none()
+            && inner.consecutive_failures >= self.config.failure_threshold
+        {
+            inner.opened_at = Some(Instant::now());
+            warn!(
+                "{}: {} consecutive failures, circuit open for {:?}",
+                self.backend, inner.consecutive_failures, self.config.open_duration
+            );
+        }
+    }
+
+    /// Close the circuit (e.g. after the backend was reconfigured)
+    pub fn reset(&self) {
+        *self.lock() = CircuitInner::default();
+    }
+
+    fn open_error(&self, retry_in: Duration) -> CircuitOpenError {
+        CircuitOpenError {
+            backend: self.backend,
+            retry_in,
+        }
+    }
+}
+
 /// AdapterHandle wraps an AdapterLogic implementation and provides:
 /// - Consistent shutdown handling (can't forget it)
 /// - Automatic ACK on stop via AdapterStopped event
@@ -227,6 +402,64 @@ impl<T: AdapterLogic> AdapterHandle<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn breaker(open_duration: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig {
+                failure_threshold: 2,
+                open_duration,
+            },
+        )
+    }
+
+    #[test]
+    fn test_circuit_opens_after_threshold() {
+        let cb = breaker(Duration::from_secs(60));
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+        assert!(cb.check().is_ok());
+
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+        let err = cb.check().unwrap_err();
+        assert_eq!(err.backend, "test");
+        assert!(err.retry_in <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_circuit_success_resets_failures() {
+        let cb = breaker(Duration::from_secs(60));
+        cb.record_failure();
+        cb.record_success();
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_half_open_single_probe() {
+        let cb = breaker(Duration::from_millis(20));
+        cb.record_failure();
+        cb.record_failure();
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+
+        // Only one probe at a time
+        assert!(cb.check().is_ok());
+        assert!(cb.check().is_err());
+
+        // Failed probe re-opens for a full period
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+        assert!(cb.check().is_err());
+
+        // Successful probe closes the circuit
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(cb.check().is_ok());
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::Closed);
+        assert!(cb.check().is_ok());
+    }
     use crate::adapters::traits::{AdapterCommand, AdapterCommandResponse};
     use crate::bus::EventBus;
     use async_trait::async_trait;
@@ -0,0 +1,1116 @@
+//! HEOS adapter - shows Denon/Marantz HEOS players and groups as zones
+//!
+//! Talks to the HEOS CLI (telnet, port 1255) of any one HEOS device, which
+//! reports every player on the network. A long-lived connection registers for
+//! change events and keeps players, groups, play state, now playing and
+//! volumes current; commands are sent on short-lived connections of their own
+//! so their responses never mix with events.
+//!
+//! Every player is a zone with its own volume, and so is every group (group
+//! volume and mute). Transport on a grouped player goes to the group leader,
+//! which plays for the whole group.
+//!
+//! Zone ids are `heos:player:<pid>` and `heos:group:<gid>`.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeScale,
+    Zone, ZoneCapabilities,
+};
+use crate::clock::unix_millis;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::knobs::image::sniff_content_type;
+
+const HEOS_CONFIG_FILE: &str = "heos-config.json";
+/// HEOS CLI port
+const DEFAULT_PORT: u16 = 1255;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Devices drop idle CLI connections; a heart beat keeps the event connection open
+const HEART_BEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedHeosConfig {
+    host: String,
+    port: u16,
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(HEOS_CONFIG_FILE)
+}
+
+// =============================================================================
+// CLI model
+// =============================================================================
+
+/// A CLI response or event: `{"heos": {...}, "payload": ...}`
+#[derive(Debug, Clone, Deserialize)]
+struct HeosMessage {
+    heos: HeosHeader,
+    #[serde(default)]
+    payload: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HeosHeader {
+    command: String,
+    /// `success` or `fail`; absent on events
+    #[serde(default)]
+    result: Option<String>,
+    /// URL-style attributes, e.g. `pid=1&level=20`
+    #[serde(default)]
+    message: String,
+}
+
+impl HeosMessage {
+    fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line.trim()).ok()
+    }
+
+    /// Decoded `message` attributes
+    fn attrs(&self) -> HashMap<String, String> {
+        parse_attrs(&self.heos.message)
+    }
+
+    fn is_failure(&self) -> bool {
+        self.heos.result.as_deref() == Some("fail")
+    }
+
+    /// Devices acknowledge slow commands first and send the result later
+    fn is_pending(&self) -> bool {
+        self.heos.message.starts_with("command under process")
+    }
+}
+
+fn parse_attrs(message: &str) -> HashMap<String, String> {
+    message
+        .split('&')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let value = urlencoding::decode(value)
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| value.to_string());
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HeosPlayer {
+    pid: i64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    model: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HeosGroup {
+    gid: i64,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    players: Vec<HeosGroupMember>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HeosGroupMember {
+    pid: i64,
+    /// `leader` or `member`
+    #[serde(default)]
+    role: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct HeosMedia {
+    /// `song` or `station`
+    #[serde(default, rename = "type")]
+    media_type: String,
+    #[serde(default)]
+    song: String,
+    #[serde(default)]
+    artist: String,
+    #[serde(default)]
+    album: String,
+    #[serde(default)]
+    image_url: String,
+    #[serde(default)]
+    station: Option<String>,
+}
+
+/// Live state of a player (or group volume)
+#[derive(Debug, Clone, Default)]
+struct PlayerState {
+    state: PlaybackState,
+    media: Option<HeosMedia>,
+    volume: u8,
+    muted: bool,
+    /// Milliseconds, from progress events
+    position_ms: Option<u64>,
+    duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct GroupVolume {
+    volume: u8,
+    muted: bool,
+}
+
+/// Everything the event connection knows
+#[derive(Debug, Default)]
+struct HeosSystem {
+    players: BTreeMap<i64, HeosPlayer>,
+    player_states: HashMap<i64, PlayerState>,
+    groups: BTreeMap<i64, HeosGroup>,
+    group_volumes: HashMap<i64, GroupVolume>,
+}
+
+/// A HEOS zone's raw id (after `heos:`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HeosTarget {
+    Player(i64),
+    Group(i64),
+}
+
+impl HeosTarget {
+    fn parse(raw_id: &str) -> Option<Self> {
+        match raw_id.split_once(':')? {
+            ("player", id) => id.parse().ok().map(Self::Player),
+            ("group", id) => id.parse().ok().map(Self::Group),
+            _ => None,
+        }
+    }
+}
+
+fn attr_i64(attrs: &HashMap<String, String>, key: &str) -> Option<i64> {
+    attrs.get(key).and_then(|v| v.parse().ok())
+}
+
+fn attr_level(attrs: &HashMap<String, String>) -> Option<u8> {
+    attrs
+        .get("level")
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|v| v.clamp(0.0, 100.0).round() as u8)
+}
+
+fn play_state(state: &str) -> PlaybackState {
+    match state {
+        "play" => PlaybackState::Playing,
+        "pause" => PlaybackState::Paused,
+        "stop" => PlaybackState::Stopped,
+        _ => PlaybackState::Unknown,
+    }
+}
+
+fn player_queries(pid: i64) -> [String; 4] {
+    [
+        format!("player/get_play_state?pid={}", pid),
+        format!("player/get_now_playing_media?pid={}", pid),
+        format!("player/get_volume?pid={}", pid),
+        format!("player/get_mute?pid={}", pid),
+    ]
+}
+
+fn group_queries(gid: i64) -> [String; 2] {
+    [
+        format!("group/get_volume?gid={}", gid),
+        format!("group/get_mute?gid={}", gid),
+    ]
+}
+
+/// What applying a message did
+#[derive(Debug, Default, PartialEq)]
+struct Applied {
+    /// Whether zones may have changed
+    changed: bool,
+    /// Queries to send on the event connection (without `heos://`)
+    queries: Vec<String>,
+}
+
+impl HeosSystem {
+    /// Apply a response or event from the event connection
+    fn apply(&mut self, message: &HeosMessage) -> Applied {
+        let mut applied = Applied::default();
+        if message.is_pending() {
+            return applied;
+        }
+        if message.is_failure() {
+            tracing::debug!(
+                "HEOS {} failed: {}",
+                message.heos.command,
+                message.heos.message
+            );
+            return applied;
+        }
+        let attrs = message.attrs();
+        let pid = attr_i64(&attrs, "pid");
+        let gid = attr_i64(&attrs, "gid");
+
+        match message.heos.command.as_str() {
+            "player/get_players" => {
+                let players: Vec<HeosPlayer> =
+                    serde_json::from_value(message.payload.clone()).unwrap_or_default();
+                self.players = players.into_iter().map(|p| (p.pid, p)).collect();
+                let players = &self.players;
+                self.player_states
+                    .retain(|pid, _| players.contains_key(pid));
+                for pid in self.players.keys() {
+                    applied.queries.extend(player_queries(*pid));
+                }
+                applied.changed = true;
+            }
+            "group/get_groups" => {
+                let groups: Vec<HeosGroup> =
+                    serde_json::from_value(message.payload.clone()).unwrap_or_default();
+                self.groups = groups.into_iter().map(|g| (g.gid, g)).collect();
+                let groups = &self.groups;
+                self.group_volumes.retain(|gid, _| groups.contains_key(gid));
+                for gid in self.groups.keys() {
+                    applied.queries.extend(group_queries(*gid));
+                }
+                applied.changed = true;
+            }
+            "player/get_play_state" | "event/player_state_changed" => {
+                if let (Some(pid), Some(state)) = (pid, attrs.get("state")) {
+                    self.player_states.entry(pid).or_default().state = play_state(state);
+                    applied.changed = true;
+                }
+            }
+            "player/get_now_playing_media" => {
+                if let Some(pid) = pid {
+                    // Nothing playing comes back as an empty payload
+                    let media = serde_json::from_value::<HeosMedia>(message.payload.clone())
+                        .ok()
+                        .filter(|m| *m != HeosMedia::default());
+                    let player = self.player_states.entry(pid).or_default();
+                    if player.media != media {
+                        player.position_ms = None;
+                        player.duration_ms = None;
+                    }
+                    player.media = media;
+                    applied.changed = true;
+                }
+            }
+            "event/player_now_playing_changed" => {
+                if let Some(pid) = pid {
+                    applied
+                        .queries
+                        .push(format!("player/get_now_playing_media?pid={}", pid));
+                }
+            }
+            "event/player_now_playing_progress" => {
+                // Kept for the next publish; progress alone doesn't republish zones
+                if let Some(pid) = pid {
+                    let player = self.player_states.entry(pid).or_default();
+                    player.position_ms = attrs.get("cur_pos").and_then(|v| v.parse().ok());
+                    player.duration_ms = attrs.get("duration").and_then(|v| v.parse().ok());
+                }
+            }
+            "player/get_volume" | "player/get_mute" | "event/player_volume_changed" => {
+                if let Some(pid) = pid {
+                    let player = self.player_states.entry(pid).or_default();
+                    if let Some(level) = attr_level(&attrs) {
+                        player.volume = level;
+                    }
+                    if let Some(mute) = attrs.get("mute").or_else(|| attrs.get("state")) {
+                        player.muted = mute == "on";
+                    }
+                    applied.changed = true;
+                }
+            }
+            "group/get_volume" | "group/get_mute" | "event/group_volume_changed" => {
+                if let Some(gid) = gid {
+                    let group = self.group_volumes.entry(gid).or_default();
+                    if let Some(level) = attr_level(&attrs) {
+                        group.volume = level;
+                    }
+                    if let Some(mute) = attrs.get("mute").or_else(|| attrs.get("state")) {
+                        group.muted = mute == "on";
+                    }
+                    applied.changed = true;
+                }
+            }
+            "event/players_changed" => applied.queries.push("player/get_players".to_string()),
+            "event/groups_changed" => applied.queries.push("group/get_groups".to_string()),
+            _ => {}
+        }
+        applied
+    }
+
+    /// Group a player belongs to
+    fn group_of(&self, pid: i64) -> Option<&HeosGroup> {
+        self.groups
+            .values()
+            .find(|g| g.players.iter().any(|m| m.pid == pid))
+    }
+
+    /// Player that plays for a zone: the leader of a group, else the player itself
+    fn playing_pid(&self, target: HeosTarget) -> i64 {
+        let gid = match target {
+            HeosTarget::Group(gid) => Some(gid),
+            HeosTarget::Player(pid) => self.group_of(pid).map(|g| g.gid),
+        };
+        gid.and_then(|gid| self.groups.get(&gid))
+            .and_then(|g| g.players.iter().find(|m| m.role == "leader"))
+            .map(|m| m.pid)
+            .unwrap_or(match target {
+                HeosTarget::Player(pid) | HeosTarget::Group(pid) => pid,
+            })
+    }
+
+    fn zones(&self) -> Vec<Zone> {
+        let mut zones = Vec::new();
+        for player in self.players.values() {
+            let own = self
+                .player_states
+                .get(&player.pid)
+                .cloned()
+                .unwrap_or_default();
+            let playing = self
+                .player_states
+                .get(&self.playing_pid(HeosTarget::Player(player.pid)))
+                .cloned()
+                .unwrap_or_default();
+            zones.push(heos_zone(
+                PrefixedZoneId::heos(format!("player:{}", player.pid)),
+                player.name.clone(),
+                &playing,
+                own.volume,
+                own.muted,
+            ));
+        }
+        for group in self.groups.values() {
+            let playing = self
+                .player_states
+                .get(&self.playing_pid(HeosTarget::Group(group.gid)))
+                .cloned()
+                .unwrap_or_default();
+            let volume = self
+                .group_volumes
+                .get(&group.gid)
+                .copied()
+                .unwrap_or_default();
+            zones.push(heos_zone(
+                PrefixedZoneId::heos(format!("group:{}", group.gid)),
+                group.name.clone(),
+                &playing,
+                volume.volume,
+                volume.muted,
+            ));
+        }
+        zones
+    }
+}
+
+fn media_now_playing(player: &PlayerState) -> Option<NowPlaying> {
+    let media = player.media.as_ref()?;
+    if media.song.is_empty() && media.artist.is_empty() && media.station.is_none() {
+        return None;
+    }
+    let title = if media.song.is_empty() {
+        media.station.clone().unwrap_or_default()
+    } else {
+        media.song.clone()
+    };
+    Some(NowPlaying {
+        title,
+        artist: media.artist.clone(),
+        album: media.album.clone(),
+        image_key: Some(media.image_url.clone()).filter(|url| !url.is_empty()),
+        seek_position: player.position_ms.map(|ms| ms as f64 / 1000.0),
+        duration: player
+            .duration_ms
+            .filter(|&ms| ms > 0)
+            .map(|ms| ms as f64 / 1000.0),
+        metadata: None,
+    })
+}
+
+/// A zone with `last_updated` left at 0 so unchanged zones compare equal
+fn heos_zone(
+    zone_id: PrefixedZoneId,
+    name: String,
+    playing: &PlayerState,
+    volume: u8,
+    muted: bool,
+) -> Zone {
+    let now_playing = media_now_playing(playing);
+    // Stations can't be skipped backwards
+    let is_song = playing
+        .media
+        .as_ref()
+        .is_some_and(|m| m.media_type == "song");
+    Zone {
+        volume_control: Some(VolumeControl {
+            value: volume as f32,
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            is_muted: muted,
+            scale: VolumeScale::Percentage,
+            output_id: Some(zone_id.to_string()),
+        }),
+        zone_id: zone_id.to_string(),
+        zone_name: name,
+        state: playing.state,
+        now_playing,
+        source: "heos".to_string(),
+        is_controllable: true,
+        is_seekable: false,
+        capabilities: ZoneCapabilities {
+            has_volume: true,
+            ..Default::default()
+        },
+        volume_leveling: None,
+        last_updated: 0,
+        is_play_allowed: playing.state != PlaybackState::Playing,
+        is_pause_allowed: playing.state == PlaybackState::Playing,
+        is_next_allowed: playing.media.is_some(),
+        is_previous_allowed: is_song,
+        metadata: None,
+    }
+}
+
+// =============================================================================
+// Adapter
+// =============================================================================
+
+/// Player summary for status responses
+#[derive(Debug, Clone, Serialize)]
+pub struct HeosPlayerSummary {
+    pub pid: i64,
+    pub name: String,
+    pub model: String,
+    pub state: String,
+    /// Group the player is in, if any
+    pub group_id: Option<i64>,
+}
+
+/// HEOS adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct HeosStatus {
+    pub connected: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    pub players: Vec<HeosPlayerSummary>,
+}
+
+struct HeosState {
+    host: Option<String>,
+    port: u16,
+    connected: bool,
+    running: bool,
+    system: HeosSystem,
+    /// Zones as last published, to publish only what changed
+    zones: HashMap<String, Zone>,
+}
+
+/// HEOS adapter for Denon/Marantz players and groups
+#[derive(Clone)]
+pub struct HeosAdapter {
+    state: Arc<RwLock<HeosState>>,
+    bus: SharedBus,
+    http: Client,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+}
+
+impl HeosAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        let adapter = Self {
+            state: Arc::new(RwLock::new(HeosState {
+                host: None,
+                port: DEFAULT_PORT,
+                connected: false,
+                running: false,
+                system: HeosSystem::default(),
+                zones: HashMap::new(),
+            })),
+            bus,
+            http: Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+        };
+        adapter.load_config_sync();
+        adapter
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(HEOS_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedHeosConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut state) = self.state.try_write() {
+                    tracing::info!(
+                        "Loaded HEOS config from disk: {}:{}",
+                        saved.host,
+                        saved.port
+                    );
+                    state.host = Some(saved.host);
+                    state.port = saved.port;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse HEOS config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let saved = {
+            let state = self.state.read().await;
+            let Some(ref host) = state.host else {
+                return;
+            };
+            SavedHeosConfig {
+                host: host.clone(),
+                port: state.port,
+            }
+        };
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&saved) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save HEOS config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize HEOS config: {}", e),
+        }
+    }
+
+    /// Configure the HEOS device to connect to
+    pub async fn configure(&self, host: String, port: Option<u16>) {
+        {
+            let mut state = self.state.write().await;
+            state.host = Some(host);
+            state.port = port.unwrap_or(DEFAULT_PORT);
+        }
+        self.save_config().await;
+    }
+
+    /// Check if configured
+    pub async fn is_configured(&self) -> bool {
+        self.state.read().await.host.is_some()
+    }
+
+    /// Get connection status
+    pub async fn get_status(&self) -> HeosStatus {
+        let state = self.state.read().await;
+        let system = &state.system;
+        let players = system
+            .players
+            .values()
+            .map(|p| {
+                let playing = system.playing_pid(HeosTarget::Player(p.pid));
+                HeosPlayerSummary {
+                    pid: p.pid,
+                    name: p.name.clone(),
+                    model: p.model.clone(),
+                    state: system
+                        .player_states
+                        .get(&playing)
+                        .map(|s| s.state)
+                        .unwrap_or_default()
+                        .to_string(),
+                    group_id: system.group_of(p.pid).map(|g| g.gid),
+                }
+            })
+            .collect();
+        HeosStatus {
+            connected: state.connected,
+            host: state.host.clone(),
+            port: state.port,
+            players,
+        }
+    }
+
+    /// Start the CLI connection (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            if state.host.is_none() {
+                bail!("HEOS device not configured");
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("HEOS adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+        self.state.write().await.running = false;
+        self.clear_zones().await;
+        tracing::info!("HEOS adapter stopped");
+    }
+
+    async fn address(&self) -> Result<String> {
+        let state = self.state.read().await;
+        let host = state
+            .host
+            .as_ref()
+            .ok_or_else(|| anyhow!("HEOS device not configured"))?;
+        Ok(format!("{}:{}", host, state.port))
+    }
+
+    /// Publish the zones that changed since the last publish
+    async fn publish_zones(&self) {
+        let (changed, removed) = {
+            let mut state = self.state.write().await;
+            let zones = state.system.zones();
+            let removed: Vec<String> = state
+                .zones
+                .keys()
+                .filter(|id| !zones.iter().any(|z| &z.zone_id == *id))
+                .cloned()
+                .collect();
+            for id in &removed {
+                state.zones.remove(id);
+            }
+            let mut changed = Vec::new();
+            for zone in zones {
+                if state.zones.get(&zone.zone_id) != Some(&zone) {
+                    state.zones.insert(zone.zone_id.clone(), zone.clone());
+                    changed.push(zone);
+                }
+            }
+            (changed, removed)
+        };
+
+        let now = unix_millis();
+        for mut zone in changed {
+            zone.last_updated = now;
+            self.bus.publish(BusEvent::ZoneDiscovered { zone });
+        }
+        for zone_id in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// Forget the system state and remove its zones
+    async fn clear_zones(&self) {
+        let removed: Vec<String> = {
+            let mut state = self.state.write().await;
+            state.connected = false;
+            state.system = HeosSystem::default();
+            state.zones.drain().map(|(id, _)| id).collect()
+        };
+        for zone_id in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// Send a command on its own connection and wait for its result
+    async fn command(&self, command: &str, target: &str) -> Result<HeosMessage> {
+        let name = command.split('?').next().unwrap_or(command).to_string();
+        ops::timed(
+            "heos",
+            &name,
+            Some(target),
+            self.command_request(command, &name),
+        )
+        .await
+    }
+
+    async fn command_request(&self, command: &str, name: &str) -> Result<HeosMessage> {
+        let address = self.address().await?;
+        tokio::time::timeout(COMMAND_TIMEOUT, exchange(&address, command, name))
+            .await
+            .map_err(|_| anyhow!("HEOS {} timed out", name))?
+    }
+
+    /// Play, pause, play_pause, stop, next or previous; grouped players control their leader
+    pub async fn control(&self, raw_id: &str, action: &str) -> Result<()> {
+        let target = HeosTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid HEOS zone"))?;
+        let (pid, current) = {
+            let state = self.state.read().await;
+            let pid = state.system.playing_pid(target);
+            let current = state
+                .system
+                .player_states
+                .get(&pid)
+                .map(|s| s.state)
+                .unwrap_or_default();
+            (pid, current)
+        };
+        let command = match action {
+            "play" => format!("player/set_play_state?pid={}&state=play", pid),
+            "pause" => format!("player/set_play_state?pid={}&state=pause", pid),
+            "play_pause" | "playpause" => {
+                let next = if current == PlaybackState::Playing {
+                    "pause"
+                } else {
+                    "play"
+                };
+                format!("player/set_play_state?pid={}&state={}", pid, next)
+            }
+            "stop" => format!("player/set_play_state?pid={}&state=stop", pid),
+            "next" => format!("player/play_next?pid={}", pid),
+            "previous" | "prev" => format!("player/play_previous?pid={}", pid),
+            _ => bail!("Unknown action: {}", action),
+        };
+        self.command(&command, raw_id).await?;
+        Ok(())
+    }
+
+    /// Set a player's or group's volume (0-100)
+    pub async fn set_volume(&self, raw_id: &str, level: f32) -> Result<()> {
+        let level = level.clamp(0.0, 100.0).round() as u8;
+        let command = match HeosTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid HEOS zone"))? {
+            HeosTarget::Player(pid) => format!("player/set_volume?pid={}&level={}", pid, level),
+            HeosTarget::Group(gid) => format!("group/set_volume?gid={}&level={}", gid, level),
+        };
+        self.command(&command, raw_id).await?;
+        Ok(())
+    }
+
+    /// Change a player's or group's volume by `delta` percentage points
+    pub async fn change_volume(&self, raw_id: &str, delta: f32) -> Result<()> {
+        let target = HeosTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid HEOS zone"))?;
+        let current = {
+            let state = self.state.read().await;
+            match target {
+                HeosTarget::Player(pid) => state.system.player_states.get(&pid).map(|s| s.volume),
+                HeosTarget::Group(gid) => state.system.group_volumes.get(&gid).map(|g| g.volume),
+            }
+        }
+        .ok_or_else(|| anyhow!("HEOS zone not found"))?;
+        self.set_volume(raw_id, current as f32 + delta).await
+    }
+
+    /// Mute or unmute a player or group
+    pub async fn set_mute(&self, raw_id: &str, mute: bool) -> Result<()> {
+        let state = if mute { "on" } else { "off" };
+        let command = match HeosTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid HEOS zone"))? {
+            HeosTarget::Player(pid) => format!("player/set_mute?pid={}&state={}", pid, state),
+            HeosTarget::Group(gid) => format!("group/set_mute?gid={}&state={}", gid, state),
+        };
+        self.command(&command, raw_id).await?;
+        Ok(())
+    }
+
+    /// Current mute state of a zone
+    pub async fn is_muted(&self, raw_id: &str) -> Option<bool> {
+        let state = self.state.read().await;
+        match HeosTarget::parse(raw_id)? {
+            HeosTarget::Player(pid) => state.system.player_states.get(&pid).map(|s| s.muted),
+            HeosTarget::Group(gid) => state.system.group_volumes.get(&gid).map(|g| g.muted),
+        }
+    }
+
+    /// Fetch artwork (`image_url` is a zone's `image_key`)
+    pub async fn get_image(&self, image_url: &str) -> Result<crate::bus::ImageData> {
+        if !image_url.starts_with("http://") && !image_url.starts_with("https://") {
+            bail!("Invalid image URL");
+        }
+        let response = self.http.get(image_url).send().await?.error_for_status()?;
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        Ok(crate::bus::ImageData {
+            content_type: sniff_content_type(&body, declared.as_deref()),
+            data: body.to_vec(),
+        })
+    }
+}
+
+crate::impl_startable!(HeosAdapter, "heos", is_configured);
+
+#[async_trait]
+impl AdapterLogic for HeosAdapter {
+    fn prefix(&self) -> &'static str {
+        "heos"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        let address = self.address().await?;
+        let connect = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&address));
+        let stream = tokio::select! {
+            _ = ctx.shutdown.cancelled() => return Ok(()),
+            result = connect => result.map_err(|_| anyhow!("Timed out connecting to {}", address))??,
+        };
+        tracing::info!("Connected to HEOS device at {}", address);
+        self.state.write().await.connected = true;
+
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        for command in [
+            "system/register_for_change_events?enable=on",
+            "player/get_players",
+            "group/get_groups",
+        ] {
+            writer
+                .write_all(format!("heos://{}\r\n", command).as_bytes())
+                .await?;
+        }
+        let mut heart_beat = tokio::time::interval(HEART_BEAT_INTERVAL);
+        heart_beat.tick().await;
+
+        let result = loop {
+            let line = tokio::select! {
+                _ = ctx.shutdown.cancelled() => break Ok(()),
+                _ = heart_beat.tick() => {
+                    if let Err(e) = writer.write_all(b"heos://system/heart_beat\r\n").await {
+                        break Err(e.into());
+                    }
+                    continue;
+                }
+                line = lines.next_line() => line,
+            };
+            let line = match line {
+                Ok(Some(line)) => line,
+                Ok(None) => break Err(anyhow!("HEOS device closed the connection")),
+                Err(e) => break Err(e.into()),
+            };
+            let Some(message) = HeosMessage::parse(&line) else {
+                tracing::debug!("Ignoring unparseable HEOS message");
+                continue;
+            };
+
+            let applied = self.state.write().await.system.apply(&message);
+            if applied.changed {
+                self.publish_zones().await;
+            }
+            let mut failed = None;
+            for query in applied.queries {
+                if let Err(e) = writer
+                    .write_all(format!("heos://{}\r\n", query).as_bytes())
+                    .await
+                {
+                    failed = Some(e);
+                    break;
+                }
+            }
+            if let Some(e) = failed {
+                break Err(e.into());
+            }
+        };
+
+        self.clear_zones().await;
+        result
+    }
+
+    async fn handle_command(
+        &self,
+        zone_id: &str,
+        command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        let raw_id = zone_id.strip_prefix("heos:").unwrap_or(zone_id);
+        let result = match command {
+            AdapterCommand::Play => self.control(raw_id, "play").await,
+            AdapterCommand::Pause => self.control(raw_id, "pause").await,
+            AdapterCommand::PlayPause => self.control(raw_id, "play_pause").await,
+            AdapterCommand::Stop => self.control(raw_id, "stop").await,
+            AdapterCommand::Next => self.control(raw_id, "next").await,
+            AdapterCommand::Previous => self.control(raw_id, "previous").await,
+            AdapterCommand::VolumeAbsolute(v) => self.set_volume(raw_id, v as f32).await,
+            AdapterCommand::VolumeRelative(delta) => self.change_volume(raw_id, delta as f32).await,
+            AdapterCommand::Mute(mute) => self.set_mute(raw_id, mute).await,
+        };
+
+        match result {
+            Ok(()) => Ok(AdapterCommandResponse {
+                success: true,
+                error: None,
+            }),
+            Err(e) => Ok(AdapterCommandResponse {
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+/// Send `command` on a new connection and read lines until the result of `name` arrives
+async fn exchange(address: &str, command: &str, name: &str) -> Result<HeosMessage> {
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| anyhow!("Timed out connecting to {}", address))??;
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(format!("heos://{}\r\n", command).as_bytes())
+        .await?;
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let Some(message) = HeosMessage::parse(&line) else {
+            continue;
+        };
+        if message.heos.command != name || message.is_pending() {
+            continue;
+        }
+        if message.is_failure() {
+            let attrs = message.attrs();
+            bail!(
+                "HEOS {} failed: {}",
+                name,
+                attrs
+                    .get("text")
+                    .map(String::as_str)
+                    .unwrap_or("unknown error")
+            );
+        }
+        return Ok(message);
+    }
+    bail!("HEOS device closed the connection")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(value: Value) -> HeosMessage {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn system() -> HeosSystem {
+        let mut system = HeosSystem::default();
+        system.apply(&message(json!({
+            "heos": {"command": "player/get_players", "result": "success", "message": ""},
+            "payload": [
+                {"name": "Living Room", "pid": 101, "model": "Denon Home 350", "gid": 101},
+                {"name": "Kitchen", "pid": -202, "model": "HEOS 1", "gid": 101},
+                {"name": "Office", "pid": 303, "model": "Marantz M1"}
+            ]
+        })));
+        system.apply(&message(json!({
+            "heos": {"command": "group/get_groups", "result": "success", "message": ""},
+            "payload": [{"name": "Living Room + Kitchen", "gid": 101, "players": [
+                {"name": "Living Room", "pid": 101, "role": "leader"},
+                {"name": "Kitchen", "pid": -202, "role": "member"}
+            ]}]
+        })));
+        system
+    }
+
+    #[test]
+    fn test_players_and_groups_become_zones() {
+        let mut system = system();
+        system.apply(&message(json!({
+            "heos": {"command": "event/player_state_changed", "message": "pid=101&state=play"}
+        })));
+        system.apply(&message(json!({
+            "heos": {"command": "player/get_now_playing_media", "result": "success", "message": "pid=101"},
+            "payload": {"type": "song", "song": "Song", "album": "LP", "artist": "Artist",
+                        "image_url": "http://art/x.jpg", "mid": "1", "qid": 1, "sid": 1}
+        })));
+        system.apply(&message(json!({
+            "heos": {"command": "event/player_volume_changed", "message": "pid=-202&level=35&mute=on"}
+        })));
+        system.apply(&message(json!({
+            "heos": {"command": "event/group_volume_changed", "message": "gid=101&level=50&mute=off"}
+        })));
+
+        let zones = system.zones();
+        let ids: Vec<&str> = zones.iter().map(|z| z.zone_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "heos:player:-202",
+                "heos:player:101",
+                "heos:player:303",
+                "heos:group:101"
+            ]
+        );
+
+        // A member shows the leader's playback with its own volume
+        let kitchen = &zones[0];
+        assert_eq!(kitchen.state, PlaybackState::Playing);
+        assert_eq!(kitchen.now_playing.as_ref().unwrap().title, "Song");
+        let volume = kitchen.volume_control.as_ref().unwrap();
+        assert_eq!(volume.value, 35.0);
+        assert!(volume.is_muted);
+
+        let group = &zones[3];
+        assert_eq!(group.zone_name, "Living Room + Kitchen");
+        assert_eq!(group.volume_control.as_ref().unwrap().value, 50.0);
+        assert_eq!(
+            group.now_playing.as_ref().unwrap().image_key.as_deref(),
+            Some("http://art/x.jpg")
+        );
+
+        assert!(zones[2].now_playing.is_none());
+        assert_eq!(system.playing_pid(HeosTarget::Player(-202)), 101);
+        assert_eq!(system.playing_pid(HeosTarget::Player(303)), 303);
+    }
+
+    #[test]
+    fn test_events_request_fresh_state() {
+        let mut system = system();
+        let applied = system.apply(&message(json!({
+            "heos": {"command": "event/player_now_playing_changed", "message": "pid=303"}
+        })));
+        assert_eq!(
+            applied.queries,
+            ["player/get_now_playing_media?pid=303".to_string()]
+        );
+        assert!(!applied.changed);
+
+        let pending = system.apply(&message(json!({
+            "heos": {"command": "player/get_players", "result": "success",
+                     "message": "command under process"}
+        })));
+        assert_eq!(pending, Applied::default());
+        assert_eq!(system.players.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_attrs_and_targets() {
+        let attrs = parse_attrs("pid=5&name=Den%20%26%20Study&level=20");
+        assert_eq!(attrs["name"], "Den & Study");
+        assert_eq!(attr_level(&attrs), Some(20));
+        assert_eq!(HeosTarget::parse("player:-5"), Some(HeosTarget::Player(-5)));
+        assert_eq!(HeosTarget::parse("group:7"), Some(HeosTarget::Group(7)));
+        assert_eq!(HeosTarget::parse("player:x"), None);
+    }
+}
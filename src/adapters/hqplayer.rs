@@ -1,7 +1,8 @@
 //! HQPlayer Native Protocol Client + HTTP/Web Client for Profiles
 //!
 //! Implements the TCP/XML control protocol on port 4321 for pipeline control.
-//! Also implements HTTP/Digest auth for web UI profile loading (port 8088).
+//! Also implements HTTP/Digest auth for web UI profile loading (port 8088),
+//! over HTTPS where the web UI requires it.
 //! Based on Jussi Laako's hqp-control reference implementation.
 
 use anyhow::{anyhow, Result};
@@ -14,20 +15,39 @@ use std::collections::HashMap;
 use std::io::Cursor;
 use std::net::{Ipv4Addr, SocketAddrV4};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpStream, UdpSocket};
+use tokio::net::TcpStream;
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::timeout;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{
+    ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider,
+};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use tokio_util::sync::CancellationToken;
 
+use crate::adapters::handle::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::adapters::ops;
 use crate::bus::{
     BusEvent, NowPlaying as BusNowPlaying, PlaybackState, PrefixedZoneId, SharedBus, TrackMetadata,
     VolumeControl as BusVolumeControl, VolumeScale, Zone as BusZone,
 };
+use crate::clock::unix_secs;
 use crate::config::{get_config_file_path, read_config_file};
 
 const HQP_CONFIG_FILE: &str = "hqp-config.json";
+const HQP_LISTS_FILE: &str = "hqp-lists.json";
+
+/// Cached option lists are refetched after this long even if HQPlayer's version hasn't changed
+const LISTS_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Serializes read-modify-write of the lists file between instances
+static LISTS_FILE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
 /// Saved config for persistence (single instance format)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +59,10 @@ struct SavedHqpConfig {
     username: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     password: Option<String>,
+    #[serde(flatten)]
+    connection: HqpConnectionSettings,
+    #[serde(flatten)]
+    web_tls: HqpWebTls,
 }
 
 /// Named instance config (for multi-instance support)
@@ -54,6 +78,171 @@ pub struct HqpInstanceConfig {
     pub username: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
+    #[serde(flatten)]
+    pub connection: HqpConnectionSettings,
+    #[serde(flatten)]
+    pub web_tls: HqpWebTls,
+}
+
+/// Timeouts and retries for one instance; unset fields use the defaults
+///
+/// The defaults suit a wired HQPlayer that is already running. HQPlayer OS
+/// while booting, or a Wi-Fi DAC, may need longer timeouts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HqpConnectionSettings {
+    /// TCP connect timeout (default 2s)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// Wait for each line of a response (default 3s)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_timeout_secs: Option<u64>,
+    /// Connection attempts per command before it fails (default 3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconnect_attempts: Option<u32>,
+}
+
+impl HqpConnectionSettings {
+    fn connect_timeout(&self) -> Duration {
+        self.connect_timeout_secs
+            .map_or(CONNECT_TIMEOUT, Duration::from_secs)
+    }
+
+    fn response_timeout(&self) -> Duration {
+        self.response_timeout_secs
+            .map_or(RESPONSE_TIMEOUT, Duration::from_secs)
+    }
+
+    /// Bound on a whole exchange, stretched for long response timeouts
+    fn command_timeout(&self) -> Duration {
+        COMMAND_TIMEOUT.max(self.response_timeout() * 3)
+    }
+
+    fn reconnect_attempts(&self) -> u32 {
+        self.reconnect_attempts
+            .unwrap_or(MAX_RECONNECT_ATTEMPTS)
+            .max(1)
+    }
+}
+
+/// HTTPS for the web UI, which profile loading uses; plain HTTP when unset
+///
+/// Newer HQPlayer Embedded builds can enforce TLS on the web interface,
+/// usually with a self-signed certificate. Pinning the certificate's SHA-256
+/// fingerprint trusts exactly that certificate; without a pin it must be
+/// signed by a public CA.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HqpWebTls {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub web_https: bool,
+    /// SHA-256 of the web UI certificate in hex; colons and case are ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_cert_sha256: Option<String>,
+}
+
+impl HqpWebTls {
+    /// The pinned fingerprint, if any; errors if it isn't 32 hex bytes
+    fn pin(&self) -> Result<Option<[u8; 32]>> {
+        let Some(fingerprint) = self.web_cert_sha256.as_deref() else {
+            return Ok(None);
+        };
+        let hex: String = fingerprint
+            .chars()
+            .filter(|c| !matches!(c, ':' | ' '))
+            .collect();
+        let mut pin = [0u8; 32];
+        if hex.len() != pin.len() * 2 {
+            return Err(anyhow!("web_cert_sha256 must be 64 hex digits"));
+        }
+        for (byte, pair) in pin.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let pair = std::str::from_utf8(pair).unwrap_or_default();
+            *byte = u8::from_str_radix(pair, 16)
+                .map_err(|_| anyhow!("web_cert_sha256 must be 64 hex digits"))?;
+        }
+        Ok(Some(pin))
+    }
+}
+
+/// Accepts only the certificate whose SHA-256 matches the pin; handshake
+/// signatures are still checked
+#[derive(Debug)]
+struct PinnedCert {
+    pin: [u8; 32],
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCert {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        use sha2::{Digest, Sha256};
+        let actual: [u8; 32] = Sha256::digest(end_entity.as_ref()).into();
+        if actual == self.pin {
+            return Ok(ServerCertVerified::assertion());
+        }
+        // Name the presented fingerprint so it can be pinned after a renewal
+        let actual: Vec<String> = actual.iter().map(|b| format!("{:02X}", b)).collect();
+        Err(tokio_rustls::rustls::Error::General(format!(
+            "HQPlayer certificate SHA-256 {} doesn't match web_cert_sha256",
+            actual.join(":")
+        )))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// HTTP client for the web UI, trusting only the pinned certificate if set
+fn web_client(tls: &HqpWebTls) -> Result<Client> {
+    let builder = Client::builder().timeout(Duration::from_secs(3));
+    let builder = match tls.pin()? {
+        Some(pin) => {
+            let provider = Arc::new(ring::default_provider());
+            let config = ClientConfig::builder_with_provider(provider.clone())
+                .with_safe_default_protocol_versions()?
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(PinnedCert { pin, provider }))
+                .with_no_client_auth();
+            builder.use_preconfigured_tls(config)
+        }
+        None => builder,
+    };
+    Ok(builder.build()?)
 }
 
 fn default_port() -> u16 {
@@ -91,6 +280,8 @@ pub fn load_hqp_configs() -> Vec<HqpInstanceConfig> {
             web_port: single.web_port,
             username: single.username,
             password: single.password,
+            connection: single.connection,
+            web_tls: single.web_tls,
         }];
     }
 
@@ -98,6 +289,88 @@ pub fn load_hqp_configs() -> Vec<HqpInstanceConfig> {
     Vec::new()
 }
 
+/// Mode, filter, shaper and rate lists of one HQPlayer, persisted per `host:port`
+///
+/// These only change when HQPlayer is upgraded or reconfigured, so connecting
+/// reuses them instead of issuing four list queries every time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedLists {
+    /// HQPlayer version the lists were read from; another version refetches them
+    version: String,
+    /// Seconds since epoch
+    fetched_at: u64,
+    modes: Vec<ListItem>,
+    filters: Vec<FilterItem>,
+    shapers: Vec<ListItem>,
+    rates: Vec<RateItem>,
+}
+
+impl CachedLists {
+    fn is_fresh(&self, version: &str) -> bool {
+        self.version == version
+            && unix_secs().saturating_sub(self.fetched_at) < LISTS_MAX_AGE.as_secs()
+    }
+}
+
+/// Element name of a request built by `build_request`, for log messages
+fn command_name(xml: &str) -> &str {
+    let body = xml.rsplit("?>").next().unwrap_or(xml);
+    body.trim_start_matches('<')
+        .split(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Command names of a pipelined batch, e.g. `State+VolumeRange`
+fn batch_name(xmls: &[String]) -> String {
+    xmls.iter()
+        .map(|xml| command_name(xml))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Whether a response read so far is a whole reply (self-closing or closed element)
+fn response_complete(response: &str) -> bool {
+    let trimmed = response.trim();
+    trimmed.ends_with("/>") || (trimmed.contains("</") && trimmed.ends_with(">"))
+}
+
+fn read_cached_lists() -> HashMap<String, CachedLists> {
+    read_config_file(HQP_LISTS_FILE)
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Cached lists for `key` (`host:port`), if any
+fn load_cached_lists(key: &str) -> Option<CachedLists> {
+    let _guard = LISTS_FILE_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    read_cached_lists().remove(key)
+}
+
+/// Store lists for `key`, keeping other instances' entries
+fn save_cached_lists(key: &str, lists: &CachedLists) {
+    let _guard = LISTS_FILE_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let mut all = read_cached_lists();
+    all.insert(key.to_string(), lists.clone());
+
+    let path = get_config_file_path(HQP_LISTS_FILE);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(&all) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                tracing::warn!("Failed to save HQPlayer option lists: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to serialize HQPlayer option lists: {}", e),
+    }
+}
+
 /// Save HQP configs to disk (always saves as array)
 pub fn save_hqp_configs(configs: &[HqpInstanceConfig]) -> bool {
     let path = hqp_config_path();
@@ -127,9 +400,14 @@ const DEFAULT_PORT: u16 = 4321;
 const DEFAULT_WEB_PORT: u16 = 8088;
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
 const RESPONSE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Upper bound on a whole command exchange (lock wait, write and every response line)
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
 const PROFILE_PATH: &str = "/config/profile/load";
 /// Maximum reconnection attempts before giving up
 const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+/// Responses slower than this are logged, so slow setups can be tuned
+/// before they start timing out
+const SLOW_RESPONSE_WARNING: Duration = Duration::from_secs(1);
 /// Delay between reconnection attempts
 const RECONNECT_DELAY: Duration = Duration::from_millis(200);
 
@@ -283,7 +561,13 @@ pub struct HqpConnectionStatus {
     pub host: Option<String>,
     pub port: u16,
     pub web_port: u16,
+    /// Web UI reached over HTTPS
+    #[serde(default)]
+    pub web_https: bool,
     pub info: Option<HqpInfo>,
+    /// Circuit breaker state; `open` means requests fail fast until the next probe
+    #[serde(default)]
+    pub circuit: CircuitState,
 }
 
 /// Internal connection state
@@ -315,6 +599,8 @@ struct HqpAdapterState {
     web_port: u16,
     web_username: Option<String>,
     web_password: Option<String>,
+    connection_settings: HqpConnectionSettings,
+    web_tls: HqpWebTls,
     connected: bool,
     info: Option<HqpInfo>,
     last_state: Option<HqpState>,
@@ -349,6 +635,8 @@ impl Default for HqpAdapterState {
             web_port: DEFAULT_WEB_PORT,
             web_username: None,
             web_password: None,
+            connection_settings: HqpConnectionSettings::default(),
+            web_tls: HqpWebTls::default(),
             connected: false,
             info: None,
             last_state: None,
@@ -369,22 +657,23 @@ impl Default for HqpAdapterState {
 pub struct HqpAdapter {
     state: Arc<RwLock<HqpAdapterState>>,
     connection: Arc<Mutex<Option<HqpConnection>>>,
-    http_client: Client,
+    /// Rebuilt when the web TLS settings change
+    http_client: std::sync::RwLock<Client>,
     bus: SharedBus,
+    /// Fails native-protocol commands fast while HQPlayer is unreachable
+    breaker: CircuitBreaker,
 }
 
 impl HqpAdapter {
     pub fn new(bus: SharedBus) -> Self {
         #[allow(clippy::expect_used)] // HTTP client creation only fails if TLS setup fails
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(3))
-            .build()
-            .expect("Failed to create HTTP client");
+        let http_client = web_client(&HqpWebTls::default()).expect("Failed to create HTTP client");
         let adapter = Self {
             state: Arc::new(RwLock::new(HqpAdapterState::default())),
             connection: Arc::new(Mutex::new(None)),
-            http_client,
+            http_client: std::sync::RwLock::new(http_client),
             bus,
+            breaker: CircuitBreaker::new("hqplayer", CircuitBreakerConfig::default()),
         };
         // Load saved config synchronously at startup
         adapter.load_config_sync();
@@ -404,6 +693,21 @@ impl HqpAdapter {
                             state.web_port = saved.web_port;
                             state.web_username = saved.username;
                             state.web_password = saved.password;
+                            state.connection_settings = saved.connection;
+                            if saved.web_tls != HqpWebTls::default() {
+                                match web_client(&saved.web_tls) {
+                                    Ok(client) => {
+                                        *self
+                                            .http_client
+                                            .write()
+                                            .unwrap_or_else(PoisonError::into_inner) = client;
+                                        state.web_tls = saved.web_tls;
+                                    }
+                                    Err(e) => {
+                                        tracing::warn!("Ignoring HQPlayer web TLS settings: {}", e)
+                                    }
+                                }
+                            }
                             tracing::info!(
                                 "Loaded HQPlayer config from disk: {}:{}",
                                 saved.host,
@@ -428,6 +732,8 @@ impl HqpAdapter {
                 web_port: state.web_port,
                 username: state.web_username.clone(),
                 password: state.web_password.clone(),
+                connection: state.connection_settings,
+                web_tls: state.web_tls.clone(),
             };
             let path = hqp_config_path();
             if let Some(parent) = path.parent() {
@@ -485,12 +791,64 @@ impl HqpAdapter {
         if changed {
             let mut conn = self.connection.lock().await;
             *conn = None;
+            self.breaker.reset();
         }
 
         // Persist to disk
         self.save_config().await;
     }
 
+    /// Set timeouts and retries; takes effect from the next command
+    pub async fn set_connection_settings(&self, settings: HqpConnectionSettings) {
+        self.state.write().await.connection_settings = settings;
+    }
+
+    pub async fn connection_settings(&self) -> HqpConnectionSettings {
+        self.state.read().await.connection_settings
+    }
+
+    /// Set how the web UI is reached; fails (keeping the old settings) if the
+    /// pinned fingerprint is malformed
+    pub async fn set_web_tls(&self, tls: HqpWebTls) -> Result<()> {
+        let client = web_client(&tls)?;
+        *self
+            .http_client
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = client;
+        let mut state = self.state.write().await;
+        if state.web_tls != tls {
+            state.web_tls = tls;
+            // A new client has no session; start the auth handshake over
+            state.digest_auth = None;
+            state.cookies.clear();
+        }
+        Ok(())
+    }
+
+    /// Change `web_https` and/or the pin (an empty pin clears it), keeping the rest
+    pub async fn update_web_tls(&self, https: Option<bool>, pin: Option<&str>) -> Result<()> {
+        let mut tls = self.web_tls().await;
+        if let Some(https) = https {
+            tls.web_https = https;
+        }
+        if let Some(pin) = pin {
+            tls.web_cert_sha256 = Some(pin.trim().to_string()).filter(|p| !p.is_empty());
+        }
+        self.set_web_tls(tls).await
+    }
+
+    pub async fn web_tls(&self) -> HqpWebTls {
+        self.state.read().await.web_tls.clone()
+    }
+
+    /// Client for web UI requests (cheap to clone)
+    fn http_client(&self) -> Client {
+        self.http_client
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
     /// Check if web credentials are configured
     pub async fn has_web_credentials(&self) -> bool {
         let state = self.state.read().await;
@@ -510,26 +868,40 @@ impl HqpAdapter {
             host: state.host.clone(),
             port: state.port,
             web_port: state.web_port,
+            web_https: state.web_tls.web_https,
             info: state.info.clone(),
+            circuit: self.breaker.state(),
         }
     }
 
     /// Connect to HQPlayer
     pub async fn connect(&self) -> Result<()> {
-        let (host, port) = {
+        let (host, port, connect_timeout) = {
             let state = self.state.read().await;
             let host = state
                 .host
                 .clone()
                 .ok_or_else(|| anyhow!("HQPlayer host not configured"))?;
-            (host, state.port)
+            (
+                host,
+                state.port,
+                state.connection_settings.connect_timeout(),
+            )
         };
 
         let addr = format!("{}:{}", host, port);
-        let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(&addr))
+        let started = std::time::Instant::now();
+        let stream = timeout(connect_timeout, TcpStream::connect(&addr))
             .await
-            .map_err(|_| anyhow!("Connection timeout"))?
+            .map_err(|_| anyhow!("Connection timeout after {:?}", connect_timeout))?
             .map_err(|e| anyhow!("Connection failed: {}", e))?;
+        if started.elapsed() > SLOW_RESPONSE_WARNING {
+            tracing::warn!(
+                "HQPlayer at {} took {:.1}s to accept the connection",
+                addr,
+                started.elapsed().as_secs_f32()
+            );
+        }
 
         let (read_half, write_half) = stream.into_split();
         let reader = BufReader::new(read_half);
@@ -545,22 +917,33 @@ impl HqpAdapter {
         {
             let mut state = self.state.write().await;
             state.connected = true;
+            state.last_state = None;
         }
 
-        // Get info and cache lists (use inner methods to avoid reconnection loop)
+        // Get info and option lists (use inner methods to avoid reconnection loop).
+        // Lists saved for this HQPlayer version are reused instead of re-queried.
         let info = self.get_info_inner().await?;
-        let modes = self.get_modes_inner().await?;
-        let filters = self.get_filters_inner().await?;
-        let shapers = self.get_shapers_inner().await?;
-        let rates = self.get_rates_inner().await?;
+        let lists_key = format!("{}:{}", host, port);
+        let lists = match load_cached_lists(&lists_key).filter(|l| l.is_fresh(&info.version)) {
+            Some(lists) => {
+                tracing::debug!("Using saved HQPlayer option lists for {}", lists_key);
+                lists
+            }
+            None => {
+                let responses = self.send_batch_inner(&Self::list_requests()).await?;
+                let lists = Self::parse_lists(info.version.clone(), &responses)?;
+                save_cached_lists(&lists_key, &lists);
+                lists
+            }
+        };
 
         {
             let mut state = self.state.write().await;
             state.info = Some(info.clone());
-            state.modes = modes;
-            state.filters = filters;
-            state.shapers = shapers;
-            state.rates = rates;
+            state.modes = lists.modes;
+            state.filters = lists.filters;
+            state.shapers = lists.shapers;
+            state.rates = lists.rates;
         }
 
         tracing::info!("HQPlayer connected: {} v{}", info.name, info.version);
@@ -568,8 +951,11 @@ impl HqpAdapter {
             .publish(BusEvent::HqpConnected { host: host.clone() });
 
         // Get status and volume range for ZoneDiscovered (using inner methods to avoid recursion)
-        let status = self.get_playback_status_inner().await.unwrap_or_default();
-        let vol_range = self.get_volume_range_inner().await.unwrap_or_default();
+        let (status, vol_range) = self
+            .send_batch_inner(&Self::status_requests())
+            .await
+            .map(|responses| Self::parse_status_and_range(&responses))
+            .unwrap_or_default();
 
         // Get instance name for zone ID
         let instance_name = {
@@ -581,7 +967,109 @@ impl HqpAdapter {
         let zone =
             Self::hqp_status_to_zone(&host, instance_name.as_deref(), &info, &status, &vol_range);
         self.bus.publish(BusEvent::ZoneDiscovered { zone });
+        self.breaker.record_success();
+
+        Ok(())
+    }
+
+    /// Reconnect a lost connection once the circuit breaker allows a probe
+    ///
+    /// No-op unless the circuit is half-open, so instances that were never
+    /// reachable, or only just failed, aren't retried on every refresh tick.
+    pub async fn probe_reconnect(&self) -> Result<()> {
+        let disconnected = {
+            let state = self.state.read().await;
+            state.host.is_some() && !state.connected
+        };
+        if !disconnected || self.breaker.state() != CircuitState::HalfOpen {
+            return Ok(());
+        }
+
+        self.breaker.check()?;
+        match self.connect().await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.breaker.record_failure();
+                Err(e)
+            }
+        }
+    }
+
+    /// Refresh playback status and republish the zone (no-op when disconnected)
+    ///
+    /// Used by the periodic status refresh so HQPlayer zones track state changes
+    /// made outside this bridge (HQPlayer UI, other clients).
+    pub async fn refresh_zone(&self) -> Result<()> {
+        let (host, instance_name, info) = {
+            let state = self.state.read().await;
+            if !state.connected {
+                return Ok(());
+            }
+            match (&state.host, &state.info) {
+                (Some(host), Some(info)) => {
+                    (host.clone(), state.instance_name.clone(), info.clone())
+                }
+                _ => return Ok(()),
+            }
+        };
+
+        let responses = self.send_batch(&Self::status_requests()).await?;
+        let (status, vol_range) = Self::parse_status_and_range(&responses);
+
+        let zone =
+            Self::hqp_status_to_zone(&host, instance_name.as_deref(), &info, &status, &vol_range);
+        self.bus.publish(BusEvent::ZoneDiscovered { zone });
+        Ok(())
+    }
 
+    /// Check the pipeline for changes and publish `HqpPipelineChanged` (no-op when disconnected)
+    ///
+    /// Called from the periodic status refresh so pipeline/matrix changes made in
+    /// HQPlayer's own UI reach SSE clients. The first check after connecting only
+    /// records a baseline.
+    pub async fn check_pipeline(&self) -> Result<()> {
+        let host = {
+            let state = self.state.read().await;
+            match (&state.host, state.connected) {
+                (Some(host), true) => host.clone(),
+                _ => return Ok(()),
+            }
+        };
+
+        let current = self.get_state().await?;
+
+        let event = {
+            let mut state = self.state.write().await;
+            let changed = state
+                .last_state
+                .as_ref()
+                .is_some_and(|previous| pipeline_differs(previous, &current));
+            let event = changed.then(|| BusEvent::HqpPipelineChanged {
+                host,
+                filter: state
+                    .filters
+                    .iter()
+                    .find(|f| f.index == current.filter1x.unwrap_or(current.filter))
+                    .map(|f| f.name.clone()),
+                shaper: state
+                    .shapers
+                    .iter()
+                    .find(|s| s.index == current.shaper)
+                    .map(|s| s.name.clone()),
+                rate: state
+                    .rates
+                    .iter()
+                    .find(|r| r.index == current.rate)
+                    .map(|r| r.rate.to_string()),
+            });
+            state.last_state = Some(current);
+            event
+        };
+
+        if let Some(event) = event {
+            tracing::debug!("HQPlayer pipeline changed externally");
+            self.bus.publish(event);
+        }
         Ok(())
     }
 
@@ -590,6 +1078,7 @@ impl HqpAdapter {
         let (host, instance_name) = {
             let mut state = self.state.write().await;
             state.connected = false;
+            state.last_state = None;
             (state.host.clone(), state.instance_name.clone())
         };
 
@@ -644,14 +1133,59 @@ impl HqpAdapter {
     }
 
     /// Send command and get response with auto-reconnection
+    ///
+    /// Fails immediately while the circuit breaker is open; a command that
+    /// still fails after all reconnect attempts counts as one failure.
     async fn send_command(&self, xml: &str) -> Result<String> {
+        let responses = self.send_batch(&[xml.to_string()]).await?;
+        responses
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No response to {}", command_name(xml)))
+    }
+
+    /// Send several queries in one write and get their responses, in order
+    ///
+    /// Same breaker and reconnect handling as `send_command`, but the whole
+    /// batch costs a single round trip.
+    async fn send_batch(&self, xmls: &[String]) -> Result<Vec<String>> {
+        self.breaker.check()?;
+        let target = self.ops_target().await;
+        let result = ops::timed(
+            "hqplayer",
+            &batch_name(xmls),
+            target.as_deref(),
+            self.send_batch_with_retry(xmls),
+        )
+        .await;
+        match &result {
+            Ok(_) => self.breaker.record_success(),
+            Err(_) => self.breaker.record_failure(),
+        }
+        result
+    }
+
+    /// Instance name (or host) shown with recorded operations
+    async fn ops_target(&self) -> Option<String> {
+        let state = self.state.read().await;
+        state.instance_name.clone().or_else(|| state.host.clone())
+    }
+
+    /// Send a batch, reconnecting up to the instance's `reconnect_attempts` times
+    async fn send_batch_with_retry(&self, xmls: &[String]) -> Result<Vec<String>> {
+        let attempts = self
+            .state
+            .read()
+            .await
+            .connection_settings
+            .reconnect_attempts();
         let mut last_error = None;
 
-        for attempt in 0..MAX_RECONNECT_ATTEMPTS {
+        for attempt in 0..attempts {
             // Ensure we're connected
             if let Err(e) = self.ensure_connected().await {
                 last_error = Some(e);
-                if attempt < MAX_RECONNECT_ATTEMPTS - 1 {
+                if attempt < attempts - 1 {
                     tracing::debug!(
                         "HQPlayer connection attempt {} failed, retrying...",
                         attempt + 1
@@ -661,15 +1195,16 @@ impl HqpAdapter {
                 continue;
             }
 
-            // Try to send command
-            match self.send_command_inner(xml).await {
-                Ok(response) => return Ok(response),
+            // Try to send the batch
+            match self.send_batch_inner(xmls).await {
+                Ok(responses) => return Ok(responses),
                 Err(e) => {
-                    // Mark as disconnected so next attempt will reconnect
+                    // Mark as disconnected so next attempt will reconnect; a
+                    // half-read batch would otherwise leave stale replies queued
                     self.mark_disconnected().await;
                     last_error = Some(e);
 
-                    if attempt < MAX_RECONNECT_ATTEMPTS - 1 {
+                    if attempt < attempts - 1 {
                         tracing::debug!(
                             "HQPlayer command failed, reconnecting (attempt {})...",
                             attempt + 1
@@ -685,42 +1220,74 @@ impl HqpAdapter {
 
     /// Inner send command (without retry logic)
     async fn send_command_inner(&self, xml: &str) -> Result<String> {
+        let responses = self.send_batch_inner(&[xml.to_string()]).await?;
+        responses
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No response to {}", command_name(xml)))
+    }
+
+    /// Inner send batch (without retry logic)
+    async fn send_batch_inner(&self, xmls: &[String]) -> Result<Vec<String>> {
+        let settings = self.state.read().await.connection_settings;
+        let started = std::time::Instant::now();
+        let responses = timeout(
+            settings.command_timeout(),
+            self.exchange(xmls, settings.response_timeout()),
+        )
+        .await
+        .map_err(|_| anyhow!("Command timeout"))??;
+
+        if started.elapsed() > SLOW_RESPONSE_WARNING {
+            tracing::warn!(
+                "HQPlayer took {:.1}s to answer {}",
+                started.elapsed().as_secs_f32(),
+                batch_name(xmls)
+            );
+        }
+        Ok(responses)
+    }
+
+    /// Write commands and read their (possibly multi-line) responses
+    ///
+    /// HQPlayer answers the commands on a connection strictly in the order
+    /// they were sent, so a batch is written in one go (pipelined) and the
+    /// replies are read back one after another.
+    async fn exchange(&self, xmls: &[String], response_timeout: Duration) -> Result<Vec<String>> {
         let mut conn_guard = self.connection.lock().await;
         let conn = conn_guard
             .as_mut()
             .ok_or_else(|| anyhow!("Not connected"))?;
 
-        // Send command
-        conn.write_half.write_all(xml.as_bytes()).await?;
-        conn.write_half.write_all(b"\n").await?;
+        // Send commands
+        let mut request = String::new();
+        for xml in xmls {
+            request.push_str(xml);
+            request.push('\n');
+        }
+        conn.write_half.write_all(request.as_bytes()).await?;
         conn.write_half.flush().await?;
 
-        // Read response - handle both single-line and multi-line XML
+        // Read responses - handle both single-line and multi-line XML
         // HQPlayer sends responses that may span multiple lines for complex data
-        let mut response = String::new();
-        let mut complete = false;
-
-        while !complete {
-            let mut line = String::new();
-            let read_result = timeout(RESPONSE_TIMEOUT, conn.stream.read_line(&mut line)).await;
-
-            match read_result {
-                Ok(Ok(0)) => break, // EOF
-                Ok(Ok(_)) => {
-                    response.push_str(&line);
-                    // Check if response is complete (has closing XML tag or is self-closing)
-                    let trimmed = response.trim();
-                    if trimmed.ends_with("/>") || (trimmed.contains("</") && trimmed.ends_with(">"))
-                    {
-                        complete = true;
-                    }
+        let mut responses = Vec::with_capacity(xmls.len());
+        while responses.len() < xmls.len() {
+            let mut response = String::new();
+            while !response_complete(&response) {
+                let mut line = String::new();
+                let read_result = timeout(response_timeout, conn.stream.read_line(&mut line)).await;
+
+                match read_result {
+                    Ok(Ok(0)) => return Err(anyhow!("Connection closed by HQPlayer")),
+                    Ok(Ok(_)) => response.push_str(&line),
+                    Ok(Err(e)) => return Err(anyhow!("Read error: {}", e)),
+                    Err(_) => return Err(anyhow!("Response timeout after {:?}", response_timeout)),
                 }
-                Ok(Err(e)) => return Err(anyhow!("Read error: {}", e)),
-                Err(_) => return Err(anyhow!("Response timeout")),
             }
+            responses.push(response.trim().to_string());
         }
 
-        Ok(response.trim().to_string())
+        Ok(responses)
     }
 
     // =========================================================================
@@ -731,103 +1298,151 @@ impl HqpAdapter {
     async fn get_info_inner(&self) -> Result<HqpInfo> {
         let xml = Self::build_request("GetInfo", &[]);
         let response = self.send_command_inner(&xml).await?;
+        Ok(Self::parse_info(&response))
+    }
 
-        Ok(HqpInfo {
-            name: Self::parse_attr(&response, "name").unwrap_or_default(),
-            product: Self::parse_attr(&response, "product").unwrap_or_default(),
-            version: Self::parse_attr(&response, "version").unwrap_or_default(),
-            platform: Self::parse_attr(&response, "platform").unwrap_or_default(),
-            engine: Self::parse_attr(&response, "engine").unwrap_or_default(),
+    // =========================================================================
+    // Response parsing (shared by single and batched queries)
+    // =========================================================================
+
+    /// Requests for the option lists, in the order `parse_lists` expects
+    fn list_requests() -> Vec<String> {
+        ["GetModes", "GetFilters", "GetShapers", "GetRates"]
+            .iter()
+            .map(|element| Self::build_request(element, &[]))
+            .collect()
+    }
+
+    /// Option lists from the responses to `list_requests`
+    fn parse_lists(version: String, responses: &[String]) -> Result<CachedLists> {
+        let [modes, filters, shapers, rates] = responses else {
+            return Err(anyhow!(
+                "Expected 4 list responses, got {}",
+                responses.len()
+            ));
+        };
+        Ok(CachedLists {
+            version,
+            fetched_at: unix_secs(),
+            modes: Self::parse_modes(modes),
+            filters: Self::parse_filters(filters),
+            shapers: Self::parse_shapers(shapers),
+            rates: Self::parse_rates(rates),
         })
     }
 
-    /// Get available modes (no reconnection)
-    async fn get_modes_inner(&self) -> Result<Vec<ListItem>> {
-        let xml = Self::build_request("GetModes", &[]);
-        let response = self.send_command_inner(&xml).await?;
+    /// Requests for playback status and volume range, as `parse_status_and_range` expects
+    fn status_requests() -> Vec<String> {
+        vec![
+            Self::build_request("Status", &[("subscribe", "0")]),
+            Self::build_request("VolumeRange", &[]),
+        ]
+    }
+
+    fn parse_status_and_range(responses: &[String]) -> (HqpStatus, VolumeRange) {
+        (
+            responses
+                .first()
+                .map(|r| Self::parse_status(r))
+                .unwrap_or_default(),
+            responses
+                .get(1)
+                .map(|r| Self::parse_volume_range(r))
+                .unwrap_or_default(),
+        )
+    }
+
+    fn parse_info(response: &str) -> HqpInfo {
+        HqpInfo {
+            name: Self::parse_attr(response, "name").unwrap_or_default(),
+            product: Self::parse_attr(response, "product").unwrap_or_default(),
+            version: Self::parse_attr(response, "version").unwrap_or_default(),
+            platform: Self::parse_attr(response, "platform").unwrap_or_default(),
+            engine: Self::parse_attr(response, "engine").unwrap_or_default(),
+        }
+    }
 
-        Ok(Self::parse_items(&response, "ModesItem", |item| ListItem {
+    fn parse_modes(response: &str) -> Vec<ListItem> {
+        Self::parse_items(response, "ModesItem", |item| ListItem {
             index: Self::parse_attr_u32(item, "index"),
             name: Self::parse_attr(item, "name").unwrap_or_default(),
             value: Self::parse_attr_i32(item, "value"), // Mode values can be negative (-1 for PCM)
-        }))
+        })
     }
 
-    /// Get available filters (no reconnection)
-    async fn get_filters_inner(&self) -> Result<Vec<FilterItem>> {
-        let xml = Self::build_request("GetFilters", &[]);
-        let response = self.send_command_inner(&xml).await?;
-
-        Ok(Self::parse_items(&response, "FiltersItem", |item| {
-            FilterItem {
-                index: Self::parse_attr_u32(item, "index"),
-                name: Self::parse_attr(item, "name").unwrap_or_default(),
-                value: Self::parse_attr_i32(item, "value"),
-                arg: Self::parse_attr_u32(item, "arg"),
-            }
-        }))
+    fn parse_filters(response: &str) -> Vec<FilterItem> {
+        Self::parse_items(response, "FiltersItem", |item| FilterItem {
+            index: Self::parse_attr_u32(item, "index"),
+            name: Self::parse_attr(item, "name").unwrap_or_default(),
+            value: Self::parse_attr_i32(item, "value"),
+            arg: Self::parse_attr_u32(item, "arg"),
+        })
     }
 
-    /// Get available shapers (no reconnection)
-    async fn get_shapers_inner(&self) -> Result<Vec<ListItem>> {
-        let xml = Self::build_request("GetShapers", &[]);
-        let response = self.send_command_inner(&xml).await?;
-
-        Ok(Self::parse_items(&response, "ShapersItem", |item| {
-            ListItem {
-                index: Self::parse_attr_u32(item, "index"),
-                name: Self::parse_attr(item, "name").unwrap_or_default(),
-                value: Self::parse_attr_i32(item, "value"),
-            }
-        }))
+    fn parse_shapers(response: &str) -> Vec<ListItem> {
+        Self::parse_items(response, "ShapersItem", |item| ListItem {
+            index: Self::parse_attr_u32(item, "index"),
+            name: Self::parse_attr(item, "name").unwrap_or_default(),
+            value: Self::parse_attr_i32(item, "value"),
+        })
     }
 
-    /// Get available sample rates (no reconnection)
-    async fn get_rates_inner(&self) -> Result<Vec<RateItem>> {
-        let xml = Self::build_request("GetRates", &[]);
-        let response = self.send_command_inner(&xml).await?;
-
-        Ok(Self::parse_items(&response, "RatesItem", |item| RateItem {
+    fn parse_rates(response: &str) -> Vec<RateItem> {
+        Self::parse_items(response, "RatesItem", |item| RateItem {
             index: Self::parse_attr_u32(item, "index"),
             rate: Self::parse_attr_u32(item, "rate"),
-        }))
+        })
     }
 
-    /// Get playback status (no reconnection)
-    async fn get_playback_status_inner(&self) -> Result<HqpStatus> {
-        let xml = Self::build_request("Status", &[("subscribe", "0")]);
-        let response = self.send_command_inner(&xml).await?;
-
-        Ok(HqpStatus {
-            state: Self::parse_attr_u32(&response, "state") as u8,
-            track: Self::parse_attr_u32(&response, "track"),
-            track_id: Self::parse_attr(&response, "track_id").unwrap_or_default(),
-            position: Self::parse_attr_u32(&response, "position"),
-            length: Self::parse_attr_u32(&response, "length"),
-            volume: Self::parse_attr_i32(&response, "volume"),
-            active_mode: Self::parse_attr(&response, "active_mode").unwrap_or_default(),
-            active_filter: Self::parse_attr(&response, "active_filter").unwrap_or_default(),
-            active_shaper: Self::parse_attr(&response, "active_shaper").unwrap_or_default(),
-            active_rate: Self::parse_attr_u32(&response, "active_rate"),
-            active_bits: Self::parse_attr_u32(&response, "active_bits"),
-            active_channels: Self::parse_attr_u32(&response, "active_channels"),
-            samplerate: Self::parse_attr_u32(&response, "samplerate"),
-            bitrate: Self::parse_attr_u32(&response, "bitrate"),
-        })
+    fn parse_status(response: &str) -> HqpStatus {
+        HqpStatus {
+            state: Self::parse_attr_u32(response, "state") as u8,
+            track: Self::parse_attr_u32(response, "track"),
+            track_id: Self::parse_attr(response, "track_id").unwrap_or_default(),
+            position: Self::parse_attr_u32(response, "position"),
+            length: Self::parse_attr_u32(response, "length"),
+            volume: Self::parse_attr_i32(response, "volume"),
+            active_mode: Self::parse_attr(response, "active_mode").unwrap_or_default(),
+            active_filter: Self::parse_attr(response, "active_filter").unwrap_or_default(),
+            active_shaper: Self::parse_attr(response, "active_shaper").unwrap_or_default(),
+            active_rate: Self::parse_attr_u32(response, "active_rate"),
+            active_bits: Self::parse_attr_u32(response, "active_bits"),
+            active_channels: Self::parse_attr_u32(response, "active_channels"),
+            samplerate: Self::parse_attr_u32(response, "samplerate"),
+            bitrate: Self::parse_attr_u32(response, "bitrate"),
+        }
     }
 
-    /// Get volume range (no reconnection)
-    async fn get_volume_range_inner(&self) -> Result<VolumeRange> {
-        let xml = Self::build_request("VolumeRange", &[]);
-        let response = self.send_command_inner(&xml).await?;
+    fn parse_state(response: &str) -> HqpState {
+        HqpState {
+            state: Self::parse_attr_u32(response, "state") as u8,
+            mode: Self::parse_attr_u32(response, "mode") as u8,
+            filter: Self::parse_attr_u32(response, "filter"),
+            filter1x: Self::parse_attr(response, "filter1x").and_then(|s| s.parse().ok()),
+            filter_nx: Self::parse_attr(response, "filterNx").and_then(|s| s.parse().ok()),
+            shaper: Self::parse_attr_u32(response, "shaper"),
+            rate: Self::parse_attr_u32(response, "rate"),
+            volume: Self::parse_attr_i32(response, "volume"),
+            active_mode: Self::parse_attr_u32(response, "active_mode") as u8,
+            active_rate: Self::parse_attr_u32(response, "active_rate"),
+            invert: Self::parse_attr_bool(response, "invert"),
+            convolution: Self::parse_attr_bool(response, "convolution"),
+            repeat: Self::parse_attr_u32(response, "repeat") as u8,
+            random: Self::parse_attr_bool(response, "random"),
+            adaptive: Self::parse_attr_bool(response, "adaptive"),
+            filter_20k: Self::parse_attr_bool(response, "filter_20k"),
+            matrix_profile: Self::parse_attr(response, "matrix_profile").unwrap_or_default(),
+        }
+    }
 
-        Ok(VolumeRange {
-            min: Self::parse_attr_i32(&response, "min"),
-            max: Self::parse_attr_i32(&response, "max"),
-            step: Self::parse_attr_i32(&response, "step").max(1),
-            enabled: Self::parse_attr_bool(&response, "enabled"),
-            adaptive: Self::parse_attr_bool(&response, "adaptive"),
-        })
+    fn parse_volume_range(response: &str) -> VolumeRange {
+        VolumeRange {
+            min: Self::parse_attr_i32(response, "min"),
+            max: Self::parse_attr_i32(response, "max"),
+            step: Self::parse_attr_i32(response, "step").max(1),
+            enabled: Self::parse_attr_bool(response, "enabled"),
+            adaptive: Self::parse_attr_bool(response, "adaptive"),
+        }
     }
 
     /// Build XML request
@@ -882,77 +1497,28 @@ impl HqpAdapter {
     pub async fn get_info(&self) -> Result<HqpInfo> {
         let xml = Self::build_request("GetInfo", &[]);
         let response = self.send_command(&xml).await?;
-
-        Ok(HqpInfo {
-            name: Self::parse_attr(&response, "name").unwrap_or_default(),
-            product: Self::parse_attr(&response, "product").unwrap_or_default(),
-            version: Self::parse_attr(&response, "version").unwrap_or_default(),
-            platform: Self::parse_attr(&response, "platform").unwrap_or_default(),
-            engine: Self::parse_attr(&response, "engine").unwrap_or_default(),
-        })
+        Ok(Self::parse_info(&response))
     }
 
     /// Get current state
     pub async fn get_state(&self) -> Result<HqpState> {
         let xml = Self::build_request("State", &[]);
         let response = self.send_command(&xml).await?;
-
-        Ok(HqpState {
-            state: Self::parse_attr_u32(&response, "state") as u8,
-            mode: Self::parse_attr_u32(&response, "mode") as u8,
-            filter: Self::parse_attr_u32(&response, "filter"),
-            filter1x: Self::parse_attr(&response, "filter1x").and_then(|s| s.parse().ok()),
-            filter_nx: Self::parse_attr(&response, "filterNx").and_then(|s| s.parse().ok()),
-            shaper: Self::parse_attr_u32(&response, "shaper"),
-            rate: Self::parse_attr_u32(&response, "rate"),
-            volume: Self::parse_attr_i32(&response, "volume"),
-            active_mode: Self::parse_attr_u32(&response, "active_mode") as u8,
-            active_rate: Self::parse_attr_u32(&response, "active_rate"),
-            invert: Self::parse_attr_bool(&response, "invert"),
-            convolution: Self::parse_attr_bool(&response, "convolution"),
-            repeat: Self::parse_attr_u32(&response, "repeat") as u8,
-            random: Self::parse_attr_bool(&response, "random"),
-            adaptive: Self::parse_attr_bool(&response, "adaptive"),
-            filter_20k: Self::parse_attr_bool(&response, "filter_20k"),
-            matrix_profile: Self::parse_attr(&response, "matrix_profile").unwrap_or_default(),
-        })
+        Ok(Self::parse_state(&response))
     }
 
     /// Get playback status
     pub async fn get_playback_status(&self) -> Result<HqpStatus> {
         let xml = Self::build_request("Status", &[("subscribe", "0")]);
         let response = self.send_command(&xml).await?;
-
-        Ok(HqpStatus {
-            state: Self::parse_attr_u32(&response, "state") as u8,
-            track: Self::parse_attr_u32(&response, "track"),
-            track_id: Self::parse_attr(&response, "track_id").unwrap_or_default(),
-            position: Self::parse_attr_u32(&response, "position"),
-            length: Self::parse_attr_u32(&response, "length"),
-            volume: Self::parse_attr_i32(&response, "volume"),
-            active_mode: Self::parse_attr(&response, "active_mode").unwrap_or_default(),
-            active_filter: Self::parse_attr(&response, "active_filter").unwrap_or_default(),
-            active_shaper: Self::parse_attr(&response, "active_shaper").unwrap_or_default(),
-            active_rate: Self::parse_attr_u32(&response, "active_rate"),
-            active_bits: Self::parse_attr_u32(&response, "active_bits"),
-            active_channels: Self::parse_attr_u32(&response, "active_channels"),
-            samplerate: Self::parse_attr_u32(&response, "samplerate"),
-            bitrate: Self::parse_attr_u32(&response, "bitrate"),
-        })
+        Ok(Self::parse_status(&response))
     }
 
     /// Get volume range
     pub async fn get_volume_range(&self) -> Result<VolumeRange> {
         let xml = Self::build_request("VolumeRange", &[]);
         let response = self.send_command(&xml).await?;
-
-        Ok(VolumeRange {
-            min: Self::parse_attr_i32(&response, "min"),
-            max: Self::parse_attr_i32(&response, "max"),
-            step: Self::parse_attr_i32(&response, "step").max(1),
-            enabled: Self::parse_attr_bool(&response, "enabled"),
-            adaptive: Self::parse_attr_bool(&response, "adaptive"),
-        })
+        Ok(Self::parse_volume_range(&response))
     }
 
     /// Parse multi-item response
@@ -977,52 +1543,69 @@ impl HqpAdapter {
     pub async fn get_modes(&self) -> Result<Vec<ListItem>> {
         let xml = Self::build_request("GetModes", &[]);
         let response = self.send_command(&xml).await?;
-
-        Ok(Self::parse_items(&response, "ModesItem", |item| ListItem {
-            index: Self::parse_attr_u32(item, "index"),
-            name: Self::parse_attr(item, "name").unwrap_or_default(),
-            value: Self::parse_attr_i32(item, "value"), // Mode values can be negative (-1 for PCM)
-        }))
+        Ok(Self::parse_modes(&response))
     }
 
     /// Get available filters
     pub async fn get_filters(&self) -> Result<Vec<FilterItem>> {
         let xml = Self::build_request("GetFilters", &[]);
         let response = self.send_command(&xml).await?;
-
-        Ok(Self::parse_items(&response, "FiltersItem", |item| {
-            FilterItem {
-                index: Self::parse_attr_u32(item, "index"),
-                name: Self::parse_attr(item, "name").unwrap_or_default(),
-                value: Self::parse_attr_i32(item, "value"),
-                arg: Self::parse_attr_u32(item, "arg"),
-            }
-        }))
+        Ok(Self::parse_filters(&response))
     }
 
     /// Get available shapers
     pub async fn get_shapers(&self) -> Result<Vec<ListItem>> {
         let xml = Self::build_request("GetShapers", &[]);
         let response = self.send_command(&xml).await?;
-
-        Ok(Self::parse_items(&response, "ShapersItem", |item| {
-            ListItem {
-                index: Self::parse_attr_u32(item, "index"),
-                name: Self::parse_attr(item, "name").unwrap_or_default(),
-                value: Self::parse_attr_i32(item, "value"),
-            }
-        }))
+        Ok(Self::parse_shapers(&response))
     }
 
     /// Get available sample rates
     pub async fn get_rates(&self) -> Result<Vec<RateItem>> {
         let xml = Self::build_request("GetRates", &[]);
         let response = self.send_command(&xml).await?;
+        Ok(Self::parse_rates(&response))
+    }
 
-        Ok(Self::parse_items(&response, "RatesItem", |item| RateItem {
-            index: Self::parse_attr_u32(item, "index"),
-            rate: Self::parse_attr_u32(item, "rate"),
-        }))
+    /// Whether `state` refers to a mode, filter, shaper or rate missing from
+    /// the cached lists (HQPlayer was reconfigured since they were saved)
+    async fn lists_outdated(&self, state: &HqpState) -> bool {
+        let cached = self.state.read().await;
+        let has_filter = |index: u32| cached.filters.iter().any(|f| f.index == index);
+        !cached.modes.iter().any(|m| m.index == state.mode as u32)
+            || !has_filter(state.filter1x.unwrap_or(state.filter))
+            || !has_filter(state.filter_nx.unwrap_or(state.filter))
+            || !cached.shapers.iter().any(|s| s.index == state.shaper)
+            || (state.rate != 0 && !cached.rates.iter().any(|r| r.index == state.rate))
+    }
+
+    /// Re-query the option lists, replacing the cached and saved ones
+    pub async fn refresh_lists(&self) -> Result<()> {
+        let (key, version) = {
+            let state = self.state.read().await;
+            let host = state
+                .host
+                .clone()
+                .ok_or_else(|| anyhow!("HQPlayer host not configured"))?;
+            let version = state
+                .info
+                .as_ref()
+                .map(|i| i.version.clone())
+                .unwrap_or_default();
+            (format!("{}:{}", host, state.port), version)
+        };
+
+        // One pipelined round trip for all four lists
+        let responses = self.send_batch(&Self::list_requests()).await?;
+        let lists = Self::parse_lists(version, &responses)?;
+        save_cached_lists(&key, &lists);
+
+        let mut state = self.state.write().await;
+        state.modes = lists.modes;
+        state.filters = lists.filters;
+        state.shapers = lists.shapers;
+        state.rates = lists.rates;
+        Ok(())
     }
 
     /// Set mode
@@ -1162,8 +1745,35 @@ impl HqpAdapter {
 
     /// Get full pipeline status
     pub async fn get_pipeline_status(&self) -> Result<PipelineStatus> {
-        let state = self.get_state().await?;
-        let vol_range = self.get_volume_range().await?;
+        let target = self.ops_target().await;
+        ops::timed(
+            "hqplayer",
+            "get_pipeline_status",
+            target.as_deref(),
+            self.pipeline_status(),
+        )
+        .await
+    }
+
+    async fn pipeline_status(&self) -> Result<PipelineStatus> {
+        // State and volume range in one round trip; the option lists come from
+        // the cache and are only re-queried when State points outside them
+        let responses = self
+            .send_batch(&[
+                Self::build_request("State", &[]),
+                Self::build_request("VolumeRange", &[]),
+            ])
+            .await?;
+        let [state, vol_range] = responses.as_slice() else {
+            return Err(anyhow!("Expected 2 responses, got {}", responses.len()));
+        };
+        let state = Self::parse_state(state);
+        let vol_range = Self::parse_volume_range(vol_range);
+
+        if self.lists_outdated(&state).await {
+            tracing::info!("HQPlayer reports settings outside its saved option lists, refreshing");
+            self.refresh_lists().await?;
+        }
 
         let cached = self.state.read().await;
         let modes = &cached.modes;
@@ -1322,7 +1932,12 @@ impl HqpAdapter {
             .host
             .as_ref()
             .ok_or_else(|| anyhow!("HQPlayer host not configured"))?;
-        Ok(format!("http://{}:{}", host, state.web_port))
+        let scheme = if state.web_tls.web_https {
+            "https"
+        } else {
+            "http"
+        };
+        Ok(format!("{}://{}:{}", scheme, host, state.web_port))
     }
 
     /// MD5 hash helper
@@ -1440,8 +2055,8 @@ impl HqpAdapter {
 
         // First attempt
         let mut request = match method {
-            "POST" => self.http_client.post(&url),
-            _ => self.http_client.get(&url),
+            "POST" => self.http_client().post(&url),
+            _ => self.http_client().get(&url),
         };
 
         if let Some(auth_header) = self.build_digest_header(method, path).await {
@@ -1465,8 +2080,8 @@ impl HqpAdapter {
 
                         // Retry with auth
                         let mut request = match method {
-                            "POST" => self.http_client.post(&url),
-                            _ => self.http_client.get(&url),
+                            "POST" => self.http_client().post(&url),
+                            _ => self.http_client().get(&url),
                         };
 
                         if let Some(auth_header) = self.build_digest_header(method, path).await {
@@ -1638,7 +2253,7 @@ impl HqpAdapter {
 
         // POST with proper headers
         let mut request = self
-            .http_client
+            .http_client()
             .post(format!("{}{}", base_url, PROFILE_PATH))
             .header("Content-Type", "application/x-www-form-urlencoded")
             .header("Origin", &base_url)
@@ -1657,7 +2272,7 @@ impl HqpAdapter {
                     self.parse_digest_challenge(header_str).await;
 
                     let mut request = self
-                        .http_client
+                        .http_client()
                         .post(format!("{}{}", base_url, PROFILE_PATH))
                         .header("Content-Type", "application/x-www-form-urlencoded")
                         .header("Origin", &base_url)
@@ -1820,6 +2435,17 @@ impl HqpAdapter {
             .map(|d| d.as_millis() as u64)
             .unwrap_or(0);
 
+        let capabilities = crate::bus::ZoneCapabilities {
+            can_seek: true,
+            can_shuffle: true,
+            has_volume: volume_control.is_some(),
+            has_queue: true,
+            supports_grouping: false,
+            // Matrix profiles (convolution filters) are the EQ
+            has_eq: true,
+            has_input: false,
+        };
+
         BusZone {
             zone_id,
             zone_name,
@@ -1829,15 +2455,33 @@ impl HqpAdapter {
             source: "hqplayer".to_string(),
             is_controllable: true,
             is_seekable: true,
+            capabilities,
+            volume_leveling: None,
             last_updated,
             is_play_allowed: state != PlaybackState::Playing,
             is_pause_allowed: state == PlaybackState::Playing,
             is_next_allowed: true,
             is_previous_allowed: true,
+            metadata: None,
         }
     }
 }
 
+/// Whether any pipeline setting (mode, filters, shaper, rate, matrix profile, ...) differs
+fn pipeline_differs(a: &HqpState, b: &HqpState) -> bool {
+    a.mode != b.mode
+        || a.filter != b.filter
+        || a.filter1x != b.filter1x
+        || a.filter_nx != b.filter_nx
+        || a.shaper != b.shaper
+        || a.rate != b.rate
+        || a.invert != b.invert
+        || a.convolution != b.convolution
+        || a.adaptive != b.adaptive
+        || a.filter_20k != b.filter_20k
+        || a.matrix_profile != b.matrix_profile
+}
+
 // =============================================================================
 // Multi-instance manager
 // =============================================================================
@@ -1852,10 +2496,15 @@ pub struct HqpInstanceInfo {
     pub info: Option<HqpInfo>,
 }
 
+/// Default interval for refreshing HQPlayer playback status
+const DEFAULT_STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Manager for multiple HQPlayer instances
 pub struct HqpInstanceManager {
     instances: Arc<RwLock<HashMap<String, Arc<HqpAdapter>>>>,
     bus: SharedBus,
+    /// Playback status refresh interval (configurable via app settings)
+    status_refresh_interval: Arc<RwLock<Duration>>,
 }
 
 impl HqpInstanceManager {
@@ -1864,9 +2513,73 @@ impl HqpInstanceManager {
         Self {
             instances: Arc::new(RwLock::new(HashMap::new())),
             bus,
+            status_refresh_interval: Arc::new(RwLock::new(DEFAULT_STATUS_REFRESH_INTERVAL)),
         }
     }
 
+    /// Set the status refresh interval (takes effect on the next refresh tick)
+    pub async fn set_status_refresh_interval(&self, refresh_interval: Duration) {
+        *self.status_refresh_interval.write().await = refresh_interval;
+    }
+
+    /// Current status refresh interval
+    pub async fn status_refresh_interval(&self) -> Duration {
+        *self.status_refresh_interval.read().await
+    }
+
+    /// Adapters the status refresh polls: every instance, including the
+    /// `default` one behind `AppState::hqplayer` (see [`Self::get_default`])
+    async fn refresh_targets(&self) -> Vec<Arc<HqpAdapter>> {
+        self.instances.read().await.values().cloned().collect()
+    }
+
+    /// Start periodic status refresh for all connected instances
+    ///
+    /// Each tick also diffs every instance's pipeline (see [`HqpAdapter::check_pipeline`]).
+    /// Only instances that are already connected are polled. Instances whose
+    /// connection was lost are reconnected through their circuit breaker's
+    /// half-open probe, so unreachable HQPlayers aren't hammered.
+    pub fn start_status_refresh(self: Arc<Self>, shutdown: CancellationToken) {
+        tokio::spawn(async move {
+            let mut current_interval = self.status_refresh_interval().await;
+            let mut ticker = tokio::time::interval(current_interval);
+            ticker.tick().await; // Skip immediate tick (connect already published zones)
+
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        tracing::debug!("HQPlayer status refresh shutting down");
+                        break;
+                    }
+                    _ = ticker.tick() => {
+                        let target_interval = self.status_refresh_interval().await;
+                        if target_interval != current_interval {
+                            tracing::debug!(
+                                "Adjusting HQPlayer status refresh: {:?} -> {:?}",
+                                current_interval, target_interval
+                            );
+                            current_interval = target_interval;
+                            ticker = tokio::time::interval(current_interval);
+                            ticker.tick().await;
+                        }
+
+                        for adapter in self.refresh_targets().await {
+                            if let Err(e) = adapter.probe_reconnect().await {
+                                tracing::debug!("HQPlayer reconnect probe failed: {}", e);
+                            }
+                            if let Err(e) = adapter.refresh_zone().await {
+                                tracing::debug!("HQPlayer status refresh failed: {}", e);
+                            }
+                            if let Err(e) = adapter.check_pipeline().await {
+                                tracing::debug!("HQPlayer pipeline check failed: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+
     /// Load instances from config file
     pub async fn load_from_config(&self) {
         let configs = load_hqp_configs();
@@ -1882,6 +2595,10 @@ impl HqpInstanceManager {
                     config.password,
                 )
                 .await;
+            adapter.set_connection_settings(config.connection).await;
+            if let Err(e) = adapter.set_web_tls(config.web_tls).await {
+                tracing::warn!("HQPlayer {}: ignoring web TLS settings: {}", config.name, e);
+            }
 
             let mut instances = self.instances.write().await;
             instances.insert(config.name, adapter);
@@ -1911,6 +2628,8 @@ impl HqpInstanceManager {
                     web_port: state.web_port,
                     username: state.web_username.clone(),
                     password: state.web_password.clone(),
+                    connection: state.connection_settings,
+                    web_tls: state.web_tls.clone(),
                 });
             }
         }
@@ -1975,6 +2694,7 @@ impl HqpInstanceManager {
     }
 
     /// Add or update an instance
+    #[allow(clippy::too_many_arguments)]
     pub async fn add_instance(
         &self,
         name: String,
@@ -1983,13 +2703,17 @@ impl HqpInstanceManager {
         web_port: Option<u16>,
         username: Option<String>,
         password: Option<String>,
-    ) -> Arc<HqpAdapter> {
+        connection: HqpConnectionSettings,
+        web_tls: HqpWebTls,
+    ) -> Result<Arc<HqpAdapter>> {
         let adapter = self.get_or_create(&name).await;
+        adapter.set_web_tls(web_tls).await?;
+        adapter.set_connection_settings(connection).await;
         adapter
             .configure(host, port, web_port, username, password)
             .await;
         self.save_to_config().await;
-        adapter
+        Ok(adapter)
     }
 
     /// Remove an instance by name
@@ -2003,6 +2727,34 @@ impl HqpInstanceManager {
         removed
     }
 
+    /// Connect all configured instances concurrently (best-effort warm-up)
+    ///
+    /// Commands connect lazily via `ensure_connected`, so failures here are only logged.
+    pub async fn connect_configured(&self) {
+        let adapters: Vec<(String, Arc<HqpAdapter>)> = {
+            let instances = self.instances.read().await;
+            instances
+                .iter()
+                .map(|(name, adapter)| (name.clone(), adapter.clone()))
+                .collect()
+        };
+
+        let connects = adapters.into_iter().map(|(name, adapter)| async move {
+            if !adapter.is_configured().await {
+                return;
+            }
+            match adapter.ensure_connected().await {
+                Ok(()) => tracing::info!("HQPlayer instance '{}' connected", name),
+                Err(e) => tracing::warn!(
+                    "HQPlayer instance '{}' connect failed (will retry on demand): {}",
+                    name,
+                    e
+                ),
+            }
+        });
+        futures::future::join_all(connects).await;
+    }
+
     /// Check if any instance is configured
     pub async fn has_instances(&self) -> bool {
         let instances = self.instances.read().await;
@@ -2140,12 +2892,7 @@ impl HqpZoneLinkService {
 
     /// Get HQP pipeline data for a linked zone
     pub async fn get_pipeline_for_zone(&self, zone_id: &str) -> Option<PipelineStatus> {
-        let instance_name = self.get_instance_for_zone(zone_id).await?;
-
-        let adapter = self.instances.get(&instance_name).await?;
-        if !adapter.is_configured().await {
-            return None;
-        }
+        let adapter = self.get_adapter_for_zone(zone_id).await?;
 
         match adapter.get_pipeline_status().await {
             Ok(pipeline) => Some(pipeline),
@@ -2156,6 +2903,16 @@ impl HqpZoneLinkService {
         }
     }
 
+    /// Get the configured HQPlayer instance a zone is linked to
+    pub async fn get_adapter_for_zone(&self, zone_id: &str) -> Option<Arc<HqpAdapter>> {
+        let instance_name = self.get_instance_for_zone(zone_id).await?;
+        let adapter = self.instances.get(&instance_name).await?;
+        if !adapter.is_configured().await {
+            return None;
+        }
+        Some(adapter)
+    }
+
     /// Remove all links pointing to a specific instance
     pub async fn remove_links_for_instance(&self, instance_name: &str) -> usize {
         let mut links = self.links.write().await;
@@ -2243,51 +3000,28 @@ pub async fn discover_hqplayers(timeout_ms: Option<u64>) -> Result<Vec<Discovere
     let timeout_duration = Duration::from_millis(timeout_ms.unwrap_or(HQP_DISCOVERY_TIMEOUT_MS));
     let mut discovered: HashMap<String, DiscoveredHqp> = HashMap::new();
 
-    // Create UDP socket
-    let socket = UdpSocket::bind("0.0.0.0:0").await?;
-
-    // Join multicast group
-    socket.set_broadcast(true)?;
+    // One socket per discovery interface (see `discovery` module)
+    let sockets = crate::adapters::discovery::bind_sockets().await?;
+    for socket in &sockets {
+        socket.set_broadcast(true)?;
+    }
 
-    // Send discovery message
     let message = b"<?xml version=\"1.0\"?><discover>hqplayer</discover>";
     let dest = SocketAddrV4::new(HQP_MULTICAST_ADDR, HQP_DISCOVERY_PORT);
-    socket.send_to(message, dest).await?;
-
     tracing::debug!(
-        "Sent HQPlayer discovery multicast to {}:{}",
+        "Sending HQPlayer discovery multicast to {}:{} from {} socket(s)",
         HQP_MULTICAST_ADDR,
-        HQP_DISCOVERY_PORT
+        HQP_DISCOVERY_PORT,
+        sockets.len()
     );
+    let replies =
+        crate::adapters::discovery::multicast_exchange(&sockets, dest, message, timeout_duration)
+            .await;
 
-    // Receive responses with timeout
-    let mut buf = [0u8; 2048];
-    let deadline = tokio::time::Instant::now() + timeout_duration;
-
-    loop {
-        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
-        if remaining.is_zero() {
-            break;
-        }
-
-        match timeout(remaining, socket.recv_from(&mut buf)).await {
-            Ok(Ok((len, addr))) => {
-                let response = String::from_utf8_lossy(&buf[..len]);
-                tracing::debug!("HQP discovery response from {}: {}", addr, response);
-
-                // Parse XML response
-                if let Some(hqp) = parse_discovery_response(&response, addr.ip().to_string()) {
-                    discovered.insert(hqp.host.clone(), hqp);
-                }
-            }
-            Ok(Err(e)) => {
-                tracing::warn!("HQP discovery recv error: {}", e);
-                break;
-            }
-            Err(_) => {
-                // Timeout - done receiving
-                break;
-            }
+    for (response, from) in replies {
+        tracing::debug!("HQP discovery response from {}: {}", from, response);
+        if let Some(hqp) = parse_discovery_response(&response, from.to_string()) {
+            discovered.insert(hqp.host.clone(), hqp);
         }
     }
 
@@ -2317,7 +3051,7 @@ fn parse_discovery_response(xml: &str, host: String) -> Option<DiscoveredHqp> {
 }
 
 /// Extract attribute value from XML string
-fn extract_xml_attr(xml: &str, attr: &str) -> Option<String> {
+pub(crate) fn extract_xml_attr(xml: &str, attr: &str) -> Option<String> {
     // Try double quotes
     let pattern = format!("{}=\"", attr);
     if let Some(start) = xml.find(&pattern) {
@@ -2338,3 +3072,27 @@ fn extract_xml_attr(xml: &str, attr: &str) -> Option<String> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn status_refresh_covers_default_instance() {
+        let manager = HqpInstanceManager::new(crate::bus::create_bus());
+        let default = manager.get_default().await;
+        let named = manager.get_or_create("study").await;
+
+        manager
+            .set_status_refresh_interval(Duration::from_secs(12))
+            .await;
+        assert_eq!(
+            manager.status_refresh_interval().await,
+            Duration::from_secs(12)
+        );
+
+        let targets = manager.refresh_targets().await;
+        assert!(targets.iter().any(|a| Arc::ptr_eq(a, &default)));
+        assert!(targets.iter().any(|a| Arc::ptr_eq(a, &named)));
+    }
+}
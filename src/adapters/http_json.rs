@@ -0,0 +1,1030 @@
+//! HTTP JSON adapter - bridges devices with a simple HTTP/JSON API as zones
+//!
+//! Each device is described by a template in `http-devices.json`: a base URL,
+//! the status endpoint with JSON paths to the fields the zone needs, and one
+//! request per control command. The status endpoint is polled like LMS or
+//! OpenHome; commands wake the poller so the zone follows right away.
+//!
+//! Paths are dotted (`player.state`, `queue.0.title` or `queue[0].title`).
+//! Command paths and bodies may contain `{value}`, replaced by the volume
+//! level or mute flag; a body string that is exactly `"{value}"` becomes the
+//! JSON number or boolean itself.
+//!
+//! The zone id is `http:<device id>`.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::{Client, Method, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::polling::{AdaptiveBackoff, PollWaker};
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeScale,
+    Zone, ZoneCapabilities,
+};
+use crate::clock::unix_millis;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::knobs::image::sniff_content_type;
+
+const HTTP_DEVICES_FILE: &str = "http-devices.json";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn config_path() -> PathBuf {
+    get_config_file_path(HTTP_DEVICES_FILE)
+}
+
+// =============================================================================
+// Device templates
+// =============================================================================
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpDevicesConfig {
+    #[serde(default)]
+    pub devices: Vec<HttpDeviceTemplate>,
+}
+
+/// How one device's HTTP API maps onto a zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpDeviceTemplate {
+    /// Zone id suffix (`http:<id>`)
+    pub id: String,
+    pub name: String,
+    /// Request paths are relative to this, e.g. `http://192.168.1.50:3000`
+    pub base_url: String,
+    /// Sent with every request (API keys, auth)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    pub status: StatusTemplate,
+    #[serde(default)]
+    pub commands: CommandTemplates,
+    #[serde(default)]
+    pub volume: VolumeRange,
+}
+
+/// The status request and where each zone field is in its response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusTemplate {
+    pub path: String,
+    pub state: Option<String>,
+    /// Device state values (`"1"`, `"true"`, `"PLAY"`) to `playing`, `paused` or `stopped`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub state_map: BTreeMap<String, PlaybackState>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// Artwork URL, absolute or relative to `base_url`
+    pub image: Option<String>,
+    pub position: Option<String>,
+    pub duration: Option<String>,
+    pub volume: Option<String>,
+    pub muted: Option<String>,
+    /// Unit of `position` and `duration`
+    pub time_unit: TimeUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeUnit {
+    #[default]
+    Seconds,
+    Milliseconds,
+}
+
+/// One request per command; commands left out aren't offered on the zone
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandTemplates {
+    pub play: Option<RequestTemplate>,
+    pub pause: Option<RequestTemplate>,
+    /// Falls back to `play` or `pause` by the current state when left out
+    pub play_pause: Option<RequestTemplate>,
+    pub stop: Option<RequestTemplate>,
+    pub next: Option<RequestTemplate>,
+    pub previous: Option<RequestTemplate>,
+    /// `{value}` is the level
+    pub volume: Option<RequestTemplate>,
+    /// `{value}` is `true` to mute, `false` to unmute
+    pub mute: Option<RequestTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// The device's volume scale
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeRange {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl Default for VolumeRange {
+    fn default() -> Self {
+        Self {
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+        }
+    }
+}
+
+impl VolumeRange {
+    fn scale(&self) -> VolumeScale {
+        if self.min == 0.0 && self.max == 100.0 {
+            VolumeScale::Percentage
+        } else if self.max <= 0.0 {
+            VolumeScale::Decibel
+        } else if self.min == 0.0 && self.max == 1.0 {
+            VolumeScale::Linear
+        } else {
+            VolumeScale::Unknown
+        }
+    }
+}
+
+impl HttpDevicesConfig {
+    /// Reject templates that can't work: duplicate or empty ids, bad URLs, bad methods
+    pub fn validate(&self) -> Result<()> {
+        let mut ids = HashSet::new();
+        for device in &self.devices {
+            let id = device.id.trim();
+            if id.is_empty() || id.contains(char::is_whitespace) {
+                bail!("Device id {:?} must be non-empty without spaces", device.id);
+            }
+            if !ids.insert(id) {
+                bail!("Duplicate device id: {}", id);
+            }
+            let url = Url::parse(&device.base_url)
+                .map_err(|e| anyhow!("{}: invalid base_url: {}", id, e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                bail!("{}: base_url must be http:// or https://", id);
+            }
+            if device.status.path.trim().is_empty() {
+                bail!("{}: status.path is required", id);
+            }
+            if device.volume.max <= device.volume.min {
+                bail!("{}: volume.max must be above volume.min", id);
+            }
+            for (command, template) in device.commands.iter() {
+                Method::from_bytes(template.method.to_uppercase().as_bytes()).map_err(|_| {
+                    anyhow!("{}: {} has invalid method {}", id, command, template.method)
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CommandTemplates {
+    fn iter(&self) -> impl Iterator<Item = (&'static str, &RequestTemplate)> {
+        [
+            ("play", &self.play),
+            ("pause", &self.pause),
+            ("play_pause", &self.play_pause),
+            ("stop", &self.stop),
+            ("next", &self.next),
+            ("previous", &self.previous),
+            ("volume", &self.volume),
+            ("mute", &self.mute),
+        ]
+        .into_iter()
+        .filter_map(|(name, template)| template.as_ref().map(|t| (name, t)))
+    }
+}
+
+// =============================================================================
+// JSON paths and templates
+// =============================================================================
+
+/// Look up a dotted path (`player.volume`, `queue.0.title`, `queue[0].title`)
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim().trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+    for segment in path.split(['.', '[']) {
+        let key = segment.trim_end_matches(']');
+        if key.is_empty() {
+            continue;
+        }
+        current = match current {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            Value::Object(map) => map.get(key)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        // Artists are often a list
+        Value::Array(items) => {
+            let parts: Vec<String> = items.iter().filter_map(as_text).collect();
+            (!parts.is_empty()).then(|| parts.join(", "))
+        }
+        _ => None,
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "1" | "on" | "yes" => Some(true),
+            "false" | "0" | "off" | "no" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Replace `{value}` in a request path (URL-encoded)
+fn render_path(path: &str, value: Option<&Value>) -> String {
+    match value.and_then(as_text) {
+        Some(text) => path.replace("{value}", &urlencoding::encode(&text)),
+        None => path.to_string(),
+    }
+}
+
+/// Replace `{value}` in a request body
+fn render_body(body: &Value, value: Option<&Value>) -> Value {
+    let Some(value) = value else {
+        return body.clone();
+    };
+    match body {
+        Value::String(s) if s == "{value}" => value.clone(),
+        Value::String(s) => {
+            Value::String(s.replace("{value}", &as_text(value).unwrap_or_default()))
+        }
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| render_body(v, Some(value))).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_body(v, Some(value))))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+impl HttpDeviceTemplate {
+    fn zone_id(&self) -> PrefixedZoneId {
+        PrefixedZoneId::http(&self.id)
+    }
+
+    fn field(&self, status: &Value, path: &Option<String>) -> Option<Value> {
+        path.as_deref()
+            .and_then(|path| json_path(status, path))
+            .filter(|v| !v.is_null())
+            .cloned()
+    }
+
+    fn text(&self, status: &Value, path: &Option<String>) -> Option<String> {
+        self.field(status, path)
+            .and_then(|v| as_text(&v))
+            .filter(|s| !s.is_empty())
+    }
+
+    fn seconds(&self, status: &Value, path: &Option<String>) -> Option<f64> {
+        let raw = as_number(&self.field(status, path)?)?;
+        Some(match self.status.time_unit {
+            TimeUnit::Seconds => raw,
+            TimeUnit::Milliseconds => raw / 1000.0,
+        })
+    }
+
+    fn state(&self, status: &Value) -> PlaybackState {
+        let Some(value) = self.field(status, &self.status.state) else {
+            return PlaybackState::Unknown;
+        };
+        let text = as_text(&value).unwrap_or_default();
+        let mapped = self
+            .status
+            .state_map
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&text))
+            .map(|(_, state)| *state);
+        match (mapped, &value) {
+            (Some(state), _) => state,
+            (None, Value::Bool(true)) => PlaybackState::Playing,
+            (None, Value::Bool(false)) => PlaybackState::Stopped,
+            (None, _) => PlaybackState::from(text.as_str()),
+        }
+    }
+
+    /// Artwork URL from the status, resolved against `base_url`
+    fn image_url(&self, status: &Value) -> Option<String> {
+        let image = self.text(status, &self.status.image)?;
+        let base = Url::parse(&self.base_url).ok()?;
+        base.join(&image).ok().map(String::from)
+    }
+
+    fn url(&self, path: &str) -> Result<Url> {
+        Ok(Url::parse(&self.base_url)?.join(path)?)
+    }
+
+    /// The zone for a status response, with `last_updated` left at 0
+    fn zone(&self, status: &Value) -> Zone {
+        let state = self.state(status);
+        let title = self.text(status, &self.status.title);
+        let now_playing = title.map(|title| NowPlaying {
+            title,
+            artist: self.text(status, &self.status.artist).unwrap_or_default(),
+            album: self.text(status, &self.status.album).unwrap_or_default(),
+            image_key: self.image_url(status),
+            seek_position: self.seconds(status, &self.status.position),
+            duration: self.seconds(status, &self.status.duration),
+            metadata: None,
+        });
+        let zone_id = self.zone_id();
+        let volume_control = self
+            .field(status, &self.status.volume)
+            .and_then(|v| as_number(&v))
+            .map(|value| VolumeControl {
+                value: value as f32,
+                min: self.volume.min,
+                max: self.volume.max,
+                step: self.volume.step,
+                is_muted: self
+                    .field(status, &self.status.muted)
+                    .and_then(|v| as_bool(&v))
+                    .unwrap_or(false),
+                scale: self.volume.scale(),
+                output_id: Some(zone_id.to_string()),
+            });
+        let commands = &self.commands;
+        let can_play = commands.play.is_some() || commands.play_pause.is_some();
+        let can_pause = commands.pause.is_some() || commands.play_pause.is_some();
+        Zone {
+            volume_control,
+            zone_id: zone_id.to_string(),
+            zone_name: self.name.clone(),
+            state,
+            now_playing,
+            source: "http".to_string(),
+            is_controllable: commands.iter().next().is_some(),
+            is_seekable: false,
+            capabilities: ZoneCapabilities {
+                has_volume: commands.volume.is_some(),
+                ..Default::default()
+            },
+            volume_leveling: None,
+            last_updated: 0,
+            is_play_allowed: can_play && state != PlaybackState::Playing,
+            is_pause_allowed: can_pause && state == PlaybackState::Playing,
+            is_next_allowed: commands.next.is_some(),
+            is_previous_allowed: commands.previous.is_some(),
+            metadata: None,
+        }
+    }
+}
+
+// =============================================================================
+// Adapter
+// =============================================================================
+
+/// One device as reported by GET /api/adapters/http/config
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpDeviceSummary {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    pub online: bool,
+    /// Why the last status request failed
+    pub error: Option<String>,
+}
+
+/// HTTP JSON adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpJsonStatus {
+    /// Any device answering
+    pub connected: bool,
+    pub devices: Vec<HttpDeviceSummary>,
+}
+
+#[derive(Debug, Default)]
+struct DeviceState {
+    online: bool,
+    error: Option<String>,
+    /// Zone as last published
+    zone: Option<Zone>,
+}
+
+struct HttpJsonState {
+    config: HttpDevicesConfig,
+    devices: HashMap<String, DeviceState>,
+    poll_interval: Duration,
+    running: bool,
+}
+
+/// Adapter for devices described by HTTP JSON templates
+#[derive(Clone)]
+pub struct HttpJsonAdapter {
+    state: Arc<RwLock<HttpJsonState>>,
+    bus: SharedBus,
+    http: Client,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+    poll_waker: PollWaker,
+}
+
+impl HttpJsonAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        let adapter = Self {
+            state: Arc::new(RwLock::new(HttpJsonState {
+                config: HttpDevicesConfig::default(),
+                devices: HashMap::new(),
+                poll_interval: DEFAULT_POLL_INTERVAL,
+                running: false,
+            })),
+            bus,
+            http: Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+            poll_waker: PollWaker::default(),
+        };
+        adapter.load_config_sync();
+        adapter
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(HTTP_DEVICES_FILE) else {
+            return;
+        };
+        let saved = match serde_json::from_str::<HttpDevicesConfig>(&content) {
+            Ok(saved) => saved,
+            Err(e) => {
+                tracing::warn!("Failed to parse HTTP JSON devices: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = saved.validate() {
+            tracing::warn!("Ignoring HTTP JSON devices: {}", e);
+            return;
+        }
+        if let Ok(mut state) = self.state.try_write() {
+            tracing::info!(
+                "Loaded {} HTTP JSON device(s) from disk",
+                saved.devices.len()
+            );
+            state.config = saved;
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let config = self.state.read().await.config.clone();
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&config) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save HTTP JSON devices: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize HTTP JSON devices: {}", e),
+        }
+    }
+
+    /// Device templates
+    pub async fn config(&self) -> HttpDevicesConfig {
+        self.state.read().await.config.clone()
+    }
+
+    /// Replace the device templates (takes effect when the adapter restarts)
+    pub async fn set_config(&self, config: HttpDevicesConfig) -> Result<()> {
+        config.validate()?;
+        self.state.write().await.config = config;
+        self.save_config().await;
+        Ok(())
+    }
+
+    /// Check if configured
+    pub async fn is_configured(&self) -> bool {
+        !self.state.read().await.config.devices.is_empty()
+    }
+
+    pub async fn set_poll_interval(&self, poll_interval: Duration) {
+        self.state.write().await.poll_interval = poll_interval;
+    }
+
+    /// Get connection status
+    pub async fn get_status(&self) -> HttpJsonStatus {
+        let state = self.state.read().await;
+        let devices: Vec<HttpDeviceSummary> = state
+            .config
+            .devices
+            .iter()
+            .map(|device| {
+                let runtime = state.devices.get(&device.id);
+                HttpDeviceSummary {
+                    id: device.id.clone(),
+                    name: device.name.clone(),
+                    state: runtime
+                        .and_then(|d| d.zone.as_ref())
+                        .map(|z| z.state.to_string())
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    online: runtime.is_some_and(|d| d.online),
+                    error: runtime.and_then(|d| d.error.clone()),
+                }
+            })
+            .collect();
+        HttpJsonStatus {
+            connected: devices.iter().any(|d| d.online),
+            devices,
+        }
+    }
+
+    /// Start polling (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            if state.config.devices.is_empty() {
+                bail!("No HTTP JSON devices configured");
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("HTTP JSON adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+        self.state.write().await.running = false;
+        self.clear_zones().await;
+        tracing::info!("HTTP JSON adapter stopped");
+    }
+
+    /// Remove every published zone
+    async fn clear_zones(&self) {
+        let removed: Vec<Zone> = {
+            let mut state = self.state.write().await;
+            state
+                .devices
+                .drain()
+                .filter_map(|(_, device)| device.zone)
+                .collect()
+        };
+        for zone in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone.zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    async fn device(&self, raw_id: &str) -> Result<HttpDeviceTemplate> {
+        self.state
+            .read()
+            .await
+            .config
+            .devices
+            .iter()
+            .find(|d| d.id == raw_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown HTTP JSON device: {}", raw_id))
+    }
+
+    /// Send a templated request and return the JSON response (`null` if it isn't JSON)
+    async fn send(
+        &self,
+        device: &HttpDeviceTemplate,
+        template: &RequestTemplate,
+        value: Option<&Value>,
+    ) -> Result<Value> {
+        let method = Method::from_bytes(template.method.to_uppercase().as_bytes())?;
+        let url = device.url(&render_path(&template.path, value))?;
+        let mut request = self.http.request(method, url);
+        for (name, header) in &device.headers {
+            request = request.header(name, header);
+        }
+        if let Some(ref body) = template.body {
+            request = request.json(&render_body(body, value));
+        }
+        let response = request.send().await?.error_for_status()?;
+        let bytes = response.bytes().await?;
+        Ok(serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    /// Fetch one device's status and publish its zone if it changed
+    async fn poll_device(&self, device: &HttpDeviceTemplate) {
+        let template = RequestTemplate {
+            method: default_method(),
+            path: device.status.path.clone(),
+            body: None,
+        };
+        let result = self.send(device, &template, None).await;
+        let event = {
+            let mut state = self.state.write().await;
+            let runtime = state.devices.entry(device.id.clone()).or_default();
+            match result {
+                Ok(status) => {
+                    runtime.online = true;
+                    runtime.error = None;
+                    let zone = device.zone(&status);
+                    if runtime.zone.as_ref() != Some(&zone) {
+                        runtime.zone = Some(zone.clone());
+                        let mut zone = zone;
+                        zone.last_updated = unix_millis();
+                        Some(BusEvent::ZoneDiscovered { zone })
+                    } else {
+                        None
+                    }
+                }
+                Err(e) => {
+                    if runtime.online {
+                        tracing::warn!("HTTP JSON device {} unreachable: {}", device.id, e);
+                    }
+                    runtime.online = false;
+                    runtime.error = Some(e.to_string());
+                    runtime.zone.take().map(|_| BusEvent::ZoneRemoved {
+                        zone_id: device.zone_id(),
+                    })
+                }
+            }
+        };
+        if let Some(event) = event {
+            self.bus.publish(event);
+        }
+    }
+
+    async fn current_zone(&self, raw_id: &str) -> Option<Zone> {
+        self.state
+            .read()
+            .await
+            .devices
+            .get(raw_id)
+            .and_then(|d| d.zone.clone())
+    }
+
+    /// Run a command's request, failing if the template doesn't define it
+    async fn command(&self, raw_id: &str, command: &str, value: Option<&Value>) -> Result<()> {
+        let device = self.device(raw_id).await?;
+        let template = device
+            .commands
+            .iter()
+            .find(|(name, _)| *name == command)
+            .map(|(_, template)| template.clone())
+            .ok_or_else(|| anyhow!("{} has no {} command", device.name, command))?;
+        let target = device.zone_id();
+        ops::timed(
+            "http",
+            command,
+            Some(target.as_str()),
+            self.send(&device, &template, value),
+        )
+        .await?;
+        // Follow the change right away
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Play, pause, play_pause, stop, next or previous
+    pub async fn control(&self, raw_id: &str, action: &str) -> Result<()> {
+        let command = match action {
+            "play" | "pause" | "stop" | "next" | "previous" => action,
+            "prev" => "previous",
+            "play_pause" | "playpause" => {
+                let device = self.device(raw_id).await?;
+                if device.commands.play_pause.is_some() {
+                    "play_pause"
+                } else {
+                    let playing = self
+                        .current_zone(raw_id)
+                        .await
+                        .is_some_and(|z| z.state == PlaybackState::Playing);
+                    if playing {
+                        "pause"
+                    } else {
+                        "play"
+                    }
+                }
+            }
+            _ => bail!("Unknown action: {}", action),
+        };
+        self.command(raw_id, command, None).await
+    }
+
+    /// Set the volume, clamped to the device's range
+    pub async fn set_volume(&self, raw_id: &str, level: f32) -> Result<()> {
+        let range = self.device(raw_id).await?.volume;
+        let level = level.clamp(range.min, range.max);
+        // Whole steps go out as integers, which is what most APIs expect
+        let value = if range.step.fract() == 0.0 {
+            Value::from(level.round() as i64)
+        } else {
+            Value::from(level as f64)
+        };
+        self.command(raw_id, "volume", Some(&value)).await
+    }
+
+    /// Change the volume by `delta` from the last polled level
+    pub async fn change_volume(&self, raw_id: &str, delta: f32) -> Result<()> {
+        let current = self
+            .current_zone(raw_id)
+            .await
+            .and_then(|z| z.volume_control)
+            .map(|v| v.value)
+            .ok_or_else(|| anyhow!("{} reports no volume", raw_id))?;
+        self.set_volume(raw_id, current + delta).await
+    }
+
+    pub async fn set_mute(&self, raw_id: &str, mute: bool) -> Result<()> {
+        self.command(raw_id, "mute", Some(&Value::Bool(mute))).await
+    }
+
+    /// Last polled mute state
+    pub async fn is_muted(&self, raw_id: &str) -> bool {
+        self.current_zone(raw_id)
+            .await
+            .and_then(|z| z.volume_control)
+            .is_some_and(|v| v.is_muted)
+    }
+
+    /// Fetch the artwork a device currently reports (`image_key` is its URL)
+    ///
+    /// Only the URL in the device's last status is fetched, so the bridge
+    /// can't be used to request arbitrary URLs.
+    pub async fn get_image(&self, raw_id: &str, image_key: &str) -> Result<crate::bus::ImageData> {
+        let device = self.device(raw_id).await?;
+        let current = self
+            .current_zone(raw_id)
+            .await
+            .and_then(|z| z.now_playing)
+            .and_then(|np| np.image_key);
+        if current.as_deref() != Some(image_key) {
+            bail!("Invalid image key");
+        }
+        let mut request = self.http.get(image_key);
+        for (name, header) in &device.headers {
+            request = request.header(name, header);
+        }
+        let response = request.send().await?.error_for_status()?;
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        Ok(crate::bus::ImageData {
+            content_type: sniff_content_type(&body, declared.as_deref()),
+            data: body.to_vec(),
+        })
+    }
+}
+
+crate::impl_startable!(HttpJsonAdapter, "http", is_configured);
+
+#[async_trait]
+impl AdapterLogic for HttpJsonAdapter {
+    fn prefix(&self) -> &'static str {
+        "http"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        let mut backoff = AdaptiveBackoff::default();
+        // First poll happens immediately
+        let mut delay = Duration::ZERO;
+
+        loop {
+            tokio::select! {
+                _ = ctx.shutdown.cancelled() => break,
+                _ = self.poll_waker.woken() => {
+                    backoff.reset();
+                }
+                _ = tokio::time::sleep(delay) => {}
+            }
+
+            let devices = self.state.read().await.config.devices.clone();
+            futures::future::join_all(devices.iter().map(|device| self.poll_device(device))).await;
+
+            // Poll at the base interval while anything plays, back off while idle
+            delay = {
+                let state = self.state.read().await;
+                let any_playing = state.devices.values().any(|d| {
+                    d.zone
+                        .as_ref()
+                        .is_some_and(|z| z.state == PlaybackState::Playing)
+                });
+                backoff.next_delay(state.poll_interval, any_playing)
+            };
+        }
+
+        self.clear_zones().await;
+        Ok(())
+    }
+
+    async fn handle_command(
+        &self,
+        zone_id: &str,
+        command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        let raw_id = zone_id.strip_prefix("http:").unwrap_or(zone_id);
+        let result = match command {
+            AdapterCommand::Play => self.control(raw_id, "play").await,
+            AdapterCommand::Pause => self.control(raw_id, "pause").await,
+            AdapterCommand::PlayPause => self.control(raw_id, "play_pause").await,
+            AdapterCommand::Stop => self.control(raw_id, "stop").await,
+            AdapterCommand::Next => self.control(raw_id, "next").await,
+            AdapterCommand::Previous => self.control(raw_id, "previous").await,
+            AdapterCommand::VolumeAbsolute(v) => self.set_volume(raw_id, v as f32).await,
+            AdapterCommand::VolumeRelative(delta) => self.change_volume(raw_id, delta as f32).await,
+            AdapterCommand::Mute(mute) => self.set_mute(raw_id, mute).await,
+        };
+
+        match result {
+            Ok(()) => Ok(AdapterCommandResponse {
+                success: true,
+                error: None,
+            }),
+            Err(e) => Ok(AdapterCommandResponse {
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn volumio() -> HttpDeviceTemplate {
+        serde_json::from_value(json!({
+            "id": "volumio",
+            "name": "Volumio",
+            "base_url": "http://volumio.local",
+            "status": {
+                "path": "/api/v1/getState",
+                "state": "status",
+                "title": "title",
+                "artist": "artist",
+                "album": "album",
+                "image": "albumart",
+                "position": "seek",
+                "duration": "duration",
+                "volume": "volume",
+                "muted": "mute"
+            },
+            "commands": {
+                "play": { "path": "/api/v1/commands/?cmd=play" },
+                "pause": { "path": "/api/v1/commands/?cmd=pause" },
+                "volume": { "path": "/api/v1/commands/?cmd=volume&volume={value}" }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_json_path_walks_objects_and_arrays() {
+        let value = json!({ "player": { "queue": [{ "title": "A" }, { "title": "B" }] } });
+        assert_eq!(json_path(&value, "player.queue.1.title"), Some(&json!("B")));
+        assert_eq!(
+            json_path(&value, "$.player.queue[0].title"),
+            Some(&json!("A"))
+        );
+        assert_eq!(json_path(&value, "player.queue.5.title"), None);
+        assert_eq!(json_path(&value, "player.missing"), None);
+    }
+
+    #[test]
+    fn test_status_becomes_zone() {
+        let mut device = volumio();
+        device.status.time_unit = TimeUnit::Milliseconds;
+        assert!(HttpDevicesConfig {
+            devices: vec![device.clone()]
+        }
+        .validate()
+        .is_ok());
+
+        let status = json!({
+            "status": "play",
+            "title": "So What",
+            "artist": "Miles Davis",
+            "album": "Kind of Blue",
+            "albumart": "/albumart?path=kob.jpg",
+            "seek": 61000,
+            "duration": 562000,
+            "volume": 35,
+            "mute": false
+        });
+        let zone = device.zone(&status);
+        assert_eq!(zone.zone_id, "http:volumio");
+        assert_eq!(zone.state, PlaybackState::Playing);
+        let np = zone.now_playing.unwrap();
+        assert_eq!(np.title, "So What");
+        assert_eq!(
+            np.image_key.as_deref(),
+            Some("http://volumio.local/albumart?path=kob.jpg")
+        );
+        assert_eq!(np.seek_position, Some(61.0));
+        assert_eq!(np.duration, Some(562.0));
+        assert_eq!(zone.volume_control.unwrap().value, 35.0);
+        assert!(zone.is_pause_allowed);
+        assert!(!zone.is_next_allowed);
+        assert!(zone.capabilities.has_volume);
+    }
+
+    #[test]
+    fn test_state_map_overrides_defaults() {
+        let mut device = volumio();
+        device
+            .status
+            .state_map
+            .insert("1".to_string(), PlaybackState::Playing);
+        assert_eq!(
+            device.state(&json!({ "status": 1 })),
+            PlaybackState::Playing
+        );
+        assert_eq!(
+            device.state(&json!({ "status": "pause" })),
+            PlaybackState::Paused
+        );
+        assert_eq!(device.state(&json!({})), PlaybackState::Unknown);
+    }
+
+    #[test]
+    fn test_value_placeholders() {
+        assert_eq!(
+            render_path("/cmd?volume={value}", Some(&json!(42))),
+            "/cmd?volume=42"
+        );
+        let body = json!({ "level": "{value}", "label": "vol {value}" });
+        assert_eq!(
+            render_body(&body, Some(&json!(42))),
+            json!({ "level": 42, "label": "vol 42" })
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_ids_and_bad_urls() {
+        let mut config = HttpDevicesConfig {
+            devices: vec![volumio(), volumio()],
+        };
+        assert!(config.validate().is_err());
+        config.devices.pop();
+        config.devices[0].base_url = "ftp://volumio.local".to_string();
+        assert!(config.validate().is_err());
+    }
+}
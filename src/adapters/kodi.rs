@@ -0,0 +1,1032 @@
+//! Kodi adapter - shows a Kodi media center's playback as a zone
+//!
+//! Connects to Kodi's JSON-RPC WebSocket (`ws://host:9090/jsonrpc`, enabled by
+//! "Allow remote control from applications on other systems") and reads the
+//! active player, its item, position and the volume, refreshing them whenever
+//! Kodi sends a `Player.*` or `Application.OnVolumeChanged` notification. Kodi
+//! doesn't push playback progress, so the position is polled while playing.
+//! Commands and artwork go over HTTP (`http://host:8080`, "Allow remote control
+//! via HTTP"), with the web server login if one is set.
+//!
+//! The zone id is `kodi:<host>`.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeScale,
+    Zone, ZoneCapabilities,
+};
+use crate::clock::unix_millis;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::knobs::image::sniff_content_type;
+
+const KODI_CONFIG_FILE: &str = "kodi-config.json";
+/// Kodi's web server port (HTTP JSON-RPC and images)
+const DEFAULT_PORT: u16 = 8080;
+/// Kodi's JSON-RPC WebSocket port
+const DEFAULT_WS_PORT: u16 = 9090;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often the position is refreshed while playing
+const POSITION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Request ids on the WebSocket
+const ID_ACTIVE_PLAYERS: u64 = 1;
+const ID_APPLICATION: u64 = 2;
+const ID_NAME: u64 = 3;
+const ID_ITEM: u64 = 4;
+const ID_PLAYER_PROPERTIES: u64 = 5;
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedKodiConfig {
+    host: String,
+    port: u16,
+    #[serde(default = "default_ws_port")]
+    ws_port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+fn default_ws_port() -> u16 {
+    DEFAULT_WS_PORT
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(KODI_CONFIG_FILE)
+}
+
+// =============================================================================
+// JSON-RPC model
+// =============================================================================
+
+/// A playing item (`Player.GetItem`)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct KodiItem {
+    #[serde(default)]
+    label: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    artist: Vec<String>,
+    #[serde(default)]
+    album: String,
+    #[serde(default)]
+    showtitle: String,
+    /// `image://...` path, served by the web server under `/image/`
+    #[serde(default)]
+    thumbnail: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+struct KodiTime {
+    #[serde(default)]
+    hours: u64,
+    #[serde(default)]
+    minutes: u64,
+    #[serde(default)]
+    seconds: u64,
+    #[serde(default)]
+    milliseconds: u64,
+}
+
+impl KodiTime {
+    fn secs(&self) -> f64 {
+        (self.hours * 3600 + self.minutes * 60 + self.seconds) as f64
+            + self.milliseconds as f64 / 1000.0
+    }
+
+    fn from_secs(secs: f64) -> Self {
+        let millis = (secs.max(0.0) * 1000.0).round() as u64;
+        Self {
+            hours: millis / 3_600_000,
+            minutes: millis / 60_000 % 60,
+            seconds: millis / 1000 % 60,
+            milliseconds: millis % 1000,
+        }
+    }
+}
+
+/// `Player.GetProperties` result
+#[derive(Debug, Clone, Default, Deserialize)]
+struct KodiPlayerProperties {
+    #[serde(default)]
+    speed: i64,
+    #[serde(default)]
+    time: KodiTime,
+    #[serde(default)]
+    totaltime: KodiTime,
+    #[serde(default)]
+    canseek: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct ActivePlayer {
+    playerid: i64,
+}
+
+/// Everything the WebSocket connection knows
+#[derive(Debug, Default)]
+struct KodiSystem {
+    /// `System.FriendlyName`
+    name: Option<String>,
+    /// Active audio or video player, `None` while idle
+    player: Option<ActivePlayer>,
+    item: Option<KodiItem>,
+    /// 0 while paused
+    speed: i64,
+    position: Option<f64>,
+    duration: Option<f64>,
+    can_seek: bool,
+    volume: u8,
+    muted: bool,
+}
+
+/// What applying a message did
+#[derive(Debug, Default, PartialEq)]
+struct Applied {
+    /// Whether the zone may have changed
+    changed: bool,
+    /// Whether the position moved
+    moved: bool,
+    /// Requests to send on the WebSocket
+    requests: Vec<String>,
+}
+
+fn request(id: u64, method: &str, params: Value) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string()
+}
+
+fn active_players_request() -> String {
+    request(ID_ACTIVE_PLAYERS, "Player.GetActivePlayers", json!({}))
+}
+
+/// Requests that read the whole state on connect
+fn initial_requests() -> Vec<String> {
+    vec![
+        request(
+            ID_APPLICATION,
+            "Application.GetProperties",
+            json!({ "properties": ["volume", "muted"] }),
+        ),
+        request(
+            ID_NAME,
+            "XBMC.GetInfoLabels",
+            json!({ "labels": ["System.FriendlyName"] }),
+        ),
+        active_players_request(),
+    ]
+}
+
+fn item_request(playerid: i64) -> String {
+    request(
+        ID_ITEM,
+        "Player.GetItem",
+        json!({
+            "playerid": playerid,
+            "properties": ["title", "artist", "album", "showtitle", "thumbnail"],
+        }),
+    )
+}
+
+fn properties_request(playerid: i64) -> String {
+    request(
+        ID_PLAYER_PROPERTIES,
+        "Player.GetProperties",
+        json!({
+            "playerid": playerid,
+            "properties": ["speed", "time", "totaltime", "canseek"],
+        }),
+    )
+}
+
+impl KodiSystem {
+    /// Apply a response or notification from the WebSocket
+    fn apply(&mut self, message: &Value) -> Applied {
+        let mut applied = Applied::default();
+        if let Some(method) = message["method"].as_str() {
+            self.apply_notification(method, &message["params"]["data"], &mut applied);
+            return applied;
+        }
+        if let Some(error) = message.get("error") {
+            tracing::debug!(
+                "Kodi request {} failed: {}",
+                message["id"],
+                error["message"].as_str().unwrap_or("unknown error")
+            );
+            return applied;
+        }
+        let result = &message["result"];
+        match message["id"].as_u64() {
+            Some(ID_ACTIVE_PLAYERS) => {
+                let players: Vec<ActivePlayer> =
+                    serde_json::from_value(result.clone()).unwrap_or_default();
+                self.player = players.first().copied();
+                match self.player {
+                    Some(player) => {
+                        applied.requests.push(item_request(player.playerid));
+                        applied.requests.push(properties_request(player.playerid));
+                    }
+                    None => {
+                        self.item = None;
+                        self.speed = 0;
+                        self.position = None;
+                        self.duration = None;
+                        self.can_seek = false;
+                    }
+                }
+                applied.changed = true;
+            }
+            Some(ID_APPLICATION) => {
+                self.apply_volume(result);
+                applied.changed = true;
+            }
+            Some(ID_NAME) => {
+                self.name = result["System.FriendlyName"]
+                    .as_str()
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string);
+                applied.changed = true;
+            }
+            Some(ID_ITEM) => {
+                self.item = serde_json::from_value::<KodiItem>(result["item"].clone())
+                    .ok()
+                    .filter(|item| *item != KodiItem::default());
+                applied.changed = true;
+            }
+            Some(ID_PLAYER_PROPERTIES) => {
+                let Ok(properties) = serde_json::from_value::<KodiPlayerProperties>(result.clone())
+                else {
+                    return applied;
+                };
+                let duration = Some(properties.totaltime.secs()).filter(|&secs| secs > 0.0);
+                applied.changed = self.speed != properties.speed
+                    || self.duration != duration
+                    || self.can_seek != properties.canseek;
+                let position = Some(properties.time.secs());
+                applied.moved = self.position != position;
+                self.speed = properties.speed;
+                self.position = position;
+                self.duration = duration;
+                self.can_seek = properties.canseek;
+            }
+            _ => {}
+        }
+        applied
+    }
+
+    fn apply_notification(&mut self, method: &str, data: &Value, applied: &mut Applied) {
+        match method {
+            "Application.OnVolumeChanged" => {
+                self.apply_volume(data);
+                applied.changed = true;
+            }
+            // The player or its item changed: start over from the active players
+            "Player.OnPlay" | "Player.OnAVStart" | "Player.OnAVChange" | "Player.OnStop" => {
+                applied.requests.push(active_players_request());
+            }
+            "Player.OnPause"
+            | "Player.OnResume"
+            | "Player.OnSeek"
+            | "Player.OnSpeedChanged"
+            | "Player.OnPropertyChanged" => match self.player {
+                Some(player) => applied.requests.push(properties_request(player.playerid)),
+                None => applied.requests.push(active_players_request()),
+            },
+            _ => {}
+        }
+    }
+
+    fn apply_volume(&mut self, value: &Value) {
+        if let Some(volume) = value["volume"].as_f64() {
+            self.volume = volume.clamp(0.0, 100.0).round() as u8;
+        }
+        if let Some(muted) = value["muted"].as_bool() {
+            self.muted = muted;
+        }
+    }
+
+    fn state(&self) -> PlaybackState {
+        match self.player {
+            None => PlaybackState::Stopped,
+            Some(_) if self.speed == 0 => PlaybackState::Paused,
+            Some(_) => PlaybackState::Playing,
+        }
+    }
+
+    fn now_playing(&self) -> Option<NowPlaying> {
+        let item = self.item.as_ref()?;
+        let title = if item.title.is_empty() {
+            item.label.clone()
+        } else {
+            item.title.clone()
+        };
+        // Episodes show their series where music shows the artist
+        let artist = if item.artist.is_empty() {
+            item.showtitle.clone()
+        } else {
+            item.artist.join(", ")
+        };
+        Some(NowPlaying {
+            title,
+            artist,
+            album: item.album.clone(),
+            image_key: Some(item.thumbnail.clone()).filter(|t| !t.is_empty()),
+            seek_position: self.position,
+            duration: self.duration,
+            metadata: None,
+        })
+    }
+
+    /// The zone, with `last_updated` left at 0 so unchanged zones compare equal
+    fn zone(&self, zone_id: &PrefixedZoneId) -> Zone {
+        let state = self.state();
+        let active = self.player.is_some();
+        Zone {
+            volume_control: Some(VolumeControl {
+                value: self.volume as f32,
+                min: 0.0,
+                max: 100.0,
+                step: 1.0,
+                is_muted: self.muted,
+                scale: VolumeScale::Percentage,
+                output_id: Some(zone_id.to_string()),
+            }),
+            zone_id: zone_id.to_string(),
+            zone_name: self.name.clone().unwrap_or_else(|| "Kodi".to_string()),
+            state,
+            now_playing: self.now_playing(),
+            source: "kodi".to_string(),
+            is_controllable: true,
+            is_seekable: self.can_seek,
+            capabilities: ZoneCapabilities {
+                can_seek: self.can_seek,
+                has_volume: true,
+                ..Default::default()
+            },
+            volume_leveling: None,
+            last_updated: 0,
+            is_play_allowed: active && state != PlaybackState::Playing,
+            is_pause_allowed: state == PlaybackState::Playing,
+            is_next_allowed: active,
+            is_previous_allowed: active,
+            metadata: None,
+        }
+    }
+}
+
+/// The zone as compared between publishes: position changes are published on their own
+fn without_position(zone: &Zone) -> Zone {
+    let mut zone = zone.clone();
+    if let Some(ref mut np) = zone.now_playing {
+        np.seek_position = None;
+    }
+    zone
+}
+
+// =============================================================================
+// Adapter
+// =============================================================================
+
+/// Kodi adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct KodiStatus {
+    pub connected: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    pub ws_port: u16,
+    /// Kodi's friendly name, once connected
+    pub name: Option<String>,
+    pub state: String,
+}
+
+struct KodiState {
+    host: Option<String>,
+    port: u16,
+    ws_port: u16,
+    username: Option<String>,
+    password: Option<String>,
+    connected: bool,
+    running: bool,
+    system: KodiSystem,
+    /// Zone as last published (without position), to publish only what changed
+    published: Option<Zone>,
+}
+
+/// Kodi adapter for HTPC playback
+#[derive(Clone)]
+pub struct KodiAdapter {
+    state: Arc<RwLock<KodiState>>,
+    bus: SharedBus,
+    http: Client,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+}
+
+impl KodiAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        let adapter = Self {
+            state: Arc::new(RwLock::new(KodiState {
+                host: None,
+                port: DEFAULT_PORT,
+                ws_port: DEFAULT_WS_PORT,
+                username: None,
+                password: None,
+                connected: false,
+                running: false,
+                system: KodiSystem::default(),
+                published: None,
+            })),
+            bus,
+            http: Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+        };
+        adapter.load_config_sync();
+        adapter
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(KODI_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedKodiConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut state) = self.state.try_write() {
+                    tracing::info!(
+                        "Loaded Kodi config from disk: {}:{}",
+                        saved.host,
+                        saved.port
+                    );
+                    state.host = Some(saved.host);
+                    state.port = saved.port;
+                    state.ws_port = saved.ws_port;
+                    state.username = saved.username;
+                    state.password = saved.password;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse Kodi config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let saved = {
+            let state = self.state.read().await;
+            let Some(ref host) = state.host else {
+                return;
+            };
+            SavedKodiConfig {
+                host: host.clone(),
+                port: state.port,
+                ws_port: state.ws_port,
+                username: state.username.clone(),
+                password: state.password.clone(),
+            }
+        };
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&saved) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save Kodi config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize Kodi config: {}", e),
+        }
+    }
+
+    /// Configure the Kodi host, web server port and login
+    pub async fn configure(
+        &self,
+        host: String,
+        port: Option<u16>,
+        username: Option<String>,
+        password: Option<String>,
+    ) {
+        {
+            let mut state = self.state.write().await;
+            state.host = Some(host);
+            state.port = port.unwrap_or(DEFAULT_PORT);
+            state.username = username.filter(|u| !u.is_empty());
+            state.password = password.filter(|p| !p.is_empty());
+        }
+        self.save_config().await;
+    }
+
+    /// Web server login (username, password)
+    pub async fn credentials(&self) -> (Option<String>, Option<String>) {
+        let state = self.state.read().await;
+        (state.username.clone(), state.password.clone())
+    }
+
+    /// Check if configured
+    pub async fn is_configured(&self) -> bool {
+        self.state.read().await.host.is_some()
+    }
+
+    /// Get connection status
+    pub async fn get_status(&self) -> KodiStatus {
+        let state = self.state.read().await;
+        KodiStatus {
+            connected: state.connected,
+            host: state.host.clone(),
+            port: state.port,
+            ws_port: state.ws_port,
+            name: state.system.name.clone(),
+            state: state.system.state().to_string(),
+        }
+    }
+
+    /// Start the WebSocket connection (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            if state.host.is_none() {
+                bail!("Kodi host not configured");
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("Kodi adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+        self.state.write().await.running = false;
+        self.clear_zone().await;
+        tracing::info!("Kodi adapter stopped");
+    }
+
+    async fn zone_id(&self) -> Result<PrefixedZoneId> {
+        let state = self.state.read().await;
+        let host = state
+            .host
+            .as_ref()
+            .ok_or_else(|| anyhow!("Kodi host not configured"))?;
+        Ok(PrefixedZoneId::kodi(host))
+    }
+
+    /// Publish the zone if it changed, or just its position if only that moved
+    async fn publish_zone(&self, moved: bool) {
+        let Ok(zone_id) = self.zone_id().await else {
+            return;
+        };
+        let event = {
+            let mut state = self.state.write().await;
+            let zone = state.system.zone(&zone_id);
+            let compared = without_position(&zone);
+            if state.published.as_ref() != Some(&compared) {
+                state.published = Some(compared);
+                let mut zone = zone;
+                zone.last_updated = unix_millis();
+                Some(BusEvent::ZoneDiscovered { zone })
+            } else if moved {
+                state
+                    .system
+                    .position
+                    .map(|position| BusEvent::SeekPositionChanged {
+                        zone_id: zone_id.clone(),
+                        position: position as i64,
+                    })
+            } else {
+                None
+            }
+        };
+        if let Some(event) = event {
+            self.bus.publish(event);
+        }
+    }
+
+    /// Forget the system state and remove the zone
+    async fn clear_zone(&self) {
+        let published = {
+            let mut state = self.state.write().await;
+            state.connected = false;
+            state.system = KodiSystem::default();
+            state.published.take()
+        };
+        if let Some(zone) = published {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone.zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// Call a JSON-RPC method over HTTP
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        let target = self.zone_id().await?;
+        ops::timed(
+            "kodi",
+            method,
+            Some(target.as_str()),
+            self.rpc_request(method, params),
+        )
+        .await
+    }
+
+    async fn rpc_request(&self, method: &str, params: Value) -> Result<Value> {
+        let (url, username, password) = {
+            let state = self.state.read().await;
+            let host = state
+                .host
+                .as_ref()
+                .ok_or_else(|| anyhow!("Kodi host not configured"))?;
+            (
+                format!("http://{}:{}/jsonrpc", host, state.port),
+                state.username.clone(),
+                state.password.clone(),
+            )
+        };
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut request = self.http.post(&url).json(&body);
+        if let Some(username) = username {
+            request = request.basic_auth(username, password);
+        }
+        let response: Value = request.send().await?.error_for_status()?.json().await?;
+        if let Some(error) = response.get("error") {
+            bail!(
+                "Kodi {} failed: {}",
+                method,
+                error["message"].as_str().unwrap_or("unknown error")
+            );
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Id of the active player, failing while nothing plays
+    async fn playerid(&self) -> Result<i64> {
+        self.state
+            .read()
+            .await
+            .system
+            .player
+            .map(|p| p.playerid)
+            .ok_or_else(|| anyhow!("Nothing is playing on Kodi"))
+    }
+
+    /// Play, pause, play_pause, stop, next or previous on the active player
+    pub async fn control(&self, action: &str) -> Result<()> {
+        let playerid = self.playerid().await?;
+        let (method, params) = match action {
+            "play" => (
+                "Player.PlayPause",
+                json!({ "playerid": playerid, "play": true }),
+            ),
+            "pause" => (
+                "Player.PlayPause",
+                json!({ "playerid": playerid, "play": false }),
+            ),
+            "play_pause" | "playpause" => (
+                "Player.PlayPause",
+                json!({ "playerid": playerid, "play": "toggle" }),
+            ),
+            "stop" => ("Player.Stop", json!({ "playerid": playerid })),
+            "next" => ("Player.GoTo", json!({ "playerid": playerid, "to": "next" })),
+            "previous" | "prev" => (
+                "Player.GoTo",
+                json!({ "playerid": playerid, "to": "previous" }),
+            ),
+            _ => bail!("Unknown action: {}", action),
+        };
+        self.rpc(method, params).await?;
+        Ok(())
+    }
+
+    /// Seek the active player to `seconds` into the item
+    pub async fn seek(&self, seconds: f64) -> Result<()> {
+        let playerid = self.playerid().await?;
+        let time = KodiTime::from_secs(seconds);
+        self.rpc(
+            "Player.Seek",
+            json!({ "playerid": playerid, "value": { "time": time } }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Set Kodi's volume (0-100)
+    pub async fn set_volume(&self, level: f32) -> Result<()> {
+        let level = level.clamp(0.0, 100.0).round() as u8;
+        self.rpc("Application.SetVolume", json!({ "volume": level }))
+            .await?;
+        Ok(())
+    }
+
+    /// Change Kodi's volume by `delta` percentage points
+    pub async fn change_volume(&self, delta: f32) -> Result<()> {
+        let current = self.state.read().await.system.volume;
+        self.set_volume(current as f32 + delta).await
+    }
+
+    /// Mute or unmute Kodi
+    pub async fn set_mute(&self, mute: bool) -> Result<()> {
+        self.rpc("Application.SetMute", json!({ "mute": mute }))
+            .await?;
+        Ok(())
+    }
+
+    /// Current mute state
+    pub async fn is_muted(&self) -> bool {
+        self.state.read().await.system.muted
+    }
+
+    /// Fetch artwork through Kodi's web server (`thumbnail` is the zone's `image_key`)
+    pub async fn get_image(&self, thumbnail: &str) -> Result<crate::bus::ImageData> {
+        if thumbnail.is_empty() {
+            bail!("Invalid image key");
+        }
+        let (url, username, password) = {
+            let state = self.state.read().await;
+            let host = state
+                .host
+                .as_ref()
+                .ok_or_else(|| anyhow!("Kodi host not configured"))?;
+            (
+                format!(
+                    "http://{}:{}/image/{}",
+                    host,
+                    state.port,
+                    urlencoding::encode(thumbnail)
+                ),
+                state.username.clone(),
+                state.password.clone(),
+            )
+        };
+        let mut request = self.http.get(&url);
+        if let Some(username) = username {
+            request = request.basic_auth(username, password);
+        }
+        let response = request.send().await?.error_for_status()?;
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        Ok(crate::bus::ImageData {
+            content_type: sniff_content_type(&body, declared.as_deref()),
+            data: body.to_vec(),
+        })
+    }
+}
+
+crate::impl_startable!(KodiAdapter, "kodi", is_configured);
+
+#[async_trait]
+impl AdapterLogic for KodiAdapter {
+    fn prefix(&self) -> &'static str {
+        "kodi"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        let url = {
+            let state = self.state.read().await;
+            let host = state
+                .host
+                .as_ref()
+                .ok_or_else(|| anyhow!("Kodi host not configured"))?;
+            format!("ws://{}:{}/jsonrpc", host, state.ws_port)
+        };
+        let connect = tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(&url));
+        let (ws, _) = tokio::select! {
+            _ = ctx.shutdown.cancelled() => return Ok(()),
+            result = connect => result.map_err(|_| anyhow!("Timed out connecting to {}", url))??,
+        };
+        tracing::info!("Connected to Kodi at {}", url);
+        self.state.write().await.connected = true;
+
+        let (mut sink, mut stream) = ws.split();
+        for request in initial_requests() {
+            sink.send(Message::text(request)).await?;
+        }
+        let mut position_poll = tokio::time::interval(POSITION_POLL_INTERVAL);
+        position_poll.tick().await;
+
+        let result = loop {
+            let message = tokio::select! {
+                _ = ctx.shutdown.cancelled() => break Ok(()),
+                _ = position_poll.tick() => {
+                    let playing = {
+                        let state = self.state.read().await;
+                        state.system.player.filter(|_| state.system.speed != 0)
+                    };
+                    if let Some(player) = playing {
+                        if let Err(e) = sink.send(Message::text(properties_request(player.playerid))).await {
+                            break Err(e.into());
+                        }
+                    }
+                    continue;
+                }
+                message = stream.next() => message,
+            };
+            let text = match message {
+                None => break Err(anyhow!("Kodi closed the connection")),
+                Some(Err(e)) => break Err(e.into()),
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) => break Err(anyhow!("Kodi closed the connection")),
+                Some(Ok(_)) => continue,
+            };
+            let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                tracing::debug!("Ignoring unparseable Kodi message");
+                continue;
+            };
+
+            let applied = self.state.write().await.system.apply(&value);
+            if applied.changed || applied.moved {
+                self.publish_zone(applied.moved).await;
+            }
+            let mut failed = None;
+            for request in applied.requests {
+                if let Err(e) = sink.send(Message::text(request)).await {
+                    failed = Some(e);
+                    break;
+                }
+            }
+            if let Some(e) = failed {
+                break Err(e.into());
+            }
+        };
+
+        let _ = sink.close().await;
+        self.clear_zone().await;
+        result
+    }
+
+    async fn handle_command(
+        &self,
+        _zone_id: &str,
+        command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        let result = match command {
+            AdapterCommand::Play => self.control("play").await,
+            AdapterCommand::Pause => self.control("pause").await,
+            AdapterCommand::PlayPause => self.control("play_pause").await,
+            AdapterCommand::Stop => self.control("stop").await,
+            AdapterCommand::Next => self.control("next").await,
+            AdapterCommand::Previous => self.control("previous").await,
+            AdapterCommand::VolumeAbsolute(v) => self.set_volume(v as f32).await,
+            AdapterCommand::VolumeRelative(delta) => self.change_volume(delta as f32).await,
+            AdapterCommand::Mute(mute) => self.set_mute(mute).await,
+        };
+
+        match result {
+            Ok(()) => Ok(AdapterCommandResponse {
+                success: true,
+                error: None,
+            }),
+            Err(e) => Ok(AdapterCommandResponse {
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(id: u64, result: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "result": result })
+    }
+
+    fn playing_system() -> KodiSystem {
+        let mut system = KodiSystem::default();
+        let applied = system.apply(&response(
+            ID_ACTIVE_PLAYERS,
+            json!([{ "playerid": 1, "type": "video" }]),
+        ));
+        assert_eq!(applied.requests, [item_request(1), properties_request(1)]);
+        system.apply(&response(
+            ID_ITEM,
+            json!({ "item": {
+                "type": "episode", "label": "Pilot", "title": "Pilot", "artist": [],
+                "album": "", "showtitle": "Show", "thumbnail": "image://x%2fthumb.jpg/"
+            }}),
+        ));
+        system.apply(&response(
+            ID_PLAYER_PROPERTIES,
+            json!({
+                "speed": 1, "canseek": true,
+                "time": { "hours": 0, "minutes": 1, "seconds": 30, "milliseconds": 500 },
+                "totaltime": { "hours": 1, "minutes": 0, "seconds": 0, "milliseconds": 0 }
+            }),
+        ));
+        system
+    }
+
+    #[test]
+    fn test_playing_item_becomes_zone() {
+        let mut system = playing_system();
+        system.apply(&json!({
+            "jsonrpc": "2.0", "method": "Application.OnVolumeChanged",
+            "params": { "data": { "volume": 42, "muted": true }, "sender": "xbmc" }
+        }));
+        system.apply(&response(
+            ID_NAME,
+            json!({ "System.FriendlyName": "Living Room HTPC" }),
+        ));
+
+        let zone = system.zone(&PrefixedZoneId::kodi("htpc.local"));
+        assert_eq!(zone.zone_id, "kodi:htpc.local");
+        assert_eq!(zone.zone_name, "Living Room HTPC");
+        assert_eq!(zone.state, PlaybackState::Playing);
+        assert!(zone.is_seekable && zone.capabilities.can_seek);
+        let np = zone.now_playing.unwrap();
+        assert_eq!(np.title, "Pilot");
+        assert_eq!(np.artist, "Show");
+        assert_eq!(np.image_key.as_deref(), Some("image://x%2fthumb.jpg/"));
+        assert_eq!(np.seek_position, Some(90.5));
+        assert_eq!(np.duration, Some(3600.0));
+        let volume = zone.volume_control.unwrap();
+        assert_eq!(volume.value, 42.0);
+        assert!(volume.is_muted);
+    }
+
+    #[test]
+    fn test_notifications_request_fresh_state() {
+        let mut system = playing_system();
+        let applied = system.apply(&json!({
+            "jsonrpc": "2.0", "method": "Player.OnPause",
+            "params": { "data": { "player": { "playerid": 1, "speed": 0 } }, "sender": "xbmc" }
+        }));
+        assert_eq!(applied.requests, [properties_request(1)]);
+
+        // Only the position moved
+        let applied = system.apply(&response(
+            ID_PLAYER_PROPERTIES,
+            json!({
+                "speed": 1, "canseek": true,
+                "time": { "minutes": 1, "seconds": 35 },
+                "totaltime": { "hours": 1 }
+            }),
+        ));
+        assert!(applied.moved && !applied.changed);
+
+        // Stopping clears the zone's playback
+        let applied =
+            system.apply(&json!({ "jsonrpc": "2.0", "method": "Player.OnStop", "params": {} }));
+        assert_eq!(applied.requests, [active_players_request()]);
+        system.apply(&response(ID_ACTIVE_PLAYERS, json!([])));
+        let zone = system.zone(&PrefixedZoneId::kodi("htpc.local"));
+        assert_eq!(zone.state, PlaybackState::Stopped);
+        assert!(zone.now_playing.is_none());
+        assert_eq!(zone.zone_name, "Kodi");
+    }
+
+    #[test]
+    fn test_time_round_trips() {
+        let time = KodiTime::from_secs(3723.25);
+        assert_eq!(
+            time,
+            KodiTime {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 250
+            }
+        );
+        assert_eq!(time.secs(), 3723.25);
+    }
+}
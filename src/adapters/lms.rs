@@ -27,6 +27,11 @@
 //! CLI reconnects  → flag = true  → Polling slows again
 //! ```
 //!
+//! Events emitted while the CLI socket is down are lost, so every (re)subscribe
+//! is followed by a resync: a full status fetch for all players that publishes
+//! ZoneUpdated/NowPlayingChanged/VolumeChanged for each one, whether or not the
+//! cached state changed.
+//!
 //! As of Issue #165, these are split into two independent adapters:
 //! - `LmsAdapter`: Polling only
 //! - `LmsCliAdapter`: CLI subscription only
@@ -36,8 +41,11 @@
 //!
 //! ## Configuration
 //!
-//! - `LMS_POLL_INTERVAL`: Base poll interval in seconds (default: 2)
+//! - `polling.lms_secs` in app-settings.json: Base poll interval in seconds (default: 2)
+//! - `LMS_POLL_INTERVAL`: Env override for the base interval (wins over app settings)
 //! - When CLI active, polling runs at 15x base interval (default: 30s)
+//! - When CLI inactive, polling backs off from the base interval while all players
+//!   are idle and wakes immediately on control commands (see `adapters::polling`)
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
@@ -51,18 +59,23 @@ use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 use tokio::sync::RwLock;
-use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
-use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::handle::{
+    AdapterHandle, CircuitBreaker, CircuitBreakerConfig, CircuitState, RetryConfig,
+};
 use crate::adapters::lms_discovery::discover_lms_servers;
+use crate::adapters::ops;
+use crate::adapters::polling::{AdaptiveBackoff, PollWaker};
 use crate::adapters::traits::{
     AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
 };
 use crate::adapters::Startable;
-use crate::bus::{BusEvent, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, Zone};
-use crate::config::{get_config_file_path, read_config_file};
+use crate::bus::{
+    BusEvent, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeLeveling, Zone,
+};
+use crate::config::{get_config_file_path, read_config_file, PollingSettings};
 
 const LMS_CONFIG_FILE: &str = "lms-config.json";
 /// Request ID for LMS JSON-RPC calls (aids debugging in LMS logs)
@@ -84,25 +97,40 @@ fn config_path() -> PathBuf {
 }
 
 const DEFAULT_PORT: u16 = 9000;
+/// JSON-RPC/artwork request timeout
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+/// HTTP and CLI connect timeout, shorter so an unreachable server fails fast
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// How often the player is checked while an announcement plays
+const ANNOUNCE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long bridge messages (scene names, announcements) stay on a player's
+/// display
+pub const DISPLAY_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+/// Favorites searched by name
+const MAX_FAVORITES: u32 = 500;
 /// CLI telnet port for event subscription
-const CLI_PORT: u16 = 9090;
+pub const CLI_PORT: u16 = 9090;
 /// Default poll interval in seconds (when no subscription active)
 const DEFAULT_POLL_INTERVAL_SECS: u64 = 2;
 /// Multiplier for poll interval when subscription is active (15x base interval)
 const SUBSCRIPTION_INTERVAL_MULTIPLIER: u64 = 15;
 
-/// Get the poll interval from LMS_POLL_INTERVAL env var, or use default
-fn get_poll_interval() -> Duration {
+/// Get the poll interval override from LMS_POLL_INTERVAL env var (if set)
+fn env_poll_interval() -> Option<Duration> {
     std::env::var("LMS_POLL_INTERVAL")
         .ok()
         .and_then(|s| s.parse::<u64>().ok())
-        .map(Duration::from_secs)
-        .unwrap_or(Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS))
+        .map(|secs| Duration::from_secs(PollingSettings::clamp_secs(secs)))
+}
+
+/// Get the initial poll interval from LMS_POLL_INTERVAL env var, or use default
+fn get_poll_interval() -> Duration {
+    env_poll_interval().unwrap_or(Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS))
 }
 
 /// Get the poll interval when subscription is active (15x base interval)
-fn get_poll_interval_with_subscription() -> Duration {
-    let base = get_poll_interval();
+fn poll_interval_with_subscription(base: Duration) -> Duration {
     Duration::from_secs(base.as_secs() * SUBSCRIPTION_INTERVAL_MULTIPLIER)
 }
 /// TCP read timeout for CLI subscription (detect unresponsive LMS)
@@ -241,11 +269,17 @@ pub fn parse_cli_event(line: &str) -> CliEvent {
 struct LmsRpc {
     state: Arc<RwLock<LmsState>>,
     client: Client,
+    /// Shared with the polling task, whose requests double as recovery probes
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl LmsRpc {
     fn new(state: Arc<RwLock<LmsState>>, client: Client) -> Self {
-        Self { state, client }
+        Self {
+            state,
+            client,
+            breaker: Arc::new(CircuitBreaker::new("lms", CircuitBreakerConfig::default())),
+        }
     }
 
     async fn base_url(&self) -> Result<String> {
@@ -257,8 +291,25 @@ impl LmsRpc {
         Ok(format!("http://{}:{}", host, state.port))
     }
 
+    /// Run a JSON-RPC request, recorded as an operation named after its command
     async fn execute(&self, player_id: Option<&str>, params: Vec<Value>) -> Result<Value> {
+        let command = params
+            .first()
+            .and_then(|p| p.as_str())
+            .unwrap_or("request")
+            .to_string();
+        ops::timed(
+            "lms",
+            &command,
+            player_id,
+            self.execute_request(player_id, params),
+        )
+        .await
+    }
+
+    async fn execute_request(&self, player_id: Option<&str>, params: Vec<Value>) -> Result<Value> {
         let base_url = self.base_url().await?;
+        self.breaker.check()?;
         let url = format!("{}/jsonrpc.js", base_url);
 
         let body = json!({
@@ -287,7 +338,16 @@ impl LmsRpc {
             }
         }
 
-        let response = request.send().await?;
+        // Only transport failures count toward the circuit breaker; an HTTP or
+        // JSON-RPC error means the server is up
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                self.breaker.record_failure();
+                return Err(e.into());
+            }
+        };
+        self.breaker.record_success();
 
         if !response.status().is_success() {
             return Err(anyhow!("LMS request failed: {}", response.status()));
@@ -403,6 +463,70 @@ impl LmsRpc {
         })
     }
 
+    /// ReplayGain mode from the `replayGainMode` player pref
+    /// (0 = off, 1 = track, 2 = album, 3 = smart)
+    async fn get_replay_gain(&self, player_id: &str) -> Result<VolumeLeveling> {
+        let result = self
+            .execute(
+                Some(player_id),
+                vec![json!("playerpref"), json!("replayGainMode"), json!("?")],
+            )
+            .await?;
+        let value = result.get("_p2").and_then(|v| {
+            v.as_i64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+        });
+        match value {
+            Some(0) => Ok(VolumeLeveling::Off),
+            Some(1) => Ok(VolumeLeveling::Track),
+            Some(2) => Ok(VolumeLeveling::Album),
+            Some(3) => Ok(VolumeLeveling::Smart),
+            _ => Err(anyhow!("Unexpected replayGainMode: {:?}", result)),
+        }
+    }
+
+    async fn set_replay_gain(&self, player_id: &str, mode: VolumeLeveling) -> Result<()> {
+        let value = match mode {
+            VolumeLeveling::Off => 0,
+            VolumeLeveling::Track => 1,
+            VolumeLeveling::Album => 2,
+            VolumeLeveling::Smart => 3,
+        };
+        self.execute(
+            Some(player_id),
+            vec![json!("playerpref"), json!("replayGainMode"), json!(value)],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// A `mixer` setting such as `bass` or `treble` (reported as `_bass`, `_treble`)
+    async fn get_mixer(&self, player_id: &str, setting: &str) -> Result<i32> {
+        let result = self
+            .execute(
+                Some(player_id),
+                vec![json!("mixer"), json!(setting), json!("?")],
+            )
+            .await?;
+        result
+            .get(format!("_{}", setting))
+            .and_then(|v| {
+                v.as_f64()
+                    .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+            })
+            .map(|v| v.round() as i32)
+            .ok_or_else(|| anyhow!("Unexpected mixer {}: {:?}", setting, result))
+    }
+
+    async fn set_mixer(&self, player_id: &str, setting: &str, value: i32) -> Result<()> {
+        self.execute(
+            Some(player_id),
+            vec![json!("mixer"), json!(setting), json!(value)],
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn get_players(&self) -> Result<Vec<LmsPlayer>> {
         let result = self
             .execute(None, vec![json!("players"), json!(0), json!(100)])
@@ -441,6 +565,20 @@ impl LmsRpc {
     }
 }
 
+/// Bass and treble of a player with tone controls
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LmsTone {
+    pub bass: i32,
+    pub treble: i32,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// Saved playlist holding a player's queue while it is in standby
+fn resume_playlist_name(player_id: &str) -> String {
+    format!("uhc_resume_{}", player_id.replace(':', ""))
+}
+
 /// LMS Player information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmsPlayer {
@@ -464,6 +602,8 @@ pub struct LmsPlayer {
     pub artwork_track_id: Option<String>,
     pub coverid: Option<String>,
     pub artwork_url: Option<String>,
+    /// ReplayGain mode (`replayGainMode` player pref), `None` until fetched
+    pub replay_gain: Option<VolumeLeveling>,
 }
 
 impl Default for LmsPlayer {
@@ -488,10 +628,72 @@ impl Default for LmsPlayer {
             artwork_track_id: None,
             coverid: None,
             artwork_url: None,
+            replay_gain: None,
         }
     }
 }
 
+/// Kind of library rescan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LmsRescanMode {
+    /// New and changed files only
+    #[default]
+    Changes,
+    /// Clear the library and scan everything (`wipecache`)
+    Full,
+    /// Playlists only
+    Playlists,
+}
+
+/// Library scan progress, from `serverstatus`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LmsScanStatus {
+    pub scanning: bool,
+    /// Current scan step (e.g. `discovering_files`), while scanning
+    pub step: Option<String>,
+    /// Items done and total of the current step
+    pub done: Option<u64>,
+    pub total: Option<u64>,
+    /// When the last scan finished (seconds since epoch)
+    pub last_scan: Option<i64>,
+    /// Why the last scan failed, if it did
+    pub last_scan_failed: Option<String>,
+}
+
+/// LMS reports numbers in `serverstatus` as either strings or numbers
+fn status_number(result: &Value, key: &str) -> Option<i64> {
+    match result.get(key)? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_scan_status(result: &Value) -> LmsScanStatus {
+    let scanning = status_number(result, "rescan").unwrap_or(0) != 0;
+    LmsScanStatus {
+        scanning,
+        step: result
+            .get("progressname")
+            .and_then(|v| v.as_str())
+            .filter(|_| scanning)
+            .map(str::to_string),
+        done: status_number(result, "progressdone")
+            .filter(|_| scanning)
+            .map(|n| n.max(0) as u64),
+        total: status_number(result, "progresstotal")
+            .filter(|_| scanning)
+            .map(|n| n.max(0) as u64),
+        last_scan: status_number(result, "lastscan"),
+        last_scan_failed: result
+            .get("lastscanfailed")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+    }
+}
+
 /// LMS connection status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmsStatus {
@@ -504,6 +706,9 @@ pub struct LmsStatus {
     pub cli_subscription_active: bool,
     /// Effective poll interval in seconds (2s base, 30s when CLI active)
     pub poll_interval_secs: u64,
+    /// Circuit breaker state; `open` means requests fail fast until the next poll probes
+    #[serde(default)]
+    pub circuit: CircuitState,
 }
 
 /// Summary information about an LMS player for status reporting
@@ -530,6 +735,8 @@ struct LmsState {
     players: HashMap<String, LmsPlayer>,
     /// Whether CLI subscription is active (for reduced polling frequency)
     cli_subscription_active: bool,
+    /// Base poll interval (from app settings or LMS_POLL_INTERVAL)
+    poll_interval: Duration,
 }
 
 impl Default for LmsState {
@@ -543,6 +750,7 @@ impl Default for LmsState {
             running: false,
             players: HashMap::new(),
             cli_subscription_active: false,
+            poll_interval: get_poll_interval(),
         }
     }
 }
@@ -555,6 +763,8 @@ pub struct LmsAdapter {
     bus: SharedBus,
     /// Wrapped in RwLock to allow creating fresh token on restart
     shutdown: Arc<RwLock<CancellationToken>>,
+    /// Wakes the adaptive poll loop after control commands
+    poll_waker: PollWaker,
 }
 
 impl LmsAdapter {
@@ -562,7 +772,8 @@ impl LmsAdapter {
         let state = Arc::new(RwLock::new(LmsState::default()));
         #[allow(clippy::expect_used)] // HTTP client creation only fails if TLS setup fails
         let client = Client::builder()
-            .timeout(Duration::from_secs(10))
+            .timeout(HTTP_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
             .build()
             .expect("Failed to create HTTP client");
         let rpc = LmsRpc::new(state.clone(), client);
@@ -571,6 +782,7 @@ impl LmsAdapter {
             rpc,
             bus,
             shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+            poll_waker: PollWaker::default(),
         };
         // Load saved config synchronously at startup
         adapter.load_config_sync();
@@ -699,6 +911,7 @@ impl LmsAdapter {
             state.password = password;
             state.connected = false;
         }
+        self.rpc.breaker.reset();
         // Persist to disk
         self.save_config().await;
     }
@@ -708,12 +921,18 @@ impl LmsAdapter {
         self.state.read().await.host.is_some()
     }
 
+    /// Configured (username, password)
+    pub async fn credentials(&self) -> (Option<String>, Option<String>) {
+        let state = self.state.read().await;
+        (state.username.clone(), state.password.clone())
+    }
+
     /// Get connection status
     pub async fn get_status(&self) -> LmsStatus {
         let state = self.state.read().await;
-        let base_interval = get_poll_interval();
+        let base_interval = state.poll_interval;
         let effective_interval = if state.cli_subscription_active {
-            get_poll_interval_with_subscription()
+            poll_interval_with_subscription(base_interval)
         } else {
             base_interval
         };
@@ -734,9 +953,20 @@ impl LmsAdapter {
                 .collect(),
             cli_subscription_active: state.cli_subscription_active,
             poll_interval_secs: effective_interval.as_secs(),
+            circuit: self.rpc.breaker.state(),
         }
     }
 
+    /// Set the base poll interval (takes effect on the next poll tick)
+    /// LMS_POLL_INTERVAL, when set, wins over app settings.
+    pub async fn set_poll_interval(&self, interval: Duration) {
+        if env_poll_interval().is_some() {
+            debug!("LMS_POLL_INTERVAL set, ignoring configured poll interval");
+            return;
+        }
+        self.state.write().await.poll_interval = interval;
+    }
+
     /// Get list of all players (delegates to shared RPC)
     pub async fn get_players(&self) -> Result<Vec<LmsPlayer>> {
         self.rpc.get_players().await
@@ -806,6 +1036,8 @@ impl LmsAdapter {
             // "pause" without args toggles pause state - matches expected UI behavior
             "pause" => vec![json!("pause")],
             "stop" => vec![json!("stop")],
+            "standby" => vec![json!("power"), json!(0)],
+            "power_on" => vec![json!("power"), json!(1)],
             "play_pause" => vec![json!("pause")], // Toggle
             "next" => vec![json!("playlist"), json!("index"), json!("+1")],
             "previous" | "prev" => vec![json!("playlist"), json!("index"), json!("-1")],
@@ -826,6 +1058,7 @@ impl LmsAdapter {
         };
 
         self.rpc.execute(Some(player_id), params).await?;
+        self.poll_waker.wake();
 
         // Update status after command
         let player_id = player_id.to_string();
@@ -848,6 +1081,371 @@ impl LmsAdapter {
         Ok(())
     }
 
+    /// Save the player's queue, then power it off, so [`Self::resume_last`]
+    /// can bring the queue back even if it is replaced meanwhile
+    pub async fn standby(&self, player_id: &str) -> Result<()> {
+        let has_queue = {
+            let state = self.state.read().await;
+            state
+                .players
+                .get(player_id)
+                .is_some_and(|player| player.playlist_tracks > 0)
+        };
+        if has_queue {
+            self.rpc
+                .execute(
+                    Some(player_id),
+                    vec![
+                        json!("playlist"),
+                        json!("save"),
+                        json!(resume_playlist_name(player_id)),
+                        json!("silent:1"),
+                    ],
+                )
+                .await?;
+        }
+        self.control(player_id, "standby", None).await
+    }
+
+    /// Power on and resume the queue saved by the last [`Self::standby`],
+    /// from the track it was on; without one, play the current queue
+    pub async fn resume_last(&self, player_id: &str) -> Result<()> {
+        let name = resume_playlist_name(player_id);
+        let saved = self
+            .rpc
+            .execute(
+                None,
+                vec![
+                    json!("playlists"),
+                    json!(0),
+                    json!(1),
+                    json!(format!("search:{}", name)),
+                ],
+            )
+            .await?;
+        self.control(player_id, "power_on", None).await?;
+        if saved["count"].as_u64().unwrap_or(0) == 0 {
+            return self.control(player_id, "play", None).await;
+        }
+        self.rpc
+            .execute(
+                Some(player_id),
+                vec![json!("playlist"), json!("resume"), json!(name)],
+            )
+            .await?;
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Set the player's ReplayGain mode and republish its zone
+    pub async fn set_volume_leveling(&self, player_id: &str, mode: VolumeLeveling) -> Result<()> {
+        self.rpc.set_replay_gain(player_id, mode).await?;
+
+        let zone = {
+            let mut state = self.state.write().await;
+            state.players.get_mut(player_id).map(|player| {
+                player.replay_gain = Some(mode);
+                lms_player_to_zone(player)
+            })
+        };
+        if let Some(zone) = zone {
+            self.bus.publish(BusEvent::ZoneDiscovered { zone });
+        }
+        Ok(())
+    }
+
+    /// Bass and treble of a player; `None` if its model has no tone controls
+    pub async fn get_tone(&self, player_id: &str) -> Result<Option<LmsTone>> {
+        let Some((min, max)) = self.player_tone_range(player_id).await else {
+            return Ok(None);
+        };
+        let bass = self.rpc.get_mixer(player_id, "bass").await?;
+        let treble = self.rpc.get_mixer(player_id, "treble").await?;
+        Ok(Some(LmsTone {
+            bass,
+            treble,
+            min,
+            max,
+        }))
+    }
+
+    /// Set bass and/or treble, clamped to the player's range
+    pub async fn set_tone(
+        &self,
+        player_id: &str,
+        bass: Option<i32>,
+        treble: Option<i32>,
+    ) -> Result<()> {
+        let (min, max) = self
+            .player_tone_range(player_id)
+            .await
+            .ok_or_else(|| anyhow!("Player {} has no tone controls", player_id))?;
+        if let Some(bass) = bass {
+            self.rpc
+                .set_mixer(player_id, "bass", bass.clamp(min, max))
+                .await?;
+        }
+        if let Some(treble) = treble {
+            self.rpc
+                .set_mixer(player_id, "treble", treble.clamp(min, max))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn player_tone_range(&self, player_id: &str) -> Option<(i32, i32)> {
+        let state = self.state.read().await;
+        state
+            .players
+            .get(player_id)
+            .and_then(|player| tone_range(&player.model))
+    }
+
+    /// Sync `followers` to `leader` so they play the leader's playlist
+    pub async fn sync_players(&self, leader: &str, followers: &[&str]) -> Result<()> {
+        for follower in followers {
+            self.rpc
+                .execute(Some(leader), vec![json!("sync"), json!(follower)])
+                .await?;
+        }
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Replace the player's playlist with `item` (a URL, file path or saved
+    /// playlist file) and start playing it
+    pub async fn play_playlist(&self, player_id: &str, item: &str) -> Result<()> {
+        self.rpc
+            .execute(
+                Some(player_id),
+                vec![json!("playlist"), json!("play"), json!(item)],
+            )
+            .await?;
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Replace the player's playlist with a library album and start playing it
+    ///
+    /// Matches the album and artist by name; fails when the library has no
+    /// such album.
+    pub async fn play_album(&self, player_id: &str, artist: &str, album: &str) -> Result<()> {
+        let artist = if artist.is_empty() { "*" } else { artist };
+        self.rpc
+            .execute(
+                Some(player_id),
+                vec![
+                    json!("playlist"),
+                    json!("loadalbum"),
+                    json!("*"),
+                    json!(artist),
+                    json!(album),
+                ],
+            )
+            .await?;
+        let count = self
+            .rpc
+            .execute(
+                Some(player_id),
+                vec![json!("playlist"), json!("tracks"), json!("?")],
+            )
+            .await?;
+        if count.get("_tracks").and_then(|v| v.as_u64()) == Some(0) {
+            return Err(anyhow!("Album not found in the LMS library: {}", album));
+        }
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Play the favorite named `name` on a player, returning its full name
+    ///
+    /// Matches top-level favorites ignoring case; an exact name wins over one
+    /// that merely contains `name`.
+    pub async fn play_favorite(&self, player_id: &str, name: &str) -> Result<String> {
+        let result = self
+            .rpc
+            .execute(
+                Some(player_id),
+                vec![
+                    json!("favorites"),
+                    json!("items"),
+                    json!(0),
+                    json!(MAX_FAVORITES),
+                ],
+            )
+            .await?;
+        let favorites: Vec<(String, String)> = result
+            .get("loop_loop")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let id = item.get("id")?;
+                let id = id
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| id.to_string());
+                Some((id, item.get("name")?.as_str()?.to_string()))
+            })
+            .collect();
+        let wanted = name.to_lowercase();
+        let (id, found) = favorites
+            .iter()
+            .find(|(_, n)| n.to_lowercase() == wanted)
+            .or_else(|| {
+                favorites
+                    .iter()
+                    .find(|(_, n)| n.to_lowercase().contains(&wanted))
+            })
+            .ok_or_else(|| anyhow!("No LMS favorite matches \"{}\"", name))?;
+        self.rpc
+            .execute(
+                Some(player_id),
+                vec![
+                    json!("favorites"),
+                    json!("playlist"),
+                    json!("play"),
+                    json!(format!("item_id:{}", id)),
+                ],
+            )
+            .await?;
+        self.poll_waker.wake();
+        Ok(found.clone())
+    }
+
+    /// Start a library rescan; LMS ignores it while a scan is running
+    pub async fn rescan(&self, mode: LmsRescanMode) -> Result<()> {
+        let params = match mode {
+            LmsRescanMode::Changes => vec![json!("rescan")],
+            LmsRescanMode::Full => vec![json!("wipecache")],
+            LmsRescanMode::Playlists => vec![json!("rescan"), json!("playlists")],
+        };
+        self.rpc.execute(None, params).await?;
+        tracing::info!("LMS library rescan requested ({:?})", mode);
+        Ok(())
+    }
+
+    /// Whether a library scan is running, and how far it got
+    pub async fn scan_status(&self) -> Result<LmsScanStatus> {
+        let result = self
+            .rpc
+            .execute(None, vec![json!("serverstatus"), json!(0), json!(0)])
+            .await?;
+        Ok(parse_scan_status(&result))
+    }
+
+    /// Play an announcement, then put back the player's playlist
+    ///
+    /// Uses LMS's `playlist preview`, which saves the current playlist and
+    /// position, plays `url`, and on `cmd:stop` restores both and resumes if
+    /// the player was playing. The volume is set to `volume` for the clip and
+    /// restored afterwards.
+    pub async fn announce(
+        &self,
+        player_id: &str,
+        url: &str,
+        volume: Option<i32>,
+        max: Duration,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let previous_volume = self.rpc.get_player_status(player_id).await?.volume;
+        if let Some(volume) = volume {
+            self.rpc
+                .execute(
+                    Some(player_id),
+                    vec![json!("mixer"), json!("volume"), json!(volume.clamp(0, 100))],
+                )
+                .await?;
+        }
+
+        let played = self
+            .rpc
+            .execute(
+                Some(player_id),
+                vec![
+                    json!("playlist"),
+                    json!("preview"),
+                    json!(format!("url:{}", url)),
+                    json!("title:Announcement"),
+                ],
+            )
+            .await;
+
+        if played.is_ok() {
+            let deadline = tokio::time::Instant::now() + max;
+            let mut started = false;
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    _ = tokio::time::sleep(ANNOUNCE_POLL_INTERVAL) => {}
+                }
+                let Ok(status) = self.rpc.get_player_status(player_id).await else {
+                    continue;
+                };
+                match status.mode.as_str() {
+                    "play" => started = true,
+                    _ if started => break,
+                    _ => {}
+                }
+            }
+        }
+
+        let restored = self
+            .rpc
+            .execute(
+                Some(player_id),
+                vec![json!("playlist"), json!("preview"), json!("cmd:stop")],
+            )
+            .await;
+        if volume.is_some() {
+            if let Err(e) = self
+                .rpc
+                .execute(
+                    Some(player_id),
+                    vec![json!("mixer"), json!("volume"), json!(previous_volume)],
+                )
+                .await
+            {
+                tracing::warn!("Failed to restore volume on {}: {}", player_id, e);
+            }
+        }
+        self.poll_waker.wake();
+        played?;
+        restored?;
+        Ok(())
+    }
+
+    /// Show two lines on a player's display for `duration`, over whatever
+    /// it shows now
+    ///
+    /// Uses the CLI `display` command. Returns `false` without sending
+    /// anything for players that have no display (see [`has_display`]).
+    pub async fn show_message(
+        &self,
+        player_id: &str,
+        line1: &str,
+        line2: &str,
+        duration: Duration,
+    ) -> Result<bool> {
+        let model = self.get_cached_player(player_id).await.map(|p| p.model);
+        if !model.is_some_and(|m| has_display(&m)) {
+            return Ok(false);
+        }
+        self.rpc
+            .execute(
+                Some(player_id),
+                vec![
+                    json!("display"),
+                    json!(line1),
+                    json!(line2),
+                    json!(duration.as_secs().max(1)),
+                ],
+            )
+            .await?;
+        Ok(true)
+    }
+
     /// Get artwork URL for a track
     pub async fn get_artwork_url(
         &self,
@@ -883,7 +1481,8 @@ impl LmsAdapter {
         let url = if image_key.starts_with("http://") || image_key.starts_with("https://") {
             image_key.to_string()
         } else {
-            // Otherwise treat as coverid
+            // Otherwise treat as coverid (served by LMS itself, so skip it while LMS is down)
+            self.rpc.breaker.check()?;
             self.get_artwork_url(image_key, width, height).await?
         };
 
@@ -932,6 +1531,27 @@ impl LmsAdapter {
     }
 }
 
+/// Whether a player model has a display that takes `display` messages:
+/// SliMP3, Squeezebox 1-3, Transporter, Boom, Radio (`baby`) and Touch (`fab4`)
+fn has_display(model: &str) -> bool {
+    matches!(
+        model,
+        "slimp3" | "squeezebox" | "squeezebox2" | "transporter" | "boom" | "baby" | "fab4"
+    )
+}
+
+/// Bass/treble range of player models whose hardware has tone controls
+///
+/// Boom's DAC takes -23..23 (flat at 0); the original Squeezebox's decoder
+/// takes 0..100 (flat at 50). Other players accept `mixer bass` and ignore it.
+fn tone_range(model: &str) -> Option<(i32, i32)> {
+    match model {
+        "boom" => Some((-23, 23)),
+        "squeezebox" => Some((0, 100)),
+        _ => None,
+    }
+}
+
 /// Convert an LMS player to a unified Zone representation
 fn lms_player_to_zone(player: &LmsPlayer) -> Zone {
     let zone_id = PrefixedZoneId::lms(&player.playerid).to_string();
@@ -967,6 +1587,17 @@ fn lms_player_to_zone(player: &LmsPlayer) -> Zone {
         source: "lms".to_string(),
         is_controllable: player.power && player.connected,
         is_seekable: true,
+        // Players can be synced into groups and always have a playlist
+        capabilities: crate::bus::ZoneCapabilities {
+            can_seek: true,
+            can_shuffle: true,
+            has_volume: true,
+            has_queue: true,
+            supports_grouping: true,
+            has_eq: tone_range(&player.model).is_some(),
+            has_input: false,
+        },
+        volume_leveling: player.replay_gain,
         last_updated: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -976,6 +1607,7 @@ fn lms_player_to_zone(player: &LmsPlayer) -> Zone {
         is_pause_allowed: player.state == "playing",
         is_next_allowed: true,
         is_previous_allowed: true,
+        metadata: None,
     }
 }
 
@@ -985,6 +1617,32 @@ async fn update_players_internal(
     rpc: &LmsRpc,
     state: &Arc<RwLock<LmsState>>,
     bus: &SharedBus,
+) -> Result<()> {
+    sync_players(rpc, state, bus, false).await
+}
+
+/// Full status fetch after a CLI (re)subscribe
+///
+/// Events sent while the CLI socket was down are lost, and the cache may already
+/// match the server (a poll ran in between) while consumers missed the change.
+/// Publishes state, now playing and volume for every known player regardless
+/// of what the cache holds so downstream state is corrected.
+async fn resync_players(
+    rpc: &LmsRpc,
+    state: &Arc<RwLock<LmsState>>,
+    bus: &SharedBus,
+) -> Result<()> {
+    sync_players(rpc, state, bus, true).await
+}
+
+/// Fetch all players and their status, update the cache and publish events.
+/// With `publish_all`, events are published for every existing player, not
+/// only those whose cached state differs.
+async fn sync_players(
+    rpc: &LmsRpc,
+    state: &Arc<RwLock<LmsState>>,
+    bus: &SharedBus,
+    publish_all: bool,
 ) -> Result<()> {
     let players = rpc.get_players().await?;
 
@@ -997,6 +1655,8 @@ async fn update_players_internal(
     let mut state_updates: Vec<(String, String, String)> = Vec::new();
     // VolumeChanged: (player_id, volume)
     let mut volume_updates: Vec<(String, i32)> = Vec::new();
+    // Zones whose ReplayGain mode changed, republished in full
+    let mut leveling_updates: Vec<Zone> = Vec::new();
 
     // Helper to convert empty strings to None (metadata cleared)
     let to_option = |s: &str| {
@@ -1030,6 +1690,29 @@ async fn update_players_internal(
             }
         }
 
+        // The ReplayGain pref rarely changes, so only ask for it when unknown
+        // or on resync rather than on every poll
+        let cached_replay_gain = state
+            .read()
+            .await
+            .players
+            .get(&player.playerid)
+            .and_then(|p| p.replay_gain);
+        player.replay_gain = match cached_replay_gain {
+            Some(mode) if !publish_all => Some(mode),
+            _ => match rpc.get_replay_gain(&player.playerid).await {
+                Ok(mode) => Some(mode),
+                Err(e) => {
+                    debug!("No ReplayGain mode for player {}: {}", player.playerid, e);
+                    cached_replay_gain
+                }
+            },
+        };
+        // New players get theirs in ZoneDiscovered below
+        if previous_ids.contains(&player.playerid) && cached_replay_gain != player.replay_gain {
+            leveling_updates.push(lms_player_to_zone(&player));
+        }
+
         // Check what changed for this player
         let (now_playing_changed, state_changed, volume_changed) = {
             let s = state.read().await;
@@ -1041,7 +1724,11 @@ async fn update_players_internal(
                     || old_player.coverid != player.coverid;
                 let state_changed = old_player.state != player.state;
                 let volume_changed = old_player.volume != player.volume;
-                (np_changed, state_changed, volume_changed)
+                (
+                    publish_all || np_changed,
+                    publish_all || state_changed,
+                    publish_all || volume_changed,
+                )
             } else {
                 // New player - will be handled by ZoneDiscovered
                 (false, false, false)
@@ -1115,6 +1802,12 @@ async fn update_players_internal(
         });
     }
 
+    // ZoneUpdated only carries the name and state, so republish the zone
+    for zone in leveling_updates {
+        debug!("Polling detected ReplayGain change for {}", zone.zone_id);
+        bus.publish(BusEvent::ZoneDiscovered { zone });
+    }
+
     // Emit events for player set changes
     let current_ids: std::collections::HashSet<String> =
         { state.read().await.players.keys().cloned().collect() };
@@ -1153,15 +1846,19 @@ const MAX_CONSECUTIVE_POLL_FAILURES: u32 = 3;
 
 /// Run the polling loop (extracted helper for AdapterLogic)
 /// Returns Err after consecutive failures to trigger adapter restart
+///
+/// Without CLI subscription, polling is adaptive: base interval while any player
+/// is playing, exponential backoff when all are idle, immediate wake on commands.
 async fn run_polling_loop(
     state: Arc<RwLock<LmsState>>,
     bus: SharedBus,
     rpc: LmsRpc,
     shutdown: CancellationToken,
+    waker: PollWaker,
 ) -> Result<()> {
-    // Start with fast polling; will switch to slow when subscription is active
-    let mut current_interval = get_poll_interval();
-    let mut poll_timer = interval(current_interval);
+    let mut backoff = AdaptiveBackoff::default();
+    // First poll happens immediately
+    let mut delay = Duration::ZERO;
     let mut consecutive_failures: u32 = 0;
 
     loop {
@@ -1170,49 +1867,59 @@ async fn run_polling_loop(
                 info!("LMS polling shutting down");
                 break;
             }
-            _ = poll_timer.tick() => {
-                // Check if we need to adjust polling interval
-                let subscription_active = state.read().await.cli_subscription_active;
-                let target_interval = if subscription_active {
-                    get_poll_interval_with_subscription()
-                } else {
-                    get_poll_interval()
-                };
+            _ = waker.woken() => {
+                debug!("LMS poll woken by command");
+                backoff.reset();
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
 
-                if target_interval != current_interval {
-                    debug!(
-                        "Adjusting poll interval: {:?} -> {:?} (subscription_active={})",
-                        current_interval, target_interval, subscription_active
+        match update_players_internal(&rpc, &state, &bus).await {
+            Ok(()) => {
+                // Reset failure counter on success
+                if consecutive_failures > 0 {
+                    debug!("LMS poll succeeded, resetting failure counter");
+                    consecutive_failures = 0;
+                }
+            }
+            Err(e) => {
+                consecutive_failures += 1;
+                if consecutive_failures >= MAX_CONSECUTIVE_POLL_FAILURES {
+                    tracing::error!(
+                        "LMS poll failed {} consecutive times, triggering restart: {}",
+                        consecutive_failures,
+                        e
+                    );
+                    return Err(anyhow!(
+                        "LMS unreachable after {} consecutive poll failures",
+                        consecutive_failures
+                    ));
+                } else {
+                    warn!(
+                        "LMS poll failed ({}/{}): {}",
+                        consecutive_failures, MAX_CONSECUTIVE_POLL_FAILURES, e
                     );
-                    current_interval = target_interval;
-                    poll_timer = interval(current_interval);
                 }
+            }
+        }
 
-                match update_players_internal(&rpc, &state, &bus).await {
-                    Ok(()) => {
-                        // Reset failure counter on success
-                        if consecutive_failures > 0 {
-                            debug!("LMS poll succeeded, resetting failure counter");
-                            consecutive_failures = 0;
-                        }
-                    }
-                    Err(e) => {
-                        consecutive_failures += 1;
-                        if consecutive_failures >= MAX_CONSECUTIVE_POLL_FAILURES {
-                            tracing::error!(
-                                "LMS poll failed {} consecutive times, triggering restart: {}",
-                                consecutive_failures, e
-                            );
-                            return Err(anyhow!("LMS unreachable after {} consecutive poll failures", consecutive_failures));
-                        } else {
-                            warn!(
-                                "LMS poll failed ({}/{}): {}",
-                                consecutive_failures, MAX_CONSECUTIVE_POLL_FAILURES, e
-                            );
-                        }
-                    }
-                }
+        // Pick next delay: CLI events cover changes when subscribed, otherwise adapt to activity
+        let target_delay = {
+            let s = state.read().await;
+            if s.cli_subscription_active {
+                backoff.reset();
+                poll_interval_with_subscription(s.poll_interval)
+            } else {
+                let any_playing = s.players.values().any(|p| p.state == "playing");
+                backoff.next_delay(s.poll_interval, any_playing)
             }
+        };
+        if target_delay != delay {
+            debug!(
+                "Adjusting LMS poll interval: {:?} -> {:?}",
+                delay, target_delay
+            );
+            delay = target_delay;
         }
     }
 
@@ -1241,7 +1948,9 @@ async fn connect_and_subscribe(
     shutdown: &CancellationToken,
 ) -> Result<()> {
     let addr = format!("{}:{}", host, CLI_PORT);
-    let stream = TcpStream::connect(&addr).await?;
+    let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&addr))
+        .await
+        .map_err(|_| anyhow!("CLI connection timeout"))??;
 
     info!("[CLI] Connected to LMS CLI at {}", addr);
 
@@ -1262,6 +1971,12 @@ async fn connect_and_subscribe(
         s.cli_subscription_active = true;
     }
 
+    // Events between the previous disconnect and this subscribe were dropped;
+    // a failed resync leaves the subscription up and polling fills the gap
+    if let Err(e) = resync_players(rpc, state, bus).await {
+        warn!("[CLI] Resync after subscribe failed: {}", e);
+    }
+
     // Process events
     let mut line = String::new();
 
@@ -1599,12 +2314,13 @@ impl AdapterLogic for LmsAdapter {
         // CLI subscription is now handled by separate LmsCliAdapter (Issue #165)
         // Polling reads cli_subscription_active flag to adjust interval:
         // - CLI active (flag=true): slow interval (30s)
-        // - CLI inactive (flag=false): fast interval (2s)
+        // - CLI inactive (flag=false): adaptive (2s while playing, backing off when idle)
         let result = run_polling_loop(
             self.state.clone(),
             ctx.bus.clone(),
             self.rpc.clone(),
             ctx.shutdown.clone(),
+            self.poll_waker.clone(),
         )
         .await;
 
@@ -1706,6 +2422,12 @@ impl LmsCliAdapter {
     pub async fn is_configured(&self) -> bool {
         self.state.read().await.host.is_some()
     }
+
+    /// Fetch full player status and publish corrective events for every player.
+    /// Runs automatically after each CLI subscribe.
+    pub async fn resync(&self) -> Result<()> {
+        resync_players(&self.rpc, &self.state, &self.bus).await
+    }
 }
 
 #[async_trait]
@@ -2086,4 +2808,54 @@ mod tests {
             _ => panic!("Expected Mixer event, got {:?}", event),
         }
     }
+
+    #[test]
+    fn test_has_eq_only_for_models_with_tone_controls() {
+        let boom = LmsPlayer {
+            model: "boom".to_string(),
+            ..Default::default()
+        };
+        let squeezelite = LmsPlayer {
+            model: "squeezelite".to_string(),
+            ..Default::default()
+        };
+        assert!(lms_player_to_zone(&boom).capabilities.has_eq);
+        assert!(!lms_player_to_zone(&squeezelite).capabilities.has_eq);
+        assert_eq!(tone_range("squeezebox"), Some((0, 100)));
+    }
+
+    #[test]
+    fn test_parse_scan_status() {
+        let scanning = parse_scan_status(&json!({
+            "rescan": 1,
+            "progressname": "discovering_files",
+            "progressdone": "1200",
+            "progresstotal": 5000,
+            "lastscan": "1760600000",
+        }));
+        assert!(scanning.scanning);
+        assert_eq!(scanning.step.as_deref(), Some("discovering_files"));
+        assert_eq!((scanning.done, scanning.total), (Some(1200), Some(5000)));
+        assert_eq!(scanning.last_scan, Some(1_760_600_000));
+
+        let idle = parse_scan_status(&json!({ "lastscan": 1760600000, "progressname": "x" }));
+        assert!(!idle.scanning);
+        assert_eq!(idle.step, None);
+    }
+
+    #[test]
+    fn test_has_display() {
+        assert!(has_display("fab4"));
+        assert!(has_display("baby"));
+        assert!(!has_display("squeezelite"));
+        assert!(!has_display("receiver"));
+    }
+
+    #[test]
+    fn test_resume_playlist_name() {
+        assert_eq!(
+            resume_playlist_name("00:04:20:12:34:56"),
+            "uhc_resume_000420123456"
+        );
+    }
 }
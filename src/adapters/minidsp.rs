@@ -0,0 +1,414 @@
+//! MiniDSP control through minidsp-rs's HTTP API
+//!
+//! Like CamillaDSP, MiniDSP units aren't zone sources: a zone is linked to
+//! the unit that processes its audio, and the linked zone's DSP preset,
+//! master volume and mute are then controlled through
+//! `/minidsp/zones/{zone_id}`. Units are reached through `minidspd`, the
+//! minidsp-rs daemon (its `[http_server]` section, port 5380 by default),
+//! which can front several devices, so an instance names the daemon and the
+//! device index. Instances and zone links are kept in `minidsp-config.json`.
+
+use anyhow::{anyhow, bail, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::adapters::ops;
+use crate::config::{get_config_file_path, read_config_file};
+
+const MINIDSP_CONFIG_FILE: &str = "minidsp-config.json";
+const DEFAULT_PORT: u16 = 5380;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// MiniDSP units have four preset slots
+pub const PRESET_SLOTS: u8 = 4;
+/// The lowest master volume
+pub const MIN_VOLUME_DB: f64 = -127.5;
+pub const MAX_VOLUME_DB: f64 = 0.0;
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(MINIDSP_CONFIG_FILE)
+}
+
+/// A device behind a minidsp-rs daemon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiniDspInstance {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Index of the device in the daemon's `/devices` list
+    #[serde(default)]
+    pub device: usize,
+    /// Labels for the preset slots, in order; unlabelled slots are "Preset N"
+    #[serde(default)]
+    pub presets: Vec<String>,
+}
+
+impl MiniDspInstance {
+    fn device_url(&self) -> String {
+        format!("http://{}:{}/devices/{}", self.host, self.port, self.device)
+    }
+
+    /// Label of a preset slot (0-based)
+    pub fn preset_name(&self, index: u8) -> String {
+        self.presets
+            .get(index as usize)
+            .filter(|name| !name.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("Preset {}", index + 1))
+    }
+
+    /// Every preset slot with its label
+    pub fn preset_list(&self) -> Vec<MiniDspPreset> {
+        (0..PRESET_SLOTS)
+            .map(|index| MiniDspPreset {
+                index,
+                name: self.preset_name(index),
+            })
+            .collect()
+    }
+}
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SavedMiniDspConfig {
+    #[serde(default)]
+    instances: Vec<MiniDspInstance>,
+    /// zone_id -> instance name
+    #[serde(default)]
+    links: HashMap<String, String>,
+}
+
+/// Zone link info for API responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MiniDspZoneLink {
+    pub zone_id: String,
+    pub instance: String,
+}
+
+/// A preset slot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MiniDspPreset {
+    /// 0-based slot
+    pub index: u8,
+    pub name: String,
+}
+
+/// State of a linked unit
+#[derive(Debug, Clone, Serialize)]
+pub struct MiniDspStatus {
+    pub instance: String,
+    /// Active preset slot
+    pub preset: u8,
+    pub presets: Vec<MiniDspPreset>,
+    /// Master volume in dB
+    pub volume: f64,
+    pub mute: bool,
+    /// Input source, on units that have a selector
+    pub source: Option<String>,
+}
+
+/// Master settings as minidsp-rs reports and accepts them; unset fields are
+/// left unchanged
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MasterStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mute: Option<bool>,
+}
+
+/// Status from `GET /devices/{index}` as an instance's status
+fn parse_status(instance: &MiniDspInstance, body: &Value) -> Result<MiniDspStatus> {
+    let master: MasterStatus = serde_json::from_value(
+        body.get("master")
+            .cloned()
+            .ok_or_else(|| anyhow!("Unexpected minidsp-rs status: no master"))?,
+    )?;
+    Ok(MiniDspStatus {
+        instance: instance.name.clone(),
+        preset: master.preset.unwrap_or(0),
+        presets: instance.preset_list(),
+        volume: master.volume.unwrap_or(MIN_VOLUME_DB),
+        mute: master.mute.unwrap_or(false),
+        source: master.source,
+    })
+}
+
+/// Service for MiniDSP units and the zones linked to them
+pub struct MiniDspService {
+    config: Arc<RwLock<SavedMiniDspConfig>>,
+    http: Client,
+}
+
+impl Default for MiniDspService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MiniDspService {
+    pub fn new() -> Self {
+        let service = Self {
+            config: Arc::new(RwLock::new(SavedMiniDspConfig::default())),
+            http: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        };
+        service.load_config_sync();
+        service
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(MINIDSP_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedMiniDspConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut config) = self.config.try_write() {
+                    tracing::info!(
+                        "Loaded {} MiniDSP instance(s) and {} zone link(s) from disk",
+                        saved.instances.len(),
+                        saved.links.len()
+                    );
+                    *config = saved;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse MiniDSP config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let config = self.config.read().await.clone();
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&config) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save MiniDSP config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize MiniDSP config: {}", e),
+        }
+    }
+
+    /// All configured instances
+    pub async fn list_instances(&self) -> Vec<MiniDspInstance> {
+        self.config.read().await.instances.clone()
+    }
+
+    /// Add an instance, or replace the one with the same name
+    pub async fn set_instance(&self, instance: MiniDspInstance) -> Result<()> {
+        if instance.name.trim().is_empty() || instance.host.trim().is_empty() {
+            bail!("name and host are required");
+        }
+        if instance.presets.len() > PRESET_SLOTS as usize {
+            bail!("MiniDSP units have at most {} presets", PRESET_SLOTS);
+        }
+        {
+            let mut config = self.config.write().await;
+            match config
+                .instances
+                .iter_mut()
+                .find(|i| i.name == instance.name)
+            {
+                Some(existing) => *existing = instance,
+                None => config.instances.push(instance),
+            }
+        }
+        self.save_config().await;
+        Ok(())
+    }
+
+    /// Remove an instance and the links to it; false if there was none
+    pub async fn remove_instance(&self, name: &str) -> bool {
+        let removed = {
+            let mut config = self.config.write().await;
+            let before = config.instances.len();
+            config.instances.retain(|i| i.name != name);
+            let removed = config.instances.len() != before;
+            if removed {
+                config.links.retain(|_, instance| instance != name);
+            }
+            removed
+        };
+        if removed {
+            self.save_config().await;
+            tracing::info!("Removed MiniDSP instance {}", name);
+        }
+        removed
+    }
+
+    /// Link a zone to an instance
+    pub async fn link_zone(&self, zone_id: String, instance_name: String) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            if !config.instances.iter().any(|i| i.name == instance_name) {
+                bail!("Unknown MiniDSP instance: {}", instance_name);
+            }
+            config.links.insert(zone_id.clone(), instance_name.clone());
+        }
+        self.save_config().await;
+        tracing::info!(
+            "Zone {} linked to MiniDSP instance {}",
+            zone_id,
+            instance_name
+        );
+        Ok(())
+    }
+
+    /// Unlink a zone; false if it wasn't linked
+    pub async fn unlink_zone(&self, zone_id: &str) -> bool {
+        let was_linked = self.config.write().await.links.remove(zone_id).is_some();
+        if was_linked {
+            self.save_config().await;
+            tracing::info!("Zone {} unlinked from MiniDSP", zone_id);
+        }
+        was_linked
+    }
+
+    /// All zone links
+    pub async fn get_links(&self) -> Vec<MiniDspZoneLink> {
+        let config = self.config.read().await;
+        let mut links: Vec<MiniDspZoneLink> = config
+            .links
+            .iter()
+            .map(|(zone_id, instance)| MiniDspZoneLink {
+                zone_id: zone_id.clone(),
+                instance: instance.clone(),
+            })
+            .collect();
+        links.sort_by(|a, b| a.zone_id.cmp(&b.zone_id));
+        links
+    }
+
+    /// The instance a zone is linked to
+    pub async fn instance_for_zone(&self, zone_id: &str) -> Option<MiniDspInstance> {
+        let config = self.config.read().await;
+        let name = config.links.get(zone_id)?;
+        config.instances.iter().find(|i| &i.name == name).cloned()
+    }
+
+    /// Read a unit's preset, volume, mute and source
+    pub async fn status(&self, instance: &MiniDspInstance) -> Result<MiniDspStatus> {
+        let body: Value = ops::timed("minidsp", "status", Some(instance.name.as_str()), async {
+            let response = self
+                .http
+                .get(instance.device_url())
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok::<_, anyhow::Error>(response.json().await?)
+        })
+        .await?;
+        parse_status(instance, &body)
+    }
+
+    /// Change the master settings in one request
+    ///
+    /// The volume is clamped to `MIN_VOLUME_DB..=MAX_VOLUME_DB`; a preset
+    /// outside the unit's slots is refused.
+    pub async fn apply(&self, instance: &MiniDspInstance, mut master: MasterStatus) -> Result<()> {
+        if let Some(preset) = master.preset {
+            if preset >= PRESET_SLOTS {
+                bail!("Unknown preset: {}", preset);
+            }
+        }
+        master.volume = master
+            .volume
+            .map(|db| db.clamp(MIN_VOLUME_DB, MAX_VOLUME_DB));
+        let operation = if master.preset.is_some() {
+            "preset"
+        } else if master.volume.is_some() {
+            "volume"
+        } else {
+            "mute"
+        };
+        ops::timed("minidsp", operation, Some(instance.name.as_str()), async {
+            self.http
+                .post(format!("{}/config", instance.device_url()))
+                .json(&serde_json::json!({ "master_status": &master }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .await?;
+        if let Some(preset) = master.preset {
+            tracing::info!(
+                "MiniDSP instance {} switched to {}",
+                instance.name,
+                instance.preset_name(preset)
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn instance() -> MiniDspInstance {
+        serde_json::from_value(json!({
+            "name": "den",
+            "host": "pi.local",
+            "presets": ["Flat", "", "Movies"],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_instance_defaults_and_preset_names() {
+        let instance = instance();
+        assert_eq!(instance.port, DEFAULT_PORT);
+        assert_eq!(instance.device, 0);
+        let names: Vec<String> = instance.preset_list().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["Flat", "Preset 2", "Movies", "Preset 4"]);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let body = json!({
+            "master": { "preset": 2, "source": "Toslink", "volume": -23.5, "mute": false, "dirac": false },
+            "input_levels": [-60.1, -61.0],
+            "output_levels": [-70.0, -70.0, -120.0, -120.0],
+        });
+        let status = parse_status(&instance(), &body).unwrap();
+        assert_eq!(status.preset, 2);
+        assert_eq!(status.volume, -23.5);
+        assert_eq!(status.source.as_deref(), Some("Toslink"));
+        assert!(parse_status(&instance(), &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_master_update_only_sends_set_fields() {
+        let master = MasterStatus {
+            preset: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&master).unwrap(),
+            json!({ "preset": 1 })
+        );
+    }
+}
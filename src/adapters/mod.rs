@@ -1,11 +1,25 @@
-//! Audio source adapters (Roon, HQPlayer, LMS, OpenHome, UPnP)
+//! Audio source adapters (Roon, HQPlayer, LMS, OpenHome, UPnP, AirPlay, Snapcast, HEOS, Kodi, Spotify, HTTP JSON, Plexamp, CamillaDSP, MiniDSP)
 
+pub mod airplay;
+pub mod camilladsp;
+pub mod discovery;
 pub mod handle;
+pub mod heos;
 pub mod hqplayer;
+pub mod http_json;
+pub mod kodi;
 pub mod lms;
 pub mod lms_discovery;
+pub mod minidsp;
+pub mod naa;
 pub mod openhome;
+pub mod ops;
+pub mod plexamp;
+pub mod polling;
 pub mod roon;
+pub mod roon_power;
+pub mod snapcast;
+pub mod spotify;
 pub mod traits;
 pub mod upnp;
 
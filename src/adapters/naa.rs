@@ -0,0 +1,327 @@
+//! HQPlayer NAA (networkaudiod) discovery and monitoring
+//!
+//! NAA endpoints answer the same kind of XML multicast discovery as
+//! HQPlayer itself, on their own port. HQPlayer's control API doesn't say
+//! which NAA an instance outputs to, so each instance is assigned its NAA
+//! (by name or address) and the assignments are kept in `hqp-naa.json`.
+//! A background scan publishes `HqpNaaLost` when an assigned NAA stops
+//! answering and `HqpNaaFound` when it answers again.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::hqplayer::extract_xml_attr;
+use crate::bus::{BusEvent, SharedBus};
+use crate::clock::unix_secs;
+use crate::config::{get_config_file_path, read_config_file};
+
+const NAA_CONFIG_FILE: &str = "hqp-naa.json";
+const NAA_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 192, 0, 199);
+const NAA_DISCOVERY_PORT: u16 = 43210;
+const NAA_DISCOVERY_TIMEOUT_MS: u64 = 2000;
+/// How often assigned NAAs are looked for
+const SCAN_INTERVAL: Duration = Duration::from_secs(30);
+/// Scans an NAA may miss before it counts as gone (a reply can get lost)
+const MISSES_BEFORE_LOST: u32 = 2;
+
+fn config_path() -> PathBuf {
+    get_config_file_path(NAA_CONFIG_FILE)
+}
+
+/// An NAA endpoint that answered discovery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveredNaa {
+    pub host: String,
+    pub name: String,
+    pub version: String,
+    /// NAA protocol version, when reported
+    pub protocol: Option<String>,
+    /// When it last answered (seconds since epoch)
+    pub last_seen: u64,
+}
+
+impl DiscoveredNaa {
+    /// Whether an assignment (NAA name or address) refers to this endpoint
+    fn matches(&self, naa: &str) -> bool {
+        self.host == naa || self.name.eq_ignore_ascii_case(naa)
+    }
+}
+
+/// Discover NAA endpoints on the network via UDP multicast
+pub async fn discover_naas(timeout_ms: Option<u64>) -> Result<Vec<DiscoveredNaa>> {
+    let timeout_duration = Duration::from_millis(timeout_ms.unwrap_or(NAA_DISCOVERY_TIMEOUT_MS));
+    let mut discovered: HashMap<String, DiscoveredNaa> = HashMap::new();
+
+    let sockets = crate::adapters::discovery::bind_sockets().await?;
+    for socket in &sockets {
+        socket.set_broadcast(true)?;
+    }
+
+    let message = b"<?xml version=\"1.0\" encoding=\"utf-8\"?><networkaudio><discover>network audio</discover></networkaudio>";
+    let dest = SocketAddrV4::new(NAA_MULTICAST_ADDR, NAA_DISCOVERY_PORT);
+    let replies =
+        crate::adapters::discovery::multicast_exchange(&sockets, dest, message, timeout_duration)
+            .await;
+
+    let now = unix_secs();
+    for (response, from) in replies {
+        tracing::debug!("NAA discovery response from {}: {}", from, response);
+        if let Some(naa) = parse_discovery_response(&response, from.to_string(), now) {
+            discovered.insert(naa.host.clone(), naa);
+        }
+    }
+
+    let mut result: Vec<DiscoveredNaa> = discovered.into_values().collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    tracing::debug!("NAA discovery found {} endpoint(s)", result.len());
+    Ok(result)
+}
+
+/// Parse an NAA discovery reply; HQPlayer's own replies are ignored
+fn parse_discovery_response(xml: &str, host: String, now: u64) -> Option<DiscoveredNaa> {
+    if !xml.contains("<networkaudio")
+        || (!xml.contains("result=\"OK\"") && !xml.contains("result='OK'"))
+    {
+        return None;
+    }
+    Some(DiscoveredNaa {
+        name: extract_xml_attr(xml, "name").unwrap_or_else(|| host.clone()),
+        version: extract_xml_attr(xml, "version").unwrap_or_else(|| "unknown".to_string()),
+        protocol: extract_xml_attr(xml, "protocol"),
+        host,
+        last_seen: now,
+    })
+}
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SavedNaaConfig {
+    /// HQPlayer instance name -> NAA name or address
+    #[serde(default)]
+    assignments: HashMap<String, String>,
+}
+
+/// The NAA an HQPlayer instance outputs to
+#[derive(Debug, Clone, Serialize)]
+pub struct HqpNaaStatus {
+    pub instance: String,
+    /// Assigned NAA name or address
+    pub naa: String,
+    /// Whether the NAA answered recent discovery
+    pub online: bool,
+    /// The endpoint as last discovered
+    pub endpoint: Option<DiscoveredNaa>,
+}
+
+#[derive(Default)]
+struct NaaState {
+    /// Endpoints by host, as last seen
+    endpoints: HashMap<String, DiscoveredNaa>,
+    /// Consecutive scans each assignment's NAA was missing from
+    misses: HashMap<String, u32>,
+    /// When the last scan finished (seconds since epoch)
+    last_scan: Option<u64>,
+}
+
+/// Service tracking NAA endpoints and the instances assigned to them
+pub struct NaaService {
+    config: Arc<RwLock<SavedNaaConfig>>,
+    state: Arc<RwLock<NaaState>>,
+    bus: SharedBus,
+}
+
+impl NaaService {
+    pub fn new(bus: SharedBus) -> Self {
+        let service = Self {
+            config: Arc::new(RwLock::new(SavedNaaConfig::default())),
+            state: Arc::new(RwLock::new(NaaState::default())),
+            bus,
+        };
+        service.load_config_sync();
+        service
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(NAA_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedNaaConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut config) = self.config.try_write() {
+                    tracing::info!(
+                        "Loaded {} HQPlayer NAA assignment(s) from disk",
+                        saved.assignments.len()
+                    );
+                    *config = saved;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse HQPlayer NAA config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let config = self.config.read().await.clone();
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&config) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save HQPlayer NAA config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize HQPlayer NAA config: {}", e),
+        }
+    }
+
+    /// Assign the NAA an instance outputs to, or clear it with `None`
+    pub async fn assign(&self, instance: &str, naa: Option<String>) {
+        {
+            let mut config = self.config.write().await;
+            match naa.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+                Some(naa) => {
+                    config.assignments.insert(instance.to_string(), naa);
+                }
+                None => {
+                    config.assignments.remove(instance);
+                }
+            }
+        }
+        self.state.write().await.misses.remove(instance);
+        self.save_config().await;
+    }
+
+    /// Endpoints seen in the last scan, and when it ran
+    pub async fn endpoints(&self) -> (Vec<DiscoveredNaa>, Option<u64>) {
+        let state = self.state.read().await;
+        let mut endpoints: Vec<DiscoveredNaa> = state.endpoints.values().cloned().collect();
+        endpoints.sort_by(|a, b| a.name.cmp(&b.name));
+        (endpoints, state.last_scan)
+    }
+
+    /// Status of every assignment, by instance name
+    pub async fn statuses(&self) -> Vec<HqpNaaStatus> {
+        let assignments = self.config.read().await.assignments.clone();
+        let state = self.state.read().await;
+        let mut statuses: Vec<HqpNaaStatus> = assignments
+            .into_iter()
+            .map(|(instance, naa)| {
+                let endpoint = state.endpoints.values().find(|e| e.matches(&naa)).cloned();
+                let online = endpoint.is_some()
+                    && state.misses.get(&instance).copied().unwrap_or(0) < MISSES_BEFORE_LOST;
+                HqpNaaStatus {
+                    instance,
+                    naa,
+                    online,
+                    endpoint,
+                }
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.instance.cmp(&b.instance));
+        statuses
+    }
+
+    /// Status of one instance's NAA, if it has one assigned
+    pub async fn status(&self, instance: &str) -> Option<HqpNaaStatus> {
+        self.statuses()
+            .await
+            .into_iter()
+            .find(|s| s.instance == instance)
+    }
+
+    /// Run discovery now and record the results
+    pub async fn scan(&self) -> Result<Vec<DiscoveredNaa>> {
+        let found = discover_naas(None).await?;
+        let assignments = self.config.read().await.assignments.clone();
+        let mut events = Vec::new();
+        {
+            let mut state = self.state.write().await;
+            for naa in &found {
+                state.endpoints.insert(naa.host.clone(), naa.clone());
+            }
+            for (instance, naa) in &assignments {
+                let endpoint = found.iter().find(|e| e.matches(naa));
+                let misses = state.misses.entry(instance.clone()).or_insert(0);
+                match endpoint {
+                    Some(endpoint) => {
+                        if *misses >= MISSES_BEFORE_LOST {
+                            events.push(BusEvent::HqpNaaFound {
+                                instance: instance.clone(),
+                                naa: endpoint.name.clone(),
+                                host: endpoint.host.clone(),
+                            });
+                        }
+                        *misses = 0;
+                    }
+                    None => {
+                        *misses += 1;
+                        if *misses == MISSES_BEFORE_LOST {
+                            tracing::warn!("NAA {} for HQPlayer {} disappeared", naa, instance);
+                            events.push(BusEvent::HqpNaaLost {
+                                instance: instance.clone(),
+                                naa: naa.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            state.last_scan = Some(unix_secs());
+        }
+        for event in events {
+            self.bus.publish(event);
+        }
+        Ok(found)
+    }
+
+    /// Scan periodically while any instance has an NAA assigned
+    pub fn start_monitor(self: Arc<Self>, shutdown: CancellationToken) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(SCAN_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        tracing::debug!("NAA monitor shutting down");
+                        break;
+                    }
+                    _ = ticker.tick() => {
+                        if self.config.read().await.assignments.is_empty() {
+                            continue;
+                        }
+                        if let Err(e) = self.scan().await {
+                            tracing::debug!("NAA discovery failed: {}", e);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_naa_reply_and_skips_hqplayer_reply() {
+        let reply = r#"<?xml version="1.0" encoding="utf-8"?><networkaudio><discover result="OK" name="living-room-naa" version="5.1.0" protocol="5" trigger="0">network audio</discover></networkaudio>"#;
+        let naa = parse_discovery_response(reply, "192.168.1.40".to_string(), 100).unwrap();
+        assert_eq!(naa.name, "living-room-naa");
+        assert_eq!(naa.version, "5.1.0");
+        assert_eq!(naa.protocol.as_deref(), Some("5"));
+        assert!(naa.matches("LIVING-ROOM-NAA"));
+        assert!(naa.matches("192.168.1.40"));
+
+        let hqplayer = r#"<?xml version="1.0"?><discover result="OK" name="hqp" version="5.0"/>"#;
+        assert!(parse_discovery_response(hqplayer, "192.168.1.2".to_string(), 100).is_none());
+    }
+}
@@ -6,11 +6,9 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::StreamExt;
 use quick_xml::de::from_str as xml_from_str;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use ssdp_client::{SearchTarget, URN};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -18,13 +16,17 @@ use tokio::sync::RwLock;
 use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
 
+use crate::adapters::discovery;
 use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::polling::{AdaptiveBackoff, PollWaker};
 use crate::adapters::traits::{
     AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
 };
 use crate::bus::{
     BusEvent, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl as BusVolumeControl, Zone,
 };
+use crate::knobs::image::sniff_content_type;
 
 /// OpenHome URNs to search for - devices may advertise different services
 const OPENHOME_URNS: &[&str] = &[
@@ -35,9 +37,11 @@ const OPENHOME_URNS: &[&str] = &[
     "urn:av-openhome-org:service:Volume:2",
 ];
 const SSDP_SEARCH_INTERVAL: Duration = Duration::from_secs(30);
-const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 const STALE_THRESHOLD: Duration = Duration::from_secs(90);
 const SOAP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Product service: device name, standby and source (input) selection
+const PRODUCT_SERVICE: &str = "urn:av-openhome-org:service:Product:1";
 
 /// OpenHome device information
 #[derive(Debug, Clone, Serialize)]
@@ -69,6 +73,62 @@ pub struct TrackInfo {
     pub album: String,
     pub album_art_uri: Option<String>,
     pub genre: Option<String>,
+    /// `album_art_uri` tagged with the track (see [`artwork_key`])
+    pub image_key: Option<String>,
+}
+
+/// One input from the Product service's source list
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenHomeSource {
+    /// Position in the device's full source list (what `SetSourceIndex` takes)
+    pub index: u32,
+    pub name: String,
+    /// e.g. `Playlist`, `Radio`, `Receiver`, `Analog`, `Digital`, `Hdmi`
+    #[serde(rename = "type")]
+    pub source_type: String,
+}
+
+/// A device's visible sources and the selected one
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenHomeSources {
+    pub sources: Vec<OpenHomeSource>,
+    /// Index of the selected source
+    pub current: u32,
+}
+
+/// `SourceXml` value: `<SourceList><Source>...</Source></SourceList>`
+#[derive(Debug, Default, Deserialize)]
+struct SourceList {
+    #[serde(rename = "Source", default)]
+    sources: Vec<SourceEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SourceEntry {
+    #[serde(rename = "Name", default)]
+    name: String,
+    #[serde(rename = "Type", default)]
+    source_type: String,
+    #[serde(rename = "Visible", default)]
+    visible: String,
+}
+
+/// Visible sources from a decoded `SourceXml` value
+///
+/// Hidden sources are left out but keep their place in the indexing.
+fn parse_source_xml(xml: &str) -> Result<Vec<OpenHomeSource>> {
+    let list: SourceList = xml_from_str(xml)?;
+    Ok(list
+        .sources
+        .into_iter()
+        .enumerate()
+        .filter(|(_, source)| !matches!(source.visible.trim(), "false" | "0"))
+        .map(|(index, source)| OpenHomeSource {
+            index: index as u32,
+            name: source.name,
+            source_type: source.source_type,
+        })
+        .collect())
 }
 
 /// OpenHome adapter status
@@ -127,6 +187,8 @@ pub struct VolumeControl {
 struct OpenHomeState {
     devices: HashMap<String, OpenHomeDevice>,
     running: bool,
+    /// Poll interval (configurable via app settings)
+    poll_interval: Duration,
 }
 
 /// OpenHome adapter for discovering and controlling OpenHome devices
@@ -137,6 +199,8 @@ pub struct OpenHomeAdapter {
     http: Client,
     /// Wrapped in RwLock to allow creating fresh token on restart
     shutdown: Arc<RwLock<CancellationToken>>,
+    /// Wakes the adaptive poll loop after control commands
+    poll_waker: PollWaker,
 }
 
 impl OpenHomeAdapter {
@@ -146,6 +210,7 @@ impl OpenHomeAdapter {
             state: Arc::new(RwLock::new(OpenHomeState {
                 devices: HashMap::new(),
                 running: false,
+                poll_interval: DEFAULT_POLL_INTERVAL,
             })),
             bus,
             http: Client::builder()
@@ -153,9 +218,15 @@ impl OpenHomeAdapter {
                 .build()
                 .unwrap_or_default(),
             shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+            poll_waker: PollWaker::default(),
         }
     }
 
+    /// Set the poll interval (takes effect on the next poll tick)
+    pub async fn set_poll_interval(&self, poll_interval: Duration) {
+        self.state.write().await.poll_interval = poll_interval;
+    }
+
     /// Start SSDP discovery (internal - use Startable trait)
     async fn start_internal(&self) -> anyhow::Result<()> {
         // Use write lock to atomically check and set running flag
@@ -222,30 +293,11 @@ impl OpenHomeAdapter {
     ) -> anyhow::Result<()> {
         // Search for all known OpenHome URNs - devices may advertise different services
         for urn_str in OPENHOME_URNS {
-            let urn: URN = match urn_str.parse() {
-                Ok(u) => u,
-                Err(e) => {
-                    tracing::warn!("Failed to parse URN {}: {}", urn_str, e);
-                    continue;
-                }
-            };
-            let search_target = SearchTarget::URN(urn);
-
-            match ssdp_client::search(&search_target, Duration::from_secs(2), 2, None).await {
+            match discovery::ssdp_search(urn_str, Duration::from_secs(2), 2).await {
                 Ok(responses) => {
-                    futures::pin_mut!(responses);
-
-                    while let Some(response) = responses.next().await {
-                        let response = match response {
-                            Ok(r) => r,
-                            Err(e) => {
-                                tracing::debug!("SSDP response error: {}", e);
-                                continue;
-                            }
-                        };
-
-                        let location = response.location().to_string();
-                        let usn = response.usn();
+                    for response in responses {
+                        let location = response.location;
+                        let usn = response.usn;
 
                         // Log what we're finding
                         tracing::debug!("OpenHome SSDP response: usn={} loc={}", usn, location);
@@ -402,8 +454,11 @@ impl OpenHomeAdapter {
         bus: SharedBus,
         http: Client,
         shutdown: CancellationToken,
+        waker: PollWaker,
     ) {
-        let mut poll_interval = interval(POLL_INTERVAL);
+        let mut backoff = AdaptiveBackoff::default();
+        // First poll happens immediately
+        let mut delay = Duration::ZERO;
 
         loop {
             tokio::select! {
@@ -411,23 +466,33 @@ impl OpenHomeAdapter {
                     tracing::info!("OpenHome poll loop shutting down");
                     break;
                 }
-                _ = poll_interval.tick() => {
-                    // Get list of devices to poll
-                    let devices: Vec<(String, String)> = {
-                        let s = state.read().await;
-                        s.devices
-                            .iter()
-                            .map(|(uuid, d)| (uuid.clone(), d.location.clone()))
-                            .collect()
-                    };
-
-                    for (uuid, location) in devices {
-                        if let Err(e) = Self::poll_device(&state, &bus, &http, &uuid, &location).await {
-                            tracing::debug!("Failed to poll {}: {}", uuid, e);
-                        }
-                    }
+                _ = waker.woken() => {
+                    backoff.reset();
+                }
+                _ = tokio::time::sleep(delay) => {}
+            }
+
+            // Get list of devices to poll
+            let devices: Vec<(String, String)> = {
+                let s = state.read().await;
+                s.devices
+                    .iter()
+                    .map(|(uuid, d)| (uuid.clone(), d.location.clone()))
+                    .collect()
+            };
+
+            for (uuid, location) in devices {
+                if let Err(e) = Self::poll_device(&state, &bus, &http, &uuid, &location).await {
+                    tracing::debug!("Failed to poll {}: {}", uuid, e);
                 }
             }
+
+            // Poll at the base interval while anything plays, back off while idle
+            delay = {
+                let s = state.read().await;
+                let any_playing = s.devices.values().any(|d| d.state == "playing");
+                backoff.next_delay(s.poll_interval, any_playing)
+            };
         }
 
         tracing::info!("OpenHome poll loop stopped");
@@ -556,11 +621,15 @@ impl OpenHomeAdapter {
                     if let Some(meta) = metadata {
                         // Decode HTML entities and parse DIDL-Lite
                         let decoded = html_decode(&meta);
-                        if let Some(track_info) = Self::parse_didl_lite(&decoded) {
+                        if let Some(mut track_info) = Self::parse_didl_lite(&decoded) {
+                            track_info.image_key = track_info
+                                .album_art_uri
+                                .as_deref()
+                                .map(|art| artwork_key(art, device.last_track_uri.as_deref()));
                             let title = Some(track_info.title.clone());
                             let artist = Some(track_info.artist.clone());
                             let album = Some(track_info.album.clone());
-                            let image_key = track_info.album_art_uri.clone();
+                            let image_key = track_info.image_key.clone();
                             device.track_info = Some(track_info);
                             bus.publish(BusEvent::NowPlayingChanged {
                                 zone_id: PrefixedZoneId::openhome(uuid),
@@ -608,15 +677,20 @@ impl OpenHomeAdapter {
             body = body_content
         );
 
-        let response = http
-            .post(url)
-            .header("Content-Type", "text/xml; charset=utf-8")
-            .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
-            .body(soap_body)
-            .send()
-            .await?;
-
-        Ok(response.text().await?)
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string));
+        let request = async {
+            let response = http
+                .post(url)
+                .header("Content-Type", "text/xml; charset=utf-8")
+                .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
+                .body(soap_body)
+                .send()
+                .await?;
+            response.text().await
+        };
+        Ok(ops::timed("openhome", action, host.as_deref(), request).await?)
     }
 
     fn extract_xml_value(xml: &str, tag: &str) -> Option<String> {
@@ -651,6 +725,7 @@ impl OpenHomeAdapter {
             album,
             album_art_uri,
             genre,
+            image_key: None,
         })
     }
 
@@ -739,7 +814,7 @@ impl OpenHomeAdapter {
             volume_max: device.volume_max.map(|m| m as i32).unwrap_or(100),
             seek_position: None,
             length: None,
-            image_key: track.and_then(|t| t.album_art_uri.clone()),
+            image_key: track.and_then(|t| t.image_key.clone()),
         })
     }
 
@@ -876,6 +951,9 @@ impl OpenHomeAdapter {
             }
         }
 
+        // Reset adaptive backoff so the poll loop follows the state change closely
+        self.poll_waker.wake();
+
         // Trigger immediate poll
         let state = self.state.clone();
         let bus = self.bus.clone();
@@ -891,24 +969,71 @@ impl OpenHomeAdapter {
         Ok(())
     }
 
-    /// Fetch album art image
+    /// Product service control URL of a known device
+    async fn product_url(&self, uuid: &str) -> anyhow::Result<String> {
+        let location = {
+            let state = self.state.read().await;
+            state
+                .devices
+                .get(uuid)
+                .map(|d| d.location.clone())
+                .ok_or_else(|| anyhow::anyhow!("Device not found: {}", uuid))?
+        };
+        Ok(format!("{}/Product", Self::get_base_url(&location)?))
+    }
+
+    /// The device's inputs (Product sources) and the selected one
+    pub async fn get_sources(&self, uuid: &str) -> anyhow::Result<OpenHomeSources> {
+        let product_url = self.product_url(uuid).await?;
+        let response =
+            Self::soap_call(&self.http, &product_url, PRODUCT_SERVICE, "SourceXml", "").await?;
+        let sources = match Self::extract_xml_value(&response, "Value") {
+            Some(value) => parse_source_xml(&html_decode(&value))?,
+            None => anyhow::bail!("No source list in SourceXml response"),
+        };
+        let response =
+            Self::soap_call(&self.http, &product_url, PRODUCT_SERVICE, "SourceIndex", "").await?;
+        let current = Self::extract_xml_value(&response, "Value")
+            .and_then(|v| v.trim().parse().ok())
+            .ok_or_else(|| anyhow::anyhow!("No index in SourceIndex response"))?;
+        Ok(OpenHomeSources { sources, current })
+    }
+
+    /// Switch the device to the source at `index` (see [`OpenHomeSource::index`])
+    pub async fn set_source(&self, uuid: &str, index: u32) -> anyhow::Result<()> {
+        let product_url = self.product_url(uuid).await?;
+        Self::soap_call(
+            &self.http,
+            &product_url,
+            PRODUCT_SERVICE,
+            "SetSourceIndex",
+            &format!("<Value>{}</Value>", index),
+        )
+        .await?;
+        // Transport state and track follow the new source
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Fetch album art image (`image_url` is a zone's `image_key`)
     pub async fn get_image(&self, image_url: &str) -> anyhow::Result<ImageData> {
         if !image_url.starts_with("http://") && !image_url.starts_with("https://") {
             anyhow::bail!("Invalid image URL");
         }
 
-        let response = self.http.get(image_url).send().await?;
-        let content_type = response
+        // The fragment is only our per-track cache-busting key (see `artwork_key`)
+        let url = image_url.split('#').next().unwrap_or(image_url);
+        let response = self.http.get(url).send().await?;
+        let declared = response
             .headers()
             .get(reqwest::header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
-            .unwrap_or("image/jpeg")
-            .to_string();
+            .map(str::to_string);
 
         let body = response.bytes().await?;
 
         Ok(ImageData {
-            content_type,
+            content_type: sniff_content_type(&body, declared.as_deref()),
             data: body.to_vec(),
         })
     }
@@ -921,6 +1046,24 @@ pub struct ImageData {
     pub data: Vec<u8>,
 }
 
+/// Artwork key for a track: the art URI plus a fragment derived from the track URI.
+///
+/// Many renderers serve every track's art from one fixed URL, so the URI alone
+/// can't tell caches (ours and the browser's) that the picture changed. The
+/// fragment is never sent to the renderer.
+pub fn artwork_key(art_uri: &str, track_uri: Option<&str>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let base = art_uri.split('#').next().unwrap_or(art_uri);
+    match track_uri {
+        Some(track) => {
+            let digest = Sha256::digest(track.as_bytes());
+            format!("{}#t={}", base, hex::encode(&digest[..4]))
+        }
+        None => base.to_string(),
+    }
+}
+
 /// Decode HTML entities
 fn html_decode(s: &str) -> String {
     s.replace("&lt;", "<")
@@ -958,7 +1101,7 @@ fn openhome_device_to_zone(device: &OpenHomeDevice) -> Zone {
             title: t.title.clone(),
             artist: t.artist.clone(),
             album: t.album.clone(),
-            image_key: t.album_art_uri.clone(),
+            image_key: t.image_key.clone(),
             seek_position: None,
             duration: None,
             metadata: None,
@@ -966,6 +1109,18 @@ fn openhome_device_to_zone(device: &OpenHomeDevice) -> Zone {
         source: "openhome".to_string(),
         is_controllable: true,
         is_seekable: false, // OpenHome seek support varies
+        capabilities: crate::bus::ZoneCapabilities {
+            can_seek: false,
+            // Playlist service has shuffle and a track list
+            can_shuffle: true,
+            has_volume: device.volume.is_some(),
+            has_queue: true,
+            supports_grouping: false,
+            has_eq: false,
+            // Every OpenHome device has the Product service's source list
+            has_input: true,
+        },
+        volume_leveling: None,
         last_updated: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -974,6 +1129,7 @@ fn openhome_device_to_zone(device: &OpenHomeDevice) -> Zone {
         is_pause_allowed: device.state == "playing",
         is_next_allowed: true,
         is_previous_allowed: true,
+        metadata: None,
     }
 }
 
@@ -1009,7 +1165,7 @@ impl AdapterLogic for OpenHomeAdapter {
             _ = Self::discovery_loop(discovery_state, discovery_bus, discovery_http, discovery_shutdown) => {
                 tracing::info!("OpenHome discovery loop ended");
             }
-            _ = Self::poll_loop(poll_state, poll_bus, poll_http, poll_shutdown) => {
+            _ = Self::poll_loop(poll_state, poll_bus, poll_http, poll_shutdown, self.poll_waker.clone()) => {
                 tracing::info!("OpenHome poll loop ended");
             }
         }
@@ -1066,3 +1222,23 @@ impl AdapterLogic for OpenHomeAdapter {
 
 // Startable trait implementation via macro
 crate::impl_startable!(OpenHomeAdapter, "openhome");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_xml_keeps_hidden_sources_in_the_indexing() {
+        let value = "&lt;SourceList&gt;\
+            &lt;Source&gt;&lt;Name&gt;Playlist&lt;/Name&gt;&lt;Type&gt;Playlist&lt;/Type&gt;&lt;Visible&gt;true&lt;/Visible&gt;&lt;/Source&gt;\
+            &lt;Source&gt;&lt;Name&gt;UPnP AV&lt;/Name&gt;&lt;Type&gt;UpnpAv&lt;/Type&gt;&lt;Visible&gt;false&lt;/Visible&gt;&lt;/Source&gt;\
+            &lt;Source&gt;&lt;Name&gt;TV &amp;amp; Film&lt;/Name&gt;&lt;Type&gt;Hdmi&lt;/Type&gt;&lt;Visible&gt;true&lt;/Visible&gt;&lt;/Source&gt;\
+            &lt;/SourceList&gt;";
+        let sources = parse_source_xml(&html_decode(value)).unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].name, "Playlist");
+        assert_eq!(sources[1].index, 2);
+        assert_eq!(sources[1].name, "TV & Film");
+        assert_eq!(sources[1].source_type, "Hdmi");
+    }
+}
@@ -0,0 +1,135 @@
+//! Recent backend operations per adapter (`/debug/adapters/{name}/ops`)
+//!
+//! Each adapter records its calls to the backend (HQPlayer commands, LMS
+//! JSON-RPC requests, Roon transport calls, ...) with their duration and
+//! outcome in a small ring buffer, so a slow or failing backend can be told
+//! apart from a network problem without turning on debug logging.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// Adapters that record operations
+pub const ADAPTERS: &[&str] = &[
+    "roon",
+    "lms",
+    "openhome",
+    "upnp",
+    "snapcast",
+    "heos",
+    "kodi",
+    "spotify",
+    "http",
+    "plexamp",
+    "hqplayer",
+    "camilladsp",
+    "minidsp",
+];
+
+/// Operations kept per adapter
+const CAPACITY: usize = 100;
+
+/// One backend call
+#[derive(Debug, Clone, Serialize)]
+pub struct OpRecord {
+    /// e.g. `GetState`, `status`, `control`
+    pub operation: String,
+    /// Instance or player the call went to, if the adapter has several
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Start time (milliseconds since epoch)
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+static OPS: LazyLock<Mutex<HashMap<&'static str, VecDeque<OpRecord>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Record a finished operation
+pub fn record(
+    adapter: &'static str,
+    operation: &str,
+    target: Option<&str>,
+    started: Instant,
+    error: Option<String>,
+) {
+    let elapsed = started.elapsed();
+    let started_at = SystemTime::now()
+        .checked_sub(elapsed)
+        .unwrap_or_else(SystemTime::now)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let op = OpRecord {
+        operation: operation.to_string(),
+        target: target.map(str::to_string),
+        started_at,
+        duration_ms: elapsed.as_millis() as u64,
+        ok: error.is_none(),
+        error,
+    };
+
+    let mut ops = OPS.lock().unwrap_or_else(PoisonError::into_inner);
+    let ring = ops.entry(adapter).or_default();
+    if ring.len() >= CAPACITY {
+        ring.pop_front();
+    }
+    ring.push_back(op);
+}
+
+/// Run `fut` and record it as `operation`
+pub async fn timed<T, E: std::fmt::Display>(
+    adapter: &'static str,
+    operation: &str,
+    target: Option<&str>,
+    fut: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = fut.await;
+    let error = result.as_ref().err().map(|e| e.to_string());
+    record(adapter, operation, target, started, error);
+    result
+}
+
+/// Recorded operations for `adapter`, newest first
+pub fn recent(adapter: &str) -> Vec<OpRecord> {
+    OPS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(adapter)
+        .map(|ring| ring.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timed_records_newest_first() {
+        let _: Result<(), String> = timed("upnp", "first", None, async { Ok(()) }).await;
+        let _: Result<(), String> =
+            timed("upnp", "second", Some("udn"), async { Err("boom".into()) }).await;
+
+        let ops = recent("upnp");
+        let second = ops.iter().position(|o| o.operation == "second").unwrap();
+        let first = ops.iter().position(|o| o.operation == "first").unwrap();
+        assert!(second < first);
+        assert!(!ops[second].ok);
+        assert_eq!(ops[second].error.as_deref(), Some("boom"));
+        assert_eq!(ops[second].target.as_deref(), Some("udn"));
+    }
+
+    #[test]
+    fn test_ring_is_bounded() {
+        for i in 0..CAPACITY + 10 {
+            record("openhome", &format!("op{i}"), None, Instant::now(), None);
+        }
+        assert_eq!(recent("openhome").len(), CAPACITY);
+    }
+}
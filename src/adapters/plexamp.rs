@@ -0,0 +1,941 @@
+//! Plexamp adapter - shows headless Plexamp players as zones
+//!
+//! Headless Plexamp (the Raspberry Pi build) serves Plex's player API on
+//! port 32500. Each configured player is polled with
+//! `/player/timeline/poll?wait=0&includeMetadata=1`, whose music timeline
+//! carries the state, position, volume and playing track, plus the Plex Media
+//! Server the track comes from. Transport and volume go to
+//! `/player/playback/*`; artwork is fetched through that server's image
+//! transcoder (`/photo/:/transcode`) with the token the timeline carries.
+//!
+//! Plexamp has no mute of its own: muting sets the volume to 0 and unmuting
+//! restores the level it had.
+//!
+//! The zone id is `plexamp:<host>`.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::polling::{AdaptiveBackoff, PollWaker};
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeScale,
+    Zone, ZoneCapabilities,
+};
+use crate::clock::unix_millis;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::knobs::image::sniff_content_type;
+
+const PLEXAMP_CONFIG_FILE: &str = "plexamp-config.json";
+/// Plexamp's player API port
+const DEFAULT_PORT: u16 = 32500;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Artwork size requested from the image transcoder (resized again per request)
+const ARTWORK_SIZE: u32 = 1000;
+/// Identifies the bridge to Plexamp as a remote control
+const CLIENT_IDENTIFIER: &str = "unified-hifi-control";
+const PRODUCT: &str = "Unified Hi-Fi Control";
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedPlexampConfig {
+    hosts: Vec<String>,
+    #[serde(default = "default_port")]
+    port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(PLEXAMP_CONFIG_FILE)
+}
+
+/// Check a player host: a name or IPv4 address, since it becomes the zone's raw id
+fn validate_host(host: &str) -> Result<()> {
+    if host.is_empty() {
+        bail!("Plexamp host is empty");
+    }
+    if host.contains(|c: char| c == ':' || c == '/' || c.is_whitespace()) {
+        bail!(
+            "Invalid Plexamp host {:?} (use a host name or IPv4 address; the port is shared)",
+            host
+        );
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Player API model
+// =============================================================================
+
+/// `/player/timeline/poll` response
+#[derive(Debug, Default, Deserialize)]
+struct TimelineContainer {
+    #[serde(rename = "Timeline", default)]
+    timelines: Vec<Timeline>,
+}
+
+/// One timeline (music, video or photo)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct Timeline {
+    #[serde(rename = "@type", default)]
+    kind: String,
+    /// `playing`, `paused`, `buffering` or `stopped`
+    #[serde(rename = "@state", default)]
+    state: String,
+    /// Position (ms)
+    #[serde(rename = "@time", default)]
+    time: Option<u64>,
+    /// Track length (ms)
+    #[serde(rename = "@duration", default)]
+    duration: Option<u64>,
+    /// 0-100
+    #[serde(rename = "@volume", default)]
+    volume: Option<u8>,
+    /// Comma-separated controls, e.g. `playPause,stop,skipNext,volume,seekTo`
+    #[serde(rename = "@controllable", default)]
+    controllable: String,
+    /// Server the track plays from
+    #[serde(rename = "@protocol", default)]
+    protocol: Option<String>,
+    #[serde(rename = "@address", default)]
+    address: Option<String>,
+    #[serde(rename = "@port", default)]
+    port: Option<u16>,
+    #[serde(rename = "@token", default)]
+    token: Option<String>,
+    #[serde(rename = "Track", default)]
+    track: Option<PlexTrack>,
+}
+
+/// The playing track (`includeMetadata=1`)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct PlexTrack {
+    #[serde(rename = "@title", default)]
+    title: String,
+    /// Track artist, when it differs from the album artist
+    #[serde(rename = "@originalTitle", default)]
+    original_title: Option<String>,
+    /// Album artist
+    #[serde(rename = "@grandparentTitle", default)]
+    grandparent_title: String,
+    /// Album
+    #[serde(rename = "@parentTitle", default)]
+    parent_title: String,
+    #[serde(rename = "@thumb", default)]
+    thumb: Option<String>,
+    #[serde(rename = "@parentThumb", default)]
+    parent_thumb: Option<String>,
+}
+
+/// `/resources` response
+#[derive(Debug, Default, Deserialize)]
+struct ResourcesContainer {
+    #[serde(rename = "Player", default)]
+    players: Vec<PlexPlayer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PlexPlayer {
+    #[serde(rename = "@title", default)]
+    title: String,
+}
+
+/// The music timeline from a timeline poll
+fn parse_music_timeline(xml: &str) -> Result<Timeline> {
+    let container: TimelineContainer = quick_xml::de::from_str(xml)?;
+    Ok(container
+        .timelines
+        .into_iter()
+        .find(|t| t.kind == "music")
+        .unwrap_or_default())
+}
+
+impl Timeline {
+    fn playback_state(&self) -> PlaybackState {
+        PlaybackState::from(self.state.as_str())
+    }
+
+    fn can(&self, control: &str) -> bool {
+        self.controllable.split(',').any(|c| c.trim() == control)
+    }
+
+    /// Artwork path on the server (the zone's `image_key`)
+    fn thumb(&self) -> Option<&str> {
+        let track = self.track.as_ref()?;
+        track
+            .thumb
+            .as_deref()
+            .or(track.parent_thumb.as_deref())
+            .filter(|t| !t.is_empty())
+    }
+
+    /// Image transcoder URL for `thumb` on the server the track plays from
+    fn artwork_url(&self, thumb: &str) -> Result<Url> {
+        let address = self
+            .address
+            .as_deref()
+            .ok_or_else(|| anyhow!("Plexamp reports no Plex server"))?;
+        let protocol = match self.protocol.as_deref() {
+            Some("https") => "https",
+            _ => "http",
+        };
+        let mut url = Url::parse(&format!(
+            "{}://{}:{}/photo/:/transcode",
+            protocol,
+            address,
+            self.port.unwrap_or(32400)
+        ))?;
+        {
+            let size = ARTWORK_SIZE.to_string();
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("width", &size)
+                .append_pair("height", &size)
+                .append_pair("minSize", "1")
+                .append_pair("upscale", "1")
+                .append_pair("url", thumb);
+            if let Some(ref token) = self.token {
+                query.append_pair("X-Plex-Token", token);
+            }
+        }
+        Ok(url)
+    }
+
+    fn now_playing(&self) -> Option<NowPlaying> {
+        let track = self.track.as_ref()?;
+        let artist = track
+            .original_title
+            .clone()
+            .filter(|a| !a.is_empty())
+            .unwrap_or_else(|| track.grandparent_title.clone());
+        Some(NowPlaying {
+            title: track.title.clone(),
+            artist,
+            album: track.parent_title.clone(),
+            image_key: self.thumb().map(str::to_string),
+            seek_position: self.time.map(|ms| ms as f64 / 1000.0),
+            duration: self
+                .duration
+                .filter(|&ms| ms > 0)
+                .map(|ms| ms as f64 / 1000.0),
+            metadata: None,
+        })
+    }
+
+    /// The zone, with `last_updated` left at 0 so unchanged zones compare equal
+    fn zone(&self, host: &str, name: Option<&str>, muted: bool) -> Zone {
+        let state = self.playback_state();
+        let active = self.track.is_some();
+        let can_seek = self.can("seekTo");
+        let has_volume = self.volume.is_some() || self.can("volume");
+        Zone {
+            zone_id: PrefixedZoneId::plexamp(host).to_string(),
+            zone_name: name.unwrap_or(host).to_string(),
+            state,
+            volume_control: has_volume.then(|| VolumeControl {
+                value: self.volume.unwrap_or(0) as f32,
+                min: 0.0,
+                max: 100.0,
+                step: 1.0,
+                is_muted: muted,
+                scale: VolumeScale::Percentage,
+                output_id: Some(PrefixedZoneId::plexamp(host).to_string()),
+            }),
+            now_playing: self.now_playing(),
+            source: "plexamp".to_string(),
+            is_controllable: true,
+            is_seekable: can_seek,
+            capabilities: ZoneCapabilities {
+                can_seek,
+                has_volume,
+                ..Default::default()
+            },
+            volume_leveling: None,
+            last_updated: 0,
+            is_play_allowed: active && state != PlaybackState::Playing,
+            is_pause_allowed: state == PlaybackState::Playing,
+            is_next_allowed: active,
+            is_previous_allowed: active,
+            metadata: None,
+        }
+    }
+}
+
+/// The zone as compared between publishes: position changes are published on their own
+fn without_position(zone: &Zone) -> Zone {
+    let mut zone = zone.clone();
+    if let Some(ref mut np) = zone.now_playing {
+        np.seek_position = None;
+    }
+    zone
+}
+
+// =============================================================================
+// Adapter
+// =============================================================================
+
+/// One player as reported by GET /api/adapters/plexamp/config
+#[derive(Debug, Clone, Serialize)]
+pub struct PlexampPlayerSummary {
+    pub host: String,
+    pub name: String,
+    pub state: String,
+    pub online: bool,
+    /// Why the last timeline poll failed
+    pub error: Option<String>,
+}
+
+/// Plexamp adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct PlexampStatus {
+    /// Any player answering
+    pub connected: bool,
+    pub hosts: Vec<String>,
+    pub port: u16,
+    pub has_token: bool,
+    pub players: Vec<PlexampPlayerSummary>,
+}
+
+#[derive(Debug, Default)]
+struct PlayerState {
+    /// Player title from `/resources`
+    name: Option<String>,
+    online: bool,
+    error: Option<String>,
+    timeline: Option<Timeline>,
+    /// Volume to restore on unmute, while muted
+    muted_volume: Option<u8>,
+    /// Zone as last published (without position)
+    published: Option<Zone>,
+}
+
+impl PlayerState {
+    fn muted(&self) -> bool {
+        self.muted_volume.is_some() && self.timeline.as_ref().and_then(|t| t.volume) == Some(0)
+    }
+}
+
+struct PlexampState {
+    hosts: Vec<String>,
+    port: u16,
+    token: Option<String>,
+    players: HashMap<String, PlayerState>,
+    poll_interval: Duration,
+    running: bool,
+}
+
+/// Adapter for headless Plexamp players
+#[derive(Clone)]
+pub struct PlexampAdapter {
+    state: Arc<RwLock<PlexampState>>,
+    bus: SharedBus,
+    http: Client,
+    /// Plexamp expects an increasing `commandID` per controller
+    command_id: Arc<AtomicU64>,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+    poll_waker: PollWaker,
+}
+
+impl PlexampAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        let adapter = Self {
+            state: Arc::new(RwLock::new(PlexampState {
+                hosts: Vec::new(),
+                port: DEFAULT_PORT,
+                token: None,
+                players: HashMap::new(),
+                poll_interval: DEFAULT_POLL_INTERVAL,
+                running: false,
+            })),
+            bus,
+            http: Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            command_id: Arc::new(AtomicU64::new(1)),
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+            poll_waker: PollWaker::default(),
+        };
+        adapter.load_config_sync();
+        adapter
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(PLEXAMP_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedPlexampConfig>(&content) {
+            Ok(saved) => {
+                if let Err(e) = saved.hosts.iter().try_for_each(|h| validate_host(h)) {
+                    tracing::warn!("Ignoring Plexamp config: {}", e);
+                    return;
+                }
+                if let Ok(mut state) = self.state.try_write() {
+                    tracing::info!("Loaded {} Plexamp player(s) from disk", saved.hosts.len());
+                    state.hosts = saved.hosts;
+                    state.port = saved.port;
+                    state.token = saved.token;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse Plexamp config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let saved = {
+            let state = self.state.read().await;
+            SavedPlexampConfig {
+                hosts: state.hosts.clone(),
+                port: state.port,
+                token: state.token.clone(),
+            }
+        };
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&saved) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save Plexamp config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize Plexamp config: {}", e),
+        }
+    }
+
+    /// Configure the player hosts, their API port and the Plex token (if they need one)
+    pub async fn configure(
+        &self,
+        hosts: Vec<String>,
+        port: Option<u16>,
+        token: Option<String>,
+    ) -> Result<()> {
+        let mut unique: Vec<String> = Vec::new();
+        for host in hosts.iter().map(|h| h.trim()) {
+            validate_host(host)?;
+            if !unique.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+                unique.push(host.to_string());
+            }
+        }
+        {
+            let mut state = self.state.write().await;
+            state.hosts = unique;
+            state.port = port.unwrap_or(DEFAULT_PORT);
+            state.token = token.filter(|t| !t.is_empty());
+        }
+        self.save_config().await;
+        Ok(())
+    }
+
+    /// Plex token sent to the players
+    pub async fn token(&self) -> Option<String> {
+        self.state.read().await.token.clone()
+    }
+
+    /// Check if configured
+    pub async fn is_configured(&self) -> bool {
+        !self.state.read().await.hosts.is_empty()
+    }
+
+    pub async fn set_poll_interval(&self, poll_interval: Duration) {
+        self.state.write().await.poll_interval = poll_interval;
+    }
+
+    /// Get connection status
+    pub async fn get_status(&self) -> PlexampStatus {
+        let state = self.state.read().await;
+        let players: Vec<PlexampPlayerSummary> = state
+            .hosts
+            .iter()
+            .map(|host| {
+                let player = state.players.get(host);
+                PlexampPlayerSummary {
+                    host: host.clone(),
+                    name: player
+                        .and_then(|p| p.name.clone())
+                        .unwrap_or_else(|| host.clone()),
+                    state: player
+                        .and_then(|p| p.timeline.as_ref())
+                        .map(|t| t.playback_state().to_string())
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    online: player.is_some_and(|p| p.online),
+                    error: player.and_then(|p| p.error.clone()),
+                }
+            })
+            .collect();
+        PlexampStatus {
+            connected: players.iter().any(|p| p.online),
+            hosts: state.hosts.clone(),
+            port: state.port,
+            has_token: state.token.is_some(),
+            players,
+        }
+    }
+
+    /// Start polling (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            if state.hosts.is_empty() {
+                bail!("No Plexamp players configured");
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("Plexamp adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+        self.state.write().await.running = false;
+        self.clear_zones().await;
+        tracing::info!("Plexamp adapter stopped");
+    }
+
+    /// Remove every published zone
+    async fn clear_zones(&self) {
+        let removed: Vec<Zone> = {
+            let mut state = self.state.write().await;
+            state
+                .players
+                .drain()
+                .filter_map(|(_, player)| player.published)
+                .collect()
+        };
+        for zone in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone.zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// GET a player API path (with query), returning the body
+    async fn get(&self, host: &str, path_and_query: &str) -> Result<String> {
+        let (port, token) = {
+            let state = self.state.read().await;
+            if !state.hosts.iter().any(|h| h == host) {
+                bail!("Unknown Plexamp player: {}", host);
+            }
+            (state.port, state.token.clone())
+        };
+        let url = format!("http://{}:{}{}", host, port, path_and_query);
+        let mut request = self
+            .http
+            .get(&url)
+            .header("X-Plex-Client-Identifier", CLIENT_IDENTIFIER)
+            .header("X-Plex-Product", PRODUCT)
+            .header("X-Plex-Device-Name", PRODUCT);
+        if let Some(token) = token {
+            request = request.header("X-Plex-Token", token);
+        }
+        Ok(request.send().await?.error_for_status()?.text().await?)
+    }
+
+    /// Player title from `/resources`
+    async fn fetch_name(&self, host: &str) -> Option<String> {
+        let xml = self.get(host, "/resources").await.ok()?;
+        let resources: ResourcesContainer = quick_xml::de::from_str(&xml).ok()?;
+        resources
+            .players
+            .into_iter()
+            .map(|p| p.title)
+            .find(|title| !title.is_empty())
+    }
+
+    /// Poll one player's timeline and publish its zone if it changed
+    async fn poll_player(&self, host: &str) {
+        let command_id = self.command_id.fetch_add(1, Ordering::Relaxed);
+        let result = self
+            .get(
+                host,
+                &format!(
+                    "/player/timeline/poll?wait=0&includeMetadata=1&type=music&commandID={}",
+                    command_id
+                ),
+            )
+            .await
+            .and_then(|xml| parse_music_timeline(&xml));
+
+        let needs_name = {
+            let state = self.state.read().await;
+            result.is_ok() && state.players.get(host).is_none_or(|p| p.name.is_none())
+        };
+        let name = if needs_name {
+            self.fetch_name(host).await
+        } else {
+            None
+        };
+
+        let event = {
+            let mut state = self.state.write().await;
+            let player = state.players.entry(host.to_string()).or_default();
+            if name.is_some() {
+                player.name = name;
+            }
+            match result {
+                Ok(timeline) => {
+                    player.online = true;
+                    player.error = None;
+                    let moved = player.timeline.as_ref().map(|t| t.time) != Some(timeline.time);
+                    player.timeline = Some(timeline.clone());
+                    let zone = timeline.zone(host, player.name.as_deref(), player.muted());
+                    let compared = without_position(&zone);
+                    if player.published.as_ref() != Some(&compared) {
+                        player.published = Some(compared);
+                        let mut zone = zone;
+                        zone.last_updated = unix_millis();
+                        Some(BusEvent::ZoneDiscovered { zone })
+                    } else if moved {
+                        timeline.time.map(|ms| BusEvent::SeekPositionChanged {
+                            zone_id: PrefixedZoneId::plexamp(host),
+                            position: (ms / 1000) as i64,
+                        })
+                    } else {
+                        None
+                    }
+                }
+                Err(e) => {
+                    if player.online {
+                        tracing::warn!("Plexamp player {} unreachable: {}", host, e);
+                    }
+                    player.online = false;
+                    player.error = Some(e.to_string());
+                    player.timeline = None;
+                    player.published.take().map(|_| BusEvent::ZoneRemoved {
+                        zone_id: PrefixedZoneId::plexamp(host),
+                    })
+                }
+            }
+        };
+        if let Some(event) = event {
+            self.bus.publish(event);
+        }
+    }
+
+    async fn timeline(&self, host: &str) -> Option<Timeline> {
+        self.state
+            .read()
+            .await
+            .players
+            .get(host)
+            .and_then(|p| p.timeline.clone())
+    }
+
+    /// Send a playback command (`play`, `skipNext`, `setParameters`, ...)
+    async fn command(&self, host: &str, command: &str, params: &[(&str, String)]) -> Result<()> {
+        let command_id = self.command_id.fetch_add(1, Ordering::Relaxed);
+        let mut query = format!("type=music&commandID={}", command_id);
+        for (key, value) in params {
+            query.push_str(&format!("&{}={}", key, urlencoding::encode(value)));
+        }
+        let target = PrefixedZoneId::plexamp(host);
+        ops::timed(
+            "plexamp",
+            command,
+            Some(target.as_str()),
+            self.get(host, &format!("/player/playback/{}?{}", command, query)),
+        )
+        .await?;
+        // Follow the change right away
+        self.poll_waker.wake();
+        Ok(())
+    }
+
+    /// Play, pause, play_pause, stop, next or previous
+    pub async fn control(&self, host: &str, action: &str) -> Result<()> {
+        let command = match action {
+            "play" => "play",
+            "pause" => "pause",
+            "stop" => "stop",
+            "next" => "skipNext",
+            "previous" | "prev" => "skipPrevious",
+            "play_pause" | "playpause" => {
+                let playing = self
+                    .timeline(host)
+                    .await
+                    .is_some_and(|t| t.playback_state() == PlaybackState::Playing);
+                if playing {
+                    "pause"
+                } else {
+                    "play"
+                }
+            }
+            _ => bail!("Unknown action: {}", action),
+        };
+        self.command(host, command, &[]).await
+    }
+
+    /// Seek to `seconds` into the track
+    pub async fn seek(&self, host: &str, seconds: f64) -> Result<()> {
+        let offset = (seconds.max(0.0) * 1000.0).round() as u64;
+        self.command(host, "seekTo", &[("offset", offset.to_string())])
+            .await
+    }
+
+    /// Set the volume (0-100)
+    pub async fn set_volume(&self, host: &str, level: f32) -> Result<()> {
+        let level = level.clamp(0.0, 100.0).round() as u8;
+        self.command(host, "setParameters", &[("volume", level.to_string())])
+            .await?;
+        if level > 0 {
+            if let Some(player) = self.state.write().await.players.get_mut(host) {
+                player.muted_volume = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Change the volume by `delta` percentage points from the last polled level
+    pub async fn change_volume(&self, host: &str, delta: f32) -> Result<()> {
+        let current = self
+            .timeline(host)
+            .await
+            .and_then(|t| t.volume)
+            .ok_or_else(|| anyhow!("Plexamp player {} reports no volume", host))?;
+        self.set_volume(host, current as f32 + delta).await
+    }
+
+    /// Mute (volume 0) or unmute (restore the previous level)
+    pub async fn set_mute(&self, host: &str, mute: bool) -> Result<()> {
+        if mute == self.is_muted(host).await {
+            return Ok(());
+        }
+        if mute {
+            let current = self
+                .timeline(host)
+                .await
+                .and_then(|t| t.volume)
+                .unwrap_or(0);
+            self.command(host, "setParameters", &[("volume", "0".to_string())])
+                .await?;
+            if let Some(player) = self.state.write().await.players.get_mut(host) {
+                player.muted_volume = Some(current);
+            }
+            Ok(())
+        } else {
+            let restore = self
+                .state
+                .read()
+                .await
+                .players
+                .get(host)
+                .and_then(|p| p.muted_volume)
+                .unwrap_or(0);
+            self.set_volume(host, restore as f32).await
+        }
+    }
+
+    /// Whether the player was muted through the bridge (and is still at 0)
+    pub async fn is_muted(&self, host: &str) -> bool {
+        self.state
+            .read()
+            .await
+            .players
+            .get(host)
+            .is_some_and(|p| p.muted())
+    }
+
+    /// Fetch the playing track's artwork through the Plex image transcoder
+    ///
+    /// Only the thumb of the track the player last reported is fetched, from
+    /// the server that timeline names.
+    pub async fn get_image(&self, host: &str, image_key: &str) -> Result<crate::bus::ImageData> {
+        let timeline = self
+            .timeline(host)
+            .await
+            .ok_or_else(|| anyhow!("Nothing is playing on Plexamp player {}", host))?;
+        if timeline.thumb() != Some(image_key) {
+            bail!("Invalid image key");
+        }
+        let url = timeline.artwork_url(image_key)?;
+        let response = self.http.get(url).send().await?.error_for_status()?;
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        Ok(crate::bus::ImageData {
+            content_type: sniff_content_type(&body, declared.as_deref()),
+            data: body.to_vec(),
+        })
+    }
+}
+
+crate::impl_startable!(PlexampAdapter, "plexamp", is_configured);
+
+#[async_trait]
+impl AdapterLogic for PlexampAdapter {
+    fn prefix(&self) -> &'static str {
+        "plexamp"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        let mut backoff = AdaptiveBackoff::default();
+        // First poll happens immediately
+        let mut delay = Duration::ZERO;
+
+        loop {
+            tokio::select! {
+                _ = ctx.shutdown.cancelled() => break,
+                _ = self.poll_waker.woken() => {
+                    backoff.reset();
+                }
+                _ = tokio::time::sleep(delay) => {}
+            }
+
+            let hosts = self.state.read().await.hosts.clone();
+            futures::future::join_all(hosts.iter().map(|host| self.poll_player(host))).await;
+
+            // Poll at the base interval while anything plays, back off while idle
+            delay = {
+                let state = self.state.read().await;
+                let any_playing = state.players.values().any(|p| {
+                    p.timeline
+                        .as_ref()
+                        .is_some_and(|t| t.playback_state() == PlaybackState::Playing)
+                });
+                backoff.next_delay(state.poll_interval, any_playing)
+            };
+        }
+
+        self.clear_zones().await;
+        Ok(())
+    }
+
+    async fn handle_command(
+        &self,
+        zone_id: &str,
+        command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        let host = zone_id.strip_prefix("plexamp:").unwrap_or(zone_id);
+        let result = match command {
+            AdapterCommand::Play => self.control(host, "play").await,
+            AdapterCommand::Pause => self.control(host, "pause").await,
+            AdapterCommand::PlayPause => self.control(host, "play_pause").await,
+            AdapterCommand::Stop => self.control(host, "stop").await,
+            AdapterCommand::Next => self.control(host, "next").await,
+            AdapterCommand::Previous => self.control(host, "previous").await,
+            AdapterCommand::VolumeAbsolute(v) => self.set_volume(host, v as f32).await,
+            AdapterCommand::VolumeRelative(delta) => self.change_volume(host, delta as f32).await,
+            AdapterCommand::Mute(mute) => self.set_mute(host, mute).await,
+        };
+
+        match result {
+            Ok(()) => Ok(AdapterCommandResponse {
+                success: true,
+                error: None,
+            }),
+            Err(e) => Ok(AdapterCommandResponse {
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYING: &str = r#"<MediaContainer commandID="3" location="fullScreenMusic">
+<Timeline type="music" state="playing" time="61500" duration="245000" volume="72" controllable="playPause,stop,volume,shuffle,repeat,seekTo,skipPrevious,skipNext,stepBack,stepForward" protocol="https" address="192.168.1.10" port="32400" token="abc123" machineIdentifier="m1" key="/library/metadata/42" ratingKey="42">
+<Track title="So What" grandparentTitle="Miles Davis" parentTitle="Kind of Blue" thumb="/library/metadata/41/thumb/1690000000" ratingKey="42" type="track"/>
+</Timeline>
+<Timeline type="video" state="stopped"/>
+<Timeline type="photo" state="stopped"/>
+</MediaContainer>"#;
+
+    #[test]
+    fn test_music_timeline_becomes_zone() {
+        let timeline = parse_music_timeline(PLAYING).unwrap();
+        let zone = timeline.zone("plexamp-pi", Some("Kitchen"), false);
+        assert_eq!(zone.zone_id, "plexamp:plexamp-pi");
+        assert_eq!(zone.zone_name, "Kitchen");
+        assert_eq!(zone.state, PlaybackState::Playing);
+        assert!(zone.is_seekable && zone.capabilities.can_seek);
+        let np = zone.now_playing.unwrap();
+        assert_eq!(np.title, "So What");
+        assert_eq!(np.artist, "Miles Davis");
+        assert_eq!(np.album, "Kind of Blue");
+        assert_eq!(
+            np.image_key.as_deref(),
+            Some("/library/metadata/41/thumb/1690000000")
+        );
+        assert_eq!(np.seek_position, Some(61.5));
+        assert_eq!(np.duration, Some(245.0));
+        assert_eq!(zone.volume_control.unwrap().value, 72.0);
+    }
+
+    #[test]
+    fn test_artwork_goes_through_the_transcoder() {
+        let timeline = parse_music_timeline(PLAYING).unwrap();
+        let url = timeline
+            .artwork_url(timeline.thumb().unwrap())
+            .unwrap()
+            .to_string();
+        assert!(url.starts_with("https://192.168.1.10:32400/photo/:/transcode?"));
+        assert!(url.contains("url=%2Flibrary%2Fmetadata%2F41%2Fthumb%2F1690000000"));
+        assert!(url.contains("X-Plex-Token=abc123"));
+    }
+
+    #[test]
+    fn test_idle_player_is_stopped() {
+        let xml = r#"<MediaContainer commandID="1"><Timeline type="music" state="stopped"/></MediaContainer>"#;
+        let zone = parse_music_timeline(xml).unwrap().zone("pi", None, false);
+        assert_eq!(zone.zone_name, "pi");
+        assert_eq!(zone.state, PlaybackState::Stopped);
+        assert!(zone.now_playing.is_none());
+        assert!(!zone.is_play_allowed && !zone.is_next_allowed);
+    }
+
+    #[test]
+    fn test_hosts_must_not_carry_ports_or_paths() {
+        assert!(validate_host("plexamp.local").is_ok());
+        assert!(validate_host("192.168.1.20").is_ok());
+        assert!(validate_host("plexamp.local:32500").is_err());
+        assert!(validate_host("http://plexamp.local").is_err());
+        assert!(validate_host("").is_err());
+    }
+}
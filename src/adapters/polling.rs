@@ -0,0 +1,111 @@
+//! Adaptive polling for adapters without push notifications (LMS, UPnP, OpenHome)
+//!
+//! Polls at the configured base interval while any zone is playing, and backs off
+//! exponentially (up to [`IDLE_BACKOFF_MULTIPLIER`] x base) once everything is stopped.
+//! Control commands wake the poller immediately so the UI never lags behind a button press.
+//!
+//! ```text
+//! playing        → base, base, base, ...
+//! all stopped    → base, 2x, 4x, 8x, 16x, 16x, ...
+//! command issued → wake now, back to base
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Maximum backoff over the base interval while all zones are idle
+pub const IDLE_BACKOFF_MULTIPLIER: u32 = 16;
+
+/// Exponential backoff state for an adapter poll loop
+#[derive(Debug, Default)]
+pub struct AdaptiveBackoff {
+    current: Option<Duration>,
+}
+
+impl AdaptiveBackoff {
+    /// Compute the delay before the next poll.
+    ///
+    /// `active` should be true if any zone owned by the adapter is playing.
+    pub fn next_delay(&mut self, base: Duration, active: bool) -> Duration {
+        let max = base * IDLE_BACKOFF_MULTIPLIER;
+        let next = match self.current {
+            Some(current) if !active && current >= base => (current * 2).min(max),
+            _ => base,
+        };
+        self.current = Some(next);
+        next
+    }
+
+    /// Reset to the base interval (e.g. after a control command)
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+/// Handle used to wake an adapter's poll loop immediately
+#[derive(Clone, Default)]
+pub struct PollWaker {
+    notify: Arc<Notify>,
+}
+
+impl PollWaker {
+    /// Request an immediate poll. Safe to call when the loop isn't waiting:
+    /// the wake-up is remembered until the loop next waits.
+    pub fn wake(&self) {
+        self.notify.notify_one();
+    }
+
+    /// Wait until [`PollWaker::wake`] is called
+    pub async fn woken(&self) {
+        self.notify.notified().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn test_active_polls_at_base_interval() {
+        let mut backoff = AdaptiveBackoff::default();
+        for _ in 0..5 {
+            assert_eq!(backoff.next_delay(BASE, true), BASE);
+        }
+    }
+
+    #[test]
+    fn test_idle_backs_off_exponentially_to_cap() {
+        let mut backoff = AdaptiveBackoff::default();
+        assert_eq!(backoff.next_delay(BASE, false), BASE);
+        assert_eq!(backoff.next_delay(BASE, false), BASE * 2);
+        assert_eq!(backoff.next_delay(BASE, false), BASE * 4);
+        assert_eq!(backoff.next_delay(BASE, false), BASE * 8);
+        assert_eq!(backoff.next_delay(BASE, false), BASE * 16);
+        assert_eq!(backoff.next_delay(BASE, false), BASE * 16);
+    }
+
+    #[test]
+    fn test_activity_and_reset_return_to_base() {
+        let mut backoff = AdaptiveBackoff::default();
+        backoff.next_delay(BASE, false);
+        backoff.next_delay(BASE, false);
+        assert_eq!(backoff.next_delay(BASE, true), BASE);
+
+        backoff.next_delay(BASE, false);
+        backoff.next_delay(BASE, false);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(BASE, false), BASE);
+    }
+
+    #[tokio::test]
+    async fn test_wake_before_wait_is_not_lost() {
+        let waker = PollWaker::default();
+        waker.wake();
+        tokio::time::timeout(Duration::from_millis(100), waker.woken())
+            .await
+            .expect("pending wake should complete immediately");
+    }
+}
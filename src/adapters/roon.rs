@@ -4,6 +4,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use roon_api::{
     image::{Args as ImageArgs, Format as ImageFormat, Image, Scale, Scaling},
     status::{self, Status},
@@ -11,7 +12,7 @@ use roon_api::{
     CoreEvent, Info, Parsed, RoonApi, Services, Svc,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -20,6 +21,7 @@ use tokio::sync::{oneshot, RwLock};
 use tokio_util::sync::CancellationToken;
 
 use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
 use crate::adapters::traits::{
     AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
 };
@@ -48,9 +50,20 @@ fn get_roon_state_path() -> PathBuf {
     get_config_file_path(ROON_STATE_FILE)
 }
 
+/// Connection changes kept for `/roon/status`
+const CONNECTION_HISTORY_LEN: usize = 50;
+/// Default reconnect backoff: first retry after 1s, doubling up to 60s
+const DEFAULT_RECONNECT_INITIAL: Duration = Duration::from_secs(1);
+const DEFAULT_RECONNECT_MAX: Duration = Duration::from_secs(60);
+
 /// Maximum relative volume step per call (prevents wild jumps)
 const MAX_RELATIVE_STEP: f32 = 10.0;
 
+/// Timeout for transport requests (control, volume, mute)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Timeout for image requests (the Core may have to fetch and scale the image)
+const IMAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Default volume range when output info unavailable
 const DEFAULT_VOLUME_MIN: f32 = 0.0;
 const DEFAULT_VOLUME_MAX: f32 = 100.0;
@@ -130,6 +143,9 @@ pub struct NowPlaying {
     pub artist: String,
     pub album: String,
     pub image_key: Option<String>,
+    /// Artist images (knob art mode); not part of the bus zone
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artist_image_keys: Vec<String>,
     pub seek_position: Option<i64>,
     pub length: Option<u32>,
 }
@@ -141,6 +157,34 @@ pub struct RoonStatus {
     pub core_name: Option<String>,
     pub core_version: Option<String>,
     pub zone_count: usize,
+    /// When the current connection was made
+    pub connected_since: Option<DateTime<Utc>>,
+    /// Connection drops since the bridge started
+    pub disconnect_count: u32,
+    /// Recent connects and drops, newest first
+    pub history: Vec<RoonConnectionEvent>,
+}
+
+/// Whether the Core connected or dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoonConnectionChange {
+    Connected,
+    Disconnected,
+}
+
+/// A connect or drop of the Core connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoonConnectionEvent {
+    pub at: DateTime<Utc>,
+    pub event: RoonConnectionChange,
+    pub core_name: Option<String>,
+    /// Why the connection dropped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// How long the dropped connection had lasted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uptime_secs: Option<i64>,
 }
 
 /// Internal state
@@ -154,6 +198,48 @@ struct RoonState {
     image: Option<Image>,
     /// Pending image requests: request_id -> (image_key, oneshot sender)
     pending_images: HashMap<usize, (String, ImageRequest)>,
+    connected_since: Option<DateTime<Utc>>,
+    disconnect_count: u32,
+    /// Connection changes, oldest first
+    history: VecDeque<RoonConnectionEvent>,
+}
+
+impl RoonState {
+    fn push_history(&mut self, event: RoonConnectionEvent) {
+        if self.history.len() == CONNECTION_HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(event);
+    }
+
+    /// Record a new connection to `core_name`
+    fn record_connected(&mut self, core_name: &str) {
+        let now = Utc::now();
+        self.connected_since = Some(now);
+        self.push_history(RoonConnectionEvent {
+            at: now,
+            event: RoonConnectionChange::Connected,
+            core_name: Some(core_name.to_string()),
+            reason: None,
+            uptime_secs: None,
+        });
+    }
+
+    /// Record a drop of the current connection; no-op when not connected
+    fn record_disconnected(&mut self, reason: &str) {
+        let Some(since) = self.connected_since.take() else {
+            return;
+        };
+        let now = Utc::now();
+        self.disconnect_count += 1;
+        self.push_history(RoonConnectionEvent {
+            at: now,
+            event: RoonConnectionChange::Disconnected,
+            core_name: self.core_name.clone(),
+            reason: Some(reason.to_string()),
+            uptime_secs: Some((now - since).num_seconds()),
+        });
+    }
 }
 
 /// Roon adapter
@@ -169,6 +255,8 @@ pub struct RoonAdapter {
     started: Arc<std::sync::atomic::AtomicBool>,
     /// Knob store for displaying controller count in Roon extension
     knob_store: Option<KnobStore>,
+    /// Backoff between reconnect attempts after the Core is lost
+    reconnect: RetryConfig,
 }
 
 impl RoonAdapter {
@@ -181,6 +269,7 @@ impl RoonAdapter {
             base_url: Arc::new(RwLock::new(None)),
             started: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             knob_store: None,
+            reconnect: RetryConfig::new(DEFAULT_RECONNECT_INITIAL, DEFAULT_RECONNECT_MAX),
         }
     }
 
@@ -196,9 +285,17 @@ impl RoonAdapter {
             base_url: Arc::new(RwLock::new(Some(base_url))),
             started: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             knob_store: Some(knob_store),
+            reconnect: RetryConfig::new(DEFAULT_RECONNECT_INITIAL, DEFAULT_RECONNECT_MAX),
         }
     }
 
+    /// Set the reconnect backoff: the first retry waits `initial`, then the
+    /// wait doubles up to `max`
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect = RetryConfig::new(initial, max.max(initial));
+        self
+    }
+
     /// Create and immediately start Roon adapter (legacy API for compatibility)
     pub async fn new(bus: SharedBus, base_url: String, knob_store: KnobStore) -> Result<Self> {
         let adapter = Self::new_configured(bus, base_url, knob_store);
@@ -233,7 +330,7 @@ impl RoonAdapter {
 
         // Create AdapterHandle and spawn run_with_retry
         let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
-        let config = RetryConfig::new(Duration::from_secs(1), Duration::from_secs(60));
+        let config = self.reconnect.clone();
 
         tokio::spawn(async move {
             if let Err(e) = handle.run_with_retry(config).await {
@@ -270,6 +367,9 @@ impl RoonAdapter {
             core_name: state.core_name.clone(),
             core_version: state.core_version.clone(),
             zone_count: state.zones.len(),
+            connected_since: state.connected_since,
+            disconnect_count: state.disconnect_count,
+            history: state.history.iter().rev().cloned().collect(),
         }
     }
 
@@ -306,10 +406,88 @@ impl RoonAdapter {
             _ => return Err(anyhow::anyhow!("Unknown action: {}", action)),
         };
 
-        transport.control(zone_id, &control).await;
+        let request = async {
+            tokio::time::timeout(REQUEST_TIMEOUT, transport.control(zone_id, &control))
+                .await
+                .map_err(|_| anyhow::anyhow!("Roon control request timed out"))
+        };
+        ops::timed("roon", action, Some(zone_id), request).await?;
         Ok(())
     }
 
+    /// Put every output of a zone into standby
+    ///
+    /// Only outputs whose source control supports standby react; the Core
+    /// ignores the rest, so the zone may keep playing on those.
+    pub async fn standby(&self, zone_id: &str) -> Result<()> {
+        // Clone transport and output ids while holding lock, then release before await
+        let (transport, output_ids) = {
+            let state = self.state.read().await;
+            let transport = state
+                .transport
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Not connected to Roon"))?;
+            let output_ids: Vec<String> = state
+                .zones
+                .get(zone_id)
+                .ok_or_else(|| anyhow::anyhow!("Zone not found: {}", zone_id))?
+                .outputs
+                .iter()
+                .map(|o| o.output_id.clone())
+                .collect();
+            (transport, output_ids)
+        };
+
+        for output_id in output_ids {
+            let request = async {
+                tokio::time::timeout(REQUEST_TIMEOUT, transport.standby(&output_id, None))
+                    .await
+                    .map_err(|_| anyhow::anyhow!("Roon standby request timed out"))
+            };
+            ops::timed("roon", "standby", Some(&output_id), request).await?;
+        }
+        Ok(())
+    }
+
+    /// Group the outputs of several zones into one zone
+    ///
+    /// Returns the first output's id, which addresses the grouped zone in
+    /// transport calls (the Core assigns the new zone its own id). Nothing is
+    /// sent when there is only one output.
+    pub async fn group_zones(&self, zone_ids: &[&str]) -> Result<String> {
+        let (transport, output_ids) = {
+            let state = self.state.read().await;
+            let transport = state
+                .transport
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Not connected to Roon"))?;
+            let mut output_ids: Vec<String> = Vec::new();
+            for zone_id in zone_ids {
+                let zone = state
+                    .zones
+                    .get(*zone_id)
+                    .ok_or_else(|| anyhow::anyhow!("Zone not found: {}", zone_id))?;
+                output_ids.extend(zone.outputs.iter().map(|o| o.output_id.clone()));
+            }
+            (transport, output_ids)
+        };
+        let leader = output_ids
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No outputs to group"))?;
+
+        if output_ids.len() > 1 {
+            let ids: Vec<&str> = output_ids.iter().map(String::as_str).collect();
+            let request = async {
+                tokio::time::timeout(REQUEST_TIMEOUT, transport.group_outputs(ids))
+                    .await
+                    .map_err(|_| anyhow::anyhow!("Roon group request timed out"))
+            };
+            ops::timed("roon", "group_outputs", Some(&leader), request).await?;
+        }
+        Ok(leader)
+    }
+
     /// Change volume
     ///
     /// SAFETY CRITICAL: For absolute volume, we must clamp to the output's actual
@@ -349,9 +527,15 @@ impl RoonAdapter {
         };
 
         // Roon transport API now takes f64 to support fractional dB steps
-        transport
-            .change_volume(output_id, &mode, final_value as f64)
-            .await;
+        let request = async {
+            tokio::time::timeout(
+                REQUEST_TIMEOUT,
+                transport.change_volume(output_id, &mode, final_value as f64),
+            )
+            .await
+            .map_err(|_| anyhow::anyhow!("Roon volume request timed out"))
+        };
+        ops::timed("roon", "change_volume", Some(output_id), request).await?;
         Ok(())
     }
 
@@ -383,7 +567,12 @@ impl RoonAdapter {
         } else {
             volume::Mute::Unmute
         };
-        transport.mute(output_id, &how).await;
+        let request = async {
+            tokio::time::timeout(REQUEST_TIMEOUT, transport.mute(output_id, &how))
+                .await
+                .map_err(|_| anyhow::anyhow!("Roon mute request timed out"))
+        };
+        ops::timed("roon", "mute", Some(output_id), request).await?;
         Ok(())
     }
 
@@ -393,6 +582,21 @@ impl RoonAdapter {
         image_key: &str,
         width: Option<u32>,
         height: Option<u32>,
+    ) -> Result<ImageData> {
+        ops::timed(
+            "roon",
+            "get_image",
+            None,
+            self.request_image(image_key, width, height),
+        )
+        .await
+    }
+
+    async fn request_image(
+        &self,
+        image_key: &str,
+        width: Option<u32>,
+        height: Option<u32>,
     ) -> Result<ImageData> {
         let (tx, rx) = oneshot::channel();
 
@@ -415,7 +619,9 @@ impl RoonAdapter {
 
         // Request the image (lock not held)
         let args = ImageArgs::new(scaling, Some(ImageFormat::Jpeg));
-        let req_id = image.get_image(image_key, args).await;
+        let req_id = tokio::time::timeout(REQUEST_TIMEOUT, image.get_image(image_key, args))
+            .await
+            .map_err(|_| anyhow::anyhow!("Image request timed out"))?;
 
         let req_id = match req_id {
             Some(id) => {
@@ -430,7 +636,7 @@ impl RoonAdapter {
         tracing::debug!("Requested image {} with req_id {}", image_key, req_id);
 
         // Wait for response with timeout
-        let result = tokio::time::timeout(std::time::Duration::from_secs(10), rx).await;
+        let result = tokio::time::timeout(IMAGE_TIMEOUT, rx).await;
 
         // Clean up pending request on timeout or cancellation
         if !matches!(result, Ok(Ok(Some(_)))) {
@@ -545,6 +751,7 @@ fn convert_zone(roon_zone: &RoonZone) -> Zone {
         artist: np.three_line.line2.clone(),
         album: np.three_line.line3.clone(),
         image_key: np.image_key.clone(),
+        artist_image_keys: np.artist_image_keys.clone().unwrap_or_default(),
         seek_position: np.seek_position,
         length: np.length,
     });
@@ -637,6 +844,16 @@ fn roon_zone_to_bus_zone(zone: &Zone) -> BusZone {
         metadata: None,
     });
 
+    let capabilities = crate::bus::ZoneCapabilities {
+        can_seek: true,
+        can_shuffle: true,
+        has_volume: volume_control.is_some(),
+        has_queue: true,
+        supports_grouping: true,
+        has_eq: false,
+        has_input: false,
+    };
+
     BusZone {
         zone_id: format!("roon:{}", zone.zone_id),
         zone_name: zone.display_name.clone(),
@@ -646,6 +863,8 @@ fn roon_zone_to_bus_zone(zone: &Zone) -> BusZone {
         source: "roon".to_string(),
         is_controllable: true,
         is_seekable: zone.now_playing.is_some(),
+        capabilities,
+        volume_leveling: None,
         last_updated: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -654,6 +873,7 @@ fn roon_zone_to_bus_zone(zone: &Zone) -> BusZone {
         is_pause_allowed: zone.is_pause_allowed,
         is_next_allowed: zone.is_next_allowed,
         is_previous_allowed: zone.is_previous_allowed,
+        metadata: None,
     }
 }
 
@@ -748,6 +968,10 @@ async fn run_roon_loop(
             let Some((event, msg)) = event_result else {
                 // Channel closed - exit gracefully to allow reconnection
                 tracing::info!("Roon event channel closed, exiting handler");
+                state_for_events
+                    .write()
+                    .await
+                    .record_disconnected("Event channel closed");
                 restart_needed_for_events.store(true, std::sync::atomic::Ordering::SeqCst);
                 break;
             };
@@ -795,6 +1019,7 @@ async fn run_roon_loop(
                         let mut s = state_for_events.write().await;
                         s.connected = true;
                         s.core_name = Some(core_name.clone());
+                        s.record_connected(&core_name);
                         s.core_version = Some(core_version.clone());
                         s.transport = transport;
                         s.image = image.clone();
@@ -829,6 +1054,7 @@ async fn run_roon_loop(
 
                     {
                         let mut s = state_for_events.write().await;
+                        s.record_disconnected("Core lost");
                         s.connected = false;
                         s.core_name = None;
                         s.core_version = None;
@@ -1068,6 +1294,12 @@ async fn run_roon_loop(
     // Clear state before returning
     {
         let mut s = state.write().await;
+        let reason = if shutdown.is_cancelled() {
+            "Adapter stopped"
+        } else {
+            "Roon tasks ended"
+        };
+        s.record_disconnected(reason);
         s.connected = false;
         s.transport = None;
         s.image = None;
@@ -1190,4 +1422,30 @@ mod tests {
             "should be None when output has no volume"
         );
     }
+
+    #[test]
+    fn connection_history_records_drops_once_and_is_bounded() {
+        let mut state = RoonState::default();
+        state.record_disconnected("Core lost");
+        assert!(state.history.is_empty(), "no drop without a connection");
+
+        state.core_name = Some("Nucleus".to_string());
+        state.record_connected("Nucleus");
+        state.record_disconnected("Core lost");
+        state.record_disconnected("Event channel closed");
+        assert_eq!(state.disconnect_count, 1);
+        assert_eq!(state.history.len(), 2);
+        let drop = state.history.back().unwrap();
+        assert_eq!(drop.event, RoonConnectionChange::Disconnected);
+        assert_eq!(drop.reason.as_deref(), Some("Core lost"));
+        assert_eq!(drop.core_name.as_deref(), Some("Nucleus"));
+        assert!(state.connected_since.is_none());
+
+        for _ in 0..CONNECTION_HISTORY_LEN {
+            state.record_connected("Nucleus");
+            state.record_disconnected("Core lost");
+        }
+        assert_eq!(state.history.len(), CONNECTION_HISTORY_LEN);
+        assert_eq!(state.disconnect_count, CONNECTION_HISTORY_LEN as u32 + 1);
+    }
 }
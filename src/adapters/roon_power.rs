@@ -0,0 +1,218 @@
+//! Power management for the Roon Core machine
+//!
+//! The Roon extension API has no service for powering the Core down: the
+//! `settings` service only exposes an extension's own settings, so standby
+//! and restart have to happen on the machine itself (or from a Nucleus'
+//! front panel). What the bridge can do is wake a sleeping Core with a
+//! Wake-on-LAN magic packet, sent to the Core's MAC address on the local
+//! broadcast address. The MAC and broadcast settings are kept in
+//! `roon-core-power.json`.
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::RwLock;
+
+use crate::adapters::ops;
+use crate::config::{get_config_file_path, read_config_file};
+
+const ROON_POWER_CONFIG_FILE: &str = "roon-core-power.json";
+const DEFAULT_BROADCAST: &str = "255.255.255.255";
+/// The discard port, which Wake-on-LAN senders conventionally use
+const DEFAULT_WOL_PORT: u16 = 9;
+
+fn default_broadcast() -> String {
+    DEFAULT_BROADCAST.to_string()
+}
+
+fn default_wol_port() -> u16 {
+    DEFAULT_WOL_PORT
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(ROON_POWER_CONFIG_FILE)
+}
+
+/// Wake-on-LAN settings for the Core machine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WakeOnLanConfig {
+    /// MAC address of the Core's network interface; unset disables waking
+    #[serde(default)]
+    pub mac: Option<String>,
+    /// Where the magic packet is sent, usually the subnet's broadcast address
+    #[serde(default = "default_broadcast")]
+    pub broadcast: String,
+    #[serde(default = "default_wol_port")]
+    pub port: u16,
+}
+
+impl Default for WakeOnLanConfig {
+    fn default() -> Self {
+        Self {
+            mac: None,
+            broadcast: default_broadcast(),
+            port: default_wol_port(),
+        }
+    }
+}
+
+/// MAC address from `aa:bb:cc:dd:ee:ff`, `aa-bb-cc-dd-ee-ff` or `aabbccddeeff`
+pub fn parse_mac(mac: &str) -> Result<[u8; 6]> {
+    let hex: String = mac
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Invalid MAC address: {}", mac);
+    }
+    let mut bytes = [0u8; 6];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)?;
+    }
+    Ok(bytes)
+}
+
+/// Six 0xFF bytes followed by the MAC repeated sixteen times
+pub fn magic_packet(mac: [u8; 6]) -> [u8; 102] {
+    let mut packet = [0xFFu8; 102];
+    for chunk in packet[6..].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac);
+    }
+    packet
+}
+
+/// Power actions for the Core. Only waking is possible: the Roon extension
+/// API has no service that puts the Core in standby or restarts it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorePowerAction {
+    Wake,
+}
+
+/// Service for waking the Roon Core machine
+pub struct RoonCorePowerService {
+    config: Arc<RwLock<WakeOnLanConfig>>,
+}
+
+impl Default for RoonCorePowerService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoonCorePowerService {
+    pub fn new() -> Self {
+        let service = Self {
+            config: Arc::new(RwLock::new(WakeOnLanConfig::default())),
+        };
+        service.load_config_sync();
+        service
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(ROON_POWER_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<WakeOnLanConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut config) = self.config.try_write() {
+                    *config = saved;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse Roon Core power config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let config = self.config.read().await.clone();
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&config) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save Roon Core power config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize Roon Core power config: {}", e),
+        }
+    }
+
+    /// Current Wake-on-LAN settings
+    pub async fn config(&self) -> WakeOnLanConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Replace the Wake-on-LAN settings
+    pub async fn set_config(&self, config: WakeOnLanConfig) -> Result<()> {
+        let mac = config
+            .mac
+            .as_deref()
+            .map(str::trim)
+            .filter(|mac| !mac.is_empty());
+        if let Some(mac) = mac {
+            parse_mac(mac)?;
+        }
+        if config.broadcast.trim().is_empty() {
+            bail!("broadcast is required");
+        }
+        *self.config.write().await = WakeOnLanConfig {
+            mac: mac.map(str::to_string),
+            broadcast: config.broadcast.trim().to_string(),
+            port: config.port,
+        };
+        self.save_config().await;
+        Ok(())
+    }
+
+    /// Send the magic packet to the configured MAC
+    pub async fn wake(&self) -> Result<()> {
+        let config = self.config().await;
+        let mac = config
+            .mac
+            .clone()
+            .ok_or_else(|| anyhow!("Wake-on-LAN is not configured: no Core MAC address"))?;
+        let packet = magic_packet(parse_mac(&mac)?);
+        ops::timed("roon", "wake", Some(&mac), async {
+            let socket = UdpSocket::bind("0.0.0.0:0").await?;
+            socket.set_broadcast(true)?;
+            socket
+                .send_to(&packet, (config.broadcast.as_str(), config.port))
+                .await?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .await?;
+        tracing::info!("Sent Wake-on-LAN packet for Roon Core {}", mac);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mac_in_common_notations() {
+        let expected = [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+        assert_eq!(parse_mac("00:1a:2b:3c:4d:5e").unwrap(), expected);
+        assert_eq!(parse_mac("00-1A-2B-3C-4D-5E").unwrap(), expected);
+        assert_eq!(parse_mac(" 001a2b3c4d5e ").unwrap(), expected);
+        assert!(parse_mac("00:1a:2b:3c:4d").is_err());
+        assert!(parse_mac("00:1a:2b:3c:4d:zz").is_err());
+    }
+
+    #[test]
+    fn magic_packet_repeats_mac_after_sync_stream() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        let packet = magic_packet(mac);
+        assert_eq!(&packet[..6], &[0xFF; 6]);
+        assert!(packet[6..].chunks(6).all(|chunk| chunk == mac));
+        assert_eq!(packet[6..].len(), 16 * 6);
+    }
+}
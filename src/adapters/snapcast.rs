@@ -0,0 +1,1040 @@
+//! Snapcast adapter - shows Snapcast clients and groups as zones
+//!
+//! Connects to snapserver's JSON-RPC WebSocket (`ws://host:1780/jsonrpc`),
+//! reads the full server status and keeps it current from the notifications
+//! the server pushes. Every connected client is a zone, and so is every group
+//! with more than one connected client (the group's volume is the average of
+//! its clients', as in Snapweb). Now playing and transport come from the
+//! stream the group plays. Commands go over HTTP JSON-RPC on the same port.
+//!
+//! Zone ids are `snapcast:client:<client id>` and `snapcast:group:<group id>`.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeScale,
+    Zone, ZoneCapabilities,
+};
+use crate::clock::unix_millis;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::knobs::image::sniff_content_type;
+
+const SNAPCAST_CONFIG_FILE: &str = "snapcast-config.json";
+/// snapserver's HTTP/WebSocket port
+const DEFAULT_PORT: u16 = 1780;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Request id of `Server.GetStatus` on the WebSocket
+const STATUS_REQUEST_ID: u64 = 1;
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedSnapcastConfig {
+    host: String,
+    port: u16,
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(SNAPCAST_CONFIG_FILE)
+}
+
+// =============================================================================
+// Server.GetStatus model
+// =============================================================================
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SnapServer {
+    #[serde(default)]
+    groups: Vec<SnapGroup>,
+    #[serde(default)]
+    streams: Vec<SnapStream>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SnapGroup {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    muted: bool,
+    #[serde(default)]
+    stream_id: String,
+    #[serde(default)]
+    clients: Vec<SnapClient>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SnapClient {
+    id: String,
+    #[serde(default)]
+    connected: bool,
+    #[serde(default)]
+    config: SnapClientConfig,
+    #[serde(default)]
+    host: SnapHost,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SnapClientConfig {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    volume: SnapVolume,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+struct SnapVolume {
+    #[serde(default)]
+    muted: bool,
+    #[serde(default)]
+    percent: u8,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SnapHost {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SnapStream {
+    id: String,
+    /// `playing`, `idle` or `unknown`
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    properties: StreamProperties,
+}
+
+/// Stream properties (snapserver 0.26+; older servers send none)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamProperties {
+    playback_status: Option<String>,
+    metadata: Option<StreamMetadata>,
+    #[serde(default)]
+    can_control: bool,
+    #[serde(default)]
+    can_play: bool,
+    #[serde(default)]
+    can_pause: bool,
+    #[serde(default)]
+    can_go_next: bool,
+    #[serde(default)]
+    can_go_previous: bool,
+    /// Seconds
+    position: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamMetadata {
+    title: Option<String>,
+    /// MPRIS style list of artists; some sources send a plain string
+    artist: Option<Value>,
+    album: Option<String>,
+    art_url: Option<String>,
+    /// Seconds
+    duration: Option<f64>,
+}
+
+impl SnapClient {
+    /// Name set in Snapweb, else the host name
+    fn name(&self) -> String {
+        if self.config.name.trim().is_empty() {
+            self.host.name.clone()
+        } else {
+            self.config.name.clone()
+        }
+    }
+}
+
+impl SnapGroup {
+    fn connected_clients(&self) -> impl Iterator<Item = &SnapClient> {
+        self.clients.iter().filter(|c| c.connected)
+    }
+
+    /// Name set in Snapweb, else the client names joined
+    fn name(&self) -> String {
+        if self.name.trim().is_empty() {
+            self.connected_clients()
+                .map(SnapClient::name)
+                .collect::<Vec<_>>()
+                .join(" + ")
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Average volume of the connected clients, as Snapweb shows it
+    fn volume(&self) -> f32 {
+        let volumes: Vec<f32> = self
+            .connected_clients()
+            .map(|c| c.config.volume.percent as f32)
+            .collect();
+        if volumes.is_empty() {
+            0.0
+        } else {
+            volumes.iter().sum::<f32>() / volumes.len() as f32
+        }
+    }
+}
+
+/// A Snapcast zone's raw id (after `snapcast:`)
+#[derive(Debug, Clone, PartialEq)]
+enum SnapTarget {
+    Client(String),
+    Group(String),
+}
+
+impl SnapTarget {
+    fn parse(raw_id: &str) -> Option<Self> {
+        match raw_id.split_once(':')? {
+            ("client", id) if !id.is_empty() => Some(Self::Client(id.to_string())),
+            ("group", id) if !id.is_empty() => Some(Self::Group(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// Adapter
+// =============================================================================
+
+/// Client summary for status responses
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapcastClientSummary {
+    pub id: String,
+    pub name: String,
+    /// Playback state of the client's stream
+    pub state: String,
+    pub group_id: String,
+}
+
+/// Snapcast adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapcastStatus {
+    pub connected: bool,
+    pub host: Option<String>,
+    pub port: u16,
+    pub clients: Vec<SnapcastClientSummary>,
+}
+
+struct SnapcastState {
+    host: Option<String>,
+    port: u16,
+    connected: bool,
+    running: bool,
+    server: SnapServer,
+    /// Zones as last published, to publish only what changed
+    zones: HashMap<String, Zone>,
+}
+
+/// Snapcast adapter for snapserver groups and clients
+#[derive(Clone)]
+pub struct SnapcastAdapter {
+    state: Arc<RwLock<SnapcastState>>,
+    bus: SharedBus,
+    http: Client,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+}
+
+impl SnapcastAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        let adapter = Self {
+            state: Arc::new(RwLock::new(SnapcastState {
+                host: None,
+                port: DEFAULT_PORT,
+                connected: false,
+                running: false,
+                server: SnapServer::default(),
+                zones: HashMap::new(),
+            })),
+            bus,
+            http: Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+        };
+        adapter.load_config_sync();
+        adapter
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(SNAPCAST_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedSnapcastConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut state) = self.state.try_write() {
+                    tracing::info!(
+                        "Loaded Snapcast config from disk: {}:{}",
+                        saved.host,
+                        saved.port
+                    );
+                    state.host = Some(saved.host);
+                    state.port = saved.port;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse Snapcast config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let saved = {
+            let state = self.state.read().await;
+            let Some(ref host) = state.host else {
+                return;
+            };
+            SavedSnapcastConfig {
+                host: host.clone(),
+                port: state.port,
+            }
+        };
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&saved) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save Snapcast config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize Snapcast config: {}", e),
+        }
+    }
+
+    /// Configure the snapserver connection
+    pub async fn configure(&self, host: String, port: Option<u16>) {
+        {
+            let mut state = self.state.write().await;
+            state.host = Some(host);
+            state.port = port.unwrap_or(DEFAULT_PORT);
+        }
+        self.save_config().await;
+    }
+
+    /// Check if configured
+    pub async fn is_configured(&self) -> bool {
+        self.state.read().await.host.is_some()
+    }
+
+    /// Get connection status
+    pub async fn get_status(&self) -> SnapcastStatus {
+        let state = self.state.read().await;
+        let mut clients: Vec<SnapcastClientSummary> = state
+            .server
+            .groups
+            .iter()
+            .flat_map(|group| {
+                let playback = stream_state(&state.server, &group.stream_id).to_string();
+                group
+                    .connected_clients()
+                    .map(move |c| SnapcastClientSummary {
+                        id: c.id.clone(),
+                        name: c.name(),
+                        state: playback.clone(),
+                        group_id: group.id.clone(),
+                    })
+            })
+            .collect();
+        clients.sort_by(|a, b| a.name.cmp(&b.name));
+        SnapcastStatus {
+            connected: state.connected,
+            host: state.host.clone(),
+            port: state.port,
+            clients,
+        }
+    }
+
+    /// Start the WebSocket connection (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            if state.host.is_none() {
+                bail!("Snapcast server not configured");
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("Snapcast adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+        self.state.write().await.running = false;
+        self.clear_zones().await;
+        tracing::info!("Snapcast adapter stopped");
+    }
+
+    async fn base_url(&self) -> Result<String> {
+        let state = self.state.read().await;
+        let host = state
+            .host
+            .as_ref()
+            .ok_or_else(|| anyhow!("Snapcast server not configured"))?;
+        Ok(format!("{}:{}", host, state.port))
+    }
+
+    /// Store a new server status and publish the zones that changed
+    async fn apply_status(&self, server: SnapServer) {
+        let zones = server_zones(&server);
+        let (changed, removed) = {
+            let mut state = self.state.write().await;
+            state.server = server;
+            let removed: Vec<String> = state
+                .zones
+                .keys()
+                .filter(|id| !zones.iter().any(|z| &z.zone_id == *id))
+                .cloned()
+                .collect();
+            for id in &removed {
+                state.zones.remove(id);
+            }
+            let mut changed = Vec::new();
+            for zone in zones {
+                if state.zones.get(&zone.zone_id) != Some(&zone) {
+                    state.zones.insert(zone.zone_id.clone(), zone.clone());
+                    changed.push(zone);
+                }
+            }
+            (changed, removed)
+        };
+
+        let now = unix_millis();
+        for mut zone in changed {
+            zone.last_updated = now;
+            self.bus.publish(BusEvent::ZoneDiscovered { zone });
+        }
+        for zone_id in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// Forget the server status and remove its zones
+    async fn clear_zones(&self) {
+        let removed: Vec<String> = {
+            let mut state = self.state.write().await;
+            state.connected = false;
+            state.server = SnapServer::default();
+            state.zones.drain().map(|(id, _)| id).collect()
+        };
+        for zone_id in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// Send a JSON-RPC request over HTTP
+    async fn rpc(&self, method: &str, target: &str, params: Value) -> Result<Value> {
+        ops::timed(
+            "snapcast",
+            method,
+            Some(target),
+            self.rpc_request(method, params),
+        )
+        .await
+    }
+
+    async fn rpc_request(&self, method: &str, params: Value) -> Result<Value> {
+        let url = format!("http://{}/jsonrpc", self.base_url().await?);
+        let body = json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        });
+        let response: Value = self
+            .http
+            .post(&url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            bail!(
+                "Snapcast {} failed: {}",
+                method,
+                error["message"].as_str().unwrap_or("unknown error")
+            );
+        }
+        Ok(response["result"].clone())
+    }
+
+    /// Stream played by a zone's group
+    async fn stream_id(&self, target: &SnapTarget) -> Result<String> {
+        let state = self.state.read().await;
+        find_group(&state.server, target)
+            .map(|g| g.stream_id.clone())
+            .ok_or_else(|| anyhow!("Snapcast zone not found"))
+    }
+
+    /// Play, pause, play_pause, stop, next or previous on the zone's stream
+    pub async fn control(&self, raw_id: &str, action: &str) -> Result<()> {
+        let target = SnapTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid Snapcast zone"))?;
+        let command = match action {
+            "play" => "play",
+            "pause" => "pause",
+            "play_pause" | "playpause" => "playPause",
+            "stop" => "stop",
+            "next" => "next",
+            "previous" | "prev" => "previous",
+            _ => bail!("Unknown action: {}", action),
+        };
+        let stream_id = self.stream_id(&target).await?;
+        self.rpc(
+            "Stream.Control",
+            raw_id,
+            json!({ "id": stream_id, "command": command }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Set a zone's volume (0-100); a group scales its clients' volumes
+    pub async fn set_volume(&self, raw_id: &str, percent: f32) -> Result<()> {
+        let target = SnapTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid Snapcast zone"))?;
+        let percent = percent.clamp(0.0, 100.0);
+        let updates: Vec<(String, SnapVolume)> = {
+            let state = self.state.read().await;
+            match &target {
+                SnapTarget::Client(id) => {
+                    let client = find_client(&state.server, id)
+                        .ok_or_else(|| anyhow!("Snapcast client not found"))?;
+                    vec![(
+                        client.id.clone(),
+                        SnapVolume {
+                            muted: client.config.volume.muted,
+                            percent: percent.round() as u8,
+                        },
+                    )]
+                }
+                SnapTarget::Group(_) => {
+                    let group = find_group(&state.server, &target)
+                        .ok_or_else(|| anyhow!("Snapcast group not found"))?;
+                    let clients: Vec<&SnapClient> = group.connected_clients().collect();
+                    let volumes: Vec<u8> =
+                        clients.iter().map(|c| c.config.volume.percent).collect();
+                    clients
+                        .iter()
+                        .zip(scale_group_volume(&volumes, percent))
+                        .map(|(c, percent)| {
+                            (
+                                c.id.clone(),
+                                SnapVolume {
+                                    muted: c.config.volume.muted,
+                                    percent,
+                                },
+                            )
+                        })
+                        .collect()
+                }
+            }
+        };
+        for (client_id, volume) in updates {
+            self.set_client_volume(&client_id, volume).await?;
+        }
+        Ok(())
+    }
+
+    /// Change a zone's volume by `delta` percentage points
+    pub async fn change_volume(&self, raw_id: &str, delta: f32) -> Result<()> {
+        let target = SnapTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid Snapcast zone"))?;
+        let current = {
+            let state = self.state.read().await;
+            match &target {
+                SnapTarget::Client(id) => {
+                    find_client(&state.server, id).map(|c| c.config.volume.percent as f32)
+                }
+                SnapTarget::Group(_) => find_group(&state.server, &target).map(SnapGroup::volume),
+            }
+        }
+        .ok_or_else(|| anyhow!("Snapcast zone not found"))?;
+        self.set_volume(raw_id, current + delta).await
+    }
+
+    /// Mute or unmute a client, or a whole group
+    pub async fn set_mute(&self, raw_id: &str, mute: bool) -> Result<()> {
+        match SnapTarget::parse(raw_id).ok_or_else(|| anyhow!("Invalid Snapcast zone"))? {
+            SnapTarget::Client(id) => {
+                let percent = {
+                    let state = self.state.read().await;
+                    find_client(&state.server, &id)
+                        .map(|c| c.config.volume.percent)
+                        .ok_or_else(|| anyhow!("Snapcast client not found"))?
+                };
+                self.set_client_volume(
+                    &id,
+                    SnapVolume {
+                        muted: mute,
+                        percent,
+                    },
+                )
+                .await
+            }
+            SnapTarget::Group(id) => {
+                self.rpc("Group.SetMute", raw_id, json!({ "id": id, "mute": mute }))
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Current mute state of a zone
+    pub async fn is_muted(&self, raw_id: &str) -> Option<bool> {
+        let target = SnapTarget::parse(raw_id)?;
+        let state = self.state.read().await;
+        match &target {
+            SnapTarget::Client(id) => find_client(&state.server, id).map(|c| c.config.volume.muted),
+            SnapTarget::Group(_) => find_group(&state.server, &target).map(|g| g.muted),
+        }
+    }
+
+    async fn set_client_volume(&self, client_id: &str, volume: SnapVolume) -> Result<()> {
+        self.rpc(
+            "Client.SetVolume",
+            client_id,
+            json!({
+                "id": client_id,
+                "volume": { "muted": volume.muted, "percent": volume.percent },
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Fetch stream artwork (`image_url` is a zone's `image_key`)
+    pub async fn get_image(&self, image_url: &str) -> Result<crate::bus::ImageData> {
+        let url = if image_url.starts_with("http://") || image_url.starts_with("https://") {
+            image_url.to_string()
+        } else if image_url.starts_with('/') {
+            format!("http://{}{}", self.base_url().await?, image_url)
+        } else {
+            bail!("Invalid image URL");
+        };
+        let response = self.http.get(&url).send().await?.error_for_status()?;
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        Ok(crate::bus::ImageData {
+            content_type: sniff_content_type(&body, declared.as_deref()),
+            data: body.to_vec(),
+        })
+    }
+}
+
+crate::impl_startable!(SnapcastAdapter, "snapcast", is_configured);
+
+#[async_trait]
+impl AdapterLogic for SnapcastAdapter {
+    fn prefix(&self) -> &'static str {
+        "snapcast"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        let url = format!("ws://{}/jsonrpc", self.base_url().await?);
+        let connect = tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(&url));
+        let (ws, _) = tokio::select! {
+            _ = ctx.shutdown.cancelled() => return Ok(()),
+            result = connect => result.map_err(|_| anyhow!("Timed out connecting to {}", url))??,
+        };
+        tracing::info!("Connected to Snapcast server at {}", url);
+        self.state.write().await.connected = true;
+
+        let (mut sink, mut stream) = ws.split();
+        sink.send(Message::text(status_request())).await?;
+        // A status request is outstanding; notifications meanwhile only mark it stale
+        let mut status_pending = true;
+        let mut stale = false;
+
+        let result = loop {
+            let message = tokio::select! {
+                _ = ctx.shutdown.cancelled() => break Ok(()),
+                message = stream.next() => message,
+            };
+            let text = match message {
+                None => break Err(anyhow!("Snapcast server closed the connection")),
+                Some(Err(e)) => break Err(e.into()),
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) => {
+                    break Err(anyhow!("Snapcast server closed the connection"))
+                }
+                Some(Ok(_)) => continue,
+            };
+            let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                tracing::debug!("Ignoring unparseable Snapcast message");
+                continue;
+            };
+
+            if value["id"].as_u64() == Some(STATUS_REQUEST_ID) {
+                status_pending = false;
+            }
+            match parse_message(&value) {
+                Incoming::Status(server) => self.apply_status(*server).await,
+                Incoming::Changed => stale = true,
+                Incoming::Error(message) => {
+                    tracing::warn!("Snapcast status request failed: {}", message);
+                }
+                Incoming::Other => {}
+            }
+
+            // Refresh once per burst of notifications (e.g. a volume drag)
+            if stale && !status_pending {
+                if let Err(e) = sink.send(Message::text(status_request())).await {
+                    break Err(e.into());
+                }
+                status_pending = true;
+                stale = false;
+            }
+        };
+
+        let _ = sink.close().await;
+        self.clear_zones().await;
+        result
+    }
+
+    async fn handle_command(
+        &self,
+        zone_id: &str,
+        command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        let raw_id = zone_id.strip_prefix("snapcast:").unwrap_or(zone_id);
+        let result = match command {
+            AdapterCommand::Play => self.control(raw_id, "play").await,
+            AdapterCommand::Pause => self.control(raw_id, "pause").await,
+            AdapterCommand::PlayPause => self.control(raw_id, "play_pause").await,
+            AdapterCommand::Stop => self.control(raw_id, "stop").await,
+            AdapterCommand::Next => self.control(raw_id, "next").await,
+            AdapterCommand::Previous => self.control(raw_id, "previous").await,
+            AdapterCommand::VolumeAbsolute(v) => self.set_volume(raw_id, v as f32).await,
+            AdapterCommand::VolumeRelative(delta) => self.change_volume(raw_id, delta as f32).await,
+            AdapterCommand::Mute(mute) => self.set_mute(raw_id, mute).await,
+        };
+
+        match result {
+            Ok(()) => Ok(AdapterCommandResponse {
+                success: true,
+                error: None,
+            }),
+            Err(e) => Ok(AdapterCommandResponse {
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+fn status_request() -> String {
+    json!({
+        "id": STATUS_REQUEST_ID,
+        "jsonrpc": "2.0",
+        "method": "Server.GetStatus",
+    })
+    .to_string()
+}
+
+/// What a WebSocket message means for the cached status
+enum Incoming {
+    /// A full server status (`Server.GetStatus` result or `Server.OnUpdate`)
+    Status(Box<SnapServer>),
+    /// A notification that changed part of the status
+    Changed,
+    /// A failed request
+    Error(String),
+    Other,
+}
+
+fn parse_message(value: &Value) -> Incoming {
+    let server = if let Some(method) = value["method"].as_str() {
+        if method != "Server.OnUpdate" {
+            return Incoming::Changed;
+        }
+        &value["params"]["server"]
+    } else if let Some(error) = value.get("error") {
+        return Incoming::Error(error["message"].as_str().unwrap_or_default().to_string());
+    } else {
+        &value["result"]["server"]
+    };
+    match serde_json::from_value(server.clone()) {
+        Ok(server) => Incoming::Status(Box::new(server)),
+        Err(_) => Incoming::Other,
+    }
+}
+
+fn find_client<'a>(server: &'a SnapServer, client_id: &str) -> Option<&'a SnapClient> {
+    server
+        .groups
+        .iter()
+        .flat_map(|g| g.clients.iter())
+        .find(|c| c.id == client_id)
+}
+
+/// A group zone's group, or the group a client zone belongs to
+fn find_group<'a>(server: &'a SnapServer, target: &SnapTarget) -> Option<&'a SnapGroup> {
+    server.groups.iter().find(|g| match target {
+        SnapTarget::Group(id) => &g.id == id,
+        SnapTarget::Client(id) => g.clients.iter().any(|c| &c.id == id),
+    })
+}
+
+fn find_stream<'a>(server: &'a SnapServer, stream_id: &str) -> Option<&'a SnapStream> {
+    server.streams.iter().find(|s| s.id == stream_id)
+}
+
+fn stream_state(server: &SnapServer, stream_id: &str) -> PlaybackState {
+    let Some(stream) = find_stream(server, stream_id) else {
+        return PlaybackState::Unknown;
+    };
+    match stream.properties.playback_status.as_deref() {
+        Some("playing") => PlaybackState::Playing,
+        Some("paused") => PlaybackState::Paused,
+        Some("stopped") => PlaybackState::Stopped,
+        _ => match stream.status.as_str() {
+            "playing" => PlaybackState::Playing,
+            "idle" => PlaybackState::Stopped,
+            _ => PlaybackState::Unknown,
+        },
+    }
+}
+
+fn stream_now_playing(stream: &SnapStream) -> Option<NowPlaying> {
+    let metadata = stream.properties.metadata.as_ref()?;
+    let artist = match &metadata.artist {
+        Some(Value::Array(artists)) => artists
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(Value::String(artist)) => artist.clone(),
+        _ => String::new(),
+    };
+    Some(NowPlaying {
+        title: metadata.title.clone().unwrap_or_default(),
+        artist,
+        album: metadata.album.clone().unwrap_or_default(),
+        image_key: metadata.art_url.clone(),
+        seek_position: stream.properties.position,
+        duration: metadata.duration,
+        metadata: None,
+    })
+}
+
+/// New client volumes that move a group's average to `target`, keeping their
+/// balance: turning down scales every client towards 0, turning up scales the
+/// headroom of every client towards 100 (Snapweb's group volume slider)
+fn scale_group_volume(volumes: &[u8], target: f32) -> Vec<u8> {
+    if volumes.is_empty() {
+        return Vec::new();
+    }
+    let current = volumes.iter().map(|&v| v as f32).sum::<f32>() / volumes.len() as f32;
+    let target = target.clamp(0.0, 100.0);
+    volumes
+        .iter()
+        .map(|&v| {
+            let v = v as f32;
+            let scaled = if target < current {
+                v * target / current
+            } else if current < 100.0 {
+                v + (100.0 - v) * (target - current) / (100.0 - current)
+            } else {
+                v
+            };
+            scaled.round().clamp(0.0, 100.0) as u8
+        })
+        .collect()
+}
+
+/// Zones for every connected client and every group of two or more, with
+/// `last_updated` left at 0 so unchanged zones compare equal
+fn server_zones(server: &SnapServer) -> Vec<Zone> {
+    let mut zones = Vec::new();
+    for group in &server.groups {
+        let stream = find_stream(server, &group.stream_id);
+        let make_zone = |zone_id: PrefixedZoneId, name: String, volume: f32, muted: bool| {
+            let props = stream.map(|s| &s.properties);
+            let controllable = props.is_some_and(|p| p.can_control);
+            Zone {
+                volume_control: Some(VolumeControl {
+                    value: volume,
+                    min: 0.0,
+                    max: 100.0,
+                    step: 1.0,
+                    is_muted: muted,
+                    scale: VolumeScale::Percentage,
+                    output_id: Some(zone_id.to_string()),
+                }),
+                zone_id: zone_id.to_string(),
+                zone_name: name,
+                state: stream_state(server, &group.stream_id),
+                now_playing: stream.and_then(stream_now_playing),
+                source: "snapcast".to_string(),
+                is_controllable: true,
+                is_seekable: false,
+                capabilities: ZoneCapabilities {
+                    has_volume: true,
+                    ..Default::default()
+                },
+                volume_leveling: None,
+                last_updated: 0,
+                is_play_allowed: controllable && props.is_some_and(|p| p.can_play),
+                is_pause_allowed: controllable && props.is_some_and(|p| p.can_pause),
+                is_next_allowed: controllable && props.is_some_and(|p| p.can_go_next),
+                is_previous_allowed: controllable && props.is_some_and(|p| p.can_go_previous),
+                metadata: None,
+            }
+        };
+
+        for client in group.connected_clients() {
+            zones.push(make_zone(
+                PrefixedZoneId::snapcast(format!("client:{}", client.id)),
+                client.name(),
+                client.config.volume.percent as f32,
+                client.config.volume.muted,
+            ));
+        }
+        if group.connected_clients().count() > 1 {
+            zones.push(make_zone(
+                PrefixedZoneId::snapcast(format!("group:{}", group.id)),
+                group.name(),
+                group.volume(),
+                group.muted,
+            ));
+        }
+    }
+    zones
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status() -> SnapServer {
+        serde_json::from_value(json!({
+            "groups": [{
+                "id": "g1",
+                "name": "",
+                "muted": false,
+                "stream_id": "Spotify",
+                "clients": [
+                    {"id": "00:11:22:33:44:55", "connected": true,
+                     "config": {"name": "Kitchen", "volume": {"muted": false, "percent": 40}},
+                     "host": {"name": "kitchen-pi"}},
+                    {"id": "aa:bb", "connected": true,
+                     "config": {"name": "", "volume": {"muted": true, "percent": 80}},
+                     "host": {"name": "patio-pi"}},
+                    {"id": "gone", "connected": false,
+                     "config": {"name": "Garage", "volume": {"muted": false, "percent": 10}},
+                     "host": {"name": "garage"}}
+                ]
+            }],
+            "streams": [{
+                "id": "Spotify",
+                "status": "playing",
+                "properties": {
+                    "playbackStatus": "playing",
+                    "canControl": true, "canPlay": true, "canPause": true,
+                    "canGoNext": true, "canGoPrevious": false,
+                    "metadata": {"title": "Song", "artist": ["A", "B"], "album": "LP",
+                                 "artUrl": "http://snap:1780/__image_cache?name=x.png", "duration": 200.5}
+                }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_clients_and_groups_become_zones() {
+        let zones = server_zones(&status());
+        let ids: Vec<&str> = zones.iter().map(|z| z.zone_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "snapcast:client:00:11:22:33:44:55",
+                "snapcast:client:aa:bb",
+                "snapcast:group:g1"
+            ]
+        );
+        assert_eq!(zones[1].zone_name, "patio-pi");
+        assert!(zones[1].volume_control.as_ref().unwrap().is_muted);
+
+        let group = &zones[2];
+        assert_eq!(group.zone_name, "Kitchen + patio-pi");
+        assert_eq!(group.volume_control.as_ref().unwrap().value, 60.0);
+        assert_eq!(group.state, PlaybackState::Playing);
+        let np = group.now_playing.as_ref().unwrap();
+        assert_eq!(np.artist, "A, B");
+        assert_eq!(np.duration, Some(200.5));
+        assert!(group.is_next_allowed);
+        assert!(!group.is_previous_allowed);
+    }
+
+    #[test]
+    fn test_group_volume_scaling_keeps_balance() {
+        assert_eq!(scale_group_volume(&[40, 80], 30.0), vec![20, 40]);
+        assert_eq!(scale_group_volume(&[40, 80], 80.0), vec![70, 90]);
+        assert_eq!(scale_group_volume(&[100, 100], 100.0), vec![100, 100]);
+        assert_eq!(scale_group_volume(&[0, 0], 50.0), vec![50, 50]);
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let result =
+            json!({"id": 1, "jsonrpc": "2.0", "result": {"server": {"groups": [], "streams": []}}});
+        assert!(matches!(parse_message(&result), Incoming::Status(_)));
+        let notification = json!({"jsonrpc": "2.0", "method": "Client.OnVolumeChanged",
+                                   "params": {"id": "aa:bb", "volume": {"muted": false, "percent": 3}}});
+        assert!(matches!(parse_message(&notification), Incoming::Changed));
+        assert_eq!(
+            SnapTarget::parse("client:00:11:22:33:44:55"),
+            Some(SnapTarget::Client("00:11:22:33:44:55".to_string()))
+        );
+        assert_eq!(SnapTarget::parse("stream:x"), None);
+    }
+}
@@ -0,0 +1,1133 @@
+//! Spotify adapter - shows Spotify Connect devices as zones
+//!
+//! Uses the Spotify Web API with the account's own OAuth authorization: the
+//! user registers an app at developer.spotify.com, enters its client id and
+//! secret, and authorizes the bridge once through `/spotify/authorize`. The
+//! refresh token from that authorization is saved with the app credentials
+//! and traded for short-lived access tokens as needed.
+//!
+//! The Web API has no push channel, so the device list and the playback state
+//! are polled. Each Connect device is a zone; the device Spotify is playing
+//! on carries the playback, the others are stopped. Playing on another
+//! device transfers playback to it. Playback control needs Spotify Premium.
+//!
+//! Zone ids are `spotify:<device id>`.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::traits::{
+    AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
+};
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, VolumeControl, VolumeScale,
+    Zone, ZoneCapabilities,
+};
+use crate::clock::unix_millis;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::knobs::image::sniff_content_type;
+
+const SPOTIFY_CONFIG_FILE: &str = "spotify-config.json";
+const API_URL: &str = "https://api.spotify.com/v1";
+const AUTHORIZE_URL: &str = "https://accounts.spotify.com/authorize";
+const TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+/// Where Spotify serves cover art; the only host images are fetched from
+const IMAGE_URL_PREFIX: &str = "https://i.scdn.co/";
+/// Reading devices and playback, and controlling playback
+const SCOPES: &str = "user-read-playback-state user-modify-playback-state";
+/// Redirect URI used unless another is configured (the bridge on its default port)
+pub const DEFAULT_REDIRECT_URI: &str = "http://127.0.0.1:8088/spotify/callback";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often devices and playback are polled
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Access tokens are refreshed this long before they expire
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+/// Saved config for persistence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SavedSpotifyConfig {
+    client_id: String,
+    client_secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    redirect_uri: Option<String>,
+}
+
+fn config_path() -> PathBuf {
+    get_config_file_path(SPOTIFY_CONFIG_FILE)
+}
+
+// =============================================================================
+// Web API model
+// =============================================================================
+
+/// A Connect device (`/me/player/devices`)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyDevice {
+    /// Missing for some restricted devices, which are skipped
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    is_restricted: bool,
+    volume_percent: Option<u8>,
+    #[serde(default = "default_true")]
+    supports_volume: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyImage {
+    url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyNamed {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyAlbum {
+    name: String,
+    #[serde(default)]
+    images: Vec<SpotifyImage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyShow {
+    name: String,
+    #[serde(default)]
+    publisher: String,
+    #[serde(default)]
+    images: Vec<SpotifyImage>,
+}
+
+/// A track or podcast episode
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyItem {
+    name: String,
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(default)]
+    artists: Vec<SpotifyNamed>,
+    album: Option<SpotifyAlbum>,
+    show: Option<SpotifyShow>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct Disallows {
+    #[serde(default)]
+    pausing: bool,
+    #[serde(default)]
+    resuming: bool,
+    #[serde(default)]
+    skipping_next: bool,
+    #[serde(default)]
+    skipping_prev: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct Actions {
+    #[serde(default)]
+    disallows: Disallows,
+}
+
+/// Current playback (`/me/player`)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct SpotifyPlayback {
+    device: Option<SpotifyDevice>,
+    #[serde(default)]
+    is_playing: bool,
+    progress_ms: Option<u64>,
+    item: Option<SpotifyItem>,
+    #[serde(default)]
+    actions: Actions,
+}
+
+impl SpotifyItem {
+    fn now_playing(&self, progress_ms: Option<u64>) -> NowPlaying {
+        // Episodes show their podcast and publisher where tracks show album and artists
+        let (artist, album, images) = match (&self.album, &self.show) {
+            (Some(album), _) => (
+                self.artists
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                album.name.clone(),
+                album.images.as_slice(),
+            ),
+            (None, Some(show)) => (
+                show.publisher.clone(),
+                show.name.clone(),
+                show.images.as_slice(),
+            ),
+            (None, None) => (String::new(), String::new(), &[][..]),
+        };
+        NowPlaying {
+            title: self.name.clone(),
+            artist,
+            album,
+            // Spotify lists the largest image first
+            image_key: images.first().map(|image| image.url.clone()),
+            seek_position: progress_ms.map(|ms| ms as f64 / 1000.0),
+            duration: Some(self.duration_ms as f64 / 1000.0).filter(|&secs| secs > 0.0),
+            metadata: None,
+        }
+    }
+}
+
+/// Zones for the Connect devices, with playback on the device that plays
+///
+/// `last_updated` is left at 0 so unchanged zones compare equal.
+fn build_zones(devices: &[SpotifyDevice], playback: Option<&SpotifyPlayback>) -> Vec<Zone> {
+    let playing_device = playback.and_then(|p| p.device.as_ref());
+    let mut all: Vec<&SpotifyDevice> = devices.iter().collect();
+    // The playing device can be missing from a device list fetched a moment earlier
+    if let Some(device) = playing_device {
+        if !devices.iter().any(|d| d.id == device.id) {
+            all.push(device);
+        }
+    }
+
+    all.into_iter()
+        .filter_map(|device| {
+            let id = device.id.as_deref()?;
+            let zone_id = PrefixedZoneId::spotify(id);
+            let playback = playback.filter(|_| playing_device.is_some_and(|d| d.id == device.id));
+            let state = match playback {
+                Some(p) if p.is_playing => PlaybackState::Playing,
+                Some(p) if p.item.is_some() => PlaybackState::Paused,
+                _ => PlaybackState::Stopped,
+            };
+            let disallows = playback.map(|p| p.actions.disallows.clone());
+            let controllable = !device.is_restricted;
+            let has_volume = device.supports_volume && device.volume_percent.is_some();
+            Some(Zone {
+                volume_control: device.volume_percent.filter(|_| has_volume).map(|value| {
+                    VolumeControl {
+                        value: value as f32,
+                        min: 0.0,
+                        max: 100.0,
+                        step: 1.0,
+                        is_muted: false,
+                        scale: VolumeScale::Percentage,
+                        output_id: Some(zone_id.to_string()),
+                    }
+                }),
+                zone_id: zone_id.to_string(),
+                zone_name: device.name.clone(),
+                state,
+                now_playing: playback
+                    .and_then(|p| p.item.as_ref().map(|item| item.now_playing(p.progress_ms))),
+                source: "spotify".to_string(),
+                is_controllable: controllable,
+                is_seekable: false,
+                capabilities: ZoneCapabilities {
+                    has_volume,
+                    ..Default::default()
+                },
+                volume_leveling: None,
+                last_updated: 0,
+                // Playing on an idle device transfers playback to it
+                is_play_allowed: controllable
+                    && state != PlaybackState::Playing
+                    && !disallows.as_ref().is_some_and(|d| d.resuming),
+                is_pause_allowed: controllable
+                    && state == PlaybackState::Playing
+                    && !disallows.as_ref().is_some_and(|d| d.pausing),
+                is_next_allowed: controllable
+                    && disallows.as_ref().is_some_and(|d| !d.skipping_next),
+                is_previous_allowed: controllable
+                    && disallows.as_ref().is_some_and(|d| !d.skipping_prev),
+                metadata: None,
+            })
+        })
+        .collect()
+}
+
+/// The zone as compared between publishes: position changes are published on their own
+fn without_position(zone: &Zone) -> Zone {
+    let mut zone = zone.clone();
+    if let Some(ref mut np) = zone.now_playing {
+        np.seek_position = None;
+    }
+    zone
+}
+
+/// URL that starts the authorization of the bridge for `client_id`
+fn authorize_url(client_id: &str, redirect_uri: &str, state: &str) -> String {
+    format!(
+        "{}?response_type=code&client_id={}&scope={}&redirect_uri={}&state={}",
+        AUTHORIZE_URL,
+        urlencoding::encode(client_id),
+        urlencoding::encode(SCOPES),
+        urlencoding::encode(redirect_uri),
+        urlencoding::encode(state)
+    )
+}
+
+// =============================================================================
+// Adapter
+// =============================================================================
+
+/// Spotify adapter status
+#[derive(Debug, Clone, Serialize)]
+pub struct SpotifyStatus {
+    pub connected: bool,
+    pub client_id: Option<String>,
+    pub has_client_secret: bool,
+    /// Whether the account has been authorized (a refresh token is saved)
+    pub authorized: bool,
+    /// Display name of the authorized account, once connected
+    pub account: Option<String>,
+    pub redirect_uri: String,
+    pub devices: Vec<SpotifyDeviceInfo>,
+}
+
+/// A Connect device in the status
+#[derive(Debug, Clone, Serialize)]
+pub struct SpotifyDeviceInfo {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+}
+
+struct AccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    refresh_token: Option<String>,
+}
+
+struct SpotifyState {
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    refresh_token: Option<String>,
+    redirect_uri: Option<String>,
+    access_token: Option<AccessToken>,
+    /// `state` of the authorization in progress, checked by the callback
+    pending_authorization: Option<String>,
+    account: Option<String>,
+    connected: bool,
+    running: bool,
+    /// Zones as last published, to publish only what changed
+    zones: HashMap<String, Zone>,
+}
+
+/// Spotify adapter for Connect devices
+#[derive(Clone)]
+pub struct SpotifyAdapter {
+    state: Arc<RwLock<SpotifyState>>,
+    bus: SharedBus,
+    http: Client,
+    /// Wrapped in RwLock to allow creating fresh token on restart
+    shutdown: Arc<RwLock<CancellationToken>>,
+}
+
+impl SpotifyAdapter {
+    pub fn new(bus: SharedBus) -> Self {
+        let adapter = Self {
+            state: Arc::new(RwLock::new(SpotifyState {
+                client_id: None,
+                client_secret: None,
+                refresh_token: None,
+                redirect_uri: None,
+                access_token: None,
+                pending_authorization: None,
+                account: None,
+                connected: false,
+                running: false,
+                zones: HashMap::new(),
+            })),
+            bus,
+            http: Client::builder()
+                .timeout(HTTP_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+        };
+        adapter.load_config_sync();
+        adapter
+    }
+
+    /// Load config from disk (sync, for startup)
+    fn load_config_sync(&self) {
+        let Some(content) = read_config_file(SPOTIFY_CONFIG_FILE) else {
+            return;
+        };
+        match serde_json::from_str::<SavedSpotifyConfig>(&content) {
+            Ok(saved) => {
+                if let Ok(mut state) = self.state.try_write() {
+                    tracing::info!(
+                        "Loaded Spotify config from disk ({})",
+                        if saved.refresh_token.is_some() {
+                            "authorized"
+                        } else {
+                            "not authorized"
+                        }
+                    );
+                    state.client_id = Some(saved.client_id).filter(|id| !id.is_empty());
+                    state.client_secret = Some(saved.client_secret).filter(|s| !s.is_empty());
+                    state.refresh_token = saved.refresh_token;
+                    state.redirect_uri = saved.redirect_uri;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to parse Spotify config: {}", e),
+        }
+    }
+
+    /// Save config to disk
+    async fn save_config(&self) {
+        let saved = {
+            let state = self.state.read().await;
+            SavedSpotifyConfig {
+                client_id: state.client_id.clone().unwrap_or_default(),
+                client_secret: state.client_secret.clone().unwrap_or_default(),
+                refresh_token: state.refresh_token.clone(),
+                redirect_uri: state.redirect_uri.clone(),
+            }
+        };
+        let path = config_path();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(&saved) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    tracing::error!("Failed to save Spotify config: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize Spotify config: {}", e),
+        }
+    }
+
+    /// Configure the Spotify app credentials and redirect URI
+    ///
+    /// A different client id drops the authorization, which belongs to the old app.
+    pub async fn configure(
+        &self,
+        client_id: String,
+        client_secret: Option<String>,
+        redirect_uri: Option<String>,
+    ) {
+        {
+            let mut state = self.state.write().await;
+            if state.client_id.as_deref() != Some(client_id.as_str()) {
+                state.refresh_token = None;
+                state.access_token = None;
+            }
+            state.client_id = Some(client_id).filter(|id| !id.is_empty());
+            state.client_secret = client_secret.filter(|s| !s.is_empty());
+            state.redirect_uri = redirect_uri.filter(|uri| !uri.is_empty());
+        }
+        self.save_config().await;
+    }
+
+    /// App credentials (client id, client secret) and the configured redirect URI
+    pub async fn credentials(&self) -> (Option<String>, Option<String>, Option<String>) {
+        let state = self.state.read().await;
+        (
+            state.client_id.clone(),
+            state.client_secret.clone(),
+            state.redirect_uri.clone(),
+        )
+    }
+
+    /// Check if configured and authorized
+    pub async fn is_configured(&self) -> bool {
+        let state = self.state.read().await;
+        state.client_id.is_some() && state.client_secret.is_some() && state.refresh_token.is_some()
+    }
+
+    /// Get connection status
+    pub async fn get_status(&self) -> SpotifyStatus {
+        let state = self.state.read().await;
+        let mut devices: Vec<SpotifyDeviceInfo> = state
+            .zones
+            .values()
+            .map(|zone| SpotifyDeviceInfo {
+                id: zone.zone_id.trim_start_matches("spotify:").to_string(),
+                name: zone.zone_name.clone(),
+                state: zone.state.to_string(),
+            })
+            .collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        SpotifyStatus {
+            connected: state.connected,
+            client_id: state.client_id.clone(),
+            has_client_secret: state.client_secret.is_some(),
+            authorized: state.refresh_token.is_some(),
+            account: state.account.clone(),
+            redirect_uri: state
+                .redirect_uri
+                .clone()
+                .unwrap_or_else(|| DEFAULT_REDIRECT_URI.to_string()),
+            devices,
+        }
+    }
+
+    /// Start an authorization: the Spotify URL to send the user to
+    pub async fn authorization_url(&self) -> Result<String> {
+        let mut state = self.state.write().await;
+        let client_id = state
+            .client_id
+            .clone()
+            .ok_or_else(|| anyhow!("Spotify client id not configured"))?;
+        let redirect_uri = state
+            .redirect_uri
+            .clone()
+            .unwrap_or_else(|| DEFAULT_REDIRECT_URI.to_string());
+        let nonce = hex::encode(rand::random::<[u8; 16]>());
+        state.pending_authorization = Some(nonce.clone());
+        Ok(authorize_url(&client_id, &redirect_uri, &nonce))
+    }
+
+    /// Finish an authorization with the code Spotify redirected back with
+    pub async fn complete_authorization(&self, code: &str, nonce: &str) -> Result<()> {
+        let (client_id, client_secret, redirect_uri) = {
+            let mut state = self.state.write().await;
+            if state.pending_authorization.as_deref() != Some(nonce) {
+                bail!("Unknown or expired authorization, start again from Settings");
+            }
+            state.pending_authorization = None;
+            (
+                state
+                    .client_id
+                    .clone()
+                    .ok_or_else(|| anyhow!("Spotify client id not configured"))?,
+                state.client_secret.clone(),
+                state
+                    .redirect_uri
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_REDIRECT_URI.to_string()),
+            )
+        };
+        let token = self
+            .token_request(
+                &client_id,
+                client_secret,
+                &[
+                    ("grant_type", "authorization_code"),
+                    ("code", code),
+                    ("redirect_uri", &redirect_uri),
+                ],
+            )
+            .await?;
+        let refresh_token = token
+            .refresh_token
+            .ok_or_else(|| anyhow!("Spotify returned no refresh token"))?;
+        {
+            let mut state = self.state.write().await;
+            state.refresh_token = Some(refresh_token);
+            state.access_token = Some(AccessToken {
+                token: token.access_token,
+                expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+            });
+        }
+        self.save_config().await;
+        tracing::info!("Spotify account authorized");
+        Ok(())
+    }
+
+    async fn token_request(
+        &self,
+        client_id: &str,
+        client_secret: Option<String>,
+        form: &[(&str, &str)],
+    ) -> Result<TokenResponse> {
+        let response = self
+            .http
+            .post(TOKEN_URL)
+            .basic_auth(client_id, client_secret)
+            .form(form)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body: Value = response.json().await.unwrap_or_default();
+            bail!(
+                "Spotify token request failed ({}): {}",
+                status,
+                body["error_description"]
+                    .as_str()
+                    .or(body["error"].as_str())
+                    .unwrap_or("unknown error")
+            );
+        }
+        Ok(response.json().await?)
+    }
+
+    /// A valid access token, refreshing it when it is about to expire
+    async fn access_token(&self) -> Result<String> {
+        let (client_id, client_secret, refresh_token) = {
+            let state = self.state.read().await;
+            if let Some(ref token) = state.access_token {
+                if token.expires_at > Instant::now() + TOKEN_MARGIN {
+                    return Ok(token.token.clone());
+                }
+            }
+            (
+                state.client_id.clone(),
+                state.client_secret.clone(),
+                state.refresh_token.clone(),
+            )
+        };
+        let (Some(client_id), Some(refresh_token)) = (client_id, refresh_token) else {
+            bail!("Spotify account not authorized");
+        };
+        let token = self
+            .token_request(
+                &client_id,
+                client_secret,
+                &[
+                    ("grant_type", "refresh_token"),
+                    ("refresh_token", &refresh_token),
+                ],
+            )
+            .await?;
+        let rotated = token.refresh_token.is_some();
+        {
+            let mut state = self.state.write().await;
+            if let Some(refresh_token) = token.refresh_token {
+                state.refresh_token = Some(refresh_token);
+            }
+            state.access_token = Some(AccessToken {
+                token: token.access_token.clone(),
+                expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+            });
+        }
+        if rotated {
+            self.save_config().await;
+        }
+        Ok(token.access_token)
+    }
+
+    /// Call the Web API; `None` for an empty (204) response
+    async fn api(&self, method: Method, path: &str, body: Option<Value>) -> Result<Option<Value>> {
+        let mut retried = false;
+        loop {
+            let token = self.access_token().await?;
+            let mut request = self
+                .http
+                .request(method.clone(), format!("{}{}", API_URL, path))
+                .bearer_auth(token);
+            request = match body {
+                Some(ref body) => request.json(body),
+                // Spotify rejects bodiless PUT/POST without a length
+                None if method != Method::GET => request.header(reqwest::header::CONTENT_LENGTH, 0),
+                None => request,
+            };
+            let response = request.send().await?;
+            let status = response.status();
+            if status == StatusCode::UNAUTHORIZED && !retried {
+                // Revoked or expired early: refresh and try once more
+                self.state.write().await.access_token = None;
+                retried = true;
+                continue;
+            }
+            if !status.is_success() {
+                let body: Value = response.json().await.unwrap_or_default();
+                bail!(
+                    "Spotify {} {} failed ({}): {}",
+                    method,
+                    path,
+                    status,
+                    body["error"]["message"].as_str().unwrap_or("unknown error")
+                );
+            }
+            let text = response.text().await?;
+            if text.trim().is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some(serde_json::from_str(&text)?));
+        }
+    }
+
+    /// Call the Web API on behalf of a zone, recording the operation
+    async fn command(
+        &self,
+        operation: &str,
+        device_id: &str,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<()> {
+        let target = PrefixedZoneId::spotify(device_id);
+        ops::timed(
+            "spotify",
+            operation,
+            Some(target.as_str()),
+            self.api(method, path, body),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Start the poll loop (internal - use Startable trait)
+    async fn start_internal(&self) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if state.running {
+                return Ok(());
+            }
+            if state.refresh_token.is_none() {
+                bail!("Spotify account not authorized");
+            }
+            state.running = true;
+        }
+
+        // Create fresh cancellation token for this run (previous token may be cancelled)
+        let shutdown = {
+            let mut token = self.shutdown.write().await;
+            *token = CancellationToken::new();
+            token.clone()
+        };
+
+        let handle = AdapterHandle::new(self.clone(), self.bus.clone(), shutdown);
+        tokio::spawn(async move {
+            let _ = handle.run_with_retry(RetryConfig::default()).await;
+        });
+
+        tracing::info!("Spotify adapter started");
+        Ok(())
+    }
+
+    async fn stop_internal(&self) {
+        self.shutdown.read().await.cancel();
+        self.state.write().await.running = false;
+        self.clear_zones().await;
+        tracing::info!("Spotify adapter stopped");
+    }
+
+    /// Read the devices and playback once and publish what changed
+    async fn poll(&self) -> Result<()> {
+        let devices = self
+            .api(Method::GET, "/me/player/devices", None)
+            .await?
+            .and_then(|v| serde_json::from_value::<Vec<SpotifyDevice>>(v["devices"].clone()).ok())
+            .unwrap_or_default();
+        let playback = self
+            .api(Method::GET, "/me/player?additional_types=episode", None)
+            .await?
+            .and_then(|v| serde_json::from_value::<SpotifyPlayback>(v).ok());
+        self.publish_zones(build_zones(&devices, playback.as_ref()))
+            .await;
+        Ok(())
+    }
+
+    /// Publish the zones that changed since the last publish
+    async fn publish_zones(&self, zones: Vec<Zone>) {
+        let mut events = Vec::new();
+        {
+            let mut state = self.state.write().await;
+            let removed: Vec<String> = state
+                .zones
+                .keys()
+                .filter(|id| !zones.iter().any(|z| &z.zone_id == *id))
+                .cloned()
+                .collect();
+            for id in removed {
+                state.zones.remove(&id);
+                if let Some(zone_id) = PrefixedZoneId::parse(&id) {
+                    events.push(BusEvent::ZoneRemoved { zone_id });
+                }
+            }
+            let now = unix_millis();
+            for zone in zones {
+                let previous = state.zones.get(&zone.zone_id);
+                let changed = previous.map(without_position) != Some(without_position(&zone));
+                let position = zone.now_playing.as_ref().and_then(|np| np.seek_position);
+                let moved = previous
+                    .and_then(|p| p.now_playing.as_ref())
+                    .and_then(|np| np.seek_position)
+                    != position;
+                if changed {
+                    let mut published = zone.clone();
+                    published.last_updated = now;
+                    events.push(BusEvent::ZoneDiscovered { zone: published });
+                } else if let (true, Some(position), Some(zone_id)) =
+                    (moved, position, PrefixedZoneId::parse(&zone.zone_id))
+                {
+                    events.push(BusEvent::SeekPositionChanged {
+                        zone_id,
+                        position: position as i64,
+                    });
+                }
+                state.zones.insert(zone.zone_id.clone(), zone);
+            }
+        }
+        for event in events {
+            self.bus.publish(event);
+        }
+    }
+
+    /// Forget the devices and remove their zones
+    async fn clear_zones(&self) {
+        let removed: Vec<String> = {
+            let mut state = self.state.write().await;
+            state.connected = false;
+            state.zones.drain().map(|(id, _)| id).collect()
+        };
+        for zone_id in removed {
+            if let Some(zone_id) = PrefixedZoneId::parse(&zone_id) {
+                self.bus.publish(BusEvent::ZoneRemoved { zone_id });
+            }
+        }
+    }
+
+    /// Zone of a device as last polled
+    async fn device_zone(&self, device_id: &str) -> Result<Zone> {
+        self.state
+            .read()
+            .await
+            .zones
+            .get(PrefixedZoneId::spotify(device_id).as_str())
+            .cloned()
+            .ok_or_else(|| anyhow!("Spotify device not found: {}", device_id))
+    }
+
+    /// Play, pause, play_pause, stop, next or previous on a device
+    ///
+    /// Playing on a device that isn't the active one transfers playback to it.
+    pub async fn control(&self, device_id: &str, action: &str) -> Result<()> {
+        let zone = self.device_zone(device_id).await?;
+        let device = urlencoding::encode(device_id);
+        let action = match action {
+            "play_pause" | "playpause" if zone.state == PlaybackState::Playing => "pause",
+            "play_pause" | "playpause" => "play",
+            // Spotify has no stop
+            "stop" => "pause",
+            other => other,
+        };
+        match action {
+            "play" if zone.now_playing.is_none() => {
+                self.command(
+                    "transfer",
+                    device_id,
+                    Method::PUT,
+                    "/me/player",
+                    Some(json!({ "device_ids": [device_id], "play": true })),
+                )
+                .await
+            }
+            "play" => {
+                self.command(
+                    action,
+                    device_id,
+                    Method::PUT,
+                    &format!("/me/player/play?device_id={}", device),
+                    None,
+                )
+                .await
+            }
+            "pause" => {
+                self.command(
+                    action,
+                    device_id,
+                    Method::PUT,
+                    &format!("/me/player/pause?device_id={}", device),
+                    None,
+                )
+                .await
+            }
+            "next" => {
+                self.command(
+                    action,
+                    device_id,
+                    Method::POST,
+                    &format!("/me/player/next?device_id={}", device),
+                    None,
+                )
+                .await
+            }
+            "previous" | "prev" => {
+                self.command(
+                    "previous",
+                    device_id,
+                    Method::POST,
+                    &format!("/me/player/previous?device_id={}", device),
+                    None,
+                )
+                .await
+            }
+            _ => bail!("Unknown action: {}", action),
+        }
+    }
+
+    /// Find an album in the Spotify catalog and play it on a device
+    pub async fn play_album(&self, device_id: &str, artist: &str, album: &str) -> Result<()> {
+        let mut query = format!("album:{}", album);
+        if !artist.is_empty() {
+            query.push_str(&format!(" artist:{}", artist));
+        }
+        let results = self
+            .api(
+                Method::GET,
+                &format!(
+                    "/search?type=album&limit=1&q={}",
+                    urlencoding::encode(&query)
+                ),
+                None,
+            )
+            .await?
+            .unwrap_or_default();
+        let uri = results["albums"]["items"][0]["uri"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Album not found on Spotify: {}", album))?
+            .to_string();
+        self.command(
+            "play_album",
+            device_id,
+            Method::PUT,
+            &format!(
+                "/me/player/play?device_id={}",
+                urlencoding::encode(device_id)
+            ),
+            Some(json!({ "context_uri": uri })),
+        )
+        .await
+    }
+
+    /// Set a device's volume (0-100)
+    pub async fn set_volume(&self, device_id: &str, level: f32) -> Result<()> {
+        let level = level.clamp(0.0, 100.0).round() as u8;
+        self.command(
+            "volume",
+            device_id,
+            Method::PUT,
+            &format!(
+                "/me/player/volume?volume_percent={}&device_id={}",
+                level,
+                urlencoding::encode(device_id)
+            ),
+            None,
+        )
+        .await
+    }
+
+    /// Change a device's volume by `delta` percentage points
+    pub async fn change_volume(&self, device_id: &str, delta: f32) -> Result<()> {
+        let zone = self.device_zone(device_id).await?;
+        let current = zone
+            .volume_control
+            .map(|vc| vc.value)
+            .ok_or_else(|| anyhow!("Spotify device has no volume control"))?;
+        self.set_volume(device_id, current + delta).await
+    }
+
+    /// Fetch cover art from Spotify's image CDN (`url` is the zone's `image_key`)
+    pub async fn get_image(&self, url: &str) -> Result<crate::bus::ImageData> {
+        if !url.starts_with(IMAGE_URL_PREFIX) {
+            bail!("Invalid image key");
+        }
+        let response = self.http.get(url).send().await?.error_for_status()?;
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.bytes().await?;
+        Ok(crate::bus::ImageData {
+            content_type: sniff_content_type(&body, declared.as_deref()),
+            data: body.to_vec(),
+        })
+    }
+}
+
+crate::impl_startable!(SpotifyAdapter, "spotify", is_configured);
+
+#[async_trait]
+impl AdapterLogic for SpotifyAdapter {
+    fn prefix(&self) -> &'static str {
+        "spotify"
+    }
+
+    async fn run(&self, ctx: AdapterContext) -> Result<()> {
+        let account = self.api(Method::GET, "/me", None).await?;
+        let account = account
+            .as_ref()
+            .and_then(|me| me["display_name"].as_str().or(me["id"].as_str()))
+            .map(str::to_string);
+        tracing::info!(
+            "Connected to Spotify as {}",
+            account.as_deref().unwrap_or("(unnamed account)")
+        );
+        {
+            let mut state = self.state.write().await;
+            state.account = account;
+            state.connected = true;
+        }
+
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let result = loop {
+            tokio::select! {
+                _ = ctx.shutdown.cancelled() => break Ok(()),
+                _ = interval.tick() => {}
+            }
+            if let Err(e) = self.poll().await {
+                break Err(e);
+            }
+        };
+
+        self.clear_zones().await;
+        result
+    }
+
+    async fn handle_command(
+        &self,
+        zone_id: &str,
+        command: AdapterCommand,
+    ) -> Result<AdapterCommandResponse> {
+        let device_id = zone_id.trim_start_matches("spotify:");
+        let result = match command {
+            AdapterCommand::Play => self.control(device_id, "play").await,
+            AdapterCommand::Pause => self.control(device_id, "pause").await,
+            AdapterCommand::PlayPause => self.control(device_id, "play_pause").await,
+            AdapterCommand::Stop => self.control(device_id, "stop").await,
+            AdapterCommand::Next => self.control(device_id, "next").await,
+            AdapterCommand::Previous => self.control(device_id, "previous").await,
+            AdapterCommand::VolumeAbsolute(v) => self.set_volume(device_id, v as f32).await,
+            AdapterCommand::VolumeRelative(delta) => {
+                self.change_volume(device_id, delta as f32).await
+            }
+            AdapterCommand::Mute(_) => Err(anyhow!("Spotify devices can't be muted")),
+        };
+
+        match result {
+            Ok(()) => Ok(AdapterCommandResponse {
+                success: true,
+                error: None,
+            }),
+            Err(e) => Ok(AdapterCommandResponse {
+                success: false,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn devices() -> Vec<SpotifyDevice> {
+        serde_json::from_value(json!([
+            { "id": "aa11", "name": "Kitchen Speaker", "type": "Speaker", "is_active": true,
+              "is_restricted": false, "volume_percent": 40, "supports_volume": true },
+            { "id": "bb22", "name": "Phone", "type": "Smartphone", "is_active": false,
+              "is_restricted": false, "volume_percent": 100, "supports_volume": false },
+            { "id": null, "name": "Restricted TV", "type": "TV", "is_active": false,
+              "is_restricted": true, "volume_percent": null }
+        ]))
+        .unwrap()
+    }
+
+    fn playback() -> SpotifyPlayback {
+        serde_json::from_value(json!({
+            "device": { "id": "aa11", "name": "Kitchen Speaker", "volume_percent": 40 },
+            "is_playing": true,
+            "progress_ms": 61500,
+            "item": {
+                "name": "So What", "duration_ms": 562000,
+                "artists": [{ "name": "Miles Davis" }],
+                "album": { "name": "Kind of Blue", "images": [
+                    { "url": "https://i.scdn.co/image/large", "width": 640 },
+                    { "url": "https://i.scdn.co/image/small", "width": 64 }
+                ]}
+            },
+            "actions": { "disallows": { "resuming": true, "skipping_prev": true } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_playing_device_carries_playback() {
+        let zones = build_zones(&devices(), Some(&playback()));
+        assert_eq!(zones.len(), 2);
+
+        let kitchen = &zones[0];
+        assert_eq!(kitchen.zone_id, "spotify:aa11");
+        assert_eq!(kitchen.zone_name, "Kitchen Speaker");
+        assert_eq!(kitchen.state, PlaybackState::Playing);
+        let np = kitchen.now_playing.as_ref().unwrap();
+        assert_eq!(np.title, "So What");
+        assert_eq!(np.artist, "Miles Davis");
+        assert_eq!(np.album, "Kind of Blue");
+        assert_eq!(
+            np.image_key.as_deref(),
+            Some("https://i.scdn.co/image/large")
+        );
+        assert_eq!(np.seek_position, Some(61.5));
+        assert_eq!(np.duration, Some(562.0));
+        assert_eq!(kitchen.volume_control.as_ref().unwrap().value, 40.0);
+        assert!(kitchen.is_pause_allowed && kitchen.is_next_allowed);
+        assert!(!kitchen.is_previous_allowed);
+
+        // Idle devices are stopped; playing on them transfers playback
+        let phone = &zones[1];
+        assert_eq!(phone.state, PlaybackState::Stopped);
+        assert!(phone.now_playing.is_none());
+        assert!(phone.volume_control.is_none());
+        assert!(phone.is_play_allowed && !phone.is_next_allowed);
+    }
+
+    #[test]
+    fn test_episode_and_missing_device() {
+        let mut playback = playback();
+        playback.device = Some(
+            serde_json::from_value(json!({ "id": "cc33", "name": "Car", "volume_percent": 70 }))
+                .unwrap(),
+        );
+        playback.is_playing = false;
+        playback.item = Some(
+            serde_json::from_value(json!({
+                "name": "Episode 12", "duration_ms": 1800000,
+                "show": { "name": "Jazz Talk", "publisher": "Radio", "images": [] }
+            }))
+            .unwrap(),
+        );
+        let zones = build_zones(&devices(), Some(&playback));
+        let car = zones.iter().find(|z| z.zone_id == "spotify:cc33").unwrap();
+        assert_eq!(car.state, PlaybackState::Paused);
+        let np = car.now_playing.as_ref().unwrap();
+        assert_eq!(
+            (np.artist.as_str(), np.album.as_str()),
+            ("Radio", "Jazz Talk")
+        );
+        assert_eq!(np.image_key, None);
+
+        // Nothing playing anywhere
+        let zones = build_zones(&devices(), None);
+        assert!(zones.iter().all(|z| z.state == PlaybackState::Stopped));
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        let url = authorize_url("abc", DEFAULT_REDIRECT_URI, "n0nce");
+        assert!(url.starts_with("https://accounts.spotify.com/authorize?response_type=code"));
+        assert!(url.contains("client_id=abc"));
+        assert!(url.contains("scope=user-read-playback-state%20user-modify-playback-state"));
+        assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A8088%2Fspotify%2Fcallback"));
+        assert!(url.ends_with("state=n0nce"));
+    }
+}
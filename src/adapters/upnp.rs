@@ -4,7 +4,10 @@
 //! Pure UPnP/DLNA has limited metadata support compared to OpenHome.
 //! Specifically, next/previous track are NOT supported by pure UPnP.
 
+use crate::adapters::discovery;
 use crate::adapters::handle::{AdapterHandle, RetryConfig};
+use crate::adapters::ops;
+use crate::adapters::polling::{AdaptiveBackoff, PollWaker};
 use crate::adapters::traits::{
     AdapterCommand, AdapterCommandResponse, AdapterContext, AdapterLogic,
 };
@@ -13,12 +16,10 @@ use crate::bus::{
 };
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::StreamExt;
 use quick_xml::de::from_str as xml_from_str;
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use ssdp_client::{SearchTarget, URN};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -30,9 +31,11 @@ const MEDIA_RENDERER_URN: &str = "urn:schemas-upnp-org:device:MediaRenderer:1";
 const AV_TRANSPORT_URN: &str = "urn:schemas-upnp-org:service:AVTransport:1";
 const RENDERING_CONTROL_URN: &str = "urn:schemas-upnp-org:service:RenderingControl:1";
 const SSDP_SEARCH_INTERVAL: Duration = Duration::from_secs(30);
-const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 const STALE_THRESHOLD: Duration = Duration::from_secs(90);
 const SOAP_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often transport state is checked while an announcement plays
+const ANNOUNCE_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 /// UPnP Media Renderer information
 #[derive(Debug, Clone, Serialize)]
@@ -111,6 +114,8 @@ pub struct VolumeControl {
 struct UPnPState {
     renderers: HashMap<String, UPnPRenderer>,
     running: bool,
+    /// Poll interval (configurable via app settings)
+    poll_interval: Duration,
 }
 
 /// UPnP adapter for discovering and controlling DLNA Media Renderers
@@ -121,6 +126,8 @@ pub struct UPnPAdapter {
     http: Client,
     /// Wrapped in RwLock to allow creating fresh token on restart
     shutdown: Arc<RwLock<CancellationToken>>,
+    /// Wakes the adaptive poll loop after control commands
+    poll_waker: PollWaker,
 }
 
 impl UPnPAdapter {
@@ -130,6 +137,7 @@ impl UPnPAdapter {
             state: Arc::new(RwLock::new(UPnPState {
                 renderers: HashMap::new(),
                 running: false,
+                poll_interval: DEFAULT_POLL_INTERVAL,
             })),
             bus,
             http: Client::builder()
@@ -137,9 +145,15 @@ impl UPnPAdapter {
                 .build()
                 .unwrap_or_default(),
             shutdown: Arc::new(RwLock::new(CancellationToken::new())),
+            poll_waker: PollWaker::default(),
         }
     }
 
+    /// Set the poll interval (takes effect on the next poll tick)
+    pub async fn set_poll_interval(&self, poll_interval: Duration) {
+        self.state.write().await.poll_interval = poll_interval;
+    }
+
     /// Start SSDP discovery (internal - use Startable trait)
     async fn start_internal(&self) -> anyhow::Result<()> {
         // Use write lock to atomically check and set running flag
@@ -206,24 +220,12 @@ impl UPnPAdapter {
         bus: &SharedBus,
         http: &Client,
     ) -> anyhow::Result<()> {
-        let urn: URN = MEDIA_RENDERER_URN.parse()?;
-        let search_target = SearchTarget::URN(urn);
         let responses =
-            ssdp_client::search(&search_target, Duration::from_secs(3), 2, None).await?;
+            discovery::ssdp_search(MEDIA_RENDERER_URN, Duration::from_secs(3), 2).await?;
 
-        futures::pin_mut!(responses);
-
-        while let Some(response) = responses.next().await {
-            let response = match response {
-                Ok(r) => r,
-                Err(e) => {
-                    tracing::debug!("SSDP response error: {}", e);
-                    continue;
-                }
-            };
-
-            let location = response.location().to_string();
-            let usn = response.usn();
+        for response in responses {
+            let location = response.location;
+            let usn = response.usn;
 
             // Extract UUID from USN
             let uuid = match usn.split("::").next() {
@@ -386,8 +388,11 @@ impl UPnPAdapter {
         bus: SharedBus,
         http: Client,
         shutdown: CancellationToken,
+        waker: PollWaker,
     ) {
-        let mut poll_interval = interval(POLL_INTERVAL);
+        let mut backoff = AdaptiveBackoff::default();
+        // First poll happens immediately
+        let mut delay = Duration::ZERO;
 
         loop {
             tokio::select! {
@@ -395,38 +400,48 @@ impl UPnPAdapter {
                     tracing::info!("UPnP poll loop shutting down");
                     break;
                 }
-                _ = poll_interval.tick() => {
-                    // Get list of renderers to poll
-                    let renderers: Vec<(String, Option<String>, Option<String>)> = {
-                        let s = state.read().await;
-                        s.renderers
-                            .iter()
-                            .map(|(uuid, r)| {
-                                (
-                                    uuid.clone(),
-                                    r.av_transport_url.clone(),
-                                    r.rendering_control_url.clone(),
-                                )
-                            })
-                            .collect()
-                    };
-
-                    for (uuid, av_url, rc_url) in renderers {
-                        if let Err(e) = Self::poll_renderer(
-                            &state,
-                            &bus,
-                            &http,
-                            &uuid,
-                            av_url.as_deref(),
-                            rc_url.as_deref(),
+                _ = waker.woken() => {
+                    backoff.reset();
+                }
+                _ = tokio::time::sleep(delay) => {}
+            }
+
+            // Get list of renderers to poll
+            let renderers: Vec<(String, Option<String>, Option<String>)> = {
+                let s = state.read().await;
+                s.renderers
+                    .iter()
+                    .map(|(uuid, r)| {
+                        (
+                            uuid.clone(),
+                            r.av_transport_url.clone(),
+                            r.rendering_control_url.clone(),
                         )
-                        .await
-                        {
-                            tracing::debug!("Failed to poll {}: {}", uuid, e);
-                        }
-                    }
+                    })
+                    .collect()
+            };
+
+            for (uuid, av_url, rc_url) in renderers {
+                if let Err(e) = Self::poll_renderer(
+                    &state,
+                    &bus,
+                    &http,
+                    &uuid,
+                    av_url.as_deref(),
+                    rc_url.as_deref(),
+                )
+                .await
+                {
+                    tracing::debug!("Failed to poll {}: {}", uuid, e);
                 }
             }
+
+            // Poll at the base interval while anything plays, back off while idle
+            delay = {
+                let s = state.read().await;
+                let any_playing = s.renderers.values().any(|r| r.state == "playing");
+                backoff.next_delay(s.poll_interval, any_playing)
+            };
         }
 
         tracing::info!("UPnP poll loop stopped");
@@ -553,15 +568,20 @@ impl UPnPAdapter {
             body = body_content
         );
 
-        let response = http
-            .post(url)
-            .header("Content-Type", "text/xml; charset=utf-8")
-            .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
-            .body(soap_body)
-            .send()
-            .await?;
-
-        Ok(response.text().await?)
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string));
+        let request = async {
+            let response = http
+                .post(url)
+                .header("Content-Type", "text/xml; charset=utf-8")
+                .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
+                .body(soap_body)
+                .send()
+                .await?;
+            response.text().await
+        };
+        Ok(ops::timed("upnp", action, host.as_deref(), request).await?)
     }
 
     /// Extract XML value, handling optional namespace prefixes (e.g., <u:Volume> or <Volume>)
@@ -680,6 +700,171 @@ impl UPnPAdapter {
         })
     }
 
+    /// AVTransport and RenderingControl URLs of a renderer
+    async fn service_urls(&self, uuid: &str) -> anyhow::Result<(String, Option<String>)> {
+        let state = self.state.read().await;
+        let renderer = state
+            .renderers
+            .get(uuid)
+            .ok_or_else(|| anyhow::anyhow!("Renderer not found: {}", uuid))?;
+        let av_url = renderer
+            .av_transport_url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No AVTransport URL"))?;
+        Ok((av_url, renderer.rendering_control_url.clone()))
+    }
+
+    /// SetAVTransportURI with an already XML-escaped URI and metadata
+    async fn set_transport_uri(&self, av_url: &str, uri: &str, metadata: &str) -> Result<()> {
+        Self::soap_call(
+            &self.http,
+            av_url,
+            AV_TRANSPORT_URN,
+            "SetAVTransportURI",
+            &format!(
+                "<InstanceID>0</InstanceID><CurrentURI>{}</CurrentURI><CurrentURIMetaData>{}</CurrentURIMetaData>",
+                uri, metadata
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn av_call(&self, av_url: &str, action: &str, args: &str) -> Result<String> {
+        Self::soap_call(
+            &self.http,
+            av_url,
+            AV_TRANSPORT_URN,
+            action,
+            &format!("<InstanceID>0</InstanceID>{}", args),
+        )
+        .await
+    }
+
+    async fn set_volume(&self, rc_url: &str, volume: i32) -> Result<()> {
+        Self::soap_call(
+            &self.http,
+            rc_url,
+            RENDERING_CONTROL_URN,
+            "SetVolume",
+            &format!(
+                "<InstanceID>0</InstanceID><Channel>Master</Channel><DesiredVolume>{}</DesiredVolume>",
+                volume.clamp(0, 100)
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Load a URL on a renderer and play it (SetAVTransportURI, then Play)
+    ///
+    /// Replaces whatever the renderer had loaded; used for announcement clips
+    /// served from `/media/announcements`.
+    pub async fn play_url(&self, uuid: &str, media_url: &str, title: &str) -> anyhow::Result<()> {
+        use quick_xml::escape::escape;
+
+        let (av_url, _) = self.service_urls(uuid).await?;
+
+        // Many renderers refuse a URI without DIDL-Lite metadata
+        let didl = format!(
+            r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/"><item id="0" parentID="-1" restricted="1"><dc:title>{}</dc:title><upnp:class>object.item.audioItem</upnp:class><res protocolInfo="http-get:*:*:*">{}</res></item></DIDL-Lite>"#,
+            escape(title),
+            escape(media_url)
+        );
+        self.set_transport_uri(&av_url, &escape(media_url), &escape(&didl))
+            .await?;
+        self.av_call(&av_url, "Play", "<Speed>1</Speed>").await?;
+        Ok(())
+    }
+
+    /// Play an announcement, then put back what the renderer was playing
+    ///
+    /// Saves the current URI, metadata, position and volume, plays `media_url`
+    /// (at `volume` if given) until the renderer stops or `max` passes, then
+    /// reloads the previous URI, seeks back and resumes if it was playing.
+    pub async fn announce(
+        &self,
+        uuid: &str,
+        media_url: &str,
+        volume: Option<i32>,
+        max: Duration,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let (av_url, rc_url) = self.service_urls(uuid).await?;
+
+        // Values stay XML-escaped as returned, ready to send back
+        let media_info = self.av_call(&av_url, "GetMediaInfo", "").await?;
+        let previous_uri = Self::extract_xml_value(&media_info, "CurrentURI").unwrap_or_default();
+        let previous_metadata =
+            Self::extract_xml_value(&media_info, "CurrentURIMetaData").unwrap_or_default();
+        let transport = self.av_call(&av_url, "GetTransportInfo", "").await?;
+        let was_playing = Self::extract_xml_value(&transport, "CurrentTransportState")
+            .is_some_and(|s| s == "PLAYING");
+        let position = self
+            .av_call(&av_url, "GetPositionInfo", "")
+            .await
+            .ok()
+            .and_then(|info| Self::extract_xml_value(&info, "RelTime"))
+            .filter(|t| t.contains(':') && t != "0:00:00" && t != "00:00:00");
+        let previous_volume = {
+            let state = self.state.read().await;
+            state.renderers.get(uuid).and_then(|r| r.volume)
+        };
+
+        if was_playing {
+            // Some renderers won't switch URI while playing
+            let _ = self.av_call(&av_url, "Stop", "").await;
+        }
+        if let (Some(rc_url), Some(volume)) = (rc_url.as_deref(), volume) {
+            self.set_volume(rc_url, volume).await?;
+        }
+        let played = self.play_url(uuid, media_url, "Announcement").await;
+
+        if played.is_ok() {
+            let deadline = tokio::time::Instant::now() + max;
+            let mut started = false;
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    _ = tokio::time::sleep(ANNOUNCE_POLL_INTERVAL) => {}
+                }
+                let Ok(info) = self.av_call(&av_url, "GetTransportInfo", "").await else {
+                    continue;
+                };
+                match Self::extract_xml_value(&info, "CurrentTransportState").as_deref() {
+                    Some("PLAYING") | Some("TRANSITIONING") => started = true,
+                    Some(_) if started => break,
+                    _ => {}
+                }
+            }
+        }
+
+        // Restore even if the announcement failed part-way
+        let _ = self.av_call(&av_url, "Stop", "").await;
+        if let (Some(rc_url), Some(_), Some(previous)) =
+            (rc_url.as_deref(), volume, previous_volume)
+        {
+            if let Err(e) = self.set_volume(rc_url, previous).await {
+                tracing::warn!("Failed to restore volume on {}: {}", uuid, e);
+            }
+        }
+        if !previous_uri.is_empty() {
+            self.set_transport_uri(&av_url, &previous_uri, &previous_metadata)
+                .await?;
+            if let Some(position) = position {
+                let target = format!("<Unit>REL_TIME</Unit><Target>{}</Target>", position);
+                if let Err(e) = self.av_call(&av_url, "Seek", &target).await {
+                    tracing::debug!("Renderer {} did not seek back: {}", uuid, e);
+                }
+            }
+            if was_playing {
+                self.av_call(&av_url, "Play", "<Speed>1</Speed>").await?;
+            }
+        }
+        played
+    }
+
     /// Send control command to a renderer
     pub async fn control(
         &self,
@@ -847,6 +1032,9 @@ impl UPnPAdapter {
             }
         }
 
+        // Reset adaptive backoff so the poll loop follows the state change closely
+        self.poll_waker.wake();
+
         // Trigger immediate poll
         let state = self.state.clone();
         let bus = self.bus.clone();
@@ -890,6 +1078,12 @@ fn upnp_renderer_to_zone(renderer: &UPnPRenderer) -> Zone {
         source: "upnp".to_string(),
         is_controllable: renderer.av_transport_url.is_some(),
         is_seekable: false, // Pure UPnP seek support is limited
+        // Plain renderers play a single URI; no queue, shuffle or grouping
+        capabilities: crate::bus::ZoneCapabilities {
+            has_volume: renderer.volume.is_some(),
+            ..Default::default()
+        },
+        volume_leveling: None,
         last_updated: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -898,6 +1092,7 @@ fn upnp_renderer_to_zone(renderer: &UPnPRenderer) -> Zone {
         is_pause_allowed: renderer.state == "playing",
         is_next_allowed: false,
         is_previous_allowed: false,
+        metadata: None,
     }
 }
 
@@ -937,7 +1132,7 @@ impl AdapterLogic for UPnPAdapter {
             _ = async {
                 tokio::join!(
                     Self::discovery_loop(discovery_state, discovery_bus, discovery_http, discovery_shutdown),
-                    Self::poll_loop(poll_state, poll_bus, poll_http, poll_shutdown)
+                    Self::poll_loop(poll_state, poll_bus, poll_http, poll_shutdown, self.poll_waker.clone())
                 );
             } => {}
         }
@@ -1,30 +1,61 @@
 //! ZoneAggregator - Single source of truth for zone state
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::time::Instant;
 use tracing::{debug, info};
 
-use crate::bus::{BusEvent, NowPlaying, SharedBus, Zone};
+use crate::bus::{BusEvent, NowPlaying, PlaybackState, PrefixedZoneId, SharedBus, Zone};
+use crate::zone_metadata::ZoneMetadataStore;
+
+/// How long an optimistic transport state is held without backend confirmation
+const OPTIMISTIC_STATE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Optimistic transport state awaiting backend confirmation
+struct PendingState {
+    expected: PlaybackState,
+    /// Latest state reported by the backend, restored on timeout
+    backend: PlaybackState,
+    deadline: Instant,
+}
 
 /// ZoneAggregator maintains unified zone state from all adapters.
 /// - Subscribes to bus events
 /// - Maintains HashMap of zones by zone_id
 /// - Flushes zones when adapter stops
 /// - Provides query interface for API layer
+/// - Merges user zone metadata (room, icon, order) into returned zones
+/// - Applies optimistic transport state for local commands (see [`Self::apply_optimistic`])
 pub struct ZoneAggregator {
     zones: Arc<RwLock<HashMap<String, Zone>>>,
     bus: SharedBus,
+    metadata: Arc<ZoneMetadataStore>,
+    pending: Mutex<HashMap<String, PendingState>>,
 }
 
 impl ZoneAggregator {
+    /// Create an aggregator with in-memory zone metadata
     pub fn new(bus: SharedBus) -> Self {
+        Self::with_metadata(bus, Arc::new(ZoneMetadataStore::default()))
+    }
+
+    /// Create an aggregator backed by the given metadata store
+    pub fn with_metadata(bus: SharedBus, metadata: Arc<ZoneMetadataStore>) -> Self {
         Self {
             zones: Arc::new(RwLock::new(HashMap::new())),
             bus,
+            metadata,
+            pending: Mutex::new(HashMap::new()),
         }
     }
 
+    /// User zone metadata store
+    pub fn metadata(&self) -> &ZoneMetadataStore {
+        &self.metadata
+    }
+
     /// Start the aggregator's event processing loop
     /// Should be spawned as a task
     pub async fn run(&self) {
@@ -32,10 +63,32 @@ impl ZoneAggregator {
 
         info!("ZoneAggregator started");
 
-        while let Ok(event) = rx.recv().await {
+        loop {
+            let deadline = self.next_pending_deadline();
+            let expired = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            // A new pending state always arrives as ZoneStatePending, which
+            // restarts the loop with its deadline
+            let event = tokio::select! {
+                result = rx.recv() => match result {
+                    Ok(event) => event,
+                    Err(_) => break,
+                },
+                _ = expired => {
+                    self.expire_pending().await;
+                    continue;
+                }
+            };
+
             match event {
-                BusEvent::ZoneDiscovered { zone } => {
+                BusEvent::ZoneDiscovered { mut zone } => {
                     debug!("Zone discovered: {}", zone.zone_id);
+                    zone.state = self.reconcile_pending(&zone.zone_id, zone.state);
                     self.zones.write().await.insert(zone.zone_id.clone(), zone);
                 }
 
@@ -45,14 +98,16 @@ impl ZoneAggregator {
                     state,
                 } => {
                     debug!("Zone updated: {}", zone_id);
+                    let state = self.reconcile_pending(zone_id.as_str(), state.as_str().into());
                     if let Some(zone) = self.zones.write().await.get_mut(zone_id.as_str()) {
                         zone.zone_name = display_name;
-                        zone.state = state.as_str().into();
+                        zone.state = state;
                     }
                 }
 
                 BusEvent::ZoneRemoved { zone_id } => {
                     debug!("Zone removed: {}", zone_id);
+                    self.clear_pending(zone_id.as_str());
                     self.zones.write().await.remove(zone_id.as_str());
                 }
 
@@ -139,6 +194,7 @@ impl ZoneAggregator {
 
                     for zone_id in &zone_ids {
                         zones.remove(zone_id);
+                        self.clear_pending(zone_id);
                     }
 
                     // Publish flush acknowledgment
@@ -164,24 +220,31 @@ impl ZoneAggregator {
 
     /// Get all zones
     pub async fn get_zones(&self) -> Vec<Zone> {
-        self.zones.read().await.values().cloned().collect()
+        let mut zones: Vec<Zone> = self.zones.read().await.values().cloned().collect();
+        zones.iter_mut().for_each(|z| self.metadata.apply(z));
+        zones
     }
 
     /// Get zones for a specific adapter
     pub async fn get_zones_by_adapter(&self, adapter: &str) -> Vec<Zone> {
         let prefix = format!("{}:", adapter);
-        self.zones
+        let mut zones: Vec<Zone> = self
+            .zones
             .read()
             .await
             .values()
             .filter(|z| z.zone_id.starts_with(&prefix))
             .cloned()
-            .collect()
+            .collect();
+        zones.iter_mut().for_each(|z| self.metadata.apply(z));
+        zones
     }
 
     /// Get a specific zone
     pub async fn get_zone(&self, zone_id: &str) -> Option<Zone> {
-        self.zones.read().await.get(zone_id).cloned()
+        let mut zone = self.zones.read().await.get(zone_id).cloned()?;
+        self.metadata.apply(&mut zone);
+        Some(zone)
     }
 
     /// Get now playing for a zone
@@ -197,4 +260,224 @@ impl ZoneAggregator {
     pub async fn zone_count(&self) -> usize {
         self.zones.read().await.len()
     }
+
+    // =========================================================================
+    // Optimistic transport state
+    // =========================================================================
+
+    /// Apply the state a transport action should lead to before the backend
+    /// reports it, so web UI and knob reads reflect the press immediately.
+    ///
+    /// Backend updates that disagree are held back until one matches; if none
+    /// does within [`OPTIMISTIC_STATE_TIMEOUT`], the last state the backend
+    /// reported is restored. Returns the predicted state, or `None` if the action
+    /// doesn't change transport state (next, previous, ...) or the zone is unknown.
+    pub async fn apply_optimistic(&self, zone_id: &str, action: &str) -> Option<PlaybackState> {
+        let predicted = {
+            let mut zones = self.zones.write().await;
+            let zone = zones.get_mut(zone_id)?;
+            let predicted = predict_state(zone.state, action)?;
+            let backend = zone.state;
+            zone.state = predicted;
+
+            if let Ok(mut pending) = self.pending.lock() {
+                // Keep the backend state from an earlier press that is still pending
+                let backend = pending
+                    .remove(zone_id)
+                    .map(|p| p.backend)
+                    .unwrap_or(backend);
+                pending.insert(
+                    zone_id.to_string(),
+                    PendingState {
+                        expected: predicted,
+                        backend,
+                        deadline: Instant::now() + OPTIMISTIC_STATE_TIMEOUT,
+                    },
+                );
+            }
+            predicted
+        };
+
+        if let Some(prefixed) = PrefixedZoneId::parse(zone_id) {
+            self.bus.publish(BusEvent::ZoneStatePending {
+                zone_id: prefixed,
+                state: predicted.to_string(),
+            });
+        }
+        Some(predicted)
+    }
+
+    /// Restore the backend state right away (the command failed to send)
+    pub async fn rollback_optimistic(&self, zone_id: &str) {
+        let Some(pending) = self.clear_pending(zone_id) else {
+            return;
+        };
+        self.restore(zone_id, pending.backend).await;
+    }
+
+    /// State to store for a backend report, resolving any pending optimistic state
+    fn reconcile_pending(&self, zone_id: &str, reported: PlaybackState) -> PlaybackState {
+        let Ok(mut pending) = self.pending.lock() else {
+            return reported;
+        };
+        let Some(entry) = pending.get_mut(zone_id) else {
+            return reported;
+        };
+        if entry.expected == reported {
+            debug!("Optimistic state confirmed: {} = {}", zone_id, reported);
+            pending.remove(zone_id);
+            reported
+        } else {
+            // Stale or intermediate report (e.g. loading); hold until timeout
+            entry.backend = reported;
+            entry.expected
+        }
+    }
+
+    fn clear_pending(&self, zone_id: &str) -> Option<PendingState> {
+        self.pending.lock().ok()?.remove(zone_id)
+    }
+
+    fn next_pending_deadline(&self) -> Option<Instant> {
+        self.pending.lock().ok()?.values().map(|p| p.deadline).min()
+    }
+
+    /// Roll back pending states the backend never confirmed
+    async fn expire_pending(&self) {
+        let now = Instant::now();
+        let expired: Vec<(String, PlaybackState)> = match self.pending.lock() {
+            Ok(mut pending) => {
+                let ids: Vec<String> = pending
+                    .iter()
+                    .filter(|(_, p)| p.deadline <= now)
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                ids.into_iter()
+                    .filter_map(|id| pending.remove(&id).map(|p| (id, p.backend)))
+                    .collect()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        for (zone_id, backend) in expired {
+            debug!("Optimistic state timed out: {} -> {}", zone_id, backend);
+            self.restore(&zone_id, backend).await;
+        }
+    }
+
+    /// Put the backend state back and tell consumers about the correction
+    async fn restore(&self, zone_id: &str, state: PlaybackState) {
+        let display_name = {
+            let mut zones = self.zones.write().await;
+            let Some(zone) = zones.get_mut(zone_id) else {
+                return;
+            };
+            if zone.state == state {
+                return;
+            }
+            zone.state = state;
+            zone.zone_name.clone()
+        };
+
+        if let Some(prefixed) = PrefixedZoneId::parse(zone_id) {
+            self.bus.publish(BusEvent::ZoneUpdated {
+                zone_id: prefixed,
+                display_name,
+                state: state.to_string(),
+            });
+        }
+    }
+}
+
+/// State a transport action leads to from `current`
+fn predict_state(current: PlaybackState, action: &str) -> Option<PlaybackState> {
+    match action {
+        "play" => Some(PlaybackState::Playing),
+        "pause" => Some(PlaybackState::Paused),
+        "stop" => Some(PlaybackState::Stopped),
+        "play_pause" | "playpause" => Some(if current == PlaybackState::Playing {
+            PlaybackState::Paused
+        } else {
+            PlaybackState::Playing
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::create_bus;
+
+    async fn aggregator_with_zone(state: PlaybackState) -> ZoneAggregator {
+        let aggregator = ZoneAggregator::new(create_bus());
+        aggregator.zones.write().await.insert(
+            "roon:1".to_string(),
+            Zone {
+                zone_id: "roon:1".to_string(),
+                zone_name: "Lounge".to_string(),
+                state,
+                volume_control: None,
+                now_playing: None,
+                source: "roon".to_string(),
+                is_controllable: true,
+                is_seekable: true,
+                capabilities: Default::default(),
+                volume_leveling: None,
+                last_updated: 0,
+                is_play_allowed: true,
+                is_pause_allowed: true,
+                is_next_allowed: true,
+                is_previous_allowed: true,
+                metadata: None,
+            },
+        );
+        aggregator
+    }
+
+    async fn zone_state(aggregator: &ZoneAggregator) -> PlaybackState {
+        aggregator.get_zone("roon:1").await.unwrap().state
+    }
+
+    #[tokio::test]
+    async fn test_optimistic_state_holds_until_confirmed() {
+        let aggregator = aggregator_with_zone(PlaybackState::Paused).await;
+
+        let predicted = aggregator.apply_optimistic("roon:1", "play_pause").await;
+        assert_eq!(predicted, Some(PlaybackState::Playing));
+        assert_eq!(zone_state(&aggregator).await, PlaybackState::Playing);
+
+        // A stale report is held back while pending
+        assert_eq!(
+            aggregator.reconcile_pending("roon:1", PlaybackState::Paused),
+            PlaybackState::Playing
+        );
+        // The matching report confirms and clears the pending state
+        assert_eq!(
+            aggregator.reconcile_pending("roon:1", PlaybackState::Playing),
+            PlaybackState::Playing
+        );
+        assert!(aggregator.next_pending_deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_optimistic_state_rolls_back_to_backend_state() {
+        let aggregator = aggregator_with_zone(PlaybackState::Playing).await;
+
+        aggregator.apply_optimistic("roon:1", "stop").await;
+        assert_eq!(zone_state(&aggregator).await, PlaybackState::Stopped);
+        aggregator.reconcile_pending("roon:1", PlaybackState::Loading);
+
+        aggregator.rollback_optimistic("roon:1").await;
+        assert_eq!(zone_state(&aggregator).await, PlaybackState::Loading);
+        assert!(aggregator.next_pending_deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_optimistic_ignores_non_transport_actions() {
+        let aggregator = aggregator_with_zone(PlaybackState::Playing).await;
+        assert_eq!(aggregator.apply_optimistic("roon:1", "next").await, None);
+        assert_eq!(aggregator.apply_optimistic("roon:2", "play").await, None);
+        assert!(aggregator.next_pending_deadline().is_none());
+    }
 }
@@ -0,0 +1,642 @@
+//! Consolidated adapter configuration (`/api/adapters/{name}/config`)
+//!
+//! One view per adapter combining connection settings, poll interval and
+//! discovery results, so the Settings page can configure every adapter the
+//! same way. Connection settings are persisted by the adapters themselves;
+//! poll intervals go to `app-settings.json`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{
+    apply_polling_settings, load_app_settings, save_app_settings, ApiError, AppState, ErrorCode,
+};
+use crate::config::PollingSettings;
+
+/// Query params for GET /api/adapters/{name}/config
+#[derive(Debug, Default, Deserialize)]
+pub struct AdapterConfigQuery {
+    /// Also run network discovery (LMS and HQPlayer; takes up to `timeout_ms`)
+    #[serde(default)]
+    pub discover: bool,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Body for PUT /api/adapters/{name}/config; omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct AdapterConfigUpdate {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    /// HQPlayer web UI port (profiles)
+    pub web_port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// HQPlayer web UI over HTTPS
+    pub web_https: Option<bool>,
+    /// HQPlayer web UI certificate pin; empty clears it
+    pub web_cert_sha256: Option<String>,
+    /// Spotify app client id
+    pub client_id: Option<String>,
+    /// Spotify app client secret
+    pub client_secret: Option<String>,
+    /// Spotify OAuth redirect URI; empty restores the default
+    pub redirect_uri: Option<String>,
+    /// Plexamp player hosts
+    pub hosts: Option<Vec<String>>,
+    /// Plex token for Plexamp players that need one; empty clears it
+    pub token: Option<String>,
+    pub poll_interval_secs: Option<u64>,
+}
+
+impl AdapterConfigUpdate {
+    fn has_connection_fields(&self) -> bool {
+        self.host.is_some()
+            || self.port.is_some()
+            || self.web_port.is_some()
+            || self.username.is_some()
+            || self.password.is_some()
+            || self.web_https.is_some()
+            || self.web_cert_sha256.is_some()
+            || self.client_id.is_some()
+            || self.client_secret.is_some()
+            || self.redirect_uri.is_some()
+            || self.hosts.is_some()
+            || self.token.is_some()
+    }
+}
+
+/// Adapters with a config view, including HQPlayer (not a zone source)
+const CONFIGURABLE_ADAPTERS: &[&str] = &[
+    "roon", "lms", "openhome", "upnp", "airplay", "snapcast", "heos", "kodi", "spotify", "http",
+    "plexamp", "hqplayer",
+];
+
+fn unknown_adapter(name: &str) -> ApiError {
+    ApiError::new(
+        ErrorCode::AdapterNotFound,
+        format!("Unknown adapter: {}", name),
+    )
+}
+
+/// Poll interval field in `PollingSettings` for an adapter
+fn poll_secs(polling: &mut PollingSettings, name: &str) -> Option<&mut u64> {
+    match name {
+        "lms" => Some(&mut polling.lms_secs),
+        "openhome" => Some(&mut polling.openhome_secs),
+        "upnp" => Some(&mut polling.upnp_secs),
+        "hqplayer" => Some(&mut polling.hqp_status_secs),
+        "http" => Some(&mut polling.http_secs),
+        "plexamp" => Some(&mut polling.plexamp_secs),
+        _ => None,
+    }
+}
+
+async fn config_view(state: &AppState, name: &str, query: &AdapterConfigQuery) -> Value {
+    let mut settings = load_app_settings();
+    let enabled = match name {
+        "roon" => settings.adapters.roon,
+        "lms" => settings.adapters.lms,
+        "openhome" => settings.adapters.openhome,
+        "upnp" => settings.adapters.upnp,
+        "airplay" => settings.adapters.airplay,
+        "snapcast" => settings.adapters.snapcast,
+        "heos" => settings.adapters.heos,
+        "kodi" => settings.adapters.kodi,
+        "spotify" => settings.adapters.spotify,
+        "http" => settings.adapters.http,
+        "plexamp" => settings.adapters.plexamp,
+        "hqplayer" => settings.adapters.hqplayer,
+        _ => false,
+    };
+    let poll_interval_secs =
+        poll_secs(&mut settings.polling, name).map(|secs| PollingSettings::clamp_secs(*secs));
+
+    let mut view = match name {
+        "roon" => {
+            let status = state.roon.get_status().await;
+            json!({
+                "connected": status.connected,
+                "core_name": status.core_name,
+                "core_version": status.core_version,
+                "zone_count": status.zone_count,
+            })
+        }
+        "lms" => {
+            let status = state.lms.get_status().await;
+            let (username, password) = state.lms.credentials().await;
+            let mut view = json!({
+                "connected": status.connected,
+                "host": status.host,
+                "port": status.port,
+                "username": username,
+                "has_password": password.is_some(),
+                "devices": status.players,
+            });
+            if query.discover {
+                view["discovered"] =
+                    match crate::adapters::discover_lms_servers(query.timeout_ms).await {
+                        Ok(servers) => json!(servers),
+                        Err(e) => {
+                            tracing::warn!("LMS discovery failed: {}", e);
+                            json!([])
+                        }
+                    };
+            }
+            view
+        }
+        "openhome" => {
+            let status = state.openhome.get_status().await;
+            json!({
+                "connected": status.connected,
+                "devices": status.devices,
+            })
+        }
+        "upnp" => {
+            let status = state.upnp.get_status().await;
+            json!({
+                "connected": status.connected,
+                "devices": status.renderers,
+            })
+        }
+        "airplay" => {
+            let status = state.airplay.get_status().await;
+            json!({
+                "connected": status.connected,
+                "devices": status.devices,
+            })
+        }
+        "snapcast" => {
+            let status = state.snapcast.get_status().await;
+            json!({
+                "connected": status.connected,
+                "host": status.host,
+                "port": status.port,
+                "devices": status.clients,
+            })
+        }
+        "heos" => {
+            let status = state.heos.get_status().await;
+            json!({
+                "connected": status.connected,
+                "host": status.host,
+                "port": status.port,
+                "devices": status.players,
+            })
+        }
+        "kodi" => {
+            let status = state.kodi.get_status().await;
+            let (username, password) = state.kodi.credentials().await;
+            let devices = if status.connected {
+                json!([{ "name": status.name.unwrap_or_else(|| "Kodi".to_string()), "state": status.state }])
+            } else {
+                json!([])
+            };
+            json!({
+                "connected": status.connected,
+                "host": status.host,
+                "port": status.port,
+                "ws_port": status.ws_port,
+                "username": username,
+                "has_password": password.is_some(),
+                "devices": devices,
+            })
+        }
+        "spotify" => {
+            let status = state.spotify.get_status().await;
+            json!({
+                "connected": status.connected,
+                "client_id": status.client_id,
+                "has_client_secret": status.has_client_secret,
+                "authorized": status.authorized,
+                "account": status.account,
+                "redirect_uri": status.redirect_uri,
+                "devices": status.devices,
+            })
+        }
+        "http" => {
+            let status = state.http.get_status().await;
+            json!({
+                "connected": status.connected,
+                "devices": status.devices,
+            })
+        }
+        "plexamp" => {
+            let status = state.plexamp.get_status().await;
+            json!({
+                "connected": status.connected,
+                "hosts": status.hosts,
+                "port": status.port,
+                "has_token": status.has_token,
+                "devices": status.players,
+            })
+        }
+        "hqplayer" => {
+            let status = state.hqplayer.get_status().await;
+            let mut view = json!({
+                "connected": status.connected,
+                "host": status.host,
+                "port": status.port,
+                "web_port": status.web_port,
+                "web_https": status.web_https,
+                "web_cert_sha256": state.hqplayer.web_tls().await.web_cert_sha256,
+                "has_password": state.hqplayer.has_web_credentials().await,
+            });
+            if query.discover {
+                view["discovered"] =
+                    match crate::adapters::hqplayer::discover_hqplayers(query.timeout_ms).await {
+                        Ok(instances) => json!(instances),
+                        Err(e) => {
+                            tracing::warn!("HQPlayer discovery failed: {}", e);
+                            json!([])
+                        }
+                    };
+            }
+            view
+        }
+        _ => json!({}),
+    };
+
+    view["name"] = json!(name);
+    view["enabled"] = json!(enabled);
+    view["poll_interval_secs"] = json!(poll_interval_secs);
+    view
+}
+
+/// GET /api/adapters/{name}/config - Connection settings, poll interval and discovery results
+pub async fn adapter_config_get_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<AdapterConfigQuery>,
+) -> Result<Json<Value>, ApiError> {
+    if !CONFIGURABLE_ADAPTERS.contains(&name.as_str()) {
+        return Err(unknown_adapter(&name));
+    }
+    Ok(Json(config_view(&state, &name, &query).await))
+}
+
+/// PUT /api/adapters/{name}/config - Update connection settings and/or poll interval
+pub async fn adapter_config_put_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(update): Json<AdapterConfigUpdate>,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    if !CONFIGURABLE_ADAPTERS.contains(&name.as_str()) {
+        return Err(unknown_adapter(&name));
+    }
+
+    if update.has_connection_fields() {
+        match name.as_str() {
+            "lms" => update_lms(&state, &update).await?,
+            "snapcast" => update_snapcast(&state, &update).await?,
+            "heos" => update_heos(&state, &update).await?,
+            "kodi" => update_kodi(&state, &update).await?,
+            "spotify" => update_spotify(&state, &update).await?,
+            "plexamp" => update_plexamp(&state, &update).await?,
+            "hqplayer" => update_hqplayer(&state, &update).await?,
+            _ => {
+                return Err(ApiError::new(
+                    ErrorCode::InvalidRequest,
+                    format!("{} has no connection settings", name),
+                ))
+            }
+        }
+    }
+
+    if let Some(secs) = update.poll_interval_secs {
+        let mut settings = load_app_settings();
+        let Some(field) = poll_secs(&mut settings.polling, &name) else {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("{} has no poll interval", name),
+            ));
+        };
+        *field = PollingSettings::clamp_secs(secs);
+        if !save_app_settings(&settings) {
+            return Err(ApiError::new(
+                ErrorCode::Internal,
+                "Failed to save settings",
+            ));
+        }
+        apply_polling_settings(&state, &settings.polling).await;
+    }
+
+    let view = config_view(&state, &name, &AdapterConfigQuery::default()).await;
+    Ok(Json(view).into_response())
+}
+
+/// Reconfigure LMS, keeping unspecified fields, and restart it if enabled
+async fn update_lms(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    let status = state.lms.get_status().await;
+    let Some(host) = update.host.clone().or(status.host) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "host is required").with_backend("lms")
+        );
+    };
+    let (username, password) = state.lms.credentials().await;
+    state
+        .lms
+        .configure(
+            host,
+            Some(update.port.unwrap_or(status.port)),
+            update.username.clone().or(username),
+            update.password.clone().or(password),
+        )
+        .await;
+
+    if !state.coordinator.is_enabled("lms").await {
+        return Ok(());
+    }
+    let Some(adapter) = state.startable_adapters.iter().find(|a| a.name() == "lms") else {
+        return Ok(());
+    };
+    state
+        .coordinator
+        .restart(adapter.as_ref())
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("lms"))
+}
+
+/// Reconfigure Snapcast (host and port only) and restart it if enabled
+async fn update_snapcast(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    if update.username.is_some()
+        || update.password.is_some()
+        || update.web_port.is_some()
+        || update.web_https.is_some()
+        || update.web_cert_sha256.is_some()
+    {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "snapcast only takes host and port",
+        )
+        .with_backend("snapcast"));
+    }
+    let status = state.snapcast.get_status().await;
+    let Some(host) = update.host.clone().or(status.host) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "host is required").with_backend("snapcast")
+        );
+    };
+    state
+        .snapcast
+        .configure(host, Some(update.port.unwrap_or(status.port)))
+        .await;
+
+    if !state.coordinator.is_enabled("snapcast").await {
+        return Ok(());
+    }
+    let Some(adapter) = state
+        .startable_adapters
+        .iter()
+        .find(|a| a.name() == "snapcast")
+    else {
+        return Ok(());
+    };
+    state
+        .coordinator
+        .restart(adapter.as_ref())
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("snapcast"))
+}
+
+/// Reconfigure HEOS (host and port only) and restart it if enabled
+async fn update_heos(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    if update.username.is_some()
+        || update.password.is_some()
+        || update.web_port.is_some()
+        || update.web_https.is_some()
+        || update.web_cert_sha256.is_some()
+    {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "heos only takes host and port")
+                .with_backend("heos"),
+        );
+    }
+    let status = state.heos.get_status().await;
+    let Some(host) = update.host.clone().or(status.host) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "host is required").with_backend("heos")
+        );
+    };
+    state
+        .heos
+        .configure(host, Some(update.port.unwrap_or(status.port)))
+        .await;
+
+    if !state.coordinator.is_enabled("heos").await {
+        return Ok(());
+    }
+    let Some(adapter) = state.startable_adapters.iter().find(|a| a.name() == "heos") else {
+        return Ok(());
+    };
+    state
+        .coordinator
+        .restart(adapter.as_ref())
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("heos"))
+}
+
+/// Reconfigure Kodi, keeping unspecified fields, and restart it if enabled
+async fn update_kodi(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    if update.web_port.is_some() || update.web_https.is_some() || update.web_cert_sha256.is_some() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "kodi only takes host, port, username and password",
+        )
+        .with_backend("kodi"));
+    }
+    let status = state.kodi.get_status().await;
+    let Some(host) = update.host.clone().or(status.host) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "host is required").with_backend("kodi")
+        );
+    };
+    let (username, password) = state.kodi.credentials().await;
+    state
+        .kodi
+        .configure(
+            host,
+            Some(update.port.unwrap_or(status.port)),
+            update.username.clone().or(username),
+            update.password.clone().or(password),
+        )
+        .await;
+
+    if !state.coordinator.is_enabled("kodi").await {
+        return Ok(());
+    }
+    let Some(adapter) = state.startable_adapters.iter().find(|a| a.name() == "kodi") else {
+        return Ok(());
+    };
+    state
+        .coordinator
+        .restart(adapter.as_ref())
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("kodi"))
+}
+
+/// Reconfigure the Spotify app, keeping unspecified fields, and restart it if enabled
+///
+/// The account itself is authorized through `/spotify/authorize`.
+async fn update_spotify(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    if update.host.is_some()
+        || update.port.is_some()
+        || update.web_port.is_some()
+        || update.username.is_some()
+        || update.password.is_some()
+        || update.web_https.is_some()
+        || update.web_cert_sha256.is_some()
+    {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "spotify only takes client_id, client_secret and redirect_uri",
+        )
+        .with_backend("spotify"));
+    }
+    let (client_id, client_secret, redirect_uri) = state.spotify.credentials().await;
+    let Some(client_id) = update.client_id.clone().or(client_id) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "client_id is required")
+                .with_backend("spotify"),
+        );
+    };
+    state
+        .spotify
+        .configure(
+            client_id,
+            update.client_secret.clone().or(client_secret),
+            update.redirect_uri.clone().or(redirect_uri),
+        )
+        .await;
+
+    restart_spotify(state).await
+}
+
+/// Restart Spotify if enabled, e.g. after its configuration or authorization changed
+pub(crate) async fn restart_spotify(state: &AppState) -> Result<(), ApiError> {
+    if !state.coordinator.is_enabled("spotify").await {
+        return Ok(());
+    }
+    let Some(adapter) = state
+        .startable_adapters
+        .iter()
+        .find(|a| a.name() == "spotify")
+    else {
+        return Ok(());
+    };
+    state
+        .coordinator
+        .restart(adapter.as_ref())
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("spotify"))
+}
+
+/// Reconfigure the Plexamp players, keeping unspecified fields, and restart it if enabled
+async fn update_plexamp(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    if update.host.is_some()
+        || update.web_port.is_some()
+        || update.username.is_some()
+        || update.password.is_some()
+        || update.web_https.is_some()
+        || update.web_cert_sha256.is_some()
+        || update.client_id.is_some()
+        || update.client_secret.is_some()
+        || update.redirect_uri.is_some()
+    {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "plexamp only takes hosts, port and token",
+        )
+        .with_backend("plexamp"));
+    }
+    let status = state.plexamp.get_status().await;
+    let hosts = update.hosts.clone().unwrap_or(status.hosts);
+    let has_hosts = !hosts.is_empty();
+    let token = update.token.clone().or(state.plexamp.token().await);
+    state
+        .plexamp
+        .configure(hosts, Some(update.port.unwrap_or(status.port)), token)
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("plexamp")
+        })?;
+
+    if !state.coordinator.is_enabled("plexamp").await {
+        return Ok(());
+    }
+    let Some(adapter) = state
+        .startable_adapters
+        .iter()
+        .find(|a| a.name() == "plexamp")
+    else {
+        return Ok(());
+    };
+    // With no hosts left the restart only stops it
+    let restarted = state.coordinator.restart(adapter.as_ref()).await;
+    if has_hosts {
+        restarted.map_err(|e| {
+            ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("plexamp")
+        })?;
+    }
+    Ok(())
+}
+
+/// Reconfigure HQPlayer, keeping unspecified fields
+async fn update_hqplayer(state: &AppState, update: &AdapterConfigUpdate) -> Result<(), ApiError> {
+    let status = state.hqplayer.get_status().await;
+    let Some(host) = update.host.clone().or(status.host) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "host is required").with_backend("hqplayer")
+        );
+    };
+    state
+        .hqplayer
+        .update_web_tls(update.web_https, update.web_cert_sha256.as_deref())
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("hqplayer")
+        })?;
+    // configure() keeps existing web credentials when these are None
+    state
+        .hqplayer
+        .configure(
+            host,
+            Some(update.port.unwrap_or(status.port)),
+            Some(update.web_port.unwrap_or(status.web_port)),
+            update.username.clone(),
+            update.password.clone(),
+        )
+        .await;
+    state.hqp_instances.save_to_config().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_interval_maps_to_settings_field() {
+        let mut polling = PollingSettings::default();
+        *poll_secs(&mut polling, "upnp").unwrap() = 7;
+        assert_eq!(polling.upnp_secs, 7);
+        assert!(poll_secs(&mut polling, "roon").is_none());
+    }
+
+    #[test]
+    fn update_without_connection_fields() {
+        let update: AdapterConfigUpdate =
+            serde_json::from_str(r#"{"poll_interval_secs": 5}"#).unwrap();
+        assert!(!update.has_connection_fields());
+        let update: AdapterConfigUpdate = serde_json::from_str(r#"{"port": 9001}"#).unwrap();
+        assert!(update.has_connection_fields());
+    }
+}
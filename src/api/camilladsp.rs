@@ -0,0 +1,200 @@
+//! CamillaDSP instances and zone links (`/camilladsp/...`)
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::adapters::camilladsp::{CamillaInstance, CamillaStatus};
+
+fn not_linked(zone_id: &str) -> ApiError {
+    ApiError::new(
+        ErrorCode::NotLinked,
+        format!("Zone {} not linked to CamillaDSP", zone_id),
+    )
+    .with_backend("camilladsp")
+}
+
+fn backend_error(e: anyhow::Error) -> ApiError {
+    ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("camilladsp")
+}
+
+/// GET /camilladsp/instances - List CamillaDSP instances
+pub async fn camilladsp_instances_handler(State(state): State<AppState>) -> Json<Value> {
+    Json(json!({ "instances": state.camilladsp.list_instances().await }))
+}
+
+/// POST /camilladsp/instances - Add an instance, or replace the one with the same name
+pub async fn camilladsp_instance_put_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(instance): Json<CamillaInstance>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    state
+        .camilladsp
+        .set_instance(instance.clone())
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("camilladsp")
+        })?;
+    Ok(Json(json!({ "ok": true, "instance": instance })))
+}
+
+/// DELETE /camilladsp/instances/{name} - Remove an instance and its zone links
+pub async fn camilladsp_instance_delete_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if !state.camilladsp.remove_instance(&name).await {
+        return Err(ApiError::new(
+            ErrorCode::InstanceNotFound,
+            format!("Unknown CamillaDSP instance: {}", name),
+        )
+        .with_backend("camilladsp"));
+    }
+    Ok(Json(json!({ "ok": true, "instance": name })))
+}
+
+/// GET /camilladsp/zones/links - Get all zone links
+pub async fn camilladsp_zone_links_handler(State(state): State<AppState>) -> Json<Value> {
+    Json(json!({ "links": state.camilladsp.get_links().await }))
+}
+
+/// Body for POST /camilladsp/zones/link
+#[derive(Debug, Deserialize)]
+pub struct CamillaLinkRequest {
+    pub zone_id: String,
+    pub instance: String,
+}
+
+/// POST /camilladsp/zones/link - Link a zone to a CamillaDSP instance
+pub async fn camilladsp_zone_link_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<CamillaLinkRequest>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if req.zone_id.is_empty() || req.instance.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "zone_id and instance are required",
+        ));
+    }
+    state
+        .camilladsp
+        .link_zone(req.zone_id.clone(), req.instance.clone())
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::InstanceNotFound, e.to_string()).with_backend("camilladsp")
+        })?;
+    Ok(Json(json!({
+        "ok": true,
+        "zone_id": req.zone_id,
+        "instance": req.instance,
+    })))
+}
+
+/// Body for POST /camilladsp/zones/unlink
+#[derive(Debug, Deserialize)]
+pub struct CamillaUnlinkRequest {
+    pub zone_id: String,
+}
+
+/// POST /camilladsp/zones/unlink - Unlink a zone from CamillaDSP
+pub async fn camilladsp_zone_unlink_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<CamillaUnlinkRequest>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    let was_linked = state.camilladsp.unlink_zone(&req.zone_id).await;
+    Ok(Json(json!({
+        "ok": true,
+        "zone_id": req.zone_id,
+        "was_linked": was_linked,
+    })))
+}
+
+/// GET /camilladsp/zones/{zone_id} - State, volume, mute and preset of the linked instance
+pub async fn camilladsp_zone_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+) -> Result<Json<CamillaStatus>, ApiError> {
+    let instance = state
+        .camilladsp
+        .instance_for_zone(&zone_id)
+        .await
+        .ok_or_else(|| not_linked(&zone_id))?;
+    let status = state
+        .camilladsp
+        .status(&instance)
+        .await
+        .map_err(backend_error)?;
+    Ok(Json(status))
+}
+
+/// Body for POST /camilladsp/zones/{zone_id}; omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct CamillaZoneUpdate {
+    /// Main volume in dB
+    pub volume: Option<f64>,
+    pub mute: Option<bool>,
+    /// Preset name
+    pub preset: Option<String>,
+}
+
+/// POST /camilladsp/zones/{zone_id} - Change the linked instance's preset, volume or mute
+///
+/// The preset is applied first, since loading a config can reset the volume.
+pub async fn camilladsp_zone_update_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    Json(update): Json<CamillaZoneUpdate>,
+) -> Result<Json<CamillaStatus>, ApiError> {
+    let instance = state
+        .camilladsp
+        .instance_for_zone(&zone_id)
+        .await
+        .ok_or_else(|| not_linked(&zone_id))?;
+    if let Some(ref preset) = update.preset {
+        if !instance.presets.iter().any(|p| &p.name == preset) {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("Unknown preset: {}", preset),
+            )
+            .with_backend("camilladsp"));
+        }
+        state
+            .camilladsp
+            .set_preset(&instance, preset)
+            .await
+            .map_err(backend_error)?;
+    }
+    if let Some(volume) = update.volume {
+        state
+            .camilladsp
+            .set_volume(&instance, volume)
+            .await
+            .map_err(backend_error)?;
+    }
+    if let Some(mute) = update.mute {
+        state
+            .camilladsp
+            .set_mute(&instance, mute)
+            .await
+            .map_err(backend_error)?;
+    }
+    let status = state
+        .camilladsp
+        .status(&instance)
+        .await
+        .map_err(backend_error)?;
+    Ok(Json(status))
+}
@@ -0,0 +1,205 @@
+//! Embeddable now-playing widget (`/embed/now_playing`)
+//!
+//! A single self-contained page meant for an iframe (Home Assistant dashboards,
+//! OBS browser sources). The first render is done here; after that a few lines
+//! of inline script follow `/events` and refetch `/now_playing` when the zone
+//! changes. A `token` query parameter is passed on to both, so the widget also
+//! works through the remote access listener.
+
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::bus::{PlaybackState, Zone};
+
+/// Artwork edge length in pixels
+const ART_SIZE: u32 = 160;
+
+#[derive(Debug, Deserialize)]
+pub struct EmbedQuery {
+    pub zone_id: String,
+    /// `dark` (default), `light` or `transparent`
+    pub theme: Option<String>,
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape_html(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Background and text colors for a theme name
+fn theme_colors(theme: Option<&str>) -> (&'static str, &'static str, &'static str) {
+    match theme {
+        Some("light") => ("#ffffff", "#111111", "#666666"),
+        Some("transparent") => ("transparent", "#ffffff", "#dddddd"),
+        _ => ("#16181d", "#f2f2f2", "#9a9ca3"),
+    }
+}
+
+fn render(zone: &Zone, theme: Option<&str>) -> String {
+    let (background, text, muted) = theme_colors(theme);
+    let np = zone.now_playing.as_ref();
+    let title = np
+        .map(|n| n.title.as_str())
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Idle");
+    let artist = np.map(|n| n.artist.as_str()).unwrap_or_default();
+    let album = np.map(|n| n.album.as_str()).unwrap_or_default();
+    let image_key = np.and_then(|n| n.image_key.as_deref()).unwrap_or_default();
+    let state = if zone.state == PlaybackState::Playing {
+        "playing"
+    } else {
+        "paused"
+    };
+    let zone_id = zone.zone_id.as_str();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{zone_name}</title>
+<style>
+html, body {{ margin: 0; height: 100%; background: {background}; color: {text}; font: 14px system-ui, sans-serif; overflow: hidden; }}
+.np {{ display: flex; align-items: center; gap: 12px; padding: 8px; height: 100%; box-sizing: border-box; }}
+.art {{ width: 25vh; height: 25vh; min-width: 64px; min-height: 64px; max-width: {size}px; max-height: {size}px; border-radius: 6px; object-fit: cover; flex: none; }}
+.meta {{ min-width: 0; }}
+.meta div {{ white-space: nowrap; overflow: hidden; text-overflow: ellipsis; }}
+.title {{ font-size: 1.2em; font-weight: 600; }}
+.sub, .zone {{ color: {muted}; }}
+.zone {{ font-size: 0.8em; margin-top: 4px; }}
+.zone::before {{ content: "❚❚ "; }}
+.playing .zone::before {{ content: "▶ "; }}
+</style>
+</head>
+<body>
+<div class="np {state}" id="np">
+<img class="art" id="art" alt="" src="{image_url}">
+<div class="meta">
+<div class="title" id="title">{title}</div>
+<div class="sub" id="artist">{artist}</div>
+<div class="sub" id="album">{album}</div>
+<div class="zone">{zone_name}</div>
+</div>
+</div>
+<script>
+(function () {{
+  var zoneId = {zone_id_json};
+  var token = new URLSearchParams(location.search).get("token");
+  var auth = token ? "&token=" + encodeURIComponent(token) : "";
+  var imageKey = {image_key_json};
+  function set(id, value) {{ document.getElementById(id).textContent = value || ""; }}
+  function refresh() {{
+    fetch("/now_playing?zone_id=" + encodeURIComponent(zoneId) + auth)
+      .then(function (r) {{ return r.ok ? r.json() : null; }})
+      .then(function (np) {{
+        if (!np) return;
+        set("title", np.line1);
+        set("artist", np.line2);
+        set("album", np.line3);
+        document.getElementById("np").className = "np " + (np.is_playing ? "playing" : "paused");
+        if ((np.image_key || "") !== imageKey) {{
+          imageKey = np.image_key || "";
+          document.getElementById("art").src = "/now_playing/image?zone_id=" + encodeURIComponent(zoneId)
+            + "&width={size}&height={size}&k=" + encodeURIComponent(imageKey) + auth;
+        }}
+      }})
+      .catch(function () {{}});
+  }}
+  var events = new EventSource("/events" + (token ? "?token=" + encodeURIComponent(token) : ""));
+  events.onmessage = function (e) {{
+    try {{
+      var event = JSON.parse(e.data);
+      var payload = event.payload || {{}};
+      var id = payload.zone_id || (payload.zone && payload.zone.zone_id);
+      if (id === zoneId && event.type !== "SeekPositionChanged") refresh();
+    }} catch (err) {{}}
+  }};
+  events.onopen = refresh;
+}})();
+</script>
+</body>
+</html>
+"#,
+        zone_name = escape_html(&zone.zone_name),
+        title = escape_html(title),
+        artist = escape_html(artist),
+        album = escape_html(album),
+        image_url = escape_html(&format!(
+            "/now_playing/image?zone_id={}&width={}&height={}&k={}",
+            urlencoding::encode(zone_id),
+            ART_SIZE,
+            ART_SIZE,
+            urlencoding::encode(image_key)
+        )),
+        zone_id_json = script_string(zone_id),
+        image_key_json = script_string(image_key),
+        size = ART_SIZE,
+    )
+}
+
+/// JSON string literal safe to place inside a `<script>` element
+fn script_string(value: &str) -> String {
+    serde_json::to_string(value)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace("</", "<\\/")
+}
+
+/// GET /embed/now_playing?zone_id= - Self-contained now-playing widget
+pub async fn embed_now_playing_handler(
+    State(state): State<AppState>,
+    Query(params): Query<EmbedQuery>,
+) -> Result<Response, ApiError> {
+    // Legacy zone_id without prefix (assume Roon), as in /now_playing
+    let zone_id = if params.zone_id.contains(':') {
+        params.zone_id.clone()
+    } else {
+        format!("roon:{}", params.zone_id)
+    };
+    let zone = state.aggregator.get_zone(&zone_id).await.ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        )
+    })?;
+
+    Ok((
+        [(header::CACHE_CONTROL, "no-cache")],
+        Html(render(&zone, params.theme.as_deref())),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<b>"AC/DC" & 'Co'</b>"#),
+            "&lt;b&gt;&quot;AC/DC&quot; &amp; &#39;Co&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_script_string_cannot_close_script() {
+        assert_eq!(script_string("a</script>"), r#""a<\/script>""#);
+    }
+}
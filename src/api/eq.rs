@@ -0,0 +1,131 @@
+//! Per-zone EQ (`/zones/{zone_id}/eq`)
+//!
+//! A thin layer over whatever tone or DSP control the zone's backend has:
+//! bass/treble on LMS players with tone controls, and matrix profile
+//! (convolution filter) selection on HQPlayer zones and zones linked to an
+//! HQPlayer pipeline. Other zones report `supported: false`.
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+
+/// EQ update; fields left unset are not changed
+#[derive(Debug, Deserialize)]
+pub struct EqRequest {
+    pub bass: Option<i32>,
+    pub treble: Option<i32>,
+    /// HQPlayer matrix profile index
+    pub matrix_profile: Option<u32>,
+}
+
+async fn eq_state(state: &AppState, zone_id: &str) -> Result<serde_json::Value, ApiError> {
+    if state.aggregator.get_zone(zone_id).await.is_none() {
+        return Err(ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        ));
+    }
+
+    let tone = match zone_id.split_once(':') {
+        Some(("lms", player_id)) => {
+            let tone = state.lms.get_tone(player_id).await;
+            tone.map_err(|e| {
+                ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
+            })?
+        }
+        _ => None,
+    };
+    let matrix = match state.hqp_for_zone(zone_id).await {
+        // Don't wait on request timeouts for an HQPlayer that isn't there
+        Some(hqp) if hqp.get_status().await.connected => {
+            let profiles = hqp.get_matrix_profiles().await;
+            let current = hqp.get_matrix_profile().await;
+            match (profiles, current) {
+                (Ok(profiles), Ok(current)) if !profiles.is_empty() => Some(serde_json::json!({
+                    "profiles": profiles,
+                    "current": current,
+                })),
+                (Ok(_), Ok(_)) => None,
+                (Err(e), _) | (_, Err(e)) => {
+                    return Err(ApiError::from_backend(ErrorCode::BackendError, &e)
+                        .with_backend("hqplayer"))
+                }
+            }
+        }
+        _ => None,
+    };
+
+    Ok(serde_json::json!({
+        "zone_id": zone_id,
+        "supported": tone.is_some() || matrix.is_some(),
+        "tone": tone,
+        "matrix": matrix,
+    }))
+}
+
+/// GET /zones/{zone_id}/eq - EQ settings the zone's backend supports
+///
+/// `tone` is `{bass, treble, min, max}` for LMS players with tone controls;
+/// `matrix` is `{profiles, current}` when an HQPlayer with matrix profiles
+/// drives the zone. Both are null when the backend has no EQ.
+pub async fn zone_eq_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    eq_state(&state, &zone_id).await.map(Json)
+}
+
+/// PUT /zones/{zone_id}/eq - Change bass/treble or the matrix profile
+///
+/// Returns the resulting EQ state, as for GET. Locked zones reject it like
+/// other control commands.
+pub async fn zone_eq_put_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<EqRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if state.aggregator.get_zone(&zone_id).await.is_none() {
+        return Err(ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        ));
+    }
+    state.check_zone_lock(&zone_id, Caller::from_headers(&state, &headers))?;
+
+    if req.bass.is_some() || req.treble.is_some() {
+        let Some(("lms", player_id)) = zone_id.split_once(':') else {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                "Bass and treble are only supported on LMS players",
+            ));
+        };
+        let _command = state.begin_command("lms")?;
+        state
+            .lms
+            .set_tone(player_id, req.bass, req.treble)
+            .await
+            .map_err(|e| {
+                ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("lms")
+            })?;
+    }
+
+    if let Some(profile) = req.matrix_profile {
+        let hqp = state.hqp_for_zone(&zone_id).await.ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::InvalidRequest,
+                "Matrix profiles need an HQPlayer zone or a zone linked to HQPlayer",
+            )
+        })?;
+        hqp.set_matrix_profile(profile).await.map_err(|e| {
+            ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("hqplayer")
+        })?;
+    }
+
+    eq_state(&state, &zone_id).await.map(Json)
+}
@@ -0,0 +1,236 @@
+//! Typed API errors
+//!
+//! Every error body has the same shape so clients can branch on `error_code`
+//! instead of matching message strings:
+//!
+//! ```json
+//! {"error": "Zone not found: roon:1234", "error_code": "ZONE_NOT_FOUND", "retryable": false, "backend": "roon"}
+//! ```
+//!
+//! `error` stays the human-readable message for compatibility with older clients.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::adapters::CircuitOpenError;
+
+/// Machine-readable error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Missing or malformed request fields
+    InvalidRequest,
+    /// `zone_id` query parameter missing
+    MissingZoneId,
+    /// Action name not supported by the zone's backend
+    UnknownAction,
+    ZoneNotFound,
+    /// No zone is assigned to the requested room
+    RoomNotFound,
+    /// Zone has no output that accepts volume commands
+    NoOutputs,
+    InstanceNotFound,
+    KnobNotFound,
+    ImageNotFound,
+    FirmwareNotFound,
+    AdapterNotFound,
+    TokenNotFound,
+    HookNotFound,
+    /// Announcement clip expired or never uploaded
+    ClipNotFound,
+    /// Zone is not linked to an HQPlayer instance
+    NotLinked,
+    /// Missing or invalid access token (remote access listener)
+    Unauthorized,
+    /// Access token's scope doesn't cover the request
+    Forbidden,
+    /// Zone is locked against control by non-admin callers
+    ZoneLocked,
+    /// Backend rejected or failed to execute a command
+    CommandFailed,
+    /// Backend is not connected
+    BackendUnavailable,
+    /// Backend returned an error while fetching data
+    BackendError,
+    /// Request exceeded the server's request timeout
+    Timeout,
+    /// Request aborted because the server is shutting down
+    ShuttingDown,
+    /// Firmware update held back until the knob is charging or charged
+    UpdateDeferred,
+    Internal,
+}
+
+impl ErrorCode {
+    /// HTTP status used unless overridden
+    pub fn status(self) -> StatusCode {
+        match self {
+            Self::InvalidRequest | Self::MissingZoneId | Self::UnknownAction => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::CommandFailed => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::ZoneLocked => StatusCode::LOCKED,
+            Self::ZoneNotFound
+            | Self::RoomNotFound
+            | Self::NoOutputs
+            | Self::InstanceNotFound
+            | Self::KnobNotFound
+            | Self::ImageNotFound
+            | Self::FirmwareNotFound
+            | Self::AdapterNotFound
+            | Self::TokenNotFound
+            | Self::HookNotFound
+            | Self::ClipNotFound
+            | Self::NotLinked => StatusCode::NOT_FOUND,
+            Self::BackendUnavailable | Self::ShuttingDown | Self::UpdateDeferred => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            Self::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            Self::BackendError | Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Whether the same request may succeed later without changes
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            Self::CommandFailed
+                | Self::BackendUnavailable
+                | Self::BackendError
+                | Self::Timeout
+                | Self::ShuttingDown
+                | Self::UpdateDeferred
+        )
+    }
+}
+
+/// Error response body shared by all handlers
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    /// Human-readable message
+    #[serde(rename = "error")]
+    pub message: String,
+    #[serde(rename = "error_code")]
+    pub code: ErrorCode,
+    pub retryable: bool,
+    /// Backend the error originated from ("roon", "hqplayer", "lms", ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<&'static str>,
+    /// Extra endpoint-specific fields merged into the body
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(skip)]
+    pub status: StatusCode,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code,
+            retryable: code.retryable(),
+            backend: None,
+            details: None,
+            status: code.status(),
+        }
+    }
+
+    pub fn with_backend(mut self, backend: &'static str) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Error from a backend call.
+    ///
+    /// Requests rejected by an open circuit breaker become `BackendUnavailable`
+    /// (503) with `retry_after_secs`, so clients can tell "down, try later" from
+    /// a failed command.
+    pub fn from_backend(code: ErrorCode, err: &anyhow::Error) -> Self {
+        match err
+            .chain()
+            .find_map(|e| e.downcast_ref::<CircuitOpenError>())
+        {
+            Some(open) => Self::new(ErrorCode::BackendUnavailable, open.to_string()).with_details(
+                serde_json::json!({
+                    "retry_after_secs": open.retry_in.as_secs().max(1)
+                }),
+            ),
+            None => Self::new(code, err.to_string()),
+        }
+    }
+
+    /// Merge extra fields into the body (ignored unless `details` is a JSON object)
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        if let serde_json::Value::Object(map) = details {
+            self.details = Some(map);
+        }
+        self
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_body_shape() {
+        let err = ApiError::new(ErrorCode::ZoneNotFound, "zone not found")
+            .with_backend("roon")
+            .with_details(serde_json::json!({ "zones_sha": "abcd" }));
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+
+        let body = serde_json::to_value(&err).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "zone not found",
+                "error_code": "ZONE_NOT_FOUND",
+                "retryable": false,
+                "backend": "roon",
+                "zones_sha": "abcd"
+            })
+        );
+    }
+
+    #[test]
+    fn test_backend_errors_are_retryable() {
+        assert!(ApiError::new(ErrorCode::BackendUnavailable, "down").retryable);
+        assert!(!ApiError::new(ErrorCode::InvalidRequest, "bad").retryable);
+    }
+
+    #[test]
+    fn test_open_circuit_maps_to_unavailable() {
+        let open = anyhow::Error::new(CircuitOpenError {
+            backend: "lms",
+            retry_in: std::time::Duration::from_secs(10),
+        });
+        let err = ApiError::from_backend(ErrorCode::CommandFailed, &open);
+        assert_eq!(err.code, ErrorCode::BackendUnavailable);
+        assert_eq!(err.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            err.details.as_ref().and_then(|d| d.get("retry_after_secs")),
+            Some(&serde_json::json!(10))
+        );
+
+        let failed = anyhow::anyhow!("Unknown command");
+        let err = ApiError::from_backend(ErrorCode::CommandFailed, &failed);
+        assert_eq!(err.code, ErrorCode::CommandFailed);
+    }
+}
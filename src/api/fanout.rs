@@ -0,0 +1,233 @@
+//! Per-connection outbox for the `/events` SSE stream
+//!
+//! Each connection drains the bus into its own bounded queue from a task, so
+//! a stalled client falls behind on its own queue instead of on the broadcast
+//! channel. While a client is behind, a seek or volume event replaces the one
+//! still queued for the same zone or output. When the queue is full the
+//! oldest event is dropped, and the client is told how many it missed with a
+//! `dropped` event (it can catch up through `/sync`).
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use futures::stream::{self, Stream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Notify;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::bus::BusEvent;
+
+/// Events queued per connection before the oldest are dropped
+pub const OUTBOX_CAPACITY: usize = 128;
+
+/// Next item for a connection
+#[derive(Debug)]
+pub enum Outgoing {
+    Event(BusEvent),
+    /// Events lost since the last item, because the client was too slow
+    Dropped(u64),
+}
+
+/// Key under which a newer event replaces a queued one
+fn coalesce_key(event: &BusEvent) -> Option<(&'static str, &str)> {
+    match event {
+        BusEvent::SeekPositionChanged { zone_id, .. } => Some(("seek", zone_id.as_str())),
+        BusEvent::VolumeChanged { output_id, .. } => Some(("volume", output_id.as_str())),
+        _ => None,
+    }
+}
+
+/// Bounded queue of events for one connection
+#[derive(Debug)]
+pub struct Outbox {
+    events: VecDeque<BusEvent>,
+    capacity: usize,
+    dropped: u64,
+    closed: bool,
+}
+
+impl Outbox {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+            closed: false,
+        }
+    }
+
+    /// Queue an event, coalescing seek/volume updates and dropping the oldest when full
+    pub fn push(&mut self, event: BusEvent) {
+        if let Some((kind, id)) = coalesce_key(&event) {
+            let queued = self
+                .events
+                .iter_mut()
+                .find(|queued| coalesce_key(queued) == Some((kind, id)));
+            if let Some(queued) = queued {
+                *queued = event;
+                return;
+            }
+        }
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    /// Count events the connection never saw (e.g. the bus lagged)
+    pub fn lost(&mut self, count: u64) {
+        self.dropped += count;
+    }
+
+    /// Next item, reporting drops before the events queued after them
+    pub fn pop(&mut self) -> Option<Outgoing> {
+        if self.dropped > 0 {
+            return Some(Outgoing::Dropped(std::mem::take(&mut self.dropped)));
+        }
+        self.events.pop_front().map(Outgoing::Event)
+    }
+}
+
+struct Shared {
+    outbox: Mutex<Outbox>,
+    notify: Notify,
+}
+
+impl Shared {
+    fn update(&self, f: impl FnOnce(&mut Outbox)) {
+        if let Ok(mut outbox) = self.outbox.lock() {
+            f(&mut outbox);
+        }
+        self.notify.notify_one();
+    }
+
+    /// Next item, or `Err(closed)` when there is nothing queued
+    fn pop(&self) -> Result<Outgoing, bool> {
+        let Ok(mut outbox) = self.outbox.lock() else {
+            return Err(true);
+        };
+        outbox.pop().ok_or(outbox.closed)
+    }
+}
+
+/// Receiving end of a connection's outbox; stops the drain task when dropped
+struct Receiver {
+    shared: Arc<Shared>,
+    _stop: DropGuard,
+}
+
+impl Receiver {
+    async fn next(&mut self) -> Option<Outgoing> {
+        loop {
+            match self.shared.pop() {
+                Ok(item) => return Some(item),
+                Err(true) => return None,
+                // notify_one keeps a permit, so a push since pop() still wakes us
+                Err(false) => self.shared.notify.notified().await,
+            }
+        }
+    }
+}
+
+/// Stream of a bus subscription through a bounded outbox
+///
+/// Ends on shutdown or when the bus closes.
+pub fn outbox_stream(
+    mut rx: broadcast::Receiver<BusEvent>,
+    shutdown: &CancellationToken,
+    capacity: usize,
+) -> impl Stream<Item = Outgoing> {
+    let shared = Arc::new(Shared {
+        outbox: Mutex::new(Outbox::new(capacity)),
+        notify: Notify::new(),
+    });
+    let stop = shutdown.child_token();
+
+    let drain = shared.clone();
+    let cancel = stop.clone();
+    tokio::spawn(async move {
+        loop {
+            let received = tokio::select! {
+                _ = cancel.cancelled() => break,
+                received = rx.recv() => received,
+            };
+            match received {
+                Ok(event) => drain.update(|outbox| outbox.push(event)),
+                Err(RecvError::Lagged(skipped)) => drain.update(|outbox| outbox.lost(skipped)),
+                Err(RecvError::Closed) => break,
+            }
+        }
+        drain.update(|outbox| outbox.closed = true);
+    });
+
+    let receiver = Receiver {
+        shared,
+        _stop: stop.drop_guard(),
+    };
+    stream::unfold(receiver, |mut receiver| async move {
+        let item = receiver.next().await?;
+        Some((item, receiver))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::PrefixedZoneId;
+
+    fn seek(zone: &str, position: i64) -> BusEvent {
+        BusEvent::SeekPositionChanged {
+            zone_id: PrefixedZoneId::lms(zone),
+            position,
+        }
+    }
+
+    fn position(item: Option<Outgoing>) -> Option<i64> {
+        match item {
+            Some(Outgoing::Event(BusEvent::SeekPositionChanged { position, .. })) => Some(position),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn seek_events_coalesce_per_zone() {
+        let mut outbox = Outbox::new(8);
+        outbox.push(seek("aa", 1));
+        outbox.push(seek("bb", 1));
+        outbox.push(seek("aa", 2));
+        assert_eq!(position(outbox.pop()), Some(2));
+        assert_eq!(position(outbox.pop()), Some(1));
+        assert!(outbox.pop().is_none());
+    }
+
+    #[test]
+    fn full_outbox_drops_oldest_and_reports_it() {
+        let mut outbox = Outbox::new(2);
+        for zone in ["aa", "bb", "cc"] {
+            outbox.push(seek(zone, 1));
+        }
+        outbox.lost(2);
+        assert!(matches!(outbox.pop(), Some(Outgoing::Dropped(3))));
+        assert!(matches!(
+            outbox.pop(),
+            Some(Outgoing::Event(BusEvent::SeekPositionChanged { ref zone_id, .. }))
+                if zone_id.as_str() == "lms:bb"
+        ));
+        assert_eq!(position(outbox.pop()), Some(1));
+        assert!(outbox.pop().is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_ends_on_shutdown() {
+        use futures::StreamExt;
+
+        let (tx, rx) = broadcast::channel(16);
+        let shutdown = CancellationToken::new();
+        let mut events = Box::pin(outbox_stream(rx, &shutdown, 8));
+        tx.send(seek("aa", 5)).unwrap();
+        assert_eq!(position(events.next().await), Some(5));
+        shutdown.cancel();
+        assert!(events.next().await.is_none());
+    }
+}
@@ -0,0 +1,206 @@
+//! Playback history export and the cover wall (`/history/...`)
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::{future, stream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+use crate::history::{self, HistoryRange, CSV_HEADER};
+use crate::knobs::image::ArtworkOptions;
+
+/// Albums listed on the wall by default
+const DEFAULT_WALL_ALBUMS: usize = 60;
+const MAX_WALL_ALBUMS: usize = 200;
+
+/// Query params for GET /history/export
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryExportQuery {
+    /// `jsonl` (default) or `csv`
+    pub format: Option<String>,
+    /// `24h`, `7d`, `4w`, `2026-01-01..2026-02-01` or `all` (default)
+    pub range: Option<String>,
+}
+
+/// GET /history/export - Stream the recorded plays, oldest first
+///
+/// Each play has its time, zone id and name, backend, title, artist and
+/// album. JSONL has one play object per line; CSV starts with a header row.
+pub async fn history_export_handler(
+    Query(query): Query<HistoryExportQuery>,
+) -> Result<Response, ApiError> {
+    let range = HistoryRange::parse(
+        query.range.as_deref().unwrap_or_default(),
+        chrono::Utc::now(),
+    )
+    .ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::InvalidRequest,
+            "range must be like 24h, 7d, 4w, 2026-01-01..2026-02-01 or all",
+        )
+    })?;
+    let csv = match query.format.as_deref().unwrap_or("jsonl") {
+        "jsonl" => false,
+        "csv" => true,
+        other => {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("Unknown format: {} (expected jsonl or csv)", other),
+            ))
+        }
+    };
+
+    let plays =
+        history::records().filter(move |record| future::ready(range.contains(record.played_at)));
+    let (body, content_type, filename) = if csv {
+        let rows = plays.map(|record| record.csv_row());
+        (
+            Body::from_stream(
+                stream::once(future::ready(CSV_HEADER.to_string()))
+                    .chain(rows)
+                    .map(Ok::<_, Infallible>),
+            ),
+            "text/csv; charset=utf-8",
+            "play-history.csv",
+        )
+    } else {
+        let lines = plays.filter_map(|record| {
+            future::ready(serde_json::to_string(&record).ok().map(|line| line + "\n"))
+        });
+        (
+            Body::from_stream(lines.map(Ok::<_, Infallible>)),
+            "application/x-ndjson",
+            "play-history.jsonl",
+        )
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Query params for GET /history/albums
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryAlbumsQuery {
+    /// Default 60, at most 200
+    pub limit: Option<usize>,
+}
+
+/// GET /history/albums - Recently played albums with cover art, newest first
+pub async fn history_albums_handler(Query(query): Query<HistoryAlbumsQuery>) -> Json<Value> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_WALL_ALBUMS)
+        .min(MAX_WALL_ALBUMS);
+    Json(json!({ "albums": history::recent_albums(limit).await }))
+}
+
+/// Query params for GET /history/albums/artwork
+#[derive(Debug, Deserialize)]
+pub struct AlbumArtworkQuery {
+    /// Zone the album was played on
+    pub zone_id: String,
+    pub image_key: String,
+    /// Square bounding box in pixels (default 300)
+    pub size: Option<u32>,
+}
+
+/// GET /history/albums/artwork - Cover of a played album, from the zone's backend
+///
+/// Unlike `/zones/{zone_id}/artwork` this doesn't need the album to still be
+/// playing, or the zone to still exist. Covers go through the artwork cache.
+pub async fn history_album_artwork_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<AlbumArtworkQuery>,
+) -> Response {
+    let size = query.size.unwrap_or(300).clamp(32, 1200);
+    let options = ArtworkOptions {
+        width: Some(size),
+        height: Some(size),
+        format: None,
+        quality: None,
+    };
+    match state
+        .get_image(&query.zone_id, &query.image_key, &options)
+        .await
+    {
+        Ok(image) => crate::http_cache::Validators::for_body(
+            &image.data,
+            crate::http_cache::CACHE_IMMUTABLE_ARTWORK,
+        )
+        .respond(&headers, &image.content_type, image.data),
+        Err(e) => ApiError::new(ErrorCode::ImageNotFound, e.to_string()).into_response(),
+    }
+}
+
+/// Body for POST /history/albums/play
+#[derive(Debug, Deserialize)]
+pub struct AlbumPlayRequest {
+    /// Zone to play on, which needn't be the one the album was played on
+    pub zone_id: String,
+    #[serde(default)]
+    pub artist: String,
+    pub album: String,
+}
+
+/// POST /history/albums/play - Play an album again on a zone
+///
+/// The album is looked up by name in the zone's library: the LMS library for
+/// `lms:` zones and the Spotify catalog for `spotify:` zones. Other backends
+/// can't start an album by name.
+pub async fn history_album_play_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<AlbumPlayRequest>,
+) -> Result<Json<Value>, ApiError> {
+    if req.album.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "album is required",
+        ));
+    }
+    state.check_zone_lock(&req.zone_id, Caller::from_headers(&state, &headers))?;
+    match req.zone_id.split_once(':') {
+        Some(("lms", player_id)) => state
+            .lms
+            .play_album(player_id, &req.artist, &req.album)
+            .await
+            .map_err(|e| {
+                ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("lms")
+            })?,
+        Some(("spotify", device_id)) => state
+            .spotify
+            .play_album(device_id, &req.artist, &req.album)
+            .await
+            .map_err(|e| {
+                ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("spotify")
+            })?,
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("Playing an album is not supported for zone {}", req.zone_id),
+            ))
+        }
+    }
+    Ok(Json(json!({
+        "ok": true,
+        "zone_id": req.zone_id,
+        "album": req.album,
+    })))
+}
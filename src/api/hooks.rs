@@ -0,0 +1,137 @@
+//! Inbound hooks (`POST /hooks/{name}`) and their management (`/api/hooks`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+use crate::hooks::{is_valid_name, Hook, HookTarget, ALL_ZONES};
+use crate::knobs::{activate_party, control_all_zones, dispatch_control};
+use crate::remote::tokens_match;
+
+/// Header carrying a hook's secret, as an alternative to `?secret=`
+pub const HOOK_SECRET_HEADER: &str = "x-hook-secret";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct HookQuery {
+    pub secret: Option<String>,
+}
+
+fn hook_not_found(name: &str) -> ApiError {
+    ApiError::new(ErrorCode::HookNotFound, format!("Hook not found: {}", name))
+}
+
+/// GET /api/hooks - List hooks by name, secrets included
+pub async fn hooks_list_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.require_admin(&headers)?;
+    Ok(Json(serde_json::json!({ "hooks": state.hooks.list() })))
+}
+
+/// PUT /api/hooks/{name} - Create or replace a hook
+pub async fn hooks_put_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(mut hook): Json<Hook>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if !is_valid_name(&name) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "Hook names are up to 64 letters, digits, '-' or '_'",
+        ));
+    }
+    if let HookTarget::Control {
+        zone_id, action, ..
+    } = &hook.target
+    {
+        if zone_id.is_empty() || action.is_empty() {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                "Control hooks need a zone_id (or \"*\") and an action",
+            ));
+        }
+    }
+    hook.secret = hook.secret.filter(|s| !s.is_empty());
+    state.hooks.set(&name, hook.clone());
+    Ok(Json(serde_json::json!({ "name": name, "hook": hook })))
+}
+
+/// DELETE /api/hooks/{name} - Remove a hook
+pub async fn hooks_delete_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if state.hooks.remove(&name) {
+        Ok(Json(serde_json::json!({ "ok": true })))
+    } else {
+        Err(hook_not_found(&name))
+    }
+}
+
+/// POST /hooks/{name} - Run a hook
+///
+/// A hook with a secret needs it as `?secret=` or `X-Hook-Secret`; a secret
+/// sent to a hook without one is rejected too, since on the remote listener
+/// the secret stands in for an access token. Returns the outcome of the
+/// scene or control action.
+pub async fn hook_trigger_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<HookQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let hook = state
+        .hooks
+        .get(&name)
+        .ok_or_else(|| hook_not_found(&name))?;
+    let presented = headers
+        .get(HOOK_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .or(query.secret);
+    let authorized = match (&hook.secret, &presented) {
+        (Some(secret), Some(presented)) => tokens_match(presented, secret),
+        (None, None) => true,
+        _ => false,
+    };
+    if !authorized {
+        return Err(ApiError::new(
+            ErrorCode::Unauthorized,
+            "Missing or wrong hook secret",
+        ));
+    }
+
+    tracing::info!("Hook {} triggered", name);
+    // Anyone with the URL can run a hook, so locked zones stay locked
+    let caller = Caller::Restricted;
+    let result = match &hook.target {
+        HookTarget::Party => activate_party(&state, &state.scenes.party(), caller).await,
+        HookTarget::Standby => control_all_zones(&state, None, None, caller).await,
+        HookTarget::Control {
+            zone_id,
+            action,
+            value,
+        } if zone_id == ALL_ZONES => {
+            control_all_zones(&state, Some(action), value.as_ref(), caller).await
+        }
+        HookTarget::Control {
+            zone_id,
+            action,
+            value,
+        } => {
+            dispatch_control(&state, zone_id, action, value.as_ref(), caller)
+                .await?
+                .0
+        }
+    };
+    Ok(Json(result))
+}
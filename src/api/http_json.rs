@@ -0,0 +1,40 @@
+//! HTTP JSON device templates (`/http/devices`)
+
+use axum::{extract::State, http::HeaderMap, Json};
+use serde_json::{json, Value};
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::adapters::http_json::HttpDevicesConfig;
+
+/// GET /http/devices - Device templates, as saved in `http-devices.json`
+pub async fn http_devices_handler(State(state): State<AppState>) -> Json<HttpDevicesConfig> {
+    Json(state.http.config().await)
+}
+
+/// PUT /http/devices - Replace the device templates and restart the adapter if enabled
+pub async fn http_devices_put_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(config): Json<HttpDevicesConfig>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    let has_devices = !config.devices.is_empty();
+    state.http.set_config(config).await.map_err(|e| {
+        ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("http")
+    })?;
+
+    if state.coordinator.is_enabled("http").await {
+        if let Some(adapter) = state.startable_adapters.iter().find(|a| a.name() == "http") {
+            // With no devices left the restart only stops it
+            let restarted = state.coordinator.restart(adapter.as_ref()).await;
+            if has_devices {
+                restarted.map_err(|e| {
+                    ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("http")
+                })?;
+            }
+        }
+    }
+    Ok(Json(
+        json!({ "ok": true, "devices": state.http.config().await.devices }),
+    ))
+}
@@ -0,0 +1,132 @@
+//! Per-zone input selection (`/zones/{zone_id}/input`)
+//!
+//! A thin layer over whatever input switching the zone's backend has: the
+//! Product service sources on OpenHome devices (streaming, radio, analog,
+//! optical, HDMI, ...). Other zones report `supported: false`.
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+use crate::adapters::openhome::OpenHomeSources;
+
+/// Input change
+#[derive(Debug, Deserialize)]
+pub struct InputRequest {
+    /// Input `id`, or its name (case-insensitive)
+    pub input: String,
+}
+
+async fn require_zone(state: &AppState, zone_id: &str) -> Result<(), ApiError> {
+    if state.aggregator.get_zone(zone_id).await.is_none() {
+        return Err(ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        ));
+    }
+    Ok(())
+}
+
+async fn openhome_sources(state: &AppState, uuid: &str) -> Result<OpenHomeSources, ApiError> {
+    state
+        .openhome
+        .get_sources(uuid)
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("openhome"))
+}
+
+async fn input_state(state: &AppState, zone_id: &str) -> Result<Value, ApiError> {
+    require_zone(state, zone_id).await?;
+
+    let (inputs, current) = match zone_id.split_once(':') {
+        Some(("openhome", uuid)) => {
+            let sources = openhome_sources(state, uuid).await?;
+            let inputs: Vec<Value> = sources
+                .sources
+                .iter()
+                .map(|s| {
+                    json!({
+                        "id": s.index.to_string(),
+                        "name": s.name,
+                        "type": s.source_type,
+                    })
+                })
+                .collect();
+            (Some(inputs), Some(sources.current.to_string()))
+        }
+        _ => (None, None),
+    };
+
+    Ok(json!({
+        "zone_id": zone_id,
+        "supported": inputs.is_some(),
+        "inputs": inputs.unwrap_or_default(),
+        "current": current,
+    }))
+}
+
+/// GET /zones/{zone_id}/input - Inputs the zone's device can switch between
+///
+/// `inputs` is a list of `{id, name, type}` and `current` the selected input's
+/// `id`; `supported` is false (and `inputs` empty) when the backend has no inputs.
+pub async fn zone_input_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    input_state(&state, &zone_id).await.map(Json)
+}
+
+/// POST /zones/{zone_id}/input - Switch to an input by id or name
+///
+/// Returns the resulting input state, as for GET. Locked zones reject it
+/// like other control commands.
+pub async fn zone_input_post_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<InputRequest>,
+) -> Result<Json<Value>, ApiError> {
+    require_zone(&state, &zone_id).await?;
+
+    let Some(("openhome", uuid)) = zone_id.split_once(':') else {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "Input selection is only supported on OpenHome devices",
+        ));
+    };
+    state.check_zone_lock(&zone_id, Caller::from_headers(&state, &headers))?;
+    let _command = state.begin_command("openhome")?;
+    let wanted = req.input.trim();
+    let sources = openhome_sources(&state, uuid).await?;
+    let source = sources
+        .sources
+        .iter()
+        .find(|s| s.index.to_string() == wanted)
+        .or_else(|| {
+            sources
+                .sources
+                .iter()
+                .find(|s| s.name.eq_ignore_ascii_case(wanted))
+        })
+        .ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("Unknown input: {}", wanted),
+            )
+            .with_backend("openhome")
+        })?;
+    state
+        .openhome
+        .set_source(uuid, source.index)
+        .await
+        .map_err(|e| {
+            ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("openhome")
+        })?;
+
+    input_state(&state, &zone_id).await.map(Json)
+}
@@ -0,0 +1,58 @@
+//! Lyrics for a zone's current track (`/zones/{zone_id}/lyrics`)
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::lyrics::TrackQuery;
+
+/// GET /zones/{zone_id}/lyrics - Lyrics for the zone's current track
+///
+/// `position` comes from the aggregator's seek tracking, and `current_line`
+/// is the synced line at that position, so clients can highlight it without
+/// keeping their own clock. `lyrics` is null when no provider has the track;
+/// `enabled` is false when no provider is configured.
+pub async fn zone_lyrics_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let zone = state.aggregator.get_zone(&zone_id).await.ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        )
+    })?;
+    let np = zone.now_playing.as_ref();
+    let position = np.and_then(|n| n.seek_position);
+
+    let lyrics = match np {
+        Some(np) if state.lyrics.is_enabled() => {
+            state
+                .lyrics
+                .lookup(&TrackQuery {
+                    artist: &np.artist,
+                    title: &np.title,
+                    album: &np.album,
+                    duration_secs: np.duration,
+                })
+                .await
+        }
+        _ => None,
+    };
+    let current_line = lyrics
+        .as_ref()
+        .zip(position)
+        .and_then(|(lyrics, position)| lyrics.current_line(position));
+
+    Ok(Json(serde_json::json!({
+        "zone_id": zone.zone_id,
+        "enabled": state.lyrics.is_enabled(),
+        "title": np.map(|n| n.title.as_str()),
+        "artist": np.map(|n| n.artist.as_str()),
+        "position": position,
+        "current_line": current_line,
+        "lyrics": lyrics,
+    })))
+}
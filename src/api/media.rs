@@ -0,0 +1,358 @@
+//! Media for UPnP/DLNA renderers (`/media/*`)
+//!
+//! Renderers can only play what they can fetch over HTTP, so announcements
+//! (doorbell chimes, TTS clips rendered by Home Assistant) are uploaded here
+//! and served back with the DLNA headers renderers expect. A clip is kept in
+//! memory for [`CLIP_TTL`]; automations upload it, point a renderer at the
+//! returned URL with the `play_url` action, and let it expire.
+//!
+//! `POST /zones/{zone_id}/announce` does the whole round trip on LMS and UPnP
+//! zones: it interrupts what is playing, plays the clip, and puts the previous
+//! playback back.
+//!
+//! The current track's artwork is served the same way for renderers and
+//! DLNA controllers that show a cover next to an announcement.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use serde::Deserialize;
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+use crate::adapters::lms::DISPLAY_MESSAGE_DURATION;
+use crate::knobs::image::{ArtworkOptions, OutputFormat};
+
+/// How long an uploaded clip stays available
+pub const CLIP_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Clips kept at once; the oldest is dropped first
+const MAX_CLIPS: usize = 20;
+
+/// Longest an announcement may play before playback is restored anyway
+const DEFAULT_ANNOUNCE_SECS: u64 = 60;
+const MAX_ANNOUNCE_SECS: u64 = 300;
+
+/// Artwork edge length served to renderers (DLNA `JPEG_LRG` tops out at 4096)
+const ARTWORK_SIZE: u32 = 600;
+
+/// DLNA flags: streaming transfer, no byte or time seeking
+const DLNA_FEATURES: &str =
+    "DLNA.ORG_OP=00;DLNA.ORG_CI=0;DLNA.ORG_FLAGS=01700000000000000000000000000000";
+
+struct Clip {
+    content_type: String,
+    data: Bytes,
+    created: Instant,
+}
+
+/// In-memory announcement clips, keyed by random id, and the zones currently
+/// playing an announcement
+#[derive(Default)]
+pub struct AnnouncementStore {
+    clips: Mutex<HashMap<String, Clip>>,
+    active: Mutex<HashSet<String>>,
+}
+
+impl AnnouncementStore {
+    /// Store a clip and return its id
+    pub fn insert(&self, content_type: String, data: Bytes) -> String {
+        let id = hex::encode(rand::random::<[u8; 8]>());
+        let mut clips = self.clips.lock().unwrap_or_else(PoisonError::into_inner);
+        clips.retain(|_, clip| clip.created.elapsed() < CLIP_TTL);
+        while clips.len() >= MAX_CLIPS {
+            let Some(oldest) = clips
+                .iter()
+                .min_by_key(|(_, clip)| clip.created)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            clips.remove(&oldest);
+        }
+        clips.insert(
+            id.clone(),
+            Clip {
+                content_type,
+                data,
+                created: Instant::now(),
+            },
+        );
+        id
+    }
+
+    /// Content type and bytes of an unexpired clip
+    pub fn get(&self, id: &str) -> Option<(String, Bytes)> {
+        let clips = self.clips.lock().unwrap_or_else(PoisonError::into_inner);
+        clips
+            .get(id)
+            .filter(|clip| clip.created.elapsed() < CLIP_TTL)
+            .map(|clip| (clip.content_type.clone(), clip.data.clone()))
+    }
+
+    /// Mark `zone_id` as announcing; false if it already is
+    fn begin(&self, zone_id: &str) -> bool {
+        self.active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(zone_id.to_string())
+    }
+
+    fn end(&self, zone_id: &str) {
+        self.active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(zone_id);
+    }
+}
+
+/// Absolute URL of a clip, using the host the request came in on
+fn clip_url(headers: &HeaderMap, id: &str) -> String {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("localhost");
+    format!("http://{}/media/announcements/{}", host, id)
+}
+
+/// Response with the headers DLNA renderers look for
+fn dlna_response(content_type: &str, transfer_mode: &'static str, data: Bytes) -> Response {
+    let mut response = ([(header::CONTENT_TYPE, content_type.to_string())], data).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        "transferMode.dlna.org",
+        HeaderValue::from_static(transfer_mode),
+    );
+    headers.insert(
+        "contentFeatures.dlna.org",
+        HeaderValue::from_static(DLNA_FEATURES),
+    );
+    response
+}
+
+/// POST /media/announcements - Upload an audio clip (raw body)
+///
+/// Returns `201 {"id", "url", "expires_in_secs"}`. `url` is absolute, built
+/// from the request's `Host`, so it can be passed straight to `play_url`.
+pub async fn clip_upload_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !content_type.starts_with("audio/") {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "Content-Type must be an audio type (e.g. audio/mpeg)",
+        ));
+    }
+    if body.is_empty() {
+        return Err(ApiError::new(ErrorCode::InvalidRequest, "Empty clip"));
+    }
+
+    let id = state.announcements.insert(content_type, body);
+    let url = clip_url(&headers, &id);
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "id": id,
+            "url": url,
+            "expires_in_secs": CLIP_TTL.as_secs(),
+        })),
+    )
+        .into_response())
+}
+
+/// GET /media/announcements/{id} - Serve an uploaded clip to a renderer
+pub async fn clip_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let (content_type, data) = state
+        .announcements
+        .get(&id)
+        .ok_or_else(|| ApiError::new(ErrorCode::ClipNotFound, format!("Clip not found: {}", id)))?;
+    Ok(dlna_response(&content_type, "Streaming", data))
+}
+
+/// Announce request body; one of `url` or `clip_id` is required
+#[derive(Debug, Deserialize)]
+pub struct AnnounceRequest {
+    /// Audio URL the player can fetch
+    pub url: Option<String>,
+    /// Clip uploaded to `/media/announcements`
+    pub clip_id: Option<String>,
+    /// Volume (0-100) for the announcement; the zone's volume is kept when unset
+    pub volume: Option<i32>,
+    /// Restore playback after this long even if the clip is still playing
+    pub max_secs: Option<u64>,
+    /// Second line under "Announcement" on LMS players with a display
+    pub text: Option<String>,
+}
+
+/// POST /zones/{zone_id}/announce - Play a clip, then restore playback
+///
+/// Returns `202` once the zone's current state is saved and the clip is
+/// handed to the backend; restoring happens in the background when the clip
+/// ends. Only one announcement per zone runs at a time.
+pub async fn announce_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<AnnounceRequest>,
+) -> Result<Response, ApiError> {
+    let url = match (req.url, req.clip_id) {
+        (Some(url), _) if !url.trim().is_empty() => url,
+        (_, Some(id)) => {
+            if state.announcements.get(&id).is_none() {
+                return Err(ApiError::new(
+                    ErrorCode::ClipNotFound,
+                    format!("Clip not found: {}", id),
+                ));
+            }
+            clip_url(&headers, &id)
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                "url or clip_id is required",
+            ))
+        }
+    };
+    if state.aggregator.get_zone(&zone_id).await.is_none() {
+        return Err(ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        ));
+    }
+    let backend = match zone_id.split(':').next() {
+        Some("lms") => "lms",
+        Some("upnp") => "upnp",
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                "Announcements are supported on LMS and UPnP zones",
+            ))
+        }
+    };
+    state.check_zone_lock(&zone_id, Caller::from_headers(&state, &headers))?;
+    let command = state.begin_command(backend)?;
+    if !state.announcements.begin(&zone_id) {
+        return Err(ApiError::new(
+            ErrorCode::CommandFailed,
+            format!("An announcement is already playing on {}", zone_id),
+        ));
+    }
+
+    let max = Duration::from_secs(
+        req.max_secs
+            .unwrap_or(DEFAULT_ANNOUNCE_SECS)
+            .clamp(1, MAX_ANNOUNCE_SECS),
+    );
+    let volume = req.volume;
+    let text = req.text.unwrap_or_default();
+    let task_state = state.clone();
+    let task_zone = zone_id.clone();
+    let task_url = url.clone();
+    tokio::spawn(async move {
+        // Held until playback is restored, so a stop drains the announcement
+        let _command = command;
+        let cancel = &task_state.shutdown;
+        let result = match task_zone.split_once(':') {
+            Some(("lms", player_id)) => {
+                if let Err(e) = task_state
+                    .lms
+                    .show_message(player_id, "Announcement", &text, DISPLAY_MESSAGE_DURATION)
+                    .await
+                {
+                    tracing::debug!("Display message on {} failed: {}", task_zone, e);
+                }
+                task_state
+                    .lms
+                    .announce(player_id, &task_url, volume, max, cancel)
+                    .await
+            }
+            Some((_, udn)) => {
+                task_state
+                    .upnp
+                    .announce(udn, &task_url, volume, max, cancel)
+                    .await
+            }
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Announcement on {} failed: {}", task_zone, e);
+        }
+        task_state.announcements.end(&task_zone);
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "ok": true, "zone_id": zone_id, "url": url })),
+    )
+        .into_response())
+}
+
+/// GET /media/artwork/{zone_id} - Current track's artwork as a JPEG
+pub async fn media_artwork_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let zone = state.aggregator.get_zone(&zone_id).await.ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        )
+    })?;
+    let image_key = zone
+        .now_playing
+        .and_then(|np| np.image_key)
+        .ok_or_else(|| ApiError::new(ErrorCode::ImageNotFound, "Nothing playing"))?;
+
+    let options = ArtworkOptions {
+        width: Some(ARTWORK_SIZE),
+        height: Some(ARTWORK_SIZE),
+        format: Some(OutputFormat::Jpeg),
+        quality: None,
+    };
+    let image = state
+        .get_image(&zone_id, &image_key, &options)
+        .await
+        .map_err(|e| ApiError::new(ErrorCode::ImageNotFound, e.to_string()))?;
+    Ok(dlna_response(
+        &image.content_type,
+        "Interactive",
+        Bytes::from(image.data),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_keeps_newest_clips() {
+        let store = AnnouncementStore::default();
+        let first = store.insert("audio/mpeg".to_string(), Bytes::from_static(b"a"));
+        let mut last = String::new();
+        for _ in 0..MAX_CLIPS {
+            last = store.insert("audio/wav".to_string(), Bytes::from_static(b"b"));
+        }
+        assert!(store.get(&first).is_none());
+        let (content_type, data) = store.get(&last).unwrap();
+        assert_eq!(content_type, "audio/wav");
+        assert_eq!(&data[..], b"b");
+    }
+}
@@ -0,0 +1,194 @@
+//! MiniDSP instances and zone links (`/minidsp/...`)
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+use crate::adapters::minidsp::{MasterStatus, MiniDspInstance, MiniDspStatus, PRESET_SLOTS};
+
+fn not_linked(zone_id: &str) -> ApiError {
+    ApiError::new(
+        ErrorCode::NotLinked,
+        format!("Zone {} not linked to MiniDSP", zone_id),
+    )
+    .with_backend("minidsp")
+}
+
+fn backend_error(e: anyhow::Error) -> ApiError {
+    ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("minidsp")
+}
+
+/// GET /minidsp/instances - List MiniDSP instances
+pub async fn minidsp_instances_handler(State(state): State<AppState>) -> Json<Value> {
+    Json(json!({ "instances": state.minidsp.list_instances().await }))
+}
+
+/// POST /minidsp/instances - Add an instance, or replace the one with the same name
+pub async fn minidsp_instance_put_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(instance): Json<MiniDspInstance>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    state
+        .minidsp
+        .set_instance(instance.clone())
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("minidsp")
+        })?;
+    Ok(Json(json!({ "ok": true, "instance": instance })))
+}
+
+/// DELETE /minidsp/instances/{name} - Remove an instance and its zone links
+pub async fn minidsp_instance_delete_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if !state.minidsp.remove_instance(&name).await {
+        return Err(ApiError::new(
+            ErrorCode::InstanceNotFound,
+            format!("Unknown MiniDSP instance: {}", name),
+        )
+        .with_backend("minidsp"));
+    }
+    Ok(Json(json!({ "ok": true, "instance": name })))
+}
+
+/// GET /minidsp/zones/links - Get all zone links
+pub async fn minidsp_zone_links_handler(State(state): State<AppState>) -> Json<Value> {
+    Json(json!({ "links": state.minidsp.get_links().await }))
+}
+
+/// Body for POST /minidsp/zones/link
+#[derive(Debug, Deserialize)]
+pub struct MiniDspLinkRequest {
+    pub zone_id: String,
+    pub instance: String,
+}
+
+/// POST /minidsp/zones/link - Link a zone to a MiniDSP instance
+pub async fn minidsp_zone_link_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<MiniDspLinkRequest>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if req.zone_id.is_empty() || req.instance.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "zone_id and instance are required",
+        ));
+    }
+    state
+        .minidsp
+        .link_zone(req.zone_id.clone(), req.instance.clone())
+        .await
+        .map_err(|e| {
+            ApiError::new(ErrorCode::InstanceNotFound, e.to_string()).with_backend("minidsp")
+        })?;
+    Ok(Json(json!({
+        "ok": true,
+        "zone_id": req.zone_id,
+        "instance": req.instance,
+    })))
+}
+
+/// Body for POST /minidsp/zones/unlink
+#[derive(Debug, Deserialize)]
+pub struct MiniDspUnlinkRequest {
+    pub zone_id: String,
+}
+
+/// POST /minidsp/zones/unlink - Unlink a zone from MiniDSP
+pub async fn minidsp_zone_unlink_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<MiniDspUnlinkRequest>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    let was_linked = state.minidsp.unlink_zone(&req.zone_id).await;
+    Ok(Json(json!({
+        "ok": true,
+        "zone_id": req.zone_id,
+        "was_linked": was_linked,
+    })))
+}
+
+/// GET /minidsp/zones/{zone_id} - Preset, volume and mute of the linked unit
+pub async fn minidsp_zone_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+) -> Result<Json<MiniDspStatus>, ApiError> {
+    let instance = state
+        .minidsp
+        .instance_for_zone(&zone_id)
+        .await
+        .ok_or_else(|| not_linked(&zone_id))?;
+    let status = state
+        .minidsp
+        .status(&instance)
+        .await
+        .map_err(backend_error)?;
+    Ok(Json(status))
+}
+
+/// Body for POST /minidsp/zones/{zone_id}; omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct MiniDspZoneUpdate {
+    /// Preset slot (0-based)
+    pub preset: Option<u8>,
+    /// Master volume in dB
+    pub volume: Option<f64>,
+    pub mute: Option<bool>,
+}
+
+/// POST /minidsp/zones/{zone_id} - Change the linked unit's preset, volume or mute
+///
+/// Locked zones reject it like other control commands.
+pub async fn minidsp_zone_update_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    headers: HeaderMap,
+    Json(update): Json<MiniDspZoneUpdate>,
+) -> Result<Json<MiniDspStatus>, ApiError> {
+    state.check_zone_lock(&zone_id, Caller::from_headers(&state, &headers))?;
+    let instance = state
+        .minidsp
+        .instance_for_zone(&zone_id)
+        .await
+        .ok_or_else(|| not_linked(&zone_id))?;
+    if update.preset.is_some_and(|p| p >= PRESET_SLOTS) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("preset must be 0-{}", PRESET_SLOTS - 1),
+        )
+        .with_backend("minidsp"));
+    }
+    if update.preset.is_some() || update.volume.is_some() || update.mute.is_some() {
+        let master = MasterStatus {
+            preset: update.preset,
+            source: None,
+            volume: update.volume,
+            mute: update.mute,
+        };
+        state
+            .minidsp
+            .apply(&instance, master)
+            .await
+            .map_err(backend_error)?;
+    }
+    let status = state
+        .minidsp
+        .status(&instance)
+        .await
+        .map_err(backend_error)?;
+    Ok(Json(status))
+}
@@ -1,21 +1,46 @@
 //! HTTP API handlers
 
-use crate::adapters::hqplayer::{HqpAdapter, HqpInstanceManager, HqpZoneLinkService};
+use crate::adapters::airplay::AirPlayAdapter;
+use crate::adapters::camilladsp::CamillaDspService;
+use crate::adapters::heos::HeosAdapter;
+use crate::adapters::hqplayer::{
+    HqpAdapter, HqpConnectionSettings, HqpInstanceManager, HqpWebTls, HqpZoneLinkService,
+};
+use crate::adapters::http_json::HttpJsonAdapter;
+use crate::adapters::kodi::KodiAdapter;
 use crate::adapters::lms::LmsAdapter;
+use crate::adapters::minidsp::MiniDspService;
+use crate::adapters::naa::NaaService;
 use crate::adapters::openhome::OpenHomeAdapter;
+use crate::adapters::plexamp::PlexampAdapter;
 use crate::adapters::roon::RoonAdapter;
+use crate::adapters::roon_power::RoonCorePowerService;
+use crate::adapters::snapcast::SnapcastAdapter;
+use crate::adapters::spotify::SpotifyAdapter;
 use crate::adapters::upnp::UPnPAdapter;
 use crate::adapters::Startable;
 use crate::aggregator::ZoneAggregator;
-use crate::bus::SharedBus;
-use crate::coordinator::AdapterCoordinator;
+use crate::artwork_cache::ArtworkCache;
+use crate::bus::{PrefixedZoneId, SharedBus};
+use crate::config::{BackupSettings, PollingSettings};
+use crate::coordinator::{AdapterCoordinator, InFlightCommand};
+use crate::hooks::HookStore;
+use crate::knobs::art_mode::ArtMode;
+use crate::knobs::image::{ArtworkOptions, OutputFormat};
 use crate::knobs::KnobStore;
+use crate::lyrics::LyricsService;
+use crate::metrics::ZoneMetrics;
+use crate::scenes::SceneStore;
+use crate::selection::SelectionStore;
+use crate::text::TextService;
+use crate::tokens::TokenStore;
+use crate::zone_resolver::{self, ZoneNames};
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
     response::{
         sse::{Event, KeepAlive, Sse},
-        IntoResponse,
+        IntoResponse, Response,
     },
     Json,
 };
@@ -25,20 +50,111 @@ use std::convert::Infallible;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 use tokio_util::sync::CancellationToken;
 
+mod adapter_config;
+mod camilladsp;
+mod embed;
+mod eq;
+mod error;
+mod fanout;
+mod history;
+mod hooks;
+mod http_json;
+mod input;
+mod lyrics;
+mod media;
+mod minidsp;
+mod naa;
+mod paging;
+mod roon_power;
+mod selection;
+mod simple;
+mod spotify;
+mod sync;
+mod timeout;
+mod tokens;
+pub use adapter_config::{adapter_config_get_handler, adapter_config_put_handler};
+pub use camilladsp::{
+    camilladsp_instance_delete_handler, camilladsp_instance_put_handler,
+    camilladsp_instances_handler, camilladsp_zone_handler, camilladsp_zone_link_handler,
+    camilladsp_zone_links_handler, camilladsp_zone_unlink_handler, camilladsp_zone_update_handler,
+};
+pub use embed::embed_now_playing_handler;
+pub use eq::{zone_eq_handler, zone_eq_put_handler};
+pub use error::{ApiError, ErrorCode};
+pub use history::{
+    history_album_artwork_handler, history_album_play_handler, history_albums_handler,
+    history_export_handler,
+};
+pub use hooks::{
+    hook_trigger_handler, hooks_delete_handler, hooks_list_handler, hooks_put_handler,
+    HOOK_SECRET_HEADER,
+};
+pub use http_json::{http_devices_handler, http_devices_put_handler};
+pub use input::{zone_input_handler, zone_input_post_handler};
+pub use lyrics::zone_lyrics_handler;
+pub use media::{
+    announce_handler, clip_handler, clip_upload_handler, media_artwork_handler, AnnouncementStore,
+};
+pub use minidsp::{
+    minidsp_instance_delete_handler, minidsp_instance_put_handler, minidsp_instances_handler,
+    minidsp_zone_handler, minidsp_zone_link_handler, minidsp_zone_links_handler,
+    minidsp_zone_unlink_handler, minidsp_zone_update_handler,
+};
+pub use naa::{
+    hqp_instance_naa_handler, hqp_instance_naa_put_handler, hqp_naa_discover_handler,
+    hqp_naa_handler,
+};
+pub use paging::{select_fields, ListQuery, PageInfo};
+pub use roon_power::{
+    roon_core_power_action_handler, roon_core_power_handler, roon_core_wol_put_handler,
+};
+pub use selection::{
+    select_zone, selection_delete_handler, selection_get_handler, selection_put_handler,
+};
+pub use simple::simple_handler;
+pub use spotify::{spotify_authorize_handler, spotify_callback_handler};
+pub use sync::sync_handler;
+pub use timeout::{enforce_deadline, RequestDeadline};
+pub use tokens::{
+    pair_handler, pairing_code_handler, tokens_create_handler, tokens_delete_handler,
+    tokens_list_handler, tokens_update_handler,
+};
+
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
     pub roon: Arc<RoonAdapter>,
+    /// Wake-on-LAN for the Roon Core machine
+    pub roon_power: Arc<RoonCorePowerService>,
     pub hqplayer: Arc<HqpAdapter>,
     pub hqp_instances: Arc<HqpInstanceManager>,
     pub hqp_zone_links: Arc<HqpZoneLinkService>,
+    /// NAA endpoints and the HQPlayer instances assigned to them
+    pub hqp_naa: Arc<NaaService>,
+    /// CamillaDSP instances and the zones linked to them
+    pub camilladsp: Arc<CamillaDspService>,
+    /// MiniDSP units and the zones linked to them
+    pub minidsp: Arc<MiniDspService>,
     pub lms: Arc<LmsAdapter>,
     pub openhome: Arc<OpenHomeAdapter>,
     pub upnp: Arc<UPnPAdapter>,
+    /// Read-only AirPlay receivers; started with an idle default (see `with_airplay`)
+    pub airplay: Arc<AirPlayAdapter>,
+    /// Snapcast clients and groups; started with an idle default (see `with_snapcast`)
+    pub snapcast: Arc<SnapcastAdapter>,
+    /// HEOS players and groups; started with an idle default (see `with_heos`)
+    pub heos: Arc<HeosAdapter>,
+    /// Kodi playback; started with an idle default (see `with_kodi`)
+    pub kodi: Arc<KodiAdapter>,
+    /// Spotify Connect devices; started with an idle default (see `with_spotify`)
+    pub spotify: Arc<SpotifyAdapter>,
+    /// Devices bridged through HTTP JSON templates; started with an idle default (see `with_http`)
+    pub http: Arc<HttpJsonAdapter>,
+    /// Headless Plexamp players; started with an idle default (see `with_plexamp`)
+    pub plexamp: Arc<PlexampAdapter>,
     pub knobs: KnobStore,
     pub bus: SharedBus,
     pub aggregator: Arc<ZoneAggregator>,
@@ -49,6 +165,56 @@ pub struct AppState {
     pub shutdown: CancellationToken,
     /// Count of active SSE connections (for shutdown diagnostics)
     pub sse_connections: Arc<AtomicUsize>,
+    /// Processed artwork for backends without their own image cache
+    pub artwork_cache: Arc<ArtworkCache>,
+    /// Per-zone counters exported on `/metrics`
+    pub metrics: Arc<ZoneMetrics>,
+    /// Named API tokens (accepted by the remote access listener)
+    pub tokens: Arc<TokenStore>,
+    /// Lyrics providers for `/zones/{zone_id}/lyrics` (disabled by default)
+    pub lyrics: Arc<LyricsService>,
+    /// Normalization of now-playing text for the knob display and MQTT
+    pub text: Arc<TextService>,
+    /// Announcement clips served to renderers from `/media/announcements`
+    pub announcements: Arc<AnnouncementStore>,
+    /// Scene presets (party mode)
+    pub scenes: Arc<SceneStore>,
+    /// Named inbound hooks (`/hooks/{name}`)
+    pub hooks: Arc<HookStore>,
+    /// Selected zone per client (`/api/selection`)
+    pub selection: Arc<SelectionStore>,
+    /// Recent covers and slideshow positions for knob art mode
+    pub art_mode: Arc<ArtMode>,
+}
+
+/// Who sent a control command, for zone locks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Caller {
+    /// A request with the remote access token or an admin-scoped token
+    Admin,
+    /// Everyone else: knobs, hooks, other named tokens and requests without
+    /// a token
+    Restricted,
+}
+
+impl Caller {
+    /// Admin only when the request carries an admin credential (see
+    /// [`TokenStore::is_admin`]); a knob is never admin
+    ///
+    /// [`TokenStore::is_admin`]: crate::tokens::TokenStore::is_admin
+    pub fn from_headers(state: &AppState, headers: &HeaderMap) -> Self {
+        if headers.contains_key("x-knob-id") || headers.contains_key("x-device-id") {
+            return Self::Restricted;
+        }
+        let presented = headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        match presented {
+            Some(token) if state.tokens.is_admin(token) => Self::Admin,
+            _ => Self::Restricted,
+        }
+    }
 }
 
 impl AppState {
@@ -71,12 +237,23 @@ impl AppState {
     ) -> Self {
         Self {
             roon,
+            roon_power: Arc::new(RoonCorePowerService::new()),
             hqplayer,
             hqp_instances,
             hqp_zone_links,
+            hqp_naa: Arc::new(NaaService::new(bus.clone())),
+            camilladsp: Arc::new(CamillaDspService::new()),
+            minidsp: Arc::new(MiniDspService::new()),
             lms,
             openhome,
             upnp,
+            airplay: Arc::new(AirPlayAdapter::new(bus.clone())),
+            snapcast: Arc::new(SnapcastAdapter::new(bus.clone())),
+            heos: Arc::new(HeosAdapter::new(bus.clone())),
+            kodi: Arc::new(KodiAdapter::new(bus.clone())),
+            spotify: Arc::new(SpotifyAdapter::new(bus.clone())),
+            http: Arc::new(HttpJsonAdapter::new(bus.clone())),
+            plexamp: Arc::new(PlexampAdapter::new(bus.clone())),
             knobs,
             bus,
             aggregator,
@@ -85,44 +262,233 @@ impl AppState {
             start_time,
             shutdown,
             sse_connections: Arc::new(AtomicUsize::new(0)),
+            artwork_cache: Arc::new(ArtworkCache::default()),
+            metrics: Arc::new(ZoneMetrics::default()),
+            tokens: Arc::new(TokenStore::load()),
+            lyrics: Arc::new(LyricsService::default()),
+            text: Arc::new(TextService::default()),
+            announcements: Arc::new(AnnouncementStore::default()),
+            scenes: Arc::new(SceneStore::load()),
+            hooks: Arc::new(HookStore::load()),
+            selection: Arc::new(SelectionStore::load()),
+            art_mode: Arc::new(ArtMode::default()),
         }
     }
 
+    /// Use configured lyrics providers
+    pub fn with_lyrics(mut self, lyrics: LyricsService) -> Self {
+        self.lyrics = Arc::new(lyrics);
+        self
+    }
+
+    /// Use configured text normalization
+    pub fn with_text(mut self, text: TextService) -> Self {
+        self.text = Arc::new(text);
+        self
+    }
+
+    /// Use the AirPlay adapter the coordinator starts
+    pub fn with_airplay(mut self, airplay: Arc<AirPlayAdapter>) -> Self {
+        self.airplay = airplay;
+        self
+    }
+
+    /// Use the Snapcast adapter the coordinator starts
+    pub fn with_snapcast(mut self, snapcast: Arc<SnapcastAdapter>) -> Self {
+        self.snapcast = snapcast;
+        self
+    }
+
+    /// Use the HEOS adapter the coordinator starts
+    pub fn with_heos(mut self, heos: Arc<HeosAdapter>) -> Self {
+        self.heos = heos;
+        self
+    }
+
+    /// Use the Kodi adapter the coordinator starts
+    pub fn with_kodi(mut self, kodi: Arc<KodiAdapter>) -> Self {
+        self.kodi = kodi;
+        self
+    }
+
+    /// Use the Spotify adapter the coordinator starts
+    pub fn with_spotify(mut self, spotify: Arc<SpotifyAdapter>) -> Self {
+        self.spotify = spotify;
+        self
+    }
+
+    /// Use the HTTP JSON adapter the coordinator starts
+    pub fn with_http(mut self, http: Arc<HttpJsonAdapter>) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Use the Plexamp adapter the coordinator starts
+    pub fn with_plexamp(mut self, plexamp: Arc<PlexampAdapter>) -> Self {
+        self.plexamp = plexamp;
+        self
+    }
+
     /// Get the count of active SSE connections
     pub fn active_sse_connections(&self) -> usize {
         self.sse_connections.load(Ordering::Relaxed)
     }
 
+    /// Register a command to `backend` as in flight, or 503 while the adapter
+    /// is draining for a stop (see [`AdapterCoordinator::begin_command`])
+    pub fn begin_command(&self, backend: &'static str) -> Result<InFlightCommand, ApiError> {
+        self.coordinator.begin_command(backend).map_err(|e| {
+            ApiError::new(ErrorCode::BackendUnavailable, e.to_string()).with_backend(backend)
+        })
+    }
+
+    /// Reject the request unless it carries an admin credential (see
+    /// [`TokenStore::is_admin`]). Until one exists there is nothing to
+    /// present, so the first admin token can be made.
+    pub fn require_admin(&self, headers: &HeaderMap) -> Result<(), ApiError> {
+        if !self.tokens.has_admin_credential() {
+            return Ok(());
+        }
+        let presented = headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        match presented {
+            Some(token) if self.tokens.is_admin(token) => Ok(()),
+            Some(_) => Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Settings and credentials need an admin token",
+            )),
+            None => Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Settings and credentials need an admin token",
+            )),
+        }
+    }
+
+    /// Reject commands to a locked zone (see [`ZoneMetadata::locked`]) unless
+    /// `caller` is an admin
+    ///
+    /// [`ZoneMetadata::locked`]: crate::bus::ZoneMetadata::locked
+    pub fn check_zone_lock(&self, zone_id: &str, caller: Caller) -> Result<(), ApiError> {
+        let locked = self
+            .aggregator
+            .metadata()
+            .get(zone_id)
+            .is_some_and(|m| m.locked);
+        if locked && caller == Caller::Restricted {
+            return Err(ApiError::new(
+                ErrorCode::ZoneLocked,
+                format!("Zone is locked: {}", zone_id),
+            ));
+        }
+        Ok(())
+    }
+
+    /// HQPlayer instance driving a zone: the instance of an `hqplayer:` zone,
+    /// or the one the zone is linked to
+    pub async fn hqp_for_zone(&self, zone_id: &str) -> Option<Arc<HqpAdapter>> {
+        match zone_id.split_once(':') {
+            Some(("hqplayer", name)) => Some(
+                self.hqp_instances
+                    .get(name)
+                    .await
+                    .unwrap_or_else(|| self.hqplayer.clone()),
+            ),
+            _ => self.hqp_zone_links.get_adapter_for_zone(zone_id).await,
+        }
+    }
+
     /// Fetch image from the appropriate adapter based on zone_id prefix
     ///
-    /// Routes to the correct backend (Roon, LMS, OpenHome) based on the zone_id
-    /// prefix and fetches the image using that adapter's API.
+    /// Routes to the correct backend (Roon, LMS, OpenHome, Snapcast, HEOS, Kodi, Spotify, HTTP JSON, Plexamp) based on the zone_id
+    /// prefix and fetches the image using that adapter's API, then resizes and
+    /// re-encodes it per `options` so every backend honors the same parameters.
     ///
     /// Note: UPnP zones don't support image retrieval as the protocol doesn't
     /// expose album art URLs in a standardized way that can be proxied.
-    ///
-    /// If `format` is Some("rgb565"), converts to RGB565 format for ESP32 LCDs.
     pub async fn get_image(
         &self,
         zone_id: &str,
         image_key: &str,
-        width: Option<u32>,
-        height: Option<u32>,
-        format: Option<&str>,
+        options: &ArtworkOptions,
     ) -> anyhow::Result<crate::bus::ImageData> {
         use crate::bus::ImageData;
-        use crate::knobs::image::jpeg_to_rgb565;
+
+        let (width, height) = (options.width, options.height);
 
         // Fetch raw image from appropriate adapter
         let raw_image = if zone_id.starts_with("lms:") {
             let (content_type, data) = self.lms.get_artwork(image_key, width, height).await?;
             ImageData { content_type, data }
         } else if zone_id.starts_with("openhome:") {
+            // Renderers serve full-size art on every request; cache the processed result
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
+            }
             let img = self.openhome.get_image(image_key).await?;
-            ImageData {
-                content_type: img.content_type,
-                data: img.data,
+            let processed = apply_artwork_options(
+                ImageData {
+                    content_type: img.content_type,
+                    data: img.data,
+                },
+                options,
+            );
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
+        } else if zone_id.starts_with("snapcast:") {
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
+            }
+            let processed =
+                apply_artwork_options(self.snapcast.get_image(image_key).await?, options);
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
+        } else if zone_id.starts_with("heos:") {
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
+            }
+            let processed = apply_artwork_options(self.heos.get_image(image_key).await?, options);
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
+        } else if zone_id.starts_with("kodi:") {
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
+            }
+            let processed = apply_artwork_options(self.kodi.get_image(image_key).await?, options);
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
+        } else if zone_id.starts_with("spotify:") {
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
+            }
+            let processed =
+                apply_artwork_options(self.spotify.get_image(image_key).await?, options);
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
+        } else if let Some(raw_id) = zone_id.strip_prefix("http:") {
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
+            }
+            let processed =
+                apply_artwork_options(self.http.get_image(raw_id, image_key).await?, options);
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
+        } else if let Some(host) = zone_id.strip_prefix("plexamp:") {
+            let cache_key = ArtworkCache::key(image_key, options);
+            if let Some(cached) = self.artwork_cache.get(&cache_key) {
+                return Ok(cached);
             }
+            let processed =
+                apply_artwork_options(self.plexamp.get_image(host, image_key).await?, options);
+            self.artwork_cache.insert(cache_key, processed.clone());
+            return Ok(processed);
         } else if zone_id.starts_with("upnp:") {
             anyhow::bail!(
                 "UPnP zones don't support image retrieval - the protocol doesn't expose album art URLs"
@@ -137,36 +503,27 @@ impl AppState {
             anyhow::bail!("Unknown zone type for image: {}", zone_id)
         };
 
-        // Convert to RGB565 if requested (for ESP32 LCD displays)
-        if format == Some("rgb565") {
-            // Use square dimensions when only one side specified (matches adapter behavior)
-            let (target_w, target_h) = match (width, height) {
-                (Some(w), Some(h)) => (w, h),
-                (Some(w), None) => (w, w),
-                (None, Some(h)) => (h, h),
-                (None, None) => (240, 240),
-            };
-
-            match jpeg_to_rgb565(&raw_image.data, target_w, target_h) {
-                Ok(rgb565) => Ok(ImageData {
-                    content_type: "application/octet-stream".to_string(),
-                    data: rgb565.data,
-                }),
-                Err(_) => {
-                    // Fall back to original on conversion error
-                    Ok(raw_image)
-                }
-            }
-        } else {
-            Ok(raw_image)
-        }
+        Ok(apply_artwork_options(raw_image, options))
     }
 }
 
-/// Error response
-#[derive(Serialize)]
-struct ErrorResponse {
-    error: String,
+/// Resize/re-encode a fetched image, falling back to the original on error
+/// (callers detect a failed RGB565 conversion by its content type)
+fn apply_artwork_options(
+    image: crate::bus::ImageData,
+    options: &ArtworkOptions,
+) -> crate::bus::ImageData {
+    match crate::knobs::image::encode_artwork(&image.data, options) {
+        Ok(Some(encoded)) => crate::bus::ImageData {
+            content_type: encoded.content_type.to_string(),
+            data: encoded.data,
+        },
+        Ok(None) => image,
+        Err(e) => {
+            tracing::debug!("Artwork conversion failed, serving original: {}", e);
+            image
+        }
+    }
 }
 
 /// Generic zones response wrapper - clients expect {zones: [...]}
@@ -224,6 +581,33 @@ pub async fn status_handler(State(state): State<AppState>) -> Json<StatusRespons
     })
 }
 
+/// GET /healthz - Liveness probe (503 once shutdown has begun)
+pub async fn healthz_handler(State(state): State<AppState>) -> Response {
+    if state.shutdown.is_cancelled() {
+        return ApiError::new(ErrorCode::ShuttingDown, "Server is shutting down").into_response();
+    }
+    Json(serde_json::json!({ "status": "ok" })).into_response()
+}
+
+/// GET /metrics - Bridge and per-zone metrics in the Prometheus text format
+pub async fn metrics_handler(State(state): State<AppState>) -> Response {
+    let bridge = crate::metrics::BridgeMetrics {
+        uptime_secs: state.start_time.elapsed().as_secs(),
+        sse_connections: state.active_sse_connections(),
+        bus_subscribers: state.bus.subscriber_count(),
+        adapters: adapter_statuses(&state).await,
+    };
+    let zones = state.aggregator.get_zones().await;
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            crate::metrics::CONTENT_TYPE,
+        )],
+        state.metrics.render(&zones, &bridge),
+    )
+        .into_response()
+}
+
 // =============================================================================
 // Roon handlers
 // =============================================================================
@@ -251,13 +635,12 @@ pub async fn roon_zone_handler(
 ) -> impl IntoResponse {
     match state.roon.get_zone(&zone_id).await {
         Some(zone) => (StatusCode::OK, Json(zone)).into_response(),
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Zone not found: {}", zone_id),
-            }),
+        None => ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
         )
-            .into_response(),
+        .with_backend("roon")
+        .into_response(),
     }
 }
 
@@ -271,17 +654,39 @@ pub struct ControlRequest {
 /// POST /roon/control - Control playback
 pub async fn roon_control_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ControlRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.check_zone_lock(
+        PrefixedZoneId::roon(&req.zone_id).as_str(),
+        Caller::from_headers(&state, &headers),
+    ) {
+        return e.into_response();
+    }
+    let _command = match state.begin_command("roon") {
+        Ok(command) => command,
+        Err(e) => return e.into_response(),
+    };
+    let prefixed_zone_id = PrefixedZoneId::roon(&req.zone_id);
+    state
+        .aggregator
+        .apply_optimistic(prefixed_zone_id.as_str(), &req.action)
+        .await;
+
     match state.roon.control(&req.zone_id, &req.action).await {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
-            .into_response(),
+        Ok(()) => {
+            state.metrics.record_command(&prefixed_zone_id, &req.action);
+            (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response()
+        }
+        Err(e) => {
+            state
+                .aggregator
+                .rollback_optimistic(prefixed_zone_id.as_str())
+                .await;
+            ApiError::from_backend(ErrorCode::CommandFailed, &e)
+                .with_backend("roon")
+                .into_response()
+        }
     }
 }
 
@@ -297,20 +702,36 @@ pub struct VolumeRequest {
 /// POST /roon/volume - Change volume
 pub async fn roon_volume_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<VolumeRequest>,
 ) -> impl IntoResponse {
+    // Volume is addressed by output; the lock belongs to the output's zone
+    let zone = state
+        .roon
+        .get_zones()
+        .await
+        .into_iter()
+        .find(|z| z.outputs.iter().any(|o| o.output_id == req.output_id));
+    if let Some(zone) = zone {
+        let zone_id = PrefixedZoneId::roon(&zone.zone_id);
+        if let Err(e) =
+            state.check_zone_lock(zone_id.as_str(), Caller::from_headers(&state, &headers))
+        {
+            return e.into_response();
+        }
+    }
+    let _command = match state.begin_command("roon") {
+        Ok(command) => command,
+        Err(e) => return e.into_response(),
+    };
     match state
         .roon
         .change_volume(&req.output_id, req.value, req.relative)
         .await
     {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("roon")
             .into_response(),
     }
 }
@@ -323,36 +744,46 @@ pub struct ImageQuery {
     pub width: Option<u32>,
     #[serde(default)]
     pub height: Option<u32>,
+    /// `jpeg`, `png`, `webp` or `rgb565`; defaults to the backend's format
+    #[serde(default)]
+    pub format: Option<OutputFormat>,
+    /// JPEG quality 1-100
+    #[serde(default)]
+    pub quality: Option<u8>,
 }
 
 /// GET /roon/image - fetch album art
+///
+/// Images are addressed by Roon's immutable `image_key`, so responses are cacheable
+/// and carry `ETag`/`Last-Modified` for conditional requests.
 pub async fn roon_image_handler(
     State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
     axum::extract::Query(params): axum::extract::Query<ImageQuery>,
 ) -> impl IntoResponse {
+    let options = ArtworkOptions {
+        width: params.width,
+        height: params.height,
+        format: params.format,
+        quality: params.quality,
+    };
     match state
         .roon
         .get_image(&params.image_key, params.width, params.height)
         .await
     {
         Ok(image_data) => {
-            let headers = [(
-                axum::http::header::CONTENT_TYPE,
-                image_data
-                    .content_type
-                    .parse()
-                    .unwrap_or(axum::http::HeaderValue::from_static("image/jpeg")),
-            )];
-            (StatusCode::OK, headers, image_data.data).into_response()
+            let image_data = apply_artwork_options(image_data, &options);
+            let validators = crate::http_cache::Validators::for_body(
+                &image_data.data,
+                crate::http_cache::CACHE_IMMUTABLE_ARTWORK,
+            );
+            validators.respond(&headers, &image_data.content_type, image_data.data)
         }
         Err(e) => {
             tracing::warn!("Image fetch failed: {}", e);
-            (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
-            )
+            ApiError::new(ErrorCode::ImageNotFound, e.to_string())
+                .with_backend("roon")
                 .into_response()
         }
     }
@@ -374,23 +805,15 @@ pub async fn hqp_pipeline_handler(State(state): State<AppState>) -> impl IntoRes
     // Quick check - if not connected, return error immediately (don't block on timeout)
     let status = state.hqplayer.get_status().await;
     if !status.connected {
-        return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "HQPlayer not connected".to_string(),
-            }),
-        )
+        return ApiError::new(ErrorCode::BackendUnavailable, "HQPlayer not connected")
+            .with_backend("hqplayer")
             .into_response();
     }
 
     match state.hqplayer.get_pipeline_status().await {
         Ok(pipeline) => (StatusCode::OK, Json(pipeline)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::BackendError, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -408,12 +831,8 @@ pub async fn hqp_control_handler(
 ) -> impl IntoResponse {
     match state.hqplayer.control(&req.action).await {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -431,12 +850,8 @@ pub async fn hqp_volume_handler(
 ) -> impl IntoResponse {
     match state.hqplayer.set_volume(req.value).await {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -465,12 +880,8 @@ pub async fn hqp_setting_handler(
 
     match result {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -487,6 +898,11 @@ pub async fn hqp_pipeline_update_handler(
     State(state): State<AppState>,
     Json(req): Json<HqpPipelineRequest>,
 ) -> impl IntoResponse {
+    apply_pipeline_setting(&state.hqplayer, req).await
+}
+
+/// Apply a pipeline setting on one HQPlayer instance
+async fn apply_pipeline_setting(hqplayer: &HqpAdapter, req: HqpPipelineRequest) -> Response {
     // Convert value to u32 - accept both numeric and string representations
     // Note: HQPlayer mode values can be negative (e.g., -1 for PCM), so we parse as i64 first
     // and cast to u32 to preserve the bit pattern
@@ -505,33 +921,27 @@ pub async fn hqp_pipeline_update_handler(
         "dither",
     ];
     if !valid_settings.contains(&req.setting.as_str()) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: format!("Invalid setting. Valid: {}", valid_settings.join(", ")),
-            }),
+        return ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("Invalid setting. Valid: {}", valid_settings.join(", ")),
         )
-            .into_response();
+        .into_response();
     }
 
     let result = match req.setting.as_str() {
-        "mode" => state.hqplayer.set_mode(value).await,
-        "filter1x" => state.hqplayer.set_filter_1x(value).await,
-        "filterNx" | "filternx" => state.hqplayer.set_filter_nx(value).await,
-        "shaper" => state.hqplayer.set_shaper(value).await,
-        "samplerate" => state.hqplayer.set_rate(value).await,
-        "dither" => state.hqplayer.set_shaper(value).await, // dither uses same API
+        "mode" => hqplayer.set_mode(value).await,
+        "filter1x" => hqplayer.set_filter_1x(value).await,
+        "filterNx" | "filternx" => hqplayer.set_filter_nx(value).await,
+        "shaper" => hqplayer.set_shaper(value).await,
+        "samplerate" => hqplayer.set_rate(value).await,
+        "dither" => hqplayer.set_shaper(value).await, // dither uses same API
         _ => Err(anyhow::anyhow!("Unknown setting: {}", req.setting)),
     };
 
     match result {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::BackendError, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -540,12 +950,8 @@ pub async fn hqp_pipeline_update_handler(
 pub async fn hqp_profiles_handler(State(state): State<AppState>) -> impl IntoResponse {
     match state.hqplayer.fetch_profiles().await {
         Ok(profiles) => (StatusCode::OK, Json(profiles)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::BackendError, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -563,12 +969,8 @@ pub async fn hqp_load_profile_handler(
 ) -> impl IntoResponse {
     match state.hqplayer.load_profile(&req.profile).await {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -600,12 +1002,8 @@ pub async fn hqp_matrix_profiles_handler(State(state): State<AppState>) -> impl
             })),
         )
             .into_response(),
-        (Err(e), _) | (_, Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        (Err(e), _) | (_, Err(e)) => ApiError::from_backend(ErrorCode::BackendError, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -623,12 +1021,8 @@ pub async fn hqp_set_matrix_profile_handler(
 ) -> impl IntoResponse {
     match state.hqplayer.set_matrix_profile(req.profile).await {
         Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -660,13 +1054,12 @@ pub async fn lms_player_handler(
 ) -> impl IntoResponse {
     match state.lms.get_cached_player(&player_id).await {
         Some(player) => (StatusCode::OK, Json(player)).into_response(),
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Player not found: {}", player_id),
-            }),
+        None => ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Player not found: {}", player_id),
         )
-            .into_response(),
+        .with_backend("lms")
+        .into_response(),
     }
 }
 
@@ -682,20 +1075,32 @@ pub struct LmsControlRequest {
 /// POST /lms/control - Control LMS player
 pub async fn lms_control_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<LmsControlRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.check_zone_lock(
+        PrefixedZoneId::lms(&req.player_id).as_str(),
+        Caller::from_headers(&state, &headers),
+    ) {
+        return e.into_response();
+    }
+    let _command = match state.begin_command("lms") {
+        Ok(command) => command,
+        Err(e) => return e.into_response(),
+    };
     match state
         .lms
         .control(&req.player_id, &req.action, req.value)
         .await
     {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Ok(()) => {
+            state
+                .metrics
+                .record_command(&PrefixedZoneId::lms(&req.player_id), &req.action);
+            (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response()
+        }
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("lms")
             .into_response(),
     }
 }
@@ -712,24 +1117,69 @@ pub struct LmsVolumeRequest {
 /// POST /lms/volume - Change LMS player volume
 pub async fn lms_volume_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<LmsVolumeRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.check_zone_lock(
+        PrefixedZoneId::lms(&req.player_id).as_str(),
+        Caller::from_headers(&state, &headers),
+    ) {
+        return e.into_response();
+    }
+    let _command = match state.begin_command("lms") {
+        Ok(command) => command,
+        Err(e) => return e.into_response(),
+    };
     match state
         .lms
         .change_volume(&req.player_id, req.value, req.relative)
         .await
     {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Ok(()) => {
+            state
+                .metrics
+                .record_command(&PrefixedZoneId::lms(&req.player_id), "volume");
+            (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response()
+        }
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("lms")
             .into_response(),
     }
 }
 
+/// LMS rescan request; without a body only new and changed files are scanned
+#[derive(Deserialize)]
+pub struct LmsRescanRequest {
+    #[serde(default)]
+    pub mode: crate::adapters::lms::LmsRescanMode,
+}
+
+/// POST /lms/rescan - Start a library rescan
+pub async fn lms_rescan_handler(
+    State(state): State<AppState>,
+    body: Option<Json<LmsRescanRequest>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let mode = body.map(|Json(req)| req.mode).unwrap_or_default();
+    state
+        .lms
+        .rescan(mode)
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("lms"))?;
+    Ok(Json(serde_json::json!({ "ok": true, "mode": mode })))
+}
+
+/// GET /lms/rescan/status - Library scan progress
+pub async fn lms_rescan_status_handler(
+    State(state): State<AppState>,
+) -> Result<Json<crate::adapters::lms::LmsScanStatus>, ApiError> {
+    let status = state
+        .lms
+        .scan_status()
+        .await
+        .map_err(|e| ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms"))?;
+    Ok(Json(status))
+}
+
 /// LMS discovery request query params
 #[derive(Deserialize)]
 pub struct LmsDiscoverRequest {
@@ -747,12 +1197,8 @@ pub async fn lms_discover_handler(Query(params): Query<LmsDiscoverRequest>) -> i
             Json(serde_json::json!({ "discovered": servers })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Discovery failed: {}", e),
-            }),
-        )
+        Err(e) => ApiError::new(ErrorCode::BackendError, format!("Discovery failed: {}", e))
+            .with_backend("lms")
             .into_response(),
     }
 }
@@ -761,7 +1207,6 @@ pub async fn lms_discover_handler(Query(params): Query<LmsDiscoverRequest>) -> i
 // SSE Events
 // =============================================================================
 
-/// GET /events - Server-Sent Events stream
 /// Guard that decrements SSE connection count on drop
 struct SseConnectionGuard {
     counter: Arc<AtomicUsize>,
@@ -774,6 +1219,7 @@ impl Drop for SseConnectionGuard {
     }
 }
 
+/// GET /events - Server-Sent Events stream
 pub async fn events_handler(
     State(state): State<AppState>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
@@ -784,25 +1230,22 @@ pub async fn events_handler(
     let guard = SseConnectionGuard {
         counter: state.sse_connections.clone(),
     };
-    let shutdown = state.shutdown.clone();
     let rx = state.bus.subscribe();
 
-    // Create stream that terminates on shutdown
-    // Use futures::StreamExt::take_until via UFCS (tokio_stream doesn't have it)
-    let base_stream = BroadcastStream::new(rx);
-    let with_shutdown =
-        futures::StreamExt::take_until(base_stream, async move { shutdown.cancelled().await });
-
-    let stream = with_shutdown
-        .filter_map(|result| match result {
-            Ok(event) => {
+    // Each connection drains the bus into its own bounded outbox, so a slow
+    // client only falls behind itself (see fanout.rs); ends on shutdown
+    let stream = fanout::outbox_stream(rx, &state.shutdown, fanout::OUTBOX_CAPACITY)
+        .filter_map(|item| match item {
+            fanout::Outgoing::Event(event) => {
                 // Serialize event to JSON
                 match serde_json::to_string(&event) {
                     Ok(json) => Some(Ok(Event::default().data(json))),
                     Err(_) => None,
                 }
             }
-            Err(_) => None, // Skip lagged messages
+            fanout::Outgoing::Dropped(count) => Some(Ok(Event::default()
+                .event("dropped")
+                .data(serde_json::json!({ "count": count }).to_string()))),
         })
         // Use map + flatten to attach guard lifetime to stream
         // When stream ends, guard is dropped (decrementing counter)
@@ -818,6 +1261,54 @@ pub async fn events_handler(
     )
 }
 
+/// Default and maximum time a long-poll request waits for new events
+const EVENT_POLL_DEFAULT_WAIT: Duration = Duration::from_secs(25);
+const EVENT_POLL_MAX_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+pub struct EventPollQuery {
+    /// Cursor from the previous response's `next`; omit to get the current cursor
+    pub since: Option<u64>,
+    /// Seconds to wait for new events (default 25, max 60)
+    pub timeout: Option<u64>,
+}
+
+/// GET /events/poll?since= - Long-poll fallback for networks that break SSE.
+///
+/// Returns retained events after `since` immediately if there are any, otherwise
+/// waits up to `timeout` seconds for the next one. Without `since`, returns the
+/// current cursor and no events.
+pub async fn events_poll_handler(
+    State(state): State<AppState>,
+    Query(query): Query<EventPollQuery>,
+) -> Json<crate::bus::EventsSince> {
+    let Some(since) = query.since else {
+        return Json(crate::bus::EventsSince {
+            events: Vec::new(),
+            next: state.bus.last_seq(),
+            truncated: false,
+        });
+    };
+
+    // Subscribe before reading history so an event published in between still wakes us
+    let mut rx = state.bus.subscribe();
+    let pending = state.bus.events_since(since);
+    if !pending.events.is_empty() || pending.truncated {
+        return Json(pending);
+    }
+
+    let wait = query
+        .timeout
+        .map(Duration::from_secs)
+        .unwrap_or(EVENT_POLL_DEFAULT_WAIT)
+        .clamp(Duration::from_secs(1), EVENT_POLL_MAX_WAIT);
+    tokio::select! {
+        _ = tokio::time::timeout(wait, rx.recv()) => {}
+        _ = state.shutdown.cancelled() => {}
+    }
+    Json(state.bus.events_since(since))
+}
+
 // =============================================================================
 // OpenHome handlers
 // =============================================================================
@@ -845,13 +1336,12 @@ pub async fn openhome_now_playing_handler(
 ) -> impl IntoResponse {
     match state.openhome.get_now_playing(&zone_id).await {
         Some(np) => (StatusCode::OK, Json(np)).into_response(),
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Zone not found: {}", zone_id),
-            }),
+        None => ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
         )
-            .into_response(),
+        .with_backend("openhome")
+        .into_response(),
     }
 }
 
@@ -867,20 +1357,32 @@ pub struct OpenHomeControlRequest {
 /// POST /openhome/control - Control OpenHome device
 pub async fn openhome_control_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<OpenHomeControlRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.check_zone_lock(
+        PrefixedZoneId::openhome(&req.zone_id).as_str(),
+        Caller::from_headers(&state, &headers),
+    ) {
+        return e.into_response();
+    }
+    let _command = match state.begin_command("openhome") {
+        Ok(command) => command,
+        Err(e) => return e.into_response(),
+    };
     match state
         .openhome
         .control(&req.zone_id, &req.action, req.value)
         .await
     {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Ok(()) => {
+            state
+                .metrics
+                .record_command(&PrefixedZoneId::openhome(&req.zone_id), &req.action);
+            (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response()
+        }
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("openhome")
             .into_response(),
     }
 }
@@ -912,13 +1414,12 @@ pub async fn upnp_now_playing_handler(
 ) -> impl IntoResponse {
     match state.upnp.get_now_playing(&zone_id).await {
         Some(np) => (StatusCode::OK, Json(np)).into_response(),
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Renderer not found: {}", zone_id),
-            }),
+        None => ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Renderer not found: {}", zone_id),
         )
-            .into_response(),
+        .with_backend("upnp")
+        .into_response(),
     }
 }
 
@@ -934,20 +1435,32 @@ pub struct UPnPControlRequest {
 /// POST /upnp/control - Control UPnP renderer
 pub async fn upnp_control_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<UPnPControlRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.check_zone_lock(
+        PrefixedZoneId::upnp(&req.zone_id).as_str(),
+        Caller::from_headers(&state, &headers),
+    ) {
+        return e.into_response();
+    }
+    let _command = match state.begin_command("upnp") {
+        Ok(command) => command,
+        Err(e) => return e.into_response(),
+    };
     match state
         .upnp
         .control(&req.zone_id, &req.action, req.value)
         .await
     {
-        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Ok(()) => {
+            state
+                .metrics
+                .record_command(&PrefixedZoneId::upnp(&req.zone_id), &req.action);
+            (StatusCode::OK, Json(serde_json::json!({"ok": true}))).into_response()
+        }
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("upnp")
             .into_response(),
     }
 }
@@ -969,8 +1482,12 @@ pub struct LmsConfigRequest {
 /// POST /lms/configure - Configure LMS connection
 pub async fn lms_configure_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<LmsConfigRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
     // Stop existing connection if any
     state.lms.stop().await;
 
@@ -991,12 +1508,8 @@ pub async fn lms_configure_handler(
             })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("lms")
             .into_response(),
     }
 }
@@ -1011,13 +1524,33 @@ pub struct HqpConfigRequest {
     pub web_port: Option<u16>,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Web UI over HTTPS; unchanged when omitted
+    #[serde(default)]
+    pub web_https: Option<bool>,
+    /// Web UI certificate pin; unchanged when omitted, cleared when empty
+    #[serde(default)]
+    pub web_cert_sha256: Option<String>,
 }
 
 /// POST /hqplayer/configure - Configure HQPlayer connection
 pub async fn hqp_configure_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<HqpConfigRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
+    if let Err(e) = state
+        .hqplayer
+        .update_web_tls(req.web_https, req.web_cert_sha256.as_deref())
+        .await
+    {
+        return ApiError::new(ErrorCode::InvalidRequest, e.to_string())
+            .with_backend("hqplayer")
+            .into_response();
+    }
+
     // Configure the adapter
     state
         .hqplayer
@@ -1078,6 +1611,8 @@ pub async fn hqp_config_handler(State(state): State<AppState>) -> impl IntoRespo
         "host": status.host,
         "port": status.port,
         "web_port": status.web_port,
+        "web_https": status.web_https,
+        "web_cert_sha256": state.hqplayer.web_tls().await.web_cert_sha256,
         "has_web_credentials": has_web_creds
     }))
 }
@@ -1205,34 +1740,33 @@ pub struct HqpAddInstanceRequest {
     pub web_port: Option<u16>,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// `connect_timeout_secs`, `response_timeout_secs` and `reconnect_attempts`
+    #[serde(flatten)]
+    pub connection: HqpConnectionSettings,
+    /// `web_https` and `web_cert_sha256`
+    #[serde(flatten)]
+    pub web_tls: HqpWebTls,
 }
 
 /// POST /hqp/instances - Add or update an HQPlayer instance
 pub async fn hqp_add_instance_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<HqpAddInstanceRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
     if req.name.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Instance name is required".to_string(),
-            }),
-        )
+        return ApiError::new(ErrorCode::InvalidRequest, "Instance name is required")
             .into_response();
     }
 
     if req.host.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Host is required".to_string(),
-            }),
-        )
-            .into_response();
+        return ApiError::new(ErrorCode::InvalidRequest, "Host is required").into_response();
     }
 
-    let _adapter = state
+    if let Err(e) = state
         .hqp_instances
         .add_instance(
             req.name.clone(),
@@ -1241,8 +1775,13 @@ pub async fn hqp_add_instance_handler(
             req.web_port,
             req.username,
             req.password,
+            req.connection,
+            req.web_tls,
         )
-        .await;
+        .await
+    {
+        return ApiError::new(ErrorCode::InvalidRequest, e.to_string()).into_response();
+    }
 
     (
         StatusCode::OK,
@@ -1260,9 +1799,14 @@ pub async fn hqp_add_instance_handler(
 pub async fn hqp_remove_instance_handler(
     State(state): State<AppState>,
     Path(name): Path<String>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
     // Remove zone links pointing to this instance first
     let _links_removed = state.hqp_zone_links.remove_links_for_instance(&name).await;
+    state.hqp_naa.assign(&name, None).await;
 
     if state.hqp_instances.remove_instance(&name).await {
         (
@@ -1271,13 +1815,11 @@ pub async fn hqp_remove_instance_handler(
         )
             .into_response()
     } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Instance not found: {}", name),
-            }),
+        ApiError::new(
+            ErrorCode::InstanceNotFound,
+            format!("Instance not found: {}", name),
         )
-            .into_response()
+        .into_response()
     }
 }
 
@@ -1289,24 +1831,18 @@ pub async fn hqp_instance_profiles_handler(
     let adapter = match state.hqp_instances.get(&name).await {
         Some(a) => a,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Instance not found: {}", name),
-                }),
+            return ApiError::new(
+                ErrorCode::InstanceNotFound,
+                format!("Instance not found: {}", name),
             )
-                .into_response()
+            .into_response()
         }
     };
 
     match adapter.fetch_profiles().await {
         Ok(profiles) => (StatusCode::OK, Json(profiles)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::BackendError, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -1320,13 +1856,11 @@ pub async fn hqp_instance_load_profile_handler(
     let adapter = match state.hqp_instances.get(&name).await {
         Some(a) => a,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Instance not found: {}", name),
-                }),
+            return ApiError::new(
+                ErrorCode::InstanceNotFound,
+                format!("Instance not found: {}", name),
             )
-                .into_response()
+            .into_response()
         }
     };
 
@@ -1336,12 +1870,8 @@ pub async fn hqp_instance_load_profile_handler(
             Json(serde_json::json!({"ok": true, "instance": name, "profile": req.profile})),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -1354,13 +1884,11 @@ pub async fn hqp_instance_matrix_profiles_handler(
     let adapter = match state.hqp_instances.get(&name).await {
         Some(a) => a,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Instance not found: {}", name),
-                }),
+            return ApiError::new(
+                ErrorCode::InstanceNotFound,
+                format!("Instance not found: {}", name),
             )
-                .into_response()
+            .into_response()
         }
     };
 
@@ -1377,12 +1905,8 @@ pub async fn hqp_instance_matrix_profiles_handler(
             })),
         )
             .into_response(),
-        (Err(e), _) | (_, Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        (Err(e), _) | (_, Err(e)) => ApiError::from_backend(ErrorCode::BackendError, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -1402,13 +1926,11 @@ pub async fn hqp_instance_set_matrix_profile_handler(
     let adapter = match state.hqp_instances.get(&name).await {
         Some(a) => a,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Instance not found: {}", name),
-                }),
+            return ApiError::new(
+                ErrorCode::InstanceNotFound,
+                format!("Instance not found: {}", name),
             )
-                .into_response()
+            .into_response()
         }
     };
 
@@ -1418,12 +1940,8 @@ pub async fn hqp_instance_set_matrix_profile_handler(
             Json(serde_json::json!({"ok": true, "instance": name, "value": req.value})),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -1448,26 +1966,18 @@ pub struct ZoneLinkRequest {
 /// POST /hqp/zones/link - Link a zone to an HQPlayer instance
 pub async fn hqp_zone_link_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ZoneLinkRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
     if req.zone_id.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "zone_id is required".to_string(),
-            }),
-        )
-            .into_response();
+        return ApiError::new(ErrorCode::InvalidRequest, "zone_id is required").into_response();
     }
 
     if req.instance.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "instance is required".to_string(),
-            }),
-        )
-            .into_response();
+        return ApiError::new(ErrorCode::InvalidRequest, "instance is required").into_response();
     }
 
     match state
@@ -1484,12 +1994,8 @@ pub async fn hqp_zone_link_handler(
             })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )
+        Err(e) => ApiError::from_backend(ErrorCode::CommandFailed, &e)
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
@@ -1503,16 +2009,14 @@ pub struct ZoneUnlinkRequest {
 /// POST /hqp/zones/unlink - Unlink a zone from HQPlayer
 pub async fn hqp_zone_unlink_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<ZoneUnlinkRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
     if req.zone_id.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "zone_id is required".to_string(),
-            }),
-        )
-            .into_response();
+        return ApiError::new(ErrorCode::InvalidRequest, "zone_id is required").into_response();
     }
 
     let was_linked = state.hqp_zone_links.unlink_zone(&req.zone_id).await;
@@ -1535,19 +2039,158 @@ pub async fn hqp_zone_pipeline_handler(
 ) -> impl IntoResponse {
     match state.hqp_zone_links.get_pipeline_for_zone(&zone_id).await {
         Some(pipeline) => (StatusCode::OK, Json(pipeline)).into_response(),
-        None => (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!(
-                    "Zone {} not linked to HQPlayer or HQPlayer not configured",
-                    zone_id
-                ),
-            }),
+        None => ApiError::new(
+            ErrorCode::NotLinked,
+            format!(
+                "Zone {} not linked to HQPlayer or HQPlayer not configured",
+                zone_id
+            ),
         )
-            .into_response(),
+        .with_backend("hqplayer")
+        .into_response(),
     }
 }
 
+/// POST /hqp/zones/:zone_id/pipeline - Change a pipeline setting on the zone's linked instance
+pub async fn hqp_zone_pipeline_update_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    Json(req): Json<HqpPipelineRequest>,
+) -> impl IntoResponse {
+    match state.hqp_zone_links.get_adapter_for_zone(&zone_id).await {
+        Some(adapter) => apply_pipeline_setting(&adapter, req).await,
+        None => ApiError::new(
+            ErrorCode::NotLinked,
+            format!(
+                "Zone {} not linked to HQPlayer or HQPlayer not configured",
+                zone_id
+            ),
+        )
+        .with_backend("hqplayer")
+        .into_response(),
+    }
+}
+
+// =============================================================================
+// Zone metadata handlers
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct ZoneResolveQuery {
+    pub name: String,
+}
+
+/// GET /zones/resolve?name=... - Find the zone meant by a name
+///
+/// Matches zone names, rooms and aliases loosely ("the kitchen speaker").
+/// `zone_id` is set when one zone matched better than the rest; `matches`
+/// lists every zone that matched, best first.
+pub async fn zone_resolve_handler(
+    State(state): State<AppState>,
+    Query(query): Query<ZoneResolveQuery>,
+) -> Json<serde_json::Value> {
+    let zones: Vec<ZoneNames> = state
+        .aggregator
+        .get_zones()
+        .await
+        .iter()
+        .map(ZoneNames::from_zone)
+        .collect();
+    let matches = zone_resolver::resolve(&zones, &query.name);
+    let best = zone_resolver::best(&matches);
+    Json(serde_json::json!({
+        "name": query.name,
+        "zone_id": best.map(|m| &m.zone_id),
+        "zone_name": best.map(|m| &m.zone_name),
+        "matches": matches,
+    }))
+}
+
+/// GET /zones/metadata - Room, icon, order and color for every zone that has any
+pub async fn zone_metadata_list_handler(
+    State(state): State<AppState>,
+) -> Json<std::collections::HashMap<String, crate::bus::ZoneMetadata>> {
+    Json(state.aggregator.metadata().all())
+}
+
+/// Reject a metadata change that locks or unlocks a zone unless the caller
+/// is an admin
+fn check_lock_change(
+    state: &AppState,
+    zone_id: &str,
+    locked: bool,
+    headers: &HeaderMap,
+) -> Result<(), ApiError> {
+    let was_locked = state
+        .aggregator
+        .metadata()
+        .get(zone_id)
+        .is_some_and(|m| m.locked);
+    if locked != was_locked && Caller::from_headers(state, headers) == Caller::Restricted {
+        return Err(ApiError::new(
+            ErrorCode::Forbidden,
+            "Locking or unlocking a zone needs an admin token",
+        ));
+    }
+    Ok(())
+}
+
+/// PUT /zones/{zone_id}/metadata - Replace a zone's metadata.
+/// The zone does not need to be online; an empty body clears the entry.
+pub async fn zone_metadata_put_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    headers: HeaderMap,
+    Json(metadata): Json<crate::bus::ZoneMetadata>,
+) -> impl IntoResponse {
+    let metadata = match crate::zone_metadata::normalize(metadata) {
+        Ok(m) => m,
+        Err(e) => return ApiError::new(ErrorCode::InvalidRequest, e).into_response(),
+    };
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
+    if let Err(e) = check_lock_change(&state, &zone_id, metadata.locked, &headers) {
+        return e.into_response();
+    }
+
+    state.aggregator.metadata().set(&zone_id, metadata.clone());
+    state
+        .bus
+        .publish(crate::bus::BusEvent::ZoneMetadataChanged {
+            zone_id: zone_id.clone(),
+        });
+
+    Json(serde_json::json!({
+        "ok": true,
+        "zone_id": zone_id,
+        "metadata": metadata
+    }))
+    .into_response()
+}
+
+/// DELETE /zones/{zone_id}/metadata - Clear a zone's metadata
+pub async fn zone_metadata_delete_handler(
+    State(state): State<AppState>,
+    Path(zone_id): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
+    if let Err(e) = check_lock_change(&state, &zone_id, false, &headers) {
+        return e.into_response();
+    }
+    if state.aggregator.metadata().remove(&zone_id) {
+        state
+            .bus
+            .publish(crate::bus::BusEvent::ZoneMetadataChanged {
+                zone_id: zone_id.clone(),
+            });
+    }
+    Json(serde_json::json!({ "ok": true, "zone_id": zone_id })).into_response()
+}
+
 // =============================================================================
 // HQPlayer discovery handler
 // =============================================================================
@@ -1569,16 +2212,64 @@ pub async fn hqp_discover_handler(Query(params): Query<HqpDiscoverRequest>) -> i
             Json(serde_json::json!({ "discovered": instances })),
         )
             .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Discovery failed: {}", e),
-            }),
-        )
+        Err(e) => ApiError::new(ErrorCode::BackendError, format!("Discovery failed: {}", e))
+            .with_backend("hqplayer")
             .into_response(),
     }
 }
 
+// =============================================================================
+// Diagnostics handlers
+// =============================================================================
+
+/// POST /debug/connectivity - Check reachability of configured backends and multicast
+pub async fn debug_connectivity_handler(State(state): State<AppState>) -> impl IntoResponse {
+    use crate::diagnostics::{run_checks, TcpTarget};
+
+    let mut targets = Vec::new();
+    for instance in state.hqp_instances.list_instances().await {
+        if let Some(host) = instance.host {
+            targets.push(TcpTarget {
+                check: format!("hqplayer:{}", instance.name),
+                host,
+                port: instance.port,
+            });
+        }
+    }
+
+    let lms = state.lms.get_status().await;
+    if let Some(host) = lms.host {
+        targets.push(TcpTarget {
+            check: "lms_jsonrpc".to_string(),
+            host: host.clone(),
+            port: lms.port,
+        });
+        targets.push(TcpTarget {
+            check: "lms_cli".to_string(),
+            host,
+            port: crate::adapters::lms::CLI_PORT,
+        });
+    }
+
+    Json(run_checks(targets).await)
+}
+
+/// GET /debug/adapters/{name}/ops - Recent backend operations of one adapter
+pub async fn adapter_ops_handler(Path(name): Path<String>) -> impl IntoResponse {
+    use crate::adapters::ops;
+
+    if !ops::ADAPTERS.contains(&name.as_str()) {
+        return ApiError::new(
+            ErrorCode::AdapterNotFound,
+            format!("Unknown adapter: {}", name),
+        )
+        .into_response();
+    }
+
+    let ops = ops::recent(&name);
+    Json(serde_json::json!({ "adapter": name, "ops": ops })).into_response()
+}
+
 // =============================================================================
 // App settings handlers
 // =============================================================================
@@ -1595,6 +2286,10 @@ pub struct AppSettings {
     pub hide_lms_page: bool,
     #[serde(default)]
     pub adapters: AdapterSettings,
+    #[serde(default)]
+    pub polling: PollingSettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -1609,6 +2304,20 @@ pub struct AdapterSettings {
     pub lms: bool,
     #[serde(default)]
     pub hqplayer: bool,
+    #[serde(default)]
+    pub airplay: bool,
+    #[serde(default)]
+    pub snapcast: bool,
+    #[serde(default)]
+    pub heos: bool,
+    #[serde(default)]
+    pub kodi: bool,
+    #[serde(default)]
+    pub spotify: bool,
+    #[serde(default)]
+    pub http: bool,
+    #[serde(default)]
+    pub plexamp: bool,
 }
 
 fn default_true() -> bool {
@@ -1627,7 +2336,16 @@ impl Default for AppSettings {
                 openhome: false,
                 lms: false,
                 hqplayer: false,
+                airplay: false,
+                snapcast: false,
+                heos: false,
+                kodi: false,
+                spotify: false,
+                http: false,
+                plexamp: false,
             },
+            polling: PollingSettings::default(),
+            backup: BackupSettings::default(),
         }
     }
 }
@@ -1663,7 +2381,8 @@ pub fn load_app_settings() -> AppSettings {
     settings
 }
 
-fn save_app_settings(settings: &AppSettings) -> bool {
+/// Save app settings to disk
+pub fn save_app_settings(settings: &AppSettings) -> bool {
     let path = settings_path();
     if let Some(parent) = path.parent() {
         let _ = std::fs::create_dir_all(parent);
@@ -1694,14 +2413,19 @@ pub async fn api_settings_get_handler() -> impl IntoResponse {
 /// POST /api/settings - Update app settings with dynamic adapter enable/disable
 pub async fn api_settings_post_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(new_settings): Json<AppSettings>,
 ) -> impl IntoResponse {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
     // Load current settings to compare
     let old_settings = load_app_settings();
 
     // Save the new settings
     if !save_app_settings(&new_settings) {
-        return Json(serde_json::json!({"ok": false, "error": "Failed to save settings"}));
+        return Json(serde_json::json!({"ok": false, "error": "Failed to save settings"}))
+            .into_response();
     }
 
     // Compare adapter enabled states and start/stop as needed
@@ -1719,6 +2443,13 @@ pub async fn api_settings_post_handler(
         ("openhome", old_adapters.openhome != new_adapters.openhome),
         ("upnp", old_adapters.upnp != new_adapters.upnp),
         ("hqplayer", old_adapters.hqplayer != new_adapters.hqplayer),
+        ("airplay", old_adapters.airplay != new_adapters.airplay),
+        ("snapcast", old_adapters.snapcast != new_adapters.snapcast),
+        ("heos", old_adapters.heos != new_adapters.heos),
+        ("kodi", old_adapters.kodi != new_adapters.kodi),
+        ("spotify", old_adapters.spotify != new_adapters.spotify),
+        ("http", old_adapters.http != new_adapters.http),
+        ("plexamp", old_adapters.plexamp != new_adapters.plexamp),
     ];
 
     for (name, changed) in adapter_changes {
@@ -1733,6 +2464,13 @@ pub async fn api_settings_post_handler(
             "openhome" => new_adapters.openhome,
             "upnp" => new_adapters.upnp,
             "hqplayer" => new_adapters.hqplayer,
+            "airplay" => new_adapters.airplay,
+            "snapcast" => new_adapters.snapcast,
+            "heos" => new_adapters.heos,
+            "kodi" => new_adapters.kodi,
+            "spotify" => new_adapters.spotify,
+            "http" => new_adapters.http,
+            "plexamp" => new_adapters.plexamp,
             _ => continue,
         };
 
@@ -1744,18 +2482,232 @@ pub async fn api_settings_post_handler(
             if now_enabled {
                 tracing::info!("Dynamically enabling adapter: {}", name);
                 if adapter.can_start().await {
-                    if let Err(e) = adapter.start().await {
+                    if let Err(e) = coord.start(adapter.as_ref()).await {
                         tracing::warn!("Failed to start adapter {}: {}", name, e);
                     }
                 }
             } else {
+                // Drain in-flight commands first so none is cut off half-applied
                 tracing::info!("Dynamically disabling adapter: {}", name);
-                adapter.stop().await;
+                coord.drain_and_stop(adapter.as_ref()).await;
             }
         }
     }
 
-    Json(serde_json::json!({"ok": true}))
+    // Apply polling changes live (adapters pick up the new interval on their next tick)
+    if old_settings.polling != new_settings.polling {
+        apply_polling_settings(&state, &new_settings.polling).await;
+    }
+
+    Json(serde_json::json!({"ok": true})).into_response()
+}
+
+/// Push configured poll intervals into the running adapters
+pub async fn apply_polling_settings(state: &AppState, polling: &PollingSettings) {
+    state.lms.set_poll_interval(polling.lms_interval()).await;
+    state.upnp.set_poll_interval(polling.upnp_interval()).await;
+    state
+        .openhome
+        .set_poll_interval(polling.openhome_interval())
+        .await;
+    state
+        .hqp_instances
+        .set_status_refresh_interval(polling.hqp_status_interval())
+        .await;
+    state.http.set_poll_interval(polling.http_interval()).await;
+    state
+        .plexamp
+        .set_poll_interval(polling.plexamp_interval())
+        .await;
+    tracing::info!(
+        "Applied polling intervals: lms={}s upnp={}s openhome={}s hqp={}s http={}s plexamp={}s",
+        polling.lms_interval().as_secs(),
+        polling.upnp_interval().as_secs(),
+        polling.openhome_interval().as_secs(),
+        polling.hqp_status_interval().as_secs(),
+        polling.http_interval().as_secs(),
+        polling.plexamp_interval().as_secs()
+    );
+}
+
+/// Adapter lifecycle status, in `AVAILABLE_ADAPTERS` order
+async fn adapter_statuses(state: &AppState) -> Vec<crate::coordinator::AdapterStatus> {
+    let mut status = state.coordinator.adapter_status().await;
+    crate::coordinator::AVAILABLE_ADAPTERS
+        .iter()
+        .filter_map(|name| status.remove(*name))
+        .collect()
+}
+
+/// GET /api/adapters - Adapter lifecycle status (enabled, running, last error, restarts)
+pub async fn adapters_list_handler(State(state): State<AppState>) -> impl IntoResponse {
+    Json(serde_json::json!({ "adapters": adapter_statuses(&state).await }))
+}
+
+/// GET /api/startup-report - What happened at boot (migrations, assets, listeners, adapter starts)
+pub async fn startup_report_handler() -> impl IntoResponse {
+    Json(crate::startup::snapshot())
+}
+
+/// POST /api/adapters/{name}/restart - Drain, stop and start a single adapter
+pub async fn adapters_restart_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, ApiError> {
+    let adapter = state
+        .startable_adapters
+        .iter()
+        .find(|a| a.name() == name)
+        .cloned()
+        .ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::AdapterNotFound,
+                format!("Unknown adapter: {}", name),
+            )
+        })?;
+    let backend = adapter.name();
+
+    if !state.coordinator.is_enabled(backend).await {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("{} adapter is disabled", backend),
+        )
+        .with_backend(backend)
+        .with_status(StatusCode::CONFLICT));
+    }
+
+    if let Err(e) = state.coordinator.restart(adapter.as_ref()).await {
+        return Err(ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend(backend));
+    }
+
+    let status = adapter_statuses(&state)
+        .await
+        .into_iter()
+        .find(|s| s.prefix == backend);
+    Ok(Json(status).into_response())
+}
+
+/// GET /api/backups - List config backups
+pub async fn backups_list_handler() -> impl IntoResponse {
+    let dir = load_app_settings().backup.directory();
+    Json(serde_json::json!({
+        "directory": dir.display().to_string(),
+        "backups": crate::backup::list_backups(&dir),
+    }))
+}
+
+/// POST /api/backups - Back up the config now
+pub async fn backups_create_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
+    let settings = load_app_settings().backup;
+    let dir = settings.directory();
+    match crate::backup::write_backup(&dir) {
+        Ok(info) => {
+            crate::backup::prune_backups(&dir, settings.keep());
+            Json(info).into_response()
+        }
+        Err(e) => {
+            ApiError::new(ErrorCode::Internal, format!("Backup failed: {}", e)).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BackupRestoreRequest {
+    pub name: String,
+}
+
+/// POST /api/backups/restore - Restore config files from a backup (takes effect after restart)
+pub async fn backups_restore_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<BackupRestoreRequest>,
+) -> Response {
+    if let Err(e) = state.require_admin(&headers) {
+        return e.into_response();
+    }
+    let dir = load_app_settings().backup.directory();
+    if !crate::backup::list_backups(&dir)
+        .iter()
+        .any(|b| b.name == req.name)
+    {
+        return ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("Backup not found: {}", req.name),
+        )
+        .into_response();
+    }
+    match crate::backup::restore_backup(&dir, &req.name) {
+        Ok(restored) => Json(serde_json::json!({
+            "ok": true,
+            "restored": restored,
+            "restart_required": true,
+        }))
+        .into_response(),
+        Err(e) => {
+            ApiError::new(ErrorCode::Internal, format!("Restore failed: {}", e)).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NodeImportQuery {
+    /// Old Node.js config directory
+    pub dir: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NodeImportRequest {
+    pub dir: String,
+    /// Replace config files the bridge already has
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// GET /api/migrate/nodejs?dir= - Preview importing a Node.js config directory
+pub async fn migrate_preview_handler(
+    Query(query): Query<NodeImportQuery>,
+) -> Result<Json<crate::migrate::ImportPlan>, ApiError> {
+    crate::migrate::plan_import(std::path::Path::new(&query.dir))
+        .map(Json)
+        .map_err(|e| ApiError::new(ErrorCode::InvalidRequest, e.to_string()))
+}
+
+/// POST /api/migrate/nodejs - Import a Node.js config directory (takes effect after restart)
+pub async fn migrate_apply_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<NodeImportRequest>,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    let dir = std::path::Path::new(&req.dir);
+    if !dir.is_dir() {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("Not a directory: {}", req.dir),
+        ));
+    }
+    let backup_dir = load_app_settings().backup.directory();
+    match crate::migrate::apply_import(dir, req.overwrite, &backup_dir) {
+        Ok(plan) => {
+            let imported = plan
+                .items
+                .iter()
+                .any(|i| i.status == crate::migrate::ImportStatus::Imported);
+            Ok(Json(serde_json::json!({
+                "dir": plan.dir,
+                "items": plan.items,
+                "restart_required": imported,
+            }))
+            .into_response())
+        }
+        Err(e) => Err(ApiError::new(
+            ErrorCode::Internal,
+            format!("Import failed: {}", e),
+        )),
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,83 @@
+//! HQPlayer NAA endpoints and instance assignments (`/hqp/naa`, `/hqp/instances/{name}/naa`)
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::adapters::naa::HqpNaaStatus;
+
+async fn require_instance(state: &AppState, name: &str) -> Result<(), ApiError> {
+    match state.hqp_instances.get(name).await {
+        Some(_) => Ok(()),
+        None => Err(ApiError::new(
+            ErrorCode::InstanceNotFound,
+            format!("Instance not found: {}", name),
+        )
+        .with_backend("hqplayer")),
+    }
+}
+
+/// GET /hqp/naa - NAA endpoints from the last scan and every instance's NAA
+pub async fn hqp_naa_handler(State(state): State<AppState>) -> Json<Value> {
+    let (endpoints, last_scan) = state.hqp_naa.endpoints().await;
+    Json(json!({
+        "endpoints": endpoints,
+        "last_scan": last_scan,
+        "assignments": state.hqp_naa.statuses().await,
+    }))
+}
+
+/// GET /hqp/naa/discover - Look for NAA endpoints now
+pub async fn hqp_naa_discover_handler(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, ApiError> {
+    let discovered = state.hqp_naa.scan().await.map_err(|e| {
+        ApiError::new(ErrorCode::BackendError, format!("Discovery failed: {}", e))
+            .with_backend("hqplayer")
+    })?;
+    Ok(Json(json!({ "discovered": discovered })))
+}
+
+/// GET /hqp/instances/{name}/naa - The NAA an instance outputs to
+pub async fn hqp_instance_naa_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<HqpNaaStatus>, ApiError> {
+    require_instance(&state, &name).await?;
+    state.hqp_naa.status(&name).await.map(Json).ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::NotLinked,
+            format!("HQPlayer instance {} has no NAA assigned", name),
+        )
+        .with_backend("hqplayer")
+    })
+}
+
+/// Body for PUT /hqp/instances/{name}/naa
+#[derive(Debug, Deserialize)]
+pub struct NaaAssignRequest {
+    /// NAA name or address; `null` clears the assignment
+    pub naa: Option<String>,
+}
+
+/// PUT /hqp/instances/{name}/naa - Set the NAA an instance outputs to
+pub async fn hqp_instance_naa_put_handler(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<NaaAssignRequest>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    require_instance(&state, &name).await?;
+    state.hqp_naa.assign(&name, req.naa).await;
+    Ok(Json(json!({
+        "ok": true,
+        "instance": name,
+        "naa": state.hqp_naa.status(&name).await,
+    })))
+}
@@ -0,0 +1,116 @@
+//! Pagination and field selection for list responses
+//!
+//! `?limit=&offset=` returns one page of a list along with `total` and
+//! `next_offset`; `?fields=a,b` trims each item to the named top-level fields
+//! (the item's id is always kept). Without them, responses are unchanged.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Largest page served, whatever `limit` asks for
+pub const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListQuery {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// Comma-separated top-level field names
+    pub fields: Option<String>,
+}
+
+/// Position of a page in the full list, included only for paged requests
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PageInfo {
+    pub total: Option<usize>,
+    pub next_offset: Option<usize>,
+}
+
+impl ListQuery {
+    pub fn is_paged(&self) -> bool {
+        self.limit.is_some() || self.offset.is_some()
+    }
+
+    /// The requested page of `items`, and where it sits in the full list
+    pub fn page<T>(&self, items: Vec<T>) -> (Vec<T>, PageInfo) {
+        if !self.is_paged() {
+            return (items, PageInfo::default());
+        }
+        let total = items.len();
+        let offset = self.offset.unwrap_or(0);
+        let limit = self.limit.unwrap_or(MAX_LIMIT).clamp(1, MAX_LIMIT);
+        let page: Vec<T> = items.into_iter().skip(offset).take(limit).collect();
+        let end = offset.saturating_add(page.len());
+        let info = PageInfo {
+            total: Some(total),
+            next_offset: (end < total).then_some(end),
+        };
+        (page, info)
+    }
+
+    /// Requested field names; `None` for every field
+    pub fn fields(&self) -> Option<Vec<&str>> {
+        let fields: Vec<&str> = self
+            .fields
+            .as_deref()?
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .collect();
+        (!fields.is_empty()).then_some(fields)
+    }
+}
+
+/// `item` as JSON with only `fields` and `id_field` kept
+pub fn select_fields<T: Serialize>(item: &T, fields: &[&str], id_field: &str) -> Value {
+    match serde_json::to_value(item) {
+        Ok(Value::Object(map)) => Value::Object(
+            map.into_iter()
+                .filter(|(key, _)| key == id_field || fields.contains(&key.as_str()))
+                .collect(),
+        ),
+        Ok(other) => other,
+        Err(_) => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(limit: Option<usize>, offset: Option<usize>, fields: Option<&str>) -> ListQuery {
+        ListQuery {
+            limit,
+            offset,
+            fields: fields.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_page() {
+        let items: Vec<u32> = (0..5).collect();
+        let (page, info) = query(Some(2), Some(1), None).page(items.clone());
+        assert_eq!(page, vec![1, 2]);
+        assert_eq!(info.total, Some(5));
+        assert_eq!(info.next_offset, Some(3));
+
+        let (page, info) = query(None, Some(3), None).page(items.clone());
+        assert_eq!(page, vec![3, 4]);
+        assert_eq!(info.next_offset, None);
+
+        let (page, info) = query(None, None, None).page(items);
+        assert_eq!(page.len(), 5);
+        assert_eq!(info, PageInfo::default());
+    }
+
+    #[test]
+    fn test_select_fields() {
+        let q = query(None, None, Some("zone_name, state,"));
+        let fields = q.fields().unwrap();
+        let zone = serde_json::json!({"zone_id": "roon:1", "zone_name": "Study", "state": "playing", "source": "roon"});
+        assert_eq!(
+            select_fields(&zone, &fields, "zone_id"),
+            serde_json::json!({"zone_id": "roon:1", "zone_name": "Study", "state": "playing"})
+        );
+        assert!(query(None, None, Some(" , ")).fields().is_none());
+    }
+}
@@ -0,0 +1,74 @@
+//! Roon Core power management (`/roon/core/power`)
+
+use axum::{extract::State, http::HeaderMap, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::adapters::roon_power::{CorePowerAction, WakeOnLanConfig};
+
+/// Response for GET /roon/core/power
+#[derive(Debug, Serialize)]
+pub struct CorePowerStatus {
+    pub connected: bool,
+    pub core_name: Option<String>,
+    pub wake_on_lan: WakeOnLanConfig,
+    /// Actions POST /roon/core/power will accept
+    pub actions: Vec<CorePowerAction>,
+}
+
+/// GET /roon/core/power - Core connection and Wake-on-LAN settings
+pub async fn roon_core_power_handler(State(state): State<AppState>) -> Json<CorePowerStatus> {
+    let status = state.roon.get_status().await;
+    let wake_on_lan = state.roon_power.config().await;
+    let actions = if wake_on_lan.mac.is_some() {
+        vec![CorePowerAction::Wake]
+    } else {
+        Vec::new()
+    };
+    Json(CorePowerStatus {
+        connected: status.connected,
+        core_name: status.core_name,
+        wake_on_lan,
+        actions,
+    })
+}
+
+/// Body for POST /roon/core/power
+#[derive(Debug, Deserialize)]
+pub struct CorePowerRequest {
+    pub action: CorePowerAction,
+}
+
+/// POST /roon/core/power - Wake the Core machine
+pub async fn roon_core_power_action_handler(
+    State(state): State<AppState>,
+    Json(req): Json<CorePowerRequest>,
+) -> Result<Json<Value>, ApiError> {
+    match req.action {
+        CorePowerAction::Wake => {
+            state.roon_power.wake().await.map_err(|e| {
+                ApiError::new(ErrorCode::CommandFailed, e.to_string()).with_backend("roon")
+            })?;
+            Ok(Json(json!({ "ok": true, "action": req.action })))
+        }
+    }
+}
+
+/// PUT /roon/core/power/wol - Set the Core's Wake-on-LAN address
+///
+/// A settings change, so it is admin-only; waking stays a POST to
+/// `/roon/core/power` that control tokens can send.
+pub async fn roon_core_wol_put_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(config): Json<WakeOnLanConfig>,
+) -> Result<Json<Value>, ApiError> {
+    state.require_admin(&headers)?;
+    state.roon_power.set_config(config).await.map_err(|e| {
+        ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("roon")
+    })?;
+    Ok(Json(
+        json!({ "ok": true, "wake_on_lan": state.roon_power.config().await }),
+    ))
+}
@@ -0,0 +1,133 @@
+//! Selected zone per client (`/api/selection`)
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+use serde::Deserialize;
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::bus::BusEvent;
+use crate::selection::{is_valid_client, knob_client, DEFAULT_CLIENT};
+use crate::tokens::is_token;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SelectionQuery {
+    pub client: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SelectionRequest {
+    pub zone_id: String,
+    /// Defaults as for `?client=`
+    #[serde(default)]
+    pub client: Option<String>,
+}
+
+/// The client a request selects for: `explicit`, else its named token, else
+/// its knob id, else [`DEFAULT_CLIENT`]
+fn client_key(
+    state: &AppState,
+    headers: &HeaderMap,
+    explicit: Option<String>,
+) -> Result<String, ApiError> {
+    let token = || {
+        let presented = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .filter(|t| is_token(t))?;
+        state
+            .tokens
+            .authenticate_token(presented)
+            .map(|token| format!("token:{}", token.id))
+    };
+    let knob = || {
+        headers
+            .get("x-knob-id")
+            .and_then(|v| v.to_str().ok())
+            .map(knob_client)
+    };
+    let client = explicit
+        .filter(|c| !c.is_empty())
+        .or_else(token)
+        .or_else(knob)
+        .unwrap_or_else(|| DEFAULT_CLIENT.to_string());
+    if is_valid_client(&client) {
+        Ok(client)
+    } else {
+        Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "client is up to 64 letters, digits, '-', '_', ':' or '.'",
+        ))
+    }
+}
+
+/// Select `zone_id` for `client`, publishing `ZoneSelected` if it changed
+pub fn select_zone(state: &AppState, client: &str, zone_id: &str) {
+    if state.selection.set(client, zone_id) {
+        state.bus.publish(BusEvent::ZoneSelected {
+            client: client.to_string(),
+            zone_id: zone_id.to_string(),
+        });
+    }
+}
+
+async fn selection_response(state: &AppState, client: String) -> serde_json::Value {
+    let selection = state.selection.get(&client);
+    let zone_name = match &selection {
+        Some(s) => state
+            .aggregator
+            .get_zone(&s.zone_id)
+            .await
+            .map(|z| z.zone_name),
+        None => None,
+    };
+    serde_json::json!({
+        "client": client,
+        "zone_id": selection.as_ref().map(|s| &s.zone_id),
+        "zone_name": zone_name,
+        "updated_at": selection.as_ref().map(|s| s.updated_at),
+    })
+}
+
+/// GET /api/selection - The zone this client (or `?client=`) last selected
+///
+/// `zone_name` is null when the zone is currently offline.
+pub async fn selection_get_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SelectionQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let client = client_key(&state, &headers, query.client)?;
+    Ok(Json(selection_response(&state, client).await))
+}
+
+/// PUT /api/selection - Select a zone for this client
+pub async fn selection_put_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SelectionRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let client = client_key(&state, &headers, req.client)?;
+    if state.aggregator.get_zone(&req.zone_id).await.is_none() {
+        return Err(ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", req.zone_id),
+        ));
+    }
+    select_zone(&state, &client, &req.zone_id);
+    Ok(Json(selection_response(&state, client).await))
+}
+
+/// DELETE /api/selection - Forget this client's selection
+pub async fn selection_delete_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SelectionQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let client = client_key(&state, &headers, query.client)?;
+    let removed = state.selection.clear(&client);
+    Ok(Json(serde_json::json!({ "ok": true, "removed": removed })))
+}
@@ -0,0 +1,166 @@
+//! Simplified GET endpoints for Shortcuts/Tasker (`/simple/{action}`)
+//!
+//! Everything is in the query string and zones are found by name (see
+//! [`crate::zone_resolver`]), so an automation can call
+//! `/simple/play?zone=kitchen&what=favorite:Jazz` without building a JSON
+//! body or knowing zone ids.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+
+use super::{ApiError, AppState, Caller, ErrorCode};
+use crate::knobs::dispatch_control;
+use crate::zone_resolver::{self, ZoneNames};
+
+#[derive(Debug, Deserialize)]
+pub struct SimpleQuery {
+    /// Zone name, room, alias or zone_id
+    pub zone: String,
+    /// Action value, e.g. the volume for `volume`
+    pub value: Option<String>,
+    /// What to play: `favorite:<name>` (LMS), `playlist:<path or URL>` (LMS)
+    /// or `url:<URL>` (LMS, UPnP)
+    pub what: Option<String>,
+}
+
+/// GET /simple/{action}?zone=... - Control a zone by name
+///
+/// `action` is any `/control` action; `toggle` is `play_pause` and `volume`
+/// sets `value` as the absolute volume. `play` with `what` starts that item
+/// instead of resuming.
+pub async fn simple_handler(
+    State(state): State<AppState>,
+    Path(action): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<SimpleQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let caller = Caller::from_headers(&state, &headers);
+    let zones: Vec<ZoneNames> = state
+        .aggregator
+        .get_zones()
+        .await
+        .iter()
+        .filter(|z| {
+            matches!(
+                z.source.as_str(),
+                "roon"
+                    | "lms"
+                    | "openhome"
+                    | "upnp"
+                    | "snapcast"
+                    | "heos"
+                    | "kodi"
+                    | "spotify"
+                    | "http"
+                    | "plexamp"
+            )
+        })
+        .map(ZoneNames::from_zone)
+        .collect();
+    let matches = zone_resolver::resolve(&zones, &query.zone);
+    let Some(found) = zone_resolver::best(&matches) else {
+        if let Some(first) = matches.first() {
+            let candidates: Vec<&str> = matches
+                .iter()
+                .filter(|m| m.quality == first.quality)
+                .map(|m| m.zone_name.as_str())
+                .collect();
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("\"{}\" matches more than one zone", query.zone),
+            )
+            .with_details(serde_json::json!({ "candidates": candidates })));
+        }
+        let names: Vec<&str> = zones
+            .iter()
+            .filter_map(|z| z.names.first())
+            .map(String::as_str)
+            .collect();
+        return Err(ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("No zone matches \"{}\"", query.zone),
+        )
+        .with_details(serde_json::json!({ "zones": names })));
+    };
+    let (zone_id, zone_name) = (found.zone_id.clone(), found.zone_name.clone());
+
+    let mut result = match (action.as_str(), query.what.as_deref()) {
+        ("play", Some(what)) => play_what(&state, &zone_id, what, caller).await?,
+        _ => {
+            let action = match action.as_str() {
+                "toggle" => "play_pause",
+                "volume" => "vol_abs",
+                other => other,
+            };
+            // Numbers go through as numbers, as /control expects for volumes
+            let value = query.value.map(|v| match v.parse::<f64>() {
+                Ok(n) => serde_json::json!(n),
+                Err(_) => serde_json::json!(v),
+            });
+            dispatch_control(&state, &zone_id, action, value.as_ref(), caller)
+                .await?
+                .0
+        }
+    };
+    if let Some(object) = result.as_object_mut() {
+        object.insert("zone_id".to_string(), serde_json::json!(zone_id));
+        object.insert("zone_name".to_string(), serde_json::json!(zone_name));
+    }
+    Ok(Json(result))
+}
+
+/// Start `favorite:`, `playlist:` or `url:` on a zone
+async fn play_what(
+    state: &AppState,
+    zone_id: &str,
+    what: &str,
+    caller: Caller,
+) -> Result<serde_json::Value, ApiError> {
+    state.check_zone_lock(zone_id, caller)?;
+    let (kind, item) = what.split_once(':').ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::InvalidRequest,
+            "what must be favorite:<name>, playlist:<item> or url:<URL>",
+        )
+    })?;
+    match (kind, zone_id.split_once(':')) {
+        ("favorite", Some(("lms", player_id))) => {
+            let name = state
+                .lms
+                .play_favorite(player_id, item)
+                .await
+                .map_err(|e| {
+                    ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("lms")
+                })?;
+            Ok(serde_json::json!({ "ok": true, "playing": name }))
+        }
+        ("playlist" | "url", Some(("lms", player_id))) => {
+            state
+                .lms
+                .play_playlist(player_id, item)
+                .await
+                .map_err(|e| {
+                    ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("lms")
+                })?;
+            Ok(serde_json::json!({ "ok": true, "playing": item }))
+        }
+        ("url", Some(("upnp", _))) => {
+            let value = serde_json::json!(item);
+            dispatch_control(state, zone_id, "play_url", Some(&value), caller)
+                .await
+                .map(|Json(result)| result)
+        }
+        ("favorite" | "playlist" | "url", _) => Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("{}: is not supported for zone {}", kind, zone_id),
+        )),
+        _ => Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("Unknown what: {}", kind),
+        )),
+    }
+}
@@ -0,0 +1,63 @@
+//! Spotify account authorization (`/spotify/authorize`, `/spotify/callback`)
+//!
+//! The Settings page links to `/spotify/authorize`, which sends the browser to
+//! Spotify's consent page. Spotify redirects back to the app's redirect URI
+//! (`/spotify/callback` on the bridge), where the code is exchanged for the
+//! refresh token the adapter keeps.
+
+use axum::{
+    extract::{Query, State},
+    response::Redirect,
+};
+use serde::Deserialize;
+
+use super::adapter_config::restart_spotify;
+use super::{ApiError, AppState, ErrorCode};
+
+/// Query params Spotify redirects back with
+#[derive(Debug, Deserialize)]
+pub struct SpotifyCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set instead of `code` when the user declined
+    pub error: Option<String>,
+}
+
+/// GET /spotify/authorize - Redirect to Spotify to authorize the bridge
+pub async fn spotify_authorize_handler(
+    State(state): State<AppState>,
+) -> Result<Redirect, ApiError> {
+    let url = state.spotify.authorization_url().await.map_err(|e| {
+        ApiError::new(ErrorCode::InvalidRequest, e.to_string()).with_backend("spotify")
+    })?;
+    Ok(Redirect::to(&url))
+}
+
+/// GET /spotify/callback - Save the authorization and return to Settings
+pub async fn spotify_callback_handler(
+    State(state): State<AppState>,
+    Query(query): Query<SpotifyCallbackQuery>,
+) -> Result<Redirect, ApiError> {
+    if let Some(error) = query.error {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            format!("Spotify authorization failed: {}", error),
+        )
+        .with_backend("spotify"));
+    }
+    let (Some(code), Some(nonce)) = (query.code, query.state) else {
+        return Err(
+            ApiError::new(ErrorCode::InvalidRequest, "code and state are required")
+                .with_backend("spotify"),
+        );
+    };
+    state
+        .spotify
+        .complete_authorization(&code, &nonce)
+        .await
+        .map_err(|e| {
+            ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("spotify")
+        })?;
+    restart_spotify(&state).await?;
+    Ok(Redirect::to("/settings"))
+}
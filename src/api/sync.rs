@@ -0,0 +1,256 @@
+//! State sync for clients that wake briefly (`GET /sync?cursor=`)
+//!
+//! Instead of replaying events, a sync returns the current state of whatever
+//! the events after the cursor touched: zones, HQPlayer instances and knobs.
+//! A missing or expired cursor (older than the event history, or from a
+//! previous server run) gets a full snapshot, so a client never has to tell
+//! the two cases apart beyond the `full` flag.
+
+use std::collections::BTreeSet;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use super::AppState;
+use crate::adapters::hqplayer::HqpInstanceInfo;
+use crate::bus::{BusEvent, SequencedEvent, Zone};
+use crate::knobs::store::KnobSummary;
+
+#[derive(Debug, Deserialize)]
+pub struct SyncQuery {
+    /// `cursor` from the previous response; omit for a full snapshot
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncResponse {
+    /// Pass as `cursor` on the next sync
+    pub cursor: String,
+    /// True if this is a full snapshot rather than changes since the cursor
+    pub full: bool,
+    /// Current state of zones that changed (all zones when `full`)
+    pub zones: Vec<Zone>,
+    /// Zones that went away since the cursor
+    pub removed_zones: Vec<String>,
+    /// HQPlayer instances, only present when one changed (always when `full`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hqp: Option<Vec<HqpInstanceInfo>>,
+    /// Knobs with new events (all knobs when `full`)
+    pub knobs: Vec<KnobSummary>,
+}
+
+fn format_cursor(run_id: u64, seq: u64) -> String {
+    format!("{}-{}", run_id, seq)
+}
+
+/// Sequence number in `cursor`, if it belongs to the run `run_id`
+fn parse_cursor(cursor: &str, run_id: u64) -> Option<u64> {
+    let (run, seq) = cursor.split_once('-')?;
+    if run.parse::<u64>().ok()? != run_id {
+        return None;
+    }
+    seq.parse().ok()
+}
+
+/// What the events after a cursor touched
+#[derive(Debug, Default, PartialEq)]
+struct Changes {
+    zones: BTreeSet<String>,
+    removed_zones: BTreeSet<String>,
+    /// Volume changes name the output, matched against zones later
+    outputs: BTreeSet<String>,
+    hqp: bool,
+    knobs: BTreeSet<String>,
+}
+
+impl Changes {
+    fn collect(events: &[SequencedEvent]) -> Self {
+        let mut changes = Self::default();
+        for sequenced in events {
+            match &sequenced.event {
+                BusEvent::ZoneDiscovered { zone } => {
+                    changes.zones.insert(zone.zone_id.clone());
+                }
+                BusEvent::ZoneUpdated { zone_id, .. }
+                | BusEvent::ZoneStatePending { zone_id, .. }
+                | BusEvent::NowPlayingChanged { zone_id, .. }
+                | BusEvent::SeekPositionChanged { zone_id, .. } => {
+                    changes.zones.insert(zone_id.to_string());
+                }
+                BusEvent::ZoneMetadataChanged { zone_id } => {
+                    changes.zones.insert(zone_id.clone());
+                }
+                BusEvent::VolumeChanged { output_id, .. } => {
+                    changes.outputs.insert(output_id.clone());
+                }
+                BusEvent::ZoneRemoved { zone_id } => {
+                    changes.removed_zones.insert(zone_id.to_string());
+                }
+                BusEvent::ZonesFlushed { zone_ids, .. } => {
+                    changes.removed_zones.extend(zone_ids.iter().cloned());
+                }
+                BusEvent::HqpConnected { .. }
+                | BusEvent::HqpDisconnected { .. }
+                | BusEvent::HqpStateChanged { .. }
+                | BusEvent::HqpPipelineChanged { .. }
+                | BusEvent::HqpNaaLost { .. }
+                | BusEvent::HqpNaaFound { .. } => changes.hqp = true,
+                BusEvent::KnobInput { knob_id, .. }
+                | BusEvent::KnobZoneChanged { knob_id, .. }
+                | BusEvent::KnobBatteryChanged { knob_id, .. } => {
+                    changes.knobs.insert(knob_id.clone());
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    fn touches(&self, zone: &Zone) -> bool {
+        self.zones.contains(&zone.zone_id)
+            || zone
+                .volume_control
+                .as_ref()
+                .and_then(|vc| vc.output_id.as_ref())
+                .is_some_and(|id| self.outputs.contains(id))
+    }
+}
+
+/// GET /sync?cursor= - Everything that changed since `cursor`, in one call
+pub async fn sync_handler(
+    State(state): State<AppState>,
+    Query(query): Query<SyncQuery>,
+) -> Json<SyncResponse> {
+    let run_id = state.bus.run_id();
+    let since = query
+        .cursor
+        .as_deref()
+        .and_then(|cursor| parse_cursor(cursor, run_id));
+    let history = since.map(|since| state.bus.events_since(since));
+
+    let Some(history) = history.filter(|h| !h.truncated) else {
+        // Read the cursor first, so changes made while snapshotting are sent again next time
+        let cursor = format_cursor(run_id, state.bus.last_seq());
+        return Json(SyncResponse {
+            cursor,
+            full: true,
+            zones: state.aggregator.get_zones().await,
+            removed_zones: Vec::new(),
+            hqp: Some(state.hqp_instances.list_instances().await),
+            knobs: state.knobs.list().await,
+        });
+    };
+
+    let changes = Changes::collect(&history.events);
+    let zones: Vec<Zone> = state
+        .aggregator
+        .get_zones()
+        .await
+        .into_iter()
+        .filter(|zone| changes.touches(zone))
+        .collect();
+    // A zone removed and then rediscovered is reported as current, not removed
+    let mut removed_zones = Vec::new();
+    for zone_id in &changes.removed_zones {
+        if state.aggregator.get_zone(zone_id).await.is_none() {
+            removed_zones.push(zone_id.clone());
+        }
+    }
+    let hqp = if changes.hqp {
+        Some(state.hqp_instances.list_instances().await)
+    } else {
+        None
+    };
+    let knobs = if changes.knobs.is_empty() {
+        Vec::new()
+    } else {
+        let mut knobs = state.knobs.list().await;
+        knobs.retain(|knob| changes.knobs.contains(&knob.knob_id));
+        knobs
+    };
+
+    Json(SyncResponse {
+        cursor: format_cursor(run_id, history.next),
+        full: false,
+        zones,
+        removed_zones,
+        hqp,
+        knobs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::PrefixedZoneId;
+
+    fn sequenced(seq: u64, event: BusEvent) -> SequencedEvent {
+        SequencedEvent { seq, event }
+    }
+
+    #[test]
+    fn cursor_round_trips_within_a_run() {
+        let cursor = format_cursor(1700, 42);
+        assert_eq!(parse_cursor(&cursor, 1700), Some(42));
+        assert_eq!(parse_cursor(&cursor, 1800), None);
+        assert_eq!(parse_cursor("42", 1700), None);
+        assert_eq!(parse_cursor("1700-x", 1700), None);
+    }
+
+    #[test]
+    fn changes_collect_touched_entities() {
+        let events = vec![
+            sequenced(
+                1,
+                BusEvent::SeekPositionChanged {
+                    zone_id: PrefixedZoneId::lms("aa"),
+                    position: 10,
+                },
+            ),
+            sequenced(
+                2,
+                BusEvent::ZoneRemoved {
+                    zone_id: PrefixedZoneId::lms("bb"),
+                },
+            ),
+            sequenced(
+                3,
+                BusEvent::VolumeChanged {
+                    output_id: "out-1".to_string(),
+                    value: 20.0,
+                    is_muted: false,
+                },
+            ),
+            sequenced(
+                4,
+                BusEvent::HqpConnected {
+                    host: "hqp.local".to_string(),
+                },
+            ),
+            sequenced(
+                5,
+                BusEvent::KnobZoneChanged {
+                    knob_id: "knob-1".to_string(),
+                    knob_name: None,
+                    from: None,
+                    zone_id: "lms:aa".to_string(),
+                },
+            ),
+            sequenced(6, BusEvent::RoonDisconnected),
+        ];
+        let changes = Changes::collect(&events);
+        assert_eq!(changes.zones, BTreeSet::from(["lms:aa".to_string()]));
+        assert_eq!(
+            changes.removed_zones,
+            BTreeSet::from(["lms:bb".to_string()])
+        );
+        assert_eq!(changes.outputs, BTreeSet::from(["out-1".to_string()]));
+        assert!(changes.hqp);
+        assert_eq!(changes.knobs, BTreeSet::from(["knob-1".to_string()]));
+
+        assert_eq!(Changes::collect(&[]), Changes::default());
+    }
+}
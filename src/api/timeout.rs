@@ -0,0 +1,113 @@
+//! Request deadline middleware
+//!
+//! Bounds how long any API handler may run and aborts in-flight handlers as
+//! soon as shutdown starts. Dropping the handler future drops whatever backend
+//! call it was awaiting (HQPlayer TCP, LMS HTTP, Roon requests), so a stuck
+//! backend can't keep `axum::serve`'s graceful shutdown waiting.
+//!
+//! Streaming responses are unaffected: the deadline covers producing the
+//! response, not sending its body.
+
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use tokio_util::sync::CancellationToken;
+
+use super::{ApiError, ErrorCode};
+
+/// Paths that bound their own wait and watch shutdown themselves
+const EXEMPT_PATHS: &[&str] = &["/events/poll"];
+
+/// Deadline applied to every request
+#[derive(Clone)]
+pub struct RequestDeadline {
+    pub timeout: Duration,
+    pub shutdown: CancellationToken,
+}
+
+/// Middleware enforcing [`RequestDeadline`]
+pub async fn enforce_deadline(
+    State(deadline): State<RequestDeadline>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if EXEMPT_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let path = request.uri().path().to_string();
+    tokio::select! {
+        result = tokio::time::timeout(deadline.timeout, next.run(request)) => match result {
+            Ok(response) => response,
+            Err(_) => {
+                tracing::warn!("{} timed out after {:?}", path, deadline.timeout);
+                ApiError::new(
+                    ErrorCode::Timeout,
+                    format!("Request timed out after {}s", deadline.timeout.as_secs()),
+                )
+                .into_response()
+            }
+        },
+        _ = deadline.shutdown.cancelled() => {
+            tracing::debug!("Aborting {} for shutdown", path);
+            ApiError::new(ErrorCode::ShuttingDown, "Server is shutting down").into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(deadline: RequestDeadline) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(axum::middleware::from_fn_with_state(
+                deadline,
+                enforce_deadline,
+            ))
+    }
+
+    fn get_request(path: &str) -> Request {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_times_out() {
+        let app = app(RequestDeadline {
+            timeout: Duration::from_millis(20),
+            shutdown: CancellationToken::new(),
+        });
+        let response = app.clone().oneshot(get_request("/slow")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let response = app.oneshot(get_request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_aborts_in_flight_request() {
+        let shutdown = CancellationToken::new();
+        let app = app(RequestDeadline {
+            timeout: Duration::from_secs(60),
+            shutdown: shutdown.clone(),
+        });
+        let request = tokio::spawn(app.oneshot(get_request("/slow")));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        shutdown.cancel();
+
+        let response = request.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,154 @@
+//! Named API token management (`/api/tokens`) and pairing (`/api/pair`)
+//!
+//! Managing tokens and creating pairing codes need an admin credential as
+//! `Authorization: Bearer`, once one exists (see [`AppState::require_admin`]).
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use super::{ApiError, AppState, ErrorCode};
+use crate::tokens::TokenScope;
+
+/// Body for POST /api/tokens
+#[derive(Debug, Deserialize)]
+pub struct TokenCreateRequest {
+    pub name: String,
+    pub scope: TokenScope,
+}
+
+/// Body for PUT /api/tokens/{id}; omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct TokenUpdateRequest {
+    pub name: Option<String>,
+    pub scope: Option<TokenScope>,
+}
+
+/// Body for POST /api/pair/code
+#[derive(Debug, Deserialize)]
+pub struct PairingCodeRequest {
+    pub scope: TokenScope,
+    pub name: Option<String>,
+}
+
+/// Body for POST /api/pair
+#[derive(Debug, Deserialize)]
+pub struct PairRequest {
+    pub code: String,
+    pub name: Option<String>,
+    /// Binds a knob-scoped token to this knob (also read from `X-Knob-Id`)
+    pub knob_id: Option<String>,
+}
+
+fn token_not_found(id: &str) -> ApiError {
+    ApiError::new(ErrorCode::TokenNotFound, format!("Token not found: {}", id))
+}
+
+fn save_failed(e: anyhow::Error) -> ApiError {
+    ApiError::new(ErrorCode::Internal, format!("Failed to save tokens: {}", e))
+}
+
+/// GET /api/tokens - List named tokens (without secrets)
+pub async fn tokens_list_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    Ok(Json(serde_json::json!({ "tokens": state.tokens.list() })).into_response())
+}
+
+/// POST /api/tokens - Create a token; the secret is only returned here
+pub async fn tokens_create_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<TokenCreateRequest>,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    if req.name.trim().is_empty() {
+        return Err(ApiError::new(ErrorCode::InvalidRequest, "name is required"));
+    }
+    let (token, secret) = state
+        .tokens
+        .create(&req.name, req.scope)
+        .map_err(save_failed)?;
+    let mut body = serde_json::json!(token);
+    body["token"] = serde_json::json!(secret);
+    Ok((StatusCode::CREATED, Json(body)).into_response())
+}
+
+/// PUT /api/tokens/{id} - Rename a token or change its scope
+pub async fn tokens_update_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<TokenUpdateRequest>,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    if req.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "name must not be empty",
+        ));
+    }
+    match state
+        .tokens
+        .update(&id, req.name.as_deref(), req.scope)
+        .map_err(save_failed)?
+    {
+        Some(token) => Ok(Json(token).into_response()),
+        None => Err(token_not_found(&id)),
+    }
+}
+
+/// DELETE /api/tokens/{id} - Revoke a token
+pub async fn tokens_delete_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    if state.tokens.delete(&id).map_err(save_failed)? {
+        Ok(Json(serde_json::json!({ "ok": true })).into_response())
+    } else {
+        Err(token_not_found(&id))
+    }
+}
+
+/// POST /api/pair/code - Create a short-lived pairing code for a new client
+pub async fn pairing_code_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<PairingCodeRequest>,
+) -> Result<Response, ApiError> {
+    state.require_admin(&headers)?;
+    let code = state
+        .tokens
+        .create_pairing_code(req.scope, req.name.as_deref());
+    Ok((StatusCode::CREATED, Json(code)).into_response())
+}
+
+/// POST /api/pair - Exchange a pairing code for a token (no token required)
+pub async fn pair_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<PairRequest>,
+) -> Result<Response, ApiError> {
+    let knob_id = req.knob_id.or_else(|| {
+        headers
+            .get("x-knob-id")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    });
+    let (token, secret) = state
+        .tokens
+        .redeem_pairing_code(&req.code, req.name.as_deref(), knob_id.as_deref())
+        .map_err(save_failed)?
+        .ok_or_else(|| ApiError::new(ErrorCode::Unauthorized, "Invalid or expired pairing code"))?;
+    let mut body = serde_json::json!(token);
+    body["token"] = serde_json::json!(secret);
+    Ok((StatusCode::CREATED, Json(body)).into_response())
+}
@@ -5,6 +5,7 @@
 
 use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 // =============================================================================
 // Status Types
@@ -24,6 +25,23 @@ pub struct RoonStatus {
     pub connected: bool,
     pub core_name: Option<String>,
     pub core_version: Option<String>,
+    /// Connection drops since the bridge started
+    #[serde(default)]
+    pub disconnect_count: u32,
+    /// Recent connects and drops, newest first
+    #[serde(default)]
+    pub history: Vec<RoonConnectionEvent>,
+}
+
+/// A connect or drop in `RoonStatus::history`
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RoonConnectionEvent {
+    /// RFC 3339 timestamp
+    pub at: String,
+    /// `connected` or `disconnected`
+    pub event: String,
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
@@ -50,9 +68,122 @@ pub struct AdapterSettings {
     pub openhome: bool,
     pub upnp: bool,
     #[serde(default)]
+    pub airplay: bool,
+    #[serde(default)]
+    pub snapcast: bool,
+    #[serde(default)]
+    pub heos: bool,
+    #[serde(default)]
+    pub kodi: bool,
+    #[serde(default)]
+    pub spotify: bool,
+    #[serde(default)]
+    pub http: bool,
+    #[serde(default)]
+    pub plexamp: bool,
+    #[serde(default)]
     pub hqplayer: bool,
 }
 
+/// Lower bound for adapter poll intervals (protects backends from being hammered)
+pub const MIN_POLL_INTERVAL_SECS: u64 = 1;
+/// Upper bound for adapter poll intervals (keeps zone state from going stale)
+pub const MAX_POLL_INTERVAL_SECS: u64 = 300;
+
+/// Adapter polling intervals (stored in app-settings.json under `polling`)
+///
+/// Values are clamped to [`MIN_POLL_INTERVAL_SECS`, `MAX_POLL_INTERVAL_SECS`] when read,
+/// so a hand-edited settings file can't stall or flood an adapter.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PollingSettings {
+    /// LMS player status poll (seconds, slowed 15x while CLI subscription is active)
+    #[serde(default = "default_lms_poll_secs")]
+    pub lms_secs: u64,
+    /// UPnP renderer poll (seconds)
+    #[serde(default = "default_upnp_poll_secs")]
+    pub upnp_secs: u64,
+    /// OpenHome device poll (seconds)
+    #[serde(default = "default_openhome_poll_secs")]
+    pub openhome_secs: u64,
+    /// HQPlayer playback status refresh (seconds)
+    #[serde(default = "default_hqp_status_secs")]
+    pub hqp_status_secs: u64,
+    /// HTTP JSON device status poll (seconds)
+    #[serde(default = "default_http_poll_secs")]
+    pub http_secs: u64,
+    /// Plexamp player timeline poll (seconds)
+    #[serde(default = "default_plexamp_poll_secs")]
+    pub plexamp_secs: u64,
+}
+
+fn default_lms_poll_secs() -> u64 {
+    2
+}
+
+fn default_upnp_poll_secs() -> u64 {
+    2
+}
+
+fn default_openhome_poll_secs() -> u64 {
+    2
+}
+
+fn default_hqp_status_secs() -> u64 {
+    5
+}
+
+fn default_http_poll_secs() -> u64 {
+    2
+}
+
+fn default_plexamp_poll_secs() -> u64 {
+    2
+}
+
+impl Default for PollingSettings {
+    fn default() -> Self {
+        Self {
+            lms_secs: default_lms_poll_secs(),
+            upnp_secs: default_upnp_poll_secs(),
+            openhome_secs: default_openhome_poll_secs(),
+            hqp_status_secs: default_hqp_status_secs(),
+            http_secs: default_http_poll_secs(),
+            plexamp_secs: default_plexamp_poll_secs(),
+        }
+    }
+}
+
+impl PollingSettings {
+    /// Clamp a raw interval to the supported range
+    pub fn clamp_secs(secs: u64) -> u64 {
+        secs.clamp(MIN_POLL_INTERVAL_SECS, MAX_POLL_INTERVAL_SECS)
+    }
+
+    pub fn lms_interval(&self) -> Duration {
+        Duration::from_secs(Self::clamp_secs(self.lms_secs))
+    }
+
+    pub fn upnp_interval(&self) -> Duration {
+        Duration::from_secs(Self::clamp_secs(self.upnp_secs))
+    }
+
+    pub fn openhome_interval(&self) -> Duration {
+        Duration::from_secs(Self::clamp_secs(self.openhome_secs))
+    }
+
+    pub fn hqp_status_interval(&self) -> Duration {
+        Duration::from_secs(Self::clamp_secs(self.hqp_status_secs))
+    }
+
+    pub fn http_interval(&self) -> Duration {
+        Duration::from_secs(Self::clamp_secs(self.http_secs))
+    }
+
+    pub fn plexamp_interval(&self) -> Duration {
+        Duration::from_secs(Self::clamp_secs(self.plexamp_secs))
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct AppSettings {
     pub adapters: AdapterSettings,
@@ -62,6 +193,203 @@ pub struct AppSettings {
     pub hide_hqp_page: bool,
     #[serde(default)]
     pub hide_lms_page: bool,
+    #[serde(default)]
+    pub polling: PollingSettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
+}
+
+/// Scheduled config backup settings
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BackupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u64,
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_backup_interval_hours(),
+            keep: default_backup_keep(),
+            directory: None,
+        }
+    }
+}
+
+/// A config backup on the server
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct BackupInfo {
+    pub name: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// Response from GET /api/backups
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct BackupList {
+    pub directory: String,
+    pub backups: Vec<BackupInfo>,
+}
+
+/// Response from POST /api/backups/restore
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct BackupRestoreResult {
+    pub restored: Vec<String>,
+}
+
+/// Adapter lifecycle entry from GET /api/adapters
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdapterInfo {
+    pub name: String,
+    pub enabled: bool,
+    pub running: bool,
+    pub last_error: Option<String>,
+    pub restart_count: u32,
+}
+
+/// Response from GET /api/adapters
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdaptersResponse {
+    pub adapters: Vec<AdapterInfo>,
+}
+
+/// Named API token (secret is only returned on creation)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    /// `read_only`, `control`, `admin` or `knob`
+    pub scope: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+}
+
+/// Response from GET /api/tokens
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokensResponse {
+    pub tokens: Vec<ApiToken>,
+}
+
+/// Response from POST /api/tokens
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct CreatedToken {
+    pub id: String,
+    pub name: String,
+    pub scope: String,
+    pub token: String,
+}
+
+/// Response from POST /api/pair/code
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PairingCode {
+    pub code: String,
+    pub scope: String,
+    /// Unix seconds
+    pub expires_at: u64,
+}
+
+/// Device or player reported by an adapter
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdapterDevice {
+    pub name: String,
+    #[serde(default)]
+    pub state: String,
+}
+
+/// Server found by LMS or HQPlayer discovery
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct DiscoveredServer {
+    pub host: String,
+    #[serde(alias = "json_port")]
+    pub port: u16,
+    pub name: String,
+}
+
+/// Response from GET/PUT /api/adapters/{name}/config
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdapterConfig {
+    pub name: String,
+    pub enabled: bool,
+    pub connected: bool,
+    #[serde(default)]
+    pub core_name: Option<String>,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub web_port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub has_password: bool,
+    /// Spotify app client id
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub has_client_secret: bool,
+    /// Spotify account authorized
+    #[serde(default)]
+    pub authorized: bool,
+    /// Spotify account display name
+    #[serde(default)]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub redirect_uri: Option<String>,
+    /// Plexamp player hosts
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// Plex token set for Plexamp
+    #[serde(default)]
+    pub has_token: bool,
+    pub poll_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub devices: Vec<AdapterDevice>,
+    #[serde(default)]
+    pub discovered: Vec<DiscoveredServer>,
+}
+
+/// Body for PUT /api/adapters/{name}/config (omitted fields are unchanged)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdapterConfigUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_secs: Option<u64>,
 }
 
 // =============================================================================
@@ -74,11 +402,124 @@ pub struct Zone {
     pub zone_name: String,
     pub source: Option<String>,
     pub dsp: Option<ZoneDsp>,
+    #[serde(default)]
+    pub metadata: Option<ZoneMetadata>,
+    /// Missing from older servers, in which case every control is shown
+    #[serde(default)]
+    pub capabilities: Option<ZoneCapabilities>,
+    /// ReplayGain mode, only for backends that expose one
+    #[serde(default)]
+    pub volume_leveling: Option<String>,
+}
+
+/// Controls a zone's backend supports
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneCapabilities {
+    pub can_seek: bool,
+    pub can_shuffle: bool,
+    pub has_volume: bool,
+    pub has_queue: bool,
+    pub supports_grouping: bool,
+    #[serde(default)]
+    pub has_eq: bool,
+    #[serde(default)]
+    pub has_input: bool,
+}
+
+/// User-assigned room, icon, order and color (see `PUT /zones/{zone_id}/metadata`)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_weight: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_step: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+}
+
+impl Zone {
+    /// Room from metadata, if assigned
+    pub fn room(&self) -> Option<&str> {
+        self.metadata.as_ref().and_then(|m| m.room.as_deref())
+    }
+
+    /// Sort weight from metadata (unset counts as 0)
+    pub fn sort_weight(&self) -> i32 {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.sort_weight)
+            .unwrap_or(0)
+    }
+
+    /// Whether to show volume controls (true when capabilities are unknown)
+    pub fn has_volume(&self) -> bool {
+        self.capabilities.is_none_or(|c| c.has_volume)
+    }
+
+    /// Whether the backend has tone or DSP settings (`/zones/{zone_id}/eq`)
+    pub fn has_eq(&self) -> bool {
+        self.capabilities.is_some_and(|c| c.has_eq)
+    }
+
+    /// Whether the backend can switch inputs (`/zones/{zone_id}/input`)
+    pub fn has_input(&self) -> bool {
+        self.capabilities.is_some_and(|c| c.has_input)
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct ZoneDsp {
     pub r#type: Option<String>,
+    /// Linked HQPlayer, CamillaDSP or MiniDSP instance name
+    #[serde(default)]
+    pub instance: Option<String>,
+}
+
+/// Preset slot of a MiniDSP unit
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct MiniDspPreset {
+    pub index: u8,
+    pub name: String,
+}
+
+/// Response from GET /minidsp/zones/{zone_id}
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct MiniDspStatus {
+    pub instance: String,
+    pub preset: u8,
+    pub presets: Vec<MiniDspPreset>,
+    /// Master volume in dB
+    pub volume: f64,
+    pub mute: bool,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Wake-on-LAN settings in GET /roon/core/power
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct WakeOnLanConfig {
+    pub mac: Option<String>,
+    pub broadcast: String,
+    pub port: u16,
+}
+
+/// Response from GET /roon/core/power
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RoonCorePower {
+    pub connected: bool,
+    pub core_name: Option<String>,
+    pub wake_on_lan: WakeOnLanConfig,
+    /// Power actions the bridge can perform (`wake`)
+    #[serde(default)]
+    pub actions: Vec<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
@@ -86,6 +527,75 @@ pub struct ZonesResponse {
     pub zones: Vec<Zone>,
 }
 
+/// One line of lyrics; `time_secs` is set for synced lyrics
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct LyricLine {
+    #[serde(default)]
+    pub time_secs: Option<f64>,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Lyrics {
+    pub source: String,
+    pub synced: bool,
+    #[serde(default)]
+    pub instrumental: bool,
+    pub lines: Vec<LyricLine>,
+}
+
+/// Response from GET /zones/{zone_id}/lyrics
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneLyricsResponse {
+    pub enabled: bool,
+    pub position: Option<f64>,
+    pub current_line: Option<usize>,
+    pub lyrics: Option<Lyrics>,
+}
+
+/// Response from GET /zones/{zone_id}/eq
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneEqResponse {
+    pub supported: bool,
+    pub tone: Option<ZoneTone>,
+    pub matrix: Option<ZoneEqMatrix>,
+}
+
+/// Bass and treble with the range the player accepts
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneTone {
+    pub bass: i32,
+    pub treble: i32,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// HQPlayer matrix profiles available to a zone
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneEqMatrix {
+    pub profiles: Vec<HqpMatrixProfile>,
+    pub current: Option<HqpMatrixProfile>,
+}
+
+/// Response from GET /zones/{zone_id}/input
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneInputResponse {
+    pub supported: bool,
+    #[serde(default)]
+    pub inputs: Vec<ZoneInputOption>,
+    /// `id` of the selected input
+    pub current: Option<String>,
+}
+
+/// One input a zone's device can switch to
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneInputOption {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub input_type: String,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct NowPlaying {
     pub line1: Option<String>,
@@ -122,6 +632,18 @@ pub struct LmsConfig {
 }
 
 /// Wrapper for /lms/players response
+/// Response from GET /lms/rescan/status
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct LmsScanStatus {
+    pub scanning: bool,
+    pub step: Option<String>,
+    pub done: Option<u64>,
+    pub total: Option<u64>,
+    /// Seconds since epoch
+    pub last_scan: Option<i64>,
+    pub last_scan_failed: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct LmsPlayersResponse {
     pub players: Vec<LmsPlayer>,
@@ -151,6 +673,10 @@ pub struct HqpConfig {
     pub port: Option<u16>,
     pub web_port: Option<u16>,
     #[serde(default)]
+    pub web_https: bool,
+    #[serde(default)]
+    pub web_cert_sha256: Option<String>,
+    #[serde(default)]
     pub has_web_credentials: bool,
 }
 
@@ -240,6 +766,35 @@ pub struct KnobDevice {
     pub version: Option<String>,
     pub last_seen: Option<String>,
     pub status: Option<KnobStatus>,
+    /// Device token state; None until the knob has been issued one
+    #[serde(default)]
+    pub token: Option<KnobTokenStatus>,
+    /// Outcome of the knob's last OTA update
+    #[serde(default)]
+    pub last_update: Option<KnobUpdateReport>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct KnobUpdateReport {
+    pub version: String,
+    pub success: bool,
+    pub error_code: Option<String>,
+    pub reported_at: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct KnobTokenStatus {
+    pub issued_at: u64,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// A rotated token the knob hasn't picked up yet
+    pub pending: bool,
+}
+
+/// Response from GET/PUT /api/knobs/auth
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct KnobAuth {
+    pub required: bool,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
@@ -282,6 +837,77 @@ pub struct KnobConfig {
     pub cpu_freq_scaling_enabled: Option<bool>,
     /// Poll interval when playback stopped (seconds)
     pub sleep_poll_stopped_sec: Option<u32>,
+    /// Smaller artwork, slower polling and a trimmed payload for weak Wi-Fi
+    #[serde(default)]
+    pub low_bandwidth: Option<bool>,
+    /// `current`, `recent_covers`, `folder` or `artist`; unset is `current`
+    #[serde(default)]
+    pub art_mode_source: Option<String>,
+    /// Encoder feel; unset is no acceleration, one step per detent
+    #[serde(default)]
+    pub encoder: Option<KnobEncoder>,
+    /// Gesture that cycles HQPlayer filters; unset is off
+    #[serde(default)]
+    pub hqp_cycle: Option<KnobHqpCycle>,
+    // Clock settings; null follows the bridge host
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub clock_24h: Option<bool>,
+    #[serde(default)]
+    pub ntp_server: Option<String>,
+    /// Clock settings the knob actually gets (read-only)
+    #[serde(default, skip_serializing)]
+    pub time: Option<KnobTime>,
+}
+
+/// How turning a knob maps to volume steps
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct KnobEncoder {
+    /// `off`, `linear` or `exponential`
+    pub acceleration: String,
+    pub detents_per_step: u8,
+    pub fine_on_press: bool,
+}
+
+impl Default for KnobEncoder {
+    fn default() -> Self {
+        Self {
+            acceleration: "off".to_string(),
+            detents_per_step: 1,
+            fine_on_press: false,
+        }
+    }
+}
+
+/// Gesture that steps the zone's HQPlayer filter or shaper
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct KnobHqpCycle {
+    /// `off`, `double_press` or `long_press`
+    pub gesture: String,
+    /// `filter` or `shaper`
+    pub setting: String,
+}
+
+impl Default for KnobHqpCycle {
+    fn default() -> Self {
+        Self {
+            gesture: "off".to_string(),
+            setting: "filter".to_string(),
+        }
+    }
+}
+
+/// Effective clock settings served to a knob, host defaults filled in
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct KnobTime {
+    pub timezone: Option<String>,
+    pub posix_tz: String,
+    pub locale: String,
+    pub clock_24h: bool,
+    pub ntp_server: String,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
@@ -295,10 +921,180 @@ pub struct FetchFirmwareResponse {
     pub error: Option<String>,
 }
 
+/// Response from GET /firmware/fleet
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FirmwareFleet {
+    pub available: Option<String>,
+    pub knob_count: usize,
+    pub versions: Vec<FleetVersion>,
+    pub channels: std::collections::BTreeMap<String, usize>,
+    pub pending: Vec<FleetPending>,
+    pub last_results: FleetResults,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FleetVersion {
+    pub version: Option<String>,
+    pub channel: Option<String>,
+    pub knobs: usize,
+}
+
+/// Knob behind the served firmware
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FleetPending {
+    pub knob_id: String,
+    pub name: String,
+    pub version: Option<String>,
+    /// Held back by the battery gate
+    pub deferred: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FleetResults {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub reports: Vec<FleetReport>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct FleetReport {
+    pub knob_id: String,
+    pub name: String,
+    pub version: String,
+    pub success: bool,
+    pub error_code: Option<String>,
+    pub reported_at: Option<String>,
+}
+
+/// Single check from POST /debug/connectivity
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConnectivityCheck {
+    pub check: String,
+    pub target: String,
+    pub ok: bool,
+    pub latency_ms: Option<u64>,
+    pub detail: String,
+}
+
+/// Network interface address from POST /debug/connectivity
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub ip: String,
+    pub loopback: bool,
+    /// Used for UPnP/OpenHome/HQPlayer discovery
+    pub selected: bool,
+}
+
+/// Response from POST /debug/connectivity
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConnectivityReport {
+    pub ok: bool,
+    pub checks: Vec<ConnectivityCheck>,
+    #[serde(default)]
+    pub interfaces: Vec<NetworkInterface>,
+}
+
+/// Backend call from GET /debug/adapters/{name}/ops
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct OpRecord {
+    pub operation: String,
+    pub target: Option<String>,
+    pub started_at: u64,
+    pub duration_ms: u64,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Response from GET /debug/adapters/{name}/ops
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AdapterOpsResponse {
+    pub adapter: String,
+    pub ops: Vec<OpRecord>,
+}
+
+// =============================================================================
+// Cover Wall Types
+// =============================================================================
+
+/// Recently played album from GET /history/albums
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct WallAlbum {
+    pub artist: String,
+    pub album: String,
+    /// Zone the album was last played on (serves the cover)
+    pub zone_id: String,
+    pub zone_name: String,
+    pub image_key: String,
+    pub played_at: String,
+}
+
+/// Response from GET /history/albums
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct WallAlbumsResponse {
+    pub albums: Vec<WallAlbum>,
+}
+
 // =============================================================================
 // Client-side fetch helpers (for use in effects/resources)
 // =============================================================================
 
+/// localStorage key for the admin token sent with the UI's requests
+#[cfg(target_arch = "wasm32")]
+const ADMIN_TOKEN_KEY: &str = "hifi-admin-token";
+
+/// The admin token saved in this browser, if any
+#[cfg(target_arch = "wasm32")]
+pub fn admin_token() -> Option<String> {
+    web_sys::window()?
+        .local_storage()
+        .ok()??
+        .get_item(ADMIN_TOKEN_KEY)
+        .ok()?
+        .filter(|t| !t.is_empty())
+}
+
+/// SSR stub - no browser storage
+#[cfg(not(target_arch = "wasm32"))]
+pub fn admin_token() -> Option<String> {
+    None
+}
+
+/// Save this browser's admin token (an empty one forgets it)
+#[cfg(target_arch = "wasm32")]
+pub fn set_admin_token(token: &str) {
+    if let Some(Ok(Some(storage))) = web_sys::window().map(|w| w.local_storage()) {
+        let token = token.trim();
+        let _ = if token.is_empty() {
+            storage.remove_item(ADMIN_TOKEN_KEY)
+        } else {
+            storage.set_item(ADMIN_TOKEN_KEY, token)
+        };
+    }
+}
+
+/// SSR stub - no browser storage
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_admin_token(_token: &str) {}
+
+/// Request headers, with the JSON content type for requests with a body and
+/// the saved admin token as `Authorization`
+#[cfg(target_arch = "wasm32")]
+fn request_headers(json_body: bool) -> Result<web_sys::Headers, String> {
+    let headers = web_sys::Headers::new().map_err(|e| format!("{:?}", e))?;
+    if json_body {
+        headers
+            .set("Content-Type", "application/json")
+            .map_err(|e| format!("{:?}", e))?;
+    }
+    if let Some(token) = admin_token() {
+        headers
+            .set("Authorization", &format!("Bearer {}", token))
+            .map_err(|e| format!("{:?}", e))?;
+    }
+    Ok(headers)
+}
+
 /// Fetch JSON from a URL (client-side only)
 #[cfg(target_arch = "wasm32")]
 pub async fn fetch_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T, String> {
@@ -309,6 +1105,7 @@ pub async fn fetch_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T, St
     let window = web_sys::window().ok_or("No window")?;
     let opts = RequestInit::new();
     opts.set_method("GET");
+    opts.set_headers(&request_headers(false)?);
 
     let request = Request::new_with_str_and_init(url, &opts).map_err(|e| format!("{:?}", e))?;
 
@@ -339,14 +1136,11 @@ pub async fn post_json<T: Serialize, R: for<'de> Deserialize<'de>>(
 ) -> Result<R, String> {
     use wasm_bindgen::JsCast;
     use wasm_bindgen_futures::JsFuture;
-    use web_sys::{Headers, Request, RequestInit, Response};
+    use web_sys::{Request, RequestInit, Response};
 
     let window = web_sys::window().ok_or("No window")?;
 
-    let headers = Headers::new().map_err(|e| format!("{:?}", e))?;
-    headers
-        .set("Content-Type", "application/json")
-        .map_err(|e| format!("{:?}", e))?;
+    let headers = request_headers(true)?;
 
     let body_str = serde_json::to_string(body).map_err(|e| e.to_string())?;
 
@@ -383,14 +1177,11 @@ pub async fn post_json<T: Serialize, R: for<'de> Deserialize<'de>>(
 #[cfg(target_arch = "wasm32")]
 pub async fn post_json_no_response<T: Serialize>(url: &str, body: &T) -> Result<(), String> {
     use wasm_bindgen_futures::JsFuture;
-    use web_sys::{Headers, Request, RequestInit};
+    use web_sys::{Request, RequestInit};
 
     let window = web_sys::window().ok_or("No window")?;
 
-    let headers = Headers::new().map_err(|e| format!("{:?}", e))?;
-    headers
-        .set("Content-Type", "application/json")
-        .map_err(|e| format!("{:?}", e))?;
+    let headers = request_headers(true)?;
 
     let body_str = serde_json::to_string(body).map_err(|e| e.to_string())?;
 
@@ -413,3 +1204,88 @@ pub async fn post_json_no_response<T: Serialize>(url: &str, body: &T) -> Result<
 pub async fn post_json_no_response<T: Serialize>(_url: &str, _body: &T) -> Result<(), String> {
     Err("post_json_no_response is only available in browser".to_string())
 }
+
+/// PUT JSON without expecting response body
+#[cfg(target_arch = "wasm32")]
+pub async fn put_json_no_response<T: Serialize>(url: &str, body: &T) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{Request, RequestInit, Response};
+
+    let window = web_sys::window().ok_or("No window")?;
+
+    let headers = request_headers(true)?;
+
+    let body_str = serde_json::to_string(body).map_err(|e| e.to_string())?;
+
+    let opts = RequestInit::new();
+    opts.set_method("PUT");
+    opts.set_headers(&headers);
+    opts.set_body(&wasm_bindgen::JsValue::from_str(&body_str));
+
+    let request = Request::new_with_str_and_init(url, &opts).map_err(|e| format!("{:?}", e))?;
+
+    let resp_value = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+    let resp: Response = resp_value.dyn_into().map_err(|_| "Not a Response")?;
+    if !resp.ok() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+
+    Ok(())
+}
+
+/// SSR stub - returns error (should not be called during SSR)
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn put_json_no_response<T: Serialize>(_url: &str, _body: &T) -> Result<(), String> {
+    Err("put_json_no_response is only available in browser".to_string())
+}
+
+/// DELETE a URL without expecting response body
+#[cfg(target_arch = "wasm32")]
+pub async fn delete_no_response(url: &str) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{Request, RequestInit, Response};
+
+    let window = web_sys::window().ok_or("No window")?;
+    let opts = RequestInit::new();
+    opts.set_method("DELETE");
+    opts.set_headers(&request_headers(false)?);
+
+    let request = Request::new_with_str_and_init(url, &opts).map_err(|e| format!("{:?}", e))?;
+
+    let resp_value = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+
+    let resp: Response = resp_value.dyn_into().map_err(|_| "Not a Response")?;
+    if !resp.ok() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+
+    Ok(())
+}
+
+/// SSR stub - returns error (should not be called during SSR)
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn delete_no_response(_url: &str) -> Result<(), String> {
+    Err("delete_no_response is only available in browser".to_string())
+}
+
+/// Resolve after `ms` milliseconds
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep_ms(ms: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// SSR stub - resolves immediately (timers only run in the browser)
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep_ms(_ms: i32) {}
@@ -2,7 +2,7 @@
 
 use dioxus::prelude::*;
 
-use crate::app::api::{HqpMatrixProfile, HqpProfile};
+use crate::app::api::{HqpMatrixProfile, HqpOption, HqpPipeline, HqpProfile};
 
 /// HQPlayer profile selector dropdown.
 #[component]
@@ -118,3 +118,48 @@ pub fn HqpControlsCompact(
         }
     }
 }
+
+/// Compact pipeline selects for cards (filter, shaper, sample rate).
+///
+/// `on_change` receives `(setting, value)` as accepted by the pipeline endpoints.
+#[component]
+pub fn HqpPipelineCompact(
+    /// Pipeline state with the selectable options
+    pipeline: HqpPipeline,
+    /// Called when a setting is changed
+    on_change: EventHandler<(String, String)>,
+) -> Element {
+    let settings = pipeline.settings.unwrap_or_default();
+    let selects: Vec<(&'static str, &'static str, Vec<HqpOption>, String)> = [
+        ("filter1x", "Filter", settings.filter1x),
+        ("shaper", "Shaper", settings.shaper),
+        ("samplerate", "Sample rate", settings.samplerate),
+    ]
+    .into_iter()
+    .filter_map(|(setting, label, options)| {
+        let options = options?;
+        let selected = options.selected.map(|s| s.value).unwrap_or_default();
+        Some((setting, label, options.options, selected))
+    })
+    .collect();
+
+    rsx! {
+        div { class: "flex flex-wrap gap-2 mt-2",
+            for (setting, label, options, selected) in selects {
+                select {
+                    key: "{setting}",
+                    class: "input flex-1 min-w-0",
+                    "aria-label": label,
+                    onchange: move |evt| on_change.call((setting.to_string(), evt.value())),
+                    for opt in options {
+                        option {
+                            value: "{opt.value}",
+                            selected: opt.value == selected,
+                            "{opt.label.as_deref().unwrap_or(&opt.value)}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
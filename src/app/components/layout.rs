@@ -25,6 +25,9 @@ pub struct LayoutProps {
     /// Hide Knobs tab in nav
     #[props(default = false)]
     pub hide_knobs: bool,
+    /// Full-window content without nav or footer (ambient pages)
+    #[props(default = false)]
+    pub bare: bool,
 }
 
 /// Main layout component wrapping all pages.
@@ -72,17 +75,21 @@ pub fn Layout(props: LayoutProps) -> Element {
         }
 
         // Body content
-        Nav {
-            active: props.nav_active.clone(),
-            hide_hqp: props.hide_hqp,
-            hide_lms: props.hide_lms,
-            hide_knobs: props.hide_knobs,
-        }
-        main { class: "max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 mt-4 overflow-x-hidden",
-            {props.children}
-        }
-        footer { class: "max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 text-center py-3",
-            small { class: "text-muted", "Unified Hi-Fi Control v{version} ({git_sha})" }
+        if props.bare {
+            main { class: "overflow-x-hidden", {props.children} }
+        } else {
+            Nav {
+                active: props.nav_active.clone(),
+                hide_hqp: props.hide_hqp,
+                hide_lms: props.hide_lms,
+                hide_knobs: props.hide_knobs,
+            }
+            main { class: "max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 mt-4 overflow-x-hidden",
+                {props.children}
+            }
+            footer { class: "max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 text-center py-3",
+                small { class: "text-muted", "Unified Hi-Fi Control v{version} ({git_sha})" }
+            }
         }
     }
 }
@@ -9,7 +9,7 @@ pub mod volume;
 
 pub use error_alert::ErrorAlert;
 pub use form_inputs::{PowerModeInput, ToggleInput};
-pub use hqp_controls::{HqpControlsCompact, HqpMatrixSelect, HqpProfileSelect};
+pub use hqp_controls::{HqpControlsCompact, HqpMatrixSelect, HqpPipelineCompact, HqpProfileSelect};
 pub use layout::Layout;
 pub use nav::Nav;
 pub use volume::{VolumeControlsCompact, VolumeControlsFull};
@@ -13,7 +13,7 @@ pub mod settings_context;
 pub mod sse;
 pub mod theme;
 
-use pages::{HqPlayer, Knobs, Lms, Settings, Zones};
+use pages::{HqPlayer, Knobs, Lms, Settings, Wall, Zones};
 use settings_context::use_settings_provider;
 use sse::use_sse_provider;
 use theme::use_theme_provider;
@@ -48,4 +48,6 @@ pub enum Route {
     Knobs {},
     #[route("/settings")]
     Settings {},
+    #[route("/wall")]
+    Wall {},
 }
@@ -21,6 +21,9 @@ struct HqpConfigureRequest {
     username: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     password: Option<String>,
+    web_https: bool,
+    /// Empty clears the pin
+    web_cert_sha256: String,
 }
 
 /// Zone link response
@@ -47,6 +50,34 @@ struct HqpInstance {
     host: Option<String>,
 }
 
+/// NAA endpoints and assignments response
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+struct NaaResponse {
+    endpoints: Vec<NaaEndpoint>,
+    last_scan: Option<u64>,
+    assignments: Vec<NaaAssignment>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+struct NaaEndpoint {
+    host: String,
+    name: String,
+    version: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+struct NaaAssignment {
+    instance: String,
+    naa: String,
+    online: bool,
+}
+
+/// NAA assignment request
+#[derive(Clone, serde::Serialize)]
+struct NaaAssignRequest {
+    naa: Option<String>,
+}
+
 /// Zone link request
 #[derive(Clone, serde::Serialize)]
 struct ZoneLinkRequest {
@@ -78,6 +109,8 @@ pub fn HqPlayer() -> Element {
     let mut host = use_signal(String::new);
     let mut port = use_signal(|| 4321u16);
     let mut web_port = use_signal(|| 8088u16);
+    let mut web_https = use_signal(|| false);
+    let mut cert_pin = use_signal(String::new);
     let username = use_signal(String::new);
     let password = use_signal(String::new);
     let mut has_credentials = use_signal(|| false);
@@ -141,6 +174,8 @@ pub fn HqPlayer() -> Element {
             host.set(cfg.host.clone().unwrap_or_default());
             port.set(cfg.port.unwrap_or(4321));
             web_port.set(cfg.web_port.unwrap_or(8088));
+            web_https.set(cfg.web_https);
+            cert_pin.set(cfg.web_cert_sha256.clone().unwrap_or_default());
             has_credentials.set(cfg.has_web_credentials);
         }
     });
@@ -218,6 +253,8 @@ pub fn HqPlayer() -> Element {
                 web_port: wp,
                 username: if u.is_empty() { None } else { Some(u) },
                 password: if pw.is_empty() { None } else { Some(pw) },
+                web_https: web_https(),
+                web_cert_sha256: cert_pin(),
             };
 
             match api::post_json::<_, serde_json::Value>("/hqplayer/configure", &req).await {
@@ -387,6 +424,8 @@ pub fn HqPlayer() -> Element {
                             host: host,
                             port: port,
                             web_port: web_port,
+                            web_https: web_https,
+                            cert_pin: cert_pin,
                             username: username,
                             password: password,
                             has_credentials: has_credentials(),
@@ -416,6 +455,8 @@ pub fn HqPlayer() -> Element {
                                 host: host,
                                 port: port,
                                 web_port: web_port,
+                                web_https: web_https,
+                                cert_pin: cert_pin,
                                 username: username,
                                 password: password,
                                 has_credentials: has_credentials(),
@@ -427,6 +468,11 @@ pub fn HqPlayer() -> Element {
                 }
             }
 
+            // NAA outputs, with an alert when an assigned NAA disappears
+            if !instances_list.is_empty() {
+                NaaOutputs { instances: instances_list.clone() }
+            }
+
             // Linked Zone Controls (only if connected and has linked zones)
             if is_connected && !linked_zones.is_empty() {
                 section { id: "linked-zones", class: "mb-8",
@@ -477,6 +523,145 @@ pub fn HqPlayer() -> Element {
     }
 }
 
+/// Which NAA each instance outputs to, and whether it is on the network
+#[component]
+fn NaaOutputs(instances: Vec<HqpInstance>) -> Element {
+    let sse = use_sse();
+    let mut naa_error = use_signal(|| None::<String>);
+    let mut scanning = use_signal(|| false);
+    let mut naa = use_resource(|| async { api::fetch_json::<NaaResponse>("/hqp/naa").await.ok() });
+
+    let event_count = sse.event_count;
+    use_effect(move || {
+        let _ = event_count();
+        if sse.should_refresh_hqp() {
+            naa.restart();
+        }
+    });
+
+    let scan = move |_| {
+        scanning.set(true);
+        naa_error.set(None);
+        spawn(async move {
+            if let Err(e) = api::fetch_json::<serde_json::Value>("/hqp/naa/discover").await {
+                naa_error.set(Some(format!("NAA scan failed: {e}")));
+            }
+            scanning.set(false);
+            naa.restart();
+        });
+    };
+
+    let mut assign = move |(instance, value): (String, String)| {
+        let url = format!("/hqp/instances/{}/naa", urlencoding::encode(&instance));
+        let req = NaaAssignRequest {
+            naa: (!value.is_empty()).then_some(value),
+        };
+        naa_error.set(None);
+        spawn(async move {
+            match api::put_json_no_response(&url, &req).await {
+                Ok(()) => naa.restart(),
+                Err(e) => naa_error.set(Some(format!("NAA assignment failed: {e}"))),
+            }
+        });
+    };
+
+    let current = naa.read().clone().flatten().unwrap_or_default();
+    let lost: Vec<NaaAssignment> = current
+        .assignments
+        .iter()
+        .filter(|a| !a.online && current.last_scan.is_some())
+        .cloned()
+        .collect();
+
+    rsx! {
+        for assignment in lost {
+            div { class: "bg-red-900/20 border border-red-500/50 rounded-lg p-4 mb-6", role: "alert",
+                p { class: "text-red-400 m-0",
+                    "NAA {assignment.naa} for {assignment.instance} is not answering on the network"
+                }
+            }
+        }
+        section { id: "hqp-naa", class: "mb-8",
+            div { class: "flex items-center justify-between mb-4",
+                h2 { class: "text-lg font-semibold", "NAA Outputs" }
+                button {
+                    class: "btn btn-ghost btn-sm",
+                    disabled: scanning(),
+                    onclick: scan,
+                    if scanning() { "Scanning..." } else { "Scan network" }
+                }
+            }
+            div { class: "card p-6",
+                table { class: "w-full text-sm",
+                    tbody {
+                        for inst in instances {
+                            {
+                                let assigned = current
+                                    .assignments
+                                    .iter()
+                                    .find(|a| a.instance == inst.name)
+                                    .cloned();
+                                let selected = assigned.as_ref().map(|a| a.naa.clone()).unwrap_or_default();
+                                let unlisted = !selected.is_empty()
+                                    && !current
+                                        .endpoints
+                                        .iter()
+                                        .any(|e| e.name == selected || e.host == selected);
+                                let status = assigned.as_ref().map(|a| {
+                                    if a.online {
+                                        ("status-ok", "Online")
+                                    } else if current.last_scan.is_some() {
+                                        ("status-err", "Not found")
+                                    } else {
+                                        ("text-muted", "Not scanned yet")
+                                    }
+                                });
+                                let name = inst.name.clone();
+                                rsx! {
+                                    tr { key: "{inst.name}",
+                                        td { class: "py-2 pr-3 font-semibold", "{inst.name}" }
+                                        td { class: "py-2 pr-3",
+                                            select {
+                                                class: "input",
+                                                "aria-label": "NAA for {inst.name}",
+                                                onchange: move |evt| assign((name.clone(), evt.value())),
+                                                option { value: "", selected: selected.is_empty(), "Not set" }
+                                                for endpoint in current.endpoints.iter() {
+                                                    option {
+                                                        value: "{endpoint.name}",
+                                                        selected: endpoint.name == selected || endpoint.host == selected,
+                                                        "{endpoint.name} ({endpoint.host}, v{endpoint.version})"
+                                                    }
+                                                }
+                                                if unlisted {
+                                                    option { value: "{selected}", selected: true, "{selected}" }
+                                                }
+                                            }
+                                        }
+                                        td { class: "py-2",
+                                            if let Some((class, text)) = status {
+                                                span { class: "{class}", "{text}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if current.endpoints.is_empty() {
+                    p { class: "text-muted text-sm mt-3",
+                        "No NAA endpoints seen yet. Scan the network to look for networkaudiod."
+                    }
+                }
+                if let Some(error) = naa_error() {
+                    p { class: "text-sm text-error mt-2", "{error}" }
+                }
+            }
+        }
+    }
+}
+
 /// Linked zone card with playback controls
 #[component]
 fn LinkedZoneCard(
@@ -507,7 +692,7 @@ fn LinkedZoneCard(
         } else {
             "?"
         };
-        format!("{}{}k={}", base_image_url, sep, key)
+        format!("{}{}k={}", base_image_url, sep, urlencoding::encode(&key))
     } else {
         base_image_url
     };
@@ -606,6 +791,8 @@ fn ConfigForm(
     host: Signal<String>,
     port: Signal<u16>,
     web_port: Signal<u16>,
+    web_https: Signal<bool>,
+    cert_pin: Signal<String>,
     username: Signal<String>,
     password: Signal<String>,
     has_credentials: bool,
@@ -652,6 +839,27 @@ fn ConfigForm(
                     }
                 }
             }
+            label { class: "flex items-center gap-2 text-sm font-medium",
+                input {
+                    r#type: "checkbox",
+                    class: "checkbox",
+                    checked: web_https(),
+                    onchange: move |_| web_https.toggle(),
+                }
+                "Web UI uses HTTPS"
+            }
+            if web_https() {
+                div {
+                    label { class: "block text-sm font-medium mb-1", "Certificate SHA-256 (self-signed only)" }
+                    input {
+                        class: "input w-full font-mono",
+                        r#type: "text",
+                        placeholder: "AB:CD:... (leave empty for a CA-signed certificate)",
+                        value: "{cert_pin}",
+                        oninput: move |evt| cert_pin.set(evt.value())
+                    }
+                }
+            }
             div { class: "grid grid-cols-1 sm:grid-cols-2 gap-4",
                 div {
                     label { class: "block text-sm font-medium mb-1", "Username" }
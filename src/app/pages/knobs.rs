@@ -5,12 +5,52 @@
 use dioxus::prelude::*;
 
 use crate::app::api::{
-    self, FetchFirmwareResponse, FirmwareVersion, KnobConfig, KnobConfigResponse, KnobDevice,
-    KnobDevicesResponse, PowerModeConfig, Zone, ZonesResponse,
+    self, FetchFirmwareResponse, FirmwareFleet, FirmwareVersion, KnobAuth, KnobConfig,
+    KnobConfigResponse, KnobDevice, KnobDevicesResponse, KnobEncoder, KnobHqpCycle, KnobTime,
+    PowerModeConfig, Zone, ZonesResponse,
 };
 use crate::app::components::Layout;
 use crate::app::sse::use_sse;
 
+/// Clock form state; empty fields (and `None`) follow the bridge host
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ClockForm {
+    timezone: String,
+    locale: String,
+    clock_24h: Option<bool>,
+    ntp_server: String,
+}
+
+/// Art mode sources as (value, label)
+const ART_MODE_SOURCES: [(&str, &str); 4] = [
+    ("current", "Current cover"),
+    ("recent_covers", "Recent covers"),
+    ("folder", "Image folder"),
+    ("artist", "Artist images (Roon)"),
+];
+
+/// Encoder acceleration curves as (value, label)
+const ACCELERATION_CURVES: [(&str, &str); 3] = [
+    ("off", "Off"),
+    ("linear", "Linear"),
+    ("exponential", "Exponential"),
+];
+
+/// Knob gestures that can cycle HQPlayer settings as (value, label)
+const HQP_CYCLE_GESTURES: [(&str, &str); 3] = [
+    ("off", "Off"),
+    ("double_press", "Double press"),
+    ("long_press", "Long press"),
+];
+
+/// HQPlayer settings a knob can cycle as (value, label)
+const HQP_CYCLE_SETTINGS: [(&str, &str); 2] = [("filter", "Filter"), ("shaper", "Shaper")];
+
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
 /// Knobs page component.
 #[component]
 pub fn Knobs() -> Element {
@@ -67,6 +107,14 @@ pub fn Knobs() -> Element {
     let mut wifi_power_save = use_signal(|| false);
     let mut cpu_freq_scaling = use_signal(|| false);
     let mut sleep_poll_stopped = use_signal(|| 60u32);
+    let mut low_bandwidth = use_signal(|| false);
+    let mut art_mode_source = use_signal(|| "current".to_string());
+    let mut encoder = use_signal(KnobEncoder::default);
+    let mut hqp_cycle = use_signal(KnobHqpCycle::default);
+
+    // Clock settings, and what the knob gets with host defaults filled in
+    let mut clock = use_signal(ClockForm::default);
+    let mut clock_defaults = use_signal(|| None::<KnobTime>);
 
     // Firmware fetch state
     let mut fw_fetching = use_signal(|| false);
@@ -79,6 +127,11 @@ pub fn Knobs() -> Element {
             .ok()
     });
 
+    // Load device token requirement
+    let mut knob_auth =
+        use_resource(|| async { api::fetch_json::<KnobAuth>("/api/knobs/auth").await.ok() });
+    let mut token_message = use_signal(|| None::<(bool, String)>); // (is_error, message)
+
     // Load zones resource
     let mut zones =
         use_resource(|| async { api::fetch_json::<ZonesResponse>("/zones").await.ok() });
@@ -90,6 +143,13 @@ pub fn Knobs() -> Element {
             .ok()
     });
 
+    // Load firmware fleet overview
+    let mut fleet = use_resource(|| async {
+        api::fetch_json::<FirmwareFleet>("/firmware/fleet")
+            .await
+            .ok()
+    });
+
     // Refresh on SSE events
     let event_count = sse.event_count;
     use_effect(move || {
@@ -97,6 +157,7 @@ pub fn Knobs() -> Element {
         if sse.should_refresh_knobs() {
             knobs.restart();
             zones.restart();
+            fleet.restart();
         }
     });
 
@@ -155,6 +216,18 @@ pub fn Knobs() -> Element {
                         wifi_power_save.set(cfg.wifi_power_save_enabled.unwrap_or(false));
                         cpu_freq_scaling.set(cfg.cpu_freq_scaling_enabled.unwrap_or(false));
                         sleep_poll_stopped.set(cfg.sleep_poll_stopped_sec.unwrap_or(60));
+                        low_bandwidth.set(cfg.low_bandwidth.unwrap_or(false));
+                        art_mode_source
+                            .set(cfg.art_mode_source.unwrap_or_else(|| "current".to_string()));
+                        encoder.set(cfg.encoder.unwrap_or_default());
+                        hqp_cycle.set(cfg.hqp_cycle.unwrap_or_default());
+                        clock.set(ClockForm {
+                            timezone: cfg.timezone.unwrap_or_default(),
+                            locale: cfg.locale.unwrap_or_default(),
+                            clock_24h: cfg.clock_24h,
+                            ntp_server: cfg.ntp_server.unwrap_or_default(),
+                        });
+                        clock_defaults.set(cfg.time);
                     } else {
                         config_name.set(String::new());
                         config_rotation_charging.set(180);
@@ -195,6 +268,12 @@ pub fn Knobs() -> Element {
                         wifi_power_save.set(false);
                         cpu_freq_scaling.set(false);
                         sleep_poll_stopped.set(60);
+                        low_bandwidth.set(false);
+                        art_mode_source.set("current".to_string());
+                        encoder.set(KnobEncoder::default());
+                        hqp_cycle.set(KnobHqpCycle::default());
+                        clock.set(ClockForm::default());
+                        clock_defaults.set(None);
                     }
                 }
                 Err(e) => {
@@ -225,6 +304,11 @@ pub fn Knobs() -> Element {
             let wifi_ps = wifi_power_save();
             let cpu_fs = cpu_freq_scaling();
             let poll_stopped = sleep_poll_stopped();
+            let low_bw = low_bandwidth();
+            let art_source = art_mode_source();
+            let encoder_cfg = encoder();
+            let hqp_cycle_cfg = hqp_cycle();
+            let clock_form = clock();
 
             save_status.set(Some("Saving...".to_string()));
 
@@ -244,6 +328,15 @@ pub fn Knobs() -> Element {
                     wifi_power_save_enabled: Some(wifi_ps),
                     cpu_freq_scaling_enabled: Some(cpu_fs),
                     sleep_poll_stopped_sec: Some(poll_stopped),
+                    low_bandwidth: Some(low_bw),
+                    art_mode_source: Some(art_source),
+                    encoder: Some(encoder_cfg),
+                    hqp_cycle: Some(hqp_cycle_cfg),
+                    timezone: non_empty(clock_form.timezone),
+                    locale: non_empty(clock_form.locale),
+                    clock_24h: clock_form.clock_24h,
+                    ntp_server: non_empty(clock_form.ntp_server),
+                    time: None,
                 };
 
                 let url = format!("/knob/config?knob_id={}", urlencoding::encode(&knob_id));
@@ -260,6 +353,48 @@ pub fn Knobs() -> Element {
         }
     };
 
+    // Toggle device token requirement
+    let mut toggle_required = move |required: bool| {
+        token_message.set(None);
+        spawn(async move {
+            if let Err(e) =
+                api::put_json_no_response("/api/knobs/auth", &KnobAuth { required }).await
+            {
+                token_message.set(Some((true, e)));
+            }
+            knob_auth.restart();
+        });
+    };
+
+    // Rotate a knob's device token
+    let rotate_token = move |knob_id: String| {
+        token_message.set(None);
+        spawn(async move {
+            let url = format!("/api/knobs/{}/token", urlencoding::encode(&knob_id));
+            match api::post_json::<_, serde_json::Value>(&url, &()).await {
+                Ok(_) => token_message.set(Some((
+                    false,
+                    "New token issued; the knob picks it up on its next config fetch".to_string(),
+                ))),
+                Err(e) => token_message.set(Some((true, e))),
+            }
+            knobs.restart();
+        });
+    };
+
+    // Revoke a knob's device tokens
+    let revoke_token = move |knob_id: String| {
+        token_message.set(None);
+        spawn(async move {
+            let url = format!("/api/knobs/{}/token", urlencoding::encode(&knob_id));
+            match api::delete_no_response(&url).await {
+                Ok(()) => token_message.set(Some((false, "Token revoked".to_string()))),
+                Err(e) => token_message.set(Some((true, e))),
+            }
+            knobs.restart();
+        });
+    };
+
     // Fetch firmware handler
     let fetch_firmware = move |_| {
         fw_fetching.set(true);
@@ -271,6 +406,7 @@ pub fn Knobs() -> Element {
                     if let Some(version) = resp.version {
                         fw_message.set(Some((false, format!("Downloaded v{}", version))));
                         firmware_version.restart();
+                        fleet.restart();
                     } else if let Some(err) = resp.error {
                         fw_message.set(Some((true, err)));
                     }
@@ -297,6 +433,12 @@ pub fn Knobs() -> Element {
         .map(|r| r.zones)
         .unwrap_or_default();
     let fw_version = firmware_version.read().clone().flatten().map(|r| r.version);
+    let fleet_overview = fleet.read().clone().flatten();
+    let tokens_required = knob_auth
+        .read()
+        .clone()
+        .flatten()
+        .is_some_and(|a| a.required);
 
     rsx! {
         Layout {
@@ -333,6 +475,7 @@ pub fn Knobs() -> Element {
                                     th { class: "text-left py-2 text-sm", "Zone" }
                                     th { class: "text-left py-2 text-sm", "Battery" }
                                     th { class: "text-left py-2 text-sm", "Last Seen" }
+                                    th { class: "text-left py-2 text-sm", "Token" }
                                     th { class: "text-left py-2 text-sm" }
                                 }
                             }
@@ -342,6 +485,8 @@ pub fn Knobs() -> Element {
                                         knob: knob.clone(),
                                         zones: zones_list.clone(),
                                         on_config: open_config,
+                                        on_rotate: rotate_token,
+                                        on_revoke: revoke_token,
                                     }
                                 }
                             }
@@ -350,6 +495,35 @@ pub fn Knobs() -> Element {
                 }
             }
 
+            // Device tokens section
+            section { id: "device-tokens-section", class: "mb-8",
+                div { class: "mb-4",
+                    h2 { class: "text-xl font-semibold", "Device Tokens" }
+                    p { class: "text-muted text-sm",
+                        "Each knob is issued its own token. Rotate replaces it; revoke locks the knob out while tokens are required."
+                    }
+                }
+                div { class: "card p-6",
+                    label { class: "flex items-center gap-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: tokens_required,
+                            onchange: move |_| toggle_required(!tokens_required),
+                        }
+                        "Require device tokens on knob routes"
+                    }
+                    if let Some((is_err, ref msg)) = token_message() {
+                        p { class: "mt-2",
+                            if is_err {
+                                span { class: "status-err", "{msg}" }
+                            } else {
+                                span { class: "status-ok", "✓ {msg}" }
+                            }
+                        }
+                    }
+                }
+            }
+
             // Firmware section
             section { id: "firmware-section", class: "mb-8",
                 div { class: "mb-4",
@@ -386,6 +560,9 @@ pub fn Knobs() -> Element {
                         }
                     }
                 }
+                if let Some(overview) = fleet_overview.filter(|f| f.knob_count > 0) {
+                    FleetOverview { fleet: overview }
+                }
             }
 
             // Config modal
@@ -409,6 +586,12 @@ pub fn Knobs() -> Element {
                     wifi_power_save: wifi_power_save(),
                     cpu_freq_scaling: cpu_freq_scaling(),
                     sleep_poll_stopped: sleep_poll_stopped(),
+                    low_bandwidth: low_bandwidth(),
+                    art_mode_source: art_mode_source(),
+                    encoder: encoder(),
+                    hqp_cycle: hqp_cycle(),
+                    clock: clock(),
+                    clock_defaults: clock_defaults(),
                     save_status: save_status(),
                     on_name_change: move |v| config_name.set(v),
                     on_rotation_charging_change: move |v| config_rotation_charging.set(v),
@@ -427,6 +610,11 @@ pub fn Knobs() -> Element {
                     on_wifi_power_save_change: move |v| wifi_power_save.set(v),
                     on_cpu_freq_scaling_change: move |v| cpu_freq_scaling.set(v),
                     on_sleep_poll_stopped_change: move |v| sleep_poll_stopped.set(v),
+                    on_low_bandwidth_change: move |v| low_bandwidth.set(v),
+                    on_art_mode_source_change: move |v| art_mode_source.set(v),
+                    on_encoder_change: move |v| encoder.set(v),
+                    on_hqp_cycle_change: move |v| hqp_cycle.set(v),
+                    on_clock_change: move |v| clock.set(v),
                     on_save: save_config,
                     on_close: move |_| modal_open.set(false),
                 }
@@ -499,9 +687,17 @@ fn knob_display_name(knob: &KnobDevice) -> String {
 
 /// Knob row component
 #[component]
-fn KnobRow(knob: KnobDevice, zones: Vec<Zone>, on_config: EventHandler<String>) -> Element {
+fn KnobRow(
+    knob: KnobDevice,
+    zones: Vec<Zone>,
+    on_config: EventHandler<String>,
+    on_rotate: EventHandler<String>,
+    on_revoke: EventHandler<String>,
+) -> Element {
     let status = knob.status.as_ref();
     let knob_id = knob.knob_id.clone();
+    let rotate_id = knob.knob_id.clone();
+    let revoke_id = knob.knob_id.clone();
 
     let battery = status
         .and_then(|s| {
@@ -519,7 +715,10 @@ fn KnobRow(knob: KnobDevice, zones: Vec<Zone>, on_config: EventHandler<String>)
     let zone_name = status
         .and_then(|s| s.zone_id.as_ref())
         .and_then(|zone_id| zones.iter().find(|z| &z.zone_id == zone_id))
-        .map(|z| z.zone_name.clone())
+        .map(|z| match z.room() {
+            Some(room) => format!("{} · {}", room, z.zone_name),
+            None => z.zone_name.clone(),
+        })
         .unwrap_or_else(|| "—".to_string());
 
     let ip = status
@@ -527,23 +726,148 @@ fn KnobRow(knob: KnobDevice, zones: Vec<Zone>, on_config: EventHandler<String>)
         .unwrap_or_else(|| "—".to_string());
 
     let version = knob.version.clone().unwrap_or_else(|| "—".to_string());
+    let last_update = knob.last_update.as_ref().map(|r| {
+        let when = format_ago(r.reported_at.as_deref());
+        if r.success {
+            (false, format!("Updated to v{} {}", r.version, when))
+        } else {
+            let code = r.error_code.as_deref().unwrap_or("unknown error");
+            (true, format!("v{} failed ({}) {}", r.version, code, when))
+        }
+    });
     let display_name = knob_display_name(&knob);
     let last_seen = format_ago(knob.last_seen.as_deref());
+    let has_token = knob.token.is_some();
+    let token_state = match knob.token {
+        Some(ref t) if t.pending => "Rotating",
+        Some(ref t) if t.last_used_at.is_some() => "Active",
+        Some(_) => "Issued",
+        None => "None",
+    };
 
     rsx! {
         tr { class: "border-b border-default",
             td { class: "py-2", "{display_name}" }
-            td { class: "py-2", "{version}" }
+            td { class: "py-2",
+                "{version}"
+                if let Some((failed, msg)) = last_update {
+                    span {
+                        class: if failed { "block text-xs status-err" } else { "block text-xs text-muted" },
+                        "{msg}"
+                    }
+                }
+            }
             td { class: "py-2", "{ip}" }
             td { class: "py-2", "{zone_name}" }
             td { class: "py-2", "{battery}" }
             td { class: "py-2 text-sm text-muted", "{last_seen}" }
-            td { class: "py-2",
+            td { class: "py-2 text-sm", "{token_state}" }
+            td { class: "py-2 flex gap-2",
                 button {
                     class: "btn btn-outline btn-sm",
                     onclick: move |_| on_config.call(knob_id.clone()),
                     "Config"
                 }
+                button {
+                    class: "btn btn-outline btn-sm",
+                    onclick: move |_| on_rotate.call(rotate_id.clone()),
+                    "Rotate"
+                }
+                if has_token {
+                    button {
+                        class: "btn btn-outline btn-sm",
+                        onclick: move |_| on_revoke.call(revoke_id.clone()),
+                        "Revoke"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Firmware posture across all knobs
+#[component]
+fn FleetOverview(fleet: FirmwareFleet) -> Element {
+    let knob_name = |name: &str, knob_id: &str| {
+        if name.is_empty() {
+            knob_id.to_string()
+        } else {
+            name.to_string()
+        }
+    };
+    let channels = fleet
+        .channels
+        .iter()
+        .map(|(channel, count)| format!("{} {}", count, channel))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    let versions: Vec<String> = fleet
+        .versions
+        .iter()
+        .map(|v| match v.version {
+            Some(ref version) => format!("v{} × {}", version, v.knobs),
+            None => format!("Unknown × {}", v.knobs),
+        })
+        .collect();
+    let pending: Vec<String> = fleet
+        .pending
+        .iter()
+        .map(|p| {
+            let version = p.version.clone().unwrap_or_default();
+            let waiting = if p.deferred {
+                " · waiting for charge"
+            } else {
+                ""
+            };
+            format!("{} v{}{}", knob_name(&p.name, &p.knob_id), version, waiting)
+        })
+        .collect();
+    let results = &fleet.last_results;
+    let summary = format!(
+        "Last OTA Results ({} ok, {} failed)",
+        results.succeeded, results.failed
+    );
+    let reports: Vec<(bool, String)> = results
+        .reports
+        .iter()
+        .take(5)
+        .map(|r| {
+            let outcome = if r.success {
+                "✓".to_string()
+            } else {
+                format!("✗ {}", r.error_code.clone().unwrap_or_default())
+            };
+            let name = knob_name(&r.name, &r.knob_id);
+            (r.success, format!("{} → v{} {}", name, r.version, outcome))
+        })
+        .collect();
+
+    rsx! {
+        div { class: "card p-6 mt-4",
+            h3 { class: "font-semibold mb-2", "Fleet" }
+            p { class: "text-sm text-muted mb-4", "{fleet.knob_count} knobs · {channels}" }
+            div { class: "grid gap-4 md:grid-cols-3",
+                div {
+                    h4 { class: "text-sm font-semibold mb-1", "Versions" }
+                    for line in versions {
+                        p { class: "text-sm", "{line}" }
+                    }
+                }
+                div {
+                    h4 { class: "text-sm font-semibold mb-1", "Pending Updates" }
+                    if pending.is_empty() {
+                        p { class: "text-sm text-muted", "All knobs up to date" }
+                    }
+                    for line in pending {
+                        p { class: "text-sm", "{line}" }
+                    }
+                }
+                div {
+                    h4 { class: "text-sm font-semibold mb-1", "{summary}" }
+                    for (success, line) in reports {
+                        p { class: if success { "text-sm status-ok" } else { "text-sm status-err" }, "{line}" }
+                    }
+                }
             }
         }
     }
@@ -696,6 +1020,13 @@ fn ConfigModal(
     wifi_power_save: bool,
     cpu_freq_scaling: bool,
     sleep_poll_stopped: u32,
+    low_bandwidth: bool,
+    art_mode_source: String,
+    encoder: KnobEncoder,
+    hqp_cycle: KnobHqpCycle,
+    // Clock settings
+    clock: ClockForm,
+    clock_defaults: Option<KnobTime>,
     save_status: Option<String>,
     on_name_change: EventHandler<String>,
     on_rotation_charging_change: EventHandler<i32>,
@@ -714,9 +1045,37 @@ fn ConfigModal(
     on_wifi_power_save_change: EventHandler<bool>,
     on_cpu_freq_scaling_change: EventHandler<bool>,
     on_sleep_poll_stopped_change: EventHandler<u32>,
+    on_low_bandwidth_change: EventHandler<bool>,
+    on_art_mode_source_change: EventHandler<String>,
+    on_encoder_change: EventHandler<KnobEncoder>,
+    on_hqp_cycle_change: EventHandler<KnobHqpCycle>,
+    on_clock_change: EventHandler<ClockForm>,
     on_save: EventHandler<()>,
     on_close: EventHandler<()>,
 ) -> Element {
+    // `time` reflects this knob's saved settings, so the automatic clock
+    // format is only known while none is saved
+    let known_auto_format = clock_defaults.is_some() && clock.clock_24h.is_none();
+    let defaults = clock_defaults.unwrap_or_default();
+    let clock_tz_placeholder = defaults
+        .timezone
+        .clone()
+        .unwrap_or_else(|| defaults.posix_tz.clone());
+    let clock_locale_placeholder = defaults.locale.clone();
+    let clock_ntp_placeholder = defaults.ntp_server.clone();
+    let clock_format_auto = if known_auto_format {
+        format!(
+            "Auto ({})",
+            if defaults.clock_24h {
+                "24-hour"
+            } else {
+                "12-hour"
+            }
+        )
+    } else {
+        "Auto".to_string()
+    };
+
     rsx! {
         div {
             class: "fixed inset-0 bg-black/50 flex items-center justify-center z-50",
@@ -883,6 +1242,161 @@ fn ConfigModal(
                             }
                         }
 
+                        // Encoder feel
+                        fieldset { class: "mb-6",
+                            legend { class: "text-sm font-medium mb-2", "Volume Feel" }
+                            div { class: "space-y-3",
+                                div { class: "flex items-center gap-4",
+                                    div { class: "flex-1",
+                                        span { class: "block text-sm font-medium", "Acceleration" }
+                                        span { class: "block text-xs text-muted", "Bigger steps when turning fast" }
+                                    }
+                                    select {
+                                        class: "input w-48 text-sm py-1",
+                                        onchange: {
+                                            let encoder = encoder.clone();
+                                            move |e: FormEvent| on_encoder_change.call(KnobEncoder { acceleration: e.value(), ..encoder.clone() })
+                                        },
+                                        for (value, label) in ACCELERATION_CURVES {
+                                            option { value: value, selected: value == encoder.acceleration, "{label}" }
+                                        }
+                                    }
+                                }
+                                div { class: "flex items-center gap-4",
+                                    div { class: "flex-1",
+                                        span { class: "block text-sm font-medium", "Detents per Step" }
+                                        span { class: "block text-xs text-muted", "Clicks needed for one volume step" }
+                                    }
+                                    input {
+                                        class: "input w-20 text-center",
+                                        r#type: "number",
+                                        min: "1",
+                                        max: "8",
+                                        value: "{encoder.detents_per_step}",
+                                        oninput: {
+                                            let encoder = encoder.clone();
+                                            move |e: FormEvent| {
+                                                if let Ok(detents_per_step) = e.value().parse::<u8>() {
+                                                    on_encoder_change.call(KnobEncoder { detents_per_step: detents_per_step.clamp(1, 8), ..encoder.clone() })
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                label { class: "flex items-center gap-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        checked: encoder.fine_on_press,
+                                        onchange: {
+                                            let encoder = encoder.clone();
+                                            move |_| on_encoder_change.call(KnobEncoder { fine_on_press: !encoder.fine_on_press, ..encoder.clone() })
+                                        }
+                                    }
+                                    div {
+                                        span { class: "block text-sm font-medium", "Fine Mode on Press" }
+                                        span { class: "block text-xs text-muted", "Turn while pressed for smaller steps" }
+                                    }
+                                }
+                                div { class: "flex items-center gap-4",
+                                    div { class: "flex-1",
+                                        span { class: "block text-sm font-medium", "HQPlayer Cycling" }
+                                        span { class: "block text-xs text-muted", "Gesture that steps HQPlayer's filter or shaper" }
+                                    }
+                                    select {
+                                        class: "input w-36 text-sm py-1",
+                                        onchange: {
+                                            let hqp_cycle = hqp_cycle.clone();
+                                            move |e: FormEvent| on_hqp_cycle_change.call(KnobHqpCycle { gesture: e.value(), ..hqp_cycle.clone() })
+                                        },
+                                        for (value, label) in HQP_CYCLE_GESTURES {
+                                            option { value: value, selected: value == hqp_cycle.gesture, "{label}" }
+                                        }
+                                    }
+                                    select {
+                                        class: "input w-28 text-sm py-1",
+                                        disabled: hqp_cycle.gesture == "off",
+                                        onchange: {
+                                            let hqp_cycle = hqp_cycle.clone();
+                                            move |e: FormEvent| on_hqp_cycle_change.call(KnobHqpCycle { setting: e.value(), ..hqp_cycle.clone() })
+                                        },
+                                        for (value, label) in HQP_CYCLE_SETTINGS {
+                                            option { value: value, selected: value == hqp_cycle.setting, "{label}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        // Clock (placeholders show the bridge host's settings)
+                        fieldset { class: "mb-6",
+                            legend { class: "text-sm font-medium mb-2", "Clock" }
+                            p { class: "text-sm text-muted mb-3",
+                                "Time zone and format for the knob's clock. Leave empty to follow the bridge."
+                            }
+                            div { class: "grid grid-cols-2 gap-3",
+                                label { class: "block",
+                                    span { class: "block text-sm font-medium mb-1", "Time Zone" }
+                                    input {
+                                        class: "input",
+                                        r#type: "text",
+                                        placeholder: "{clock_tz_placeholder}",
+                                        value: "{clock.timezone}",
+                                        oninput: {
+                                            let clock = clock.clone();
+                                            move |e: FormEvent| on_clock_change.call(ClockForm { timezone: e.value(), ..clock.clone() })
+                                        }
+                                    }
+                                }
+                                label { class: "block",
+                                    span { class: "block text-sm font-medium mb-1", "Locale" }
+                                    input {
+                                        class: "input",
+                                        r#type: "text",
+                                        placeholder: "{clock_locale_placeholder}",
+                                        value: "{clock.locale}",
+                                        oninput: {
+                                            let clock = clock.clone();
+                                            move |e: FormEvent| on_clock_change.call(ClockForm { locale: e.value(), ..clock.clone() })
+                                        }
+                                    }
+                                }
+                                label { class: "block",
+                                    span { class: "block text-sm font-medium mb-1", "Clock Format" }
+                                    select {
+                                        class: "input",
+                                        onchange: {
+                                            let clock = clock.clone();
+                                            move |e: FormEvent| {
+                                                let clock_24h = match e.value().as_str() {
+                                                    "24" => Some(true),
+                                                    "12" => Some(false),
+                                                    _ => None,
+                                                };
+                                                on_clock_change.call(ClockForm { clock_24h, ..clock.clone() })
+                                            }
+                                        },
+                                        option { value: "", selected: clock.clock_24h.is_none(), "{clock_format_auto}" }
+                                        option { value: "24", selected: clock.clock_24h == Some(true), "24-hour" }
+                                        option { value: "12", selected: clock.clock_24h == Some(false), "12-hour" }
+                                    }
+                                }
+                                label { class: "block",
+                                    span { class: "block text-sm font-medium mb-1", "NTP Server" }
+                                    input {
+                                        class: "input",
+                                        r#type: "text",
+                                        placeholder: "{clock_ntp_placeholder}",
+                                        value: "{clock.ntp_server}",
+                                        oninput: {
+                                            let clock = clock.clone();
+                                            move |e: FormEvent| on_clock_change.call(ClockForm { ntp_server: e.value(), ..clock.clone() })
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
                         // Advanced Settings
                         fieldset { class: "mb-6",
                             legend { class: "text-sm font-medium mb-2", "Advanced Settings" }
@@ -912,6 +1426,31 @@ fn ConfigModal(
                                         span { class: "block text-xs text-muted", "Dynamic frequency for power savings" }
                                     }
                                 }
+                                label { class: "flex items-center gap-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        checked: low_bandwidth,
+                                        onchange: move |_| on_low_bandwidth_change.call(!low_bandwidth)
+                                    }
+                                    div {
+                                        span { class: "block text-sm font-medium", "Low Bandwidth" }
+                                        span { class: "block text-xs text-muted", "Smaller artwork and slower polling for weak Wi-Fi" }
+                                    }
+                                }
+                                div { class: "flex items-center gap-4",
+                                    div { class: "flex-1",
+                                        span { class: "block text-sm font-medium", "Art Mode Shows" }
+                                        span { class: "block text-xs text-muted", "Folder images go in art/ in the config directory" }
+                                    }
+                                    select {
+                                        class: "input w-48 text-sm py-1",
+                                        onchange: move |e| on_art_mode_source_change.call(e.value()),
+                                        for (value, label) in ART_MODE_SOURCES {
+                                            option { value: value, selected: value == art_mode_source, "{label}" }
+                                        }
+                                    }
+                                }
                                 div { class: "flex items-center gap-4",
                                     div { class: "flex-1",
                                         span { class: "block text-sm font-medium", "Sleep Poll Interval" }
@@ -4,7 +4,7 @@
 
 use dioxus::prelude::*;
 
-use crate::app::api::{AppSettings, LmsConfig, LmsPlayer, LmsPlayersResponse};
+use crate::app::api::{AppSettings, LmsConfig, LmsPlayer, LmsPlayersResponse, LmsScanStatus};
 use crate::app::components::Layout;
 use crate::app::sse::use_sse;
 
@@ -26,6 +26,15 @@ struct LmsControlRequest {
     action: String,
 }
 
+/// LMS rescan request
+#[derive(Clone, serde::Serialize)]
+struct LmsRescanRequest {
+    mode: String,
+}
+
+/// How often scan progress is refreshed while a scan runs
+const SCAN_POLL_MS: i32 = 2000;
+
 /// LMS page component.
 #[component]
 pub fn Lms() -> Element {
@@ -132,6 +141,7 @@ pub fn Lms() -> Element {
     let lms_enabled = settings.read().clone().flatten().map(|s| s.adapters.lms);
     let players_list = players.read().clone().flatten().unwrap_or_default();
     let is_loading = config.read().is_none();
+    let lms_connected = cfg.as_ref().is_some_and(|c| c.connected);
 
     rsx! {
         Layout {
@@ -256,6 +266,17 @@ pub fn Lms() -> Element {
                 }
             }
 
+            // Library section (rescan after adding music)
+            if lms_connected {
+                section { id: "lms-library", class: "mb-8",
+                    div { class: "mb-4",
+                        h2 { class: "text-xl font-semibold", "Library" }
+                        p { class: "text-muted text-sm", "Rescan the music library after adding or changing files" }
+                    }
+                    LibraryScan {}
+                }
+            }
+
             // Players section
             section { id: "lms-players", class: "mb-8",
                 div { class: "mb-4",
@@ -294,6 +315,101 @@ pub fn Lms() -> Element {
     }
 }
 
+/// Library scan progress with rescan buttons
+///
+/// Progress is refreshed every couple of seconds while a scan runs.
+#[component]
+fn LibraryScan() -> Element {
+    let mut scan_error = use_signal(|| None::<String>);
+    let mut scan = use_resource(|| async {
+        crate::app::api::fetch_json::<LmsScanStatus>("/lms/rescan/status")
+            .await
+            .ok()
+    });
+
+    use_effect(move || {
+        let scanning = scan
+            .read()
+            .as_ref()
+            .is_some_and(|s| s.as_ref().is_some_and(|s| s.scanning));
+        if scanning {
+            spawn(async move {
+                crate::app::api::sleep_ms(SCAN_POLL_MS).await;
+                scan.restart();
+            });
+        }
+    });
+
+    let mut rescan = move |mode: &'static str| {
+        scan_error.set(None);
+        spawn(async move {
+            let req = LmsRescanRequest {
+                mode: mode.to_string(),
+            };
+            match crate::app::api::post_json::<_, serde_json::Value>("/lms/rescan", &req).await {
+                Ok(_) => scan.restart(),
+                Err(e) => scan_error.set(Some(format!("Rescan failed: {}", e))),
+            }
+        });
+    };
+
+    let status = scan.read().clone().flatten();
+    let scanning = status.as_ref().is_some_and(|s| s.scanning);
+    let progress = status.as_ref().filter(|s| s.scanning).map(|s| {
+        let step = s.step.clone().unwrap_or_else(|| "Scanning".to_string());
+        match (s.done, s.total) {
+            (Some(done), Some(total)) if total > 0 => format!("{}: {} of {}", step, done, total),
+            _ => step,
+        }
+    });
+    let last_scan = status
+        .as_ref()
+        .and_then(|s| s.last_scan)
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string());
+    let last_failed = status.as_ref().and_then(|s| s.last_scan_failed.clone());
+
+    rsx! {
+        div { class: "card p-6",
+            div { class: "mb-4 text-sm",
+                if let Some(progress) = progress {
+                    span { class: "text-muted", aria_busy: "true", "{progress}" }
+                } else if let Some(failed) = last_failed {
+                    span { class: "status-err", "✗ Last scan failed: {failed}" }
+                } else if let Some(last_scan) = last_scan {
+                    span { class: "text-muted", "Last scan finished {last_scan}" }
+                } else if status.is_none() {
+                    span { class: "text-muted", "Checking..." }
+                }
+            }
+            div { class: "flex flex-wrap items-center gap-2",
+                button {
+                    class: "btn btn-primary",
+                    disabled: scanning,
+                    onclick: move |_| rescan("changes"),
+                    "Scan for new music"
+                }
+                button {
+                    class: "btn btn-outline",
+                    disabled: scanning,
+                    onclick: move |_| rescan("playlists"),
+                    "Rescan playlists"
+                }
+                button {
+                    class: "btn btn-ghost",
+                    disabled: scanning,
+                    title: "Clears the library and scans everything again",
+                    onclick: move |_| rescan("full"),
+                    "Full rescan"
+                }
+            }
+            if let Some(error) = scan_error() {
+                p { class: "text-sm text-error mt-2", "{error}" }
+            }
+        }
+    }
+}
+
 /// Player card component
 #[component]
 fn PlayerCard(player: LmsPlayer, on_control: EventHandler<(String, String)>) -> Element {
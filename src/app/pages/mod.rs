@@ -6,10 +6,12 @@ mod hqplayer;
 mod knobs;
 mod lms;
 mod settings;
+mod wall;
 mod zones;
 
 pub use hqplayer::HqPlayer;
 pub use knobs::Knobs;
 pub use lms::Lms;
 pub use settings::Settings;
+pub use wall::Wall;
 pub use zones::Zones;
@@ -4,7 +4,12 @@
 
 use dioxus::prelude::*;
 
-use crate::app::api::{AdapterSettings, AppSettings, HqpStatus, LmsConfig, RoonStatus};
+use crate::app::api::{
+    AdapterConfig, AdapterConfigUpdate, AdapterInfo, AdapterOpsResponse, AdapterSettings,
+    AdaptersResponse, AppSettings, BackupInfo, BackupList, BackupRestoreResult, BackupSettings,
+    ConnectivityReport, CreatedToken, HqpStatus, LmsConfig, PairingCode, PollingSettings,
+    RoonStatus, TokensResponse, MAX_POLL_INTERVAL_SECS, MIN_POLL_INTERVAL_SECS,
+};
 use crate::app::components::Layout;
 use crate::app::settings_context::use_settings;
 use crate::app::sse::use_sse;
@@ -22,6 +27,42 @@ struct UpnpStatus {
     renderer_count: usize,
 }
 
+/// Adapters with a configuration card: (API name, label)
+const CONFIGURABLE_ADAPTERS: [(&str, &str); 12] = [
+    ("lms", "LMS"),
+    ("hqplayer", "HQPlayer"),
+    ("openhome", "OpenHome"),
+    ("upnp", "UPnP/DLNA"),
+    ("airplay", "AirPlay"),
+    ("snapcast", "Snapcast"),
+    ("heos", "HEOS"),
+    ("kodi", "Kodi"),
+    ("spotify", "Spotify"),
+    ("http", "HTTP JSON"),
+    ("plexamp", "Plexamp"),
+    ("roon", "Roon"),
+];
+
+/// Token scopes: (API name, label)
+const TOKEN_SCOPES: [(&str, &str); 4] = [
+    ("read_only", "Read-only"),
+    ("control", "Control"),
+    ("knob", "Knob"),
+    ("admin", "Admin"),
+];
+
+/// "3 drops, last 2026-10-16 21:04 UTC (Core lost)" once the Core connection has dropped
+fn last_roon_drop(status: &RoonStatus) -> Option<String> {
+    let last = status.history.iter().find(|e| e.event == "disconnected")?;
+    let at = last.at.get(..16).unwrap_or(&last.at).replace('T', " ");
+    let count = status.disconnect_count;
+    let plural = if count == 1 { "" } else { "s" };
+    Some(match &last.reason {
+        Some(reason) => format!("{count} drop{plural}, last {at} UTC ({reason})"),
+        None => format!("{count} drop{plural}, last {at} UTC"),
+    })
+}
+
 /// Settings page component.
 #[component]
 pub fn Settings() -> Element {
@@ -34,11 +75,37 @@ pub fn Settings() -> Element {
     let mut lms_enabled = use_signal(|| false);
     let mut openhome_enabled = use_signal(|| false);
     let mut upnp_enabled = use_signal(|| false);
+    let mut airplay_enabled = use_signal(|| false);
+    let mut snapcast_enabled = use_signal(|| false);
+    let mut heos_enabled = use_signal(|| false);
+    let mut kodi_enabled = use_signal(|| false);
+    let mut spotify_enabled = use_signal(|| false);
+    let mut http_enabled = use_signal(|| false);
+    let mut plexamp_enabled = use_signal(|| false);
     let mut hqplayer_enabled = use_signal(|| false);
 
     // Hide knobs signal (LMS/HQPlayer visibility follows adapter enabled state)
     let mut hide_knobs = use_signal(|| false);
 
+    // Adapter poll intervals
+    let mut polling = use_signal(PollingSettings::default);
+
+    // Adapter shown in the configuration card
+    let mut config_adapter = use_signal(|| "lms");
+
+    // Config backups
+    let mut backup = use_signal(BackupSettings::default);
+    let mut restore_pick = use_signal(String::new);
+    let mut backup_message = use_signal(|| None::<String>);
+
+    // Adapter restart in progress (adapter name) and its outcome
+    let mut restarting = use_signal(|| None::<String>);
+    let mut restart_message = use_signal(|| None::<String>);
+
+    // Network diagnostics state
+    let mut diag_running = use_signal(|| false);
+    let mut diag_report = use_signal(|| None::<Result<ConnectivityReport, String>>);
+
     // Load settings resource
     let settings = use_resource(|| async {
         crate::app::api::fetch_json::<AppSettings>("/api/settings")
@@ -53,8 +120,17 @@ pub fn Settings() -> Element {
             lms_enabled.set(s.adapters.lms);
             openhome_enabled.set(s.adapters.openhome);
             upnp_enabled.set(s.adapters.upnp);
+            airplay_enabled.set(s.adapters.airplay);
+            snapcast_enabled.set(s.adapters.snapcast);
+            heos_enabled.set(s.adapters.heos);
+            kodi_enabled.set(s.adapters.kodi);
+            spotify_enabled.set(s.adapters.spotify);
+            http_enabled.set(s.adapters.http);
+            plexamp_enabled.set(s.adapters.plexamp);
             hqplayer_enabled.set(s.adapters.hqplayer);
             hide_knobs.set(s.hide_knobs_page);
+            polling.set(s.polling.clone());
+            backup.set(s.backup.clone());
             // Sync to shared context for Nav reactivity (page visibility follows adapter state)
             settings_ctx.update(s.hide_knobs_page, s.adapters.hqplayer, s.adapters.lms);
             settings_ctx.mark_loaded();
@@ -77,6 +153,41 @@ pub fn Settings() -> Element {
             .await
             .ok()
     });
+    let mut airplay_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/airplay/config")
+            .await
+            .ok()
+    });
+    let mut snapcast_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/snapcast/config")
+            .await
+            .ok()
+    });
+    let mut heos_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/heos/config")
+            .await
+            .ok()
+    });
+    let mut kodi_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/kodi/config")
+            .await
+            .ok()
+    });
+    let mut spotify_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/spotify/config")
+            .await
+            .ok()
+    });
+    let mut http_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/http/config")
+            .await
+            .ok()
+    });
+    let mut plexamp_config = use_resource(|| async {
+        crate::app::api::fetch_json::<AdapterConfig>("/api/adapters/plexamp/config")
+            .await
+            .ok()
+    });
     let mut lms_config = use_resource(|| async {
         crate::app::api::fetch_json::<LmsConfig>("/lms/config")
             .await
@@ -96,6 +207,13 @@ pub fn Settings() -> Element {
             roon_status.restart();
             openhome_status.restart();
             upnp_status.restart();
+            airplay_config.restart();
+            snapcast_config.restart();
+            heos_config.restart();
+            kodi_config.restart();
+            spotify_config.restart();
+            http_config.restart();
+            plexamp_config.restart();
             lms_config.restart();
             hqp_status.restart();
         }
@@ -116,23 +234,113 @@ pub fn Settings() -> Element {
                 lms,
                 openhome: openhome_enabled(),
                 upnp: upnp_enabled(),
+                airplay: airplay_enabled(),
+                snapcast: snapcast_enabled(),
+                heos: heos_enabled(),
+                kodi: kodi_enabled(),
+                spotify: spotify_enabled(),
+                http: http_enabled(),
+                plexamp: plexamp_enabled(),
                 hqplayer: hqp,
             },
             hide_knobs_page: hk,
             // These are now derived from adapter state but we keep them for API compat
             hide_hqp_page: !hqp,
             hide_lms_page: !lms,
+            polling: polling(),
+            backup: backup(),
         };
         spawn(async move {
             let _ = crate::app::api::post_json_no_response("/api/settings", &settings).await;
         });
     };
 
+    let mut backups = use_resource(|| async {
+        crate::app::api::fetch_json::<BackupList>("/api/backups")
+            .await
+            .ok()
+    });
+
+    let backup_now = move |_| {
+        spawn(async move {
+            match crate::app::api::post_json::<_, BackupInfo>("/api/backups", &()).await {
+                Ok(info) => backup_message.set(Some(format!("Saved {}", info.name))),
+                Err(e) => backup_message.set(Some(format!("Backup failed: {}", e))),
+            }
+            backups.restart();
+        });
+    };
+
+    let restore_backup = move |_| {
+        let name = restore_pick();
+        if name.is_empty() {
+            return;
+        }
+        spawn(async move {
+            let body = serde_json::json!({ "name": name });
+            match crate::app::api::post_json::<_, BackupRestoreResult>(
+                "/api/backups/restore",
+                &body,
+            )
+            .await
+            {
+                Ok(result) => backup_message.set(Some(format!(
+                    "Restored {} files. Restart the bridge to apply.",
+                    result.restored.len()
+                ))),
+                Err(e) => backup_message.set(Some(format!("Restore failed: {}", e))),
+            }
+            backups.restart();
+        });
+    };
+
+    let mut adapters = use_resource(|| async {
+        crate::app::api::fetch_json::<AdaptersResponse>("/api/adapters")
+            .await
+            .ok()
+    });
+
+    let mut restart_adapter = move |name: String| {
+        restarting.set(Some(name.clone()));
+        restart_message.set(None);
+        spawn(async move {
+            let url = format!("/api/adapters/{}/restart", name);
+            match crate::app::api::post_json::<_, Option<AdapterInfo>>(&url, &()).await {
+                Ok(_) => restart_message.set(Some(format!("Restarted {}", name))),
+                Err(e) => restart_message.set(Some(format!("Restart failed: {}", e))),
+            }
+            restarting.set(None);
+            adapters.restart();
+        });
+    };
+
+    // Run diagnostics handler
+    let run_diagnostics = move |_| {
+        diag_running.set(true);
+        diag_report.set(None);
+        spawn(async move {
+            let result =
+                crate::app::api::post_json::<_, ConnectivityReport>("/debug/connectivity", &())
+                    .await;
+            diag_report.set(Some(result));
+            diag_running.set(false);
+        });
+    };
+
     let roon_st = roon_status.read().clone().flatten();
     let openhome_st = openhome_status.read().clone().flatten();
     let upnp_st = upnp_status.read().clone().flatten();
+    let airplay_cfg = airplay_config.read().clone().flatten();
+    let snapcast_cfg = snapcast_config.read().clone().flatten();
+    let heos_cfg = heos_config.read().clone().flatten();
+    let kodi_cfg = kodi_config.read().clone().flatten();
+    let spotify_cfg = spotify_config.read().clone().flatten();
+    let http_cfg = http_config.read().clone().flatten();
+    let plexamp_cfg = plexamp_config.read().clone().flatten();
     let lms_cfg = lms_config.read().clone().flatten();
     let hqp_st = hqp_status.read().clone().flatten();
+    let backup_list = backups.read().clone().flatten();
+    let adapter_list = adapters.read().clone().flatten();
 
     rsx! {
         Layout {
@@ -185,6 +393,9 @@ pub fn Settings() -> Element {
                                             } else {
                                                 span { class: "status-err", "✗ Not connected" }
                                             }
+                                            if let Some(drop) = last_roon_drop(status) {
+                                                span { class: "text-muted text-sm ml-2", "{drop}" }
+                                            }
                                         } else {
                                             "..."
                                         }
@@ -255,6 +466,235 @@ pub fn Settings() -> Element {
                                     }
                                 }
                             }
+                            // AirPlay (read-only receivers)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable AirPlay",
+                                        checked: airplay_enabled(),
+                                        onchange: move |_| {
+                                            airplay_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "AirPlay" }
+                                td { class: "py-2 px-3",
+                                    if airplay_enabled() {
+                                        if let Some(ref cfg) = airplay_cfg {
+                                            if !cfg.devices.is_empty() {
+                                                span { class: "status-ok", "✓ {cfg.devices.len()} receivers (view only)" }
+                                            } else {
+                                                "Searching..."
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
+                            // Snapcast (server configured under Adapter Configuration)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable Snapcast",
+                                        checked: snapcast_enabled(),
+                                        onchange: move |_| {
+                                            snapcast_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "Snapcast" }
+                                td { class: "py-2 px-3",
+                                    if snapcast_enabled() {
+                                        if let Some(ref cfg) = snapcast_cfg {
+                                            if cfg.connected {
+                                                span { class: "status-ok", "✓ {cfg.devices.len()} clients" }
+                                            } else if cfg.host.is_none() {
+                                                "Set the server under Adapter Configuration"
+                                            } else {
+                                                span { class: "status-err", "✗ Not connected" }
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
+                            // HEOS (device configured under Adapter Configuration)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable HEOS",
+                                        checked: heos_enabled(),
+                                        onchange: move |_| {
+                                            heos_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "HEOS (Denon/Marantz)" }
+                                td { class: "py-2 px-3",
+                                    if heos_enabled() {
+                                        if let Some(ref cfg) = heos_cfg {
+                                            if cfg.connected {
+                                                span { class: "status-ok", "✓ {cfg.devices.len()} players" }
+                                            } else if cfg.host.is_none() {
+                                                "Set a HEOS device under Adapter Configuration"
+                                            } else {
+                                                span { class: "status-err", "✗ Not connected" }
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
+                            // Kodi (host configured under Adapter Configuration)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable Kodi",
+                                        checked: kodi_enabled(),
+                                        onchange: move |_| {
+                                            kodi_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "Kodi" }
+                                td { class: "py-2 px-3",
+                                    if kodi_enabled() {
+                                        if let Some(ref cfg) = kodi_cfg {
+                                            if cfg.connected {
+                                                span { class: "status-ok", "✓ Connected" }
+                                            } else if cfg.host.is_none() {
+                                                "Set a Kodi host under Adapter Configuration"
+                                            } else {
+                                                span { class: "status-err", "✗ Not connected" }
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
+                            // Spotify (app and account configured under Adapter Configuration)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable Spotify",
+                                        checked: spotify_enabled(),
+                                        onchange: move |_| {
+                                            spotify_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "Spotify" }
+                                td { class: "py-2 px-3",
+                                    if spotify_enabled() {
+                                        if let Some(ref cfg) = spotify_cfg {
+                                            if cfg.connected {
+                                                span { class: "status-ok", "✓ Connected" }
+                                            } else if !cfg.authorized {
+                                                "Authorize Spotify under Adapter Configuration"
+                                            } else {
+                                                span { class: "status-err", "✗ Not connected" }
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
+                            // HTTP JSON (device templates edited under Adapter Configuration)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable HTTP JSON",
+                                        checked: http_enabled(),
+                                        onchange: move |_| {
+                                            http_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "HTTP JSON" }
+                                td { class: "py-2 px-3",
+                                    if http_enabled() {
+                                        if let Some(ref cfg) = http_cfg {
+                                            if cfg.devices.is_empty() {
+                                                "Add devices under Adapter Configuration"
+                                            } else if cfg.connected {
+                                                span { class: "status-ok", "✓ Connected" }
+                                            } else {
+                                                span { class: "status-err", "✗ Not connected" }
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
+                            // Plexamp (players added under Adapter Configuration)
+                            tr { class: "border-b border-default",
+                                td { class: "py-2 px-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        aria_label: "Enable Plexamp",
+                                        checked: plexamp_enabled(),
+                                        onchange: move |_| {
+                                            plexamp_enabled.toggle();
+                                            save_settings();
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3", "Plexamp" }
+                                td { class: "py-2 px-3",
+                                    if plexamp_enabled() {
+                                        if let Some(ref cfg) = plexamp_cfg {
+                                            if cfg.hosts.is_empty() {
+                                                "Add players under Adapter Configuration"
+                                            } else if cfg.connected {
+                                                span { class: "status-ok", "✓ Connected" }
+                                            } else {
+                                                span { class: "status-err", "✗ Not connected" }
+                                            }
+                                        } else {
+                                            "..."
+                                        }
+                                    } else {
+                                        span { class: "text-muted", "-" }
+                                    }
+                                }
+                            }
                             // LMS (adapter + page)
                             tr { class: "border-b border-default",
                                 td { class: "py-2 px-3",
@@ -343,34 +783,961 @@ pub fn Settings() -> Element {
                 }
             }
 
-            // Theme Settings section
+            // Adapter configuration section
             section { class: "mb-8",
                 div { class: "mb-4",
-                    h2 { class: "text-xl font-semibold", "Appearance" }
-                    p { class: "text-muted text-sm", "Choose your preferred color theme" }
+                    h2 { class: "text-xl font-semibold", "Adapter Configuration" }
+                    p { class: "text-muted text-sm", "Connection settings, discovered devices and poll intervals ({MIN_POLL_INTERVAL_SECS}-{MAX_POLL_INTERVAL_SECS} seconds; slower saves CPU and network on low-power hosts)" }
                 }
 
-                div { class: "card p-6",
-                    div { class: "grid grid-cols-2 sm:grid-cols-4 gap-4",
-                        for theme in [Theme::System, Theme::Light, Theme::Dark, Theme::Oled] {
-                            button {
-                                class: if theme_ctx.get() == theme { "btn-primary py-3" } else { "btn-outline py-3" },
-                                onclick: move |_| theme_ctx.set(theme),
-                                "{theme.label()}"
-                            }
+                div { class: "flex flex-wrap gap-2 mb-4",
+                    for (name, label) in CONFIGURABLE_ADAPTERS {
+                        button {
+                            key: "{name}",
+                            class: if config_adapter() == name { "btn btn-primary" } else { "btn btn-outline" },
+                            onclick: move |_| config_adapter.set(name),
+                            "{label}"
                         }
                     }
-                    p { class: "mt-4 text-sm text-muted",
-                        match theme_ctx.get() {
-                            Theme::System => "Using your system's color scheme preference.",
-                            Theme::Light => "Light theme for bright environments.",
-                            Theme::Dark => "Dark theme for low-light environments.",
-                            Theme::Oled => "Pure black theme for AMOLED displays.",
+                }
+                AdapterConfigCard {
+                    key: "{config_adapter}",
+                    name: config_adapter(),
+                    on_poll_change: move |secs: u64| {
+                        // Keep the local copy in sync so the next settings save doesn't revert it
+                        let mut p = polling.write();
+                        match config_adapter() {
+                            "lms" => p.lms_secs = secs,
+                            "upnp" => p.upnp_secs = secs,
+                            "openhome" => p.openhome_secs = secs,
+                            "hqplayer" => p.hqp_status_secs = secs,
+                            "http" => p.http_secs = secs,
+                            "plexamp" => p.plexamp_secs = secs,
+                            _ => {}
                         }
                     }
                 }
             }
 
+            // Backups section
+            section { class: "mb-8",
+                div { class: "mb-4",
+                    h2 { class: "text-xl font-semibold", "Backups" }
+                    p { class: "text-muted text-sm", "Periodic copies of all configuration (Roon pairing, HQPlayer instances and links, LMS, knobs)" }
+                }
+
+                div { class: "card p-6 space-y-4", id: "backups-card",
+                    label { class: "flex items-center gap-3",
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox",
+                            checked: backup().enabled,
+                            onchange: move |_| {
+                                let enabled = !backup().enabled;
+                                backup.write().enabled = enabled;
+                                save_settings();
+                            }
+                        }
+                        span { class: "text-sm font-medium", "Automatic backups" }
+                    }
+                    div { class: "flex items-center gap-4",
+                        label { class: "flex-1 text-sm font-medium", "Every" }
+                        div { class: "flex items-center gap-2",
+                            input {
+                                class: "input w-20 text-center",
+                                r#type: "number",
+                                min: "1",
+                                max: "720",
+                                value: "{backup().interval_hours}",
+                                onchange: move |e| {
+                                    if let Ok(v) = e.value().parse::<u64>() {
+                                        backup.write().interval_hours = v.clamp(1, 720);
+                                        save_settings();
+                                    }
+                                }
+                            }
+                            span { class: "text-sm text-muted", "hours" }
+                        }
+                    }
+                    div { class: "flex items-center gap-4",
+                        label { class: "flex-1 text-sm font-medium", "Keep" }
+                        div { class: "flex items-center gap-2",
+                            input {
+                                class: "input w-20 text-center",
+                                r#type: "number",
+                                min: "1",
+                                max: "100",
+                                value: "{backup().keep}",
+                                onchange: move |e| {
+                                    if let Ok(v) = e.value().parse::<usize>() {
+                                        backup.write().keep = v.clamp(1, 100);
+                                        save_settings();
+                                    }
+                                }
+                            }
+                            span { class: "text-sm text-muted", "backups" }
+                        }
+                    }
+                    div { class: "flex items-center gap-4",
+                        label { class: "flex-1 text-sm font-medium", "Directory" }
+                        input {
+                            class: "input flex-1",
+                            r#type: "text",
+                            placeholder: backup_list.as_ref().map(|l| l.directory.clone()).unwrap_or_default(),
+                            value: backup().directory.unwrap_or_default(),
+                            onchange: move |e| {
+                                let dir = e.value().trim().to_string();
+                                backup.write().directory = (!dir.is_empty()).then_some(dir);
+                                save_settings();
+                                backups.restart();
+                            }
+                        }
+                    }
+                    div { class: "flex flex-wrap items-center gap-2 pt-2 border-t border-default",
+                        button { class: "btn btn-outline", onclick: backup_now, "Back up now" }
+                        select {
+                            class: "input flex-1",
+                            aria_label: "Backup to restore",
+                            value: restore_pick(),
+                            onchange: move |e| restore_pick.set(e.value()),
+                            option { value: "", "Select a backup..." }
+                            if let Some(ref list) = backup_list {
+                                for b in list.backups.iter() {
+                                    option { key: "{b.name}", value: "{b.name}", "{b.name}" }
+                                }
+                            }
+                        }
+                        button {
+                            class: "btn btn-primary",
+                            disabled: restore_pick().is_empty(),
+                            onclick: restore_backup,
+                            "Restore"
+                        }
+                    }
+                    if let Some(msg) = backup_message() {
+                        p { class: "text-sm text-muted", "{msg}" }
+                    }
+                }
+            }
+
+            // Adapters section
+            section { class: "mb-8",
+                div { class: "mb-4",
+                    h2 { class: "text-xl font-semibold", "Adapters" }
+                    p { class: "text-muted text-sm", "Restart a single adapter without restarting the bridge" }
+                }
+
+                div { class: "card p-6",
+                    table { class: "w-full", id: "adapters-table",
+                        tbody {
+                            if let Some(ref list) = adapter_list {
+                                for (adapter, name) in list.adapters.iter().map(|a| (a.clone(), a.name.clone())) {
+                                    tr { key: "{adapter.name}", class: "border-b border-default",
+                                        td { class: "py-2 px-3 font-medium", "{adapter.name}" }
+                                        td { class: "py-2 px-3 text-sm",
+                                            if !adapter.enabled {
+                                                span { class: "text-muted", "Disabled" }
+                                            } else if adapter.running {
+                                                span { class: "status-ok", "✓ Running" }
+                                            } else if let Some(ref err) = adapter.last_error {
+                                                span { class: "status-err", "✗ {err}" }
+                                            } else {
+                                                span { class: "text-muted", "Stopped" }
+                                            }
+                                        }
+                                        td { class: "py-2 px-3 text-muted text-sm",
+                                            if adapter.restart_count > 0 {
+                                                "{adapter.restart_count} restarts"
+                                            }
+                                        }
+                                        td { class: "py-2 px-3 text-right",
+                                            button {
+                                                class: "btn btn-outline",
+                                                disabled: !adapter.enabled || restarting().is_some(),
+                                                onclick: move |_| restart_adapter(name.clone()),
+                                                if restarting().as_deref() == Some(adapter.name.as_str()) {
+                                                    "Restarting..."
+                                                } else {
+                                                    "Restart"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if let Some(msg) = restart_message() {
+                        p { class: "mt-4 text-sm text-muted", "{msg}" }
+                    }
+                }
+            }
+
+            // Access tokens section
+            section { class: "mb-8",
+                div { class: "mb-4",
+                    h2 { class: "text-xl font-semibold", "Access Tokens" }
+                    p { class: "text-muted text-sm", "Named tokens for apps, knobs and scripts using remote access, each limited to a scope" }
+                }
+                AccessTokens {}
+            }
+
+            // Diagnostics section
+            section { class: "mb-8",
+                div { class: "mb-4",
+                    h2 { class: "text-xl font-semibold", "Diagnostics" }
+                    p { class: "text-muted text-sm", "Check connectivity to configured backends and whether multicast discovery (SSDP/mDNS) works on this network" }
+                }
+
+                div { class: "card p-6",
+                    button {
+                        id: "diagnostics-btn",
+                        class: "btn btn-primary",
+                        disabled: diag_running(),
+                        aria_busy: if diag_running() { "true" } else { "false" },
+                        onclick: run_diagnostics,
+                        if diag_running() { "Running..." } else { "Run diagnostics" }
+                    }
+                    match diag_report() {
+                        Some(Ok(report)) => rsx! {
+                            table { class: "w-full mt-4", id: "diagnostics-table",
+                                tbody {
+                                    for check in report.checks {
+                                        tr { class: "border-b border-default",
+                                            td { class: "py-2 px-3 w-8",
+                                                if check.ok {
+                                                    span { class: "status-ok", "✓" }
+                                                } else {
+                                                    span { class: "status-err", "✗" }
+                                                }
+                                            }
+                                            td { class: "py-2 px-3 font-medium", "{check.check}" }
+                                            td { class: "py-2 px-3 text-muted text-sm", "{check.target}" }
+                                            td { class: "py-2 px-3 text-sm",
+                                                "{check.detail}"
+                                                if let Some(ms) = check.latency_ms {
+                                                    span { class: "text-muted", " ({ms} ms)" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            if !report.interfaces.is_empty() {
+                                h3 { class: "font-semibold mt-6 mb-2", "Network interfaces" }
+                                p { class: "text-muted text-sm mb-2", "Discovery uses the marked addresses, or the default route when none is marked. Set UHC_DISCOVERY_INTERFACES to choose." }
+                                table { class: "w-full", id: "interfaces-table",
+                                    tbody {
+                                        for iface in report.interfaces.iter().filter(|i| !i.loopback) {
+                                            tr { class: "border-b border-default",
+                                                td { class: "py-2 px-3 w-8",
+                                                    if iface.selected {
+                                                        span { class: "status-ok", "✓" }
+                                                    }
+                                                }
+                                                td { class: "py-2 px-3 font-medium", "{iface.name}" }
+                                                td { class: "py-2 px-3 text-muted text-sm", "{iface.ip}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        Some(Err(e)) => rsx! {
+                            p { class: "mt-4 status-err", "Diagnostics failed: {e}" }
+                        },
+                        None => rsx! {},
+                    }
+                }
+
+                AdapterOps {}
+            }
+
+            // Theme Settings section
+            section { class: "mb-8",
+                div { class: "mb-4",
+                    h2 { class: "text-xl font-semibold", "Appearance" }
+                    p { class: "text-muted text-sm", "Choose your preferred color theme" }
+                }
+
+                div { class: "card p-6",
+                    div { class: "grid grid-cols-2 sm:grid-cols-4 gap-4",
+                        for theme in [Theme::System, Theme::Light, Theme::Dark, Theme::Oled] {
+                            button {
+                                class: if theme_ctx.get() == theme { "btn-primary py-3" } else { "btn-outline py-3" },
+                                onclick: move |_| theme_ctx.set(theme),
+                                "{theme.label()}"
+                            }
+                        }
+                    }
+                    p { class: "mt-4 text-sm text-muted",
+                        match theme_ctx.get() {
+                            Theme::System => "Using your system's color scheme preference.",
+                            Theme::Light => "Light theme for bright environments.",
+                            Theme::Dark => "Dark theme for low-light environments.",
+                            Theme::Oled => "Pure black theme for AMOLED displays.",
+                        }
+                    }
+                }
+            }
+
+        }
+    }
+}
+
+/// Configuration for one adapter, backed by `/api/adapters/{name}/config`.
+#[component]
+fn AdapterConfigCard(name: &'static str, on_poll_change: EventHandler<u64>) -> Element {
+    let mut discover = use_signal(|| false);
+    let mut config = use_resource(move || async move {
+        let query = if discover() { "?discover=true" } else { "" };
+        crate::app::api::fetch_json::<AdapterConfig>(&format!(
+            "/api/adapters/{}/config{}",
+            name, query
+        ))
+        .await
+    });
+
+    let mut host = use_signal(String::new);
+    let mut port = use_signal(String::new);
+    let mut web_port = use_signal(String::new);
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut client_id = use_signal(String::new);
+    let mut client_secret = use_signal(String::new);
+    let mut hosts = use_signal(String::new);
+    let mut token = use_signal(String::new);
+    let mut message = use_signal(|| None::<String>);
+
+    // Fill the form from the server (once per load, not while editing)
+    use_effect(move || {
+        if let Some(Ok(cfg)) = config.read().as_ref() {
+            host.set(cfg.host.clone().unwrap_or_default());
+            port.set(cfg.port.map(|p| p.to_string()).unwrap_or_default());
+            web_port.set(cfg.web_port.map(|p| p.to_string()).unwrap_or_default());
+            username.set(cfg.username.clone().unwrap_or_default());
+            client_id.set(cfg.client_id.clone().unwrap_or_default());
+            hosts.set(cfg.hosts.join(", "));
+        }
+    });
+
+    let mut save = move |update: AdapterConfigUpdate| {
+        message.set(None);
+        spawn(async move {
+            let url = format!("/api/adapters/{}/config", name);
+            match crate::app::api::put_json_no_response(&url, &update).await {
+                Ok(()) => {
+                    if let Some(secs) = update.poll_interval_secs {
+                        on_poll_change.call(secs);
+                    }
+                    message.set(Some("Saved".to_string()));
+                }
+                Err(e) => message.set(Some(format!("Save failed: {}", e))),
+            }
+            password.set(String::new());
+            client_secret.set(String::new());
+            token.set(String::new());
+            config.restart();
+        });
+    };
+
+    let save_connection = move |_| {
+        let text = |s: Signal<String>| Some(s()).filter(|v| !v.trim().is_empty());
+        save(AdapterConfigUpdate {
+            host: text(host),
+            port: port().trim().parse().ok(),
+            web_port: web_port().trim().parse().ok(),
+            username: text(username),
+            password: text(password),
+            ..Default::default()
+        });
+    };
+
+    let save_app = move |_| {
+        let text = |s: Signal<String>| Some(s()).filter(|v| !v.trim().is_empty());
+        save(AdapterConfigUpdate {
+            client_id: text(client_id),
+            client_secret: text(client_secret),
+            ..Default::default()
+        });
+    };
+
+    let save_players = move |_| {
+        let players = hosts()
+            .split(',')
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .collect();
+        save(AdapterConfigUpdate {
+            hosts: Some(players),
+            port: port().trim().parse().ok(),
+            token: Some(token()).filter(|v| !v.trim().is_empty()),
+            ..Default::default()
+        });
+    };
+
+    let has_connection = matches!(name, "lms" | "hqplayer" | "snapcast" | "heos" | "kodi");
+    // Snapcast and HEOS have no login
+    let has_login = !matches!(name, "snapcast" | "heos");
+    let has_discovery = matches!(name, "lms" | "hqplayer");
+    let cfg = config.read().clone();
+
+    rsx! {
+        div { class: "card p-6 space-y-4", id: "adapter-config-card",
+            match cfg {
+                None => rsx! { p { class: "text-muted", "Loading..." } },
+                Some(Err(e)) => rsx! { p { class: "status-err", "Failed to load configuration: {e}" } },
+                Some(Ok(cfg)) => rsx! {
+                    div { class: "flex items-center gap-3",
+                        if !cfg.enabled {
+                            span { class: "text-muted", "Disabled (enable it under Features)" }
+                        } else if cfg.connected {
+                            span { class: "status-ok",
+                                if let Some(core) = cfg.core_name.as_ref().or(cfg.account.as_ref()) { "✓ {core}" } else { "✓ Connected" }
+                            }
+                        } else {
+                            span { class: "status-err", "✗ Not connected" }
+                        }
+                    }
+
+                    if has_connection {
+                        div { class: "grid grid-cols-1 sm:grid-cols-2 gap-4",
+                            label { class: "text-sm font-medium", "Host"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "text",
+                                    value: host(),
+                                    oninput: move |e| host.set(e.value()),
+                                }
+                            }
+                            label { class: "text-sm font-medium", "Port"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "number",
+                                    value: port(),
+                                    oninput: move |e| port.set(e.value()),
+                                }
+                            }
+                            if name == "hqplayer" {
+                                label { class: "text-sm font-medium", "Web port"
+                                    input {
+                                        class: "input w-full",
+                                        r#type: "number",
+                                        value: web_port(),
+                                        oninput: move |e| web_port.set(e.value()),
+                                    }
+                                }
+                            }
+                            if has_login {
+                                label { class: "text-sm font-medium", "Username"
+                                    input {
+                                        class: "input w-full",
+                                        r#type: "text",
+                                        autocomplete: "off",
+                                        value: username(),
+                                        oninput: move |e| username.set(e.value()),
+                                    }
+                                }
+                                label { class: "text-sm font-medium", "Password"
+                                    input {
+                                        class: "input w-full",
+                                        r#type: "password",
+                                        autocomplete: "new-password",
+                                        placeholder: if cfg.has_password { "(unchanged)" } else { "" },
+                                        value: password(),
+                                        oninput: move |e| password.set(e.value()),
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "flex flex-wrap items-center gap-2",
+                            button { class: "btn btn-primary", onclick: save_connection, "Save" }
+                            if has_discovery {
+                                button {
+                                    class: "btn btn-outline",
+                                    onclick: move |_| discover.set(true),
+                                    "Discover"
+                                }
+                            }
+                        }
+                        if !cfg.discovered.is_empty() {
+                            ul { class: "space-y-1",
+                                for (server, (server_host, server_port)) in cfg.discovered.iter().map(|d| (d.clone(), (d.host.clone(), d.port))) {
+                                    li { key: "{server.host}:{server.port}", class: "flex items-center gap-3 text-sm",
+                                        span { class: "flex-1", "{server.name} ({server.host}:{server.port})" }
+                                        button {
+                                            class: "btn btn-outline",
+                                            onclick: move |_| {
+                                                host.set(server_host.clone());
+                                                port.set(server_port.to_string());
+                                            },
+                                            "Use"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    if name == "spotify" {
+                        div { class: "grid grid-cols-1 sm:grid-cols-2 gap-4",
+                            label { class: "text-sm font-medium", "Client ID"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "text",
+                                    autocomplete: "off",
+                                    value: client_id(),
+                                    oninput: move |e| client_id.set(e.value()),
+                                }
+                            }
+                            label { class: "text-sm font-medium", "Client secret"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "password",
+                                    autocomplete: "new-password",
+                                    placeholder: if cfg.has_client_secret { "(unchanged)" } else { "" },
+                                    value: client_secret(),
+                                    oninput: move |e| client_secret.set(e.value()),
+                                }
+                            }
+                        }
+                        p { class: "text-sm text-muted",
+                            "Create an app at developer.spotify.com with redirect URI "
+                            code { "{cfg.redirect_uri.clone().unwrap_or_default()}" }
+                            ". Playback control needs Spotify Premium."
+                        }
+                        div { class: "flex flex-wrap items-center gap-2",
+                            button { class: "btn btn-primary", onclick: save_app, "Save" }
+                            if cfg.client_id.is_some() && cfg.has_client_secret {
+                                a {
+                                    class: "btn btn-outline",
+                                    href: "/spotify/authorize",
+                                    if cfg.authorized { "Re-authorize with Spotify" } else { "Authorize with Spotify" }
+                                }
+                            }
+                        }
+                    }
+
+                    if name == "plexamp" {
+                        div { class: "grid grid-cols-1 sm:grid-cols-2 gap-4",
+                            label { class: "text-sm font-medium sm:col-span-2", "Players"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "text",
+                                    placeholder: "plexamp-pi.local, 192.168.1.40",
+                                    value: hosts(),
+                                    oninput: move |e| hosts.set(e.value()),
+                                }
+                            }
+                            label { class: "text-sm font-medium", "Port"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "number",
+                                    value: port(),
+                                    oninput: move |e| port.set(e.value()),
+                                }
+                            }
+                            label { class: "text-sm font-medium", "Plex token"
+                                input {
+                                    class: "input w-full",
+                                    r#type: "password",
+                                    autocomplete: "new-password",
+                                    placeholder: if cfg.has_token { "(unchanged)" } else { "(optional)" },
+                                    value: token(),
+                                    oninput: move |e| token.set(e.value()),
+                                }
+                            }
+                        }
+                        div { class: "flex flex-wrap items-center gap-2",
+                            button { class: "btn btn-primary", onclick: save_players, "Save" }
+                        }
+                    }
+
+                    if name == "http" {
+                        HttpDevicesEditor { on_saved: move |_| config.restart() }
+                    }
+
+                    if let Some(secs) = cfg.poll_interval_secs {
+                        PollIntervalInput {
+                            label: "Poll interval",
+                            value: secs,
+                            on_change: move |v| save(AdapterConfigUpdate {
+                                poll_interval_secs: Some(v),
+                                ..Default::default()
+                            }),
+                        }
+                    }
+
+                    if !cfg.devices.is_empty() {
+                        table { class: "w-full",
+                            tbody {
+                                for device in cfg.devices.iter() {
+                                    tr { class: "border-b border-default",
+                                        td { class: "py-2 px-3", "{device.name}" }
+                                        td { class: "py-2 px-3 text-muted text-sm", "{device.state}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+            if let Some(msg) = message() {
+                p { class: "text-sm text-muted", "{msg}" }
+            }
+        }
+    }
+}
+
+/// JSON editor for the HTTP JSON device templates (`/http/devices`).
+#[component]
+fn HttpDevicesEditor(on_saved: EventHandler<()>) -> Element {
+    let mut text = use_signal(String::new);
+    let mut message = use_signal(|| None::<String>);
+    let devices = use_resource(|| async {
+        crate::app::api::fetch_json::<serde_json::Value>("/http/devices").await
+    });
+
+    use_effect(move || {
+        if let Some(Ok(devices)) = devices.read().as_ref() {
+            text.set(serde_json::to_string_pretty(devices).unwrap_or_default());
+        }
+    });
+
+    let save = move |_| {
+        let body = match serde_json::from_str::<serde_json::Value>(&text()) {
+            Ok(body) => body,
+            Err(e) => {
+                message.set(Some(format!("Invalid JSON: {}", e)));
+                return;
+            }
+        };
+        message.set(None);
+        spawn(async move {
+            match crate::app::api::put_json_no_response("/http/devices", &body).await {
+                Ok(()) => {
+                    message.set(Some("Saved".to_string()));
+                    on_saved.call(());
+                }
+                Err(e) => message.set(Some(format!(
+                    "Save failed: {} (check each device's id, base_url and status.path)",
+                    e
+                ))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "space-y-2",
+            label { class: "text-sm font-medium", r#for: "http-devices", "Device templates" }
+            textarea {
+                id: "http-devices",
+                class: "input w-full font-mono text-sm",
+                rows: "16",
+                spellcheck: "false",
+                value: text(),
+                oninput: move |e| text.set(e.value()),
+            }
+            p { class: "text-sm text-muted",
+                "Each device has an id, name, base_url, a status request with JSON paths to its fields, and one request per command. See the README for an example."
+            }
+            div { class: "flex items-center gap-2",
+                button { class: "btn btn-primary", onclick: save, "Save devices" }
+                if let Some(msg) = message() {
+                    span { class: "text-sm text-muted", "{msg}" }
+                }
+            }
+        }
+    }
+}
+
+/// Labeled number input for an adapter poll interval (seconds).
+#[component]
+fn PollIntervalInput(label: &'static str, value: u64, on_change: EventHandler<u64>) -> Element {
+    rsx! {
+        div { class: "flex items-center gap-4",
+            label { class: "flex-1 text-sm font-medium", "{label}" }
+            div { class: "flex items-center gap-2",
+                input {
+                    class: "input w-20 text-center",
+                    r#type: "number",
+                    min: "{MIN_POLL_INTERVAL_SECS}",
+                    max: "{MAX_POLL_INTERVAL_SECS}",
+                    value: "{value}",
+                    onchange: move |e| {
+                        if let Ok(v) = e.value().parse::<u64>() {
+                            on_change.call(PollingSettings::clamp_secs(v));
+                        }
+                    }
+                }
+                span { class: "text-sm text-muted", "sec" }
+            }
+        }
+    }
+}
+
+/// Token list with create, scope change and revoke
+/// Recent backend calls of one adapter, for spotting slow or failing backends
+#[component]
+fn AdapterOps() -> Element {
+    let mut adapter = use_signal(|| "hqplayer".to_string());
+
+    let mut ops = use_resource(move || {
+        let name = adapter();
+        async move {
+            crate::app::api::fetch_json::<AdapterOpsResponse>(&format!(
+                "/debug/adapters/{}/ops",
+                name
+            ))
+            .await
+        }
+    });
+
+    rsx! {
+        div { class: "card p-6 mt-4",
+            div { class: "flex items-center gap-3 mb-4",
+                h3 { class: "font-semibold flex-1", "Recent operations" }
+                select {
+                    class: "input",
+                    aria_label: "Adapter",
+                    value: adapter(),
+                    onchange: move |e| adapter.set(e.value()),
+                    // AirPlay only browses mDNS; it makes no backend calls to list
+                    for (value, label) in CONFIGURABLE_ADAPTERS.into_iter().filter(|(v, _)| *v != "airplay") {
+                        option { value: "{value}", selected: adapter() == value, "{label}" }
+                    }
+                }
+                button {
+                    class: "btn btn-outline",
+                    onclick: move |_| ops.restart(),
+                    "Refresh"
+                }
+            }
+            match ops.read().clone() {
+                Some(Ok(resp)) if resp.ops.is_empty() => rsx! {
+                    p { class: "text-muted text-sm", "No operations recorded yet" }
+                },
+                Some(Ok(resp)) => rsx! {
+                    table { class: "w-full", id: "adapter-ops-table",
+                        tbody {
+                            for (i, op) in resp.ops.into_iter().enumerate() {
+                                tr { key: "{i}", class: "border-b border-default",
+                                    td { class: "py-2 px-3 w-8",
+                                        if op.ok {
+                                            span { class: "status-ok", "✓" }
+                                        } else {
+                                            span { class: "status-err", "✗" }
+                                        }
+                                    }
+                                    td { class: "py-2 px-3 font-medium", "{op.operation}" }
+                                    td { class: "py-2 px-3 text-muted text-sm",
+                                        "{op.target.clone().unwrap_or_default()}"
+                                    }
+                                    td { class: "py-2 px-3 text-sm", "{op.duration_ms} ms" }
+                                    td { class: "py-2 px-3 text-sm status-err",
+                                        "{op.error.clone().unwrap_or_default()}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    p { class: "status-err", "Failed to load operations: {e}" }
+                },
+                None => rsx! {
+                    p { class: "text-muted text-sm", "Loading..." }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn AccessTokens() -> Element {
+    let mut new_name = use_signal(String::new);
+    let mut new_scope = use_signal(|| "control".to_string());
+    let mut created = use_signal(|| None::<CreatedToken>);
+    let mut pairing = use_signal(|| None::<PairingCode>);
+    let mut message = use_signal(|| None::<String>);
+    let mut admin_token = use_signal(|| crate::app::api::admin_token().unwrap_or_default());
+
+    let mut tokens = use_resource(|| async {
+        crate::app::api::fetch_json::<TokensResponse>("/api/tokens")
+            .await
+            .ok()
+    });
+
+    let create_token = move |_| {
+        let name = new_name();
+        if name.trim().is_empty() {
+            return;
+        }
+        let body = serde_json::json!({ "name": name, "scope": new_scope() });
+        spawn(async move {
+            match crate::app::api::post_json::<_, CreatedToken>("/api/tokens", &body).await {
+                Ok(token) => {
+                    // The first admin token becomes this browser's credential
+                    if token.scope == "admin" && crate::app::api::admin_token().is_none() {
+                        crate::app::api::set_admin_token(&token.token);
+                        admin_token.set(token.token.clone());
+                    }
+                    created.set(Some(token));
+                    message.set(None);
+                    new_name.set(String::new());
+                }
+                Err(e) => message.set(Some(format!("Create failed: {}", e))),
+            }
+            tokens.restart();
+        });
+    };
+
+    let create_pairing_code = move |_| {
+        let name = new_name();
+        let body = serde_json::json!({
+            "scope": new_scope(),
+            "name": if name.trim().is_empty() { None } else { Some(name) },
+        });
+        spawn(async move {
+            match crate::app::api::post_json::<_, PairingCode>("/api/pair/code", &body).await {
+                Ok(code) => {
+                    pairing.set(Some(code));
+                    message.set(None);
+                }
+                Err(e) => message.set(Some(format!("Pairing failed: {}", e))),
+            }
+        });
+    };
+
+    let mut set_scope = move |id: String, scope: String| {
+        spawn(async move {
+            let url = format!("/api/tokens/{}", id);
+            let body = serde_json::json!({ "scope": scope });
+            if let Err(e) = crate::app::api::put_json_no_response(&url, &body).await {
+                message.set(Some(format!("Update failed: {}", e)));
+            }
+            tokens.restart();
+        });
+    };
+
+    let mut revoke = move |id: String| {
+        spawn(async move {
+            let url = format!("/api/tokens/{}", id);
+            if let Err(e) = crate::app::api::delete_no_response(&url).await {
+                message.set(Some(format!("Revoke failed: {}", e)));
+            }
+            tokens.restart();
+        });
+    };
+
+    let save_admin_token = move |_| {
+        crate::app::api::set_admin_token(&admin_token());
+        tokens.restart();
+    };
+
+    let token_list = tokens.read().clone().flatten();
+
+    rsx! {
+        div { class: "card p-6 space-y-4",
+            div { class: "flex flex-wrap items-center gap-2",
+                input {
+                    class: "input flex-1",
+                    r#type: "password",
+                    placeholder: "Admin token for this browser",
+                    aria_label: "Admin token",
+                    value: admin_token(),
+                    oninput: move |e| admin_token.set(e.value()),
+                }
+                button { class: "btn btn-outline", onclick: save_admin_token, "Save" }
+            }
+            if tokens.read().is_some() && token_list.is_none() {
+                p { class: "text-sm text-muted",
+                    "Managing tokens needs an admin token or the remote access token. Enter one above."
+                }
+            }
+            table { class: "w-full", id: "tokens-table",
+                tbody {
+                    if let Some(ref list) = token_list {
+                        if list.tokens.is_empty() {
+                            tr {
+                                td { class: "py-2 px-3 text-muted text-sm", "No tokens yet" }
+                            }
+                        }
+                        for (token, scope_id, revoke_id) in list.tokens.iter().map(|t| (t.clone(), t.id.clone(), t.id.clone())) {
+                            tr { key: "{token.id}", class: "border-b border-default",
+                                td { class: "py-2 px-3 font-medium", "{token.name}" }
+                                td { class: "py-2 px-3",
+                                    select {
+                                        class: "input",
+                                        aria_label: "Scope for {token.name}",
+                                        value: "{token.scope}",
+                                        onchange: move |e| set_scope(scope_id.clone(), e.value()),
+                                        for (value, label) in TOKEN_SCOPES {
+                                            option { value: "{value}", selected: token.scope == value, "{label}" }
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 px-3 text-muted text-sm",
+                                    if token.last_used_at.is_some() { "Used" } else { "Never used" }
+                                }
+                                td { class: "py-2 px-3 text-right",
+                                    button {
+                                        class: "btn btn-outline",
+                                        onclick: move |_| revoke(revoke_id.clone()),
+                                        "Revoke"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-wrap items-center gap-2 pt-2 border-t border-default",
+                input {
+                    class: "input flex-1",
+                    r#type: "text",
+                    placeholder: "Token name (e.g. Kitchen iPad)",
+                    aria_label: "Token name",
+                    value: new_name(),
+                    oninput: move |e| new_name.set(e.value()),
+                }
+                select {
+                    class: "input",
+                    aria_label: "Token scope",
+                    value: new_scope(),
+                    onchange: move |e| new_scope.set(e.value()),
+                    for (value, label) in TOKEN_SCOPES {
+                        option { value: "{value}", "{label}" }
+                    }
+                }
+                button {
+                    class: "btn btn-primary",
+                    disabled: new_name().trim().is_empty(),
+                    onclick: create_token,
+                    "Create"
+                }
+                button {
+                    class: "btn btn-outline",
+                    title: "Show a code a new app or knob can enter to get a token with this scope",
+                    onclick: create_pairing_code,
+                    "Pair Device"
+                }
+            }
+            if let Some(code) = pairing() {
+                div { class: "text-sm",
+                    p { "Enter this code on the new device within 5 minutes ({code.scope} access):" }
+                    code { class: "block mt-1 text-2xl tracking-widest", "{code.code}" }
+                }
+            }
+            if let Some(token) = created() {
+                div { class: "text-sm",
+                    p { "Token for {token.name}. Copy it now, it won't be shown again:" }
+                    code { class: "block mt-1 break-all", "{token.token}" }
+                }
+            }
+            if let Some(msg) = message() {
+                p { class: "text-sm text-muted", "{msg}" }
+            }
         }
     }
 }
@@ -0,0 +1,161 @@
+//! Cover wall page component.
+//!
+//! A full-window grid of recently played album covers, meant as an ambient
+//! display for a spare screen. Clicking a cover plays the album again on the
+//! chosen zone.
+
+use dioxus::prelude::*;
+
+use crate::app::api::{WallAlbum, WallAlbumsResponse, Zone, ZonesResponse};
+use crate::app::components::Layout;
+use crate::app::sse::{use_sse, SseEvent};
+use crate::app::Route;
+
+/// Album replay request body
+#[derive(Clone, serde::Serialize)]
+struct AlbumPlayRequest {
+    zone_id: String,
+    artist: String,
+    album: String,
+}
+
+/// Cover URL, served by the backend of the zone the album was played on
+fn artwork_url(album: &WallAlbum) -> String {
+    format!(
+        "/history/albums/artwork?zone_id={}&image_key={}&size=300",
+        urlencoding::encode(&album.zone_id),
+        urlencoding::encode(&album.image_key)
+    )
+}
+
+/// Zones whose backend can start an album by name (see POST /history/albums/play)
+fn can_play_albums(zone: &Zone) -> bool {
+    zone.zone_id.starts_with("lms:") || zone.zone_id.starts_with("spotify:")
+}
+
+/// Cover wall page component.
+#[component]
+pub fn Wall() -> Element {
+    let sse = use_sse();
+
+    let mut albums = use_resource(|| async {
+        crate::app::api::fetch_json::<WallAlbumsResponse>("/history/albums")
+            .await
+            .ok()
+            .map(|r| r.albums)
+    });
+
+    let zones = use_resource(|| async {
+        crate::app::api::fetch_json::<ZonesResponse>("/zones")
+            .await
+            .ok()
+            .map(|r| r.zones)
+    });
+
+    let mut target = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+
+    // New albums appear as they start playing
+    let event_count = sse.event_count;
+    use_effect(move || {
+        let _ = event_count();
+        if matches!(
+            sse.last_event.read().as_ref(),
+            Some(SseEvent::NowPlayingChanged { .. })
+        ) {
+            albums.restart();
+        }
+    });
+
+    let mut play = move |album: WallAlbum| {
+        let zone_id = target();
+        if zone_id.is_empty() {
+            status.set(Some("Choose a zone to play on".to_string()));
+            return;
+        }
+        status.set(Some(format!("Starting {}...", album.album)));
+        spawn(async move {
+            let req = AlbumPlayRequest {
+                zone_id,
+                artist: album.artist,
+                album: album.album.clone(),
+            };
+            match crate::app::api::post_json::<_, serde_json::Value>("/history/albums/play", &req)
+                .await
+            {
+                Ok(_) => status.set(Some(format!("Playing {}", album.album))),
+                Err(e) => status.set(Some(format!("Error: {}", e))),
+            }
+        });
+    };
+
+    let playable: Vec<Zone> = zones
+        .read()
+        .clone()
+        .flatten()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(can_play_albums)
+        .collect();
+    let albums_list = albums.read().clone().flatten();
+    let is_loading = albums.read().is_none();
+
+    rsx! {
+        Layout {
+            title: "Cover Wall".to_string(),
+            nav_active: "wall".to_string(),
+            bare: true,
+
+            div { class: "min-h-screen bg-black",
+                // Controls stay dim until hovered so the covers dominate
+                div { class: "flex flex-wrap items-center gap-3 p-3 opacity-30 hover:opacity-100 transition-opacity",
+                    Link { class: "link text-sm", to: Route::Zones {}, "Zones" }
+                    select {
+                        class: "input w-auto",
+                        "aria-label": "Zone to play on",
+                        onchange: move |evt| target.set(evt.value()),
+                        option { value: "", selected: target().is_empty(), "Play on..." }
+                        for zone in playable {
+                            option {
+                                value: "{zone.zone_id}",
+                                selected: zone.zone_id == target(),
+                                "{zone.zone_name}"
+                            }
+                        }
+                    }
+                    if let Some(message) = status() {
+                        span { class: "text-sm text-muted", "{message}" }
+                    }
+                }
+
+                if is_loading {
+                    p { class: "text-muted p-6", "Loading..." }
+                } else if let Some(list) = albums_list.filter(|l| !l.is_empty()) {
+                    div { class: "grid grid-cols-3 sm:grid-cols-4 md:grid-cols-6 xl:grid-cols-8",
+                        for album in list {
+                            button {
+                                key: "{album.artist}|{album.album}",
+                                class: "aspect-square overflow-hidden hover:opacity-80 transition-opacity",
+                                title: "{album.album} - {album.artist}",
+                                onclick: {
+                                    let album = album.clone();
+                                    move |_| play(album.clone())
+                                },
+                                img {
+                                    class: "w-full h-full object-cover",
+                                    src: artwork_url(&album),
+                                    alt: "{album.album}",
+                                    loading: "lazy",
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    p { class: "text-muted p-6",
+                        "No albums yet. Covers appear here as albums are played."
+                    }
+                }
+            }
+        }
+    }
+}
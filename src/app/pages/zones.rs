@@ -1,10 +1,17 @@
 //! Zones listing page component.
 //!
-//! Shows all available zones using Dioxus resources.
+//! Shows all available zones using Dioxus resources, grouped by room when
+//! rooms are assigned (see zone metadata), otherwise by source protocol.
 
-use crate::app::api::{HqpMatrixProfilesResponse, HqpProfile, NowPlaying, Zone, ZonesResponse};
-use crate::app::components::{ErrorAlert, HqpControlsCompact, Layout, VolumeControlsCompact};
+use crate::app::api::{
+    HqpMatrixProfilesResponse, HqpPipeline, HqpProfile, MiniDspStatus, NowPlaying, RoonCorePower,
+    Zone, ZoneEqResponse, ZoneInputResponse, ZoneLyricsResponse, ZoneMetadata, ZonesResponse,
+};
+use crate::app::components::{
+    ErrorAlert, HqpControlsCompact, HqpPipelineCompact, Layout, VolumeControlsCompact,
+};
 use crate::app::sse::{use_sse, SseEvent};
+use crate::app::Route;
 use dioxus::prelude::*;
 use std::collections::HashMap;
 
@@ -17,6 +24,50 @@ struct ControlRequest {
     value: Option<f64>,
 }
 
+/// Control request with a string value (e.g. `volume_leveling`)
+#[derive(Clone, serde::Serialize)]
+struct ModeControlRequest {
+    zone_id: String,
+    action: String,
+    value: String,
+}
+
+/// EQ change body for `PUT /zones/{zone_id}/eq`; unset fields are left alone
+#[derive(Clone, Default, serde::Serialize)]
+struct EqRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bass: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    treble: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matrix_profile: Option<u32>,
+}
+
+/// Change body for `POST /minidsp/zones/{zone_id}`; unset fields are left alone
+#[derive(Clone, Default, serde::Serialize)]
+struct MiniDspRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    preset: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mute: Option<bool>,
+}
+
+/// Input change body for `POST /zones/{zone_id}/input`
+#[derive(Clone, serde::Serialize)]
+struct InputRequest {
+    input: String,
+}
+
+/// ReplayGain modes as (value, label)
+const VOLUME_LEVELING_MODES: [(&str, &str); 4] = [
+    ("off", "Off"),
+    ("track", "Track"),
+    ("album", "Album"),
+    ("smart", "Smart"),
+];
+
 /// Fetch now playing for all zones
 async fn fetch_all_now_playing(zones: &[Zone]) -> HashMap<String, NowPlaying> {
     let mut np_map = HashMap::new();
@@ -38,6 +89,71 @@ async fn fetch_zone_now_playing(zone_id: &str) -> Option<NowPlaying> {
     crate::app::api::fetch_json::<NowPlaying>(&url).await.ok()
 }
 
+/// HQPlayer instance a zone is linked to (from its DSP info)
+fn hqp_instance(zone: &Zone) -> Option<&str> {
+    zone.dsp
+        .as_ref()
+        .filter(|d| d.r#type.as_deref() == Some("hqplayer"))
+        .and_then(|d| d.instance.as_deref())
+}
+
+/// Whether a zone is linked to a MiniDSP unit (from its DSP info)
+fn has_minidsp(zone: &Zone) -> bool {
+    zone.dsp
+        .as_ref()
+        .is_some_and(|d| d.r#type.as_deref() == Some("minidsp"))
+}
+
+/// Distinct instances in a list of (zone_id, instance) links
+fn linked_instances(links: &[(String, String)]) -> Vec<String> {
+    let mut instances: Vec<String> = links.iter().map(|(_, i)| i.clone()).collect();
+    instances.sort();
+    instances.dedup();
+    instances
+}
+
+fn hqp_instance_url(instance: &str, path: &str) -> String {
+    format!("/hqp/instances/{}/{}", urlencoding::encode(instance), path)
+}
+
+fn hqp_zone_pipeline_url(zone_id: &str) -> String {
+    format!("/hqp/zones/{}/pipeline", urlencoding::encode(zone_id))
+}
+
+/// Fetch matrix profiles of an HQPlayer instance
+async fn fetch_hqp_matrix(instance: &str) -> Option<HqpMatrixProfilesResponse> {
+    crate::app::api::fetch_json(&hqp_instance_url(instance, "matrix/profiles"))
+        .await
+        .ok()
+}
+
+/// Fetch the pipeline of the instance a zone is linked to
+async fn fetch_hqp_pipeline(zone_id: &str) -> Option<HqpPipeline> {
+    crate::app::api::fetch_json(&hqp_zone_pipeline_url(zone_id))
+        .await
+        .ok()
+}
+
+/// Group zones by room, keeping the server's order (rooms first, unassigned last)
+fn group_by_room(zones: &[Zone]) -> Vec<(String, Vec<Zone>)> {
+    let mut groups: Vec<(String, Vec<Zone>)> = Vec::new();
+    let mut unassigned = Vec::new();
+    for zone in zones {
+        let Some(room) = zone.room() else {
+            unassigned.push(zone.clone());
+            continue;
+        };
+        match groups.iter_mut().find(|(name, _)| name == room) {
+            Some((_, group)) => group.push(zone.clone()),
+            None => groups.push((room.to_string(), vec![zone.clone()])),
+        }
+    }
+    if !unassigned.is_empty() {
+        groups.push(("Other zones".to_string(), unassigned));
+    }
+    groups
+}
+
 /// Zones listing page component.
 #[component]
 pub fn Zones() -> Element {
@@ -85,6 +201,7 @@ pub fn Zones() -> Element {
             Some(
                 SseEvent::ZoneUpdated { .. }
                     | SseEvent::ZoneRemoved { .. }
+                    | SseEvent::ZoneMetadataChanged { .. }
                     | SseEvent::RoonConnected
                     | SseEvent::RoonDisconnected
                     | SseEvent::LmsConnected
@@ -100,8 +217,11 @@ pub fn Zones() -> Element {
         if let Some(ref evt) = event {
             match evt {
                 // Zone-scoped events: fetch only the specific zone that changed
-                // ZoneUpdated includes state changes (play/pause) that affect is_playing
-                SseEvent::NowPlayingChanged { .. } | SseEvent::ZoneUpdated { .. } => {
+                // ZoneUpdated/ZoneStatePending carry state changes (play/pause) that
+                // affect is_playing
+                SseEvent::NowPlayingChanged { .. }
+                | SseEvent::ZoneUpdated { .. }
+                | SseEvent::ZoneStatePending { .. } => {
                     if let Some(zone_id) = evt.zone_id() {
                         let zone_id = zone_id.to_string();
                         spawn(async move {
@@ -151,43 +271,148 @@ pub fn Zones() -> Element {
         });
     };
 
-    // HQPlayer state (shared across all HQP zones)
-    let mut hqp_profiles = use_signal(Vec::<HqpProfile>::new);
-    let mut hqp_matrix = use_signal(|| None::<HqpMatrixProfilesResponse>);
+    // Volume leveling (ReplayGain) mode; the zone is republished, so reload it
+    let set_leveling = move |(zone_id, mode): (String, String)| {
+        spawn(async move {
+            let req = ModeControlRequest {
+                zone_id,
+                action: "volume_leveling".to_string(),
+                value: mode,
+            };
+            match crate::app::api::post_json_no_response("/control", &req).await {
+                Ok(()) => zones.restart(),
+                Err(e) => {
+                    #[cfg(target_arch = "wasm32")]
+                    web_sys::console::warn_1(&format!("Volume leveling failed: {e}").into());
+                    #[cfg(not(target_arch = "wasm32"))]
+                    tracing::warn!("Volume leveling failed: {e}");
+                }
+            }
+        });
+    };
+
+    // Room and volume step (merged into the zone's existing metadata)
+    let mut metadata_error = use_signal(|| None::<String>);
+    let mut save_metadata = move |zone_id: String, what: &'static str, update: ZoneMetadata| {
+        metadata_error.set(None);
+        spawn(async move {
+            let url = format!("/zones/{}/metadata", urlencoding::encode(&zone_id));
+            match crate::app::api::put_json_no_response(&url, &update).await {
+                Ok(()) => zones.restart(),
+                Err(e) => metadata_error.set(Some(format!("Saving {what} failed: {e}"))),
+            }
+        });
+    };
+    let existing_metadata = move |zone_id: &str| {
+        zones_list_signal()
+            .iter()
+            .find(|z| z.zone_id == zone_id)
+            .and_then(|z| z.metadata.clone())
+            .unwrap_or_default()
+    };
+    let set_room = move |(zone_id, room): (String, Option<String>)| {
+        let metadata = ZoneMetadata {
+            room,
+            ..existing_metadata(&zone_id)
+        };
+        save_metadata(zone_id, "room", metadata);
+    };
+    let set_volume_step = move |(zone_id, volume_step): (String, Option<f32>)| {
+        let metadata = ZoneMetadata {
+            volume_step,
+            ..existing_metadata(&zone_id)
+        };
+        save_metadata(zone_id, "volume step", metadata);
+    };
+    let set_aliases = move |(zone_id, aliases): (String, Vec<String>)| {
+        let metadata = ZoneMetadata {
+            aliases,
+            ..existing_metadata(&zone_id)
+        };
+        save_metadata(zone_id, "aliases", metadata);
+    };
+    let set_locked = move |(zone_id, locked): (String, bool)| {
+        let metadata = ZoneMetadata {
+            locked,
+            ..existing_metadata(&zone_id)
+        };
+        save_metadata(zone_id, "lock", metadata);
+    };
+    let mut group_by_source = use_signal(|| false);
+
+    // HQPlayer state: profiles and matrix per instance, pipeline per zone, so
+    // each card controls the engine its zone is linked to
+    let mut hqp_profiles = use_signal(HashMap::<String, Vec<HqpProfile>>::new);
+    let mut hqp_matrix = use_signal(HashMap::<String, HqpMatrixProfilesResponse>::new);
+    let mut hqp_pipelines = use_signal(HashMap::<String, HqpPipeline>::new);
     let mut hqp_error = use_signal(|| None::<String>);
 
-    // Check if any zone has HQP
-    let has_any_hqp = use_memo(move || {
-        zones_list_signal().iter().any(|z| {
-            z.dsp
-                .as_ref()
-                .map(|d| d.r#type.as_deref() == Some("hqplayer"))
-                .unwrap_or(false)
-        })
+    // HQP-linked zones as (zone_id, instance)
+    let hqp_links = use_memo(move || {
+        zones_list_signal()
+            .iter()
+            .filter_map(|z| hqp_instance(z).map(|i| (z.zone_id.clone(), i.to_string())))
+            .collect::<Vec<_>>()
     });
 
-    // Fetch HQP profiles/matrix when there are HQP zones
+    // Fetch profiles/matrix per linked instance and the pipeline per zone
     use_effect(move || {
-        if has_any_hqp() {
+        let links = hqp_links();
+        if !links.is_empty() {
             spawn(async move {
-                if let Ok(profiles) =
-                    crate::app::api::fetch_json::<Vec<HqpProfile>>("/hqplayer/profiles").await
-                {
-                    hqp_profiles.set(profiles);
+                for instance in linked_instances(&links) {
+                    if let Ok(profiles) = crate::app::api::fetch_json::<Vec<HqpProfile>>(
+                        &hqp_instance_url(&instance, "profiles"),
+                    )
+                    .await
+                    {
+                        hqp_profiles.with_mut(|map| {
+                            map.insert(instance.clone(), profiles);
+                        });
+                    }
+                    if let Some(matrix) = fetch_hqp_matrix(&instance).await {
+                        hqp_matrix.with_mut(|map| {
+                            map.insert(instance, matrix);
+                        });
+                    }
                 }
-                if let Ok(matrix) = crate::app::api::fetch_json::<HqpMatrixProfilesResponse>(
-                    "/hqplayer/matrix/profiles",
-                )
-                .await
-                {
-                    hqp_matrix.set(Some(matrix));
+                for (zone_id, _) in links {
+                    if let Some(pipeline) = fetch_hqp_pipeline(&zone_id).await {
+                        hqp_pipelines.with_mut(|map| {
+                            map.insert(zone_id, pipeline);
+                        });
+                    }
                 }
             });
         }
     });
 
-    // Load profile handler
-    let load_profile = move |profile: String| {
+    // Pipeline/matrix changed outside the bridge (e.g. in HQPlayer's own UI)
+    use_effect(move || {
+        let _ = (sse.event_count)();
+        let links = hqp_links();
+        if !links.is_empty() && sse.should_refresh_hqp() {
+            spawn(async move {
+                for instance in linked_instances(&links) {
+                    if let Some(matrix) = fetch_hqp_matrix(&instance).await {
+                        hqp_matrix.with_mut(|map| {
+                            map.insert(instance, matrix);
+                        });
+                    }
+                }
+                for (zone_id, _) in links {
+                    if let Some(pipeline) = fetch_hqp_pipeline(&zone_id).await {
+                        hqp_pipelines.with_mut(|map| {
+                            map.insert(zone_id, pipeline);
+                        });
+                    }
+                }
+            });
+        }
+    });
+
+    // Load profile handler: (instance, profile)
+    let load_profile = move |(instance, profile): (String, String)| {
         hqp_error.set(None);
         spawn(async move {
             #[derive(serde::Serialize)]
@@ -195,33 +420,38 @@ pub fn Zones() -> Element {
                 profile: String,
             }
             let req = ProfileRequest { profile };
-            if let Err(e) = crate::app::api::post_json_no_response("/hqplayer/profile", &req).await
+            if let Err(e) = crate::app::api::post_json_no_response(
+                &hqp_instance_url(&instance, "profile"),
+                &req,
+            )
+            .await
             {
                 hqp_error.set(Some(format!("Profile load failed: {e}")));
             }
         });
     };
 
-    // Set matrix profile handler
-    let set_matrix = move |profile_idx: u32| {
+    // Set matrix profile handler: (instance, profile index)
+    let set_matrix = move |(instance, profile_idx): (String, u32)| {
         hqp_error.set(None);
         spawn(async move {
             #[derive(serde::Serialize)]
             struct MatrixRequest {
-                profile: u32,
+                value: u32,
             }
-            let req = MatrixRequest {
-                profile: profile_idx,
-            };
-            match crate::app::api::post_json_no_response("/hqplayer/matrix/profile", &req).await {
+            let req = MatrixRequest { value: profile_idx };
+            match crate::app::api::post_json_no_response(
+                &hqp_instance_url(&instance, "matrix/profile"),
+                &req,
+            )
+            .await
+            {
                 Ok(_) => {
                     // Refresh matrix after change
-                    if let Ok(matrix) = crate::app::api::fetch_json::<HqpMatrixProfilesResponse>(
-                        "/hqplayer/matrix/profiles",
-                    )
-                    .await
-                    {
-                        hqp_matrix.set(Some(matrix));
+                    if let Some(matrix) = fetch_hqp_matrix(&instance).await {
+                        hqp_matrix.with_mut(|map| {
+                            map.insert(instance, matrix);
+                        });
                     }
                 }
                 Err(e) => {
@@ -231,6 +461,33 @@ pub fn Zones() -> Element {
         });
     };
 
+    // Pipeline setting handler: (zone_id, setting, value), routed by zone link
+    let set_pipeline = move |(zone_id, setting, value): (String, String, String)| {
+        hqp_error.set(None);
+        spawn(async move {
+            #[derive(serde::Serialize)]
+            struct PipelineRequest {
+                setting: String,
+                value: String,
+            }
+            let req = PipelineRequest { setting, value };
+            match crate::app::api::post_json_no_response(&hqp_zone_pipeline_url(&zone_id), &req)
+                .await
+            {
+                Ok(_) => {
+                    if let Some(pipeline) = fetch_hqp_pipeline(&zone_id).await {
+                        hqp_pipelines.with_mut(|map| {
+                            map.insert(zone_id, pipeline);
+                        });
+                    }
+                }
+                Err(e) => {
+                    hqp_error.set(Some(format!("Pipeline change failed: {e}")));
+                }
+            }
+        });
+    };
+
     let is_loading = zones.read().is_none();
     let zones_list = zones
         .read()
@@ -242,18 +499,34 @@ pub fn Zones() -> Element {
 
     let profiles = hqp_profiles();
     let matrix = hqp_matrix();
+    let pipelines = hqp_pipelines();
+
+    // Rooms already in use, offered as suggestions when assigning a room
+    let mut known_rooms: Vec<String> = zones_list
+        .iter()
+        .filter_map(|z| z.room().map(str::to_string))
+        .collect();
+    known_rooms.sort();
+    known_rooms.dedup();
+    let has_rooms = !known_rooms.is_empty();
 
-    // Group zones by source protocol
-    let grouped_zones: Vec<(String, Vec<Zone>)> = {
+    // Group zones by room when any are assigned, otherwise by source protocol
+    let grouped_zones: Vec<(String, Vec<Zone>)> = if has_rooms && !group_by_source() {
+        group_by_room(&zones_list)
+    } else {
         let mut groups: std::collections::HashMap<String, Vec<Zone>> =
             std::collections::HashMap::new();
         for zone in zones_list.iter() {
             let source = zone.source.clone().unwrap_or_else(|| "Other".to_string());
             groups.entry(source).or_default().push(zone.clone());
         }
-        // Sort zones within each group by name for stable ordering
+        // Sort zones within each group by sort weight, then name (same order as /zones)
         for zones in groups.values_mut() {
-            zones.sort_by(|a, b| a.zone_name.cmp(&b.zone_name));
+            zones.sort_by(|a, b| {
+                a.sort_weight()
+                    .cmp(&b.sort_weight())
+                    .then_with(|| a.zone_name.cmp(&b.zone_name))
+            });
         }
         // Sort groups in a sensible order: Roon, LMS, OpenHome, UPnP, then others
         let priority = |s: &str| -> i32 {
@@ -269,6 +542,7 @@ pub fn Zones() -> Element {
         result.sort_by(|a, b| priority(&a.0).cmp(&priority(&b.0)));
         result
     };
+    let by_source = group_by_source();
 
     let content = if is_loading {
         rsx! {
@@ -280,20 +554,39 @@ pub fn Zones() -> Element {
         }
     } else {
         rsx! {
-            for (source, group_zones) in grouped_zones {
+            datalist { id: "zone-rooms",
+                for room in known_rooms.iter() {
+                    option { value: "{room}" }
+                }
+            }
+            for (group_name, group_zones) in grouped_zones {
                 div { class: "mb-8",
-                    h3 { class: "text-lg font-semibold mb-4 text-muted", "{source}" }
+                    h3 { class: "text-lg font-semibold mb-4 text-muted", "{group_name}" }
                     div { class: "grid gap-4 grid-cols-1 md:grid-cols-2 lg:grid-cols-3",
                         for zone in group_zones {
-                            ZoneCard {
-                                key: "{zone.zone_id}",
-                                zone: zone.clone(),
-                                now_playing: np_map.get(&zone.zone_id).cloned(),
-                                hqp_profiles: profiles.clone(),
-                                hqp_matrix: matrix.clone(),
-                                on_control: control,
-                                on_load_profile: load_profile,
-                                on_set_matrix: set_matrix,
+                            {
+                                let instance = hqp_instance(&zone);
+                                rsx! {
+                                    ZoneCard {
+                                        key: "{zone.zone_id}",
+                                        zone: zone.clone(),
+                                        now_playing: np_map.get(&zone.zone_id).cloned(),
+                                        hqp_profiles: instance
+                                            .and_then(|i| profiles.get(i).cloned())
+                                            .unwrap_or_default(),
+                                        hqp_matrix: instance.and_then(|i| matrix.get(i).cloned()),
+                                        hqp_pipeline: pipelines.get(&zone.zone_id).cloned(),
+                                        on_control: control,
+                                        on_set_leveling: set_leveling,
+                                        on_load_profile: load_profile,
+                                        on_set_matrix: set_matrix,
+                                        on_set_pipeline: set_pipeline,
+                                        on_set_room: set_room,
+                                        on_set_volume_step: set_volume_step,
+                                        on_set_aliases: set_aliases,
+                                        on_set_locked: set_locked,
+                                    }
+                                }
                             }
                         }
                     }
@@ -307,7 +600,34 @@ pub fn Zones() -> Element {
             title: "Zones".to_string(),
             nav_active: "zones".to_string(),
 
-            h1 { class: "text-2xl font-bold mb-6", "Zones" }
+            div { class: "flex items-center justify-between mb-6",
+                div { class: "flex items-baseline gap-4",
+                    h1 { class: "text-2xl font-bold", "Zones" }
+                    Link { class: "link text-sm", to: Route::Wall {}, "Cover wall" }
+                    RoonCoreWake {}
+                }
+                if has_rooms {
+                    div { class: "flex gap-2", role: "group", "aria-label": "Group zones by",
+                        button {
+                            class: if by_source { "btn btn-ghost" } else { "btn btn-primary" },
+                            onclick: move |_| group_by_source.set(false),
+                            "Rooms"
+                        }
+                        button {
+                            class: if by_source { "btn btn-primary" } else { "btn btn-ghost" },
+                            onclick: move |_| group_by_source.set(true),
+                            "Sources"
+                        }
+                    }
+                }
+            }
+
+            if let Some(error) = metadata_error() {
+                ErrorAlert {
+                    message: error,
+                    on_dismiss: move |_| metadata_error.set(None),
+                }
+            }
 
             // HQP error display
             if let Some(error) = hqp_error() {
@@ -331,29 +651,80 @@ fn ZoneCard(
     now_playing: Option<NowPlaying>,
     hqp_profiles: Vec<HqpProfile>,
     hqp_matrix: Option<HqpMatrixProfilesResponse>,
+    hqp_pipeline: Option<HqpPipeline>,
     on_control: EventHandler<(String, String)>,
-    on_load_profile: EventHandler<String>,
-    on_set_matrix: EventHandler<u32>,
+    on_set_leveling: EventHandler<(String, String)>,
+    on_load_profile: EventHandler<(String, String)>,
+    on_set_matrix: EventHandler<(String, u32)>,
+    on_set_pipeline: EventHandler<(String, String, String)>,
+    on_set_room: EventHandler<(String, Option<String>)>,
+    on_set_volume_step: EventHandler<(String, Option<f32>)>,
+    on_set_aliases: EventHandler<(String, Vec<String>)>,
+    on_set_locked: EventHandler<(String, bool)>,
 ) -> Element {
     let zone_id = zone.zone_id.clone();
+    let zone_id_room = zone_id.clone();
+    let current_room = zone.room().map(str::to_string);
+    let has_volume = zone.has_volume();
+    let mut editing_room = use_signal(|| false);
+    let mut room_input = use_signal(String::new);
+    let mut show_lyrics = use_signal(|| false);
+    let zone_id_lyrics = zone_id.clone();
+    let zone_id_leveling = zone_id.clone();
+    let volume_leveling = zone.volume_leveling.clone();
+    let has_eq = zone.has_eq();
+    let zone_id_eq = zone_id.clone();
+    let has_input = zone.has_input();
+    let zone_id_input = zone_id.clone();
+    let linked_minidsp = has_minidsp(&zone);
+    let zone_id_minidsp = zone_id.clone();
     let zone_id_prev = zone_id.clone();
     let zone_id_play = zone_id.clone();
     let zone_id_next = zone_id.clone();
     let zone_id_vol_down = zone_id.clone();
     let zone_id_vol_up = zone_id.clone();
+    let zone_id_step = zone_id.clone();
+    let configured_step = zone
+        .metadata
+        .as_ref()
+        .and_then(|m| m.volume_step)
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+    let zone_id_aliases = zone_id.clone();
+    let zone_id_locked = zone_id.clone();
+    let locked = zone.metadata.as_ref().is_some_and(|m| m.locked);
+    let current_aliases = zone
+        .metadata
+        .as_ref()
+        .map(|m| m.aliases.join(", "))
+        .unwrap_or_default();
 
     let np = now_playing.as_ref();
     let is_playing = np.map(|n| n.is_playing).unwrap_or(false);
 
-    let has_hqp = zone
-        .dsp
+    let icon = zone.metadata.as_ref().and_then(|m| m.icon.clone());
+    // Accent stripe from the zone's metadata color
+    let card_style = zone
+        .metadata
         .as_ref()
-        .map(|d| d.r#type.as_deref() == Some("hqplayer"))
-        .unwrap_or(false);
+        .and_then(|m| m.color.as_ref())
+        .map(|c| format!("border-left: 4px solid {c};"))
+        .unwrap_or_default();
+
+    let hqp_instance_name = hqp_instance(&zone).map(str::to_string);
+    let has_hqp = hqp_instance_name.is_some();
+    let instance_profile = hqp_instance_name.clone().unwrap_or_default();
+    let instance_matrix = instance_profile.clone();
+    let zone_id_pipeline = zone_id.clone();
 
     // Extract volume info for component
     let volume = np.and_then(|n| n.volume);
     let volume_type = np.and_then(|n| n.volume_type.clone());
+    let step_unit = if volume_type.as_deref() == Some("db") {
+        "dB"
+    } else {
+        "%"
+    };
     let volume_step = np.and_then(|n| n.volume_step);
 
     // Album art URL with cache-busting image_key
@@ -365,7 +736,7 @@ fn ZoneCard(
         } else {
             "?"
         };
-        format!("{}{}k={}", base_image_url, sep, key)
+        format!("{}{}k={}", base_image_url, sep, urlencoding::encode(&key))
     } else {
         base_image_url
     };
@@ -397,7 +768,7 @@ fn ZoneCard(
     let matrix_current = hqp_matrix.as_ref().and_then(|m| m.current);
 
     rsx! {
-        article { class: "zone-card",
+        article { class: "zone-card", style: "{card_style}",
             // Main content with album art and info (same layout as zone detail)
             div { class: "flex gap-3 sm:gap-5 items-start overflow-hidden",
                 // Album art (smaller on mobile, 96px on larger screens)
@@ -417,9 +788,19 @@ fn ZoneCard(
                 div { class: "flex-1 min-w-0",
                     // Header with zone name and HQP badge
                     h3 { class: "flex items-center gap-2 mb-2 text-base font-semibold",
+                        if let Some(icon) = icon {
+                            span { "aria-hidden": "true", "{icon}" }
+                        }
                         span { class: "truncate", "{zone.zone_name}" }
-                        if has_hqp {
-                            span { class: "badge badge-primary", "HQP" }
+                        if let Some(instance) = hqp_instance_name {
+                            span { class: "badge badge-primary", title: "{instance}", "HQP" }
+                        }
+                        if locked {
+                            span {
+                                class: "badge badge-secondary",
+                                title: "Only admins can control this zone",
+                                "Locked"
+                            }
                         }
                     }
 
@@ -439,8 +820,16 @@ fn ZoneCard(
                     profiles: hqp_profiles,
                     matrix_profiles: matrix_profiles,
                     active_matrix: matrix_current,
-                    on_profile_select: on_load_profile,
-                    on_matrix_select: on_set_matrix,
+                    on_profile_select: move |profile| on_load_profile.call((instance_profile.clone(), profile)),
+                    on_matrix_select: move |idx| on_set_matrix.call((instance_matrix.clone(), idx)),
+                }
+            }
+            if let Some(pipeline) = hqp_pipeline.filter(|_| has_hqp) {
+                HqpPipelineCompact {
+                    pipeline: pipeline,
+                    on_change: move |(setting, value)| {
+                        on_set_pipeline.call((zone_id_pipeline.clone(), setting, value))
+                    },
                 }
             }
 
@@ -477,14 +866,537 @@ fn ZoneCard(
                     }
                 }
 
-                VolumeControlsCompact {
-                    volume: volume,
-                    volume_type: volume_type,
-                    volume_step: volume_step,
-                    on_vol_down: move |_| on_control.call((zone_id_vol_down.clone(), "vol_down".to_string())),
-                    on_vol_up: move |_| on_control.call((zone_id_vol_up.clone(), "vol_up".to_string())),
+                if has_volume {
+                    VolumeControlsCompact {
+                        volume: volume,
+                        volume_type: volume_type,
+                        volume_step: volume_step,
+                        on_vol_down: move |_| on_control.call((zone_id_vol_down.clone(), "vol_down".to_string())),
+                        on_vol_up: move |_| on_control.call((zone_id_vol_up.clone(), "vol_up".to_string())),
+                    }
+                }
+
+                if !track.is_empty() {
+                    button {
+                        class: if show_lyrics() { "btn btn-primary" } else { "btn btn-ghost" },
+                        "aria-pressed": if show_lyrics() { "true" } else { "false" },
+                        onclick: move |_| show_lyrics.set(!show_lyrics()),
+                        "Lyrics"
+                    }
+                }
+            }
+
+            if let Some(current) = volume_leveling {
+                div { class: "flex items-center gap-2 mt-3 text-sm",
+                    span { class: "text-muted", "ReplayGain" }
+                    select {
+                        class: "input flex-1 min-w-0",
+                        "aria-label": "ReplayGain mode",
+                        onchange: move |evt| on_set_leveling.call((zone_id_leveling.clone(), evt.value())),
+                        for (value, label) in VOLUME_LEVELING_MODES {
+                            option { value: value, selected: value == current, "{label}" }
+                        }
+                    }
+                }
+            }
+
+            if has_volume {
+                div { class: "flex items-center gap-2 mt-3 text-sm",
+                    span { class: "text-muted", "Volume step" }
+                    input {
+                        class: "input w-20",
+                        r#type: "number",
+                        min: "0.5",
+                        max: "10",
+                        step: "0.5",
+                        placeholder: "Default",
+                        "aria-label": "Volume step",
+                        value: "{configured_step}",
+                        // Empty restores the backend's own step
+                        onchange: move |e| {
+                            let step = e.value().trim().parse::<f32>().ok().filter(|s| *s > 0.0);
+                            on_set_volume_step.call((zone_id_step.clone(), step));
+                        },
+                    }
+                    span { class: "text-muted", "{step_unit}" }
+                }
+            }
+
+            // Knobs, hooks and non-admin tokens can't control a locked zone
+            label { class: "flex items-center gap-2 mt-3 text-sm",
+                input {
+                    r#type: "checkbox",
+                    checked: locked,
+                    onchange: move |_| on_set_locked.call((zone_id_locked.clone(), !locked)),
+                }
+                "Lock transport (do not disturb)"
+            }
+
+            // Extra names for /simple and /zones/resolve ("lounge, tv")
+            div { class: "flex items-center gap-2 mt-3 text-sm",
+                span { class: "text-muted", "Also called" }
+                input {
+                    class: "input flex-1",
+                    r#type: "text",
+                    placeholder: "Comma-separated names",
+                    "aria-label": "Zone aliases",
+                    value: "{current_aliases}",
+                    onchange: move |e| {
+                        let aliases = e
+                            .value()
+                            .split(',')
+                            .map(|a| a.trim().to_string())
+                            .filter(|a| !a.is_empty())
+                            .collect();
+                        on_set_aliases.call((zone_id_aliases.clone(), aliases));
+                    },
+                }
+            }
+
+            if has_eq {
+                // HQP-linked cards already show the matrix select above
+                ZoneEq { zone_id: zone_id_eq.clone(), show_matrix: !has_hqp }
+            }
+
+            if has_input {
+                ZoneInput { zone_id: zone_id_input.clone() }
+            }
+
+            if linked_minidsp {
+                ZoneMiniDsp { zone_id: zone_id_minidsp.clone() }
+            }
+
+            if show_lyrics() && !track.is_empty() {
+                ZoneLyrics { zone_id: zone_id_lyrics.clone() }
+            }
+
+            // Room assignment
+            div { class: "flex items-center gap-2 mt-3 text-sm",
+                if editing_room() {
+                    input {
+                        class: "input flex-1",
+                        r#type: "text",
+                        list: "zone-rooms",
+                        placeholder: "Room",
+                        "aria-label": "Room",
+                        value: "{room_input}",
+                        oninput: move |e| room_input.set(e.value()),
+                    }
+                    button {
+                        class: "btn btn-primary",
+                        onclick: move |_| {
+                            let room = room_input().trim().to_string();
+                            let room = if room.is_empty() { None } else { Some(room) };
+                            on_set_room.call((zone_id_room.clone(), room));
+                            editing_room.set(false);
+                        },
+                        "Save"
+                    }
+                    button {
+                        class: "btn btn-ghost",
+                        onclick: move |_| editing_room.set(false),
+                        "Cancel"
+                    }
+                } else {
+                    button {
+                        class: "btn btn-ghost text-muted",
+                        onclick: move |_| {
+                            room_input.set(current_room.clone().unwrap_or_default());
+                            editing_room.set(true);
+                        },
+                        if let Some(room) = zone.room() {
+                            "Room: {room}"
+                        } else {
+                            "Set room"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bass/treble sliders and matrix profile select for zones with EQ
+///
+/// Renders nothing until `/zones/{zone_id}/eq` reports a setting; sliders
+/// send their value on release rather than on every step.
+#[component]
+fn ZoneEq(zone_id: String, show_matrix: bool) -> Element {
+    let mut eq_error = use_signal(|| None::<String>);
+    let eq_url = use_signal(|| format!("/zones/{}/eq", urlencoding::encode(&zone_id)));
+    let mut eq = use_resource(move || async move {
+        crate::app::api::fetch_json::<ZoneEqResponse>(&eq_url())
+            .await
+            .ok()
+    });
+
+    let mut set_eq = move |req: EqRequest| {
+        let url = eq_url();
+        eq_error.set(None);
+        spawn(async move {
+            match crate::app::api::put_json_no_response(&url, &req).await {
+                Ok(()) => eq.restart(),
+                Err(e) => eq_error.set(Some(format!("EQ change failed: {e}"))),
+            }
+        });
+    };
+
+    let Some(Some(response)) = eq.read().clone() else {
+        return rsx! {};
+    };
+    let matrix = response.matrix.filter(|_| show_matrix);
+    let current_matrix = matrix
+        .as_ref()
+        .and_then(|m| m.current.as_ref())
+        .map(|p| p.index);
+
+    rsx! {
+        if let Some(tone) = response.tone {
+            for (label, value, is_bass) in [("Bass", tone.bass, true), ("Treble", tone.treble, false)] {
+                div { key: "{label}", class: "flex items-center gap-2 mt-3 text-sm",
+                    span { class: "text-muted w-14", "{label}" }
+                    input {
+                        class: "flex-1 min-w-0",
+                        r#type: "range",
+                        min: "{tone.min}",
+                        max: "{tone.max}",
+                        value: "{value}",
+                        "aria-label": "{label}",
+                        onchange: move |evt| {
+                            if let Ok(value) = evt.value().parse::<i32>() {
+                                set_eq(if is_bass {
+                                    EqRequest { bass: Some(value), ..Default::default() }
+                                } else {
+                                    EqRequest { treble: Some(value), ..Default::default() }
+                                });
+                            }
+                        },
+                    }
+                    span { class: "w-8 text-right", "{value}" }
+                }
+            }
+        }
+        if let Some(matrix) = matrix {
+            div { class: "flex items-center gap-2 mt-3 text-sm",
+                span { class: "text-muted", "Matrix" }
+                select {
+                    class: "input flex-1 min-w-0",
+                    "aria-label": "Matrix profile",
+                    onchange: move |evt| {
+                        if let Ok(index) = evt.value().parse::<u32>() {
+                            set_eq(EqRequest { matrix_profile: Some(index), ..Default::default() });
+                        }
+                    },
+                    for profile in matrix.profiles {
+                        option {
+                            value: "{profile.index}",
+                            selected: Some(profile.index) == current_matrix,
+                            "{profile.name}"
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(error) = eq_error() {
+            p { class: "text-sm text-error mt-1", "{error}" }
+        }
+    }
+}
+
+/// Input select for zones whose device can switch inputs
+///
+/// Renders nothing until `/zones/{zone_id}/input` lists more than one input.
+#[component]
+fn ZoneInput(zone_id: String) -> Element {
+    let mut input_error = use_signal(|| None::<String>);
+    let input_url = use_signal(|| format!("/zones/{}/input", urlencoding::encode(&zone_id)));
+    let mut inputs = use_resource(move || async move {
+        crate::app::api::fetch_json::<ZoneInputResponse>(&input_url())
+            .await
+            .ok()
+    });
+
+    let Some(Some(response)) = inputs.read().clone() else {
+        return rsx! {};
+    };
+    if response.inputs.len() < 2 {
+        return rsx! {};
+    }
+    let current = response.current.clone();
+
+    rsx! {
+        div { class: "flex items-center gap-2 mt-3 text-sm",
+            span { class: "text-muted", "Input" }
+            select {
+                class: "input flex-1 min-w-0",
+                "aria-label": "Input",
+                onchange: move |evt| {
+                    let url = input_url();
+                    let req = InputRequest { input: evt.value() };
+                    input_error.set(None);
+                    spawn(async move {
+                        match crate::app::api::post_json_no_response(&url, &req).await {
+                            Ok(()) => inputs.restart(),
+                            Err(e) => input_error.set(Some(format!("Input change failed: {e}"))),
+                        }
+                    });
+                },
+                for input in response.inputs {
+                    option {
+                        value: "{input.id}",
+                        selected: current.as_deref() == Some(input.id.as_str()),
+                        "{input.name}"
+                    }
                 }
             }
         }
+        if let Some(error) = input_error() {
+            p { class: "text-sm text-error mt-1", "{error}" }
+        }
+    }
+}
+
+/// Preset select, master volume and mute for zones linked to a MiniDSP unit
+///
+/// Renders nothing until `/minidsp/zones/{zone_id}` answers; the volume
+/// slider sends its value on release.
+#[component]
+fn ZoneMiniDsp(zone_id: String) -> Element {
+    let mut minidsp_error = use_signal(|| None::<String>);
+    let minidsp_url = use_signal(|| format!("/minidsp/zones/{}", urlencoding::encode(&zone_id)));
+    let mut status = use_resource(move || async move {
+        crate::app::api::fetch_json::<MiniDspStatus>(&minidsp_url())
+            .await
+            .ok()
+    });
+
+    let mut update = move |req: MiniDspRequest| {
+        let url = minidsp_url();
+        minidsp_error.set(None);
+        spawn(async move {
+            match crate::app::api::post_json_no_response(&url, &req).await {
+                Ok(()) => status.restart(),
+                Err(e) => minidsp_error.set(Some(format!("MiniDSP change failed: {e}"))),
+            }
+        });
+    };
+
+    let Some(Some(current)) = status.read().clone() else {
+        return rsx! {};
+    };
+    let mute = current.mute;
+
+    rsx! {
+        div { class: "flex items-center gap-2 mt-3 text-sm",
+            span { class: "text-muted", "MiniDSP" }
+            select {
+                class: "input flex-1 min-w-0",
+                "aria-label": "MiniDSP preset",
+                onchange: move |evt| {
+                    if let Ok(index) = evt.value().parse::<u8>() {
+                        update(MiniDspRequest { preset: Some(index), ..Default::default() });
+                    }
+                },
+                for preset in current.presets {
+                    option {
+                        value: "{preset.index}",
+                        selected: preset.index == current.preset,
+                        "{preset.name}"
+                    }
+                }
+            }
+            button {
+                class: if mute { "btn btn-primary" } else { "btn btn-ghost" },
+                "aria-pressed": if mute { "true" } else { "false" },
+                onclick: move |_| update(MiniDspRequest { mute: Some(!mute), ..Default::default() }),
+                "Mute"
+            }
+        }
+        div { class: "flex items-center gap-2 mt-3 text-sm",
+            span { class: "text-muted w-14", "Level" }
+            input {
+                class: "flex-1 min-w-0",
+                r#type: "range",
+                min: "-127.5",
+                max: "0",
+                step: "0.5",
+                value: "{current.volume}",
+                "aria-label": "MiniDSP master volume",
+                onchange: move |evt| {
+                    if let Ok(volume) = evt.value().parse::<f64>() {
+                        update(MiniDspRequest { volume: Some(volume), ..Default::default() });
+                    }
+                },
+            }
+            span { class: "w-16 text-right", "{current.volume} dB" }
+        }
+        if let Some(error) = minidsp_error() {
+            p { class: "text-sm text-error mt-1", "{error}" }
+        }
+    }
+}
+
+/// Body for POST /roon/core/power
+#[derive(Clone, serde::Serialize)]
+struct CorePowerRequest {
+    action: &'static str,
+}
+
+/// Wake button for a sleeping Roon Core, shown once Wake-on-LAN is set up
+#[component]
+fn RoonCoreWake() -> Element {
+    let mut message = use_signal(|| None::<String>);
+    let mut power = use_resource(|| async {
+        crate::app::api::fetch_json::<RoonCorePower>("/roon/core/power")
+            .await
+            .ok()
+    });
+
+    let Some(Some(current)) = power.read().clone() else {
+        return rsx! {};
+    };
+    if !current.actions.iter().any(|a| a == "wake") {
+        return rsx! {};
+    }
+
+    let wake = move |_| {
+        message.set(None);
+        spawn(async move {
+            let req = CorePowerRequest { action: "wake" };
+            match crate::app::api::post_json::<_, serde_json::Value>("/roon/core/power", &req).await
+            {
+                Ok(resp) => match resp.get("error").and_then(|e| e.as_str()) {
+                    Some(error) => message.set(Some(error.to_string())),
+                    None => {
+                        message.set(Some("Wake packet sent".to_string()));
+                        power.restart();
+                    }
+                },
+                Err(e) => message.set(Some(format!("Wake failed: {e}"))),
+            }
+        });
+    };
+
+    rsx! {
+        span { class: "flex items-baseline gap-2 text-sm",
+            if current.connected {
+                span { class: "text-muted", "Roon Core online" }
+            } else {
+                button { class: "btn btn-ghost btn-sm", onclick: wake, "Wake Roon Core" }
+            }
+            if let Some(msg) = message() {
+                span { class: "text-muted", "{msg}" }
+            }
+        }
+    }
+}
+
+/// Lines shown before and after the current one in the synced view
+const LYRICS_CONTEXT_LINES: usize = 2;
+
+/// Lyrics panel for a zone's current track
+///
+/// Refetched when the track changes; the highlighted line follows the
+/// bridge's seek position from `SeekPositionChanged` events.
+#[component]
+fn ZoneLyrics(zone_id: String) -> Element {
+    let sse = use_sse();
+    let mut position = use_signal(|| None::<f64>);
+
+    let zone_fetch = zone_id.clone();
+    let mut lyrics = use_resource(move || {
+        let zone_id = zone_fetch.clone();
+        async move {
+            let url = format!("/zones/{}/lyrics", urlencoding::encode(&zone_id));
+            crate::app::api::fetch_json::<ZoneLyricsResponse>(&url)
+                .await
+                .ok()
+        }
+    });
+
+    use_effect(move || {
+        let _ = (sse.event_count)();
+        match (sse.last_event)() {
+            Some(SseEvent::NowPlayingChanged { payload }) if payload.zone_id == zone_id => {
+                position.set(None);
+                lyrics.restart();
+            }
+            Some(SseEvent::SeekPositionChanged { payload }) if payload.zone_id == zone_id => {
+                position.set(payload.position);
+            }
+            _ => {}
+        }
+    });
+
+    let response = lyrics.read().clone();
+    let content = match response {
+        None => rsx! { p { class: "text-sm text-muted", aria_busy: "true", "Loading lyrics..." } },
+        Some(None) => rsx! { p { class: "text-sm text-muted", "Lyrics unavailable" } },
+        Some(Some(r)) if !r.enabled => rsx! {
+            p { class: "text-sm text-muted", "No lyrics provider configured (see README: Lyrics)" }
+        },
+        Some(Some(ZoneLyricsResponse { lyrics: None, .. })) => {
+            rsx! { p { class: "text-sm text-muted", "No lyrics found" } }
+        }
+        Some(Some(ZoneLyricsResponse {
+            lyrics: Some(l), ..
+        })) if l.instrumental => {
+            rsx! { p { class: "text-sm text-muted", "Instrumental" } }
+        }
+        Some(Some(ZoneLyricsResponse {
+            lyrics: Some(l),
+            position: server_position,
+            current_line,
+            ..
+        })) if l.synced => {
+            let current = match position().or(server_position) {
+                Some(pos) => l
+                    .lines
+                    .iter()
+                    .rposition(|line| line.time_secs.is_some_and(|t| t <= pos)),
+                None => current_line,
+            };
+            let start = current.unwrap_or(0).saturating_sub(LYRICS_CONTEXT_LINES);
+            let end = (current.unwrap_or(0) + LYRICS_CONTEXT_LINES + 1).min(l.lines.len());
+            let window: Vec<(usize, String)> = l.lines[start.min(end)..end]
+                .iter()
+                .enumerate()
+                .map(|(i, line)| (start + i, line.text.clone()))
+                .collect();
+            rsx! {
+                div { class: "space-y-1 text-center",
+                    for (index, text) in window {
+                        p {
+                            key: "{index}",
+                            class: if Some(index) == current { "font-semibold" } else { "text-sm text-muted" },
+                            if text.is_empty() { "♪" } else { "{text}" }
+                        }
+                    }
+                }
+            }
+        }
+        Some(Some(ZoneLyricsResponse {
+            lyrics: Some(l), ..
+        })) => {
+            let lines: Vec<(usize, String)> = l
+                .lines
+                .iter()
+                .map(|line| line.text.clone())
+                .enumerate()
+                .collect();
+            rsx! {
+                div { class: "max-h-48 overflow-y-auto text-sm",
+                    for (index, text) in lines {
+                        p { key: "{index}", "{text}" }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div { class: "mt-4 pt-3 border-t border-default", "aria-live": "polite",
+            {content}
+        }
     }
 }
@@ -2,12 +2,17 @@
 //!
 //! Provides a single EventSource connection shared across all components,
 //! with typed event signals for reactive updates.
+//!
+//! Some networks (captive portals, corporate proxies) keep dropping the SSE
+//! stream. After repeated errors without a successful open, the client closes the
+//! EventSource and falls back to long-polling `/events/poll`, feeding the same
+//! signals.
 
 use dioxus::prelude::*;
 use serde::Deserialize;
 
 #[cfg(target_arch = "wasm32")]
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 #[cfg(target_arch = "wasm32")]
 use std::rc::Rc;
 #[cfg(target_arch = "wasm32")]
@@ -17,6 +22,9 @@ use wasm_bindgen::prelude::*;
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct ZonePayload {
     pub zone_id: String,
+    /// Seek position in seconds (`SeekPositionChanged` only)
+    #[serde(default)]
+    pub position: Option<f64>,
 }
 
 /// Payload for LMS player events
@@ -47,6 +55,12 @@ pub enum SseEvent {
     ZoneRemoved {
         payload: ZonePayload,
     },
+    ZoneMetadataChanged {
+        payload: ZonePayload,
+    },
+    ZoneStatePending {
+        payload: ZonePayload,
+    },
     NowPlayingChanged {
         payload: ZonePayload,
     },
@@ -62,6 +76,8 @@ pub enum SseEvent {
     HqpDisconnected,
     HqpStateChanged,
     HqpPipelineChanged,
+    HqpNaaLost,
+    HqpNaaFound,
 
     // LMS events
     LmsConnected,
@@ -89,6 +105,8 @@ impl SseEvent {
         match self {
             SseEvent::ZoneUpdated { payload } => Some(&payload.zone_id),
             SseEvent::ZoneRemoved { payload } => Some(&payload.zone_id),
+            SseEvent::ZoneMetadataChanged { payload } => Some(&payload.zone_id),
+            SseEvent::ZoneStatePending { payload } => Some(&payload.zone_id),
             SseEvent::NowPlayingChanged { payload } => Some(&payload.zone_id),
             SseEvent::SeekPositionChanged { payload } => Some(&payload.zone_id),
             _ => None,
@@ -115,6 +133,8 @@ impl SseContext {
             Some(
                 SseEvent::ZoneUpdated { .. }
                     | SseEvent::ZoneRemoved { .. }
+                    | SseEvent::ZoneMetadataChanged { .. }
+                    | SseEvent::ZoneStatePending { .. }
                     | SseEvent::NowPlayingChanged { .. }
                     | SseEvent::SeekPositionChanged { .. }
                     | SseEvent::VolumeChanged { .. }
@@ -141,6 +161,8 @@ impl SseContext {
                     | SseEvent::HqpDisconnected
                     | SseEvent::HqpStateChanged
                     | SseEvent::HqpPipelineChanged
+                    | SseEvent::HqpNaaLost
+                    | SseEvent::HqpNaaFound
             )
         )
     }
@@ -175,6 +197,7 @@ impl SseContext {
             Some(
                 SseEvent::ZoneUpdated { .. }
                     | SseEvent::ZoneRemoved { .. }
+                    | SseEvent::ZoneMetadataChanged { .. }
                     | SseEvent::RoonConnected
                     | SseEvent::RoonDisconnected
                     | SseEvent::LmsConnected
@@ -184,10 +207,30 @@ impl SseContext {
     }
 }
 
+/// Consecutive EventSource errors (without an open) before switching to long-poll
+#[cfg(target_arch = "wasm32")]
+const SSE_MAX_ERRORS: u32 = 3;
+
+/// Delay before retrying a failed long-poll request
+#[cfg(target_arch = "wasm32")]
+const POLL_RETRY_MS: i32 = 5_000;
+
+/// Response from `GET /events/poll`
+#[cfg(target_arch = "wasm32")]
+#[derive(Deserialize)]
+struct EventPollResponse {
+    events: Vec<serde_json::Value>,
+    next: u64,
+    #[serde(default)]
+    truncated: bool,
+}
+
 /// RAII guard to close EventSource on drop
 #[cfg(target_arch = "wasm32")]
 struct EventSourceGuard {
     es: web_sys::EventSource,
+    /// Stops the long-poll fallback loop, if running
+    stopped: Rc<Cell<bool>>,
     // Store closures so they're dropped with the guard (prevents leaks)
     _onopen: Closure<dyn FnMut(web_sys::Event)>,
     _onmessage: Closure<dyn FnMut(web_sys::MessageEvent)>,
@@ -198,10 +241,51 @@ struct EventSourceGuard {
 impl Drop for EventSourceGuard {
     fn drop(&mut self) {
         web_sys::console::log_1(&"SSE: Closing EventSource connection".into());
+        self.stopped.set(true);
         self.es.close();
     }
 }
 
+/// Long-poll `/events/poll` until `stopped` is set, publishing into the SSE signals
+#[cfg(target_arch = "wasm32")]
+async fn run_long_poll(
+    mut last_event: Signal<Option<SseEvent>>,
+    mut connected: Signal<bool>,
+    mut event_count: Signal<u64>,
+    stopped: Rc<Cell<bool>>,
+) {
+    use crate::app::api::{fetch_json, sleep_ms};
+
+    web_sys::console::log_1(&"SSE: Falling back to long-poll on /events/poll".into());
+    let mut since: Option<u64> = None;
+    while !stopped.get() {
+        let url = match since {
+            Some(cursor) => format!("/events/poll?since={}", cursor),
+            None => "/events/poll".to_string(),
+        };
+        match fetch_json::<EventPollResponse>(&url).await {
+            Ok(response) => {
+                connected.set(true);
+                if response.truncated {
+                    web_sys::console::warn_1(&"SSE: Long-poll missed events".into());
+                }
+                for value in response.events {
+                    if let Ok(event) = serde_json::from_value::<SseEvent>(value) {
+                        last_event.set(Some(event));
+                        event_count.set(event_count() + 1);
+                    }
+                }
+                since = Some(response.next);
+            }
+            Err(e) => {
+                web_sys::console::warn_1(&format!("SSE: Long-poll error: {}", e).into());
+                connected.set(false);
+                sleep_ms(POLL_RETRY_MS).await;
+            }
+        }
+    }
+}
+
 /// Initialize SSE context provider - call once at app root
 pub fn use_sse_provider() {
     let last_event = use_signal(|| None::<SseEvent>);
@@ -246,10 +330,15 @@ pub fn use_sse_provider() {
 
             web_sys::console::log_1(&"SSE: Creating EventSource connection to /events".into());
 
+            let stopped = Rc::new(Cell::new(false));
+            let errors = Rc::new(Cell::new(0u32));
+
             // onopen handler
             let mut connected_clone = connected;
+            let errors_open = errors.clone();
             let onopen = Closure::wrap(Box::new(move |_: web_sys::Event| {
                 web_sys::console::log_1(&"SSE: Connection opened".into());
+                errors_open.set(0);
                 connected_clone.set(true);
             }) as Box<dyn FnMut(_)>);
             es.set_onopen(Some(onopen.as_ref().unchecked_ref()));
@@ -279,17 +368,31 @@ pub fn use_sse_provider() {
             }) as Box<dyn FnMut(_)>);
             es.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
 
-            // onerror handler
+            // onerror handler - give up on SSE after repeated failures
             let mut connected_err = connected;
+            let es_err = es.clone();
+            let stopped_err = stopped.clone();
             let onerror = Closure::wrap(Box::new(move |_: web_sys::Event| {
                 web_sys::console::warn_1(&"SSE: Connection error".into());
                 connected_err.set(false);
+                let count = errors.get() + 1;
+                errors.set(count);
+                if count == SSE_MAX_ERRORS && !stopped_err.get() {
+                    es_err.close();
+                    wasm_bindgen_futures::spawn_local(run_long_poll(
+                        last_event,
+                        connected,
+                        event_count,
+                        stopped_err.clone(),
+                    ));
+                }
             }) as Box<dyn FnMut(_)>);
             es.set_onerror(Some(onerror.as_ref().unchecked_ref()));
 
             // Store guard - closures are now owned by the guard and will be dropped properly
             *guard_clone.borrow_mut() = Some(EventSourceGuard {
                 es,
+                stopped,
                 _onopen: onopen,
                 _onmessage: onmessage,
                 _onerror: onerror,
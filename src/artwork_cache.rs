@@ -0,0 +1,149 @@
+//! Shared in-memory cache for processed artwork
+//!
+//! Backends that can't resize or cache artwork themselves (OpenHome renderers
+//! serve full-size files on every request) go through this cache after
+//! resizing/re-encoding, keyed by source key plus the requested options.
+//! Entries are evicted least-recently-used once the byte budget is exceeded.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::bus::ImageData;
+use crate::knobs::image::ArtworkOptions;
+
+/// Default byte budget (enough for a few hundred knob-sized JPEGs)
+pub const DEFAULT_MAX_BYTES: usize = 32 * 1024 * 1024;
+
+struct CachedArtwork {
+    image: ImageData,
+    last_used: Instant,
+}
+
+/// Bounded LRU cache of processed artwork
+pub struct ArtworkCache {
+    entries: Mutex<HashMap<String, CachedArtwork>>,
+    max_bytes: usize,
+}
+
+impl Default for ArtworkCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BYTES)
+    }
+}
+
+impl ArtworkCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_bytes,
+        }
+    }
+
+    /// Cache key for a source image rendered with the given options
+    pub fn key(source: &str, options: &ArtworkOptions) -> String {
+        format!(
+            "{}|{:?}|{:?}|{:?}|{:?}",
+            source, options.width, options.height, options.format, options.quality
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<ImageData> {
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries.get_mut(key)?;
+        entry.last_used = Instant::now();
+        Some(entry.image.clone())
+    }
+
+    pub fn insert(&self, key: String, image: ImageData) {
+        // Larger than the whole budget: serve it but don't cache it
+        if image.data.len() > self.max_bytes {
+            return;
+        }
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        entries.insert(
+            key,
+            CachedArtwork {
+                image,
+                last_used: Instant::now(),
+            },
+        );
+
+        let mut total: usize = entries.values().map(|e| e.image.data.len()).sum();
+        while total > self.max_bytes {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = entries.remove(&oldest) {
+                total -= evicted.image.data.len();
+            }
+        }
+    }
+
+    /// Number of cached entries
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(len: usize) -> ImageData {
+        ImageData {
+            content_type: "image/jpeg".to_string(),
+            data: vec![0; len],
+        }
+    }
+
+    #[test]
+    fn test_key_includes_options() {
+        let small = ArtworkOptions {
+            width: Some(100),
+            ..Default::default()
+        };
+        let large = ArtworkOptions {
+            width: Some(400),
+            ..Default::default()
+        };
+        assert_ne!(
+            ArtworkCache::key("http://a/art.jpg", &small),
+            ArtworkCache::key("http://a/art.jpg", &large)
+        );
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = ArtworkCache::new(100);
+        cache.insert("a".to_string(), image(40));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("b".to_string(), image(40));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        // Touch "a" so "b" becomes the oldest
+        assert!(cache.get("a").is_some());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("c".to_string(), image(40));
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[test]
+    fn test_skips_oversized_images() {
+        let cache = ArtworkCache::new(10);
+        cache.insert("big".to_string(), image(11));
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+//! Config backups
+//!
+//! A backup is a single JSON bundle of every config/state file (Roon pairing,
+//! HQPlayer instances and zone links, LMS, knobs, app settings), written to the
+//! backup directory on a schedule and pruned to the configured retention.
+//! Restoring writes the bundled files back to the config directory; adapters
+//! pick them up after a restart.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::{get_config_file_path, read_config_file, BackupSettings};
+
+/// Files included in a bundle
+const BUNDLE_FILES: &[&str] = &[
+    "app-settings.json",
+    "roon_state.json",
+    "roon-core-power.json",
+    "hqp-config.json",
+    "hqp-zone-links.json",
+    "hqp-naa.json",
+    "camilladsp-config.json",
+    "minidsp-config.json",
+    "lms-config.json",
+    "snapcast-config.json",
+    "heos-config.json",
+    "kodi-config.json",
+    "spotify-config.json",
+    "http-devices.json",
+    "plexamp-config.json",
+    "knobs.json",
+    "zone-metadata.json",
+    "remote-access.json",
+    "webhooks.json",
+    "hooks.json",
+    "selection.json",
+    "tokens.json",
+];
+
+const BUNDLE_VERSION: u32 = 1;
+const BACKUP_PREFIX: &str = "uhc-backup-";
+const BACKUP_SUFFIX: &str = ".json";
+
+/// How often the scheduler checks whether a backup is due
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Exported config files
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// File name → parsed file contents
+    pub files: BTreeMap<String, serde_json::Value>,
+}
+
+/// A backup file in the backup directory
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Collect the current config files into a bundle
+pub fn export_bundle() -> ConfigBundle {
+    let mut files = BTreeMap::new();
+    for &name in BUNDLE_FILES {
+        let Some(content) = read_config_file(name) else {
+            continue;
+        };
+        match serde_json::from_str(&content) {
+            Ok(value) => {
+                files.insert(name.to_string(), value);
+            }
+            Err(e) => tracing::warn!("Skipping unparseable {} in backup: {}", name, e),
+        }
+    }
+    ConfigBundle {
+        version: BUNDLE_VERSION,
+        created_at: Utc::now(),
+        files,
+    }
+}
+
+/// Write a new backup to `dir`
+pub fn write_backup(dir: &Path) -> Result<BackupInfo> {
+    std::fs::create_dir_all(dir)?;
+    let bundle = export_bundle();
+    let name = format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        bundle.created_at.format("%Y%m%d-%H%M%S"),
+        BACKUP_SUFFIX
+    );
+    let json = serde_json::to_string_pretty(&bundle)?;
+    std::fs::write(dir.join(&name), &json)?;
+    tracing::info!(
+        "Wrote config backup {} ({} files)",
+        name,
+        bundle.files.len()
+    );
+    Ok(BackupInfo {
+        name,
+        created_at: bundle.created_at,
+        size_bytes: json.len() as u64,
+    })
+}
+
+/// Backups in `dir`, newest first
+pub fn list_backups(dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !is_backup_name(&name) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some(BackupInfo {
+                name,
+                created_at: modified.into(),
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect();
+    // Names embed the UTC timestamp, so they sort chronologically
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+/// Delete all but the newest `keep` backups in `dir`
+pub fn prune_backups(dir: &Path, keep: usize) {
+    for old in list_backups(dir).into_iter().skip(keep) {
+        match std::fs::remove_file(dir.join(&old.name)) {
+            Ok(()) => tracing::info!("Removed old config backup {}", old.name),
+            Err(e) => tracing::warn!("Failed to remove old backup {}: {}", old.name, e),
+        }
+    }
+}
+
+/// Restore the named backup from `dir`, returning the restored file names.
+///
+/// The current config is backed up first so a wrong pick can be undone.
+pub fn restore_backup(dir: &Path, name: &str) -> Result<Vec<String>> {
+    if !is_backup_name(name) {
+        return Err(anyhow!("Invalid backup name: {}", name));
+    }
+    let content = std::fs::read_to_string(dir.join(name))?;
+    let bundle: ConfigBundle = serde_json::from_str(&content)?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(anyhow!(
+            "Backup version {} is newer than supported ({})",
+            bundle.version,
+            BUNDLE_VERSION
+        ));
+    }
+
+    write_backup(dir)?;
+
+    let mut restored = Vec::new();
+    for (file, value) in &bundle.files {
+        // Only ever write known files, whatever the bundle contains
+        if !BUNDLE_FILES.contains(&file.as_str()) {
+            tracing::warn!("Ignoring unknown file {} in backup {}", file, name);
+            continue;
+        }
+        let path = get_config_file_path(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(value)?)?;
+        restored.push(file.clone());
+    }
+    tracing::info!("Restored {} config files from {}", restored.len(), name);
+    Ok(restored)
+}
+
+/// Plain file name of the form `uhc-backup-*.json` (no path components)
+fn is_backup_name(name: &str) -> bool {
+    name.starts_with(BACKUP_PREFIX)
+        && name.ends_with(BACKUP_SUFFIX)
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
+
+/// Whether a scheduled backup is due given the newest existing backup
+fn backup_due(settings: &BackupSettings, newest: Option<&BackupInfo>) -> bool {
+    let Some(newest) = newest else {
+        return true;
+    };
+    let age = Utc::now().signed_duration_since(newest.created_at);
+    age.to_std().is_ok_and(|age| age >= settings.interval())
+}
+
+/// Run scheduled backups until shutdown.
+///
+/// Settings are re-read on every check so changes in the UI apply without a
+/// restart, and the due time is based on the newest backup on disk so restarts
+/// don't trigger extra backups.
+pub fn start_scheduler(load_settings: fn() -> BackupSettings, shutdown: CancellationToken) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => {
+                    tracing::debug!("Backup scheduler shutdown requested");
+                    break;
+                }
+                _ = ticker.tick() => {
+                    let settings = load_settings();
+                    if !settings.enabled {
+                        continue;
+                    }
+                    let dir = settings.directory();
+                    if !backup_due(&settings, list_backups(&dir).first()) {
+                        continue;
+                    }
+                    match write_backup(&dir) {
+                        Ok(_) => prune_backups(&dir, settings.keep()),
+                        Err(e) => tracing::warn!("Scheduled config backup failed: {}", e),
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_name_validation() {
+        assert!(is_backup_name("uhc-backup-20261016-120000.json"));
+        assert!(!is_backup_name("../uhc-backup-1.json"));
+        assert!(!is_backup_name("uhc-backup-../../etc.json"));
+        assert!(!is_backup_name("knobs.json"));
+    }
+
+    #[test]
+    fn test_list_and_prune_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        for ts in ["20260101-000000", "20260301-000000", "20260201-000000"] {
+            std::fs::write(dir.path().join(format!("uhc-backup-{ts}.json")), "{}").unwrap();
+        }
+        std::fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+        let names: Vec<_> = list_backups(dir.path())
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "uhc-backup-20260301-000000.json",
+                "uhc-backup-20260201-000000.json",
+                "uhc-backup-20260101-000000.json",
+            ]
+        );
+
+        prune_backups(dir.path(), 1);
+        assert_eq!(list_backups(dir.path()).len(), 1);
+        assert!(dir.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_backup_due() {
+        let settings = BackupSettings::default();
+        assert!(backup_due(&settings, None));
+        let recent = BackupInfo {
+            name: "uhc-backup-x.json".to_string(),
+            created_at: Utc::now(),
+            size_bytes: 0,
+        };
+        assert!(!backup_due(&settings, Some(&recent)));
+        let old = BackupInfo {
+            created_at: Utc::now() - chrono::Duration::hours(25),
+            ..recent
+        };
+        assert!(backup_due(&settings, Some(&old)));
+    }
+}
@@ -105,6 +105,9 @@ struct SuccessResponse {
 #[derive(Debug, Deserialize)]
 struct ErrorResponse {
     error: String,
+    error_code: String,
+    retryable: bool,
+    backend: Option<String>,
 }
 
 // HQPlayer types
@@ -472,7 +475,10 @@ fn generate_example(schema_type: &str) {
             "ok": true
         }),
         "error-response" => serde_json::json!({
-            "error": "Zone not found: zone-invalid"
+            "error": "Zone not found: zone-invalid",
+            "error_code": "ZONE_NOT_FOUND",
+            "retryable": false,
+            "backend": "roon"
         }),
         "bus-event" => {
             // Show multiple examples for bus events
@@ -47,6 +47,41 @@ impl PrefixedZoneId {
         Self(format!("upnp:{}", raw_id.as_ref()))
     }
 
+    /// Create an AirPlay zone ID
+    pub fn airplay(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("airplay:{}", raw_id.as_ref()))
+    }
+
+    /// Create a Snapcast zone ID (`client:<id>` or `group:<id>`)
+    pub fn snapcast(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("snapcast:{}", raw_id.as_ref()))
+    }
+
+    /// Create a HEOS zone ID (`player:<pid>` or `group:<gid>`)
+    pub fn heos(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("heos:{}", raw_id.as_ref()))
+    }
+
+    /// Create a Kodi zone ID (the Kodi host)
+    pub fn kodi(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("kodi:{}", raw_id.as_ref()))
+    }
+
+    /// Create a Spotify zone ID (the Connect device id)
+    pub fn spotify(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("spotify:{}", raw_id.as_ref()))
+    }
+
+    /// Create an HTTP JSON zone ID (the device id from its template)
+    pub fn http(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("http:{}", raw_id.as_ref()))
+    }
+
+    /// Create a Plexamp zone ID (the player host)
+    pub fn plexamp(raw_id: impl AsRef<str>) -> Self {
+        Self(format!("plexamp:{}", raw_id.as_ref()))
+    }
+
     /// Create a HQPlayer zone ID
     pub fn hqplayer(raw_id: impl AsRef<str>) -> Self {
         Self(format!("hqplayer:{}", raw_id.as_ref()))
@@ -56,7 +91,20 @@ impl PrefixedZoneId {
     /// Returns None if the string doesn't contain a valid prefix.
     pub fn parse(s: impl AsRef<str>) -> Option<Self> {
         let s = s.as_ref();
-        let valid_prefixes = ["roon:", "lms:", "openhome:", "upnp:", "hqplayer:"];
+        let valid_prefixes = [
+            "roon:",
+            "lms:",
+            "openhome:",
+            "upnp:",
+            "airplay:",
+            "snapcast:",
+            "heos:",
+            "kodi:",
+            "spotify:",
+            "http:",
+            "plexamp:",
+            "hqplayer:",
+        ];
         if valid_prefixes.iter().any(|p| s.starts_with(p)) {
             Some(Self(s.to_string()))
         } else {
@@ -132,6 +180,14 @@ pub struct Zone {
     /// Whether the zone supports seeking
     pub is_seekable: bool,
 
+    /// What the zone's backend supports at all (see [`ZoneCapabilities`])
+    #[serde(default)]
+    pub capabilities: ZoneCapabilities,
+
+    /// Loudness normalization mode, if the backend exposes one (LMS ReplayGain)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_leveling: Option<VolumeLeveling>,
+
     /// Last update timestamp (milliseconds since epoch)
     pub last_updated: u64,
 
@@ -146,6 +202,119 @@ pub struct Zone {
 
     /// Whether previous track command is allowed
     pub is_previous_allowed: bool,
+
+    /// User-assigned room, icon, ordering and color (merged in by the aggregator)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ZoneMetadata>,
+}
+
+/// Features a zone's backend supports, independent of playback state.
+///
+/// Clients use these to hide controls that can never work for a zone (e.g. a
+/// seek bar on a UPnP renderer) rather than showing them and failing on click.
+/// The `is_*_allowed` flags on [`Zone`] cover what is possible right now.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ZoneCapabilities {
+    /// Position within a track can be changed
+    pub can_seek: bool,
+    /// Backend has a shuffle mode
+    pub can_shuffle: bool,
+    /// Volume can be changed (false for fixed-volume outputs)
+    pub has_volume: bool,
+    /// Backend keeps a play queue / playlist
+    pub has_queue: bool,
+    /// Zone can be grouped or synced with other zones of the same backend
+    pub supports_grouping: bool,
+    /// Tone or DSP settings can be changed through `/zones/{zone_id}/eq`
+    #[serde(default)]
+    pub has_eq: bool,
+    /// Inputs can be switched through `/zones/{zone_id}/input`
+    #[serde(default)]
+    pub has_input: bool,
+}
+
+/// Loudness normalization applied by a zone's backend.
+///
+/// Mirrors LMS's ReplayGain modes; `smart` picks album gain when playing an
+/// album in order and track gain otherwise.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeLeveling {
+    Off,
+    Track,
+    Album,
+    Smart,
+}
+
+impl std::str::FromStr for VolumeLeveling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "track" => Ok(Self::Track),
+            "album" => Ok(Self::Album),
+            "smart" => Ok(Self::Smart),
+            other => Err(format!(
+                "Invalid volume leveling mode '{}' (expected off, track, album or smart)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for VolumeLeveling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Track => write!(f, "track"),
+            Self::Album => write!(f, "album"),
+            Self::Smart => write!(f, "smart"),
+        }
+    }
+}
+
+/// User-assigned presentation metadata for a zone.
+///
+/// Adapters never set this; it is stored in `zone-metadata.json` and merged
+/// into zones by the aggregator so every client sees the same rooms and order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ZoneMetadata {
+    /// Room the zone belongs to (e.g. "Living Room")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    /// Short icon name or emoji shown next to the zone name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Ordering weight; lower sorts first, unset counts as 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_weight: Option<i32>,
+    /// Accent color as `#rrggbb`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Relative volume change per knob detent or up/down press, in the zone's
+    /// volume units (dB or %); overrides `volume_control.step`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_step: Option<f32>,
+    /// Other names the zone answers to when looked up by name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Transport lock: control commands from non-admin callers are rejected
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+}
+
+impl ZoneMetadata {
+    /// True if no field is set
+    pub fn is_empty(&self) -> bool {
+        self.room.is_none()
+            && self.icon.is_none()
+            && self.sort_weight.is_none()
+            && self.color.is_none()
+            && self.volume_step.is_none()
+            && self.aliases.is_empty()
+            && !self.locked
+    }
 }
 
 /// Playback state enumeration
@@ -414,6 +583,16 @@ pub struct CommandResponse {
     pub timestamp: u64,
 }
 
+/// Physical input on a knob
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KnobGesture {
+    Press,
+    DoublePress,
+    LongPress,
+    Rotate,
+}
+
 // =============================================================================
 // Bus Events
 // =============================================================================
@@ -425,6 +604,8 @@ pub struct CommandResponse {
 /// - Now playing: Track changes, seek updates
 /// - Commands: Incoming commands and their results
 /// - Adapter lifecycle: Adapter start/stop, cleanup
+/// - Knobs: Physical input, zone changes, battery transitions
+/// - HQPlayer NAA: Assigned NAA endpoints disappearing and returning
 /// - System: Shutdown, health checks
 /// - Legacy: Backward-compatible events for existing integrations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -456,6 +637,49 @@ pub enum BusEvent {
         zone_id: PrefixedZoneId,
     },
 
+    /// User-assigned zone metadata (room, icon, order, color) was changed
+    ZoneMetadataChanged {
+        /// Zone identifier
+        zone_id: String,
+    },
+
+    /// A client selected a zone (`/api/selection`, or a knob switching zones)
+    ZoneSelected {
+        /// Client key, e.g. "default", "token:<id>" or "knob:<id>"
+        client: String,
+        /// Zone identifier
+        zone_id: String,
+    },
+
+    /// Aggregator applied an optimistic state for a locally issued transport
+    /// command; confirmed by a matching ZoneUpdated or rolled back on timeout
+    ZoneStatePending {
+        /// Zone identifier (must be prefixed, e.g., "roon:xxx")
+        zone_id: PrefixedZoneId,
+        /// Predicted state
+        state: String,
+    },
+
+    /// A zone has been playing for the activity debounce window (see the
+    /// `activity` module); brief pauses and track changes don't repeat it
+    ZoneActive {
+        /// Zone identifier (must be prefixed, e.g., "roon:xxx")
+        zone_id: PrefixedZoneId,
+        zone_name: String,
+        /// Track playing when the zone became active
+        title: Option<String>,
+        artist: Option<String>,
+    },
+
+    /// An active zone has been stopped, paused or gone for the idle window
+    ZoneIdle {
+        /// Zone identifier (must be prefixed, e.g., "roon:xxx")
+        zone_id: PrefixedZoneId,
+        zone_name: String,
+        /// Seconds from starting to play until playback stopped
+        active_secs: u64,
+    },
+
     // =========================================================================
     // Now Playing Events
     // =========================================================================
@@ -549,6 +773,61 @@ pub enum BusEvent {
         reason: Option<String>,
     },
 
+    // =========================================================================
+    // Knob Events
+    // =========================================================================
+    /// A knob was pressed or turned
+    KnobInput {
+        knob_id: String,
+        /// User-assigned knob name, if set
+        knob_name: Option<String>,
+        /// Zone the knob controls
+        zone_id: Option<String>,
+        gesture: KnobGesture,
+        /// Control action the input triggered, if any
+        action: Option<String>,
+        /// Volume steps for rotation (negative turns down)
+        steps: Option<i32>,
+    },
+
+    /// A knob switched to another zone
+    KnobZoneChanged {
+        knob_id: String,
+        knob_name: Option<String>,
+        /// Previous zone
+        from: Option<String>,
+        zone_id: String,
+    },
+
+    /// A knob started or stopped charging, or its battery crossed the low
+    /// threshold
+    KnobBatteryChanged {
+        knob_id: String,
+        knob_name: Option<String>,
+        battery_level: Option<u8>,
+        charging: bool,
+        low: bool,
+    },
+
+    // =========================================================================
+    // HQPlayer NAA Events
+    // =========================================================================
+    /// The NAA assigned to an HQPlayer instance stopped answering discovery
+    HqpNaaLost {
+        /// HQPlayer instance name
+        instance: String,
+        /// Assigned NAA name or address
+        naa: String,
+    },
+
+    /// The NAA assigned to an HQPlayer instance answers discovery again
+    HqpNaaFound {
+        instance: String,
+        /// NAA name as reported by the endpoint
+        naa: String,
+        host: String,
+    },
+
     // =========================================================================
     // System Events
     // =========================================================================
@@ -614,6 +893,11 @@ impl BusEvent {
             Self::ZoneDiscovered { .. } => "zone_discovered",
             Self::ZoneUpdated { .. } => "zone_updated",
             Self::ZoneRemoved { .. } => "zone_removed",
+            Self::ZoneMetadataChanged { .. } => "zone_metadata_changed",
+            Self::ZoneSelected { .. } => "zone_selected",
+            Self::ZoneStatePending { .. } => "zone_state_pending",
+            Self::ZoneActive { .. } => "zone_active",
+            Self::ZoneIdle { .. } => "zone_idle",
             Self::NowPlayingChanged { .. } => "now_playing_changed",
             Self::SeekPositionChanged { .. } => "seek_position_changed",
             Self::VolumeChanged { .. } => "volume_changed",
@@ -624,6 +908,11 @@ impl BusEvent {
             Self::ZonesFlushed { .. } => "zones_flushed",
             Self::AdapterConnected { .. } => "adapter_connected",
             Self::AdapterDisconnected { .. } => "adapter_disconnected",
+            Self::KnobInput { .. } => "knob_input",
+            Self::KnobZoneChanged { .. } => "knob_zone_changed",
+            Self::KnobBatteryChanged { .. } => "knob_battery_changed",
+            Self::HqpNaaLost { .. } => "hqp_naa_lost",
+            Self::HqpNaaFound { .. } => "hqp_naa_found",
             Self::ShuttingDown { .. } => "shutting_down",
             Self::HealthCheck { .. } => "health_check",
             Self::RoonConnected { .. } => "roon_connected",
@@ -646,6 +935,11 @@ impl BusEvent {
             Self::ZoneDiscovered { .. }
                 | Self::ZoneUpdated { .. }
                 | Self::ZoneRemoved { .. }
+                | Self::ZoneMetadataChanged { .. }
+                | Self::ZoneSelected { .. }
+                | Self::ZoneStatePending { .. }
+                | Self::ZoneActive { .. }
+                | Self::ZoneIdle { .. }
                 | Self::ZonesFlushed { .. }
         )
     }
@@ -679,6 +973,14 @@ impl BusEvent {
         )
     }
 
+    /// Check if this is a knob interaction event
+    pub fn is_knob_event(&self) -> bool {
+        matches!(
+            self,
+            Self::KnobInput { .. } | Self::KnobZoneChanged { .. } | Self::KnobBatteryChanged { .. }
+        )
+    }
+
     /// Check if this is a legacy event
     pub fn is_legacy_event(&self) -> bool {
         matches!(
@@ -726,11 +1028,14 @@ mod tests {
                 source: "test".to_string(),
                 is_controllable: true,
                 is_seekable: true,
+                capabilities: ZoneCapabilities::default(),
+                volume_leveling: None,
                 last_updated: 0,
                 is_play_allowed: true,
                 is_pause_allowed: false,
                 is_next_allowed: true,
                 is_previous_allowed: true,
+                metadata: None,
             },
         };
         assert_eq!(event.event_type(), "zone_discovered");
@@ -762,6 +1067,39 @@ mod tests {
         assert!(json.contains("now_playing_changed") || json.contains("NowPlayingChanged"));
     }
 
+    #[test]
+    fn test_zone_capabilities_default_when_missing() {
+        let json = r#"{
+            "zone_id": "upnp:1",
+            "zone_name": "Renderer",
+            "state": "stopped",
+            "volume_control": null,
+            "now_playing": null,
+            "source": "upnp",
+            "is_controllable": true,
+            "is_seekable": false,
+            "last_updated": 0,
+            "is_play_allowed": true,
+            "is_pause_allowed": false,
+            "is_next_allowed": false,
+            "is_previous_allowed": false
+        }"#;
+        let zone: Zone = serde_json::from_str(json).unwrap();
+        assert_eq!(zone.capabilities, ZoneCapabilities::default());
+        assert!(!zone.capabilities.can_seek);
+        assert_eq!(zone.volume_leveling, None);
+    }
+
+    #[test]
+    fn test_volume_leveling_round_trip() {
+        for mode in ["off", "track", "album", "smart"] {
+            let parsed: VolumeLeveling = mode.parse().unwrap();
+            assert_eq!(parsed.to_string(), mode);
+            assert_eq!(serde_json::to_value(parsed).unwrap(), mode);
+        }
+        assert!("loud".parse::<VolumeLeveling>().is_err());
+    }
+
     #[test]
     fn test_prefixed_zone_id_constructors() {
         let roon = PrefixedZoneId::roon("abc123");
@@ -789,6 +1127,13 @@ mod tests {
         assert!(PrefixedZoneId::parse("lms:abc").is_some());
         assert!(PrefixedZoneId::parse("openhome:abc").is_some());
         assert!(PrefixedZoneId::parse("upnp:abc").is_some());
+        assert!(PrefixedZoneId::parse("airplay:abc").is_some());
+        assert!(PrefixedZoneId::parse("snapcast:client:abc").is_some());
+        assert!(PrefixedZoneId::parse("heos:player:-12").is_some());
+        assert!(PrefixedZoneId::parse("kodi:htpc.local").is_some());
+        assert!(PrefixedZoneId::parse("spotify:0d1841b0976bae2a").is_some());
+        assert!(PrefixedZoneId::parse("http:volumio").is_some());
+        assert!(PrefixedZoneId::parse("plexamp:plexamp-pi.local").is_some());
         assert!(PrefixedZoneId::parse("hqplayer:abc").is_some());
 
         // Invalid - no prefix
@@ -2,8 +2,15 @@
 //!
 //! Uses tokio::sync::broadcast for pub/sub pattern.
 //! Events are typed and can carry payloads.
-
-use std::sync::Arc;
+//!
+//! Recent events are also kept in a small sequenced history so clients that
+//! cannot hold an SSE stream open can catch up via `GET /events/poll`.
+//! Sequence numbers restart with the process; [`EventBus::run_id`] tells a
+//! cursor from a previous run apart from a current one.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 
 pub mod events;
@@ -13,6 +20,36 @@ pub use events::*;
 #[derive(Clone)]
 pub struct EventBus {
     sender: broadcast::Sender<BusEvent>,
+    history: Arc<Mutex<EventHistory>>,
+    run_id: u64,
+}
+
+/// Number of recent events retained for long-poll clients
+pub const HISTORY_CAPACITY: usize = 256;
+
+/// Bus event tagged with its position in the history
+#[derive(Debug, Clone, Serialize)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: BusEvent,
+}
+
+/// Result of reading the history after a cursor
+#[derive(Debug, Clone, Serialize)]
+pub struct EventsSince {
+    pub events: Vec<SequencedEvent>,
+    /// Cursor to pass as `since` on the next poll
+    pub next: u64,
+    /// True if events between `since` and the oldest returned event were dropped
+    /// (or the cursor is from a previous server run); clients should refetch state.
+    pub truncated: bool,
+}
+
+#[derive(Default)]
+struct EventHistory {
+    events: VecDeque<SequencedEvent>,
+    last_seq: u64,
 }
 
 impl Default for EventBus {
@@ -25,11 +62,31 @@ impl EventBus {
     /// Create a new event bus with specified capacity
     pub fn new(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender }
+        Self {
+            sender,
+            history: Arc::new(Mutex::new(EventHistory::default())),
+            run_id: crate::clock::unix_millis(),
+        }
+    }
+
+    /// Identifies this bus's sequence numbers (creation time in Unix millis)
+    pub fn run_id(&self) -> u64 {
+        self.run_id
     }
 
     /// Publish an event to all subscribers
     pub fn publish(&self, event: BusEvent) {
+        if let Ok(mut history) = self.history.lock() {
+            history.last_seq += 1;
+            let seq = history.last_seq;
+            if history.events.len() >= HISTORY_CAPACITY {
+                history.events.pop_front();
+            }
+            history.events.push_back(SequencedEvent {
+                seq,
+                event: event.clone(),
+            });
+        }
         // Ignore send errors (no subscribers)
         let _ = self.sender.send(event);
     }
@@ -43,6 +100,34 @@ impl EventBus {
     pub fn subscriber_count(&self) -> usize {
         self.sender.receiver_count()
     }
+
+    /// Sequence number of the most recently published event (0 if none)
+    pub fn last_seq(&self) -> u64 {
+        self.history.lock().map(|h| h.last_seq).unwrap_or(0)
+    }
+
+    /// Retained events published after `since`
+    pub fn events_since(&self, since: u64) -> EventsSince {
+        let Ok(history) = self.history.lock() else {
+            return EventsSince {
+                events: Vec::new(),
+                next: since,
+                truncated: true,
+            };
+        };
+        let oldest = history.events.front().map(|e| e.seq);
+        let truncated = since > history.last_seq || oldest.is_some_and(|seq| since + 1 < seq);
+        EventsSince {
+            events: history
+                .events
+                .iter()
+                .filter(|e| e.seq > since)
+                .cloned()
+                .collect(),
+            next: history.last_seq,
+            truncated,
+        }
+    }
 }
 
 /// Shared event bus wrapped in Arc for thread-safe sharing
@@ -93,4 +178,32 @@ mod tests {
             BusEvent::RoonDisconnected
         ));
     }
+
+    #[test]
+    fn test_events_since_cursor_and_truncation() {
+        let bus = EventBus::new(16);
+        assert_eq!(bus.last_seq(), 0);
+        for _ in 0..HISTORY_CAPACITY + 10 {
+            bus.publish(BusEvent::RoonDisconnected);
+        }
+        let last = bus.last_seq();
+        assert_eq!(last, (HISTORY_CAPACITY + 10) as u64);
+
+        let recent = bus.events_since(last - 2);
+        assert_eq!(recent.events.len(), 2);
+        assert_eq!(recent.next, last);
+        assert!(!recent.truncated);
+
+        // Oldest retained event is seq 11, so cursor 5 missed some
+        let stale = bus.events_since(5);
+        assert_eq!(stale.events.len(), HISTORY_CAPACITY);
+        assert!(stale.truncated);
+
+        // Cursor from a previous server run
+        assert!(bus.events_since(last + 100).truncated);
+
+        let json = serde_json::to_value(&recent.events[0]).unwrap();
+        assert_eq!(json["type"], "RoonDisconnected");
+        assert_eq!(json["seq"], last - 1);
+    }
 }
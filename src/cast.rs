@@ -0,0 +1,627 @@
+//! Cast dashboard
+//!
+//! Shows a web page (by default the `/embed/now_playing` card for the chosen
+//! zone) on a Chromecast or Android TV while that zone plays, and closes it
+//! again once the zone has been idle for a while.
+//!
+//! The page is opened with the DashCast receiver, which loads any URL. Each
+//! action is a short Cast v2 session: TLS to port 8009, then length-prefixed
+//! `CastMessage` protobufs carrying JSON payloads. The bridge never interrupts
+//! another app: if something other than the idle screen or DashCast is running,
+//! the cast is skipped, and only a DashCast session is ever stopped.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{
+    ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider,
+};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
+
+use crate::bus::{BusEvent, PlaybackState, SharedBus};
+use crate::config::CastConfig;
+
+/// Cast v2 port on every Cast device
+const CAST_PORT: u16 = 8009;
+
+/// DashCast receiver app (loads an arbitrary URL)
+const DASHCAST_APP_ID: &str = "84912283";
+const DASHCAST_NAMESPACE: &str = "urn:x-cast:com.madmod.dashcast";
+
+const CONNECTION_NAMESPACE: &str = "urn:x-cast:com.google.cast.tp.connection";
+const HEARTBEAT_NAMESPACE: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const RECEIVER_NAMESPACE: &str = "urn:x-cast:com.google.cast.receiver";
+
+const SENDER_ID: &str = "sender-0";
+const RECEIVER_ID: &str = "receiver-0";
+
+/// Longest a launch or stop may take, including the TLS handshake
+const SESSION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often the idle timer is checked
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Largest message accepted from the device
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// Start casting the dashboard for the configured zone until `shutdown`
+///
+/// `port` is the bridge's HTTP port, used to build the default page URL.
+pub fn start(
+    config: &CastConfig,
+    port: u16,
+    bus: SharedBus,
+    shutdown: CancellationToken,
+) -> Result<()> {
+    if config.device.trim().is_empty() || config.zone_id.trim().is_empty() {
+        return Err(anyhow!("cast device and zone_id are required"));
+    }
+    let device = CastDevice::parse(&config.device)?;
+    let zone_id = config.zone_id.trim().to_string();
+    let url = config.url.clone().filter(|u| !u.trim().is_empty());
+    let idle_timeout = Duration::from_secs(config.idle_secs);
+
+    tracing::info!(
+        "Cast dashboard enabled for {} on {}",
+        zone_id,
+        config.device
+    );
+    let mut rx = bus.subscribe();
+    tokio::spawn(async move {
+        let mut casting = false;
+        let mut idle_since: Option<Instant> = None;
+        let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            let playing = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = idle_check.tick() => {
+                    if casting && idle_since.is_some_and(|t| t.elapsed() >= idle_timeout) {
+                        if let Err(e) = device.stop().await {
+                            tracing::warn!("Cast dashboard stop failed: {}", e);
+                        }
+                        casting = false;
+                        idle_since = None;
+                    }
+                    continue;
+                }
+                result = rx.recv() => match result {
+                    Ok(event) => match zone_playing(&event, &zone_id) {
+                        Some(playing) => playing,
+                        None => continue,
+                    },
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            };
+
+            if !playing {
+                if casting && idle_since.is_none() {
+                    idle_since = Some(Instant::now());
+                }
+                continue;
+            }
+            idle_since = None;
+            if casting {
+                continue;
+            }
+            match device.cast(url.as_deref(), &zone_id, port).await {
+                Ok(true) => casting = true,
+                Ok(false) => tracing::debug!("Cast device busy, dashboard not shown"),
+                Err(e) => tracing::warn!("Cast dashboard launch failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Whether `event` says the zone is playing (`Some(true)`) or not; `None` for
+/// events about other zones or that carry no state
+fn zone_playing(event: &BusEvent, zone_id: &str) -> Option<bool> {
+    match event {
+        BusEvent::ZoneUpdated {
+            zone_id: id, state, ..
+        } if id.as_str() == zone_id => {
+            Some(PlaybackState::from(state.as_str()) == PlaybackState::Playing)
+        }
+        BusEvent::ZoneDiscovered { zone } if zone.zone_id == zone_id => {
+            Some(zone.state == PlaybackState::Playing)
+        }
+        BusEvent::ZoneRemoved { zone_id: id } if id.as_str() == zone_id => Some(false),
+        _ => None,
+    }
+}
+
+/// A Cast device addressed by host and port
+struct CastDevice {
+    host: String,
+    port: u16,
+    tls: TlsConnector,
+}
+
+impl CastDevice {
+    /// `host` or `host:port`
+    fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let (host, port) = match spec.rsplit_once(':') {
+            Some((host, port)) if !host.contains(':') => (
+                host,
+                port.parse()
+                    .with_context(|| format!("invalid cast port in {}", spec))?,
+            ),
+            _ => (spec, CAST_PORT),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            tls: tls_connector()?,
+        })
+    }
+
+    /// Open the dashboard; `Ok(false)` when another app is in use
+    async fn cast(&self, url: Option<&str>, zone_id: &str, bridge_port: u16) -> Result<bool> {
+        tokio::time::timeout(SESSION_TIMEOUT, async {
+            let (mut session, local_ip) = self.connect().await?;
+            let status = session.receiver_status().await?;
+            if status.busy() {
+                return Ok(false);
+            }
+
+            let url = match url {
+                Some(url) => url.to_string(),
+                None => format!(
+                    "http://{}:{}/embed/now_playing?zone_id={}",
+                    local_ip,
+                    bridge_port,
+                    urlencoding::encode(zone_id)
+                ),
+            };
+            let transport_id = match status.dashcast {
+                Some(app) if app.ready => app.transport_id,
+                _ => session.launch_dashcast().await?,
+            };
+            session
+                .send(
+                    &transport_id,
+                    CONNECTION_NAMESPACE,
+                    &json!({ "type": "CONNECT" }),
+                )
+                .await?;
+            session
+                .send(
+                    &transport_id,
+                    DASHCAST_NAMESPACE,
+                    &json!({ "url": url, "force": true, "reload": false, "reload_time": 0 }),
+                )
+                .await?;
+            tracing::info!("Cast dashboard showing {}", url);
+            Ok(true)
+        })
+        .await
+        .map_err(|_| anyhow!("timed out talking to {}", self.host))?
+    }
+
+    /// Close the dashboard if it is still the running app
+    async fn stop(&self) -> Result<()> {
+        tokio::time::timeout(SESSION_TIMEOUT, async {
+            let (mut session, _) = self.connect().await?;
+            let status = session.receiver_status().await?;
+            if let Some(app) = status.dashcast {
+                let request_id = session.next_request_id();
+                session
+                    .send(
+                        RECEIVER_ID,
+                        RECEIVER_NAMESPACE,
+                        &json!({
+                            "type": "STOP",
+                            "sessionId": app.session_id,
+                            "requestId": request_id,
+                        }),
+                    )
+                    .await?;
+                tracing::info!("Cast dashboard closed");
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|_| anyhow!("timed out talking to {}", self.host))?
+    }
+
+    /// Connect to the receiver; also returns our address as the device sees it
+    async fn connect(&self) -> Result<(CastSession, std::net::IpAddr)> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .with_context(|| format!("connecting to {}:{}", self.host, self.port))?;
+        let local_ip = tcp.local_addr()?.ip();
+        let server_name = ServerName::try_from(self.host.clone())?;
+        let stream = self.tls.connect(server_name, tcp).await?;
+
+        let mut session = CastSession {
+            stream: Box::new(stream),
+            request_id: 0,
+        };
+        session
+            .send(
+                RECEIVER_ID,
+                CONNECTION_NAMESPACE,
+                &json!({ "type": "CONNECT" }),
+            )
+            .await?;
+        Ok((session, local_ip))
+    }
+}
+
+trait CastStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> CastStream for T {}
+
+/// One connection to a device's receiver
+struct CastSession {
+    stream: Box<dyn CastStream>,
+    request_id: u64,
+}
+
+impl CastSession {
+    fn next_request_id(&mut self) -> u64 {
+        self.request_id += 1;
+        self.request_id
+    }
+
+    async fn send(&mut self, destination: &str, namespace: &str, payload: &Value) -> Result<()> {
+        let message = CastMessage {
+            source: SENDER_ID.to_string(),
+            destination: destination.to_string(),
+            namespace: namespace.to_string(),
+            payload: payload.to_string(),
+        };
+        let body = message.encode();
+        self.stream
+            .write_all(&(body.len() as u32).to_be_bytes())
+            .await?;
+        self.stream.write_all(&body).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    /// Next non-heartbeat message, answering pings on the way
+    async fn recv(&mut self) -> Result<CastMessage> {
+        loop {
+            let len = self.stream.read_u32().await? as usize;
+            if len > MAX_MESSAGE_LEN {
+                return Err(anyhow!("cast message too large ({} bytes)", len));
+            }
+            let mut body = vec![0; len];
+            self.stream.read_exact(&mut body).await?;
+            let message = CastMessage::decode(&body)?;
+            if message.namespace == HEARTBEAT_NAMESPACE {
+                if message.payload.contains("\"PING\"") {
+                    let source = message.source.clone();
+                    self.send(&source, HEARTBEAT_NAMESPACE, &json!({ "type": "PONG" }))
+                        .await?;
+                }
+                continue;
+            }
+            return Ok(message);
+        }
+    }
+
+    /// Wait for a `RECEIVER_STATUS` message
+    async fn next_status(&mut self) -> Result<ReceiverStatus> {
+        loop {
+            let message = self.recv().await?;
+            if message.namespace != RECEIVER_NAMESPACE {
+                continue;
+            }
+            let payload: Value = serde_json::from_str(&message.payload)?;
+            match payload["type"].as_str() {
+                Some("RECEIVER_STATUS") => return Ok(ReceiverStatus::parse(&payload)),
+                Some("LAUNCH_ERROR") => {
+                    return Err(anyhow!(
+                        "launch failed: {}",
+                        payload["reason"].as_str().unwrap_or("unknown")
+                    ))
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    async fn receiver_status(&mut self) -> Result<ReceiverStatus> {
+        let request_id = self.next_request_id();
+        self.send(
+            RECEIVER_ID,
+            RECEIVER_NAMESPACE,
+            &json!({ "type": "GET_STATUS", "requestId": request_id }),
+        )
+        .await?;
+        self.next_status().await
+    }
+
+    /// Launch DashCast and wait until it listens on its namespace
+    async fn launch_dashcast(&mut self) -> Result<String> {
+        let request_id = self.next_request_id();
+        self.send(
+            RECEIVER_ID,
+            RECEIVER_NAMESPACE,
+            &json!({ "type": "LAUNCH", "appId": DASHCAST_APP_ID, "requestId": request_id }),
+        )
+        .await?;
+        loop {
+            if let Some(app) = self.next_status().await?.dashcast {
+                if app.ready {
+                    return Ok(app.transport_id);
+                }
+            }
+        }
+    }
+}
+
+/// Running DashCast session from a receiver status
+#[derive(Debug, Clone, PartialEq)]
+struct DashCastApp {
+    session_id: String,
+    transport_id: String,
+    /// The app has registered its namespace and accepts URLs
+    ready: bool,
+}
+
+/// The parts of `RECEIVER_STATUS` the dashboard cares about
+#[derive(Debug, Default, PartialEq)]
+struct ReceiverStatus {
+    dashcast: Option<DashCastApp>,
+    /// Some other app (not the idle screen) is running
+    other_app: bool,
+}
+
+impl ReceiverStatus {
+    fn parse(payload: &Value) -> Self {
+        let mut status = Self::default();
+        let apps = payload["status"]["applications"].as_array();
+        for app in apps.into_iter().flatten() {
+            if app["appId"].as_str() == Some(DASHCAST_APP_ID) {
+                let ready = app["namespaces"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .any(|ns| ns["name"].as_str() == Some(DASHCAST_NAMESPACE));
+                status.dashcast = Some(DashCastApp {
+                    session_id: app["sessionId"].as_str().unwrap_or_default().to_string(),
+                    transport_id: app["transportId"].as_str().unwrap_or_default().to_string(),
+                    ready,
+                });
+            } else if !app["isIdleScreen"].as_bool().unwrap_or(false) {
+                status.other_app = true;
+            }
+        }
+        status
+    }
+
+    fn busy(&self) -> bool {
+        self.other_app
+    }
+}
+
+/// `CastMessage` protobuf (Cast v2), always with a UTF-8 JSON payload
+#[derive(Debug, Clone, PartialEq)]
+struct CastMessage {
+    source: String,
+    destination: String,
+    namespace: String,
+    payload: String,
+}
+
+impl CastMessage {
+    fn encode(&self) -> Vec<u8> {
+        fn string_field(out: &mut Vec<u8>, field: u8, value: &str) {
+            out.push((field << 3) | 2);
+            put_varint(out, value.len() as u64);
+            out.extend_from_slice(value.as_bytes());
+        }
+
+        let mut out = Vec::with_capacity(self.payload.len() + 128);
+        // protocol_version = CASTV2_1_0
+        out.extend_from_slice(&[1 << 3, 0]);
+        string_field(&mut out, 2, &self.source);
+        string_field(&mut out, 3, &self.destination);
+        string_field(&mut out, 4, &self.namespace);
+        // payload_type = STRING
+        out.extend_from_slice(&[5 << 3, 0]);
+        string_field(&mut out, 6, &self.payload);
+        out
+    }
+
+    fn decode(mut buf: &[u8]) -> Result<Self> {
+        let mut message = Self {
+            source: String::new(),
+            destination: String::new(),
+            namespace: String::new(),
+            payload: String::new(),
+        };
+        while !buf.is_empty() {
+            let key = get_varint(&mut buf)?;
+            match key & 7 {
+                0 => {
+                    get_varint(&mut buf)?;
+                }
+                2 => {
+                    let len = get_varint(&mut buf)? as usize;
+                    if len > buf.len() {
+                        return Err(anyhow!("truncated cast message"));
+                    }
+                    let (value, rest) = buf.split_at(len);
+                    buf = rest;
+                    let value = String::from_utf8_lossy(value).into_owned();
+                    match key >> 3 {
+                        2 => message.source = value,
+                        3 => message.destination = value,
+                        4 => message.namespace = value,
+                        6 => message.payload = value,
+                        _ => {}
+                    }
+                }
+                wire_type => return Err(anyhow!("unexpected wire type {}", wire_type)),
+            }
+        }
+        Ok(message)
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn get_varint(buf: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = buf
+            .split_first()
+            .ok_or_else(|| anyhow!("truncated varint"))?;
+        *buf = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("varint too long"))
+}
+
+/// Cast devices present self-signed certificates, so the certificate itself
+/// is not checked; handshake signatures still are
+#[derive(Debug)]
+struct AcceptDeviceCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptDeviceCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn tls_connector() -> Result<TlsConnector> {
+    let provider = Arc::new(ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptDeviceCert(provider)))
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::PrefixedZoneId;
+
+    #[test]
+    fn test_cast_message_round_trip() {
+        let message = CastMessage {
+            source: SENDER_ID.to_string(),
+            destination: RECEIVER_ID.to_string(),
+            namespace: RECEIVER_NAMESPACE.to_string(),
+            payload: "x".repeat(300),
+        };
+        let encoded = message.encode();
+        assert_eq!(&encoded[..2], &[0x08, 0x00]);
+        assert_eq!(CastMessage::decode(&encoded).unwrap(), message);
+        assert!(CastMessage::decode(&encoded[..encoded.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_receiver_status() {
+        let idle = json!({ "type": "RECEIVER_STATUS", "status": { "applications": [
+            { "appId": "E8C28D3C", "isIdleScreen": true, "sessionId": "a", "transportId": "a" }
+        ]}});
+        assert_eq!(ReceiverStatus::parse(&idle), ReceiverStatus::default());
+
+        let dashcast = json!({ "type": "RECEIVER_STATUS", "status": { "applications": [{
+            "appId": DASHCAST_APP_ID, "sessionId": "s1", "transportId": "t1",
+            "namespaces": [{ "name": DASHCAST_NAMESPACE }]
+        }]}});
+        let status = ReceiverStatus::parse(&dashcast);
+        assert!(!status.busy());
+        assert_eq!(
+            status.dashcast,
+            Some(DashCastApp {
+                session_id: "s1".to_string(),
+                transport_id: "t1".to_string(),
+                ready: true,
+            })
+        );
+
+        let netflix = json!({ "type": "RECEIVER_STATUS", "status": { "applications": [
+            { "appId": "CA5E8412", "sessionId": "n", "transportId": "n" }
+        ]}});
+        assert!(ReceiverStatus::parse(&netflix).busy());
+    }
+
+    #[test]
+    fn test_zone_playing() {
+        let update = |state: &str| BusEvent::ZoneUpdated {
+            zone_id: PrefixedZoneId::lms("00:11"),
+            display_name: "Kitchen".to_string(),
+            state: state.to_string(),
+        };
+        assert_eq!(zone_playing(&update("playing"), "lms:00:11"), Some(true));
+        assert_eq!(zone_playing(&update("paused"), "lms:00:11"), Some(false));
+        assert_eq!(zone_playing(&update("playing"), "lms:99"), None);
+    }
+
+    #[test]
+    fn test_device_spec() {
+        let device = CastDevice::parse("192.168.1.50").unwrap();
+        assert_eq!((device.host.as_str(), device.port), ("192.168.1.50", 8009));
+        let device = CastDevice::parse("tv.local:8010").unwrap();
+        assert_eq!((device.host.as_str(), device.port), ("tv.local", 8010));
+    }
+}
@@ -0,0 +1,23 @@
+//! Wall-clock timestamps
+//!
+//! Unix time for zone `last_updated` fields, token and webhook bookkeeping,
+//! and anything else persisted or sent to clients. A clock set before 1970
+//! reads as 0 rather than failing.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Milliseconds since the Unix epoch
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Seconds since the Unix epoch
+pub fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -1,13 +1,51 @@
 //! Configuration management
 
 use anyhow::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 pub struct Config {
     #[serde(default = "default_port")]
     pub port: u16,
 
+    /// Port for the optional gRPC server (`grpc` feature); disabled when unset
+    #[serde(default)]
+    pub grpc_port: Option<u16>,
+
+    /// Optional token-authenticated listener for access from outside the LAN
+    /// (see `remote` module); disabled when unset
+    #[serde(default)]
+    pub remote: Option<RemoteConfig>,
+
+    /// Signed outbound event webhooks (see `webhooks` module); disabled when unset
+    #[serde(default)]
+    pub webhooks: Option<WebhooksConfig>,
+
+    /// Home Assistant over MQTT (see `mqtt` module); disabled when unset
+    #[serde(default)]
+    pub mqtt: Option<MqttConfig>,
+
+    /// Now-playing dashboard on a Chromecast (see `cast` module); disabled
+    /// when unset
+    #[serde(default)]
+    pub cast: Option<CastConfig>,
+
+    /// Lyrics lookup for `/zones/{zone_id}/lyrics` (see `lyrics` module);
+    /// disabled when unset
+    #[serde(default)]
+    pub lyrics: Option<LyricsConfig>,
+
+    /// Debounce windows for `zone_active`/`zone_idle` events (see `activity`
+    /// module)
+    #[serde(default)]
+    pub activity: ActivityConfig,
+
+    /// Now-playing text normalization for knob displays and MQTT (see `text`
+    /// module); text is passed through unchanged by default
+    #[serde(default)]
+    pub text: TextConfig,
+
     #[serde(default)]
     pub roon: RoonConfig,
 
@@ -16,16 +54,219 @@ pub struct Config {
 
     #[serde(default)]
     pub lms: Option<LmsConfig>,
+
+    /// Longest an API request may take before failing with `TIMEOUT`
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// Interfaces for UPnP/OpenHome/HQPlayer discovery: comma-separated names
+    /// or IPv4 addresses (e.g. `eth1,macvlan0`); the OS default route when unset
+    #[serde(default)]
+    pub discovery_interfaces: Option<String>,
 }
 
 fn default_port() -> u16 {
     8088
 }
 
-#[derive(Debug, Default, Deserialize)]
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+/// Remote access listener (`[remote]` in the config file, `UHC_REMOTE__*` env vars)
+#[derive(Debug, Deserialize)]
+pub struct RemoteConfig {
+    /// `tailscale` (the host's Tailscale address), an interface name such as
+    /// `wg0`, or an IP address
+    #[serde(default = "default_remote_bind")]
+    pub bind: String,
+    #[serde(default = "default_remote_port")]
+    pub port: u16,
+    /// Bearer token; generated and saved to `remote-access.json` when unset
+    pub token: Option<String>,
+}
+
+fn default_remote_bind() -> String {
+    "tailscale".to_string()
+}
+
+fn default_remote_port() -> u16 {
+    8089
+}
+
+/// Outbound webhooks (`[webhooks]` in the config file, `UHC_WEBHOOKS__*` env vars)
+#[derive(Debug, Deserialize)]
+pub struct WebhooksConfig {
+    /// Comma-separated receiver URLs
+    pub urls: String,
+    /// HMAC signing secret; generated and saved to `webhooks.json` when unset
+    pub secret: Option<String>,
+    /// Comma-separated event types to deliver (e.g. `volume_changed`); all
+    /// except seek and health check events when unset
+    pub events: Option<String>,
+}
+
+/// MQTT broker for Home Assistant (`[mqtt]` in the config file,
+/// `UHC_MQTT__*` env vars)
+#[derive(Debug, Deserialize)]
+pub struct MqttConfig {
+    /// Broker host name or address
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// MQTT client id; `unified-hifi-control-<hostname>` when unset
+    pub client_id: Option<String>,
+    /// Prefix of the bridge's state, command and status topics
+    #[serde(default = "default_mqtt_base_topic")]
+    pub base_topic: String,
+    /// Home Assistant's discovery prefix
+    #[serde(default = "default_mqtt_discovery_prefix")]
+    pub discovery_prefix: String,
+    /// QoS of every publish and subscription: 0, 1 or 2
+    #[serde(default = "default_mqtt_qos")]
+    pub qos: u8,
+    /// Retain zone state topics (discovery, status and availability always are)
+    #[serde(default = "default_mqtt_retain")]
+    pub retain: bool,
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_base_topic() -> String {
+    "unified-hifi".to_string()
+}
+
+fn default_mqtt_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+fn default_mqtt_qos() -> u8 {
+    1
+}
+
+fn default_mqtt_retain() -> bool {
+    true
+}
+
+/// Cast dashboard (`[cast]` in the config file, `UHC_CAST__*` env vars)
+#[derive(Debug, Deserialize)]
+pub struct CastConfig {
+    /// Chromecast or Android TV address, `host` or `host:port`
+    pub device: String,
+    /// Zone whose playback opens and closes the dashboard
+    pub zone_id: String,
+    /// Page to show; the zone's `/embed/now_playing` card when unset
+    pub url: Option<String>,
+    /// Seconds the zone must be idle before the dashboard is closed
+    #[serde(default = "default_cast_idle_secs")]
+    pub idle_secs: u64,
+}
+
+fn default_cast_idle_secs() -> u64 {
+    300
+}
+
+/// Lyrics providers (`[lyrics]` in the config file, `UHC_LYRICS__*` env vars)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LyricsConfig {
+    /// Directory of `.lrc`/`.txt` files named `Artist - Title`, checked first
+    pub dir: Option<std::path::PathBuf>,
+    /// Fall back to LRCLIB (lrclib.net) when no local file matches
+    #[serde(default)]
+    pub lrclib: bool,
+}
+
+/// Zone activity debounce (`[activity]` in the config file,
+/// `UHC_ACTIVITY__*` env vars)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActivityConfig {
+    /// Seconds a zone must play before it is reported active
+    #[serde(default = "default_active_after_secs")]
+    pub active_after_secs: u64,
+    /// Seconds an active zone must be stopped or paused before it is reported idle
+    #[serde(default = "default_idle_after_secs")]
+    pub idle_after_secs: u64,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            active_after_secs: default_active_after_secs(),
+            idle_after_secs: default_idle_after_secs(),
+        }
+    }
+}
+
+fn default_active_after_secs() -> u64 {
+    10
+}
+
+fn default_idle_after_secs() -> u64 {
+    300
+}
+
+/// Text normalization per consumer (`[text.knob]` and `[text.mqtt]` in the
+/// config file, `UHC_TEXT__KNOB__*` / `UHC_TEXT__MQTT__*` env vars)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TextConfig {
+    /// `line1`-`line3` of `/knob/now_playing`
+    #[serde(default)]
+    pub knob: TextNormalization,
+    /// Title, artist and album state topics
+    #[serde(default)]
+    pub mqtt: TextNormalization,
+}
+
+/// How now-playing text is rewritten for one consumer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TextNormalization {
+    /// Rewrite everything as ASCII (Cyrillic, Greek, CJK, ... spelled in
+    /// Latin letters)
+    #[serde(default)]
+    pub transliterate: bool,
+    /// Drop accents from Latin letters (`é` to `e`), leaving other scripts
+    #[serde(default)]
+    pub strip_diacritics: bool,
+    /// Longest string in characters; longer ones are cut with an ellipsis
+    #[serde(default)]
+    pub max_length: Option<usize>,
+}
+
+/// Roon settings (`[roon]` in the config file, `UHC_ROON__*` env vars)
+#[derive(Debug, Deserialize)]
 pub struct RoonConfig {
     pub extension_id: Option<String>,
     pub display_name: Option<String>,
+    /// Seconds before the first reconnect attempt after the Core is lost;
+    /// each failed attempt doubles the wait
+    #[serde(default = "default_roon_reconnect_initial_secs")]
+    pub reconnect_initial_secs: u64,
+    /// Longest wait between reconnect attempts
+    #[serde(default = "default_roon_reconnect_max_secs")]
+    pub reconnect_max_secs: u64,
+}
+
+impl Default for RoonConfig {
+    fn default() -> Self {
+        Self {
+            extension_id: None,
+            display_name: None,
+            reconnect_initial_secs: default_roon_reconnect_initial_secs(),
+            reconnect_max_secs: default_roon_reconnect_max_secs(),
+        }
+    }
+}
+
+fn default_roon_reconnect_initial_secs() -> u64 {
+    1
+}
+
+fn default_roon_reconnect_max_secs() -> u64 {
+    60
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +295,63 @@ fn default_lms_port() -> u16 {
     9000
 }
 
+// Polling settings are shared with the web UI, so both read the same defaults
+// and bounds
+pub use crate::app::api::{PollingSettings, MAX_POLL_INTERVAL_SECS, MIN_POLL_INTERVAL_SECS};
+
+/// Scheduled config backups (stored in app-settings.json under `backup`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours between automatic backups
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u64,
+    /// Number of backups to keep; older ones are deleted after each backup
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+    /// Target directory (defaults to `backups/` in the config subdirectory).
+    /// Point this at a mounted NAS share to survive a dead SD card.
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_backup_interval_hours(),
+            keep: default_backup_keep(),
+            directory: None,
+        }
+    }
+}
+
+impl BackupSettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_hours.clamp(1, 24 * 30) * 3600)
+    }
+
+    pub fn keep(&self) -> usize {
+        self.keep.max(1)
+    }
+
+    pub fn directory(&self) -> std::path::PathBuf {
+        match self.directory.as_deref().map(str::trim) {
+            Some(dir) if !dir.is_empty() => std::path::PathBuf::from(dir),
+            _ => get_config_subdir().join("backups"),
+        }
+    }
+}
+
 /// Subdirectory name for unified-hifi config files
 /// Issue #76: Organize config files into a subdirectory to avoid clutter
 const CONFIG_SUBDIR_NAME: &str = "unified-hifi";
@@ -191,6 +489,10 @@ fn migrate_single_file(source_dir: &std::path::Path, subdir: &std::path::Path, f
                 filename,
                 filename
             );
+            crate::startup::record_migration(
+                source_path.display().to_string(),
+                subdir_path.display().to_string(),
+            );
         }
         Err(e) => {
             // If rename fails (e.g., cross-device), try copy + delete
@@ -212,6 +514,10 @@ fn migrate_single_file(source_dir: &std::path::Path, subdir: &std::path::Path, f
                             filename,
                             filename
                         );
+                        crate::startup::record_migration(
+                            source_path.display().to_string(),
+                            subdir_path.display().to_string(),
+                        );
                     }
                 }
                 Err(_) => {
@@ -357,6 +663,10 @@ fn migrate_roon_config(data_dir: &std::path::Path) {
                             nodejs_path.display(),
                             rust_path.display()
                         );
+                        crate::startup::record_migration(
+                            nodejs_path.display().to_string(),
+                            rust_path.display().to_string(),
+                        );
                     }
                     Err(e) => tracing::warn!("Failed to write Roon state file: {}", e),
                 }
@@ -641,6 +951,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_polling_settings_clamped_to_bounds() {
+        let polling: PollingSettings =
+            serde_json::from_str(r#"{"lms_secs": 0, "upnp_secs": 9999}"#).expect("parse");
+
+        assert_eq!(
+            polling.lms_interval(),
+            Duration::from_secs(MIN_POLL_INTERVAL_SECS)
+        );
+        assert_eq!(
+            polling.upnp_interval(),
+            Duration::from_secs(MAX_POLL_INTERVAL_SECS)
+        );
+        // Missing fields fall back to defaults
+        assert_eq!(polling.openhome_interval(), Duration::from_secs(2));
+        assert_eq!(polling.hqp_status_interval(), Duration::from_secs(5));
+    }
+
     #[test]
     #[serial]
     fn test_read_config_file_with_fallback() {
@@ -680,48 +1008,51 @@ fn migrate_hqp_config(data_dir: &std::path::Path) {
         Err(_) => return,
     };
 
-    // Check if it's Node.js format (single object without web_port field)
-    // Node.js format: {"host":"...", "port":8088, "username":"...", "password":"..."}
-    // Rust format: {"host":"...", "port":4321, "web_port":8088, ...} or array format
     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) {
-        // Skip if already migrated (has web_port or is array format)
-        if value.is_array() {
+        let Some(rust_config) = hqp_config_from_nodejs(&value) else {
             return;
-        }
-        if value.get("web_port").is_some() {
-            return;
-        }
-
-        // It's Node.js single-object format - convert it
-        if let Some(obj) = value.as_object() {
-            let host = obj.get("host").and_then(|v| v.as_str()).unwrap_or("");
-            let nodejs_port = obj.get("port").and_then(|v| v.as_u64()).unwrap_or(8088) as u16;
-            let username = obj.get("username").and_then(|v| v.as_str());
-            let password = obj.get("password").and_then(|v| v.as_str());
-
-            // In Node.js, "port" is the web UI port (8088)
-            // In Rust, "port" is the native protocol port (4321), "web_port" is web UI
-            let rust_config = serde_json::json!([{
-                "name": "default",
-                "host": host,
-                "port": 4321,  // Native protocol port
-                "web_port": nodejs_port,  // Node.js port becomes web_port
-                "username": username,
-                "password": password
-            }]);
-
-            if let Ok(json) = serde_json::to_string_pretty(&rust_config) {
-                match std::fs::write(&hqp_path, &json) {
-                    Ok(()) => {
-                        tracing::info!(
-                            "Migrated HQPlayer config from Node.js format (port {} → web_port {})",
-                            nodejs_port,
-                            nodejs_port
-                        );
-                    }
-                    Err(e) => tracing::warn!("Failed to write migrated HQP config: {}", e),
+        };
+        if let Ok(json) = serde_json::to_string_pretty(&rust_config) {
+            match std::fs::write(&hqp_path, &json) {
+                Ok(()) => {
+                    tracing::info!(
+                        "Migrated HQPlayer config from Node.js format (port → web_port {})",
+                        rust_config[0]["web_port"]
+                    );
+                    let path = hqp_path.display().to_string();
+                    crate::startup::record_migration(path.clone(), path);
                 }
+                Err(e) => tracing::warn!("Failed to write migrated HQP config: {}", e),
             }
         }
     }
 }
+
+/// Convert a Node.js HQPlayer config to the Rust instance list; `None` if it
+/// is already in Rust format
+///
+/// Node.js format: `{"host":"...", "port":8088, "username":"...", "password":"..."}`
+/// Rust format: `[{"host":"...", "port":4321, "web_port":8088, ...}]` (or a
+/// single object with `web_port`)
+pub fn hqp_config_from_nodejs(value: &serde_json::Value) -> Option<serde_json::Value> {
+    // Already migrated (array format or has web_port)
+    if value.is_array() || value.get("web_port").is_some() {
+        return None;
+    }
+    let obj = value.as_object()?;
+    let host = obj.get("host").and_then(|v| v.as_str()).unwrap_or("");
+    let nodejs_port = obj.get("port").and_then(|v| v.as_u64()).unwrap_or(8088) as u16;
+    let username = obj.get("username").and_then(|v| v.as_str());
+    let password = obj.get("password").and_then(|v| v.as_str());
+
+    // In Node.js, "port" is the web UI port (8088)
+    // In Rust, "port" is the native protocol port (4321), "web_port" is web UI
+    Some(serde_json::json!([{
+        "name": "default",
+        "host": host,
+        "port": 4321,  // Native protocol port
+        "web_port": nodejs_port,  // Node.js port becomes web_port
+        "username": username,
+        "password": password
+    }]))
+}
@@ -2,12 +2,23 @@
 //!
 //! The coordinator serves as a registry of all available adapters and manages their lifecycle.
 //! It tracks which adapters are enabled and handles starting/stopping them uniformly.
+//!
+//! It also tracks in-flight commands per adapter so a stop can drain them first:
+//! handlers hold an [`InFlightCommand`] while talking to a backend, and
+//! [`AdapterCoordinator::drain_and_stop`] rejects new commands, waits for the
+//! held ones (up to a deadline), then stops the adapter.
+//!
+//! Starts, stops and restarts that go through the coordinator are recorded per
+//! adapter (running flag, last start error, restart count) for `GET /api/adapters`.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
 use std::time::Duration;
 
 use anyhow::Result;
-use tokio::sync::RwLock;
+use serde::Serialize;
+use tokio::sync::{Notify, RwLock};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
@@ -15,12 +26,57 @@ use tracing::{debug, error, info, warn};
 use crate::adapters::Startable;
 use crate::api::AdapterSettings;
 use crate::bus::{BusEvent, SharedBus};
+use crate::startup::{self, AdapterOutcome};
 use std::sync::Arc;
 
 /// All available adapters in the system.
 /// This is the single source of truth for what adapters exist.
 /// Note: "lms-cli" is a companion to "lms" and shares its enabled state.
-pub const AVAILABLE_ADAPTERS: &[&str] = &["roon", "lms", "lms-cli", "openhome", "upnp"];
+pub const AVAILABLE_ADAPTERS: &[&str] = &[
+    "roon", "lms", "lms-cli", "openhome", "upnp", "airplay", "snapcast", "heos", "kodi", "spotify",
+    "http", "plexamp",
+];
+
+/// How long a stop waits for in-flight commands before stopping anyway
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// In-flight command tracking for one adapter
+#[derive(Default)]
+struct CommandGate {
+    /// Set while the adapter is draining; new commands are rejected
+    stopping: AtomicBool,
+    in_flight: AtomicUsize,
+    /// Notified when `in_flight` drops to zero
+    idle: Notify,
+}
+
+/// A command was rejected because its adapter is being stopped
+#[derive(Debug, Clone)]
+pub struct AdapterStopping {
+    pub adapter: String,
+}
+
+impl std::fmt::Display for AdapterStopping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} adapter is stopping", self.adapter)
+    }
+}
+
+impl std::error::Error for AdapterStopping {}
+
+/// Held by a handler for the duration of a backend command (see
+/// [`AdapterCoordinator::begin_command`]); dropping it ends the command
+pub struct InFlightCommand {
+    gate: Arc<CommandGate>,
+}
+
+impl Drop for InFlightCommand {
+    fn drop(&mut self) {
+        if self.gate.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.gate.idle.notify_waiters();
+        }
+    }
+}
 
 /// Registered adapter with its spawn function
 struct RegisteredAdapter {
@@ -33,6 +89,12 @@ struct RegisteredAdapter {
     handle: Option<JoinHandle<()>>,
     /// Cancellation token for this adapter
     cancel: CancellationToken,
+    /// Started through [`AdapterCoordinator::start`] and not stopped since
+    started: bool,
+    /// Error from the most recent failed start, cleared on success
+    last_error: Option<String>,
+    /// Restarts requested through [`AdapterCoordinator::restart`]
+    restart_count: u32,
 }
 
 /// AdapterCoordinator manages adapter lifecycle:
@@ -46,6 +108,8 @@ pub struct AdapterCoordinator {
     shutdown: CancellationToken,
     /// Timeout for shutdown acknowledgments
     shutdown_timeout: Duration,
+    /// In-flight command tracking, by adapter prefix
+    gates: Mutex<HashMap<String, Arc<CommandGate>>>,
 }
 
 impl AdapterCoordinator {
@@ -55,6 +119,7 @@ impl AdapterCoordinator {
             bus,
             shutdown: CancellationToken::new(),
             shutdown_timeout: Duration::from_secs(5),
+            gates: Mutex::new(HashMap::new()),
         }
     }
 
@@ -65,6 +130,7 @@ impl AdapterCoordinator {
             bus,
             shutdown: CancellationToken::new(),
             shutdown_timeout: timeout,
+            gates: Mutex::new(HashMap::new()),
         }
     }
 
@@ -79,6 +145,13 @@ impl AdapterCoordinator {
                 "lms-cli" => settings.lms,
                 "openhome" => settings.openhome,
                 "upnp" => settings.upnp,
+                "airplay" => settings.airplay,
+                "snapcast" => settings.snapcast,
+                "heos" => settings.heos,
+                "kodi" => settings.kodi,
+                "spotify" => settings.spotify,
+                "http" => settings.http,
+                "plexamp" => settings.plexamp,
                 _ => false,
             };
             self.register(name, enabled).await;
@@ -92,22 +165,130 @@ impl AdapterCoordinator {
 
     /// Start all enabled adapters from the provided list.
     /// This is the single codepath for starting adapters.
+    /// Adapters start concurrently so one slow backend doesn't delay the others.
+    /// Each outcome goes into the startup report.
     pub async fn start_all_enabled(&self, adapters: &[Arc<dyn Startable>]) {
-        for adapter in adapters {
+        let starts = adapters.iter().map(|adapter| async move {
             let name = adapter.name();
             if !self.is_enabled(name).await {
                 debug!("Adapter {} is disabled, skipping", name);
-                continue;
+                startup::record_adapter(name, AdapterOutcome::Disabled, None);
+                return;
             }
             if !adapter.can_start().await {
                 debug!("Adapter {} cannot start (not configured?), skipping", name);
-                continue;
+                startup::record_adapter(name, AdapterOutcome::NotConfigured, None);
+                return;
             }
-            match adapter.start().await {
-                Ok(()) => info!("Started adapter: {}", name),
-                Err(e) => warn!("Failed to start adapter {}: {}", name, e),
+            match self.start(adapter.as_ref()).await {
+                Ok(()) => {
+                    info!("Started adapter: {}", name);
+                    startup::record_adapter(name, AdapterOutcome::Started, None);
+                }
+                Err(e) => {
+                    warn!("Failed to start adapter {}: {}", name, e);
+                    startup::record_adapter(name, AdapterOutcome::Failed, Some(e.to_string()));
+                }
             }
+        });
+        futures::future::join_all(starts).await;
+        startup::finish_adapters();
+    }
+
+    /// Start an adapter, recording the outcome for [`adapter_status`](Self::adapter_status)
+    pub async fn start(&self, adapter: &dyn Startable) -> Result<()> {
+        let result = adapter.start().await;
+        let mut adapters = self.adapters.write().await;
+        if let Some(registered) = adapters.get_mut(adapter.name()) {
+            registered.started = result.is_ok();
+            registered.last_error = result.as_ref().err().map(|e| e.to_string());
+        }
+        result
+    }
+
+    /// Drain and stop an adapter, then start it again.
+    ///
+    /// Counts towards the adapter's `restart_count` whether or not the new start
+    /// succeeds. An adapter that can't start (not configured) is left stopped.
+    pub async fn restart(&self, adapter: &dyn Startable) -> Result<()> {
+        let name = adapter.name();
+        info!("Restarting adapter: {}", name);
+        self.drain_and_stop(adapter).await;
+
+        if let Some(registered) = self.adapters.write().await.get_mut(name) {
+            registered.restart_count = registered.restart_count.saturating_add(1);
+        }
+
+        if !adapter.can_start().await {
+            let err = anyhow::anyhow!("{} adapter is not configured", name);
+            if let Some(registered) = self.adapters.write().await.get_mut(name) {
+                registered.last_error = Some(err.to_string());
+            }
+            return Err(err);
+        }
+        self.start(adapter).await
+    }
+
+    fn gate(&self, prefix: &str) -> Arc<CommandGate> {
+        self.gates
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(prefix.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Register a command about to be sent to `prefix`'s backend.
+    ///
+    /// Fails while the adapter is draining for a stop. Hold the returned guard
+    /// until the command has completed.
+    pub fn begin_command(&self, prefix: &str) -> Result<InFlightCommand, AdapterStopping> {
+        let gate = self.gate(prefix);
+        let stopping = || AdapterStopping {
+            adapter: prefix.to_string(),
+        };
+        if gate.stopping.load(Ordering::SeqCst) {
+            return Err(stopping());
+        }
+        gate.in_flight.fetch_add(1, Ordering::SeqCst);
+        let command = InFlightCommand { gate };
+        // Re-check after counting ourselves so a concurrent drain either sees
+        // this command or this command sees the drain
+        if command.gate.stopping.load(Ordering::SeqCst) {
+            return Err(stopping());
+        }
+        Ok(command)
+    }
+
+    /// Number of commands currently in flight for `prefix`
+    pub fn in_flight(&self, prefix: &str) -> usize {
+        self.gate(prefix).in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Stop an adapter after draining its in-flight commands.
+    ///
+    /// New commands are rejected from the moment this is called; the adapter is
+    /// stopped once in-flight commands finish or `DRAIN_TIMEOUT` passes.
+    pub async fn drain_and_stop(&self, adapter: &dyn Startable) {
+        let name = adapter.name();
+        let gate = self.gate(name);
+        gate.stopping.store(true, Ordering::SeqCst);
+
+        if !wait_for_idle(&gate, DRAIN_TIMEOUT).await {
+            warn!(
+                "Adapter {} still had {} command(s) in flight after {:?}, stopping anyway",
+                name,
+                gate.in_flight.load(Ordering::SeqCst),
+                DRAIN_TIMEOUT
+            );
         }
+
+        adapter.stop().await;
+        if let Some(registered) = self.adapters.write().await.get_mut(name) {
+            registered.started = false;
+        }
+        gate.stopping.store(false, Ordering::SeqCst);
+        debug!("Drained and stopped adapter: {}", name);
     }
 
     /// Stop all adapters from the provided list.
@@ -128,6 +309,9 @@ impl AdapterCoordinator {
                 enabled,
                 handle: None,
                 cancel: self.shutdown.child_token(),
+                started: false,
+                last_error: None,
+                restart_count: 0,
             },
         );
         debug!("Registered adapter: {} (enabled: {})", prefix, enabled);
@@ -353,7 +537,9 @@ impl AdapterCoordinator {
                     AdapterStatus {
                         prefix: prefix.clone(),
                         enabled: adapter.enabled,
-                        running: adapter.handle.is_some(),
+                        running: adapter.started || adapter.handle.is_some(),
+                        last_error: adapter.last_error.clone(),
+                        restart_count: adapter.restart_count,
                     },
                 )
             })
@@ -362,11 +548,33 @@ impl AdapterCoordinator {
 }
 
 /// Status information for an adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AdapterStatus {
+    #[serde(rename = "name")]
     pub prefix: String,
     pub enabled: bool,
     pub running: bool,
+    pub last_error: Option<String>,
+    pub restart_count: u32,
+}
+
+/// Wait until no commands are in flight, up to `timeout`. Returns whether it drained.
+async fn wait_for_idle(gate: &CommandGate, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        // Register for the notification before checking, so a command finishing
+        // in between isn't missed
+        let idle = gate.idle.notified();
+        tokio::pin!(idle);
+        idle.as_mut().enable();
+
+        if gate.in_flight.load(Ordering::SeqCst) == 0 {
+            return true;
+        }
+        if tokio::time::timeout_at(deadline, idle).await.is_err() {
+            return false;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -501,4 +709,92 @@ mod tests {
         assert!(!status["a"].running);
         assert!(!status["b"].enabled);
     }
+
+    struct MockStartable {
+        stopped: AtomicBool,
+        fail_start: AtomicBool,
+    }
+
+    impl MockStartable {
+        fn new() -> Self {
+            Self {
+                stopped: AtomicBool::new(false),
+                fail_start: AtomicBool::new(false),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Startable for MockStartable {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        async fn start(&self) -> Result<()> {
+            if self.fail_start.load(Ordering::SeqCst) {
+                anyhow::bail!("connection refused");
+            }
+            self.stopped.store(false, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn stop(&self) {
+            self.stopped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_in_flight_commands() {
+        let coord = Arc::new(AdapterCoordinator::new(create_bus()));
+        let adapter = Arc::new(MockStartable::new());
+
+        let command = coord.begin_command("mock").unwrap();
+        assert_eq!(coord.in_flight("mock"), 1);
+
+        let drain = {
+            let coord = coord.clone();
+            let adapter = adapter.clone();
+            tokio::spawn(async move { coord.drain_and_stop(adapter.as_ref()).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // New commands are rejected while draining; the held one keeps the adapter running
+        assert!(coord.begin_command("mock").is_err());
+        assert!(!adapter.stopped.load(Ordering::SeqCst));
+
+        drop(command);
+        drain.await.unwrap();
+        assert!(adapter.stopped.load(Ordering::SeqCst));
+        assert_eq!(coord.in_flight("mock"), 0);
+
+        // Accepting commands again once stopped (the adapter reports its own state)
+        assert!(coord.begin_command("mock").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_restart_tracks_count_and_last_error() {
+        let coord = AdapterCoordinator::new(create_bus());
+        coord.register("mock", true).await;
+        let adapter = MockStartable::new();
+
+        coord.start(&adapter).await.unwrap();
+        let status = &coord.adapter_status().await["mock"];
+        assert!(status.running);
+        assert_eq!(status.restart_count, 0);
+
+        adapter.fail_start.store(true, Ordering::SeqCst);
+        assert!(coord.restart(&adapter).await.is_err());
+        let status = &coord.adapter_status().await["mock"];
+        assert!(!status.running);
+        assert_eq!(status.restart_count, 1);
+        assert_eq!(status.last_error.as_deref(), Some("connection refused"));
+
+        // A successful restart clears the error
+        adapter.fail_start.store(false, Ordering::SeqCst);
+        coord.restart(&adapter).await.unwrap();
+        let status = &coord.adapter_status().await["mock"];
+        assert!(status.running);
+        assert_eq!(status.restart_count, 2);
+        assert!(status.last_error.is_none());
+    }
 }
@@ -0,0 +1,155 @@
+//! Demo mode (`--demo`): fake backends for exercising the bridge without hardware
+//!
+//! Starts the mock LMS and HQPlayer servers in-process, points the adapters at them,
+//! and publishes a few fake Roon zones on the bus. The UI, knob protocol and MCP
+//! then behave exactly as they would against real backends, which makes demo mode
+//! handy for screenshots and client development.
+//!
+//! Configuration is redirected to a throwaway directory (see [`demo_config_dir`]) so
+//! a real installation's settings are never touched.
+//!
+//! Roon zones are display-only: the real Roon adapter speaks the proprietary
+//! SOOD/WebSocket protocol, which [`MockRoonCore`] does not implement.
+
+use std::path::PathBuf;
+
+use crate::bus::{
+    BusEvent, NowPlaying, PlaybackState, SharedBus, VolumeControl, VolumeScale, Zone,
+};
+use crate::mock_servers::roon::MockZone;
+use crate::mock_servers::{MockHqpServer, MockLmsServer, MockRoonCore};
+
+/// Config directory used while running in demo mode
+pub fn demo_config_dir() -> PathBuf {
+    std::env::temp_dir().join("unified-hifi-control-demo")
+}
+
+/// In-process fake backends for demo mode
+pub struct DemoBackends {
+    pub lms: MockLmsServer,
+    pub hqp: MockHqpServer,
+    pub roon: MockRoonCore,
+}
+
+impl DemoBackends {
+    /// Start the mock servers and seed them with a few zones
+    pub async fn start() -> std::io::Result<Self> {
+        let lms = MockLmsServer::start().await?;
+        lms.add_player("00:04:20:de:00:01", "Living Room").await;
+        lms.set_mode("00:04:20:de:00:01", "play").await;
+        lms.set_volume("00:04:20:de:00:01", 42).await;
+        lms.set_now_playing(
+            "00:04:20:de:00:01",
+            "So What",
+            "Miles Davis",
+            "Kind of Blue",
+        )
+        .await;
+        lms.add_player("00:04:20:de:00:02", "Kitchen").await;
+        lms.set_volume("00:04:20:de:00:02", 25).await;
+
+        let hqp = MockHqpServer::start().await?;
+        hqp.set_state(2).await;
+        hqp.set_volume(-18).await;
+        hqp.set_now_playing("Spiegel im Spiegel", "Arvo Pärt", "Alina", 612)
+            .await;
+
+        let roon = MockRoonCore::new();
+        roon.add_zone("demo-study", "Study").await;
+        roon.set_zone_state("demo-study", "playing").await;
+        roon.set_now_playing("demo-study", "Teardrop", "Massive Attack", "Mezzanine")
+            .await;
+        roon.add_zone("demo-bedroom", "Bedroom").await;
+        roon.set_zone_volume("demo-bedroom", 30.0).await;
+
+        Ok(Self { lms, hqp, roon })
+    }
+
+    /// Stop the mock servers
+    pub async fn stop(self) {
+        self.lms.stop().await;
+        self.hqp.stop().await;
+    }
+}
+
+/// Publish the fake Roon zones so the aggregator picks them up
+pub async fn publish_roon_zones(roon: &MockRoonCore, bus: &SharedBus) {
+    for zone in roon.get_zones().await {
+        bus.publish(BusEvent::ZoneDiscovered {
+            zone: mock_roon_zone_to_bus_zone(&zone),
+        });
+    }
+}
+
+/// Convert a mock Roon zone to a bus Zone (mirrors the real Roon adapter's conversion)
+fn mock_roon_zone_to_bus_zone(zone: &MockZone) -> Zone {
+    let volume_control = zone.outputs.first().and_then(|o| {
+        o.volume.as_ref().map(|v| VolumeControl {
+            value: v.value,
+            min: v.min,
+            max: v.max,
+            step: 1.0,
+            is_muted: v.is_muted,
+            scale: VolumeScale::Percentage,
+            output_id: Some(format!("roon:{}", o.output_id)),
+        })
+    });
+
+    let now_playing = zone.now_playing.as_ref().map(|np| NowPlaying {
+        title: np.title.clone(),
+        artist: np.artist.clone(),
+        album: np.album.clone(),
+        image_key: None,
+        seek_position: np.seek_position.map(|p| p as f64),
+        duration: np.length.map(|l| l as f64),
+        metadata: None,
+    });
+
+    Zone {
+        zone_id: format!("roon:{}", zone.zone_id),
+        zone_name: zone.display_name.clone(),
+        state: PlaybackState::from(zone.state.as_str()),
+        volume_control,
+        now_playing,
+        source: "roon".to_string(),
+        // No Roon core behind these zones, so commands would fail
+        is_controllable: false,
+        is_seekable: false,
+        capabilities: Default::default(),
+        volume_leveling: None,
+        last_updated: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        is_play_allowed: false,
+        is_pause_allowed: false,
+        is_next_allowed: false,
+        is_previous_allowed: false,
+        metadata: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_demo_publishes_roon_zones() {
+        let demo = DemoBackends::start().await.unwrap();
+        let bus = crate::bus::create_bus();
+        let mut rx = bus.subscribe();
+
+        publish_roon_zones(&demo.roon, &bus).await;
+
+        let mut zone_ids = Vec::new();
+        while let Ok(BusEvent::ZoneDiscovered { zone }) = rx.try_recv() {
+            assert_eq!(zone.source, "roon");
+            assert!(!zone.is_controllable);
+            zone_ids.push(zone.zone_id);
+        }
+        zone_ids.sort();
+        assert_eq!(zone_ids, vec!["roon:demo-bedroom", "roon:demo-study"]);
+
+        demo.stop().await;
+    }
+}
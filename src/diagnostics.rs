@@ -0,0 +1,256 @@
+//! Network connectivity diagnostics
+//!
+//! Backs `POST /debug/connectivity`: TCP reachability of configured backends plus
+//! SSDP and mDNS multicast probes, to triage "nothing is discovered" reports.
+//! The SSDP probe goes out on the configured discovery interfaces, and the
+//! report lists the host's interfaces so a wrong selection is easy to spot.
+
+use serde::Serialize;
+
+use crate::adapters::discovery::{self, NetworkInterface};
+use std::collections::HashSet;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::time::{Duration, Instant};
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Timeout for each TCP connect check
+const TCP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long to collect replies to a multicast probe
+const MULTICAST_LISTEN: Duration = Duration::from_secs(2);
+
+/// SSDP multicast group (UPnP/OpenHome discovery)
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// mDNS multicast group (knob discovery, Roon, etc.)
+const MDNS_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(224, 0, 0, 251), 5353);
+
+/// Result of a single connectivity check
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityCheck {
+    /// Check identifier (e.g. "hqplayer:default", "lms_cli", "ssdp_multicast")
+    pub check: String,
+    /// What was probed (host:port or multicast group)
+    pub target: String,
+    pub ok: bool,
+    /// Time to connect (TCP) or to the first reply (multicast)
+    pub latency_ms: Option<u64>,
+    /// Human-readable outcome
+    pub detail: String,
+}
+
+/// Result of a full diagnostics run
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityReport {
+    /// True if every check passed
+    pub ok: bool,
+    pub checks: Vec<ConnectivityCheck>,
+    /// Detected interface addresses; `selected` ones are used for discovery
+    pub interfaces: Vec<NetworkInterface>,
+}
+
+/// A TCP endpoint to probe
+#[derive(Debug, Clone)]
+pub struct TcpTarget {
+    pub check: String,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Run TCP checks against the given targets plus SSDP/mDNS multicast probes.
+/// All checks run concurrently, so the whole run takes a few seconds at most.
+pub async fn run_checks(targets: Vec<TcpTarget>) -> ConnectivityReport {
+    let tcp = futures::future::join_all(targets.into_iter().map(check_tcp));
+    let (mut checks, ssdp, mdns) = tokio::join!(tcp, check_ssdp(), check_mdns());
+    checks.push(ssdp);
+    checks.push(mdns);
+
+    ConnectivityReport {
+        ok: checks.iter().all(|c| c.ok),
+        checks,
+        interfaces: discovery::list_interfaces(),
+    }
+}
+
+/// Check that a TCP connection can be opened to the target
+pub async fn check_tcp(target: TcpTarget) -> ConnectivityCheck {
+    let addr = format!("{}:{}", target.host, target.port);
+    let started = Instant::now();
+
+    let (ok, detail) = match tokio::time::timeout(TCP_TIMEOUT, TcpStream::connect(&addr)).await {
+        Ok(Ok(_)) => (true, "Connected".to_string()),
+        Ok(Err(e)) => (false, format!("Connection failed: {}", e)),
+        Err(_) => (false, format!("Timed out after {}s", TCP_TIMEOUT.as_secs())),
+    };
+
+    ConnectivityCheck {
+        check: target.check,
+        target: addr,
+        ok,
+        latency_ms: ok.then(|| started.elapsed().as_millis() as u64),
+        detail,
+    }
+}
+
+/// Send an SSDP M-SEARCH and count responding devices
+async fn check_ssdp() -> ConnectivityCheck {
+    let request = "M-SEARCH * HTTP/1.1\r\n\
+                   HOST: 239.255.255.250:1900\r\n\
+                   MAN: \"ssdp:discover\"\r\n\
+                   MX: 1\r\n\
+                   ST: ssdp:all\r\n\r\n";
+    probe_multicast(
+        "ssdp_multicast",
+        SSDP_ADDR,
+        request.as_bytes(),
+        "UPnP device(s)",
+        discovery::bind_addrs(),
+    )
+    .await
+}
+
+/// Send an mDNS service enumeration query and count responders
+async fn check_mdns() -> ConnectivityCheck {
+    probe_multicast(
+        "mdns_multicast",
+        MDNS_ADDR,
+        &mdns_services_query(),
+        "mDNS responder(s)",
+        vec![Ipv4Addr::UNSPECIFIED],
+    )
+    .await
+}
+
+/// DNS query for `_services._dns-sd._udp.local` PTR (enumerates advertised service types).
+/// Sent from an ephemeral port, so responders answer us directly via unicast.
+fn mdns_services_query() -> Vec<u8> {
+    // Header: id=0, flags=0, 1 question, no answer/authority/additional records
+    let mut packet = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in ["_services", "_dns-sd", "_udp", "local"] {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    // QTYPE=PTR, QCLASS=IN
+    packet.extend_from_slice(&[0, 12, 0, 1]);
+    packet
+}
+
+/// Send a probe to a multicast group from each bind address and collect replies
+/// for [`MULTICAST_LISTEN`]
+async fn probe_multicast(
+    check: &str,
+    group: SocketAddrV4,
+    payload: &[u8],
+    what: &str,
+    bind: Vec<Ipv4Addr>,
+) -> ConnectivityCheck {
+    let failed = |detail: String| ConnectivityCheck {
+        check: check.to_string(),
+        target: group.to_string(),
+        ok: false,
+        latency_ms: None,
+        detail,
+    };
+    let via = if bind.iter().all(|ip| ip.is_unspecified()) {
+        String::new()
+    } else {
+        let addrs: Vec<String> = bind.iter().map(ToString::to_string).collect();
+        format!(" via {}", addrs.join(", "))
+    };
+
+    let mut sockets = Vec::new();
+    for ip in bind {
+        let socket = match UdpSocket::bind((ip, 0)).await {
+            Ok(socket) => socket,
+            Err(e) => return failed(format!("Cannot bind UDP socket on {}: {}", ip, e)),
+        };
+        if let Err(e) = socket.set_multicast_ttl_v4(2) {
+            tracing::debug!("Diagnostics: failed to set multicast TTL: {}", e);
+        }
+        sockets.push(socket);
+    }
+
+    let started = Instant::now();
+    for socket in &sockets {
+        if let Err(e) = socket.send_to(payload, group).await {
+            return failed(format!(
+                "Multicast send{} failed: {} (no multicast route?)",
+                via, e
+            ));
+        }
+    }
+
+    let deadline = tokio::time::Instant::now() + MULTICAST_LISTEN;
+    let replies = futures::future::join_all(sockets.iter().map(|socket| async move {
+        let mut replies = Vec::new();
+        let mut buf = [0u8; 2048];
+        while let Ok(Ok((_, from))) =
+            tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+        {
+            replies.push((from.ip(), started.elapsed()));
+        }
+        replies
+    }))
+    .await;
+
+    let responders: HashSet<_> = replies.iter().flatten().map(|(ip, _)| *ip).collect();
+    let first_reply = replies.iter().flatten().map(|(_, at)| *at).min();
+
+    if responders.is_empty() {
+        return failed(format!(
+            "No {} answered within {}s{}. Multicast may be blocked (Docker bridge networking, \
+             Wi-Fi client isolation, or a firewall), or discovery is using the wrong interface",
+            what,
+            MULTICAST_LISTEN.as_secs(),
+            via
+        ));
+    }
+
+    ConnectivityCheck {
+        check: check.to_string(),
+        target: group.to_string(),
+        ok: true,
+        latency_ms: first_reply.map(|d| d.as_millis() as u64),
+        detail: format!("{} {} answered{}", responders.len(), what, via),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mdns_query_encodes_services_ptr() {
+        let packet = mdns_services_query();
+        assert_eq!(&packet[4..6], &[0, 1], "one question");
+        assert_eq!(packet[12], 9);
+        assert_eq!(&packet[13..22], b"_services");
+        assert_eq!(&packet[packet.len() - 4..], &[0, 12, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_check_reports_reachable_and_unreachable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ok = check_tcp(TcpTarget {
+            check: "test".to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+        })
+        .await;
+        assert!(ok.ok);
+        assert!(ok.latency_ms.is_some());
+
+        drop(listener);
+        let failed = check_tcp(TcpTarget {
+            check: "test".to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+        })
+        .await;
+        assert!(!failed.ok);
+        assert!(failed.latency_ms.is_none());
+    }
+}
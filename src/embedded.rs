@@ -24,15 +24,18 @@
 
 use axum::{
     body::Body,
-    http::{header, Request, StatusCode},
+    http::{header, HeaderMap, Request, StatusCode},
     response::Response,
 };
 use futures::future::BoxFuture;
 use http_body_util::BodyExt;
-use rust_embed::Embed;
+use rust_embed::{Embed, EmbeddedFile};
 use std::task::{Context, Poll};
+use std::time::{Duration, UNIX_EPOCH};
 use tower::{Layer, Service};
 
+use crate::http_cache::Validators;
+
 /// Embedded assets from dx build output.
 /// The `allow_missing = true` attribute lets this compile even when the folder doesn't exist
 /// (e.g., during development without running dx build first).
@@ -58,24 +61,19 @@ pub fn get_index_html() -> Option<String> {
 /// This handles WASM, JS, and other hashed assets.
 pub async fn serve_embedded_asset(
     axum::extract::Path(path): axum::extract::Path<String>,
+    headers: HeaderMap,
 ) -> Response<Body> {
     // Files are in assets/ subfolder
     let asset_path = format!("assets/{}", path);
 
     match PublicAssets::get(&asset_path) {
-        Some(content) => {
-            let mime = mime_guess::from_path(&asset_path)
-                .first_or_octet_stream()
-                .to_string();
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, mime)
-                // Immutable cache for hashed assets
-                .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
-                .body(Body::from(content.data.into_owned()))
-                .unwrap_or_else(|_| Response::new(Body::empty()))
-        }
+        // Immutable cache for hashed assets
+        Some(content) => embedded_file_response(
+            &asset_path,
+            content,
+            &headers,
+            "public, max-age=31536000, immutable",
+        ),
         None => Response::builder()
             .status(StatusCode::NOT_FOUND)
             .body(Body::from("Asset not found"))
@@ -83,6 +81,26 @@ pub async fn serve_embedded_asset(
     }
 }
 
+/// Build a conditional response for an embedded file, using the content hash and
+/// build-time modification date recorded by rust-embed as validators.
+fn embedded_file_response(
+    path: &str,
+    content: EmbeddedFile,
+    headers: &HeaderMap,
+    cache_control: &'static str,
+) -> Response<Body> {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let validators = Validators {
+        etag: format!("\"{}\"", hex::encode(&content.metadata.sha256_hash()[..8])),
+        last_modified: content
+            .metadata
+            .last_modified()
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+        cache_control,
+    };
+    validators.respond(headers, mime.as_ref(), content.data.into_owned())
+}
+
 /// Axum handler to serve the embedded index.html (SPA shell with WASM script tags).
 /// This is used as a fallback for client-side routing.
 pub async fn serve_index_html() -> Response<Body> {
@@ -102,20 +120,10 @@ pub async fn serve_index_html() -> Response<Body> {
 /// Axum handler to serve other embedded static files (favicon, CSS, images).
 pub async fn serve_static_file(
     axum::extract::Path(path): axum::extract::Path<String>,
+    headers: HeaderMap,
 ) -> Response<Body> {
     match PublicAssets::get(&path) {
-        Some(content) => {
-            let mime = mime_guess::from_path(&path)
-                .first_or_octet_stream()
-                .to_string();
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, mime)
-                .header(header::CACHE_CONTROL, "public, max-age=3600")
-                .body(Body::from(content.data.into_owned()))
-                .unwrap_or_else(|_| Response::new(Body::empty()))
-        }
+        Some(content) => embedded_file_response(&path, content, &headers, "public, max-age=3600"),
         None => Response::builder()
             .status(StatusCode::NOT_FOUND)
             .body(Body::from("File not found"))
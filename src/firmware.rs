@@ -27,6 +27,23 @@ pub struct FirmwareVersion {
     pub release_url: Option<String>,
 }
 
+/// Release channel of a version: its pre-release tag (`2.4.0-beta.1` is
+/// `beta`), or `stable`
+pub fn release_channel(version: &str) -> String {
+    let tag: String = version
+        .split_once('-')
+        .map(|(_, pre)| pre)
+        .unwrap_or_default()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if tag.is_empty() {
+        "stable".to_string()
+    } else {
+        tag.to_ascii_lowercase()
+    }
+}
+
 /// GitHub release asset
 #[derive(Debug, Deserialize)]
 struct GitHubAsset {
@@ -183,7 +200,7 @@ impl FirmwareService {
     }
 
     /// Compare versions (returns true if remote > local)
-    fn is_newer_version(remote: &str, local: &str) -> bool {
+    pub fn is_newer_version(remote: &str, local: &str) -> bool {
         let parse = |v: &str| -> Vec<u32> {
             v.trim_start_matches('v')
                 .split('-')
@@ -0,0 +1,304 @@
+//! Optional GraphQL API (`graphql` feature)
+//!
+//! Exposes zones, now playing, HQPlayer pipeline state and knobs through a single
+//! schema at `/graphql`, for dashboard builders who would otherwise stitch together
+//! a dozen REST calls. Subscriptions at `/graphql/ws` (graphql-ws / graphql-transport-ws)
+//! stream bus events, mirroring the `/events` SSE feed.
+//!
+//! Queries are read-only; control still goes through the REST/knob endpoints.
+
+use async_graphql::{
+    http::GraphiQLSource, Context, EmptyMutation, Json, Object, Schema, SimpleObject, Subscription,
+};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use axum::{
+    extract::{ws::WebSocketUpgrade, Extension},
+    response::{Html, IntoResponse, Response},
+};
+use futures::{Stream, StreamExt};
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::adapters::hqplayer::PipelineStatus;
+use crate::api::AppState;
+use crate::bus::{BusEvent, NowPlaying, VolumeControl, Zone, ZoneCapabilities, ZoneMetadata};
+use crate::knobs::store::KnobSummary;
+
+/// Schema type shared by the HTTP and WebSocket handlers
+pub type HifiSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// Build the schema. `AppState` is attached as context data.
+pub fn build_schema(state: AppState) -> HifiSchema {
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+        .data(state)
+        .finish()
+}
+
+/// Zone as exposed over GraphQL
+#[derive(SimpleObject)]
+pub struct GqlZone {
+    pub zone_id: String,
+    pub zone_name: String,
+    /// Adapter that owns the zone ("roon", "lms", ...)
+    pub source: String,
+    /// Playback state ("playing", "paused", "stopped", ...)
+    pub state: String,
+    pub is_controllable: bool,
+    pub is_seekable: bool,
+    pub volume: Option<GqlVolume>,
+    pub now_playing: Option<GqlNowPlaying>,
+    /// Last update (milliseconds since epoch)
+    pub last_updated: u64,
+    /// User-assigned room, icon, order and color
+    pub metadata: Option<GqlZoneMetadata>,
+    /// Controls the backend supports
+    pub capabilities: GqlZoneCapabilities,
+    /// ReplayGain mode ("off", "track", "album", "smart"), if exposed
+    pub volume_leveling: Option<String>,
+}
+
+impl From<Zone> for GqlZone {
+    fn from(zone: Zone) -> Self {
+        Self {
+            zone_id: zone.zone_id,
+            zone_name: zone.zone_name,
+            source: zone.source,
+            state: zone.state.to_string(),
+            is_controllable: zone.is_controllable,
+            is_seekable: zone.is_seekable,
+            volume: zone.volume_control.map(GqlVolume::from),
+            now_playing: zone.now_playing.map(GqlNowPlaying::from),
+            last_updated: zone.last_updated,
+            metadata: zone.metadata.map(GqlZoneMetadata::from),
+            capabilities: zone.capabilities.into(),
+            volume_leveling: zone.volume_leveling.map(|m| m.to_string()),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct GqlZoneCapabilities {
+    pub can_seek: bool,
+    pub can_shuffle: bool,
+    pub has_volume: bool,
+    pub has_queue: bool,
+    pub supports_grouping: bool,
+    pub has_eq: bool,
+    pub has_input: bool,
+}
+
+impl From<ZoneCapabilities> for GqlZoneCapabilities {
+    fn from(capabilities: ZoneCapabilities) -> Self {
+        Self {
+            can_seek: capabilities.can_seek,
+            can_shuffle: capabilities.can_shuffle,
+            has_volume: capabilities.has_volume,
+            has_queue: capabilities.has_queue,
+            supports_grouping: capabilities.supports_grouping,
+            has_eq: capabilities.has_eq,
+            has_input: capabilities.has_input,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct GqlZoneMetadata {
+    pub room: Option<String>,
+    pub icon: Option<String>,
+    /// Lower sorts first; null counts as 0
+    pub sort_weight: Option<i32>,
+    /// `#rrggbb`
+    pub color: Option<String>,
+    /// Volume change per knob detent or up/down press
+    pub volume_step: Option<f32>,
+    /// Other names the zone answers to when looked up by name
+    pub aliases: Vec<String>,
+    /// Control commands from non-admin callers are rejected
+    pub locked: bool,
+}
+
+impl From<ZoneMetadata> for GqlZoneMetadata {
+    fn from(metadata: ZoneMetadata) -> Self {
+        Self {
+            room: metadata.room,
+            icon: metadata.icon,
+            sort_weight: metadata.sort_weight,
+            color: metadata.color,
+            volume_step: metadata.volume_step,
+            aliases: metadata.aliases,
+            locked: metadata.locked,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct GqlVolume {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub is_muted: bool,
+}
+
+impl From<VolumeControl> for GqlVolume {
+    fn from(vc: VolumeControl) -> Self {
+        Self {
+            value: vc.value,
+            min: vc.min,
+            max: vc.max,
+            step: vc.step,
+            is_muted: vc.is_muted,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct GqlNowPlaying {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub image_key: Option<String>,
+    /// Seconds
+    pub seek_position: Option<f64>,
+    /// Seconds
+    pub duration: Option<f64>,
+}
+
+impl From<NowPlaying> for GqlNowPlaying {
+    fn from(np: NowPlaying) -> Self {
+        Self {
+            title: np.title,
+            artist: np.artist,
+            album: np.album,
+            image_key: np.image_key,
+            seek_position: np.seek_position,
+            duration: np.duration,
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// All zones from all adapters
+    async fn zones(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<GqlZone>> {
+        let state = ctx.data::<AppState>()?;
+        let mut zones = state.aggregator.get_zones().await;
+        crate::zone_metadata::sort_zones(&mut zones);
+        Ok(zones.into_iter().map(GqlZone::from).collect())
+    }
+
+    /// A single zone by prefixed ID (e.g. "roon:1234")
+    async fn zone(
+        &self,
+        ctx: &Context<'_>,
+        zone_id: String,
+    ) -> async_graphql::Result<Option<GqlZone>> {
+        let state = ctx.data::<AppState>()?;
+        Ok(state.aggregator.get_zone(&zone_id).await.map(GqlZone::from))
+    }
+
+    /// Current track for a zone
+    async fn now_playing(
+        &self,
+        ctx: &Context<'_>,
+        zone_id: String,
+    ) -> async_graphql::Result<Option<GqlNowPlaying>> {
+        let state = ctx.data::<AppState>()?;
+        Ok(state
+            .aggregator
+            .get_now_playing(&zone_id)
+            .await
+            .map(GqlNowPlaying::from))
+    }
+
+    /// HQPlayer pipeline for an instance (default instance if omitted).
+    /// Null when the instance is unknown or not connected.
+    async fn hqp_pipeline(
+        &self,
+        ctx: &Context<'_>,
+        instance: Option<String>,
+    ) -> async_graphql::Result<Option<Json<PipelineStatus>>> {
+        let state = ctx.data::<AppState>()?;
+        let adapter = match instance {
+            Some(name) => match state.hqp_instances.get(&name).await {
+                Some(adapter) => adapter,
+                None => return Ok(None),
+            },
+            None => state.hqplayer.clone(),
+        };
+        if !adapter.get_status().await.connected {
+            return Ok(None);
+        }
+        Ok(adapter.get_pipeline_status().await.ok().map(Json))
+    }
+
+    /// Registered knobs
+    async fn knobs(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Json<KnobSummary>>> {
+        let state = ctx.data::<AppState>()?;
+        Ok(state.knobs.list().await.into_iter().map(Json).collect())
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Bus events as `{type, payload}` JSON, the same shape as the `/events` SSE feed.
+    /// Ends when the server shuts down.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<impl Stream<Item = Json<BusEvent>>> {
+        let state = ctx.data::<AppState>()?;
+        let shutdown = state.shutdown.clone();
+        let events = BroadcastStream::new(state.bus.subscribe())
+            // Skip lagged messages, like the SSE handler
+            .filter_map(|result| async move { result.ok().map(Json) });
+        Ok(events.take_until(async move { shutdown.cancelled().await }))
+    }
+}
+
+/// GET /graphql - GraphiQL explorer
+pub async fn graphiql_handler() -> impl IntoResponse {
+    Html(
+        GraphiQLSource::build()
+            .endpoint("/graphql")
+            .subscription_endpoint("/graphql/ws")
+            .finish(),
+    )
+}
+
+/// POST /graphql - Execute a query
+pub async fn graphql_handler(
+    Extension(schema): Extension<HifiSchema>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    schema.execute(request.into_inner()).await.into()
+}
+
+/// GET /graphql/ws - Subscriptions over WebSocket
+pub async fn graphql_ws_handler(
+    Extension(schema): Extension<HifiSchema>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade
+        .protocols(async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| GraphQLWebSocket::new(stream, schema, protocol).serve())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_exposes_expected_roots() {
+        let sdl = Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+            .finish()
+            .sdl();
+        for field in ["zones:", "nowPlaying(", "hqpPipeline(", "knobs:", "events:"] {
+            assert!(sdl.contains(field), "schema is missing {}", field);
+        }
+    }
+}
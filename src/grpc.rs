@@ -0,0 +1,325 @@
+//! Optional gRPC server (`grpc` feature)
+//!
+//! Serves the services in `proto/unified_hifi.proto` on a separate port
+//! (`grpc_port` in config, or `UHC_GRPC_PORT`) for native integrations that want
+//! typed clients generated from the proto:
+//!
+//! - `ZoneService`: zones and now playing from the aggregator
+//! - `ControlService`: the same prefix-routed control as `POST /control`
+//! - `EventService`: server-streaming bus events, like `GET /events`
+//!
+//! With remote access on, peers off the local network need the access token
+//! as `authorization: Bearer <token>` metadata, as on the LAN HTTP listener.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use axum::{extract::State, Json};
+use futures::{Stream, StreamExt};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_util::sync::CancellationToken;
+use tonic::{Request, Response, Status};
+
+use crate::api::{ApiError, AppState, ErrorCode};
+use crate::bus::{BusEvent, NowPlaying, VolumeControl, Zone, ZoneCapabilities, ZoneMetadata};
+use crate::knobs::{knob_control_handler, KnobControlRequest};
+use crate::remote::{is_lan_peer, tokens_match, RemoteAuth};
+
+/// Generated protobuf types and service traits
+#[allow(clippy::all, clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+pub mod pb {
+    tonic::include_proto!("unifiedhifi.v1");
+}
+
+use pb::control_service_server::{ControlService, ControlServiceServer};
+use pb::event_service_server::{EventService, EventServiceServer};
+use pb::zone_service_server::{ZoneService, ZoneServiceServer};
+
+/// Implementation of all gRPC services over the shared application state
+#[derive(Clone)]
+pub struct GrpcApi {
+    state: AppState,
+}
+
+impl GrpcApi {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+/// Serve the gRPC services until `shutdown` is cancelled
+///
+/// `remote` is the remote access auth when remote access is on.
+pub async fn serve(
+    state: AppState,
+    addr: SocketAddr,
+    remote: Option<Arc<RemoteAuth>>,
+    shutdown: CancellationToken,
+) -> Result<(), tonic::transport::Error> {
+    let api = GrpcApi::new(state);
+    let check = remote_access_check(remote);
+    tonic::transport::Server::builder()
+        .add_service(ZoneServiceServer::with_interceptor(
+            api.clone(),
+            check.clone(),
+        ))
+        .add_service(ControlServiceServer::with_interceptor(
+            api.clone(),
+            check.clone(),
+        ))
+        .add_service(EventServiceServer::with_interceptor(api, check))
+        .serve_with_shutdown(addr, async move { shutdown.cancelled().await })
+        .await
+}
+
+/// Interceptor letting local peers through and asking anyone else for the
+/// remote access token
+fn remote_access_check(
+    remote: Option<Arc<RemoteAuth>>,
+) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone + Send + Sync + 'static {
+    move |request: Request<()>| {
+        let Some(auth) = &remote else {
+            return Ok(request);
+        };
+        if request
+            .remote_addr()
+            .is_some_and(|peer| is_lan_peer(&peer.ip()))
+        {
+            return Ok(request);
+        }
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        match token {
+            Some(token) if tokens_match(token, &auth.token) => Ok(request),
+            _ => Err(Status::unauthenticated("Missing or invalid access token")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ZoneService for GrpcApi {
+    async fn list_zones(
+        &self,
+        _request: Request<pb::ListZonesRequest>,
+    ) -> Result<Response<pb::ListZonesResponse>, Status> {
+        let mut zones = self.state.aggregator.get_zones().await;
+        crate::zone_metadata::sort_zones(&mut zones);
+        Ok(Response::new(pb::ListZonesResponse {
+            zones: zones.into_iter().map(pb::Zone::from).collect(),
+        }))
+    }
+
+    async fn get_zone(
+        &self,
+        request: Request<pb::ZoneRequest>,
+    ) -> Result<Response<pb::Zone>, Status> {
+        let zone_id = request.into_inner().zone_id;
+        self.state
+            .aggregator
+            .get_zone(&zone_id)
+            .await
+            .map(|zone| Response::new(pb::Zone::from(zone)))
+            .ok_or_else(|| Status::not_found(format!("Zone not found: {}", zone_id)))
+    }
+
+    async fn get_now_playing(
+        &self,
+        request: Request<pb::ZoneRequest>,
+    ) -> Result<Response<pb::NowPlaying>, Status> {
+        let zone_id = request.into_inner().zone_id;
+        self.state
+            .aggregator
+            .get_now_playing(&zone_id)
+            .await
+            .map(|np| Response::new(pb::NowPlaying::from(np)))
+            .ok_or_else(|| Status::not_found(format!("Nothing playing in zone: {}", zone_id)))
+    }
+}
+
+#[tonic::async_trait]
+impl ControlService for GrpcApi {
+    async fn control(
+        &self,
+        request: Request<pb::ControlRequest>,
+    ) -> Result<Response<pb::ControlResponse>, Status> {
+        // Metadata carries the same headers as HTTP, so zone locks see the
+        // caller's token
+        let (metadata, _, request) = request.into_parts();
+        let body = KnobControlRequest {
+            zone_id: request.zone_id,
+            action: request.action,
+            value: request.value.map(serde_json::Value::from),
+            gesture: None,
+        };
+        knob_control_handler(
+            State(self.state.clone()),
+            metadata.into_headers(),
+            Json(body),
+        )
+        .await
+        .map(|_| Response::new(pb::ControlResponse { ok: true }))
+        .map_err(status_from_api_error)
+    }
+}
+
+#[tonic::async_trait]
+impl EventService for GrpcApi {
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<pb::Event, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        _request: Request<pb::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let shutdown = self.state.shutdown.clone();
+        let events = BroadcastStream::new(self.state.bus.subscribe())
+            // Skip lagged messages, like the SSE handler
+            .filter_map(|result| async move { result.ok().and_then(|e| event_to_pb(&e)).map(Ok) })
+            .take_until(async move { shutdown.cancelled().await });
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+/// Split a bus event into its `type` tag and JSON payload (same encoding as SSE)
+fn event_to_pb(event: &BusEvent) -> Option<pb::Event> {
+    let value = serde_json::to_value(event).ok()?;
+    Some(pb::Event {
+        r#type: value.get("type")?.as_str()?.to_string(),
+        payload_json: value
+            .get("payload")
+            .map(|payload| payload.to_string())
+            .unwrap_or_else(|| "null".to_string()),
+    })
+}
+
+/// Map API error codes onto gRPC status codes
+fn status_from_api_error(err: ApiError) -> Status {
+    let code = match err.code {
+        ErrorCode::InvalidRequest | ErrorCode::MissingZoneId | ErrorCode::UnknownAction => {
+            tonic::Code::InvalidArgument
+        }
+        ErrorCode::ZoneNotFound
+        | ErrorCode::RoomNotFound
+        | ErrorCode::NoOutputs
+        | ErrorCode::InstanceNotFound
+        | ErrorCode::KnobNotFound
+        | ErrorCode::ImageNotFound
+        | ErrorCode::FirmwareNotFound
+        | ErrorCode::AdapterNotFound
+        | ErrorCode::TokenNotFound
+        | ErrorCode::HookNotFound
+        | ErrorCode::ClipNotFound
+        | ErrorCode::NotLinked => tonic::Code::NotFound,
+        ErrorCode::Unauthorized => tonic::Code::Unauthenticated,
+        ErrorCode::Forbidden | ErrorCode::ZoneLocked => tonic::Code::PermissionDenied,
+        // Retryable backend failures
+        ErrorCode::CommandFailed
+        | ErrorCode::BackendUnavailable
+        | ErrorCode::BackendError
+        | ErrorCode::ShuttingDown
+        | ErrorCode::UpdateDeferred => tonic::Code::Unavailable,
+        ErrorCode::Timeout => tonic::Code::DeadlineExceeded,
+        ErrorCode::Internal => tonic::Code::Internal,
+    };
+    Status::new(code, err.message)
+}
+
+impl From<Zone> for pb::Zone {
+    fn from(zone: Zone) -> Self {
+        Self {
+            zone_id: zone.zone_id,
+            zone_name: zone.zone_name,
+            source: zone.source,
+            state: zone.state.to_string(),
+            is_controllable: zone.is_controllable,
+            is_seekable: zone.is_seekable,
+            volume: zone.volume_control.map(pb::Volume::from),
+            now_playing: zone.now_playing.map(pb::NowPlaying::from),
+            last_updated: zone.last_updated,
+            metadata: zone.metadata.map(pb::ZoneMetadata::from),
+            capabilities: Some(pb::ZoneCapabilities::from(zone.capabilities)),
+            volume_leveling: zone.volume_leveling.map(|m| m.to_string()),
+        }
+    }
+}
+
+impl From<ZoneCapabilities> for pb::ZoneCapabilities {
+    fn from(capabilities: ZoneCapabilities) -> Self {
+        Self {
+            can_seek: capabilities.can_seek,
+            can_shuffle: capabilities.can_shuffle,
+            has_volume: capabilities.has_volume,
+            has_queue: capabilities.has_queue,
+            supports_grouping: capabilities.supports_grouping,
+            has_eq: capabilities.has_eq,
+            has_input: capabilities.has_input,
+        }
+    }
+}
+
+impl From<ZoneMetadata> for pb::ZoneMetadata {
+    fn from(metadata: ZoneMetadata) -> Self {
+        Self {
+            room: metadata.room,
+            icon: metadata.icon,
+            sort_weight: metadata.sort_weight,
+            color: metadata.color,
+            volume_step: metadata.volume_step,
+            aliases: metadata.aliases,
+            locked: metadata.locked,
+        }
+    }
+}
+
+impl From<VolumeControl> for pb::Volume {
+    fn from(vc: VolumeControl) -> Self {
+        Self {
+            value: vc.value,
+            min: vc.min,
+            max: vc.max,
+            step: vc.step,
+            is_muted: vc.is_muted,
+        }
+    }
+}
+
+impl From<NowPlaying> for pb::NowPlaying {
+    fn from(np: NowPlaying) -> Self {
+        Self {
+            title: np.title,
+            artist: np.artist,
+            album: np.album,
+            image_key: np.image_key,
+            seek_position: np.seek_position,
+            duration: np.duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_to_pb_splits_type_and_payload() {
+        let event = BusEvent::RoonConnected {
+            core_name: "Roon Core".to_string(),
+            version: "2.0.0".to_string(),
+        };
+        let pb = event_to_pb(&event).unwrap();
+        assert_eq!(pb.r#type, "RoonConnected");
+        let payload: serde_json::Value = serde_json::from_str(&pb.payload_json).unwrap();
+        assert_eq!(payload["core_name"], "Roon Core");
+    }
+
+    #[test]
+    fn test_api_errors_map_to_grpc_codes() {
+        let status = status_from_api_error(ApiError::new(ErrorCode::ZoneNotFound, "gone"));
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        let status = status_from_api_error(ApiError::new(ErrorCode::BackendError, "down"));
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+    }
+}
@@ -0,0 +1,475 @@
+//! Playback history (`/history/export`, the `/wall` cover wall)
+//!
+//! Every track a zone starts is appended as a JSON line to
+//! `play-history.jsonl` in the config directory, with the zone's name and
+//! backend. Once the file passes `MAX_FILE_BYTES` it becomes the archive,
+//! `play-history.1.jsonl` (replacing the previous one), and a new file is
+//! started, so roughly the last two files' worth of plays are kept. The
+//! export reads the archive and then the current file, oldest play first.
+//! Plays keep the cover's image key so the wall can show recent albums.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::bus::{BusEvent, PlaybackState, SharedBus};
+use crate::config::get_config_file_path;
+
+const HISTORY_FILE: &str = "play-history.jsonl";
+const ARCHIVE_FILE: &str = "play-history.1.jsonl";
+
+/// Size at which the history file is archived
+const MAX_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// CSV header, matching [`PlayRecord::csv_row`]
+pub const CSV_HEADER: &str = "played_at,zone_id,zone_name,backend,title,artist,album\r\n";
+
+/// A track played on a zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayRecord {
+    pub played_at: DateTime<Utc>,
+    pub zone_id: String,
+    pub zone_name: String,
+    /// Zone id prefix (`roon`, `lms`, ...)
+    pub backend: String,
+    pub title: String,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub album: String,
+    /// Cover art key on the zone's backend, for the `/wall` page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_key: Option<String>,
+}
+
+impl PlayRecord {
+    /// The record as a CSV line (RFC 4180)
+    pub fn csv_row(&self) -> String {
+        let played_at = self.played_at.to_rfc3339();
+        let fields: [&str; 7] = [
+            &played_at,
+            &self.zone_id,
+            &self.zone_name,
+            &self.backend,
+            &self.title,
+            &self.artist,
+            &self.album,
+        ];
+        let mut row = fields.map(csv_field).join(",");
+        row.push_str("\r\n");
+        row
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Time span for an export
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HistoryRange {
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl HistoryRange {
+    /// Parse `24h`, `7d` or `4w` (back from `now`), `2026-01-01..2026-02-01`
+    /// (UTC days, end exclusive, either end may be left out), or `all`
+    pub fn parse(range: &str, now: DateTime<Utc>) -> Option<Self> {
+        let range = range.trim();
+        if range.is_empty() || range == "all" {
+            return Some(Self::default());
+        }
+        if let Some((from, to)) = range.split_once("..") {
+            let day = |s: &str| -> Option<Option<DateTime<Utc>>> {
+                if s.is_empty() {
+                    return Some(None);
+                }
+                let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+                Some(Some(date.and_hms_opt(0, 0, 0)?.and_utc()))
+            };
+            return Some(Self {
+                from: day(from)?,
+                to: day(to)?,
+            });
+        }
+        let unit = range.chars().last()?;
+        let count: i64 = range[..range.len() - unit.len_utf8()].parse().ok()?;
+        let span = match unit {
+            'h' => Duration::try_hours(count)?,
+            'd' => Duration::try_days(count)?,
+            'w' => Duration::try_weeks(count)?,
+            _ => return None,
+        };
+        Some(Self {
+            from: Some(now - span),
+            to: None,
+        })
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| at >= from) && self.to.is_none_or(|to| at < to)
+    }
+}
+
+/// Turns zone events into play records
+#[derive(Debug, Default)]
+struct Recorder {
+    zone_names: HashMap<String, String>,
+    /// (title, artist) of the last recorded track per zone
+    last_track: HashMap<String, (String, String)>,
+}
+
+impl Recorder {
+    fn record(
+        &mut self,
+        zone_id: &str,
+        title: &str,
+        artist: &str,
+        album: &str,
+        image_key: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Option<PlayRecord> {
+        if title.is_empty() {
+            return None;
+        }
+        let track = (title.to_string(), artist.to_string());
+        if self.last_track.get(zone_id) == Some(&track) {
+            return None;
+        }
+        self.last_track.insert(zone_id.to_string(), track);
+        Some(PlayRecord {
+            played_at: now,
+            zone_id: zone_id.to_string(),
+            zone_name: self.zone_names.get(zone_id).cloned().unwrap_or_default(),
+            backend: zone_id.split(':').next().unwrap_or_default().to_string(),
+            title: title.to_string(),
+            artist: artist.to_string(),
+            album: album.to_string(),
+            image_key: image_key.map(str::to_string),
+        })
+    }
+
+    /// The play a bus event starts, if any; repeats of a zone's current track are ignored
+    fn observe(&mut self, event: &BusEvent, now: DateTime<Utc>) -> Option<PlayRecord> {
+        match event {
+            BusEvent::ZoneDiscovered { zone } => {
+                self.zone_names
+                    .insert(zone.zone_id.clone(), zone.zone_name.clone());
+                let np = zone.now_playing.as_ref()?;
+                if zone.state != PlaybackState::Playing {
+                    return None;
+                }
+                self.record(
+                    &zone.zone_id,
+                    &np.title,
+                    &np.artist,
+                    &np.album,
+                    np.image_key.as_deref(),
+                    now,
+                )
+            }
+            BusEvent::ZoneUpdated {
+                zone_id,
+                display_name,
+                ..
+            } => {
+                self.zone_names
+                    .insert(zone_id.as_str().to_string(), display_name.clone());
+                None
+            }
+            BusEvent::NowPlayingChanged {
+                zone_id,
+                title,
+                artist,
+                album,
+                image_key,
+            } => self.record(
+                zone_id.as_str(),
+                title.as_deref().unwrap_or_default(),
+                artist.as_deref().unwrap_or_default(),
+                album.as_deref().unwrap_or_default(),
+                image_key.as_deref(),
+                now,
+            ),
+            _ => None,
+        }
+    }
+}
+
+fn history_path() -> PathBuf {
+    get_config_file_path(HISTORY_FILE)
+}
+
+fn archive_path() -> PathBuf {
+    get_config_file_path(ARCHIVE_FILE)
+}
+
+/// Append a record, archiving the file first once it is full
+fn append(record: &PlayRecord) -> std::io::Result<()> {
+    let path = history_path();
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_FILE_BYTES) {
+        std::fs::rename(&path, archive_path())?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(line.as_bytes())
+}
+
+/// Record plays from the bus until `shutdown`
+pub fn start(bus: SharedBus, shutdown: CancellationToken) {
+    let mut recorder = Recorder::default();
+    let mut rx = bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                result = rx.recv() => match result {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Play history lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if let Some(record) = recorder.observe(&event, Utc::now()) {
+                if let Err(e) = append(&record) {
+                    tracing::warn!("Failed to write play history: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Recorded plays, oldest first, read from disk as the stream is polled
+pub fn records() -> impl Stream<Item = PlayRecord> {
+    let paths = vec![archive_path(), history_path()].into_iter();
+    let lines: Option<Lines<BufReader<tokio::fs::File>>> = None;
+    stream::unfold((paths, lines), |(mut paths, mut lines)| async move {
+        loop {
+            if let Some(current) = lines.as_mut() {
+                match current.next_line().await {
+                    Ok(Some(line)) => {
+                        // A line cut short by a concurrent write is skipped
+                        if let Ok(record) = serde_json::from_str(&line) {
+                            return Some((record, (paths, lines)));
+                        }
+                        continue;
+                    }
+                    _ => lines = None,
+                }
+            }
+            let path = paths.next()?;
+            // There is no archive until the first file fills up
+            if let Ok(file) = tokio::fs::File::open(path).await {
+                lines = Some(BufReader::new(file).lines());
+            }
+        }
+    })
+}
+
+/// An album on the cover wall (`/wall`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WallAlbum {
+    pub artist: String,
+    pub album: String,
+    /// Zone the album was last played on; its backend serves the cover
+    pub zone_id: String,
+    pub zone_name: String,
+    pub image_key: String,
+    pub played_at: DateTime<Utc>,
+}
+
+/// Distinct albums with cover art among `records` (oldest first), most
+/// recently played first
+///
+/// Albums are told apart by artist and title, ignoring case, so the same
+/// album played on two backends appears once.
+pub fn latest_albums(
+    records: impl IntoIterator<Item = PlayRecord>,
+    limit: usize,
+) -> Vec<WallAlbum> {
+    let mut latest: HashMap<(String, String), WallAlbum> = HashMap::new();
+    for record in records {
+        let Some(image_key) = record.image_key.filter(|k| !k.is_empty()) else {
+            continue;
+        };
+        if record.album.is_empty() {
+            continue;
+        }
+        let key = (record.artist.to_lowercase(), record.album.to_lowercase());
+        latest.insert(
+            key,
+            WallAlbum {
+                artist: record.artist,
+                album: record.album,
+                zone_id: record.zone_id,
+                zone_name: record.zone_name,
+                image_key,
+                played_at: record.played_at,
+            },
+        );
+    }
+    let mut albums: Vec<WallAlbum> = latest.into_values().collect();
+    albums.sort_by(|a, b| b.played_at.cmp(&a.played_at));
+    albums.truncate(limit);
+    albums
+}
+
+/// The most recently played albums with cover art, read from disk
+pub async fn recent_albums(limit: usize) -> Vec<WallAlbum> {
+    latest_albums(records().collect::<Vec<_>>().await, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::{NowPlaying, PrefixedZoneId, Zone};
+
+    fn now_playing(title: &str) -> BusEvent {
+        BusEvent::NowPlayingChanged {
+            zone_id: PrefixedZoneId::lms("aa"),
+            title: Some(title.to_string()),
+            artist: Some("Miles Davis".to_string()),
+            album: Some("Kind of Blue".to_string()),
+            image_key: None,
+        }
+    }
+
+    #[test]
+    fn records_each_track_once_with_zone_name() {
+        let now = Utc::now();
+        let mut recorder = Recorder::default();
+        let zone = Zone {
+            zone_id: "lms:aa".to_string(),
+            zone_name: "Kitchen".to_string(),
+            state: PlaybackState::Paused,
+            now_playing: Some(NowPlaying {
+                title: "So What".to_string(),
+                artist: "Miles Davis".to_string(),
+                album: "Kind of Blue".to_string(),
+                image_key: None,
+                seek_position: None,
+                duration: None,
+                metadata: None,
+            }),
+            volume_control: None,
+            source: "lms".to_string(),
+            is_controllable: true,
+            is_seekable: false,
+            capabilities: Default::default(),
+            volume_leveling: None,
+            last_updated: 0,
+            is_play_allowed: true,
+            is_pause_allowed: false,
+            is_next_allowed: true,
+            is_previous_allowed: true,
+            metadata: None,
+        };
+        // Paused at discovery isn't a play
+        assert!(recorder
+            .observe(&BusEvent::ZoneDiscovered { zone }, now)
+            .is_none());
+
+        let record = recorder.observe(&now_playing("So What"), now).unwrap();
+        assert_eq!(record.zone_name, "Kitchen");
+        assert_eq!(record.backend, "lms");
+        assert_eq!(record.album, "Kind of Blue");
+        assert!(recorder.observe(&now_playing("So What"), now).is_none());
+        assert!(recorder
+            .observe(&now_playing("Freddie Freeloader"), now)
+            .is_some());
+    }
+
+    #[test]
+    fn csv_row_quotes_fields() {
+        let record = PlayRecord {
+            played_at: DateTime::parse_from_rfc3339("2026-10-16T08:30:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            zone_id: "roon:1601".to_string(),
+            zone_name: "Living Room".to_string(),
+            backend: "roon".to_string(),
+            title: "Hello, \"World\"".to_string(),
+            artist: "A".to_string(),
+            album: String::new(),
+            image_key: Some("abc".to_string()),
+        };
+        assert_eq!(
+            record.csv_row(),
+            "2026-10-16T08:30:00+00:00,roon:1601,Living Room,roon,\"Hello, \"\"World\"\"\",A,\r\n"
+        );
+    }
+
+    #[test]
+    fn wall_lists_each_album_once_newest_first() {
+        let start = Utc::now();
+        let play = |minutes: i64, album: &str, image_key: Option<&str>| PlayRecord {
+            played_at: start + Duration::minutes(minutes),
+            zone_id: "lms:aa".to_string(),
+            zone_name: "Kitchen".to_string(),
+            backend: "lms".to_string(),
+            title: "Track".to_string(),
+            artist: "Miles Davis".to_string(),
+            album: album.to_string(),
+            image_key: image_key.map(str::to_string),
+        };
+        let albums = latest_albums(
+            vec![
+                play(0, "Kind of Blue", Some("1")),
+                play(1, "Bitches Brew", Some("2")),
+                play(2, "kind of blue", Some("1")),
+                play(3, "Sketches of Spain", None),
+            ],
+            10,
+        );
+        let names: Vec<&str> = albums.iter().map(|a| a.album.as_str()).collect();
+        assert_eq!(names, ["kind of blue", "Bitches Brew"]);
+
+        let newest = latest_albums(vec![play(0, "A", Some("3")), play(1, "B", Some("4"))], 1);
+        assert_eq!(newest.len(), 1);
+        assert_eq!(newest[0].album, "B");
+    }
+
+    #[test]
+    fn parses_ranges() {
+        let now = DateTime::parse_from_rfc3339("2026-10-16T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let day = HistoryRange::parse("24h", now).unwrap();
+        assert_eq!(day.from, Some(now - Duration::hours(24)));
+        assert!(day.contains(now) && !day.contains(now - Duration::days(2)));
+
+        let october = HistoryRange::parse("2026-10-01..2026-11-01", now).unwrap();
+        assert!(october.contains(now));
+        let open = HistoryRange::parse("2026-10-17..", now).unwrap();
+        assert!(!open.contains(now) && open.to.is_none());
+
+        assert_eq!(
+            HistoryRange::parse("all", now),
+            Some(HistoryRange::default())
+        );
+        assert!(HistoryRange::parse("3y", now).is_none());
+        assert!(HistoryRange::parse("2026-13-01..", now).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+//! Inbound hooks
+//!
+//! A hook is a named action triggered by `POST /hooks/{name}`, so a
+//! doorbell, calendar or Shortcuts automation can "pause everything" with a
+//! single URL. A hook can run a scene or a control action on one zone or all
+//! of them, and can require a secret. Hooks are persisted to `hooks.json` in
+//! the config directory.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{PoisonError, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{get_config_file_path, read_config_file};
+
+const HOOKS_FILE: &str = "hooks.json";
+
+/// Longest hook name, as it ends up in URLs
+const MAX_NAME_LEN: usize = 64;
+
+/// `zone_id` of a control hook that targets every zone
+pub const ALL_ZONES: &str = "*";
+
+/// What a hook does when triggered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HookTarget {
+    /// Start the party scene
+    Party,
+    /// Put every zone into standby
+    Standby,
+    /// A `/control` action on a zone, or on every zone with `zone_id: "*"`
+    Control {
+        zone_id: String,
+        action: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<serde_json::Value>,
+    },
+}
+
+/// A stored hook
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Hook {
+    #[serde(flatten)]
+    pub target: HookTarget,
+    /// Required as `?secret=` or `X-Hook-Secret` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// Hook names are URL path segments: letters, digits, `-` and `_`
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HooksFile {
+    #[serde(default)]
+    hooks: BTreeMap<String, Hook>,
+}
+
+/// Stored hooks, optionally persisted to disk
+#[derive(Default)]
+pub struct HookStore {
+    hooks: RwLock<HooksFile>,
+    /// `None` keeps the store in memory only (tests, demo)
+    path: Option<PathBuf>,
+}
+
+impl HookStore {
+    /// Load hooks from the config directory; changes are written back
+    pub fn load() -> Self {
+        let hooks = read_config_file(HOOKS_FILE)
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(hooks) => Some(hooks),
+                Err(e) => {
+                    tracing::warn!("Failed to parse hooks: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            hooks: RwLock::new(hooks),
+            path: Some(get_config_file_path(HOOKS_FILE)),
+        }
+    }
+
+    /// All hooks by name
+    pub fn list(&self) -> BTreeMap<String, Hook> {
+        self.hooks
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .hooks
+            .clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Hook> {
+        self.hooks
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .hooks
+            .get(name)
+            .cloned()
+    }
+
+    /// Create or replace a hook
+    pub fn set(&self, name: &str, hook: Hook) {
+        let mut hooks = self.hooks.write().unwrap_or_else(PoisonError::into_inner);
+        hooks.hooks.insert(name.to_string(), hook);
+        self.save(&hooks);
+    }
+
+    /// Remove a hook; `false` if there was none
+    pub fn remove(&self, name: &str) -> bool {
+        let mut hooks = self.hooks.write().unwrap_or_else(PoisonError::into_inner);
+        let removed = hooks.hooks.remove(name).is_some();
+        if removed {
+            self.save(&hooks);
+        }
+        removed
+    }
+
+    fn save(&self, hooks: &HooksFile) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(hooks) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(path, json) {
+                    tracing::error!("Failed to save hooks: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize hooks: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_json_shape() {
+        let hook: Hook = serde_json::from_str(
+            r#"{"type": "control", "zone_id": "*", "action": "pause", "secret": "s3cret"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            hook.target,
+            HookTarget::Control {
+                zone_id: ALL_ZONES.to_string(),
+                action: "pause".to_string(),
+                value: None,
+            }
+        );
+        assert_eq!(hook.secret.as_deref(), Some("s3cret"));
+
+        let party: Hook = serde_json::from_str(r#"{"type": "party"}"#).unwrap();
+        assert_eq!(party.target, HookTarget::Party);
+        assert_eq!(
+            serde_json::to_value(&party).unwrap(),
+            serde_json::json!({"type": "party"})
+        );
+    }
+
+    #[test]
+    fn test_names_and_store() {
+        assert!(is_valid_name("doorbell_pause-all"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("../x"));
+        assert!(!is_valid_name(&"a".repeat(MAX_NAME_LEN + 1)));
+
+        let store = HookStore::default();
+        let hook = Hook {
+            target: HookTarget::Standby,
+            secret: None,
+        };
+        store.set("goodnight", hook.clone());
+        assert_eq!(store.get("goodnight"), Some(hook));
+        assert!(store.remove("goodnight"));
+        assert!(!store.remove("goodnight"));
+    }
+}
@@ -0,0 +1,281 @@
+//! HTTP conditional request helpers (ETag / If-None-Match / If-Modified-Since)
+//!
+//! Knobs and other pollers hit `/zones` and `/now_playing` every few seconds; most
+//! of those responses are identical to the previous one. Tagging responses with a
+//! hash of the body lets clients send `If-None-Match` and get an empty 304 instead.
+//!
+//! Artwork and embedded static assets additionally carry `Cache-Control` and
+//! `Last-Modified` via [`Validators`].
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cache policy for artwork addressed by an immutable key (e.g. Roon `image_key`)
+pub const CACHE_IMMUTABLE_ARTWORK: &str = "public, max-age=86400";
+
+/// Cache policy for URLs whose content changes over time (e.g. a zone's current artwork).
+/// Clients keep a copy but revalidate on every use, which is cheap with an ETag.
+pub const CACHE_REVALIDATE: &str = "no-cache";
+
+/// Upper bound on remembered artwork ETags before the table is reset
+const MAX_TRACKED_ETAGS: usize = 1024;
+
+/// When each artwork ETag was first served, used as its `Last-Modified`
+static FIRST_SEEN: LazyLock<Mutex<HashMap<String, SystemTime>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Compute a strong ETag (quoted, first 16 hex chars of SHA-256) for a response body
+pub fn etag_for(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("\"{}\"", hex::encode(&digest[..8]))
+}
+
+/// Check whether the request's `If-None-Match` header matches the given ETag.
+///
+/// Handles `*`, comma-separated lists, and weak (`W/`) validators.
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+/// Empty 304 response carrying the ETag
+pub fn not_modified(etag: &str) -> Response {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+/// Serialize `value` as JSON with an ETag, or return 304 if the client already has it
+pub fn json_with_etag<T: Serialize>(headers: &HeaderMap, value: &T) -> Response {
+    match serde_json::to_vec(value) {
+        Ok(body) => body_with_etag(headers, "application/json", body),
+        // Fall back to axum's own serialization (and its error response)
+        Err(_) => Json(value).into_response(),
+    }
+}
+
+/// Return an already-encoded body with an ETag, or 304 if the client already has it
+pub fn body_with_etag(headers: &HeaderMap, content_type: &'static str, body: Vec<u8>) -> Response {
+    let etag = etag_for(&body);
+    if if_none_match(headers, &etag) {
+        return not_modified(&etag);
+    }
+
+    let mut response = Response::new(Body::from(body));
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    response
+}
+
+/// Cache validators for a binary response (artwork, static files)
+#[derive(Debug, Clone)]
+pub struct Validators {
+    pub etag: String,
+    pub last_modified: Option<SystemTime>,
+    pub cache_control: &'static str,
+}
+
+impl Validators {
+    /// Validators for dynamically fetched content. `Last-Modified` is the first time
+    /// this process served content with the same hash.
+    pub fn for_body(body: &[u8], cache_control: &'static str) -> Self {
+        let etag = etag_for(body);
+        let last_modified = first_seen(&etag);
+        Self {
+            etag,
+            last_modified: Some(last_modified),
+            cache_control,
+        }
+    }
+
+    /// True if the client's cached copy is still current.
+    ///
+    /// `If-None-Match` takes precedence; `If-Modified-Since` is only consulted
+    /// when it is absent (RFC 9110 §13.2.2).
+    pub fn is_fresh(&self, headers: &HeaderMap) -> bool {
+        if headers.contains_key(header::IF_NONE_MATCH) {
+            return if_none_match(headers, &self.etag);
+        }
+        let (Some(last_modified), Some(since)) = (
+            self.last_modified,
+            headers
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_http_date),
+        ) else {
+            return false;
+        };
+        // HTTP dates have one-second resolution
+        truncate_to_secs(last_modified) <= since
+    }
+
+    /// Add `ETag`, `Last-Modified` and `Cache-Control` to a response
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.etag) {
+            headers.insert(header::ETAG, value);
+        }
+        if let Some(value) = self
+            .last_modified
+            .and_then(|t| HeaderValue::from_str(&format_http_date(t)).ok())
+        {
+            headers.insert(header::LAST_MODIFIED, value);
+        }
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(self.cache_control),
+        );
+    }
+
+    /// Empty 304 response carrying the validators
+    pub fn not_modified(&self) -> Response {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        self.apply(response.headers_mut());
+        response
+    }
+
+    /// 200 response with the given body and content type, or 304 if the client is current
+    pub fn respond(&self, headers: &HeaderMap, content_type: &str, body: Vec<u8>) -> Response {
+        if self.is_fresh(headers) {
+            return self.not_modified();
+        }
+        let mut response = Response::new(Body::from(body));
+        if let Ok(value) = HeaderValue::from_str(content_type) {
+            response.headers_mut().insert(header::CONTENT_TYPE, value);
+        }
+        self.apply(response.headers_mut());
+        response
+    }
+}
+
+/// First time an ETag was seen by this process (recorded on first call)
+fn first_seen(etag: &str) -> SystemTime {
+    let now = SystemTime::now();
+    let Ok(mut seen) = FIRST_SEEN.lock() else {
+        return now;
+    };
+    if seen.len() >= MAX_TRACKED_ETAGS && !seen.contains_key(etag) {
+        seen.clear();
+    }
+    *seen.entry(etag.to_string()).or_insert(now)
+}
+
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+/// Format a timestamp as an IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`)
+pub fn format_http_date(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+/// Parse an IMF-fixdate as sent in `If-Modified-Since`
+pub fn parse_http_date(value: &str) -> Option<SystemTime> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|t| SystemTime::from(t.with_timezone(&Utc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with(if_none_match: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(if_none_match).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_if_none_match_variants() {
+        let etag = etag_for(b"{}");
+        assert!(if_none_match(&headers_with(&etag), &etag));
+        assert!(if_none_match(&headers_with(&format!("W/{}", etag)), &etag));
+        assert!(if_none_match(
+            &headers_with(&format!("\"other\", {}", etag)),
+            &etag
+        ));
+        assert!(if_none_match(&headers_with("*"), &etag));
+        assert!(!if_none_match(&headers_with("\"other\""), &etag));
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn test_json_with_etag_returns_304_when_unchanged() {
+        let value = serde_json::json!({ "zones": [] });
+        let first = json_with_etag(&HeaderMap::new(), &value);
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let second = json_with_etag(&headers_with(&etag), &value);
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers().get(header::ETAG).unwrap(), etag.as_str());
+
+        let changed = serde_json::json!({ "zones": ["roon:1"] });
+        let third = json_with_etag(&headers_with(&etag), &changed);
+        assert_eq!(third.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_validators_honour_if_modified_since() {
+        let validators = Validators {
+            etag: etag_for(b"art"),
+            last_modified: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            cache_control: CACHE_IMMUTABLE_ARTWORK,
+        };
+        let date = format_http_date(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert_eq!(date, "Tue, 14 Nov 2023 22:13:20 GMT");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_str(&date).unwrap(),
+        );
+        assert!(validators.is_fresh(&headers));
+
+        let response = validators.respond(&headers, "image/jpeg", b"art".to_vec());
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            CACHE_IMMUTABLE_ARTWORK
+        );
+
+        // A mismatched If-None-Match wins over a matching If-Modified-Since
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+        assert!(!validators.is_fresh(&headers));
+    }
+}
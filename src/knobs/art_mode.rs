@@ -0,0 +1,203 @@
+//! Art mode slideshow sources for idle knobs (`/knob/art_mode/next`)
+//!
+//! In art mode a knob hides its controls and shows artwork. By default that
+//! is the current cover; a knob can instead rotate through:
+//! - `recent_covers`: covers recently played on any zone
+//! - `folder`: images in `art/` in the config directory
+//! - `artist`: artist images of what its zone plays (Roon only)
+//!
+//! Each knob keeps its own position, so two knobs on the same source don't
+//! skip each other's images.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use super::image::{encode_artwork, sniff_content_type, ArtworkOptions};
+use crate::bus::{BusEvent, ImageData, SharedBus};
+use crate::config::get_config_dir;
+
+/// Recent covers kept; the oldest is dropped first
+const MAX_RECENT_COVERS: usize = 50;
+
+/// Largest file served from the art folder
+const MAX_FOLDER_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+const FOLDER_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "gif", "bmp"];
+
+/// What a knob shows in art mode
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtModeSource {
+    /// The zone's current cover
+    #[default]
+    Current,
+    RecentCovers,
+    Folder,
+    Artist,
+}
+
+impl ArtModeSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Current => "current",
+            Self::RecentCovers => "recent_covers",
+            Self::Folder => "folder",
+            Self::Artist => "artist",
+        }
+    }
+
+    pub fn is_current(&self) -> bool {
+        *self == Self::Current
+    }
+}
+
+/// A cover played on a zone, fetched again through the zone's backend
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cover {
+    pub zone_id: String,
+    pub image_key: String,
+}
+
+/// Recently played covers and each knob's slideshow position
+#[derive(Default)]
+pub struct ArtMode {
+    recent: Mutex<VecDeque<Cover>>,
+    positions: Mutex<HashMap<String, usize>>,
+}
+
+impl ArtMode {
+    /// Remember a cover, moving it to the front if already known
+    pub fn record(&self, zone_id: &str, image_key: &str) {
+        let mut recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+        recent.retain(|c| c.image_key != image_key);
+        recent.push_front(Cover {
+            zone_id: zone_id.to_string(),
+            image_key: image_key.to_string(),
+        });
+        recent.truncate(MAX_RECENT_COVERS);
+    }
+
+    /// Recent covers, newest first
+    pub fn recent(&self) -> Vec<Cover> {
+        let recent = self.recent.lock().unwrap_or_else(PoisonError::into_inner);
+        recent.iter().cloned().collect()
+    }
+
+    /// Index of a knob's next image in a list of `len`; `None` when empty
+    pub fn advance(&self, knob_id: &str, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let mut positions = self
+            .positions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let position = positions.entry(knob_id.to_string()).or_default();
+        let index = *position % len;
+        *position = index + 1;
+        Some(index)
+    }
+}
+
+/// Record covers from now playing events until `shutdown`
+pub fn start(bus: SharedBus, art_mode: std::sync::Arc<ArtMode>, shutdown: CancellationToken) {
+    let mut rx = bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                result = rx.recv() => match result {
+                    Ok(BusEvent::NowPlayingChanged {
+                        zone_id,
+                        image_key: Some(image_key),
+                        ..
+                    }) => art_mode.record(zone_id.as_str(), &image_key),
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    });
+}
+
+/// Folder of images for the `folder` source
+pub fn art_dir() -> PathBuf {
+    get_config_dir().join("art")
+}
+
+/// Images in the art folder, sorted by name
+pub fn folder_images() -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(art_dir()) else {
+        return Vec::new();
+    };
+    let mut images: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| FOLDER_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        })
+        .collect();
+    images.sort();
+    images
+}
+
+/// Read and convert an image from the art folder
+pub async fn load_image(path: &Path, options: &ArtworkOptions) -> Option<ImageData> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FOLDER_IMAGE_BYTES {
+        return None;
+    }
+    let data = tokio::fs::read(path).await.ok()?;
+    match encode_artwork(&data, options) {
+        Ok(Some(encoded)) => Some(ImageData {
+            content_type: encoded.content_type.to_string(),
+            data: encoded.data,
+        }),
+        Ok(None) => Some(ImageData {
+            content_type: sniff_content_type(&data, None),
+            data,
+        }),
+        Err(e) => {
+            tracing::debug!("Art mode image {} unreadable: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_newest_first_without_duplicates() {
+        let art_mode = ArtMode::default();
+        art_mode.record("roon:1", "a");
+        art_mode.record("lms:2", "b");
+        art_mode.record("roon:1", "a");
+        let keys: Vec<String> = art_mode.recent().into_iter().map(|c| c.image_key).collect();
+        assert_eq!(keys, ["a", "b"]);
+
+        for i in 0..MAX_RECENT_COVERS + 5 {
+            art_mode.record("roon:1", &i.to_string());
+        }
+        assert_eq!(art_mode.recent().len(), MAX_RECENT_COVERS);
+    }
+
+    #[test]
+    fn test_advance_per_knob() {
+        let art_mode = ArtMode::default();
+        assert_eq!(art_mode.advance("knob1", 0), None);
+        assert_eq!(art_mode.advance("knob1", 2), Some(0));
+        assert_eq!(art_mode.advance("knob1", 2), Some(1));
+        assert_eq!(art_mode.advance("knob1", 2), Some(0));
+        assert_eq!(art_mode.advance("knob2", 2), Some(0));
+        // A shorter list wraps instead of going out of bounds
+        assert_eq!(art_mode.advance("knob1", 1), Some(0));
+    }
+}
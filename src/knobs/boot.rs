@@ -0,0 +1,151 @@
+//! Startup macro a knob runs after booting (`/config/{knob_id}/boot`)
+//!
+//! The steps are stored in the knob config and edited like any other setting,
+//! so what a knob does on startup can change without a firmware release. The
+//! bridge resolves each step before serving it: "last zone" becomes a zone id
+//! the knob can select, and steps that can't run are left out.
+
+use serde::{Deserialize, Serialize};
+
+/// Most steps kept in a macro
+pub const MAX_BOOT_STEPS: usize = 8;
+
+/// Longest greeting, in characters; the display fits about two lines
+pub const MAX_GREETING_LEN: usize = 64;
+
+/// Seconds a greeting stays up when the step doesn't say
+const DEFAULT_GREETING_SEC: u32 = 3;
+
+/// Longest a greeting may stay up
+const MAX_GREETING_SEC: u32 = 30;
+
+fn default_greeting_sec() -> u32 {
+    DEFAULT_GREETING_SEC
+}
+
+/// One step of a knob's startup macro, run in order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BootStep {
+    /// Select a zone; without `zone_id`, the zone the knob last reported
+    SelectZone {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        zone_id: Option<String>,
+    },
+    /// Set display brightness, 1 to 100 percent
+    Brightness { percent: u8 },
+    /// Show a line of text before the now playing screen
+    Greeting {
+        text: String,
+        #[serde(default = "default_greeting_sec")]
+        duration_sec: u32,
+    },
+}
+
+impl BootStep {
+    /// The step with values brought into range; `None` for a step that does
+    /// nothing (an empty greeting)
+    fn normalized(self) -> Option<Self> {
+        match self {
+            Self::SelectZone { zone_id } => Some(Self::SelectZone {
+                zone_id: zone_id
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty()),
+            }),
+            Self::Brightness { percent } => Some(Self::Brightness {
+                percent: percent.clamp(1, 100),
+            }),
+            Self::Greeting { text, duration_sec } => {
+                let text: String = text.trim().chars().take(MAX_GREETING_LEN).collect();
+                (!text.is_empty()).then_some(Self::Greeting {
+                    text,
+                    duration_sec: duration_sec.clamp(1, MAX_GREETING_SEC),
+                })
+            }
+        }
+    }
+}
+
+/// Steps as stored: in range, empty ones dropped, at most [`MAX_BOOT_STEPS`]
+pub fn normalize_steps(steps: Vec<BootStep>) -> Vec<BootStep> {
+    steps
+        .into_iter()
+        .filter_map(BootStep::normalized)
+        .take(MAX_BOOT_STEPS)
+        .collect()
+}
+
+/// A step as served to the knob, with its zone resolved
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BootAction {
+    SelectZone { zone_id: String, zone_name: String },
+    Brightness { percent: u8 },
+    Greeting { text: String, duration_sec: u32 },
+}
+
+/// Resolve stored steps for serving. `last_zone` is the zone the knob last
+/// reported; `zone_name` looks up a zone that still exists. Zone steps whose
+/// zone is unknown or gone are dropped, so the knob falls back to its own
+/// default.
+pub fn resolve_steps(
+    steps: &[BootStep],
+    last_zone: Option<&str>,
+    zone_name: impl Fn(&str) -> Option<String>,
+) -> Vec<BootAction> {
+    steps
+        .iter()
+        .filter_map(|step| match step {
+            BootStep::SelectZone { zone_id } => {
+                let zone_id = zone_id.as_deref().or(last_zone)?;
+                zone_name(zone_id).map(|zone_name| BootAction::SelectZone {
+                    zone_id: zone_id.to_string(),
+                    zone_name,
+                })
+            }
+            BootStep::Brightness { percent } => Some(BootAction::Brightness { percent: *percent }),
+            BootStep::Greeting { text, duration_sec } => Some(BootAction::Greeting {
+                text: text.clone(),
+                duration_sec: *duration_sec,
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_resolve() {
+        let steps: Vec<BootStep> = serde_json::from_value(serde_json::json!([
+            { "action": "select_zone" },
+            { "action": "brightness", "percent": 0 },
+            { "action": "greeting", "text": "  " },
+            { "action": "greeting", "text": "Good evening" },
+        ]))
+        .unwrap();
+        let steps = normalize_steps(steps);
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[1], BootStep::Brightness { percent: 1 });
+
+        let name = |id: &str| (id == "roon:1").then(|| "Study".to_string());
+        let actions = resolve_steps(&steps, Some("roon:1"), name);
+        assert_eq!(
+            actions[0],
+            BootAction::SelectZone {
+                zone_id: "roon:1".to_string(),
+                zone_name: "Study".to_string(),
+            }
+        );
+        assert_eq!(
+            actions[2],
+            BootAction::Greeting {
+                text: "Good evening".to_string(),
+                duration_sec: DEFAULT_GREETING_SEC,
+            }
+        );
+        // Gone zone: the step is left out
+        assert_eq!(resolve_steps(&steps, Some("lms:aa"), name).len(), 2);
+    }
+}
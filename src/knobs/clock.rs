@@ -0,0 +1,235 @@
+//! Local time and locale for knob clocks
+//!
+//! A knob only knows UTC (from NTP), so the bridge serves its time zone and
+//! display preferences with the config. Each setting can be stored per knob;
+//! unset ones come from the bridge host, so a knob on the same network shows
+//! local time without configuration.
+//!
+//! The zone is served as a POSIX TZ string, the form ESP-IDF takes in
+//! `setenv("TZ", ...)`. It is read from the footer of the host's tzdata file,
+//! so DST rules travel with it and the knob switches on its own.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::store::KnobConfig;
+
+/// NTP server suggested to knobs without one configured
+pub const DEFAULT_NTP_SERVER: &str = "pool.ntp.org";
+
+/// Locale used when neither the knob nor the host has one
+const DEFAULT_LOCALE: &str = "en-US";
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+/// Regions that default to a 12-hour clock; everywhere else gets 24-hour
+const TWELVE_HOUR_REGIONS: &[&str] = &[
+    "US", "CA", "AU", "NZ", "IN", "PH", "PK", "BD", "EG", "SA", "MY", "CO",
+];
+
+/// Time settings served to a knob as `time` in its config
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KnobTime {
+    /// IANA zone name, when known
+    pub timezone: Option<String>,
+    /// POSIX TZ string for `setenv("TZ")`, e.g. `CET-1CEST,M3.5.0,M10.5.0/3`
+    pub posix_tz: String,
+    /// BCP 47 locale for day and month names, e.g. `en-GB`
+    pub locale: String,
+    pub clock_24h: bool,
+    pub ntp_server: String,
+    /// Bridge clock (Unix seconds), for a first reading before NTP answers
+    pub server_time: i64,
+}
+
+impl KnobTime {
+    /// Resolve a knob's time settings, filling gaps from the host
+    pub fn for_knob(config: &KnobConfig) -> Self {
+        let timezone = config.timezone.clone().or_else(host_timezone);
+        let posix_tz = timezone
+            .as_deref()
+            .and_then(zone_posix_tz)
+            .or_else(host_posix_tz)
+            .unwrap_or_else(|| posix_from_offset(chrono::Local::now().offset().local_minus_utc()));
+        let locale = config
+            .locale
+            .clone()
+            .or_else(host_locale)
+            .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+        let clock_24h = config
+            .clock_24h
+            .unwrap_or_else(|| default_clock_24h(&locale));
+        Self {
+            timezone,
+            posix_tz,
+            locale,
+            clock_24h,
+            ntp_server: config
+                .ntp_server
+                .clone()
+                .unwrap_or_else(|| DEFAULT_NTP_SERVER.to_string()),
+            server_time: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Host zone from `TZ`, `/etc/timezone` or the `/etc/localtime` symlink
+fn host_timezone() -> Option<String> {
+    let from_env = std::env::var("TZ")
+        .ok()
+        .map(|tz| tz.trim_start_matches(':').to_string())
+        .filter(|tz| is_zone_name(tz));
+    from_env
+        .or_else(|| {
+            std::fs::read_to_string("/etc/timezone")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|tz| is_zone_name(tz))
+        })
+        .or_else(|| {
+            let target = std::fs::read_link("/etc/localtime").ok()?;
+            let target = target.to_string_lossy();
+            let (_, zone) = target.split_once("zoneinfo/")?;
+            Some(zone.to_string()).filter(|tz| is_zone_name(tz))
+        })
+}
+
+/// POSIX rule of the host's zone when it has no IANA name (a POSIX `TZ`
+/// value, or a copied `/etc/localtime`)
+fn host_posix_tz() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        // `EST5EDT` style names are valid either way; `Etc/GMT+5` is not POSIX
+        let posix = !is_zone_name(&tz) || !tz.contains('/');
+        if posix && !tz.starts_with(':') && tz.chars().any(|c| c.is_ascii_digit()) {
+            return Some(tz);
+        }
+    }
+    std::fs::read("/etc/localtime")
+        .ok()
+        .and_then(|data| tzif_footer(&data))
+}
+
+fn zone_posix_tz(zone: &str) -> Option<String> {
+    if !is_zone_name(zone) {
+        return None;
+    }
+    std::fs::read(Path::new(ZONEINFO_DIR).join(zone))
+        .ok()
+        .and_then(|data| tzif_footer(&data))
+}
+
+/// Plausible IANA name: `Europe/Berlin`, `UTC`, `Etc/GMT+5`; never a path
+/// outside the zoneinfo directory
+pub fn is_zone_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('/')
+        && !name
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'))
+}
+
+/// POSIX TZ string from the footer of a TZif v2+ file (the last line)
+fn tzif_footer(data: &[u8]) -> Option<String> {
+    if !data.starts_with(b"TZif") || data.get(4).is_none_or(|v| *v < b'2') {
+        return None;
+    }
+    let body = data.strip_suffix(b"\n")?;
+    let start = body.iter().rposition(|b| *b == b'\n')? + 1;
+    let footer = std::str::from_utf8(&body[start..]).ok()?;
+    (!footer.is_empty() && footer.chars().all(|c| c.is_ascii_graphic())).then(|| footer.to_string())
+}
+
+/// Fixed-offset POSIX TZ string (POSIX counts hours west of UTC)
+fn posix_from_offset(offset_secs: i32) -> String {
+    if offset_secs == 0 {
+        return "UTC0".to_string();
+    }
+    let (east, secs) = (offset_secs > 0, offset_secs.unsigned_abs());
+    let (hours, minutes) = (secs / 3600, secs % 3600 / 60);
+    let name = format!(
+        "<{}{:02}{:02}>",
+        if east { '+' } else { '-' },
+        hours,
+        minutes
+    );
+    let sign = if east { "-" } else { "" };
+    if minutes == 0 {
+        format!("{}{}{}", name, sign, hours)
+    } else {
+        format!("{}{}{}:{:02}", name, sign, hours, minutes)
+    }
+}
+
+/// Host locale from `LC_ALL`, `LC_TIME` or `LANG` (`en_GB.UTF-8` -> `en-GB`)
+fn host_locale() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|value| posix_locale_to_bcp47(&value))
+}
+
+fn posix_locale_to_bcp47(value: &str) -> Option<String> {
+    let base = value.split(['.', '@']).next().unwrap_or_default();
+    if base.is_empty() || base == "C" || base == "POSIX" {
+        return None;
+    }
+    Some(base.replace('_', "-"))
+}
+
+fn default_clock_24h(locale: &str) -> bool {
+    let region = locale.split('-').nth(1).unwrap_or_default();
+    !TWELVE_HOUR_REGIONS
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(region))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tzif_footer() {
+        let mut data = b"TZif2".to_vec();
+        data.extend_from_slice(&[0, 1, 2, b'\n']);
+        data.extend_from_slice(b"\nCET-1CEST,M3.5.0,M10.5.0/3\n");
+        assert_eq!(
+            tzif_footer(&data).as_deref(),
+            Some("CET-1CEST,M3.5.0,M10.5.0/3")
+        );
+        // Version 1 files have no footer
+        assert_eq!(tzif_footer(b"TZif\0\0\nUTC0\n"), None);
+    }
+
+    #[test]
+    fn test_posix_from_offset() {
+        assert_eq!(posix_from_offset(0), "UTC0");
+        assert_eq!(posix_from_offset(3600), "<+0100>-1");
+        assert_eq!(posix_from_offset(-5 * 3600), "<-0500>5");
+        assert_eq!(posix_from_offset(19800), "<+0530>-5:30");
+    }
+
+    #[test]
+    fn test_locale_and_clock_defaults() {
+        assert_eq!(
+            posix_locale_to_bcp47("en_GB.UTF-8").as_deref(),
+            Some("en-GB")
+        );
+        assert_eq!(posix_locale_to_bcp47("C.UTF-8"), None);
+        assert!(!default_clock_24h("en-US"));
+        assert!(default_clock_24h("de-DE"));
+        assert!(default_clock_24h("fr"));
+    }
+
+    #[test]
+    fn test_zone_names() {
+        assert!(is_zone_name("Europe/Berlin"));
+        assert!(is_zone_name("Etc/GMT+5"));
+        assert!(!is_zone_name("../../etc/passwd"));
+        assert!(!is_zone_name("/etc/localtime"));
+        assert!(!is_zone_name("CET-1CEST,M3.5.0"));
+    }
+}
@@ -0,0 +1,159 @@
+//! Binary response encodings for the hot knob endpoints
+//!
+//! ESP32 firmware polls `/zones` and `/now_playing` every few seconds, and JSON
+//! parsing is a noticeable share of each poll on the device. Clients can send
+//! `Accept: application/cbor` or `Accept: application/msgpack` to receive the same
+//! structure in a binary encoding instead. JSON remains the default.
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue},
+    response::Response,
+};
+use serde::Serialize;
+
+use crate::http_cache::{body_with_etag, json_with_etag};
+
+/// Wire format selected from the request's `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl ResponseFormat {
+    /// Pick the first binary media type the client lists; anything else means JSON.
+    /// Entries with `q=0` are treated as refused.
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|entry| {
+                let mut parts = entry.split(';').map(str::trim);
+                let media_type = parts.next()?.to_ascii_lowercase();
+                let refused = parts.any(|p| {
+                    p.strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                });
+                if refused {
+                    return None;
+                }
+                match media_type.as_str() {
+                    "application/cbor" => Some(Self::Cbor),
+                    "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                        Some(Self::MessagePack)
+                    }
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or(Self::Json)
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+}
+
+/// Serialize `value` in the format the client asked for, with an ETag.
+/// Falls back to JSON if binary encoding fails.
+pub fn negotiated_with_etag<T: Serialize>(headers: &HeaderMap, value: &T) -> Response {
+    let format = ResponseFormat::from_accept(headers);
+    let encoded = match format {
+        ResponseFormat::Json => Ok(None),
+        ResponseFormat::Cbor => {
+            let mut buf = Vec::new();
+            ciborium::into_writer(value, &mut buf)
+                .map(|_| Some(buf))
+                .map_err(|e| e.to_string())
+        }
+        // Named fields keep the map layout of the JSON response
+        ResponseFormat::MessagePack => rmp_serde::to_vec_named(value)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+    };
+
+    let mut response = match encoded {
+        Ok(Some(body)) => body_with_etag(headers, format.content_type(), body),
+        Ok(None) => json_with_etag(headers, value),
+        Err(e) => {
+            tracing::warn!(
+                "{} encoding failed, sending JSON: {}",
+                format.content_type(),
+                e
+            );
+            json_with_etag(headers, value)
+        }
+    };
+    response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_format_from_accept() {
+        assert_eq!(
+            ResponseFormat::from_accept(&HeaderMap::new()),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(&accept("*/*")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(&accept("application/cbor")),
+            ResponseFormat::Cbor
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(&accept("application/x-msgpack, application/json")),
+            ResponseFormat::MessagePack
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(&accept("application/cbor;q=0, application/json")),
+            ResponseFormat::Json
+        );
+    }
+
+    #[tokio::test]
+    async fn test_binary_bodies_round_trip() {
+        let value = serde_json::json!({ "zones": [{ "zone_id": "roon:1", "volume": 42 }] });
+
+        let response = negotiated_with_etag(&accept("application/cbor"), &value);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/cbor"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let decoded: serde_json::Value = ciborium::from_reader(body.as_ref()).unwrap();
+        assert_eq!(decoded, value);
+
+        let response = negotiated_with_etag(&accept("application/msgpack"), &value);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let decoded: serde_json::Value = rmp_serde::from_slice(&body).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
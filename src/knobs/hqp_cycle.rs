@@ -0,0 +1,87 @@
+//! HQPlayer filter cycling from a knob gesture (`/knob/hqp_cycle`)
+//!
+//! Rotation keeps adjusting volume; the configured gesture steps the zone's
+//! HQPlayer filter or shaper through the options HQPlayer reports, and the
+//! knob shows the `display` text it gets back.
+
+use serde::{Deserialize, Serialize};
+
+/// Knob gesture that cycles, handled on the knob
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HqpCycleGesture {
+    #[default]
+    Off,
+    DoublePress,
+    LongPress,
+}
+
+/// HQPlayer setting a knob cycles
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HqpCycleSetting {
+    /// Upsampling filter, applied to 1x and Nx rates alike
+    #[default]
+    Filter,
+    /// Noise shaper (or dither in PCM modes)
+    Shaper,
+}
+
+impl HqpCycleSetting {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Filter => "Filter",
+            Self::Shaper => "Shaper",
+        }
+    }
+}
+
+/// Per-knob cycling mode, stored in the knob config
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HqpCycleConfig {
+    pub gesture: HqpCycleGesture,
+    pub setting: HqpCycleSetting,
+}
+
+impl HqpCycleConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleDirection {
+    #[default]
+    Next,
+    Previous,
+}
+
+/// Position after `current` in a list of `len` options, wrapping around;
+/// starts from the first option when the current one isn't in the list.
+/// `None` when the list is empty.
+pub fn cycle_index(len: usize, current: Option<usize>, direction: CycleDirection) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current, direction) {
+        (Some(i), CycleDirection::Next) => (i + 1) % len,
+        (Some(i), CycleDirection::Previous) => (i % len + len - 1) % len,
+        (None, _) => 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_index_wraps() {
+        assert_eq!(cycle_index(3, Some(0), CycleDirection::Next), Some(1));
+        assert_eq!(cycle_index(3, Some(2), CycleDirection::Next), Some(0));
+        assert_eq!(cycle_index(3, Some(0), CycleDirection::Previous), Some(2));
+        assert_eq!(cycle_index(3, None, CycleDirection::Previous), Some(0));
+        assert_eq!(cycle_index(0, None, CycleDirection::Next), None);
+    }
+}
@@ -6,10 +6,19 @@
 //! - SVG rasterization (via resvg)
 //! - Image resizing (bilinear)
 //! - RGB565 conversion (little-endian for ESP32)
-
-use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageFormat};
+//! - Re-encoding artwork as JPEG/PNG/WebP for other clients (`format=`/`quality=`)
+
+use image::{
+    codecs::{jpeg::JpegEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+    DynamicImage, ImageFormat, ImageReader,
+};
+use serde::Deserialize;
 use std::io::Cursor;
 
+/// JPEG quality when re-encoding without an explicit `quality=`
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
 /// RGB565 image data for LCD display
 pub struct Rgb565Image {
     pub data: Vec<u8>,
@@ -26,12 +35,7 @@ pub fn jpeg_to_rgb565(
     target_width: u32,
     target_height: u32,
 ) -> Result<Rgb565Image, image::ImageError> {
-    // Check if it's SVG (starts with '<' after optional whitespace/BOM)
-    let trimmed = image_data
-        .iter()
-        .find(|&&b| b != 0xEF && b != 0xBB && b != 0xBF && !b.is_ascii_whitespace());
-
-    if trimmed == Some(&b'<') {
+    if is_svg(image_data) {
         // Try SVG rasterization
         if let Ok(rgb565) = svg_to_rgb565(image_data, target_width, target_height) {
             return Ok(rgb565);
@@ -194,6 +198,162 @@ pub fn resize_jpeg(
     Ok(output.into_inner())
 }
 
+/// Whether the data looks like SVG (starts with '<' after optional whitespace/BOM)
+fn is_svg(data: &[u8]) -> bool {
+    data.iter()
+        .find(|&&b| b != 0xEF && b != 0xBB && b != 0xBF && !b.is_ascii_whitespace())
+        == Some(&b'<')
+}
+
+/// Content type from the image bytes, falling back to the declared type.
+///
+/// Renderers often label artwork `application/octet-stream` or `text/plain`,
+/// which browsers and the knob firmware won't display.
+pub fn sniff_content_type(data: &[u8], declared: Option<&str>) -> String {
+    if let Ok(format) = image::guess_format(data) {
+        return format.to_mime_type().to_string();
+    }
+    if is_svg(data) {
+        return "image/svg+xml".to_string();
+    }
+    declared
+        .filter(|ct| ct.starts_with("image/"))
+        .unwrap_or("image/jpeg")
+        .to_string()
+}
+
+/// Artwork encoding requested by a client (`format=` query parameter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[serde(alias = "jpg")]
+    Jpeg,
+    Png,
+    /// Lossless; `quality` is ignored
+    Webp,
+    /// Raw little-endian RGB565 for ESP32 LCDs, resized to exactly the requested box
+    Rgb565,
+}
+
+impl OutputFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+            Self::Rgb565 => "application/octet-stream",
+        }
+    }
+
+    fn from_image_format(format: ImageFormat) -> Option<Self> {
+        match format {
+            ImageFormat::Jpeg => Some(Self::Jpeg),
+            ImageFormat::Png => Some(Self::Png),
+            ImageFormat::WebP => Some(Self::Webp),
+            _ => None,
+        }
+    }
+}
+
+/// Size and encoding requested for a piece of artwork
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArtworkOptions {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: Option<OutputFormat>,
+    /// JPEG quality 1-100
+    pub quality: Option<u8>,
+}
+
+impl ArtworkOptions {
+    /// Bounding box, square when only one side is given (matches adapter behavior)
+    pub fn bounds(&self) -> Option<(u32, u32)> {
+        match (self.width, self.height) {
+            (Some(w), Some(h)) => Some((w, h)),
+            (Some(w), None) => Some((w, w)),
+            (None, Some(h)) => Some((h, h)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Re-encoded artwork
+pub struct EncodedImage {
+    pub content_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// Resize and re-encode artwork from any backend.
+///
+/// Images larger than the requested box are scaled down to fit (aspect ratio
+/// kept); smaller ones are never scaled up. Returns `None` when the original
+/// already satisfies the request and can be served as-is, which is the common
+/// case for backends that resize themselves (Roon, LMS). SVG passes through
+/// unless RGB565 is requested.
+pub fn encode_artwork(
+    data: &[u8],
+    options: &ArtworkOptions,
+) -> Result<Option<EncodedImage>, image::ImageError> {
+    if options.format == Some(OutputFormat::Rgb565) {
+        let (width, height) = options.bounds().unwrap_or((240, 240));
+        let rgb565 = jpeg_to_rgb565(data, width, height)?;
+        return Ok(Some(EncodedImage {
+            content_type: OutputFormat::Rgb565.content_type(),
+            data: rgb565.data,
+        }));
+    }
+    if is_svg(data) {
+        return Ok(None);
+    }
+
+    let source = image::guess_format(data)?;
+    let target = options
+        .format
+        .or_else(|| OutputFormat::from_image_format(source))
+        .unwrap_or(OutputFormat::Jpeg);
+
+    let (src_width, src_height) = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()?
+        .into_dimensions()?;
+    let oversized = options
+        .bounds()
+        .is_some_and(|(w, h)| src_width > w || src_height > h);
+    let reencode = OutputFormat::from_image_format(source) != Some(target)
+        || (options.quality.is_some() && target == OutputFormat::Jpeg);
+    if !oversized && !reencode {
+        return Ok(None);
+    }
+
+    let mut img = image::load_from_memory(data)?;
+    if let Some((w, h)) = options.bounds().filter(|_| oversized) {
+        img = img.resize(w, h, FilterType::Triangle);
+    }
+
+    let mut output = Cursor::new(Vec::new());
+    match target {
+        OutputFormat::Jpeg => {
+            let quality = options
+                .quality
+                .unwrap_or(DEFAULT_JPEG_QUALITY)
+                .clamp(1, 100);
+            let encoder = JpegEncoder::new_with_quality(&mut output, quality);
+            DynamicImage::ImageRgb8(img.to_rgb8()).write_with_encoder(encoder)?;
+        }
+        OutputFormat::Png => img.write_to(&mut output, ImageFormat::Png)?,
+        OutputFormat::Webp => {
+            let encoder = WebPEncoder::new_lossless(&mut output);
+            DynamicImage::ImageRgba8(img.to_rgba8()).write_with_encoder(encoder)?;
+        }
+        // Exact-size RGB565 is handled above; this only keeps the match total
+        OutputFormat::Rgb565 => output.get_mut().extend(rgba_to_rgb565(&img)),
+    }
+
+    Ok(Some(EncodedImage {
+        content_type: target.content_type(),
+        data: output.into_inner(),
+    }))
+}
+
 /// Generate placeholder SVG for missing album art
 pub fn placeholder_svg(width: u32, height: u32) -> String {
     format!(
@@ -292,6 +452,79 @@ mod tests {
         assert_eq!(rgb565.data.len(), 240 * 240 * 2);
     }
 
+    fn red_png(width: u32, height: u32) -> Vec<u8> {
+        let img = image::RgbaImage::from_pixel(width, height, image::Rgba([255, 0, 0, 255]));
+        let mut png_data = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png_data)
+            .write_image(&img, width, height, image::ExtendedColorType::Rgba8)
+            .unwrap();
+        png_data
+    }
+
+    #[test]
+    fn test_encode_artwork_passthrough_when_satisfied() {
+        let png = red_png(4, 4);
+        let options = ArtworkOptions {
+            width: Some(8),
+            ..Default::default()
+        };
+        assert!(encode_artwork(&png, &options).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_encode_artwork_downscales_and_converts() {
+        let png = red_png(8, 4);
+        let options = ArtworkOptions {
+            width: Some(4),
+            format: Some(OutputFormat::Jpeg),
+            quality: Some(50),
+            ..Default::default()
+        };
+        let encoded = encode_artwork(&png, &options).unwrap().unwrap();
+        assert_eq!(encoded.content_type, "image/jpeg");
+        let decoded = image::load_from_memory(&encoded.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 2));
+    }
+
+    #[test]
+    fn test_encode_artwork_webp_and_rgb565() {
+        let png = red_png(4, 4);
+        let webp = encode_artwork(
+            &png,
+            &ArtworkOptions {
+                format: Some(OutputFormat::Webp),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(image::guess_format(&webp.data).unwrap(), ImageFormat::WebP);
+
+        let rgb565 = encode_artwork(
+            &png,
+            &ArtworkOptions {
+                width: Some(2),
+                height: Some(2),
+                format: Some(OutputFormat::Rgb565),
+                quality: None,
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(rgb565.data.len(), 8);
+    }
+
+    #[test]
+    fn test_sniff_content_type() {
+        assert_eq!(
+            sniff_content_type(&red_png(1, 1), Some("application/octet-stream")),
+            "image/png"
+        );
+        assert_eq!(sniff_content_type(b"<svg/>", None), "image/svg+xml");
+        assert_eq!(sniff_content_type(b"??", Some("image/gif")), "image/gif");
+        assert_eq!(sniff_content_type(b"??", Some("text/plain")), "image/jpeg");
+    }
+
     #[test]
     fn test_png_to_rgb565() {
         // Create a 2x2 red PNG programmatically
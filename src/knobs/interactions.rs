@@ -0,0 +1,103 @@
+//! Knob interactions published on the bus (`knob_*` events)
+//!
+//! Lets webhook receivers and home automation react to a knob being
+//! pressed, turned, pointed at another zone, or plugged in. Inputs come from
+//! `/knob/control`; zone and battery transitions from the status a knob
+//! sends with each `/knob/now_playing` poll.
+
+use super::store::KnobStatus;
+use crate::bus::{BusEvent, KnobGesture};
+
+/// Battery level below which a knob counts as low
+pub const LOW_BATTERY_LEVEL: u8 = 20;
+
+/// Gesture and volume steps behind a control action, for knobs that don't
+/// report the gesture themselves
+pub fn gesture_for_action(action: &str) -> (KnobGesture, Option<i32>) {
+    match action {
+        "vol_up" | "volume_up" => (KnobGesture::Rotate, Some(1)),
+        "vol_down" | "volume_down" => (KnobGesture::Rotate, Some(-1)),
+        "vol_abs" | "volume" => (KnobGesture::Rotate, None),
+        _ => (KnobGesture::Press, None),
+    }
+}
+
+fn is_low(status: &KnobStatus) -> bool {
+    status.battery_charging != Some(true)
+        && status
+            .battery_level
+            .is_some_and(|level| level < LOW_BATTERY_LEVEL)
+}
+
+/// Events for what changed between two status reports from a knob
+pub fn status_events(
+    knob_id: &str,
+    knob_name: Option<&str>,
+    before: &KnobStatus,
+    after: &KnobStatus,
+) -> Vec<BusEvent> {
+    let mut events = Vec::new();
+    if let (Some(from), Some(zone_id)) = (&before.zone_id, &after.zone_id) {
+        if from != zone_id {
+            events.push(BusEvent::KnobZoneChanged {
+                knob_id: knob_id.to_string(),
+                knob_name: knob_name.map(String::from),
+                from: Some(from.clone()),
+                zone_id: zone_id.clone(),
+            });
+        }
+    }
+
+    let charging_changed = before.battery_charging.is_some()
+        && after.battery_charging.is_some()
+        && before.battery_charging != after.battery_charging;
+    // Only crossings between two known levels; a first report isn't a change
+    let low_changed = before.battery_level.is_some()
+        && after.battery_level.is_some()
+        && is_low(before) != is_low(after);
+    if charging_changed || low_changed {
+        events.push(BusEvent::KnobBatteryChanged {
+            knob_id: knob_id.to_string(),
+            knob_name: knob_name.map(String::from),
+            battery_level: after.battery_level,
+            charging: after.battery_charging == Some(true),
+            low: is_low(after),
+        });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(zone_id: &str, level: u8, charging: bool) -> KnobStatus {
+        KnobStatus {
+            battery_level: Some(level),
+            battery_charging: Some(charging),
+            zone_id: Some(zone_id.to_string()),
+            ip: None,
+        }
+    }
+
+    #[test]
+    fn test_status_events() {
+        let idle = status("roon:1", 80, false);
+        assert!(status_events("k", None, &idle, &idle).is_empty());
+        assert!(status_events("k", None, &KnobStatus::default(), &idle).is_empty());
+
+        let types = |after: &KnobStatus| -> Vec<&'static str> {
+            status_events("k", Some("Bedroom"), &idle, after)
+                .iter()
+                .map(BusEvent::event_type)
+                .collect()
+        };
+        assert_eq!(types(&status("lms:2", 80, false)), ["knob_zone_changed"]);
+        assert_eq!(types(&status("roon:1", 79, true)), ["knob_battery_changed"]);
+        assert_eq!(
+            types(&status("roon:1", 15, false)),
+            ["knob_battery_changed"]
+        );
+        assert!(types(&status("roon:1", 50, false)).is_empty());
+    }
+}
@@ -8,12 +8,27 @@
 //!
 //! This module provides:
 //! - Device store (registration, config, status tracking)
+//! - Local time zone, clock format and locale for the knob's clock
+//! - Art mode slideshow sources (recent covers, an image folder, artist images)
+//! - HQPlayer filter/shaper cycling from a knob gesture
+//! - Startup macros served to knobs on boot
+//! - Bus events for knob presses, rotation, zone and battery changes
 //! - Hardware API endpoints (/now_playing, /control, /config)
+//! - Zone-scoped event stream with artwork change hints for knob displays
+//! - CBOR/MessagePack encodings of the hot polling endpoints
 //! - RGB565 image conversion for LCD display
 
+pub mod art_mode;
+pub mod boot;
+pub mod clock;
+pub mod encoding;
+pub mod hqp_cycle;
 pub mod image;
+pub mod interactions;
 pub mod routes;
 pub mod store;
+pub mod stream;
 
 pub use routes::*;
 pub use store::KnobStore;
+pub use stream::knob_events_handler;
@@ -4,31 +4,58 @@
 //! - GET /knob/zones - List available zones
 //! - GET /knob/now_playing - Current playback state + album art URL
 //! - GET /knob/now_playing/image - Album art (JPEG or RGB565)
+//! - GET /knob/events - Zone-scoped SSE hints (see [`crate::knobs::stream`])
+//! - GET /knob/art_mode/next - Next image for the art mode slideshow
+//! - POST /knob/hqp_cycle - Step the zone's HQPlayer filter or shaper
 //! - POST /knob/control - Playback control commands
 //! - GET /knob/config - Get device configuration
 //! - POST /knob/config - Update device configuration
 //! - GET /knob/devices - List registered knobs (admin)
+//! - POST /firmware/report - OTA update outcome
+//! - GET /firmware/fleet - Firmware versions and update results across knobs
+//! - GET/PUT /api/knobs/auth - Whether knobs must present device tokens (admin)
+//! - POST/DELETE /api/knobs/{id}/token - Rotate or revoke a device token (admin)
+//!
+//! `/zones` and `/rooms` share the zone list built here, and
+//! `/zones/{zone_id}/artwork` the artwork handling.
 
 use std::net::SocketAddr;
 
 use axum::{
     body::Body,
-    extract::{ConnectInfo, Query, State},
-    http::{header, HeaderMap, StatusCode},
-    response::Response,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 
 use sha2::{Digest, Sha256};
 
-use crate::api::AppState;
-use crate::bus::VolumeControl;
-use crate::knobs::image::placeholder_svg;
-use crate::knobs::store::{KnobConfigUpdate, KnobStatusUpdate};
+use crate::adapters::lms::DISPLAY_MESSAGE_DURATION;
+use crate::api::{select_fields, select_zone, ApiError, AppState, Caller, ErrorCode, ListQuery};
+use crate::bus::{
+    BusEvent, ImageData, KnobGesture, PrefixedZoneId, VolumeControl, VolumeLeveling,
+    ZoneCapabilities, ZoneMetadata,
+};
+use crate::http_cache::{json_with_etag, Validators, CACHE_REVALIDATE};
+use crate::knobs::art_mode::{self, ArtModeSource};
+use crate::knobs::boot::resolve_steps;
+use crate::knobs::clock::KnobTime;
+use crate::knobs::encoding::negotiated_with_etag;
+use crate::knobs::hqp_cycle::{cycle_index, CycleDirection, HqpCycleSetting};
+use crate::knobs::image::{placeholder_svg, ArtworkOptions, OutputFormat};
+use crate::knobs::interactions::{gesture_for_action, status_events};
+use crate::knobs::store::{
+    Knob, KnobConfigUpdate, KnobStatus, KnobStatusUpdate, KnobSummary, UpdateReport,
+};
+use crate::knobs::stream::image_version;
+use crate::scenes::PartyScene;
+use crate::selection::knob_client;
+use crate::tokens::is_token;
 
 /// Extract knob ID from headers or query params
-fn extract_knob_id(headers: &HeaderMap, query_knob_id: Option<&str>) -> Option<String> {
+pub(crate) fn extract_knob_id(headers: &HeaderMap, query_knob_id: Option<&str>) -> Option<String> {
     headers
         .get("x-knob-id")
         .or_else(|| headers.get("x-device-id"))
@@ -37,6 +64,66 @@ fn extract_knob_id(headers: &HeaderMap, query_knob_id: Option<&str>) -> Option<S
         .or_else(|| query_knob_id.map(|s| s.to_string()))
 }
 
+/// Check the device token of a request, `knob_id` being the knob it identifies as.
+///
+/// A named (`uhc_`) bearer token is always checked, which also completes a
+/// pending rotation. While device tokens are required, requests identifying as
+/// a knob need a token for that knob; otherwise a knob without a token is
+/// issued one, delivered by `GET /config/{knob_id}`. Requests that neither
+/// identify as a knob nor carry a token (the web UI) are not affected.
+pub(crate) fn authorize_knob(
+    state: &AppState,
+    headers: &HeaderMap,
+    knob_id: Option<&str>,
+) -> Result<(), ApiError> {
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .filter(|t| is_token(t));
+    let required = state.tokens.knob_tokens_required();
+
+    match presented.map(|secret| state.tokens.authenticate_token(secret)) {
+        Some(Some(token)) if token.allows_knob(knob_id) => return Ok(()),
+        Some(Some(_)) if required => {
+            return Err(ApiError::new(
+                ErrorCode::Forbidden,
+                "Token is not valid for this knob",
+            ))
+        }
+        Some(None) if required => {
+            return Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Invalid or revoked device token",
+            ))
+        }
+        None if required && knob_id.is_some() => {
+            return Err(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Device token required",
+            ))
+        }
+        _ => {}
+    }
+
+    if let Some(id) = knob_id {
+        if let Err(e) = state.tokens.ensure_knob_token(id) {
+            tracing::warn!("Failed to issue device token for knob {}: {}", id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Low bandwidth profile: artwork is served at this fraction of the requested size
+const LOW_BANDWIDTH_IMAGE_DIVISOR: u32 = 2;
+/// Low bandwidth profile: JPEG quality when the client doesn't ask for one
+const LOW_BANDWIDTH_JPEG_QUALITY: u8 = 50;
+/// Low bandwidth profile: now_playing poll interval hint for the device
+const LOW_BANDWIDTH_POLL_INTERVAL_MS: u32 = 3000;
+/// Low bandwidth profile: optional now_playing fields left out of the response
+/// (the zone list is still available from /knob/zones when `zones_sha` changes)
+const LOW_BANDWIDTH_STRIPPED_FIELDS: &[&str] = &["zones", "image_key", "seek_position", "length"];
+
 /// Format IP address, converting IPv4-mapped IPv6 to plain IPv4
 fn format_ip(ip: std::net::IpAddr) -> String {
     match ip {
@@ -99,21 +186,84 @@ pub struct ZoneInfo {
     pub volume_control: Option<VolumeControl>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dsp: Option<DspInfo>,
+    /// User-assigned room, icon, order and color
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ZoneMetadata>,
+    /// Controls the backend supports; firmware hides the rest
+    pub capabilities: ZoneCapabilities,
+    /// ReplayGain mode, for backends that expose one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_leveling: Option<VolumeLeveling>,
 }
 
-/// GET /knob/zones response
+/// GET /knob/zones response; zones are JSON values when `fields=` trims them
 #[derive(Serialize)]
-pub struct ZonesResponse {
-    pub zones: Vec<ZoneInfo>,
+pub struct ZonesResponse<Z = ZoneInfo> {
+    pub zones: Vec<Z>,
+    /// Room membership, for clients that show zones grouped by room.
+    /// Zones are already ordered so each room's zones are contiguous.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rooms: Vec<RoomZoneIds>,
+    /// Zones in all pages; only for paged requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    /// `offset` of the next page; only for paged requests with more zones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
 }
 
 /// GET /knob/zones - List all zones from all adapters
+/// Supports `If-None-Match` (304 when the zone list is unchanged) and CBOR/MessagePack
+/// via `Accept` (see [`crate::knobs::encoding`]). `limit`/`offset` page the
+/// list and `fields` trims each zone (see [`crate::api::ListQuery`]); `rooms`
+/// then covers only the zones on the page.
 pub async fn knob_zones_handler(
     State(state): State<AppState>,
-    _headers: HeaderMap,
-) -> Json<ZonesResponse> {
+    headers: HeaderMap,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    if let Err(e) = authorize_knob(&state, &headers, extract_knob_id(&headers, None).as_deref()) {
+        return e.into_response();
+    }
     let zones = get_all_zones_internal(&state).await;
-    Json(ZonesResponse { zones })
+    let (rooms, _) = group_by_room(zones.clone());
+    let (zones, page) = query.page(zones);
+    let rooms = rooms
+        .into_iter()
+        .map(|r| RoomZoneIds {
+            room: r.room,
+            zone_ids: r
+                .zones
+                .into_iter()
+                .map(|z| z.zone_id)
+                .filter(|id| !query.is_paged() || zones.iter().any(|z| &z.zone_id == id))
+                .collect(),
+        })
+        .filter(|r| !r.zone_ids.is_empty())
+        .collect();
+    match query.fields() {
+        None => negotiated_with_etag(
+            &headers,
+            &ZonesResponse {
+                zones,
+                rooms,
+                total: page.total,
+                next_offset: page.next_offset,
+            },
+        ),
+        Some(fields) => negotiated_with_etag(
+            &headers,
+            &ZonesResponse {
+                zones: zones
+                    .iter()
+                    .map(|z| select_fields(z, &fields, "zone_id"))
+                    .collect(),
+                rooms,
+                total: page.total,
+                next_offset: page.next_offset,
+            },
+        ),
+    }
 }
 
 /// Helper to aggregate zones from aggregator (respects adapter settings, public for UI module)
@@ -132,10 +282,24 @@ pub async fn get_all_zones_internal(state: &AppState) -> Vec<ZoneInfo> {
         .into_iter()
         .map(|l| (l.zone_id, l.instance))
         .collect();
+    let camilla_links: HashMap<String, String> = state
+        .camilladsp
+        .get_links()
+        .await
+        .into_iter()
+        .map(|l| (l.zone_id, l.instance))
+        .collect();
+    let minidsp_links: HashMap<String, String> = state
+        .minidsp
+        .get_links()
+        .await
+        .into_iter()
+        .map(|l| (l.zone_id, l.instance))
+        .collect();
 
-    // Helper to create DspInfo if zone is linked to HQPlayer
+    // Helper to create DspInfo if zone is linked to HQPlayer, else CamillaDSP or MiniDSP
     let get_dsp = |zone_id: &str| -> Option<DspInfo> {
-        hqp_links.get(zone_id).map(|instance| DspInfo {
+        let hqp = hqp_links.get(zone_id).map(|instance| DspInfo {
             r#type: "hqplayer".to_string(),
             instance: Some(instance.clone()),
             pipeline: Some(format!(
@@ -143,6 +307,25 @@ pub async fn get_all_zones_internal(state: &AppState) -> Vec<ZoneInfo> {
                 urlencoding::encode(zone_id)
             )),
             profiles: Some("/hqp/profiles".to_string()),
+        });
+        hqp.or_else(|| {
+            camilla_links.get(zone_id).map(|instance| DspInfo {
+                r#type: "camilladsp".to_string(),
+                instance: Some(instance.clone()),
+                pipeline: Some(format!(
+                    "/camilladsp/zones/{}",
+                    urlencoding::encode(zone_id)
+                )),
+                profiles: None,
+            })
+        })
+        .or_else(|| {
+            minidsp_links.get(zone_id).map(|instance| DspInfo {
+                r#type: "minidsp".to_string(),
+                instance: Some(instance.clone()),
+                pipeline: Some(format!("/minidsp/zones/{}", urlencoding::encode(zone_id))),
+                profiles: None,
+            })
         })
     };
 
@@ -150,7 +333,7 @@ pub async fn get_all_zones_internal(state: &AppState) -> Vec<ZoneInfo> {
     let all_zones = state.aggregator.get_zones().await;
 
     // Filter by enabled adapters and convert to ZoneInfo
-    all_zones
+    let mut zones: Vec<ZoneInfo> = all_zones
         .into_iter()
         .filter(|z| {
             // Filter based on adapter settings
@@ -162,6 +345,20 @@ pub async fn get_all_zones_internal(state: &AppState) -> Vec<ZoneInfo> {
                 adapters.openhome
             } else if z.zone_id.starts_with("upnp:") {
                 adapters.upnp
+            } else if z.zone_id.starts_with("airplay:") {
+                adapters.airplay
+            } else if z.zone_id.starts_with("snapcast:") {
+                adapters.snapcast
+            } else if z.zone_id.starts_with("heos:") {
+                adapters.heos
+            } else if z.zone_id.starts_with("kodi:") {
+                adapters.kodi
+            } else if z.zone_id.starts_with("spotify:") {
+                adapters.spotify
+            } else if z.zone_id.starts_with("http:") {
+                adapters.http
+            } else if z.zone_id.starts_with("plexamp:") {
+                adapters.plexamp
             } else if z.zone_id.starts_with("hqp:") {
                 adapters.hqplayer
             } else {
@@ -175,8 +372,77 @@ pub async fn get_all_zones_internal(state: &AppState) -> Vec<ZoneInfo> {
             source: z.source,
             state: z.state.to_string(),
             volume_control: z.volume_control,
+            metadata: z.metadata,
+            capabilities: z.capabilities,
+            volume_leveling: z.volume_leveling,
         })
-        .collect()
+        .collect();
+
+    // Same order as the web UI (grouped by room, then sort weight and name)
+    crate::zone_metadata::sort_by_room(&mut zones, |z| (z.metadata.as_ref(), z.zone_name.as_str()));
+    zones
+}
+
+/// Zones sharing a room
+#[derive(Serialize, Clone)]
+pub struct RoomZones {
+    pub room: String,
+    pub zones: Vec<ZoneInfo>,
+}
+
+/// Room membership for the knob zone list (zone IDs only, to keep the payload small)
+#[derive(Serialize, Clone)]
+pub struct RoomZoneIds {
+    pub room: String,
+    pub zone_ids: Vec<String>,
+}
+
+/// GET /rooms response
+#[derive(Serialize)]
+pub struct RoomsResponse {
+    pub rooms: Vec<RoomZones>,
+    /// Zones without a room
+    pub unassigned: Vec<ZoneInfo>,
+}
+
+/// Split zones (already in room order) into rooms and unassigned zones
+fn group_by_room(zones: Vec<ZoneInfo>) -> (Vec<RoomZones>, Vec<ZoneInfo>) {
+    let mut rooms: Vec<RoomZones> = Vec::new();
+    let mut unassigned = Vec::new();
+    for zone in zones {
+        let Some(room) = zone.metadata.as_ref().and_then(|m| m.room.clone()) else {
+            unassigned.push(zone);
+            continue;
+        };
+        match rooms.iter_mut().find(|r| r.room == room) {
+            Some(group) => group.zones.push(zone),
+            None => rooms.push(RoomZones {
+                room,
+                zones: vec![zone],
+            }),
+        }
+    }
+    (rooms, unassigned)
+}
+
+/// GET /rooms - Zones grouped by room, in display order
+pub async fn rooms_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let (rooms, unassigned) = group_by_room(get_all_zones_internal(&state).await);
+    json_with_etag(&headers, &RoomsResponse { rooms, unassigned })
+}
+
+/// GET /rooms/{room} - Zones in a single room
+pub async fn room_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(room): Path<String>,
+) -> Result<Response, ApiError> {
+    let (rooms, _) = group_by_room(get_all_zones_internal(&state).await);
+    rooms
+        .into_iter()
+        .find(|r| r.room == room)
+        .map(|r| json_with_etag(&headers, &r))
+        .ok_or_else(|| ApiError::new(ErrorCode::RoomNotFound, format!("Room not found: {}", room)))
 }
 
 /// Query params for now_playing
@@ -213,6 +479,9 @@ pub struct NowPlayingResponse {
     pub zones: Vec<ZoneInfo>,
     pub config_sha: Option<String>,
     pub zones_sha: Option<String>,
+    /// Suggested poll interval, only sent to knobs using the low bandwidth profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_ms: Option<u32>,
 }
 
 /// Helper to build zone info list for error responses
@@ -221,14 +490,25 @@ async fn get_zone_infos(state: &AppState) -> Vec<ZoneInfo> {
 }
 
 /// Compute SHA256 hash of zone list (first 8 hex chars)
-/// Changes when zones are added/removed, enabling clients to detect zone list updates
+/// Changes when zones are added/removed or their metadata (room, order) changes,
+/// enabling clients to detect zone list updates
 fn compute_zones_sha(zones: &[ZoneInfo]) -> String {
     let mut hasher = Sha256::new();
     // Hash zone IDs and names - sorted for deterministic output
     // Use length-prefixing to avoid delimiter collision (e.g., if zone name contains special chars)
+    // Metadata is only appended when set, so hashes of plain zone lists are unchanged
     let mut zone_data: Vec<_> = zones
         .iter()
-        .map(|z| format!("{}:{}", z.zone_id, z.zone_name))
+        .map(|z| {
+            match z
+                .metadata
+                .as_ref()
+                .and_then(|m| serde_json::to_string(m).ok())
+            {
+                Some(metadata) => format!("{}:{}:{}", z.zone_id, z.zone_name, metadata),
+                None => format!("{}:{}", z.zone_id, z.zone_name),
+            }
+        })
         .collect();
     zone_data.sort();
     for item in &zone_data {
@@ -241,27 +521,30 @@ fn compute_zones_sha(zones: &[ZoneInfo]) -> String {
 }
 
 /// GET /knob/now_playing - Get current playback state (routes by zone_id prefix)
+///
+/// Supports `If-None-Match` (304 when nothing changed). The knob status update
+/// still happens on a 304, so conditional polls keep counting as heartbeats.
+/// CBOR/MessagePack are available via `Accept` (see [`crate::knobs::encoding`]).
 pub async fn knob_now_playing_handler(
     State(state): State<AppState>,
     connect_info: Result<ConnectInfo<SocketAddr>, axum::extract::rejection::ExtensionRejection>,
     headers: HeaderMap,
     Query(params): Query<NowPlayingQuery>,
-) -> Result<Json<NowPlayingResponse>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Response, ApiError> {
+    authorize_knob(
+        &state,
+        &headers,
+        extract_knob_id(&headers, params.knob_id.as_deref()).as_deref(),
+    )?;
+
     // Check zone_id first
     let zone_id = match params.zone_id {
         Some(id) => id,
         None => {
             let zone_infos = get_zone_infos(&state).await;
             let zones_sha = compute_zones_sha(&zone_infos);
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": "zone_id required",
-                    "error_code": "MISSING_ZONE_ID",
-                    "zones": zone_infos,
-                    "zones_sha": zones_sha
-                })),
-            ));
+            return Err(ApiError::new(ErrorCode::MissingZoneId, "zone_id required")
+                .with_details(serde_json::json!({ "zones": zone_infos, "zones_sha": zones_sha })));
         }
     };
 
@@ -270,6 +553,7 @@ pub async fn knob_now_playing_handler(
     let knob_version = extract_knob_version(&headers);
     let client_ip = extract_client_ip(&headers, connect_info.ok().map(|c| c.0));
     let mut config_sha = None;
+    let mut low_bandwidth = false;
 
     if let Some(ref id) = knob_id {
         state.knobs.get_or_create(id, knob_version.as_deref()).await;
@@ -284,8 +568,11 @@ pub async fn knob_now_playing_handler(
             battery_charging,
             ip: client_ip,
         };
-        state.knobs.update_status(id, status_update).await;
+        if let Some(before) = state.knobs.update_status(id, status_update).await {
+            publish_status_events(&state, id, &before).await;
+        }
         config_sha = state.knobs.get_config_sha(id).await;
+        low_bandwidth = state.knobs.is_low_bandwidth(id).await;
     }
 
     let image_url = format!(
@@ -306,15 +593,8 @@ pub async fn knob_now_playing_handler(
         Some(z) => z,
         None => {
             let zones_sha = compute_zones_sha(&zone_infos);
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": "zone not found",
-                    "error_code": "ZONE_NOT_FOUND",
-                    "zones": zone_infos,
-                    "zones_sha": zones_sha
-                })),
-            ));
+            return Err(ApiError::new(ErrorCode::ZoneNotFound, "zone not found")
+                .with_details(serde_json::json!({ "zones": zone_infos, "zones_sha": zones_sha })));
         }
     };
 
@@ -326,40 +606,44 @@ pub async fn knob_now_playing_handler(
         "lms" => settings.adapters.lms,
         "openhome" => settings.adapters.openhome,
         "upnp" => settings.adapters.upnp,
+        "airplay" => settings.adapters.airplay,
+        "snapcast" => settings.adapters.snapcast,
+        "heos" => settings.adapters.heos,
+        "kodi" => settings.adapters.kodi,
+        "spotify" => settings.adapters.spotify,
+        "http" => settings.adapters.http,
+        "plexamp" => settings.adapters.plexamp,
         "hqplayer" => settings.adapters.hqplayer,
         _ => true,
     };
 
     if !adapter_enabled {
         let zones_sha = compute_zones_sha(&zone_infos);
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "error": "zone not found",
-                "error_code": "ZONE_NOT_FOUND",
-                "zones": zone_infos,
-                "zones_sha": zones_sha
-            })),
-        ));
+        return Err(ApiError::new(ErrorCode::ZoneNotFound, "zone not found")
+            .with_details(serde_json::json!({ "zones": zone_infos, "zones_sha": zones_sha })));
     }
 
-    // Extract now_playing info (title/artist/album -> line1/line2/line3)
+    // Extract now_playing info (title/artist/album -> line1/line2/line3),
+    // normalized for the knob display
     let np = zone.now_playing.as_ref();
+    let text = &state.text.knob;
     let line1 = np
         .map(|n| {
             if n.title.is_empty() {
                 "Idle".to_string()
             } else {
-                n.title.clone()
+                text.apply(&n.title).into_owned()
             }
         })
         .unwrap_or_else(|| "Idle".to_string());
-    let line2 = np.map(|n| n.artist.clone()).unwrap_or_default();
+    let line2 = np
+        .map(|n| text.apply(&n.artist).into_owned())
+        .unwrap_or_default();
     let line3 = np.and_then(|n| {
         if n.album.is_empty() {
             None
         } else {
-            Some(n.album.clone())
+            Some(text.apply(&n.album).into_owned())
         }
     });
 
@@ -378,7 +662,7 @@ pub async fn knob_now_playing_handler(
         None => "fixed".to_string(),
     };
 
-    Ok(Json(NowPlayingResponse {
+    let response = NowPlayingResponse {
         zone_id: zone.zone_id,
         line1,
         line2,
@@ -400,114 +684,441 @@ pub async fn knob_now_playing_handler(
         zones: zone_infos.clone(),
         config_sha,
         zones_sha: Some(compute_zones_sha(&zone_infos)),
-    }))
+        poll_interval_ms: low_bandwidth.then_some(LOW_BANDWIDTH_POLL_INTERVAL_MS),
+    };
+
+    if low_bandwidth {
+        let mut trimmed = serde_json::to_value(&response).unwrap_or_default();
+        if let serde_json::Value::Object(ref mut obj) = trimmed {
+            for field in LOW_BANDWIDTH_STRIPPED_FIELDS {
+                obj.remove(*field);
+            }
+        }
+        return Ok(negotiated_with_etag(&headers, &trimmed));
+    }
+    Ok(negotiated_with_etag(&headers, &response))
 }
 
 /// Query params for image endpoint
 #[derive(Deserialize)]
 pub struct ImageQuery {
     pub zone_id: String,
+    pub knob_id: Option<String>,
     pub width: Option<u32>,
     pub height: Option<u32>,
-    pub format: Option<String>,
+    /// `jpeg`, `png`, `webp` or `rgb565`; defaults to the backend's format
+    pub format: Option<OutputFormat>,
+    /// JPEG quality 1-100
+    pub quality: Option<u8>,
 }
 
 // Image conversion is now handled by state.get_image()
 
 use crate::knobs::image::svg_to_rgb565;
 
+/// Artwork size and quality for a knob, reduced on the low bandwidth profile
+/// (actual size is in `X-Image-Width`/`X-Image-Height`)
+async fn knob_artwork_options(
+    state: &AppState,
+    knob_id: Option<&str>,
+    width: Option<u32>,
+    height: Option<u32>,
+    format: Option<OutputFormat>,
+    quality: Option<u8>,
+) -> ArtworkOptions {
+    let low_bandwidth = match knob_id {
+        Some(id) => state.knobs.is_low_bandwidth(id).await,
+        None => false,
+    };
+    let divisor = if low_bandwidth {
+        LOW_BANDWIDTH_IMAGE_DIVISOR
+    } else {
+        1
+    };
+    ArtworkOptions {
+        width: Some((width.unwrap_or(240) / divisor).max(1)),
+        height: Some((height.unwrap_or(240) / divisor).max(1)),
+        format,
+        quality: quality.or(low_bandwidth.then_some(LOW_BANDWIDTH_JPEG_QUALITY)),
+    }
+}
+
+/// Conditional artwork response with RGB565 metadata headers for ESP32 clients
+fn artwork_response(
+    headers: &HeaderMap,
+    content_type: &str,
+    data: Vec<u8>,
+    rgb565: Option<(u32, u32)>,
+) -> Response {
+    let validators = Validators::for_body(&data, CACHE_REVALIDATE);
+    let mut response = validators.respond(headers, content_type, data);
+    if let Some((width, height)) = rgb565 {
+        let response_headers = response.headers_mut();
+        response_headers.insert("X-Image-Format", HeaderValue::from_static("rgb565"));
+        response_headers.insert("X-Image-Width", HeaderValue::from(width));
+        response_headers.insert("X-Image-Height", HeaderValue::from(height));
+    }
+    response
+}
+
+/// Placeholder artwork, as RGB565 when that was requested
+fn placeholder_response(headers: &HeaderMap, options: &ArtworkOptions) -> Response {
+    let (width, height) = (options.width.unwrap_or(240), options.height.unwrap_or(240));
+    let svg = placeholder_svg(width, height);
+    if options.format == Some(OutputFormat::Rgb565) {
+        if let Ok(rgb565) = svg_to_rgb565(svg.as_bytes(), width, height) {
+            return artwork_response(
+                headers,
+                "application/octet-stream",
+                rgb565.data,
+                Some((rgb565.width, rgb565.height)),
+            );
+        }
+    }
+    artwork_response(headers, "image/svg+xml", svg.into_bytes(), None)
+}
+
+/// Processed artwork as a response; the placeholder if a requested RGB565
+/// conversion failed rather than misleading headers
+fn image_response(headers: &HeaderMap, image: ImageData, options: &ArtworkOptions) -> Response {
+    let rgb565 = options.format == Some(OutputFormat::Rgb565);
+    if rgb565 && image.content_type != OutputFormat::Rgb565.content_type() {
+        return placeholder_response(headers, options);
+    }
+    let dimensions = rgb565.then(|| (options.width.unwrap_or(240), options.height.unwrap_or(240)));
+    artwork_response(headers, &image.content_type, image.data, dimensions)
+}
+
+/// Current cover of a zone (legacy unprefixed ids are Roon's)
+async fn zone_cover(
+    state: &AppState,
+    zone_id: &str,
+    options: &ArtworkOptions,
+) -> Option<ImageData> {
+    let zone_id = if !zone_id.contains(':') {
+        format!("roon:{}", zone_id)
+    } else {
+        zone_id.to_string()
+    };
+    let image_key = state
+        .aggregator
+        .get_zone(&zone_id)
+        .await?
+        .now_playing
+        .and_then(|np| np.image_key)?;
+    state.get_image(&zone_id, &image_key, options).await.ok()
+}
+
 /// GET /knob/now_playing/image - Get album artwork
-#[allow(clippy::unwrap_used)] // Response::builder().body().unwrap() cannot fail with valid inputs
+///
+/// The URL is keyed by zone, so the artwork behind it changes with the track.
+/// Responses are revalidated on every use (`Cache-Control: no-cache`) and carry
+/// `ETag`/`Last-Modified`, so an unchanged image costs an empty 304.
 pub async fn knob_image_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(params): Query<ImageQuery>,
 ) -> Response {
-    let target_width = params.width.unwrap_or(240);
-    let target_height = params.height.unwrap_or(240);
-    let format = params.format.as_deref();
-
-    // Helper to return placeholder image in appropriate format
-    let placeholder_response = || -> Response {
-        let svg = placeholder_svg(target_width, target_height);
-        if format == Some("rgb565") {
-            // Convert SVG placeholder to RGB565
-            match svg_to_rgb565(svg.as_bytes(), target_width, target_height) {
-                Ok(rgb565) => Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, "application/octet-stream")
-                    .header("X-Image-Format", "rgb565")
-                    .header("X-Image-Width", rgb565.width.to_string())
-                    .header("X-Image-Height", rgb565.height.to_string())
-                    .body(Body::from(rgb565.data))
-                    .unwrap(),
-                Err(_) => Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, "image/svg+xml")
-                    .body(Body::from(svg))
-                    .unwrap(),
-            }
-        } else {
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "image/svg+xml")
-                .body(Body::from(svg))
-                .unwrap()
-        }
-    };
+    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref());
+    if let Err(e) = authorize_knob(&state, &headers, knob_id.as_deref()) {
+        return e.into_response();
+    }
+    let options = knob_artwork_options(
+        &state,
+        knob_id.as_deref(),
+        params.width,
+        params.height,
+        params.format,
+        params.quality,
+    )
+    .await;
+
+    match zone_cover(&state, &params.zone_id, &options).await {
+        Some(image) => image_response(&headers, image, &options),
+        None => placeholder_response(&headers, &options),
+    }
+}
 
-    // Handle legacy zone_id without prefix (assume Roon)
-    let zone_id = if !params.zone_id.contains(':') {
-        format!("roon:{}", params.zone_id)
+/// Query params for the zone artwork endpoint
+#[derive(Deserialize)]
+pub struct ZoneArtworkQuery {
+    /// Square bounding box in pixels (default 240)
+    pub size: Option<u32>,
+    pub format: Option<OutputFormat>,
+    pub quality: Option<u8>,
+    pub knob_id: Option<String>,
+}
+
+/// GET /zones/{zone_id}/artwork?size= - Current artwork of a zone
+///
+/// Resolves the zone's image key internally, so clients never handle
+/// backend-specific keys. `X-Artwork-Version` is the same version knobs get
+/// from `/knob/events` (absent with the placeholder); conditional requests
+/// work as for `/knob/now_playing/image`.
+pub async fn zone_artwork_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(zone_id): Path<String>,
+    Query(params): Query<ZoneArtworkQuery>,
+) -> Response {
+    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref());
+    if let Err(e) = authorize_knob(&state, &headers, knob_id.as_deref()) {
+        return e.into_response();
+    }
+    let zone_id = if zone_id.contains(':') {
+        zone_id
     } else {
-        params.zone_id.clone()
+        format!("roon:{}", zone_id)
+    };
+    let Some(zone) = state.aggregator.get_zone(&zone_id).await else {
+        return ApiError::new(
+            ErrorCode::ZoneNotFound,
+            format!("Zone not found: {}", zone_id),
+        )
+        .into_response();
     };
+    let options = knob_artwork_options(
+        &state,
+        knob_id.as_deref(),
+        params.size,
+        params.size,
+        params.format,
+        params.quality,
+    )
+    .await;
+
+    let Some(image_key) = zone.now_playing.and_then(|np| np.image_key) else {
+        return placeholder_response(&headers, &options);
+    };
+    match state.get_image(&zone_id, &image_key, &options).await {
+        Ok(image) => {
+            let mut response = image_response(&headers, image, &options);
+            if let Ok(version) = HeaderValue::from_str(&image_version(&image_key)) {
+                response.headers_mut().insert("X-Artwork-Version", version);
+            }
+            response
+        }
+        Err(_) => placeholder_response(&headers, &options),
+    }
+}
 
-    // Get zone from aggregator to find image_key
-    let zone = match state.aggregator.get_zone(&zone_id).await {
-        Some(z) => z,
-        None => return placeholder_response(),
+/// Body for POST /knob/hqp_cycle
+#[derive(Deserialize)]
+pub struct HqpCycleRequest {
+    pub zone_id: String,
+    /// Defaults to the knob's configured setting
+    pub setting: Option<HqpCycleSetting>,
+    #[serde(default)]
+    pub direction: CycleDirection,
+    /// Jump to this option value instead of stepping
+    pub value: Option<String>,
+}
+
+/// POST /knob/hqp_cycle - Step the zone's HQPlayer filter or shaper
+///
+/// Works on HQPlayer zones and zones linked to an HQPlayer. Options come
+/// from HQPlayer's cached lists (refreshed when its settings fall outside
+/// them); a `value` not among them is rejected. `display` is the text for the
+/// knob to show, e.g. `Filter: poly-sinc-gauss-long`. Locked zones reject it.
+pub async fn knob_hqp_cycle_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<HqpCycleRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let knob_id = extract_knob_id(&headers, None);
+    authorize_knob(&state, &headers, knob_id.as_deref())?;
+
+    let setting = match (req.setting, knob_id) {
+        (Some(setting), _) => setting,
+        (None, Some(id)) => state
+            .knobs
+            .get(&id)
+            .await
+            .map(|k| k.config.hqp_cycle.setting)
+            .unwrap_or_default(),
+        (None, None) => HqpCycleSetting::default(),
     };
+    let hqp = state.hqp_for_zone(&req.zone_id).await.ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::NotLinked,
+            format!("Zone {} is not driven by HQPlayer", req.zone_id),
+        )
+        .with_backend("hqplayer")
+    })?;
+    state.check_zone_lock(&req.zone_id, Caller::from_headers(&state, &headers))?;
+    let pipeline = hqp.get_pipeline_status().await.map_err(|e| {
+        ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("hqplayer")
+    })?;
 
-    // Get image_key from now_playing
-    let image_key = match zone.now_playing.and_then(|np| np.image_key) {
-        Some(key) => key,
-        None => return placeholder_response(),
+    let options = match setting {
+        HqpCycleSetting::Filter => pipeline.settings.filter_nx,
+        HqpCycleSetting::Shaper => pipeline.settings.shaper,
+    };
+    let index = match req.value {
+        Some(value) => options
+            .options
+            .iter()
+            .position(|o| o.value == value)
+            .ok_or_else(|| {
+                ApiError::new(
+                    ErrorCode::InvalidRequest,
+                    format!(
+                        "{} {} is not among HQPlayer's options",
+                        setting.label(),
+                        value
+                    ),
+                )
+                .with_backend("hqplayer")
+            })?,
+        None => {
+            let current = options
+                .options
+                .iter()
+                .position(|o| o.value == options.selected.value);
+            cycle_index(options.options.len(), current, req.direction).ok_or_else(|| {
+                ApiError::new(
+                    ErrorCode::InvalidRequest,
+                    format!("HQPlayer reports no {} options", setting.label()),
+                )
+                .with_backend("hqplayer")
+            })?
+        }
+    };
+    let Some(option) = options.options.get(index) else {
+        return Err(ApiError::new(
+            ErrorCode::Internal,
+            "option index out of range",
+        ));
     };
 
-    // Fetch image through unified interface (handles format conversion)
-    match state
-        .get_image(
-            &zone_id,
-            &image_key,
-            Some(target_width),
-            Some(target_height),
-            format,
-        )
-        .await
-    {
-        Ok(image_data) => {
-            // If RGB565 was requested but conversion failed (content_type != octet-stream),
-            // return the placeholder instead of misleading headers
-            if format == Some("rgb565") && image_data.content_type != "application/octet-stream" {
-                return placeholder_response();
-            }
+    // Same value encoding as /hqplayer/pipeline
+    let value = option.value.parse::<i64>().unwrap_or(0) as u32;
+    let result = match setting {
+        HqpCycleSetting::Filter => hqp.set_filter(value, Some(value)).await,
+        HqpCycleSetting::Shaper => hqp.set_shaper(value).await,
+    };
+    result.map_err(|e| {
+        ApiError::from_backend(ErrorCode::CommandFailed, &e).with_backend("hqplayer")
+    })?;
 
-            let mut response = Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, &image_data.content_type);
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "zone_id": req.zone_id,
+        "setting": setting,
+        "value": option.value,
+        "name": option.label,
+        "display": format!("{}: {}", setting.label(), option.label),
+    })))
+}
 
-            // Add RGB565 metadata headers for ESP32 clients
-            if format == Some("rgb565") {
-                response = response
-                    .header("X-Image-Format", "rgb565")
-                    .header("X-Image-Width", target_width.to_string())
-                    .header("X-Image-Height", target_height.to_string());
-            }
+/// Query params for the art mode endpoint
+#[derive(Deserialize)]
+pub struct ArtModeQuery {
+    /// The knob's zone, for `current` and `artist`
+    pub zone_id: Option<String>,
+    pub knob_id: Option<String>,
+    /// Overrides the knob's configured source
+    pub source: Option<ArtModeSource>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: Option<OutputFormat>,
+    pub quality: Option<u8>,
+}
 
-            response.body(Body::from(image_data.data)).unwrap()
+/// GET /knob/art_mode/next - Next image for an idle knob's art mode
+///
+/// Rotates through the knob's `art_mode_source` (see [`crate::knobs::art_mode`]);
+/// each call advances that knob's position. Sources with nothing to show
+/// fall back to the zone's current cover, then the placeholder.
+/// `X-Art-Source` names the source the image came from.
+pub async fn knob_art_next_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ArtModeQuery>,
+) -> Response {
+    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref());
+    if let Err(e) = authorize_knob(&state, &headers, knob_id.as_deref()) {
+        return e.into_response();
+    }
+    let configured = match knob_id.as_deref() {
+        Some(id) => state.knobs.get(id).await.map(|k| k.config.art_mode_source),
+        None => None,
+    };
+    let source = params.source.or(configured).unwrap_or_default();
+    let options = knob_artwork_options(
+        &state,
+        knob_id.as_deref(),
+        params.width,
+        params.height,
+        params.format,
+        params.quality,
+    )
+    .await;
+    let cursor = knob_id.as_deref().unwrap_or_default();
+    let zone_id = params.zone_id.as_deref().unwrap_or_default();
+
+    let mut image = None;
+    let mut used = source;
+    if source == ArtModeSource::Artist {
+        image = next_artist_image(&state, cursor, zone_id, &options).await;
+        if image.is_none() {
+            used = ArtModeSource::RecentCovers;
+        }
+    }
+    if image.is_none() && used == ArtModeSource::RecentCovers {
+        let covers = state.art_mode.recent();
+        if let Some(cover) = state
+            .art_mode
+            .advance(cursor, covers.len())
+            .and_then(|i| covers.get(i))
+        {
+            image = state
+                .get_image(&cover.zone_id, &cover.image_key, &options)
+                .await
+                .ok();
+        }
+    }
+    if image.is_none() && used == ArtModeSource::Folder {
+        let files = art_mode::folder_images();
+        if let Some(path) = state
+            .art_mode
+            .advance(cursor, files.len())
+            .and_then(|i| files.get(i))
+        {
+            image = art_mode::load_image(path, &options).await;
         }
-        Err(_) => placeholder_response(),
     }
+    if image.is_none() {
+        used = ArtModeSource::Current;
+        image = zone_cover(&state, zone_id, &options).await;
+    }
+
+    let mut response = match image {
+        Some(image) => image_response(&headers, image, &options),
+        None => placeholder_response(&headers, &options),
+    };
+    response
+        .headers_mut()
+        .insert("X-Art-Source", HeaderValue::from_static(used.as_str()));
+    response
+}
+
+/// Next artist image of what a Roon zone is playing
+async fn next_artist_image(
+    state: &AppState,
+    cursor: &str,
+    zone_id: &str,
+    options: &ArtworkOptions,
+) -> Option<ImageData> {
+    let (_, raw_id) = zone_id.split_once(':').filter(|(p, _)| *p == "roon")?;
+    let keys = state
+        .roon
+        .get_zone(raw_id)
+        .await?
+        .now_playing?
+        .artist_image_keys;
+    let key = keys.get(state.art_mode.advance(cursor, keys.len())?)?;
+    state.get_image(zone_id, key, options).await.ok()
 }
 
 /// Control request body
@@ -516,37 +1127,401 @@ pub struct KnobControlRequest {
     pub zone_id: String,
     pub action: String,
     pub value: Option<serde_json::Value>,
+    /// Gesture that triggered the action; inferred from the action if unset
+    #[serde(default)]
+    pub gesture: Option<KnobGesture>,
+}
+
+/// Knob's user-assigned name, if set
+fn knob_name(knob: &Knob) -> Option<&str> {
+    Some(knob.name.as_str()).filter(|name| !name.is_empty())
+}
+
+/// Publish zone and battery transitions since the knob's previous report
+async fn publish_status_events(state: &AppState, knob_id: &str, before: &KnobStatus) {
+    let Some(knob) = state.knobs.get(knob_id).await else {
+        return;
+    };
+    for event in status_events(knob_id, knob_name(&knob), before, &knob.status) {
+        state.bus.publish(event);
+    }
+    if let Some(zone_id) = &knob.status.zone_id {
+        select_zone(state, &knob_client(knob_id), zone_id);
+    }
 }
 
 /// POST /knob/control - Send control command (routes by zone_id prefix)
+///
+/// Each request from an identified knob is also published as a `knob_input`
+/// bus event. The `input` action only publishes, for gestures not bound to a
+/// command.
 pub async fn knob_control_handler(
     State(state): State<AppState>,
-    _headers: HeaderMap,
+    headers: HeaderMap,
     Json(req): Json<KnobControlRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let knob_id = extract_knob_id(&headers, None);
+    authorize_knob(&state, &headers, knob_id.as_deref())?;
+    if let Some(id) = knob_id {
+        let (inferred, steps) = gesture_for_action(&req.action);
+        let gesture = req.gesture.unwrap_or(inferred);
+        let knob = state.knobs.get(&id).await;
+        state.bus.publish(BusEvent::KnobInput {
+            knob_name: knob.as_ref().and_then(knob_name).map(String::from),
+            knob_id: id,
+            zone_id: Some(req.zone_id.clone()).filter(|z| !z.is_empty()),
+            gesture,
+            action: Some(req.action.clone()).filter(|a| a != "input"),
+            steps: steps.filter(|_| gesture == KnobGesture::Rotate),
+        });
+    }
+    if req.action == "input" {
+        return Ok(Json(serde_json::json!({"ok": true})));
+    }
+    let caller = Caller::from_headers(&state, &headers);
+    // Knob gesture for party mode; applies to all zones, not just req.zone_id
+    if req.action == "party_mode" {
+        let scene = state.scenes.party();
+        return Ok(Json(activate_party(&state, &scene, caller).await));
+    }
+    dispatch_control(
+        &state,
+        &req.zone_id,
+        &req.action,
+        req.value.as_ref(),
+        caller,
+    )
+    .await
+}
+
+/// Route a control command to the zone's backend by zone_id prefix
+///
+/// Commands to a locked zone are rejected unless `caller` is an admin.
+pub async fn dispatch_control(
+    state: &AppState,
+    zone_id: &str,
+    action: &str,
+    value: Option<&serde_json::Value>,
+    caller: Caller,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if zone_id.starts_with("airplay:") {
+        return Err(ApiError::new(
+            ErrorCode::InvalidRequest,
+            "AirPlay zones are read-only: control them from the device streaming to them",
+        )
+        .with_backend("airplay"));
+    }
+    // Held until the command completes so disabling the adapter waits for it
+    let backend = [
+        "lms", "openhome", "upnp", "snapcast", "heos", "kodi", "spotify", "http", "plexamp",
+    ]
+    .into_iter()
+    .find(|prefix| {
+        zone_id
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with(':'))
+    })
+    .unwrap_or("roon");
+    // Metadata is keyed by prefixed id; legacy Roon zone ids come without one
+    let roon_id =
+        (backend == "roon").then(|| PrefixedZoneId::roon(zone_id.trim_start_matches("roon:")));
+    state.check_zone_lock(roon_id.as_ref().map_or(zone_id, |id| id.as_str()), caller)?;
+    let _command = state.begin_command(backend)?;
+
     // Route based on zone_id prefix
-    if req.zone_id.starts_with("lms:") {
+    let (prefixed, result) = if zone_id.starts_with("lms:") {
         // LMS player control
-        let player_id = req.zone_id.trim_start_matches("lms:");
-        return control_lms(&state, player_id, &req.action, req.value.as_ref()).await;
-    } else if req.zone_id.starts_with("openhome:") {
+        let player_id = zone_id.trim_start_matches("lms:");
+        (
+            PrefixedZoneId::lms(player_id),
+            control_lms(state, player_id, action, value).await,
+        )
+    } else if zone_id.starts_with("openhome:") {
         // OpenHome zone control
-        let udn = req.zone_id.trim_start_matches("openhome:");
-        return control_openhome(&state, udn, &req.action).await;
-    } else if req.zone_id.starts_with("upnp:") {
+        let udn = zone_id.trim_start_matches("openhome:");
+        (
+            PrefixedZoneId::openhome(udn),
+            control_openhome(state, udn, action).await,
+        )
+    } else if zone_id.starts_with("upnp:") {
         // UPnP zone control
-        let udn = req.zone_id.trim_start_matches("upnp:");
-        return control_upnp(&state, udn, &req.action).await;
+        let udn = zone_id.trim_start_matches("upnp:");
+        (
+            PrefixedZoneId::upnp(udn),
+            control_upnp(state, udn, action, value).await,
+        )
+    } else if zone_id.starts_with("snapcast:") {
+        // Snapcast client or group control
+        let raw_id = zone_id.trim_start_matches("snapcast:");
+        (
+            PrefixedZoneId::snapcast(raw_id),
+            control_snapcast(state, raw_id, action, value).await,
+        )
+    } else if zone_id.starts_with("heos:") {
+        // HEOS player or group control
+        let raw_id = zone_id.trim_start_matches("heos:");
+        (
+            PrefixedZoneId::heos(raw_id),
+            control_heos(state, raw_id, action, value).await,
+        )
+    } else if zone_id.starts_with("kodi:") {
+        // Kodi playback control
+        let raw_id = zone_id.trim_start_matches("kodi:");
+        (
+            PrefixedZoneId::kodi(raw_id),
+            control_kodi(state, raw_id, action, value).await,
+        )
+    } else if zone_id.starts_with("spotify:") {
+        // Spotify Connect device control
+        let raw_id = zone_id.trim_start_matches("spotify:");
+        (
+            PrefixedZoneId::spotify(raw_id),
+            control_spotify(state, raw_id, action, value).await,
+        )
+    } else if zone_id.starts_with("http:") {
+        // HTTP JSON device control (requests from its template)
+        let raw_id = zone_id.trim_start_matches("http:");
+        (
+            PrefixedZoneId::http(raw_id),
+            control_http(state, raw_id, action, value).await,
+        )
+    } else if zone_id.starts_with("plexamp:") {
+        // Plexamp player control (raw id is the player host)
+        let host = zone_id.trim_start_matches("plexamp:");
+        (
+            PrefixedZoneId::plexamp(host),
+            control_plexamp(state, host, action, value).await,
+        )
+    } else {
+        // Roon zone (or legacy zone_id without prefix)
+        let roon_zone_id = zone_id.trim_start_matches("roon:");
+        (
+            PrefixedZoneId::roon(roon_zone_id),
+            control_roon(state, roon_zone_id, action, value).await,
+        )
+    };
+
+    if result.is_ok() {
+        state.metrics.record_command(&prefixed, action);
     }
+    result
+}
 
-    // Roon zone (or legacy zone_id without prefix)
-    let roon_zone_id = if req.zone_id.starts_with("roon:") {
-        req.zone_id.trim_start_matches("roon:").to_string()
-    } else {
-        req.zone_id.clone()
+/// POST /zones/standby_all - Put every zone into standby ("goodnight")
+///
+/// Roon outputs and LMS players go into standby; OpenHome and UPnP renderers
+/// have no standby and are stopped instead. HQPlayer zones are left alone, as
+/// they follow the zone they are linked to. One failing zone doesn't stop the
+/// rest; each zone's outcome is listed in `results`.
+pub async fn zones_standby_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let caller = Caller::from_headers(&state, &headers);
+    Ok(Json(control_all_zones(&state, None, None, caller).await))
+}
+
+/// Send an action to every controllable zone, `{ok, results}` per zone
+///
+/// Without an action, Roon and LMS zones go into standby and OpenHome/UPnP
+/// renderers are stopped. HQPlayer zones are skipped either way.
+pub async fn control_all_zones(
+    state: &AppState,
+    action: Option<&str>,
+    value: Option<&serde_json::Value>,
+    caller: Caller,
+) -> serde_json::Value {
+    let mut results = Vec::new();
+    for zone in state.aggregator.get_zones().await {
+        let action = match zone.source.as_str() {
+            "roon" | "lms" => action.unwrap_or("standby"),
+            "openhome" | "upnp" => action.unwrap_or("stop"),
+            _ => continue,
+        };
+        let outcome = dispatch_control(state, &zone.zone_id, action, value, caller).await;
+        results.push(serde_json::json!({
+            "zone_id": zone.zone_id,
+            "action": action,
+            "ok": outcome.is_ok(),
+            "error": outcome.err().map(|e| e.message),
+        }));
+    }
+    let ok = results.iter().all(|r| r["ok"] == true);
+    serde_json::json!({ "ok": ok, "results": results })
+}
+
+/// GET /api/scenes/party - Party mode preset
+pub async fn party_scene_get_handler(State(state): State<AppState>) -> Json<PartyScene> {
+    Json(state.scenes.party())
+}
+
+/// PUT /api/scenes/party - Save the party mode preset
+pub async fn party_scene_put_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(scene): Json<PartyScene>,
+) -> Result<Json<PartyScene>, ApiError> {
+    state.require_admin(&headers)?;
+    state.scenes.set_party(scene);
+    Ok(Json(state.scenes.party()))
+}
+
+/// Optional overrides for a party mode activation
+#[derive(Debug, Default, Deserialize)]
+pub struct PartyRequest {
+    pub playlist: Option<String>,
+}
+
+/// POST /scenes/party - Start party mode from the stored preset
+///
+/// Sets each zone's preset volume, groups the included zones per backend
+/// (Roon outputs into one zone, LMS players synced to the first), then starts
+/// playback: the preset `playlist` on the LMS leader, the current queue
+/// elsewhere. Backends can't be grouped with each other, and OpenHome/UPnP
+/// renderers can't be grouped at all, so they just start playing. Each step's
+/// outcome is listed in `results`.
+pub async fn party_scene_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Option<Json<PartyRequest>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let mut scene = state.scenes.party();
+    if let Some(Json(PartyRequest {
+        playlist: Some(playlist),
+    })) = body
+    {
+        scene.playlist = Some(playlist);
+    }
+    let caller = Caller::from_headers(&state, &headers);
+    Ok(Json(activate_party(&state, &scene, caller).await))
+}
+
+/// Run the party scene; zones locked against `caller` are left out and
+/// listed as failed `locked` steps
+pub async fn activate_party(
+    state: &AppState,
+    scene: &PartyScene,
+    caller: Caller,
+) -> serde_json::Value {
+    fn step(step: &str, zone_id: &str, outcome: Result<(), String>) -> serde_json::Value {
+        serde_json::json!({
+            "step": step,
+            "zone_id": zone_id,
+            "ok": outcome.is_ok(),
+            "error": outcome.err(),
+        })
+    }
+
+    let zones: Vec<_> = state
+        .aggregator
+        .get_zones()
+        .await
+        .into_iter()
+        .filter(|z| matches!(z.source.as_str(), "roon" | "lms" | "openhome" | "upnp"))
+        .filter(|z| scene.includes(&z.zone_id))
+        .collect();
+    let (zones, locked): (Vec<_>, Vec<_>) = zones
+        .into_iter()
+        .partition(|z| state.check_zone_lock(&z.zone_id, caller).is_ok());
+    let mut results: Vec<_> = locked
+        .iter()
+        .map(|z| step("locked", &z.zone_id, Err("Zone is locked".to_string())))
+        .collect();
+
+    // Volumes first: Roon zone ids change once outputs are grouped
+    for zone in &zones {
+        if let Some(volume) = scene.volumes.get(&zone.zone_id) {
+            let value = serde_json::json!(volume);
+            let outcome =
+                dispatch_control(state, &zone.zone_id, "vol_abs", Some(&value), caller).await;
+            results.push(step(
+                "volume",
+                &zone.zone_id,
+                outcome.map(|_| ()).map_err(|e| e.message),
+            ));
+        }
+    }
+
+    let raw_ids = |source: &str| -> Vec<String> {
+        zones
+            .iter()
+            .filter(|z| z.source == source)
+            .filter_map(|z| z.zone_id.split_once(':'))
+            .map(|(_, raw)| raw.to_string())
+            .collect()
     };
 
-    control_roon(&state, &roon_zone_id, &req.action, req.value.as_ref()).await
+    let roon_zones = raw_ids("roon");
+    if !roon_zones.is_empty() {
+        let zone_id = format!("roon:{}", roon_zones[0]);
+        let outcome = match state.begin_command("roon") {
+            Ok(_command) => {
+                let ids: Vec<&str> = roon_zones.iter().map(String::as_str).collect();
+                match state.roon.group_zones(&ids).await {
+                    Ok(leader) => {
+                        results.push(step("group", &zone_id, Ok(())));
+                        state
+                            .roon
+                            .control(&leader, "play")
+                            .await
+                            .map_err(|e| e.to_string())
+                    }
+                    Err(e) => {
+                        results.push(step("group", &zone_id, Err(e.to_string())));
+                        Err("not started".to_string())
+                    }
+                }
+            }
+            Err(e) => Err(e.message),
+        };
+        results.push(step("play", &zone_id, outcome));
+    }
+
+    let lms_players = raw_ids("lms");
+    if let Some((leader, followers)) = lms_players.split_first() {
+        let zone_id = format!("lms:{}", leader);
+        let outcome = match state.begin_command("lms") {
+            Ok(_command) => {
+                let followers: Vec<&str> = followers.iter().map(String::as_str).collect();
+                let grouped = state.lms.sync_players(leader, &followers).await;
+                let grouped_ok = grouped.is_ok();
+                results.push(step("group", &zone_id, grouped.map_err(|e| e.to_string())));
+                let played = match (&scene.playlist, grouped_ok) {
+                    (Some(playlist), true) => state.lms.play_playlist(leader, playlist).await,
+                    _ => state.lms.control(leader, "play", None).await,
+                };
+                played.map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e.message),
+        };
+        results.push(step("play", &zone_id, outcome));
+        // Scene name on the players' displays
+        let detail = format!("{} zones", zones.len());
+        for player_id in &lms_players {
+            if let Err(e) = state
+                .lms
+                .show_message(player_id, "Party", &detail, DISPLAY_MESSAGE_DURATION)
+                .await
+            {
+                tracing::debug!("Display message on lms:{} failed: {}", player_id, e);
+            }
+        }
+    }
+
+    for zone in zones
+        .iter()
+        .filter(|z| matches!(z.source.as_str(), "openhome" | "upnp"))
+    {
+        let outcome = dispatch_control(state, &zone.zone_id, "play", None, caller).await;
+        results.push(step(
+            "play",
+            &zone.zone_id,
+            outcome.map(|_| ()).map_err(|e| e.message),
+        ));
+    }
+
+    let ok = results.iter().all(|r| r["ok"] == true);
+    serde_json::json!({ "ok": ok, "results": results })
 }
 
 /// Control Roon zone
@@ -555,7 +1530,7 @@ async fn control_roon(
     zone_id: &str,
     action: &str,
     value: Option<&serde_json::Value>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     let roon_action = match action {
         "play" => "play",
         "pause" => "pause",
@@ -563,60 +1538,52 @@ async fn control_roon(
         "next" => "next",
         "previous" | "prev" => "previous",
         "stop" => "stop",
+        // Bringing back what played before standby needs Roon's browse service
+        // (play from history), which the bridge doesn't use
+        "resume_last" => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                "resume_last is not supported for Roon zones; use play to start the zone's queue",
+            )
+            .with_backend("roon"));
+        }
+        "standby" => {
+            state.roon.standby(zone_id).await.map_err(|e| {
+                ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("roon")
+            })?;
+            return Ok(Json(serde_json::json!({"ok": true})));
+        }
         "vol_up" | "volume_up" => {
             let output = get_first_output_id(state, zone_id).await.ok_or_else(|| {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(serde_json::json!({"error": "no outputs in zone"})),
-                )
+                ApiError::new(ErrorCode::NoOutputs, "no outputs in zone").with_backend("roon")
             })?;
-            // Use provided value, or look up zone's actual step from aggregator
-            let step = match value.and_then(|v| v.as_f64()) {
-                Some(v) => v as f32,
-                None => get_zone_step(state, &format!("roon:{}", zone_id)).await,
-            };
+            let step = volume_step(state, &format!("roon:{}", zone_id), value).await;
             state
                 .roon
                 .change_volume(&output, step, true)
                 .await
                 .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({"error": e.to_string()})),
-                    )
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("roon")
                 })?;
             return Ok(Json(serde_json::json!({"ok": true})));
         }
         "vol_down" | "volume_down" => {
             let output = get_first_output_id(state, zone_id).await.ok_or_else(|| {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(serde_json::json!({"error": "no outputs in zone"})),
-                )
+                ApiError::new(ErrorCode::NoOutputs, "no outputs in zone").with_backend("roon")
             })?;
-            // Use provided value, or look up zone's actual step from aggregator
-            let step = match value.and_then(|v| v.as_f64()) {
-                Some(v) => v as f32,
-                None => get_zone_step(state, &format!("roon:{}", zone_id)).await,
-            };
+            let step = volume_step(state, &format!("roon:{}", zone_id), value).await;
             state
                 .roon
                 .change_volume(&output, -step, true)
                 .await
                 .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({"error": e.to_string()})),
-                    )
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("roon")
                 })?;
             return Ok(Json(serde_json::json!({"ok": true})));
         }
         "vol_abs" | "volume" => {
             let output = get_first_output_id(state, zone_id).await.ok_or_else(|| {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(serde_json::json!({"error": "no outputs in zone"})),
-                )
+                ApiError::new(ErrorCode::NoOutputs, "no outputs in zone").with_backend("roon")
             })?;
             // Log raw value for debugging - knob sends floats like 75.0
             tracing::debug!("vol_abs raw value: {:?}", value);
@@ -628,27 +1595,35 @@ async fn control_roon(
                 .change_volume(&output, vol, false)
                 .await
                 .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({"error": e.to_string()})),
-                    )
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("roon")
                 })?;
             return Ok(Json(serde_json::json!({"ok": true})));
         }
         _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Unknown action: {}", action)})),
-            ));
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("roon"));
         }
     };
 
+    // Reflect the press right away; the aggregator rolls back if Roon disagrees
+    let prefixed_zone_id = format!("roon:{}", zone_id);
+    state
+        .aggregator
+        .apply_optimistic(&prefixed_zone_id, roon_action)
+        .await;
+
     match state.roon.control(zone_id, roon_action).await {
         Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e.to_string()})),
-        )),
+        Err(e) => {
+            state
+                .aggregator
+                .rollback_optimistic(&prefixed_zone_id)
+                .await;
+            Err(ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("roon"))
+        }
     }
 }
 
@@ -658,7 +1633,7 @@ async fn control_lms(
     player_id: &str,
     action: &str,
     value: Option<&serde_json::Value>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
     let lms_action = match action {
         "play" => "play",
         "pause" => "pause",
@@ -666,39 +1641,37 @@ async fn control_lms(
         "next" => "next",
         "previous" | "prev" => "prev",
         "stop" => "stop",
+        "standby" => {
+            state.lms.standby(player_id).await.map_err(|e| {
+                ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
+            })?;
+            return Ok(Json(serde_json::json!({"ok": true})));
+        }
+        "resume_last" => {
+            state.lms.resume_last(player_id).await.map_err(|e| {
+                ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
+            })?;
+            return Ok(Json(serde_json::json!({"ok": true})));
+        }
         "vol_up" | "volume_up" => {
-            // Use provided value, or look up zone's actual step from aggregator
-            let step = match value.and_then(|v| v.as_f64()) {
-                Some(v) => v as f32,
-                None => get_zone_step(state, &format!("lms:{}", player_id)).await,
-            };
+            let step = volume_step(state, &format!("lms:{}", player_id), value).await;
             state
                 .lms
                 .change_volume(player_id, step, true)
                 .await
                 .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({"error": e.to_string()})),
-                    )
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
                 })?;
             return Ok(Json(serde_json::json!({"ok": true})));
         }
         "vol_down" | "volume_down" => {
-            // Use provided value, or look up zone's actual step from aggregator
-            let step = match value.and_then(|v| v.as_f64()) {
-                Some(v) => v as f32,
-                None => get_zone_step(state, &format!("lms:{}", player_id)).await,
-            };
+            let step = volume_step(state, &format!("lms:{}", player_id), value).await;
             state
                 .lms
                 .change_volume(player_id, -step, true)
                 .await
                 .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({"error": e.to_string()})),
-                    )
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
                 })?;
             return Ok(Json(serde_json::json!({"ok": true})));
         }
@@ -710,104 +1683,508 @@ async fn control_lms(
                 .change_volume(player_id, vol, false)
                 .await
                 .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({"error": e.to_string()})),
-                    )
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
+                })?;
+            return Ok(Json(serde_json::json!({"ok": true})));
+        }
+        "volume_leveling" => {
+            // ReplayGain mode: "off", "track", "album" or "smart"
+            let mode: VolumeLeveling =
+                value
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(|e: String| ApiError::new(ErrorCode::InvalidRequest, e))?;
+            state
+                .lms
+                .set_volume_leveling(player_id, mode)
+                .await
+                .map_err(|e| {
+                    ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")
                 })?;
             return Ok(Json(serde_json::json!({"ok": true})));
         }
         _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Unknown action: {}", action)})),
-            ));
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("lms"));
         }
     };
 
     match state.lms.control(player_id, lms_action, None).await {
         Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e.to_string()})),
-        )),
+        Err(e) => Err(ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("lms")),
+    }
+}
+
+/// Control OpenHome zone
+async fn control_openhome(
+    state: &AppState,
+    zone_id: &str,
+    action: &str,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let oh_action = match action {
+        "play" => "play",
+        "pause" => "pause",
+        "play_pause" | "playpause" => "pause", // OpenHome uses pause to toggle
+        "next" => "next",
+        "previous" | "prev" => "previous",
+        "stop" => "stop",
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("openhome"));
+        }
+    };
+
+    match state.openhome.control(zone_id, oh_action, None).await {
+        Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
+        Err(e) => Err(ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("openhome")),
+    }
+}
+
+/// Control UPnP zone
+async fn control_upnp(
+    state: &AppState,
+    zone_id: &str,
+    action: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if action == "play_url" {
+        let url = value.and_then(|v| v.as_str()).ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::InvalidRequest,
+                "play_url requires a URL string value",
+            )
+            .with_backend("upnp")
+        })?;
+        return match state.upnp.play_url(zone_id, url, "Announcement").await {
+            Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
+            Err(e) => Err(ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("upnp")),
+        };
+    }
+
+    let upnp_action = match action {
+        "play" => "play",
+        "pause" => "pause",
+        "play_pause" | "playpause" => "pause",
+        "next" => "next",
+        "previous" | "prev" => "previous",
+        "stop" => "stop",
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("upnp"));
+        }
+    };
+
+    match state.upnp.control(zone_id, upnp_action, None).await {
+        Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
+        Err(e) => Err(ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("upnp")),
+    }
+}
+
+/// Control Snapcast zone (a client, or a group of clients)
+///
+/// Transport goes to the stream the group plays; volume and `mute` (bool
+/// value, toggles without one) go to the client or, for a group, to all of
+/// its clients.
+async fn control_snapcast(
+    state: &AppState,
+    raw_id: &str,
+    action: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let backend_error = |e: anyhow::Error| {
+        ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("snapcast")
+    };
+    match action {
+        "play" | "pause" | "play_pause" | "playpause" | "next" | "previous" | "prev" | "stop" => {
+            state
+                .snapcast
+                .control(raw_id, action)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_up" | "volume_up" | "vol_down" | "volume_down" => {
+            let step = volume_step(state, &format!("snapcast:{}", raw_id), value).await;
+            let delta = if action.ends_with("up") { step } else { -step };
+            state
+                .snapcast
+                .change_volume(raw_id, delta)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_abs" | "volume" => {
+            let vol = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(ErrorCode::InvalidRequest, "volume requires a numeric value")
+                    .with_backend("snapcast")
+            })? as f32;
+            state
+                .snapcast
+                .set_volume(raw_id, vol)
+                .await
+                .map_err(backend_error)?;
+        }
+        "mute" => {
+            let mute = match value.and_then(|v| v.as_bool()) {
+                Some(mute) => mute,
+                None => !state.snapcast.is_muted(raw_id).await.unwrap_or(false),
+            };
+            state
+                .snapcast
+                .set_mute(raw_id, mute)
+                .await
+                .map_err(backend_error)?;
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("snapcast"));
+        }
+    }
+    Ok(Json(serde_json::json!({"ok": true})))
+}
+
+/// Control HEOS zone (a player, or a group of players)
+///
+/// Transport on a grouped player goes to the group leader; volume and `mute`
+/// (bool value, toggles without one) go to the player or the group.
+async fn control_heos(
+    state: &AppState,
+    raw_id: &str,
+    action: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let backend_error =
+        |e: anyhow::Error| ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("heos");
+    match action {
+        "play" | "pause" | "play_pause" | "playpause" | "next" | "previous" | "prev" | "stop" => {
+            state
+                .heos
+                .control(raw_id, action)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_up" | "volume_up" | "vol_down" | "volume_down" => {
+            let step = volume_step(state, &format!("heos:{}", raw_id), value).await;
+            let delta = if action.ends_with("up") { step } else { -step };
+            state
+                .heos
+                .change_volume(raw_id, delta)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_abs" | "volume" => {
+            let vol = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(ErrorCode::InvalidRequest, "volume requires a numeric value")
+                    .with_backend("heos")
+            })? as f32;
+            state
+                .heos
+                .set_volume(raw_id, vol)
+                .await
+                .map_err(backend_error)?;
+        }
+        "mute" => {
+            let mute = match value.and_then(|v| v.as_bool()) {
+                Some(mute) => mute,
+                None => !state.heos.is_muted(raw_id).await.unwrap_or(false),
+            };
+            state
+                .heos
+                .set_mute(raw_id, mute)
+                .await
+                .map_err(backend_error)?;
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("heos"));
+        }
+    }
+    Ok(Json(serde_json::json!({"ok": true})))
+}
+
+/// Control Kodi zone (the active player and Kodi's volume)
+///
+/// `seek` takes a position in seconds as `value`; `mute` works as for HEOS.
+async fn control_kodi(
+    state: &AppState,
+    raw_id: &str,
+    action: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let backend_error =
+        |e: anyhow::Error| ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("kodi");
+    match action {
+        "play" | "pause" | "play_pause" | "playpause" | "next" | "previous" | "prev" | "stop" => {
+            state.kodi.control(action).await.map_err(backend_error)?;
+        }
+        "seek" => {
+            let position = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(
+                    ErrorCode::InvalidRequest,
+                    "seek requires a position in seconds",
+                )
+                .with_backend("kodi")
+            })?;
+            state.kodi.seek(position).await.map_err(backend_error)?;
+        }
+        "vol_up" | "volume_up" | "vol_down" | "volume_down" => {
+            let step = volume_step(state, &format!("kodi:{}", raw_id), value).await;
+            let delta = if action.ends_with("up") { step } else { -step };
+            state
+                .kodi
+                .change_volume(delta)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_abs" | "volume" => {
+            let vol = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(ErrorCode::InvalidRequest, "volume requires a numeric value")
+                    .with_backend("kodi")
+            })? as f32;
+            state.kodi.set_volume(vol).await.map_err(backend_error)?;
+        }
+        "mute" => {
+            let mute = match value.and_then(|v| v.as_bool()) {
+                Some(mute) => mute,
+                None => !state.kodi.is_muted().await,
+            };
+            state.kodi.set_mute(mute).await.map_err(backend_error)?;
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("kodi"));
+        }
+    }
+    Ok(Json(serde_json::json!({"ok": true})))
+}
+
+/// Control Spotify zone (a Connect device)
+///
+/// Playing on a device other than the active one transfers playback to it.
+async fn control_spotify(
+    state: &AppState,
+    raw_id: &str,
+    action: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let backend_error = |e: anyhow::Error| {
+        ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("spotify")
+    };
+    match action {
+        "play" | "pause" | "play_pause" | "playpause" | "next" | "previous" | "prev" | "stop" => {
+            state
+                .spotify
+                .control(raw_id, action)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_up" | "volume_up" | "vol_down" | "volume_down" => {
+            let step = volume_step(state, &format!("spotify:{}", raw_id), value).await;
+            let delta = if action.ends_with("up") { step } else { -step };
+            state
+                .spotify
+                .change_volume(raw_id, delta)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_abs" | "volume" => {
+            let vol = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(ErrorCode::InvalidRequest, "volume requires a numeric value")
+                    .with_backend("spotify")
+            })? as f32;
+            state
+                .spotify
+                .set_volume(raw_id, vol)
+                .await
+                .map_err(backend_error)?;
+        }
+        _ => {
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("spotify"));
+        }
     }
+    Ok(Json(serde_json::json!({"ok": true})))
 }
 
-/// Control OpenHome zone
-async fn control_openhome(
+/// Control an HTTP JSON zone (the requests its template defines)
+///
+/// Actions the template has no request for fail; `mute` works as for HEOS.
+async fn control_http(
     state: &AppState,
-    zone_id: &str,
+    raw_id: &str,
     action: &str,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let oh_action = match action {
-        "play" => "play",
-        "pause" => "pause",
-        "play_pause" | "playpause" => "pause", // OpenHome uses pause to toggle
-        "next" => "next",
-        "previous" | "prev" => "previous",
-        "stop" => "stop",
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let backend_error =
+        |e: anyhow::Error| ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("http");
+    match action {
+        "play" | "pause" | "play_pause" | "playpause" | "next" | "previous" | "prev" | "stop" => {
+            state
+                .http
+                .control(raw_id, action)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_up" | "volume_up" | "vol_down" | "volume_down" => {
+            let step = volume_step(state, &format!("http:{}", raw_id), value).await;
+            let delta = if action.ends_with("up") { step } else { -step };
+            state
+                .http
+                .change_volume(raw_id, delta)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_abs" | "volume" => {
+            let vol = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(ErrorCode::InvalidRequest, "volume requires a numeric value")
+                    .with_backend("http")
+            })? as f32;
+            state
+                .http
+                .set_volume(raw_id, vol)
+                .await
+                .map_err(backend_error)?;
+        }
+        "mute" => {
+            let mute = match value.and_then(|v| v.as_bool()) {
+                Some(mute) => mute,
+                None => !state.http.is_muted(raw_id).await,
+            };
+            state
+                .http
+                .set_mute(raw_id, mute)
+                .await
+                .map_err(backend_error)?;
+        }
         _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Unknown action: {}", action)})),
-            ));
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("http"));
         }
-    };
-
-    match state.openhome.control(zone_id, oh_action, None).await {
-        Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e.to_string()})),
-        )),
     }
+    Ok(Json(serde_json::json!({"ok": true})))
 }
 
-/// Control UPnP zone
-async fn control_upnp(
+/// Control a Plexamp player (its local player API)
+///
+/// Plexamp has no mute of its own; `mute` sets volume 0 and restores the level.
+async fn control_plexamp(
     state: &AppState,
-    zone_id: &str,
+    host: &str,
     action: &str,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let upnp_action = match action {
-        "play" => "play",
-        "pause" => "pause",
-        "play_pause" | "playpause" => "pause",
-        "next" => "next",
-        "previous" | "prev" => "previous",
-        "stop" => "stop",
+    value: Option<&serde_json::Value>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let backend_error = |e: anyhow::Error| {
+        ApiError::from_backend(ErrorCode::BackendError, &e).with_backend("plexamp")
+    };
+    match action {
+        "play" | "pause" | "play_pause" | "playpause" | "next" | "previous" | "prev" | "stop" => {
+            state
+                .plexamp
+                .control(host, action)
+                .await
+                .map_err(backend_error)?;
+        }
+        "seek" => {
+            let position = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(
+                    ErrorCode::InvalidRequest,
+                    "seek requires a position in seconds",
+                )
+                .with_backend("plexamp")
+            })?;
+            state
+                .plexamp
+                .seek(host, position)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_up" | "volume_up" | "vol_down" | "volume_down" => {
+            let step = volume_step(state, &format!("plexamp:{}", host), value).await;
+            let delta = if action.ends_with("up") { step } else { -step };
+            state
+                .plexamp
+                .change_volume(host, delta)
+                .await
+                .map_err(backend_error)?;
+        }
+        "vol_abs" | "volume" => {
+            let vol = value.and_then(|v| v.as_f64()).ok_or_else(|| {
+                ApiError::new(ErrorCode::InvalidRequest, "volume requires a numeric value")
+                    .with_backend("plexamp")
+            })? as f32;
+            state
+                .plexamp
+                .set_volume(host, vol)
+                .await
+                .map_err(backend_error)?;
+        }
+        "mute" => {
+            let mute = match value.and_then(|v| v.as_bool()) {
+                Some(mute) => mute,
+                None => !state.plexamp.is_muted(host).await,
+            };
+            state
+                .plexamp
+                .set_mute(host, mute)
+                .await
+                .map_err(backend_error)?;
+        }
         _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": format!("Unknown action: {}", action)})),
-            ));
+            return Err(ApiError::new(
+                ErrorCode::UnknownAction,
+                format!("Unknown action: {}", action),
+            )
+            .with_backend("plexamp"));
         }
-    };
-
-    match state.upnp.control(zone_id, upnp_action, None).await {
-        Ok(()) => Ok(Json(serde_json::json!({"ok": true}))),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": e.to_string()})),
-        )),
     }
+    Ok(Json(serde_json::json!({"ok": true})))
 }
 
 /// Helper to get first output ID for a Roon zone (for volume control)
 async fn get_first_output_id(state: &AppState, zone_id: &str) -> Option<String> {
-    let zone = state.roon.get_zone(zone_id).await?;
-    zone.outputs.first().map(|o| o.output_id.clone())
+    // The aggregated zone's volume control is its first output's
+    let zone = state
+        .aggregator
+        .get_zone(PrefixedZoneId::roon(zone_id).as_str())
+        .await?;
+    let output_id = zone.volume_control?.output_id?;
+    output_id.strip_prefix("roon:").map(str::to_string)
 }
 
-/// Helper to get zone's volume step from aggregator (returns 1.0 if not found)
-async fn get_zone_step(state: &AppState, zone_id: &str) -> f32 {
-    state
-        .aggregator
-        .get_zone(zone_id)
-        .await
-        .and_then(|z| z.volume_control)
-        .map(|vc| vc.step)
+/// Step for vol_up/vol_down: the zone's configured `volume_step`, else the
+/// request's value, else the backend's step (1.0 if unknown)
+async fn volume_step(state: &AppState, zone_id: &str, value: Option<&serde_json::Value>) -> f32 {
+    let zone = state.aggregator.get_zone(zone_id).await;
+    let configured = zone
+        .as_ref()
+        .and_then(|z| z.metadata.as_ref())
+        .and_then(|m| m.volume_step);
+    configured
+        .or_else(|| value.and_then(|v| v.as_f64()).map(|v| v as f32))
+        .or_else(|| zone.and_then(|z| z.volume_control).map(|vc| vc.step))
         .unwrap_or(1.0)
 }
 
@@ -816,26 +2193,26 @@ pub async fn knob_config_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     Query(params): Query<KnobIdQuery>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref()).ok_or_else(|| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "knob_id required"})),
-        )
-    })?;
+) -> Result<Json<serde_json::Value>, ApiError> {
+    authorize_knob(&state, &headers, extract_knob_id(&headers, None).as_deref())?;
+    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref())
+        .ok_or_else(|| ApiError::new(ErrorCode::InvalidRequest, "knob_id required"))?;
 
-    let knob = state.knobs.get(&knob_id).await.ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "knob not found"})),
-        )
-    })?;
+    let knob = state
+        .knobs
+        .get(&knob_id)
+        .await
+        .ok_or_else(|| ApiError::new(ErrorCode::KnobNotFound, "knob not found"))?;
 
     // Build config response with name included in config object (matches frontend expected format)
     let mut config = serde_json::to_value(&knob.config).unwrap_or_default();
     if let serde_json::Value::Object(ref mut obj) = config {
         obj.insert("knob_id".to_string(), serde_json::json!(knob_id.clone()));
         obj.insert("name".to_string(), serde_json::json!(knob.name));
+        obj.insert(
+            "time".to_string(),
+            serde_json::json!(KnobTime::for_knob(&knob.config)),
+        );
     }
 
     Ok(Json(serde_json::json!({
@@ -856,24 +2233,16 @@ pub async fn knob_config_update_handler(
     headers: HeaderMap,
     Query(params): Query<KnobIdQuery>,
     Json(updates): Json<KnobConfigUpdate>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref()).ok_or_else(|| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "knob_id required"})),
-        )
-    })?;
+) -> Result<Json<serde_json::Value>, ApiError> {
+    authorize_knob(&state, &headers, extract_knob_id(&headers, None).as_deref())?;
+    let knob_id = extract_knob_id(&headers, params.knob_id.as_deref())
+        .ok_or_else(|| ApiError::new(ErrorCode::InvalidRequest, "knob_id required"))?;
 
     let knob = state
         .knobs
         .update_config(&knob_id, updates)
         .await
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "knob not found"})),
-            )
-        })?;
+        .ok_or_else(|| ApiError::new(ErrorCode::KnobNotFound, "knob not found"))?;
 
     Ok(Json(serde_json::json!({
         "ok": true,
@@ -881,18 +2250,131 @@ pub async fn knob_config_update_handler(
     })))
 }
 
-/// GET /knob/devices - List all registered knobs (admin)
-pub async fn knob_devices_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let knobs = state.knobs.list().await;
-    Json(serde_json::json!({ "knobs": knobs }))
+/// GET /knob/devices - List all registered knobs (admin), by knob id
+///
+/// Supports `If-None-Match` (304 when no knob changed), and `limit`/`offset`
+/// and `fields` as on `/zones`.
+pub async fn knob_devices_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ListQuery>,
+) -> Response {
+    let mut knobs = state.knobs.list().await;
+    knobs.sort_by(|a, b| a.knob_id.cmp(&b.knob_id));
+    let (knobs, page) = query.page(knobs);
+    let fields = query.fields();
+    let knobs: Vec<serde_json::Value> = knobs
+        .into_iter()
+        .map(|knob| {
+            let token = state.tokens.knob_token_status(&knob.knob_id);
+            let mut value = serde_json::json!(knob);
+            value["token"] = serde_json::json!(token);
+            match &fields {
+                Some(fields) => select_fields(&value, fields, "knob_id"),
+                None => value,
+            }
+        })
+        .collect();
+    let mut body = serde_json::json!({ "knobs": knobs });
+    if let Some(total) = page.total {
+        body["total"] = serde_json::json!(total);
+    }
+    if let Some(next_offset) = page.next_offset {
+        body["next_offset"] = serde_json::json!(next_offset);
+    }
+    json_with_etag(&headers, &body)
+}
+
+/// Body for PUT /api/knobs/auth
+#[derive(Debug, Deserialize)]
+pub struct KnobAuthUpdate {
+    pub required: bool,
+}
+
+/// GET /api/knobs/auth - Whether knobs must present device tokens
+pub async fn knob_auth_handler(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "required": state.tokens.knob_tokens_required() }))
+}
+
+/// PUT /api/knobs/auth - Require (or stop requiring) device tokens
+pub async fn knob_auth_update_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(update): Json<KnobAuthUpdate>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.require_admin(&headers)?;
+    state
+        .tokens
+        .set_knob_tokens_required(update.required)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, format!("Failed to save tokens: {}", e)))?;
+    tracing::info!(
+        "Knob device tokens {}",
+        if update.required {
+            "required"
+        } else {
+            "optional"
+        }
+    );
+    Ok(Json(serde_json::json!({ "required": update.required })))
+}
+
+/// POST /api/knobs/{id}/token - Rotate a knob's device token
+///
+/// The new token is delivered on the knob's next `GET /config/{knob_id}`; the
+/// current one keeps working until the knob first uses the new one.
+pub async fn knob_rotate_handler(
+    State(state): State<AppState>,
+    Path(knob_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.require_admin(&headers)?;
+    if state.knobs.get(&knob_id).await.is_none() {
+        return Err(ApiError::new(ErrorCode::KnobNotFound, "knob not found"));
+    }
+    state
+        .tokens
+        .issue_knob_token(&knob_id)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, format!("Failed to save tokens: {}", e)))?;
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "token": state.tokens.knob_token_status(&knob_id),
+    })))
+}
+
+/// DELETE /api/knobs/{id}/token - Revoke all of a knob's device tokens
+pub async fn knob_revoke_handler(
+    State(state): State<AppState>,
+    Path(knob_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.require_admin(&headers)?;
+    let revoked = state
+        .tokens
+        .revoke_knob_tokens(&knob_id)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, format!("Failed to save tokens: {}", e)))?;
+    if !revoked {
+        return Err(ApiError::new(
+            ErrorCode::TokenNotFound,
+            format!("No device token for knob {}", knob_id),
+        ));
+    }
+    tracing::info!("Revoked device tokens for knob {}", knob_id);
+    Ok(Json(serde_json::json!({ "ok": true })))
 }
 
 /// GET /config/{knob_id} - Get knob configuration (path parameter format)
+///
+/// Includes `token` while the knob has a device token it hasn't used yet.
+/// That delivery is how a knob without a working token gets one, so it is
+/// allowed without a token even while tokens are required.
 pub async fn knob_config_by_path_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     axum::extract::Path(knob_id): axum::extract::Path<String>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if state.tokens.pending_knob_token(&knob_id).is_none() {
+        authorize_knob(&state, &headers, Some(&knob_id))?;
+    }
     let version = extract_knob_version(&headers);
 
     // Get or create knob (ensures it exists for newly connected devices)
@@ -901,41 +2383,50 @@ pub async fn knob_config_by_path_handler(
         .get_or_create(&knob_id, version.as_deref())
         .await;
 
-    // Build config response matching Node.js format
-    let mut config = serde_json::to_value(&knob.config).unwrap_or_default();
+    // Build config response matching Node.js format (bandwidth profile applied)
+    let mut config = serde_json::to_value(knob.config.for_device()).unwrap_or_default();
     if let serde_json::Value::Object(ref mut obj) = config {
         obj.insert("knob_id".to_string(), serde_json::json!(knob_id));
         obj.insert("name".to_string(), serde_json::json!(knob.name));
+        obj.insert(
+            "time".to_string(),
+            serde_json::json!(KnobTime::for_knob(&knob.config)),
+        );
     }
 
-    Ok(Json(serde_json::json!({
+    let mut body = serde_json::json!({
         "config": config,
         "config_sha": knob.config_sha,
-    })))
+    });
+    if let Some(token) = state.tokens.pending_knob_token(&knob_id) {
+        body["token"] = serde_json::json!(token);
+    }
+    Ok(Json(body))
 }
 
 /// PUT /config/{knob_id} - Update knob configuration (path parameter format)
 pub async fn knob_config_update_by_path_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     axum::extract::Path(knob_id): axum::extract::Path<String>,
     Json(updates): Json<KnobConfigUpdate>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<serde_json::Value>, ApiError> {
+    authorize_knob(&state, &headers, Some(&knob_id))?;
     let knob = state
         .knobs
         .update_config(&knob_id, updates)
         .await
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": "knob not found"})),
-            )
-        })?;
+        .ok_or_else(|| ApiError::new(ErrorCode::KnobNotFound, "knob not found"))?;
 
-    // Build config response matching Node.js format
-    let mut config = serde_json::to_value(&knob.config).unwrap_or_default();
+    // Build config response matching Node.js format (bandwidth profile applied)
+    let mut config = serde_json::to_value(knob.config.for_device()).unwrap_or_default();
     if let serde_json::Value::Object(ref mut obj) = config {
         obj.insert("knob_id".to_string(), serde_json::json!(knob_id));
         obj.insert("name".to_string(), serde_json::json!(knob.name));
+        obj.insert(
+            "time".to_string(),
+            serde_json::json!(KnobTime::for_knob(&knob.config)),
+        );
     }
 
     Ok(Json(serde_json::json!({
@@ -944,6 +2435,44 @@ pub async fn knob_config_update_by_path_handler(
     })))
 }
 
+/// GET /config/{knob_id}/boot - Startup macro for the knob to run once booted
+///
+/// Steps are resolved for this boot: `select_zone` without a zone becomes the
+/// zone the knob last reported (or last selected), and zone steps for zones
+/// that are gone are left out. Empty when nothing is configured.
+pub async fn knob_boot_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    axum::extract::Path(knob_id): axum::extract::Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    authorize_knob(&state, &headers, Some(&knob_id))?;
+    let version = extract_knob_version(&headers);
+    let knob = state
+        .knobs
+        .get_or_create(&knob_id, version.as_deref())
+        .await;
+
+    let last_zone = knob.status.zone_id.clone().or_else(|| {
+        state
+            .selection
+            .get(&knob_client(&knob_id))
+            .map(|s| s.zone_id)
+    });
+    let zones = state.aggregator.get_zones().await;
+    let steps = resolve_steps(&knob.config.boot, last_zone.as_deref(), |zone_id| {
+        zones
+            .iter()
+            .find(|z| z.zone_id == zone_id)
+            .map(|z| z.zone_name.clone())
+    });
+
+    Ok(Json(serde_json::json!({
+        "knob_id": knob_id,
+        "steps": steps,
+        "config_sha": knob.config_sha,
+    })))
+}
+
 // ========== Firmware endpoints ==========
 
 use crate::config::get_config_dir;
@@ -960,19 +2489,89 @@ struct FirmwareVersionInfo {
     file: Option<String>,
 }
 
+/// Battery level a knob needs for an update unless it is charging
+const DEFAULT_FIRMWARE_MIN_BATTERY: u8 = 50;
+
+/// `FIRMWARE_MIN_BATTERY` (percent); 0 offers updates regardless of battery
+fn firmware_min_battery() -> u8 {
+    std::env::var("FIRMWARE_MIN_BATTERY")
+        .ok()
+        .and_then(|v| v.trim().parse::<u8>().ok())
+        .map(|v| v.min(100))
+        .unwrap_or(DEFAULT_FIRMWARE_MIN_BATTERY)
+}
+
+/// Query params for the firmware endpoints; battery fields as for now_playing
+#[derive(Deserialize, Default)]
+pub struct FirmwareQuery {
+    pub knob_id: Option<String>,
+    pub battery_level: Option<u8>,
+    pub battery_charging: Option<String>,
+}
+
+/// Whether a knob can safely flash: charging, at or above `min_battery`, or
+/// without a battery reading (USB-powered or older firmware)
+fn battery_allows_update(level: Option<u8>, charging: Option<bool>, min_battery: u8) -> bool {
+    charging == Some(true) || level.is_none_or(|level| level >= min_battery)
+}
+
+/// Hold back an update from a knob that could brown out mid-flash
+///
+/// Uses the battery reported with the request, falling back to the knob's
+/// last now_playing report. Requests not identifying as a knob (the web UI)
+/// are not gated.
+async fn check_update_battery(
+    state: &AppState,
+    headers: &HeaderMap,
+    params: &FirmwareQuery,
+) -> Result<(), ApiError> {
+    let mut level = params.battery_level.filter(|&level| level <= 100);
+    let mut charging = params
+        .battery_charging
+        .as_ref()
+        .map(|c| c == "1" || c == "true");
+    if let Some(knob_id) = extract_knob_id(headers, params.knob_id.as_deref()) {
+        if let Some(knob) = state.knobs.get(&knob_id).await {
+            level = level.or(knob.status.battery_level);
+            charging = charging.or(knob.status.battery_charging);
+        }
+    }
+
+    let min_battery = firmware_min_battery();
+    if battery_allows_update(level, charging, min_battery) {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        ErrorCode::UpdateDeferred,
+        format!(
+            "Battery at {}%; firmware updates need {}% or a charger",
+            level.unwrap_or_default(),
+            min_battery
+        ),
+    )
+    .with_details(serde_json::json!({
+        "battery_level": level,
+        "min_battery_level": min_battery,
+    })))
+}
+
 /// GET /firmware/version - Get available firmware version
+///
+/// Knobs on battery below `FIRMWARE_MIN_BATTERY` get `UPDATE_DEFERRED`
+/// (503) instead, so they keep their firmware until charged.
 #[allow(clippy::unwrap_used)] // Response::builder().body().unwrap() cannot fail with valid inputs
-pub async fn firmware_version_handler() -> Response {
+pub async fn firmware_version_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<FirmwareQuery>,
+) -> Response {
+    if let Err(e) = check_update_battery(&state, &headers, &params).await {
+        return e.into_response();
+    }
     let fw_dir = firmware_dir();
 
     if !fw_dir.exists() {
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(Body::from(
-                r#"{"error":"No firmware available","error_code":"FIRMWARE_NOT_FOUND"}"#,
-            ))
-            .unwrap();
+        return ApiError::new(ErrorCode::FirmwareNotFound, "No firmware available").into_response();
     }
 
     // Look for .bin files
@@ -989,13 +2588,7 @@ pub async fn firmware_version_handler() -> Response {
         .collect();
 
     if bin_files.is_empty() {
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(Body::from(
-                r#"{"error":"No firmware available","error_code":"FIRMWARE_NOT_FOUND"}"#,
-            ))
-            .unwrap();
+        return ApiError::new(ErrorCode::FirmwareNotFound, "No firmware available").into_response();
     }
 
     // Try to read version.json
@@ -1023,11 +2616,8 @@ pub async fn firmware_version_handler() -> Response {
     let version = match version {
         Some(v) => v,
         None => {
-            return Response::builder()
-                .status(StatusCode::NOT_FOUND)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(Body::from(r#"{"error":"No firmware version available","error_code":"FIRMWARE_NOT_FOUND"}"#))
-                .unwrap();
+            return ApiError::new(ErrorCode::FirmwareNotFound, "No firmware version available")
+                .into_response();
         }
     };
 
@@ -1051,18 +2641,21 @@ pub async fn firmware_version_handler() -> Response {
 }
 
 /// GET /firmware/download - Download firmware binary
+///
+/// Battery gated like `/firmware/version`, for knobs that skip the check.
 #[allow(clippy::unwrap_used)] // Response::builder().body().unwrap() cannot fail with valid inputs
-pub async fn firmware_download_handler() -> Response {
+pub async fn firmware_download_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<FirmwareQuery>,
+) -> Response {
+    if let Err(e) = check_update_battery(&state, &headers, &params).await {
+        return e.into_response();
+    }
     let fw_dir = firmware_dir();
 
     if !fw_dir.exists() {
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(Body::from(
-                r#"{"error":"No firmware available","error_code":"FIRMWARE_NOT_FOUND"}"#,
-            ))
-            .unwrap();
+        return ApiError::new(ErrorCode::FirmwareNotFound, "No firmware available").into_response();
     }
 
     // Determine firmware file
@@ -1097,13 +2690,8 @@ pub async fn firmware_download_handler() -> Response {
             .collect();
 
         if bin_files.is_empty() {
-            return Response::builder()
-                .status(StatusCode::NOT_FOUND)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(Body::from(
-                    r#"{"error":"Firmware file not found","error_code":"FIRMWARE_NOT_FOUND"}"#,
-                ))
-                .unwrap();
+            return ApiError::new(ErrorCode::FirmwareNotFound, "Firmware file not found")
+                .into_response();
         }
         bin_files[0].clone()
     };
@@ -1112,11 +2700,8 @@ pub async fn firmware_download_handler() -> Response {
     let data = match std::fs::read(&firmware_path) {
         Ok(d) => d,
         Err(_) => {
-            return Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(Body::from(r#"{"error":"Failed to read firmware file"}"#))
-                .unwrap();
+            return ApiError::new(ErrorCode::Internal, "Failed to read firmware file")
+                .into_response();
         }
     };
 
@@ -1137,6 +2722,171 @@ pub async fn firmware_download_handler() -> Response {
         .unwrap()
 }
 
+/// Body for POST /firmware/report
+#[derive(Debug, Deserialize)]
+pub struct FirmwareReport {
+    pub knob_id: Option<String>,
+    /// Version the knob tried to install
+    pub version: String,
+    pub success: bool,
+    /// Firmware error code, as a name or an `esp_err_t` number
+    pub error_code: Option<serde_json::Value>,
+}
+
+/// POST /firmware/report - A knob reports the outcome of an OTA update
+///
+/// Kept per knob as `last_update` in `/knob/devices`.
+pub async fn firmware_report_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(report): Json<FirmwareReport>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let knob_id = extract_knob_id(&headers, report.knob_id.as_deref())
+        .ok_or_else(|| ApiError::new(ErrorCode::InvalidRequest, "knob_id required"))?;
+    authorize_knob(&state, &headers, Some(&knob_id))?;
+    if report.version.trim().is_empty() {
+        return Err(ApiError::new(ErrorCode::InvalidRequest, "version required"));
+    }
+
+    let error_code = report.error_code.map(|code| match code {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    });
+    if report.success {
+        tracing::info!("Knob {} updated to {}", knob_id, report.version);
+    } else {
+        tracing::warn!(
+            "Knob {} failed to update to {}: {}",
+            knob_id,
+            report.version,
+            error_code.as_deref().unwrap_or("no error code")
+        );
+    }
+
+    state
+        .knobs
+        .get_or_create(&knob_id, extract_knob_version(&headers).as_deref())
+        .await;
+    state
+        .knobs
+        .record_update(
+            &knob_id,
+            UpdateReport {
+                version: report.version,
+                success: report.success,
+                error_code,
+                reported_at: chrono::Utc::now(),
+            },
+        )
+        .await;
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// Fleet overview from the registered knobs and the version the bridge serves
+fn fleet_overview(
+    knobs: &[KnobSummary],
+    available: Option<&str>,
+    min_battery: u8,
+) -> serde_json::Value {
+    use crate::firmware::{release_channel, FirmwareService};
+
+    let mut versions: Vec<(Option<&str>, usize)> = Vec::new();
+    let mut channels: std::collections::BTreeMap<String, usize> = Default::default();
+    for knob in knobs {
+        let version = knob.version.as_deref();
+        match versions.iter_mut().find(|(v, _)| *v == version) {
+            Some((_, count)) => *count += 1,
+            None => versions.push((version, 1)),
+        }
+        let channel = version.map_or_else(|| "unknown".to_string(), release_channel);
+        *channels.entry(channel).or_default() += 1;
+    }
+    // Newest first, unknown last
+    versions.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) if FirmwareService::is_newer_version(a, b) => std::cmp::Ordering::Less,
+        (Some(a), Some(b)) if FirmwareService::is_newer_version(b, a) => {
+            std::cmp::Ordering::Greater
+        }
+        _ => b.is_some().cmp(&a.is_some()).then_with(|| a.cmp(b)),
+    });
+
+    let pending: Vec<serde_json::Value> = available
+        .map(|available| {
+            knobs
+                .iter()
+                .filter(|k| {
+                    k.version
+                        .as_deref()
+                        .is_some_and(|v| FirmwareService::is_newer_version(available, v))
+                })
+                .map(|k| {
+                    let deferred = !battery_allows_update(
+                        k.status.battery_level,
+                        k.status.battery_charging,
+                        min_battery,
+                    );
+                    serde_json::json!({
+                        "knob_id": k.knob_id,
+                        "name": k.name,
+                        "version": k.version,
+                        "deferred": deferred,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut results: Vec<(&KnobSummary, &UpdateReport)> = knobs
+        .iter()
+        .filter_map(|k| k.last_update.as_ref().map(|r| (k, r)))
+        .collect();
+    results.sort_by(|(_, a), (_, b)| b.reported_at.cmp(&a.reported_at));
+    let failed = results.iter().filter(|(_, r)| !r.success).count();
+
+    serde_json::json!({
+        "available": available,
+        "knob_count": knobs.len(),
+        "versions": versions
+            .iter()
+            .map(|(version, count)| serde_json::json!({
+                "version": version,
+                "channel": version.map(release_channel),
+                "knobs": count,
+            }))
+            .collect::<Vec<_>>(),
+        "channels": channels,
+        "pending": pending,
+        "last_results": {
+            "succeeded": results.len() - failed,
+            "failed": failed,
+            "reports": results
+                .iter()
+                .map(|(knob, report)| serde_json::json!({
+                    "knob_id": knob.knob_id,
+                    "name": knob.name,
+                    "version": report.version,
+                    "success": report.success,
+                    "error_code": report.error_code,
+                    "reported_at": report.reported_at,
+                }))
+                .collect::<Vec<_>>(),
+        },
+    })
+}
+
+/// GET /firmware/fleet - Update posture of all registered knobs
+///
+/// Versions in use (newest first) with their release channel, knobs behind
+/// the served firmware (`deferred` while their battery holds the update
+/// back) and each knob's last OTA report, newest first.
+pub async fn firmware_fleet_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let knobs = state.knobs.list().await;
+    let available = crate::firmware::FirmwareService::get_current_version();
+    let overview = fleet_overview(&knobs, available.as_deref(), firmware_min_battery());
+    json_with_etag(&headers, &overview)
+}
+
 /// GET /manifest-s3.json - ESP Web Tools manifest
 #[allow(clippy::unwrap_used)] // Response::builder().body().unwrap() cannot fail with valid inputs
 pub async fn manifest_handler() -> Response {
@@ -1175,9 +2925,12 @@ pub async fn manifest_handler() -> Response {
 
 /// POST /admin/fetch-firmware - Manually trigger firmware download from GitHub
 pub async fn admin_fetch_firmware_handler(
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<serde_json::Value>, ApiError> {
     use crate::firmware::FirmwareService;
 
+    state.require_admin(&headers)?;
     let service = FirmwareService::new();
     match service.check_for_updates().await {
         Ok(downloaded) => {
@@ -1198,12 +2951,11 @@ pub async fn admin_fetch_firmware_handler(
                 })))
             }
         }
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to fetch firmware: {}", e)
-            })),
-        )),
+        Err(e) => Err(ApiError::new(
+            ErrorCode::BackendError,
+            format!("Failed to fetch firmware: {}", e),
+        )
+        .with_backend("github")),
     }
 }
 
@@ -1219,6 +2971,9 @@ mod tests {
             state: "stopped".to_string(),
             volume_control: None,
             dsp: None,
+            metadata: None,
+            capabilities: ZoneCapabilities::default(),
+            volume_leveling: None,
         }
     }
 
@@ -1300,6 +3055,51 @@ mod tests {
         assert_ne!(sha_before, sha_after, "SHA should change when zone renamed");
     }
 
+    #[test]
+    fn group_by_room_keeps_order_and_collects_unassigned() {
+        let in_room = |id: &str, room: &str| {
+            let mut zone = make_zone(id, id);
+            zone.metadata = Some(ZoneMetadata {
+                room: Some(room.to_string()),
+                ..Default::default()
+            });
+            zone
+        };
+        let zones = vec![
+            in_room("zone-1", "Kitchen"),
+            in_room("zone-2", "Kitchen"),
+            in_room("zone-3", "Study"),
+            make_zone("zone-4", "Garage"),
+        ];
+
+        let (rooms, unassigned) = group_by_room(zones);
+        let summary: Vec<(&str, usize)> = rooms
+            .iter()
+            .map(|r| (r.room.as_str(), r.zones.len()))
+            .collect();
+        assert_eq!(summary, [("Kitchen", 2), ("Study", 1)]);
+        assert_eq!(unassigned.len(), 1);
+        assert_eq!(unassigned[0].zone_id, "zone-4");
+    }
+
+    #[test]
+    fn zones_sha_changes_on_room_assignment() {
+        let zones_before = vec![make_zone("zone-1", "Living Room")];
+
+        let mut zone = make_zone("zone-1", "Living Room");
+        zone.metadata = Some(ZoneMetadata {
+            room: Some("Downstairs".to_string()),
+            ..Default::default()
+        });
+        let zones_after = vec![zone];
+
+        assert_ne!(
+            compute_zones_sha(&zones_before),
+            compute_zones_sha(&zones_after),
+            "SHA should change when a zone moves to a room"
+        );
+    }
+
     #[test]
     fn zones_sha_empty_list() {
         // Empty list should produce a valid SHA
@@ -1329,4 +3129,76 @@ mod tests {
             "Special chars in names should not cause collision"
         );
     }
+
+    #[test]
+    fn battery_gates_firmware_updates() {
+        assert!(battery_allows_update(Some(80), Some(false), 50));
+        assert!(battery_allows_update(Some(50), None, 50));
+        assert!(!battery_allows_update(Some(49), Some(false), 50));
+        // Charging overrides a low battery
+        assert!(battery_allows_update(Some(5), Some(true), 50));
+        // No battery reading: USB powered or older firmware
+        assert!(battery_allows_update(None, None, 50));
+        assert!(battery_allows_update(Some(0), None, 0));
+    }
+
+    fn make_knob(id: &str, version: Option<&str>, battery: Option<u8>) -> KnobSummary {
+        KnobSummary {
+            knob_id: id.to_string(),
+            name: String::new(),
+            last_seen: chrono::Utc::now(),
+            version: version.map(str::to_string),
+            status: crate::knobs::store::KnobStatus {
+                battery_level: battery,
+                ..Default::default()
+            },
+            last_update: None,
+        }
+    }
+
+    #[test]
+    fn fleet_overview_counts_versions_and_pending() {
+        let mut failed = make_knob("k3", Some("2.4.0-beta.1"), None);
+        failed.last_update = Some(UpdateReport {
+            version: "2.5.0".to_string(),
+            success: false,
+            error_code: Some("ESP_ERR_OTA_VALIDATE_FAILED".to_string()),
+            reported_at: chrono::Utc::now(),
+        });
+        let knobs = vec![
+            make_knob("k1", Some("2.3.0"), Some(20)),
+            make_knob("k2", Some("2.5.0"), None),
+            failed,
+            make_knob("k4", None, None),
+        ];
+
+        let fleet = fleet_overview(&knobs, Some("2.5.0"), 50);
+        let versions: Vec<_> = fleet["versions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["version"].clone())
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                serde_json::json!("2.5.0"),
+                serde_json::json!("2.4.0-beta.1"),
+                serde_json::json!("2.3.0"),
+                serde_json::Value::Null,
+            ]
+        );
+        assert_eq!(fleet["channels"]["stable"], 2);
+        assert_eq!(fleet["channels"]["beta"], 1);
+        assert_eq!(fleet["channels"]["unknown"], 1);
+
+        let pending = fleet["pending"].as_array().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0]["knob_id"], "k1");
+        assert_eq!(pending[0]["deferred"], true);
+        assert_eq!(pending[1]["deferred"], false);
+
+        assert_eq!(fleet["last_results"]["failed"], 1);
+        assert_eq!(fleet["last_results"]["succeeded"], 0);
+    }
 }
@@ -3,7 +3,7 @@
 //! Each knob has:
 //! - Unique ID (from ESP32 chip ID)
 //! - Name (user-assigned)
-//! - Configuration (power saving, display rotation, etc.)
+//! - Configuration (power saving, display rotation, encoder feel, bandwidth profile, etc.)
 //! - Status (battery level, current zone, last seen)
 
 use chrono::{DateTime, Utc};
@@ -14,10 +14,17 @@ use std::fs;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use super::art_mode::ArtModeSource;
+use super::boot::{normalize_steps, BootStep};
+use super::clock::is_zone_name;
+use super::hqp_cycle::HqpCycleConfig;
 use crate::config::{get_config_file_path, read_config_file};
 
 const KNOBS_FILE: &str = "knobs.json";
 
+/// Minimum stopped-playback poll interval served to low-bandwidth knobs
+pub const LOW_BANDWIDTH_MIN_POLL_STOPPED_SEC: u32 = 180;
+
 /// Power mode configuration (timeout-based state transition)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PowerModeConfig {
@@ -25,6 +32,49 @@ pub struct PowerModeConfig {
     pub timeout_sec: u32,
 }
 
+/// Largest accepted `detents_per_step`
+pub const MAX_DETENTS_PER_STEP: u8 = 8;
+
+/// How turning speed scales volume changes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Acceleration {
+    /// Same change per step however fast the knob turns
+    #[default]
+    Off,
+    /// Change grows in proportion to turning speed
+    Linear,
+    /// Slow turns stay precise, fast turns cover the range quickly
+    Exponential,
+}
+
+/// Rotary encoder feel, applied on the knob
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EncoderConfig {
+    pub acceleration: Acceleration,
+    /// Detents per volume step (1 = every detent)
+    pub detents_per_step: u8,
+    /// Turning while pressed changes volume in fine steps
+    pub fine_on_press: bool,
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            acceleration: Acceleration::Off,
+            detents_per_step: 1,
+            fine_on_press: false,
+        }
+    }
+}
+
+impl EncoderConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Knob configuration (synced to device via config_sha)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KnobConfig {
@@ -51,6 +101,38 @@ pub struct KnobConfig {
     pub cpu_freq_scaling_enabled: bool,
     /// Poll interval when playback stopped
     pub sleep_poll_stopped_sec: u32,
+    /// Low bandwidth profile for knobs on weak Wi-Fi: smaller artwork, slower
+    /// polling and a trimmed now_playing payload (see the knob routes).
+    /// Omitted when off so existing config hashes don't change.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub low_bandwidth: bool,
+    /// What art mode shows (see `art_mode`); omitted for the current cover
+    #[serde(default, skip_serializing_if = "ArtModeSource::is_current")]
+    pub art_mode_source: ArtModeSource,
+    /// Volume feel of the encoder; omitted while default
+    #[serde(default, skip_serializing_if = "EncoderConfig::is_default")]
+    pub encoder: EncoderConfig,
+    /// Gesture that cycles HQPlayer filters (see `hqp_cycle`); omitted while off
+    #[serde(default, skip_serializing_if = "HqpCycleConfig::is_default")]
+    pub hqp_cycle: HqpCycleConfig,
+    /// Startup macro served from `/config/{knob_id}/boot` (see `boot`);
+    /// omitted while empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boot: Vec<BootStep>,
+
+    // Clock and locale; unset ones follow the bridge host (see `clock`)
+    /// IANA time zone, e.g. `Europe/Berlin`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// BCP 47 locale for day and month names, e.g. `en-GB`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// 24-hour clock; 12-hour locales (e.g. `en-US`) default to false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock_24h: Option<bool>,
+    /// NTP server for the knob to sync with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntp_server: Option<String>,
 }
 
 impl Default for KnobConfig {
@@ -93,10 +175,32 @@ impl Default for KnobConfig {
             wifi_power_save_enabled: false,
             cpu_freq_scaling_enabled: false,
             sleep_poll_stopped_sec: 60,
+            low_bandwidth: false,
+            art_mode_source: ArtModeSource::Current,
+            encoder: EncoderConfig::default(),
+            hqp_cycle: HqpCycleConfig::default(),
+            boot: Vec::new(),
+            timezone: None,
+            locale: None,
+            clock_24h: None,
+            ntp_server: None,
         }
     }
 }
 
+impl KnobConfig {
+    /// Config as served to the device, with the bandwidth profile applied
+    pub fn for_device(&self) -> KnobConfig {
+        let mut config = self.clone();
+        if config.low_bandwidth {
+            config.sleep_poll_stopped_sec = config
+                .sleep_poll_stopped_sec
+                .max(LOW_BANDWIDTH_MIN_POLL_STOPPED_SEC);
+        }
+        config
+    }
+}
+
 /// Knob runtime status (updated on each request)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct KnobStatus {
@@ -106,6 +210,24 @@ pub struct KnobStatus {
     pub ip: Option<String>,
 }
 
+/// Outcome of a knob's last OTA update (`POST /firmware/report`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdateReport {
+    /// Firmware version the knob tried to install
+    pub version: String,
+    pub success: bool,
+    /// Firmware's error code on failure, e.g. `ESP_ERR_OTA_VALIDATE_FAILED`
+    pub error_code: Option<String>,
+    pub reported_at: DateTime<Utc>,
+}
+
+/// Reported outcomes of updates to one firmware version
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub struct UpdateOutcomes {
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
 /// Registered knob device
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Knob {
@@ -115,6 +237,8 @@ pub struct Knob {
     pub config: KnobConfig,
     pub config_sha: String,
     pub status: KnobStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<UpdateReport>,
 }
 
 /// Compute SHA256 hash of config (first 8 chars)
@@ -213,6 +337,7 @@ impl KnobStore {
             config,
             config_sha,
             status: KnobStatus::default(),
+            last_update: None,
         };
 
         knobs.insert(knob_id.to_string(), knob.clone());
@@ -223,11 +348,17 @@ impl KnobStore {
         knob
     }
 
-    /// Update knob status (battery, zone, IP)
-    pub async fn update_status(&self, knob_id: &str, updates: KnobStatusUpdate) {
+    /// Update knob status (battery, zone, IP); returns the status it replaced
+    pub async fn update_status(
+        &self,
+        knob_id: &str,
+        updates: KnobStatusUpdate,
+    ) -> Option<KnobStatus> {
         let mut knobs = self.knobs.write().await;
 
+        let mut previous = None;
         if let Some(knob) = knobs.get_mut(knob_id) {
+            previous = Some(knob.status.clone());
             if let Some(level) = updates.battery_level {
                 knob.status.battery_level = Some(level);
             }
@@ -245,6 +376,42 @@ impl KnobStore {
 
         drop(knobs);
         self.save_to_disk().await;
+        previous
+    }
+
+    /// Store a knob's OTA outcome; a successful update also sets its version
+    pub async fn record_update(&self, knob_id: &str, report: UpdateReport) -> bool {
+        let mut knobs = self.knobs.write().await;
+
+        let Some(knob) = knobs.get_mut(knob_id) else {
+            return false;
+        };
+        if report.success {
+            knob.version = Some(report.version.clone());
+        }
+        knob.last_update = Some(report);
+        knob.last_seen = Utc::now();
+
+        drop(knobs);
+        self.save_to_disk().await;
+        true
+    }
+
+    /// Knobs whose last update went to `version`, by outcome (rollout health)
+    pub async fn update_outcomes(&self, version: &str) -> UpdateOutcomes {
+        let knobs = self.knobs.read().await;
+        knobs
+            .values()
+            .filter_map(|k| k.last_update.as_ref())
+            .filter(|r| r.version == version)
+            .fold(UpdateOutcomes::default(), |mut outcomes, r| {
+                if r.success {
+                    outcomes.succeeded += 1;
+                } else {
+                    outcomes.failed += 1;
+                }
+                outcomes
+            })
     }
 
     /// Update knob configuration
@@ -296,6 +463,37 @@ impl KnobStore {
         if let Some(v) = updates.sleep_poll_stopped_sec {
             knob.config.sleep_poll_stopped_sec = v;
         }
+        if let Some(v) = updates.low_bandwidth {
+            knob.config.low_bandwidth = v;
+        }
+        if let Some(v) = updates.art_mode_source {
+            knob.config.art_mode_source = v;
+        }
+        if let Some(v) = updates.encoder {
+            knob.config.encoder = EncoderConfig {
+                detents_per_step: v.detents_per_step.clamp(1, MAX_DETENTS_PER_STEP),
+                ..v
+            };
+        }
+        if let Some(v) = updates.hqp_cycle {
+            knob.config.hqp_cycle = v;
+        }
+        if let Some(v) = updates.boot {
+            knob.config.boot = normalize_steps(v);
+        }
+        // Clock settings: null (or an empty string) goes back to the host's
+        if let Some(v) = updates.timezone {
+            knob.config.timezone = non_empty(v).filter(|tz| is_zone_name(tz));
+        }
+        if let Some(v) = updates.locale {
+            knob.config.locale = non_empty(v);
+        }
+        if let Some(v) = updates.clock_24h {
+            knob.config.clock_24h = v;
+        }
+        if let Some(v) = updates.ntp_server {
+            knob.config.ntp_server = non_empty(v);
+        }
 
         // Recompute config hash
         knob.config_sha = compute_sha(&knob.config, &knob.name);
@@ -324,10 +522,17 @@ impl KnobStore {
                 last_seen: knob.last_seen,
                 version: knob.version.clone(),
                 status: knob.status.clone(),
+                last_update: knob.last_update.clone(),
             })
             .collect()
     }
 
+    /// Whether a knob uses the low bandwidth profile (false for unknown knobs)
+    pub async fn is_low_bandwidth(&self, knob_id: &str) -> bool {
+        let knobs = self.knobs.read().await;
+        knobs.get(knob_id).is_some_and(|k| k.config.low_bandwidth)
+    }
+
     /// Get config SHA for a knob (for change detection)
     pub async fn get_config_sha(&self, knob_id: &str) -> Option<String> {
         let knobs = self.knobs.read().await;
@@ -361,6 +566,34 @@ pub struct KnobConfigUpdate {
     pub wifi_power_save_enabled: Option<bool>,
     pub cpu_freq_scaling_enabled: Option<bool>,
     pub sleep_poll_stopped_sec: Option<u32>,
+    pub low_bandwidth: Option<bool>,
+    pub art_mode_source: Option<ArtModeSource>,
+    pub encoder: Option<EncoderConfig>,
+    pub hqp_cycle: Option<HqpCycleConfig>,
+    pub boot: Option<Vec<BootStep>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub timezone: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub locale: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub clock_24h: Option<Option<bool>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub ntp_server: Option<Option<String>>,
+}
+
+/// Tells an explicit `null` (`Some(None)`, reset) from a missing field (`None`)
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
 }
 
 /// Summary for listing knobs
@@ -371,4 +604,6 @@ pub struct KnobSummary {
     pub last_seen: DateTime<Utc>,
     pub version: Option<String>,
     pub status: KnobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<UpdateReport>,
 }
@@ -0,0 +1,277 @@
+//! Zone-scoped event stream for knob displays
+//!
+//! `GET /knob/events?zone_id=...` pushes compact hints for a single zone, so a
+//! knob can refetch `/knob/now_playing` when something changed and only
+//! re-download artwork when its version changes, instead of polling both on a
+//! timer:
+//! - `image` - artwork version and URL (sent on connect, then on change)
+//! - `now_playing` - the zone's state, track or volume changed
+//! - `zone_removed` - the zone went away
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+
+use super::routes::{authorize_knob, extract_knob_id};
+use crate::aggregator::ZoneAggregator;
+use crate::api::{ApiError, AppState, ErrorCode};
+use crate::bus::{BusEvent, Zone};
+
+/// Query params for the knob event stream
+#[derive(Deserialize)]
+pub struct KnobEventsQuery {
+    pub zone_id: Option<String>,
+    pub knob_id: Option<String>,
+}
+
+/// Payload of the `image` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImageHint {
+    pub zone_id: String,
+    /// Artwork version, null when the zone has no artwork
+    pub v: Option<String>,
+    /// Image URL carrying the version, null when the zone has no artwork
+    pub url: Option<String>,
+}
+
+/// Short version of an image key (first 8 hex chars of its SHA-256)
+pub fn image_version(image_key: &str) -> String {
+    let hash = Sha256::digest(image_key.as_bytes());
+    hex::encode(&hash[..4])
+}
+
+/// Remembers the artwork version last sent, so `image` is only sent on change
+struct ImageTracker {
+    zone_id: String,
+    /// `None` until a version has been sent
+    sent: Option<Option<String>>,
+}
+
+impl ImageTracker {
+    fn new(zone_id: String) -> Self {
+        Self {
+            zone_id,
+            sent: None,
+        }
+    }
+
+    /// Hint for the zone's current image key, if its version differs from the last one sent
+    fn update(&mut self, image_key: Option<&str>) -> Option<ImageHint> {
+        let version = image_key.map(image_version);
+        if self.sent.as_ref() == Some(&version) {
+            return None;
+        }
+        self.sent = Some(version.clone());
+        let url = version.as_ref().map(|v| {
+            format!(
+                "/knob/now_playing/image?zone_id={}&v={}",
+                urlencoding::encode(&self.zone_id),
+                v
+            )
+        });
+        Some(ImageHint {
+            zone_id: self.zone_id.clone(),
+            v: version,
+            url,
+        })
+    }
+
+    /// Forget the last version, so the next update is sent even if unchanged
+    fn reset(&mut self) {
+        self.sent = None;
+    }
+}
+
+struct KnobStream {
+    rx: broadcast::Receiver<BusEvent>,
+    shutdown: CancellationToken,
+    aggregator: Arc<ZoneAggregator>,
+    zone_id: String,
+    images: ImageTracker,
+    pending: VecDeque<Event>,
+}
+
+fn zone_image_key(zone: &Zone) -> Option<&str> {
+    zone.now_playing
+        .as_ref()
+        .and_then(|np| np.image_key.as_deref())
+}
+
+impl KnobStream {
+    fn push_image(&mut self, image_key: Option<&str>) {
+        if let Some(hint) = self.images.update(image_key) {
+            if let Ok(json) = serde_json::to_string(&hint) {
+                self.pending
+                    .push_back(Event::default().event("image").data(json));
+            }
+        }
+    }
+
+    fn push_hint(&mut self, event: &str) {
+        let json = serde_json::json!({ "zone_id": self.zone_id });
+        self.pending
+            .push_back(Event::default().event(event).data(json.to_string()));
+    }
+
+    /// Send the zone's current artwork and a refetch hint (on connect and after lagging)
+    async fn sync(&mut self) {
+        let image_key = self
+            .aggregator
+            .get_zone(&self.zone_id)
+            .await
+            .and_then(|zone| zone_image_key(&zone).map(String::from));
+        self.push_image(image_key.as_deref());
+        self.push_hint("now_playing");
+    }
+
+    async fn handle(&mut self, event: BusEvent) {
+        match event {
+            BusEvent::ZoneDiscovered { zone } if zone.zone_id == self.zone_id => {
+                self.push_image(zone_image_key(&zone));
+                self.push_hint("now_playing");
+            }
+            BusEvent::NowPlayingChanged {
+                zone_id, image_key, ..
+            } if zone_id.as_str() == self.zone_id => {
+                self.push_image(image_key.as_deref());
+                self.push_hint("now_playing");
+            }
+            BusEvent::ZoneUpdated { zone_id, .. } if zone_id.as_str() == self.zone_id => {
+                self.push_hint("now_playing");
+            }
+            BusEvent::VolumeChanged { output_id, .. } => {
+                let is_ours = self
+                    .aggregator
+                    .get_zone(&self.zone_id)
+                    .await
+                    .and_then(|zone| zone.volume_control)
+                    .and_then(|vc| vc.output_id)
+                    .is_some_and(|id| id == output_id);
+                if is_ours {
+                    self.push_hint("now_playing");
+                }
+            }
+            BusEvent::ZoneRemoved { zone_id } if zone_id.as_str() == self.zone_id => {
+                self.images.reset();
+                self.push_hint("zone_removed");
+            }
+            BusEvent::ZonesFlushed { zone_ids, .. } if zone_ids.contains(&self.zone_id) => {
+                self.images.reset();
+                self.push_hint("zone_removed");
+            }
+            _ => {}
+        }
+    }
+
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let received = tokio::select! {
+                _ = self.shutdown.cancelled() => return None,
+                received = self.rx.recv() => received,
+            };
+            match received {
+                Ok(event) => self.handle(event).await,
+                Err(RecvError::Lagged(_)) => self.sync().await,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// GET /knob/events?zone_id= - Server-Sent Events for one zone's knob display
+///
+/// Starts with the zone's current `image` and a `now_playing` hint. The image
+/// URL carries the artwork version as `v`, so a knob only fetches it when `v`
+/// changes.
+pub async fn knob_events_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<KnobEventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    authorize_knob(
+        &state,
+        &headers,
+        extract_knob_id(&headers, params.knob_id.as_deref()).as_deref(),
+    )?;
+    let zone_id = params
+        .zone_id
+        .ok_or_else(|| ApiError::new(ErrorCode::MissingZoneId, "zone_id required"))?;
+    // Legacy zone_id without prefix (assume Roon), as for /knob/now_playing
+    let zone_id = if zone_id.contains(':') {
+        zone_id
+    } else {
+        format!("roon:{}", zone_id)
+    };
+
+    // Subscribe before reading the zone, so no change falls in between
+    let mut knob_stream = KnobStream {
+        rx: state.bus.subscribe(),
+        shutdown: state.shutdown.clone(),
+        aggregator: state.aggregator.clone(),
+        images: ImageTracker::new(zone_id.clone()),
+        zone_id,
+        pending: VecDeque::new(),
+    };
+    knob_stream.sync().await;
+
+    let events = stream::unfold(knob_stream, |mut knob_stream| async move {
+        let event = knob_stream.next_event().await?;
+        Some((Ok(event), knob_stream))
+    });
+
+    Ok(Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("ping"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_hint_only_on_version_change() {
+        let mut images = ImageTracker::new("roon:1".to_string());
+
+        let first = images.update(Some("abc")).unwrap();
+        let v = image_version("abc");
+        assert_eq!(first.v.as_deref(), Some(v.as_str()));
+        assert_eq!(
+            first.url.as_deref(),
+            Some(format!("/knob/now_playing/image?zone_id=roon%3A1&v={}", v).as_str())
+        );
+        assert!(images.update(Some("abc")).is_none());
+
+        assert!(images.update(Some("def")).is_some());
+        let cleared = images.update(None).unwrap();
+        assert_eq!(cleared.v, None);
+        assert_eq!(cleared.url, None);
+        assert!(images.update(None).is_none());
+
+        images.reset();
+        assert!(images.update(None).is_some());
+    }
+
+    #[test]
+    fn image_version_is_short_and_stable() {
+        assert_eq!(image_version("abc").len(), 8);
+        assert_eq!(image_version("abc"), image_version("abc"));
+        assert_ne!(image_version("abc"), image_version("abd"));
+    }
+}
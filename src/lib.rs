@@ -34,22 +34,76 @@ pub mod components;
 
 // Server-only modules (excluded from WASM build)
 #[cfg(feature = "server")]
+pub mod activity;
+#[cfg(feature = "server")]
 pub mod adapters;
 #[cfg(feature = "server")]
 pub mod aggregator;
 #[cfg(feature = "server")]
 pub mod api;
 #[cfg(feature = "server")]
+pub mod artwork_cache;
+#[cfg(feature = "server")]
+pub mod backup;
+#[cfg(feature = "server")]
 pub mod bus;
 #[cfg(feature = "server")]
+pub mod cast;
+#[cfg(feature = "server")]
+pub mod clock;
+#[cfg(feature = "server")]
 pub mod config;
 #[cfg(feature = "server")]
 pub mod coordinator;
+#[cfg(feature = "test-util")]
+pub mod demo;
+#[cfg(feature = "server")]
+pub mod diagnostics;
 #[cfg(feature = "server")]
 pub mod embedded;
 #[cfg(feature = "server")]
 pub mod firmware;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "server")]
+pub mod history;
+#[cfg(feature = "server")]
+pub mod hooks;
+#[cfg(feature = "server")]
+pub mod http_cache;
 #[cfg(feature = "server")]
 pub mod knobs;
 #[cfg(feature = "server")]
+pub mod lyrics;
+#[cfg(feature = "server")]
 pub mod mdns;
+#[cfg(feature = "server")]
+pub mod metrics;
+#[cfg(feature = "server")]
+pub mod migrate;
+#[cfg(feature = "test-util")]
+pub mod mock_servers;
+#[cfg(feature = "server")]
+pub mod mqtt;
+#[cfg(feature = "server")]
+pub mod remote;
+#[cfg(feature = "server")]
+pub mod scenes;
+#[cfg(feature = "server")]
+pub mod selection;
+#[cfg(feature = "server")]
+pub mod soak;
+#[cfg(feature = "server")]
+pub mod startup;
+#[cfg(feature = "server")]
+pub mod text;
+#[cfg(feature = "server")]
+pub mod tokens;
+#[cfg(feature = "server")]
+pub mod webhooks;
+#[cfg(feature = "server")]
+pub mod zone_metadata;
+#[cfg(feature = "server")]
+pub mod zone_resolver;
@@ -0,0 +1,345 @@
+//! Lyrics lookup by track metadata
+//!
+//! Two optional providers, tried in order:
+//! - a local directory of `Artist - Title.lrc` (synced) or `.txt` (plain) files
+//! - LRCLIB (<https://lrclib.net>), a free synced-lyrics database
+//!
+//! Results, including misses, are cached per track so a zone page polling
+//! `/zones/{zone_id}/lyrics` doesn't repeat the lookup.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::LyricsConfig;
+
+const LRCLIB_SEARCH_URL: &str = "https://lrclib.net/api/search";
+
+/// Per-request timeout for LRCLIB
+const LRCLIB_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// LRCLIB matches whose duration differs by more than this are ignored
+const DURATION_TOLERANCE_SECS: f64 = 5.0;
+
+/// Cached tracks before the cache is cleared
+const CACHE_CAPACITY: usize = 256;
+
+/// One line of lyrics; `time_secs` is set for synced lyrics
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LyricLine {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_secs: Option<f64>,
+    pub text: String,
+}
+
+/// Lyrics for one track
+#[derive(Debug, Clone, Serialize)]
+pub struct Lyrics {
+    /// `local` or `lrclib`
+    pub source: &'static str,
+    /// Every line carries a timestamp
+    pub synced: bool,
+    pub instrumental: bool,
+    pub lines: Vec<LyricLine>,
+}
+
+impl Lyrics {
+    fn from_text(source: &'static str, text: &str, synced: bool) -> Self {
+        let lines = if synced {
+            parse_lrc(text)
+        } else {
+            plain_lines(text)
+        };
+        Self {
+            source,
+            synced: synced && !lines.is_empty(),
+            instrumental: false,
+            lines,
+        }
+    }
+
+    /// Index of the line being sung at `position_secs` (synced lyrics only)
+    pub fn current_line(&self, position_secs: f64) -> Option<usize> {
+        if !self.synced {
+            return None;
+        }
+        self.lines
+            .iter()
+            .rposition(|line| line.time_secs.is_some_and(|t| t <= position_secs))
+    }
+}
+
+/// Track to look up
+#[derive(Debug, Clone)]
+pub struct TrackQuery<'a> {
+    pub artist: &'a str,
+    pub title: &'a str,
+    pub album: &'a str,
+    pub duration_secs: Option<f64>,
+}
+
+/// Parse LRC text (`[mm:ss.xx]text`, several timestamps per line allowed)
+/// into lines ordered by time; metadata tags like `[ar:...]` are skipped
+pub fn parse_lrc(text: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim();
+        let mut times = Vec::new();
+        while let Some(stripped) = rest.strip_prefix('[') {
+            let Some(end) = stripped.find(']') else {
+                break;
+            };
+            if let Some(time) = parse_timestamp(&stripped[..end]) {
+                times.push(time);
+            }
+            rest = &stripped[end + 1..];
+        }
+        for time_secs in times {
+            lines.push(LyricLine {
+                time_secs: Some(time_secs),
+                text: rest.trim().to_string(),
+            });
+        }
+    }
+    lines.sort_by(|a, b| {
+        a.time_secs
+            .partial_cmp(&b.time_secs)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    lines
+}
+
+/// `mm:ss`, `mm:ss.xx` or `mm:ss:xx` in seconds
+fn parse_timestamp(tag: &str) -> Option<f64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u32 = minutes.trim().parse().ok()?;
+    let seconds: f64 = seconds.trim().replacen(':', ".", 1).parse().ok()?;
+    Some(f64::from(minutes) * 60.0 + seconds)
+}
+
+fn plain_lines(text: &str) -> Vec<LyricLine> {
+    text.lines()
+        .map(|line| LyricLine {
+            time_secs: None,
+            text: line.trim().to_string(),
+        })
+        .collect()
+}
+
+/// Lowercase letters and digits only, so `AC/DC - T.N.T.` matches `acdc - tnt`
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// LRCLIB search result (fields used here)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LrclibRecord {
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    instrumental: bool,
+    plain_lyrics: Option<String>,
+    synced_lyrics: Option<String>,
+}
+
+/// Lyrics lookup with a per-track cache; disabled without a config
+#[derive(Debug, Default)]
+pub struct LyricsService {
+    config: Option<LyricsConfig>,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, Option<Lyrics>>>,
+}
+
+impl LyricsService {
+    pub fn new(config: Option<LyricsConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(LRCLIB_TIMEOUT)
+            .user_agent(concat!("unified-hifi-control/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            client,
+            cache: Mutex::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|c| c.dir.is_some() || c.lrclib)
+    }
+
+    /// Lyrics for a track, `None` if no provider has them
+    pub async fn lookup(&self, track: &TrackQuery<'_>) -> Option<Lyrics> {
+        let config = self.config.as_ref()?;
+        if track.title.is_empty() {
+            return None;
+        }
+        let key = format!("{}\n{}", normalize(track.artist), normalize(track.title));
+        if let Some(cached) = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            return cached.clone();
+        }
+
+        let mut lyrics = None;
+        if let Some(ref dir) = config.dir {
+            lyrics = lookup_local(dir, track).await;
+        }
+        if lyrics.is_none() && config.lrclib {
+            match self.lookup_lrclib(track).await {
+                Ok(found) => lyrics = found,
+                Err(e) => {
+                    // Not cached, so the next request retries
+                    tracing::debug!("LRCLIB lookup failed for {}: {}", track.title, e);
+                    return None;
+                }
+            }
+        }
+
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(key, lyrics.clone());
+        lyrics
+    }
+
+    async fn lookup_lrclib(&self, track: &TrackQuery<'_>) -> anyhow::Result<Option<Lyrics>> {
+        let mut query = vec![("track_name", track.title), ("artist_name", track.artist)];
+        if !track.album.is_empty() {
+            query.push(("album_name", track.album));
+        }
+        let records: Vec<LrclibRecord> = self
+            .client
+            .get(LRCLIB_SEARCH_URL)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // Closest duration wins; without one, LRCLIB's ranking does
+        let record = match track.duration_secs {
+            Some(duration) => records
+                .into_iter()
+                .filter_map(|r| {
+                    let diff = (r.duration? - duration).abs();
+                    (diff <= DURATION_TOLERANCE_SECS).then_some((diff, r))
+                })
+                .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(_, r)| r),
+            None => records.into_iter().next(),
+        };
+
+        Ok(record.and_then(|r| {
+            if r.instrumental {
+                return Some(Lyrics {
+                    source: "lrclib",
+                    synced: false,
+                    instrumental: true,
+                    lines: Vec::new(),
+                });
+            }
+            match (r.synced_lyrics, r.plain_lyrics) {
+                (Some(synced), _) if !synced.trim().is_empty() => {
+                    Some(Lyrics::from_text("lrclib", &synced, true))
+                }
+                (_, Some(plain)) if !plain.trim().is_empty() => {
+                    Some(Lyrics::from_text("lrclib", &plain, false))
+                }
+                _ => None,
+            }
+        }))
+    }
+}
+
+/// `Artist - Title.lrc`, or `.txt` for plain lyrics, matched loosely
+async fn lookup_local(dir: &std::path::Path, track: &TrackQuery<'_>) -> Option<Lyrics> {
+    let wanted = normalize(&format!("{} - {}", track.artist, track.title));
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Cannot read lyrics directory {}: {}", dir.display(), e);
+            return None;
+        }
+    };
+
+    let mut plain = None;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let matches = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .is_some_and(|stem| normalize(stem) == wanted);
+        if !matches {
+            continue;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("lrc") => {
+                let text = tokio::fs::read_to_string(&path).await.ok()?;
+                return Some(Lyrics::from_text("local", &text, true));
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("txt") => plain = Some(path),
+            _ => {}
+        }
+    }
+
+    let text = tokio::fs::read_to_string(plain?).await.ok()?;
+    Some(Lyrics::from_text("local", &text, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc() {
+        let lines = parse_lrc("[ar:Artist]\n[00:12.50]First\n[01:02.00][00:05]Second\nnot timed\n");
+        assert_eq!(
+            lines,
+            vec![
+                LyricLine {
+                    time_secs: Some(5.0),
+                    text: "Second".to_string()
+                },
+                LyricLine {
+                    time_secs: Some(12.5),
+                    text: "First".to_string()
+                },
+                LyricLine {
+                    time_secs: Some(62.0),
+                    text: "Second".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_current_line() {
+        let lyrics = Lyrics::from_text("local", "[00:10]One\n[00:20]Two\n", true);
+        assert_eq!(lyrics.current_line(5.0), None);
+        assert_eq!(lyrics.current_line(10.0), Some(0));
+        assert_eq!(lyrics.current_line(25.0), Some(1));
+
+        let plain = Lyrics::from_text("local", "One\nTwo", false);
+        assert_eq!(plain.current_line(25.0), None);
+    }
+
+    #[test]
+    fn test_normalize_matches_loosely() {
+        assert_eq!(normalize("AC/DC - T.N.T."), normalize("acdc - tnt"));
+    }
+}
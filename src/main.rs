@@ -6,9 +6,20 @@
 #[cfg(feature = "server")]
 mod server {
     use unified_hifi_control::{
-        adapters, aggregator, api, app, bus, config, coordinator, embedded, firmware, knobs, mdns,
+        activity, adapters, aggregator, api, app, backup, bus, cast, config, coordinator, embedded,
+        firmware, history, knobs, lyrics, mdns, mqtt, remote, soak, startup, text, webhooks,
+        zone_metadata,
     };
 
+    #[cfg(feature = "test-util")]
+    use unified_hifi_control::demo;
+
+    #[cfg(feature = "graphql")]
+    use unified_hifi_control::graphql;
+
+    #[cfg(feature = "grpc")]
+    use unified_hifi_control::grpc;
+
     // Import Startable trait for adapter lifecycle methods
     use adapters::Startable;
 
@@ -30,6 +41,9 @@ mod server {
     use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+    /// Longest the post-shutdown adapter cleanup may take
+    const ADAPTER_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
     /// Flash page - redirects to external web flasher
     async fn flash_page() -> impl IntoResponse {
         Html(
@@ -63,7 +77,35 @@ mod server {
         Redirect::to("/settings")
     }
 
-    pub async fn run() -> Result<()> {
+    /// Longest `healthcheck` waits for `/healthz`
+    const HEALTHCHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+    /// `healthcheck` subcommand: GET the local `/healthz`, failing (non-zero exit)
+    /// unless it answers 200. Lets container healthchecks use the binary itself.
+    pub async fn healthcheck() -> Result<()> {
+        let config = config::load_config()?;
+        let url = format!("http://127.0.0.1:{}/healthz", config.port);
+        let response = reqwest::Client::builder()
+            .timeout(HEALTHCHECK_TIMEOUT)
+            .build()?
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("{} unreachable: {}", url, e))?;
+        if !response.status().is_success() {
+            anyhow::bail!("{} returned {}", url, response.status());
+        }
+        println!("ok");
+        Ok(())
+    }
+
+    /// `--soak`: load test the running bridge and print latency percentiles
+    pub async fn soak(args: &[String]) -> Result<()> {
+        let config = config::load_config()?;
+        soak::run(soak::SoakOptions::from_args(args, config.port)?).await
+    }
+
+    pub async fn run(demo_mode: bool) -> Result<()> {
         // Initialize logging
         // Priority: RUST_LOG > LOG_LEVEL (legacy) > default
         let log_filter = std::env::var("RUST_LOG")
@@ -74,6 +116,7 @@ mod server {
             .with(tracing_subscriber::EnvFilter::new(&log_filter))
             .with(tracing_subscriber::fmt::layer())
             .init();
+        startup::begin();
 
         tracing::info!(
             "Starting Unified Hi-Fi Control (Rust) v{} ({})",
@@ -89,13 +132,31 @@ mod server {
                 assets.len()
             );
             tracing::debug!("Embedded files: {:?}", assets);
+            startup::record_assets("embedded", assets.len());
         } else {
             tracing::info!("No embedded WASM assets (development mode, use dx serve)");
         }
 
+        // Demo mode must be set up before any config is read (it redirects the config dir)
+        #[cfg(feature = "test-util")]
+        let demo_backends = if demo_mode {
+            Some(start_demo_backends().await?)
+        } else {
+            None
+        };
+        #[cfg(not(feature = "test-util"))]
+        if demo_mode {
+            anyhow::bail!(
+                "--demo requires a build with the `test-util` feature (cargo run --features test-util -- --demo)"
+            );
+        }
+
         // Load configuration
         let config = config::load_config()?;
         tracing::info!("Configuration loaded, port: {}", config.port);
+        if let Some(interfaces) = &config.discovery_interfaces {
+            adapters::discovery::set_interfaces(interfaces);
+        }
 
         // Issue #76: Migrate config files to unified-hifi/ subdirectory
         config::migrate_config_to_subdir();
@@ -131,11 +192,17 @@ mod server {
 
         // Roon adapter - coordinator handles starting based on enabled state
         // Issue #169: Pass knob_store for controller count in extension status
-        let roon = Arc::new(adapters::roon::RoonAdapter::new_configured(
-            bus.clone(),
-            base_url.clone(),
-            knob_store.clone(),
-        ));
+        let roon = Arc::new(
+            adapters::roon::RoonAdapter::new_configured(
+                bus.clone(),
+                base_url.clone(),
+                knob_store.clone(),
+            )
+            .with_reconnect_backoff(
+                std::time::Duration::from_secs(config.roon.reconnect_initial_secs.max(1)),
+                std::time::Duration::from_secs(config.roon.reconnect_max_secs),
+            ),
+        );
 
         // HQPlayer instance manager (multi-instance support, no settings toggle)
         let hqp_instances = Arc::new(adapters::hqplayer::HqpInstanceManager::new(bus.clone()));
@@ -171,16 +238,18 @@ mod server {
                 tracing::info!("HQPlayer default instance: {}:{}", host, status.port);
             }
         }
-
-        // Auto-connect HQPlayer if configured (establishes TCP connection at startup)
-        if hqplayer.is_configured().await {
-            match hqplayer.get_pipeline_status().await {
-                Ok(_) => tracing::info!("HQPlayer auto-connected at startup"),
-                Err(e) => tracing::warn!(
-                    "HQPlayer auto-connect failed (will retry on page access): {}",
-                    e
-                ),
-            }
+        #[cfg(feature = "test-util")]
+        if let Some(ref demo) = demo_backends {
+            hqplayer
+                .configure(
+                    demo.hqp.addr().ip().to_string(),
+                    Some(demo.hqp.addr().port()),
+                    None,
+                    None,
+                    None,
+                )
+                .await;
+            hqp_instances.save_to_config().await;
         }
 
         // HQP zone link service
@@ -205,6 +274,16 @@ mod server {
             )
             .await;
         }
+        #[cfg(feature = "test-util")]
+        if let Some(ref demo) = demo_backends {
+            lms.configure(
+                demo.lms.addr().ip().to_string(),
+                Some(demo.lms.addr().port()),
+                None,
+                None,
+            )
+            .await;
+        }
 
         // OpenHome adapter
         let openhome = Arc::new(adapters::openhome::OpenHomeAdapter::new(bus.clone()));
@@ -212,11 +291,29 @@ mod server {
         // UPnP adapter
         let upnp = Arc::new(adapters::upnp::UPnPAdapter::new(bus.clone()));
 
-        // =========================================================================
-        // Start enabled adapters (single codepath using coordinator)
-        // =========================================================================
+        // AirPlay adapter (read-only receivers)
+        let airplay = Arc::new(adapters::airplay::AirPlayAdapter::new(bus.clone()));
+
+        // Snapcast adapter (loads its saved server from disk)
+        let snapcast = Arc::new(adapters::snapcast::SnapcastAdapter::new(bus.clone()));
+
+        // HEOS adapter (loads its saved device from disk)
+        let heos = Arc::new(adapters::heos::HeosAdapter::new(bus.clone()));
+
+        // Kodi adapter (loads its saved host from disk)
+        let kodi = Arc::new(adapters::kodi::KodiAdapter::new(bus.clone()));
+
+        // Spotify adapter (loads its saved authorization from disk)
+        let spotify = Arc::new(adapters::spotify::SpotifyAdapter::new(bus.clone()));
+
+        // HTTP JSON adapter (loads its device templates from disk)
+        let http = Arc::new(adapters::http_json::HttpJsonAdapter::new(bus.clone()));
+
+        // Plexamp adapter (loads its player hosts from disk)
+        let plexamp = Arc::new(adapters::plexamp::PlexampAdapter::new(bus.clone()));
 
         // Build list of startable adapters
+        // (started in the background once the HTTP listener is bound, see below)
         // Note: lms_cli shares config with lms - both start when LMS is configured
         let startable_adapters: Vec<Arc<dyn adapters::Startable>> = vec![
             roon.clone(),
@@ -224,13 +321,20 @@ mod server {
             lms_cli.clone(),
             openhome.clone(),
             upnp.clone(),
+            airplay.clone(),
+            snapcast.clone(),
+            heos.clone(),
+            kodi.clone(),
+            spotify.clone(),
+            http.clone(),
+            plexamp.clone(),
         ];
 
-        // Single loop to start all enabled adapters
-        coord.start_all_enabled(&startable_adapters).await;
-
         // Initialize ZoneAggregator for unified zone state
-        let zone_aggregator = Arc::new(aggregator::ZoneAggregator::new(bus.clone()));
+        let zone_aggregator = Arc::new(aggregator::ZoneAggregator::with_metadata(
+            bus.clone(),
+            Arc::new(zone_metadata::ZoneMetadataStore::load()),
+        ));
         let aggregator_for_spawn = zone_aggregator.clone();
         tokio::spawn(async move {
             aggregator_for_spawn.run().await;
@@ -259,7 +363,43 @@ mod server {
             startable_adapters.clone(),
             Instant::now(),
             shutdown_token.clone(),
-        );
+        )
+        .with_lyrics(lyrics::LyricsService::new(config.lyrics.clone()))
+        .with_text(text::TextService::new(&config.text))
+        .with_airplay(airplay.clone())
+        .with_snapcast(snapcast.clone())
+        .with_heos(heos.clone())
+        .with_kodi(kodi.clone())
+        .with_spotify(spotify.clone())
+        .with_http(http.clone())
+        .with_plexamp(plexamp.clone());
+
+        // Count tracks played per zone for /metrics
+        let metrics = state.metrics.clone();
+        let metrics_bus = bus.clone();
+        let metrics_shutdown = shutdown_token.clone();
+        tokio::spawn(async move {
+            metrics.run(metrics_bus, metrics_shutdown).await;
+        });
+
+        // Record plays for /history/export
+        history::start(bus.clone(), shutdown_token.clone());
+
+        // Remember played covers for the knob art mode slideshow
+        knobs::art_mode::start(bus.clone(), state.art_mode.clone(), shutdown_token.clone());
+
+        // Apply configured polling intervals and start HQPlayer status refresh
+        api::apply_polling_settings(&state, &app_settings.polling).await;
+        state
+            .hqp_instances
+            .clone()
+            .start_status_refresh(shutdown_token.clone());
+
+        // Watch for assigned NAA endpoints dropping off the network
+        state.hqp_naa.clone().start_monitor(shutdown_token.clone());
+
+        // Scheduled config backups (no-op while disabled in settings)
+        backup::start_scheduler(|| api::load_app_settings().backup, shutdown_token.clone());
 
         // Clone state for shutdown diagnostics
         let state_for_shutdown = state.clone();
@@ -268,6 +408,8 @@ mod server {
         let router = Router::new()
             // Health check
             .route("/status", get(api::status_handler))
+            .route("/healthz", get(api::healthz_handler))
+            .route("/metrics", get(api::metrics_handler))
             // Roon routes
             .route("/roon/status", get(api::roon_status_handler))
             .route("/roon/zones", get(api::roon_zones_handler))
@@ -275,6 +417,12 @@ mod server {
             .route("/roon/control", post(api::roon_control_handler))
             .route("/roon/volume", post(api::roon_volume_handler))
             .route("/roon/image", get(api::roon_image_handler))
+            .route("/roon/core/power", get(api::roon_core_power_handler))
+            .route(
+                "/roon/core/power",
+                post(api::roon_core_power_action_handler),
+            )
+            .route("/roon/core/power/wol", put(api::roon_core_wol_put_handler))
             // HQPlayer routes
             .route("/hqplayer/status", get(api::hqp_status_handler))
             .route("/hqplayer/pipeline", get(api::hqp_pipeline_handler))
@@ -337,13 +485,83 @@ mod server {
                 "/hqp/zones/{zone_id}/pipeline",
                 get(api::hqp_zone_pipeline_handler),
             )
+            .route(
+                "/hqp/zones/{zone_id}/pipeline",
+                post(api::hqp_zone_pipeline_update_handler),
+            )
             // HQPlayer network discovery
             .route("/hqp/discover", get(api::hqp_discover_handler))
+            .route("/hqp/naa", get(api::hqp_naa_handler))
+            .route("/hqp/naa/discover", get(api::hqp_naa_discover_handler))
+            .route(
+                "/hqp/instances/{name}/naa",
+                get(api::hqp_instance_naa_handler),
+            )
+            .route(
+                "/hqp/instances/{name}/naa",
+                put(api::hqp_instance_naa_put_handler),
+            )
+            // CamillaDSP instances and zone links
+            .route(
+                "/camilladsp/instances",
+                get(api::camilladsp_instances_handler),
+            )
+            .route(
+                "/camilladsp/instances",
+                post(api::camilladsp_instance_put_handler),
+            )
+            .route(
+                "/camilladsp/instances/{name}",
+                delete(api::camilladsp_instance_delete_handler),
+            )
+            .route(
+                "/camilladsp/zones/links",
+                get(api::camilladsp_zone_links_handler),
+            )
+            .route(
+                "/camilladsp/zones/link",
+                post(api::camilladsp_zone_link_handler),
+            )
+            .route(
+                "/camilladsp/zones/unlink",
+                post(api::camilladsp_zone_unlink_handler),
+            )
+            .route(
+                "/camilladsp/zones/{zone_id}",
+                get(api::camilladsp_zone_handler),
+            )
+            .route(
+                "/camilladsp/zones/{zone_id}",
+                post(api::camilladsp_zone_update_handler),
+            )
+            // MiniDSP instances and zone links
+            .route("/minidsp/instances", get(api::minidsp_instances_handler))
+            .route(
+                "/minidsp/instances",
+                post(api::minidsp_instance_put_handler),
+            )
+            .route(
+                "/minidsp/instances/{name}",
+                delete(api::minidsp_instance_delete_handler),
+            )
+            .route("/minidsp/zones/links", get(api::minidsp_zone_links_handler))
+            .route("/minidsp/zones/link", post(api::minidsp_zone_link_handler))
+            .route(
+                "/minidsp/zones/unlink",
+                post(api::minidsp_zone_unlink_handler),
+            )
+            .route("/minidsp/zones/{zone_id}", get(api::minidsp_zone_handler))
+            .route(
+                "/minidsp/zones/{zone_id}",
+                post(api::minidsp_zone_update_handler),
+            )
             // LMS routes
             .route("/lms/status", get(api::lms_status_handler))
             .route("/lms/config", get(api::lms_config_handler))
             .route("/lms/configure", post(api::lms_configure_handler))
             .route("/lms/players", get(api::lms_players_handler))
+            .route("/lms/rescan", post(api::lms_rescan_handler))
+            .route("/lms/rescan/status", get(api::lms_rescan_status_handler))
             .route("/lms/player/{player_id}", get(api::lms_player_handler))
             .route("/lms/control", post(api::lms_control_handler))
             .route("/lms/volume", post(api::lms_volume_handler))
@@ -367,18 +585,83 @@ mod server {
             // App settings API
             .route("/api/settings", get(api::api_settings_get_handler))
             .route("/api/settings", post(api::api_settings_post_handler))
+            .route("/api/backups", get(api::backups_list_handler))
+            .route("/api/adapters", get(api::adapters_list_handler))
+            .route("/api/startup-report", get(api::startup_report_handler))
+            .route(
+                "/api/adapters/{name}/restart",
+                post(api::adapters_restart_handler),
+            )
+            .route(
+                "/api/adapters/{name}/config",
+                get(api::adapter_config_get_handler).put(api::adapter_config_put_handler),
+            )
+            .route("/api/backups", post(api::backups_create_handler))
+            .route("/api/backups/restore", post(api::backups_restore_handler))
+            .route("/api/migrate/nodejs", get(api::migrate_preview_handler))
+            .route("/api/migrate/nodejs", post(api::migrate_apply_handler))
+            .route("/api/tokens", get(api::tokens_list_handler))
+            .route("/api/tokens", post(api::tokens_create_handler))
+            .route("/api/tokens/{id}", put(api::tokens_update_handler))
+            .route("/api/tokens/{id}", delete(api::tokens_delete_handler))
+            .route("/api/pair", post(api::pair_handler))
+            .route("/api/pair/code", post(api::pairing_code_handler))
+            .route("/api/knobs/auth", get(knobs::knob_auth_handler))
+            .route("/api/knobs/auth", put(knobs::knob_auth_update_handler))
+            .route("/api/knobs/{id}/token", post(knobs::knob_rotate_handler))
+            .route("/api/knobs/{id}/token", delete(knobs::knob_revoke_handler))
+            // Network diagnostics
+            .route("/debug/connectivity", post(api::debug_connectivity_handler))
+            .route("/debug/adapters/{name}/ops", get(api::adapter_ops_handler))
             // Event stream (SSE)
             .route("/events", get(api::events_handler))
+            .route("/events/poll", get(api::events_poll_handler))
+            .route("/sync", get(api::sync_handler))
             // Knob hardware API routes
             .route("/knob/zones", get(knobs::knob_zones_handler))
             .route("/knob/now_playing", get(knobs::knob_now_playing_handler))
             .route("/knob/now_playing/image", get(knobs::knob_image_handler))
+            .route("/knob/events", get(knobs::knob_events_handler))
+            .route("/knob/art_mode/next", get(knobs::knob_art_next_handler))
+            .route("/knob/hqp_cycle", post(knobs::knob_hqp_cycle_handler))
             .route("/knob/control", post(knobs::knob_control_handler))
             .route("/knob/config", get(knobs::knob_config_handler))
             .route("/knob/config", post(knobs::knob_config_update_handler))
             .route("/knob/devices", get(knobs::knob_devices_handler))
             // Knob protocol routes (firmware uses these paths directly)
             .route("/now_playing", get(knobs::knob_now_playing_handler))
+            .route("/embed/now_playing", get(api::embed_now_playing_handler))
+            // Media for UPnP/DLNA renderers
+            .route("/media/announcements", post(api::clip_upload_handler))
+            .route("/media/announcements/{id}", get(api::clip_handler))
+            .route("/media/artwork/{zone_id}", get(api::media_artwork_handler))
+            .route("/zones/{zone_id}/announce", post(api::announce_handler))
+            // Scenes
+            .route("/scenes/party", post(knobs::party_scene_handler))
+            .route("/api/scenes/party", get(knobs::party_scene_get_handler))
+            .route("/api/scenes/party", put(knobs::party_scene_put_handler))
+            .route("/history/export", get(api::history_export_handler))
+            .route("/history/albums", get(api::history_albums_handler))
+            .route(
+                "/history/albums/artwork",
+                get(api::history_album_artwork_handler),
+            )
+            .route(
+                "/history/albums/play",
+                post(api::history_album_play_handler),
+            )
+            .route("/hooks/{name}", post(api::hook_trigger_handler))
+            .route("/simple/{action}", get(api::simple_handler))
+            .route("/spotify/authorize", get(api::spotify_authorize_handler))
+            .route("/spotify/callback", get(api::spotify_callback_handler))
+            .route("/http/devices", get(api::http_devices_handler))
+            .route("/http/devices", put(api::http_devices_put_handler))
+            .route("/api/hooks", get(api::hooks_list_handler))
+            .route("/api/hooks/{name}", put(api::hooks_put_handler))
+            .route("/api/hooks/{name}", delete(api::hooks_delete_handler))
+            .route("/api/selection", get(api::selection_get_handler))
+            .route("/api/selection", put(api::selection_put_handler))
+            .route("/api/selection", delete(api::selection_delete_handler))
             .route("/now_playing/image", get(knobs::knob_image_handler))
             .route("/control", post(knobs::knob_control_handler))
             .route("/config/{knob_id}", get(knobs::knob_config_by_path_handler))
@@ -386,9 +669,12 @@ mod server {
                 "/config/{knob_id}",
                 put(knobs::knob_config_update_by_path_handler),
             )
+            .route("/config/{knob_id}/boot", get(knobs::knob_boot_handler))
             // Firmware OTA routes
             .route("/firmware/version", get(knobs::firmware_version_handler))
             .route("/firmware/download", get(knobs::firmware_download_handler))
+            .route("/firmware/report", post(knobs::firmware_report_handler))
+            .route("/firmware/fleet", get(knobs::firmware_fleet_handler))
             .route("/manifest-s3.json", get(knobs::manifest_handler))
             .route(
                 "/admin/fetch-firmware",
@@ -396,6 +682,27 @@ mod server {
             )
             // Protocol route: /zones returns JSON (for knob, iOS, etc.)
             .route("/zones", get(knobs::knob_zones_handler))
+            .route("/zones/standby_all", post(knobs::zones_standby_handler))
+            // Rooms (zones grouped by metadata room)
+            .route("/rooms", get(knobs::rooms_handler))
+            .route("/rooms/{room}", get(knobs::room_handler))
+            // Zone metadata (rooms, icons, sort order, colors)
+            .route("/zones/metadata", get(api::zone_metadata_list_handler))
+            .route("/zones/resolve", get(api::zone_resolve_handler))
+            .route("/zones/{zone_id}/lyrics", get(api::zone_lyrics_handler))
+            .route("/zones/{zone_id}/artwork", get(knobs::zone_artwork_handler))
+            .route("/zones/{zone_id}/eq", get(api::zone_eq_handler))
+            .route("/zones/{zone_id}/eq", put(api::zone_eq_put_handler))
+            .route("/zones/{zone_id}/input", get(api::zone_input_handler))
+            .route("/zones/{zone_id}/input", post(api::zone_input_post_handler))
+            .route(
+                "/zones/{zone_id}/metadata",
+                put(api::zone_metadata_put_handler),
+            )
+            .route(
+                "/zones/{zone_id}/metadata",
+                delete(api::zone_metadata_delete_handler),
+            )
             // Legacy SSR routes (flash page not yet migrated)
             .route("/knobs/flash", get(flash_page))
             // Legacy redirects
@@ -406,31 +713,61 @@ mod server {
             // Embedded static files (favicon, CSS, images)
             .route(
                 "/favicon.ico",
-                get(|| embedded::serve_static_file(axum::extract::Path("favicon.ico".to_string()))),
+                get(|headers: axum::http::HeaderMap| {
+                    embedded::serve_static_file(
+                        axum::extract::Path("favicon.ico".to_string()),
+                        headers,
+                    )
+                }),
             )
             .route(
                 "/apple-touch-icon.png",
-                get(|| {
-                    embedded::serve_static_file(axum::extract::Path(
-                        "apple-touch-icon.png".to_string(),
-                    ))
+                get(|headers: axum::http::HeaderMap| {
+                    embedded::serve_static_file(
+                        axum::extract::Path("apple-touch-icon.png".to_string()),
+                        headers,
+                    )
                 }),
             )
             .route(
                 "/tailwind.css",
-                get(|| {
-                    embedded::serve_static_file(axum::extract::Path("tailwind.css".to_string()))
+                get(|headers: axum::http::HeaderMap| {
+                    embedded::serve_static_file(
+                        axum::extract::Path("tailwind.css".to_string()),
+                        headers,
+                    )
                 }),
             )
             .route(
                 "/dx-components-theme.css",
-                get(|| {
-                    embedded::serve_static_file(axum::extract::Path(
-                        "dx-components-theme.css".to_string(),
-                    ))
+                get(|headers: axum::http::HeaderMap| {
+                    embedded::serve_static_file(
+                        axum::extract::Path("dx-components-theme.css".to_string()),
+                        headers,
+                    )
                 }),
-            )
+            );
+
+        // Optional GraphQL API: queries at /graphql, subscriptions at /graphql/ws
+        #[cfg(feature = "graphql")]
+        let router = router
+            .route("/graphql", get(graphql::graphiql_handler))
+            .route("/graphql", post(graphql::graphql_handler))
+            .route("/graphql/ws", get(graphql::graphql_ws_handler))
+            .layer(axum::Extension(graphql::build_schema(state.clone())));
+
+        // Per-request timeout; also aborts in-flight requests when shutdown starts
+        let deadline = api::RequestDeadline {
+            timeout: std::time::Duration::from_secs(config.request_timeout_secs.max(1)),
+            shutdown: shutdown_token.clone(),
+        };
+
+        let router = router
             // Middleware
+            .layer(axum::middleware::from_fn_with_state(
+                deadline,
+                api::enforce_deadline,
+            ))
             .layer(CorsLayer::permissive())
             .layer(CompressionLayer::new())
             .layer(TraceLayer::new_for_http())
@@ -451,12 +788,14 @@ mod server {
                 tracing::warn!(
                     "Embedded assets found but no bootstrap scripts - falling back to SPA"
                 );
+                startup::record_assets("embedded_spa", embedded::list_embedded_assets().len());
                 router
                     .serve_api_application(dioxus::server::ServeConfig::new(), app::App)
                     .fallback(embedded::serve_index_html)
             }
         } else {
             tracing::info!("Using SSR mode (no embedded assets, use dx serve for development)");
+            startup::record_assets("dev", 0);
             // Standard SSR mode for development
             router.serve_dioxus_application(dioxus::server::ServeConfig::new(), app::App)
         };
@@ -466,13 +805,48 @@ mod server {
         tracing::info!("Listening on http://{}", addr);
 
         // Advertise via mDNS for knob discovery
-        let _mdns = match mdns::advertise(config.port, "Unified Hi-Fi Control", &base_url) {
+        // TXT records only list surfaces this build actually serves
+        // `auth` follows the knob device token setting, so knobs know to pair
+        let mut api_surfaces = mdns::ApiSurfaces {
+            auth_required: state_for_shutdown.tokens.knob_tokens_required(),
+            ..Default::default()
+        };
+        let _mdns = match mdns::advertise(
+            config.port,
+            "Unified Hi-Fi Control",
+            &base_url,
+            &api_surfaces,
+        ) {
             Ok(daemon) => {
                 tracing::info!("mDNS advertising started");
+                let mdns_daemon = daemon.clone();
+                let mdns_tokens = state_for_shutdown.tokens.clone();
+                let mdns_base_url = base_url.clone();
+                let mdns_shutdown = shutdown_token.clone();
+                let port = config.port;
+                tokio::spawn(async move {
+                    loop {
+                        tokio::select! {
+                            _ = mdns_tokens.knob_auth_changed() => {}
+                            _ = mdns_shutdown.cancelled() => break,
+                        }
+                        api_surfaces.auth_required = mdns_tokens.knob_tokens_required();
+                        if let Err(e) = mdns::readvertise(
+                            &mdns_daemon,
+                            port,
+                            "Unified Hi-Fi Control",
+                            &mdns_base_url,
+                            &api_surfaces,
+                        ) {
+                            tracing::warn!("Failed to update mDNS TXT records: {}", e);
+                        }
+                    }
+                });
                 Some(daemon)
             }
             Err(e) => {
                 tracing::warn!("Failed to start mDNS advertising: {}", e);
+                startup::record_warning(format!("mDNS advertising failed: {}", e));
                 None
             }
         };
@@ -498,7 +872,176 @@ mod server {
             None
         };
 
-        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => {
+                startup::record_listener("http", addr, None);
+                listener
+            }
+            Err(e) => {
+                startup::record_listener("http", addr, Some(e.to_string()));
+                return Err(e.into());
+            }
+        };
+
+        // With remote access on, its token also guards the LAN listener and gRPC
+        // against peers that aren't on the local network
+        let remote_auth = match &config.remote {
+            Some(remote_config) => {
+                let token = remote::load_or_create_token(remote_config.token.clone())?;
+                state_for_shutdown.tokens.set_remote_token(&token);
+                Some(Arc::new(remote::RemoteAuth {
+                    token,
+                    tokens: state_for_shutdown.tokens.clone(),
+                }))
+            }
+            None => None,
+        };
+
+        // Optional gRPC server on its own port
+        if let Some(grpc_port) = config.grpc_port {
+            #[cfg(feature = "grpc")]
+            {
+                let grpc_addr = SocketAddr::from(([0, 0, 0, 0], grpc_port));
+                let grpc_state = state_for_shutdown.clone();
+                let grpc_remote = remote_auth.clone();
+                let grpc_shutdown = shutdown_token.clone();
+                tracing::info!("gRPC listening on {}", grpc_addr);
+                startup::record_listener("grpc", grpc_addr, None);
+                tokio::spawn(async move {
+                    if let Err(e) =
+                        grpc::serve(grpc_state, grpc_addr, grpc_remote, grpc_shutdown).await
+                    {
+                        tracing::error!("gRPC server failed: {}", e);
+                        startup::record_warning(format!("gRPC server failed: {}", e));
+                    }
+                });
+            }
+            #[cfg(not(feature = "grpc"))]
+            {
+                tracing::warn!(
+                    "grpc_port {} is set but this build lacks the grpc feature; gRPC disabled",
+                    grpc_port
+                );
+                startup::record_warning(format!(
+                    "grpc_port {} is set but this build lacks the grpc feature",
+                    grpc_port
+                ));
+            }
+        }
+
+        // Optional token-authenticated listener for remote access (Tailscale/WireGuard)
+        if let (Some(remote_config), Some(token)) = (&config.remote, remote_auth.clone()) {
+            match remote::resolve_bind(&remote_config.bind) {
+                Ok(ip) => {
+                    let remote_addr = SocketAddr::new(ip, remote_config.port);
+                    let remote_listener = match tokio::net::TcpListener::bind(remote_addr).await {
+                        Ok(listener) => {
+                            startup::record_listener("remote", remote_addr, None);
+                            listener
+                        }
+                        Err(e) => {
+                            startup::record_listener("remote", remote_addr, Some(e.to_string()));
+                            return Err(e.into());
+                        }
+                    };
+                    let remote_router = router.clone().layer(axum::middleware::from_fn_with_state(
+                        token,
+                        remote::require_token,
+                    ));
+                    let remote_shutdown = shutdown_token.clone();
+                    tracing::info!("Remote access listening on http://{}", remote_addr);
+                    tokio::spawn(async move {
+                        if let Err(e) = axum::serve(
+                            remote_listener,
+                            remote_router.into_make_service_with_connect_info::<SocketAddr>(),
+                        )
+                        .with_graceful_shutdown(remote_shutdown.cancelled_owned())
+                        .await
+                        {
+                            tracing::error!("Remote access server failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Remote access bind '{}' unavailable, remote access disabled: {}",
+                        remote_config.bind,
+                        e
+                    );
+                    startup::record_listener("remote", &remote_config.bind, Some(e.to_string()));
+                }
+            }
+        }
+
+        // Debounced zone_active/zone_idle events
+        activity::start(&config.activity, bus.clone(), shutdown_token.clone());
+
+        // Optional signed event webhooks
+        if let Some(webhooks_config) = &config.webhooks {
+            if let Err(e) = webhooks::start(webhooks_config, bus.clone(), shutdown_token.clone()) {
+                tracing::warn!("Webhooks disabled: {}", e);
+                startup::record_warning(format!("Webhooks disabled: {}", e));
+            }
+        }
+
+        // Optional Home Assistant media players over MQTT
+        if let Some(mqtt_config) = &config.mqtt {
+            if let Err(e) = mqtt::start(
+                mqtt_config,
+                state_for_shutdown.clone(),
+                shutdown_token.clone(),
+            ) {
+                tracing::warn!("MQTT disabled: {}", e);
+                startup::record_warning(format!("MQTT disabled: {}", e));
+            }
+        }
+
+        // Optional now-playing dashboard on a Chromecast
+        if let Some(cast_config) = &config.cast {
+            if let Err(e) = cast::start(
+                cast_config,
+                config.port,
+                bus.clone(),
+                shutdown_token.clone(),
+            ) {
+                tracing::warn!("Cast dashboard disabled: {}", e);
+                startup::record_warning(format!("Cast dashboard disabled: {}", e));
+            }
+        }
+
+        // =========================================================================
+        // Start enabled adapters (single codepath using coordinator)
+        // =========================================================================
+
+        // Adapters start concurrently in the background so the web UI is reachable
+        // immediately; HQPlayer connections are warmed up alongside (commands also
+        // connect lazily if this fails).
+        {
+            let coord = coord.clone();
+            let adapters = startable_adapters.clone();
+            let hqp_instances = state_for_shutdown.hqp_instances.clone();
+            let shutdown = shutdown_token.clone();
+            #[cfg(feature = "test-util")]
+            let demo_roon = demo_backends.as_ref().map(|d| d.roon.clone());
+            #[cfg(feature = "test-util")]
+            let bus = bus.clone();
+            tokio::spawn(async move {
+                #[cfg(feature = "test-util")]
+                if let Some(roon) = demo_roon {
+                    demo::publish_roon_zones(&roon, &bus).await;
+                }
+                let started = Instant::now();
+                tokio::select! {
+                    _ = async {
+                        tokio::join!(
+                            coord.start_all_enabled(&adapters),
+                            hqp_instances.connect_configured(),
+                        )
+                    } => tracing::info!("Adapter startup finished in {:?}", started.elapsed()),
+                    _ = shutdown.cancelled() => tracing::info!("Adapter startup abandoned for shutdown"),
+                }
+            });
+        }
 
         // Create shutdown future that cancels token before graceful shutdown (fixes #73)
         let graceful_shutdown = {
@@ -521,6 +1064,13 @@ mod server {
             }
         };
 
+        let router = match remote_auth {
+            Some(auth) => router.layer(axum::middleware::from_fn_with_state(
+                auth,
+                remote::require_token_off_lan,
+            )),
+            None => router,
+        };
         axum::serve(
             listener,
             router.into_make_service_with_connect_info::<SocketAddr>(),
@@ -539,19 +1089,73 @@ mod server {
         // Give listeners a moment to react to ShuttingDown
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
-        // Stop adapters
-        roon_for_shutdown.stop().await;
-        if let Some(ref fw) = firmware_service {
-            fw.stop();
+        // Stop adapters, bounded so an unresponsive backend can't block exit
+        let stop_adapters = async {
+            roon_for_shutdown.stop().await;
+            if let Some(ref fw) = firmware_service {
+                fw.stop();
+            }
+            lms.stop().await;
+            openhome.stop().await;
+            upnp.stop().await;
+            airplay.stop().await;
+            snapcast.stop().await;
+            heos.stop().await;
+            kodi.stop().await;
+            spotify.stop().await;
+            http.stop().await;
+            plexamp.stop().await;
+            #[cfg(feature = "test-util")]
+            if let Some(demo) = demo_backends {
+                demo.stop().await;
+            }
+        };
+        if tokio::time::timeout(ADAPTER_STOP_TIMEOUT, stop_adapters)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "Adapters did not stop within {:?}, exiting anyway",
+                ADAPTER_STOP_TIMEOUT
+            );
         }
-        lms.stop().await;
-        openhome.stop().await;
-        upnp.stop().await;
         tracing::info!("Shutdown complete");
 
         Ok(())
     }
 
+    /// Start the fake demo backends, redirecting config to a throwaway directory and
+    /// enabling only the adapters that talk to them
+    #[cfg(feature = "test-util")]
+    async fn start_demo_backends() -> Result<demo::DemoBackends> {
+        std::env::set_var("UHC_CONFIG_DIR", demo::demo_config_dir());
+
+        let backends = demo::DemoBackends::start().await?;
+        let mut settings = load_app_settings();
+        settings.adapters = api::AdapterSettings {
+            roon: false,
+            upnp: false,
+            openhome: false,
+            lms: true,
+            hqplayer: true,
+            airplay: false,
+            snapcast: false,
+            heos: false,
+            kodi: false,
+            spotify: false,
+            http: false,
+            plexamp: false,
+        };
+        api::save_app_settings(&settings);
+        tracing::info!(
+            "Demo mode: mock LMS at {}, mock HQPlayer at {} (config in {:?})",
+            backends.lms.addr(),
+            backends.hqp.addr(),
+            demo::demo_config_dir()
+        );
+        Ok(backends)
+    }
+
     /// Wait for shutdown signal (Ctrl+C or SIGTERM)
     #[allow(clippy::expect_used)] // Signal handlers must succeed for graceful shutdown
     async fn shutdown_signal() {
@@ -606,10 +1210,25 @@ async fn main() -> anyhow::Result<()> {
         println!();
         println!("USAGE:");
         println!("    unified-hifi-control [OPTIONS]");
+        println!("    unified-hifi-control healthcheck");
+        println!();
+        println!("COMMANDS:");
+        println!(
+            "    healthcheck      Check the running bridge's /healthz and exit non-zero on failure"
+        );
         println!();
         println!("OPTIONS:");
         println!("    -h, --help       Print help information");
         println!("    -V, --version    Print version information");
+        println!(
+            "        --demo       Run against fake Roon/LMS/HQPlayer backends (test-util builds)"
+        );
+        println!(
+            "        --soak       Load test the running bridge (--url, --duration, --clients, --knobs,"
+        );
+        println!(
+            "                     --bursts, --token, --max-p99-ms) and print latency percentiles"
+        );
         println!();
         println!("ENVIRONMENT VARIABLES:");
         println!("    PORT             HTTP server port (default: 8088)");
@@ -620,7 +1239,16 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    server::run().await
+    if args.get(1).map(String::as_str) == Some("healthcheck") {
+        return server::healthcheck().await;
+    }
+
+    if args.iter().any(|a| a == "--soak") {
+        return server::soak(&args).await;
+    }
+
+    let demo_mode = args.iter().any(|a| a == "--demo");
+    server::run(demo_mode).await
 }
 
 // WASM entry point (client-side only)
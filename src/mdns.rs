@@ -1,18 +1,114 @@
 //! mDNS service advertising for knob discovery
 //!
 //! Publishes a _roonknob._tcp service so S3 Knob devices can discover the server.
+//! TXT records describe the available API surfaces (see [`ApiSurfaces`]).
 
 use mdns_sd::{ServiceDaemon, ServiceInfo};
 use std::collections::HashMap;
 
+/// HTTP API protocol version advertised in the `api` TXT record
+pub const API_VERSION: u32 = 1;
+
+/// API surfaces advertised via TXT records so clients can capability-detect the
+/// bridge during discovery instead of probing endpoints.
+///
+/// | Key       | Value                                    |
+/// |-----------|------------------------------------------|
+/// | `base`    | Base URL of the bridge                   |
+/// | `api`     | HTTP API protocol version                |
+/// | `version` | Bridge version                           |
+/// | `sse`     | Server-Sent Events path                  |
+/// | `mcp`     | MCP endpoint path (only if served)       |
+/// | `ws`      | WebSocket endpoint path (only if served) |
+/// | `graphql` | GraphQL endpoint path (only if served)   |
+/// | `auth`    | `1` if API requests need auth, else `0`  |
+#[derive(Debug, Clone)]
+pub struct ApiSurfaces {
+    pub api_version: u32,
+    pub version: String,
+    pub sse_path: Option<String>,
+    pub mcp_path: Option<String>,
+    pub ws_path: Option<String>,
+    pub graphql_path: Option<String>,
+    pub auth_required: bool,
+}
+
+impl Default for ApiSurfaces {
+    fn default() -> Self {
+        Self {
+            api_version: API_VERSION,
+            version: env!("UHC_VERSION").to_string(),
+            sse_path: Some("/events".to_string()),
+            mcp_path: None,
+            ws_path: None,
+            graphql_path: cfg!(feature = "graphql").then(|| "/graphql".to_string()),
+            auth_required: false,
+        }
+    }
+}
+
+impl ApiSurfaces {
+    /// Build the TXT record map for this bridge
+    pub fn txt_records(&self, base_url: &str) -> HashMap<String, String> {
+        let mut txt = HashMap::new();
+        txt.insert("base".to_string(), base_url.to_string());
+        txt.insert("api".to_string(), self.api_version.to_string());
+        txt.insert("version".to_string(), self.version.clone());
+        if let Some(ref path) = self.sse_path {
+            txt.insert("sse".to_string(), path.clone());
+        }
+        if let Some(ref path) = self.mcp_path {
+            txt.insert("mcp".to_string(), path.clone());
+        }
+        if let Some(ref path) = self.ws_path {
+            txt.insert("ws".to_string(), path.clone());
+        }
+        if let Some(ref path) = self.graphql_path {
+            txt.insert("graphql".to_string(), path.clone());
+        }
+        txt.insert(
+            "auth".to_string(),
+            if self.auth_required { "1" } else { "0" }.to_string(),
+        );
+        txt
+    }
+}
+
 /// Advertise the service via mDNS
-pub fn advertise(port: u16, name: &str, base_url: &str) -> anyhow::Result<ServiceDaemon> {
+pub fn advertise(
+    port: u16,
+    name: &str,
+    base_url: &str,
+    surfaces: &ApiSurfaces,
+) -> anyhow::Result<ServiceDaemon> {
     let mdns = ServiceDaemon::new()?;
+    mdns.register(service_info(port, name, base_url, surfaces)?)?;
+
+    tracing::info!("mDNS: Service registered successfully");
 
+    Ok(mdns)
+}
+
+/// Register the service again with new TXT records (e.g. `auth` changed)
+pub fn readvertise(
+    mdns: &ServiceDaemon,
+    port: u16,
+    name: &str,
+    base_url: &str,
+    surfaces: &ApiSurfaces,
+) -> anyhow::Result<()> {
+    mdns.register(service_info(port, name, base_url, surfaces)?)?;
+    Ok(())
+}
+
+fn service_info(
+    port: u16,
+    name: &str,
+    base_url: &str,
+    surfaces: &ApiSurfaces,
+) -> anyhow::Result<ServiceInfo> {
     // Build TXT records
-    let mut txt = HashMap::new();
-    txt.insert("base".to_string(), base_url.to_string());
-    txt.insert("api".to_string(), "1".to_string());
+    let txt = surfaces.txt_records(base_url);
 
     // Create service info
     // Type is "_roonknob._tcp.local."
@@ -45,10 +141,35 @@ pub fn advertise(port: u16, name: &str, base_url: &str) -> anyhow::Result<Servic
         service_type
     );
 
-    // Register the service
-    mdns.register(service_info)?;
+    Ok(service_info)
+}
 
-    tracing::info!("mDNS: Service registered successfully");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(mdns)
+    #[test]
+    fn test_txt_records_only_advertise_served_surfaces() {
+        let txt = ApiSurfaces::default().txt_records("http://bridge.local:8088");
+        assert_eq!(
+            txt.get("base").map(String::as_str),
+            Some("http://bridge.local:8088")
+        );
+        assert_eq!(txt.get("api").map(String::as_str), Some("1"));
+        assert_eq!(txt.get("sse").map(String::as_str), Some("/events"));
+        assert_eq!(txt.get("auth").map(String::as_str), Some("0"));
+        assert!(!txt.contains_key("mcp"));
+        assert!(!txt.contains_key("ws"));
+
+        let surfaces = ApiSurfaces {
+            mcp_path: Some("/mcp".to_string()),
+            ws_path: Some("/ws".to_string()),
+            auth_required: true,
+            ..ApiSurfaces::default()
+        };
+        let txt = surfaces.txt_records("http://bridge.local:8088");
+        assert_eq!(txt.get("mcp").map(String::as_str), Some("/mcp"));
+        assert_eq!(txt.get("ws").map(String::as_str), Some("/ws"));
+        assert_eq!(txt.get("auth").map(String::as_str), Some("1"));
+    }
 }
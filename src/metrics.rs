@@ -0,0 +1,426 @@
+//! Prometheus metrics (`GET /metrics`)
+//!
+//! Bridge gauges plus per-zone gauges (state, volume, position) and counters
+//! (tracks played, commands issued), so dashboards can show which rooms are
+//! actually used. Gauges are read from the aggregator at scrape time; counters
+//! live in [`ZoneMetrics`] and reset when the bridge restarts.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::bus::{BusEvent, PlaybackState, PrefixedZoneId, SharedBus, Zone};
+use crate::coordinator::AdapterStatus;
+
+/// Content type for the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Per-zone counters
+#[derive(Debug, Default)]
+struct ZoneCounters {
+    tracks_played: u64,
+    /// By action name
+    commands: HashMap<String, u64>,
+    /// (title, artist) of the last counted track
+    last_track: Option<(String, String)>,
+}
+
+/// Counters that can't be derived from current zone state
+#[derive(Debug, Default)]
+pub struct ZoneMetrics {
+    zones: Mutex<HashMap<String, ZoneCounters>>,
+}
+
+impl ZoneMetrics {
+    /// Count a control command sent to a zone
+    pub fn record_command(&self, zone_id: &PrefixedZoneId, action: &str) {
+        let mut zones = self.zones.lock().unwrap_or_else(PoisonError::into_inner);
+        *zones
+            .entry(zone_id.as_str().to_string())
+            .or_default()
+            .commands
+            .entry(action.to_string())
+            .or_default() += 1;
+    }
+
+    /// Count a track change; repeats of the current track are ignored
+    pub fn record_now_playing(&self, zone_id: &str, title: Option<&str>, artist: Option<&str>) {
+        let Some(title) = title.filter(|t| !t.is_empty()) else {
+            return;
+        };
+        let track = (title.to_string(), artist.unwrap_or_default().to_string());
+        let mut zones = self.zones.lock().unwrap_or_else(PoisonError::into_inner);
+        let counters = zones.entry(zone_id.to_string()).or_default();
+        if counters.last_track.as_ref() != Some(&track) {
+            counters.tracks_played += 1;
+            counters.last_track = Some(track);
+        }
+    }
+
+    /// Count track changes from the bus until `shutdown`
+    pub async fn run(&self, bus: SharedBus, shutdown: CancellationToken) {
+        let mut rx = bus.subscribe();
+        loop {
+            let event = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                result = rx.recv() => match result {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Metrics lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if let BusEvent::NowPlayingChanged {
+                zone_id,
+                title,
+                artist,
+                ..
+            } = event
+            {
+                self.record_now_playing(zone_id.as_str(), title.as_deref(), artist.as_deref());
+            }
+        }
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self, zones: &[Zone], bridge: &BridgeMetrics) -> String {
+        let mut out = String::new();
+
+        family(
+            &mut out,
+            "uhc_uptime_seconds",
+            "gauge",
+            "Seconds since the bridge started",
+        );
+        sample(
+            &mut out,
+            "uhc_uptime_seconds",
+            &[],
+            bridge.uptime_secs as f64,
+        );
+        family(
+            &mut out,
+            "uhc_sse_connections",
+            "gauge",
+            "Open SSE connections",
+        );
+        sample(
+            &mut out,
+            "uhc_sse_connections",
+            &[],
+            bridge.sse_connections as f64,
+        );
+        family(
+            &mut out,
+            "uhc_bus_subscribers",
+            "gauge",
+            "Event bus subscribers",
+        );
+        sample(
+            &mut out,
+            "uhc_bus_subscribers",
+            &[],
+            bridge.bus_subscribers as f64,
+        );
+        family(&mut out, "uhc_zones", "gauge", "Zones currently known");
+        sample(&mut out, "uhc_zones", &[], zones.len() as f64);
+
+        family(
+            &mut out,
+            "uhc_adapter_up",
+            "gauge",
+            "Adapter is enabled and running",
+        );
+        for adapter in &bridge.adapters {
+            let up = adapter.enabled && adapter.running;
+            sample(
+                &mut out,
+                "uhc_adapter_up",
+                &[("adapter", &adapter.prefix)],
+                f64::from(u8::from(up)),
+            );
+        }
+        family(
+            &mut out,
+            "uhc_adapter_restarts_total",
+            "counter",
+            "Adapter restarts requested through the API",
+        );
+        for adapter in &bridge.adapters {
+            sample(
+                &mut out,
+                "uhc_adapter_restarts_total",
+                &[("adapter", &adapter.prefix)],
+                f64::from(adapter.restart_count),
+            );
+        }
+
+        let names: HashMap<&str, &Zone> = zones.iter().map(|z| (z.zone_id.as_str(), z)).collect();
+        let zone_labels = |zone: &Zone| -> Vec<(&'static str, String)> {
+            vec![
+                ("zone_id", zone.zone_id.clone()),
+                ("zone_name", zone.zone_name.clone()),
+                ("source", zone.source.clone()),
+                (
+                    "room",
+                    zone.metadata
+                        .as_ref()
+                        .and_then(|m| m.room.clone())
+                        .unwrap_or_default(),
+                ),
+            ]
+        };
+
+        family(
+            &mut out,
+            "uhc_zone_state",
+            "gauge",
+            "Zone playback state (1 for the current state)",
+        );
+        for zone in zones {
+            let mut labels = zone_labels(zone);
+            labels.push(("state", zone.state.to_string()));
+            sample(&mut out, "uhc_zone_state", &as_refs(&labels), 1.0);
+        }
+        family(&mut out, "uhc_zone_playing", "gauge", "Zone is playing");
+        for zone in zones {
+            let playing = zone.state == PlaybackState::Playing;
+            sample(
+                &mut out,
+                "uhc_zone_playing",
+                &as_refs(&zone_labels(zone)),
+                f64::from(u8::from(playing)),
+            );
+        }
+        family(
+            &mut out,
+            "uhc_zone_volume",
+            "gauge",
+            "Zone volume in the zone's own scale (dB or percent)",
+        );
+        for zone in zones {
+            if let Some(volume) = &zone.volume_control {
+                sample(
+                    &mut out,
+                    "uhc_zone_volume",
+                    &as_refs(&zone_labels(zone)),
+                    f64::from(volume.value),
+                );
+            }
+        }
+        family(&mut out, "uhc_zone_muted", "gauge", "Zone is muted");
+        for zone in zones {
+            if let Some(volume) = &zone.volume_control {
+                sample(
+                    &mut out,
+                    "uhc_zone_muted",
+                    &as_refs(&zone_labels(zone)),
+                    f64::from(u8::from(volume.is_muted)),
+                );
+            }
+        }
+        family(
+            &mut out,
+            "uhc_zone_position_seconds",
+            "gauge",
+            "Position within the current track",
+        );
+        for zone in zones {
+            if let Some(position) = zone.now_playing.as_ref().and_then(|np| np.seek_position) {
+                sample(
+                    &mut out,
+                    "uhc_zone_position_seconds",
+                    &as_refs(&zone_labels(zone)),
+                    position,
+                );
+            }
+        }
+        family(
+            &mut out,
+            "uhc_zone_track_duration_seconds",
+            "gauge",
+            "Length of the current track",
+        );
+        for zone in zones {
+            if let Some(duration) = zone.now_playing.as_ref().and_then(|np| np.duration) {
+                sample(
+                    &mut out,
+                    "uhc_zone_track_duration_seconds",
+                    &as_refs(&zone_labels(zone)),
+                    duration,
+                );
+            }
+        }
+
+        let counters = self.zones.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut zone_ids: Vec<&String> = counters.keys().collect();
+        zone_ids.sort();
+        let counter_labels = |zone_id: &str| -> Vec<(&'static str, String)> {
+            let zone_name = names
+                .get(zone_id)
+                .map(|z| z.zone_name.clone())
+                .unwrap_or_default();
+            vec![("zone_id", zone_id.to_string()), ("zone_name", zone_name)]
+        };
+
+        family(
+            &mut out,
+            "uhc_zone_tracks_played_total",
+            "counter",
+            "Track changes seen on the zone",
+        );
+        for zone_id in &zone_ids {
+            let played = counters[*zone_id].tracks_played;
+            if played > 0 {
+                sample(
+                    &mut out,
+                    "uhc_zone_tracks_played_total",
+                    &as_refs(&counter_labels(zone_id)),
+                    played as f64,
+                );
+            }
+        }
+        family(
+            &mut out,
+            "uhc_zone_commands_total",
+            "counter",
+            "Control commands sent to the zone, by action",
+        );
+        for zone_id in &zone_ids {
+            let mut actions: Vec<_> = counters[*zone_id].commands.iter().collect();
+            actions.sort();
+            for (action, count) in actions {
+                let mut labels = counter_labels(zone_id);
+                labels.push(("action", action.clone()));
+                sample(
+                    &mut out,
+                    "uhc_zone_commands_total",
+                    &as_refs(&labels),
+                    *count as f64,
+                );
+            }
+        }
+
+        out
+    }
+}
+
+/// Bridge-level values for [`ZoneMetrics::render`]
+#[derive(Debug, Clone, Default)]
+pub struct BridgeMetrics {
+    pub uptime_secs: u64,
+    pub sse_connections: usize,
+    pub bus_subscribers: usize,
+    pub adapters: Vec<AdapterStatus>,
+}
+
+fn as_refs<'a>(labels: &'a [(&'static str, String)]) -> Vec<(&'static str, &'a str)> {
+    labels.iter().map(|(k, v)| (*k, v.as_str())).collect()
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    out.push_str(&format!(
+        "# HELP {} {}\n# TYPE {} {}\n",
+        name, help, name, kind
+    ));
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        out.push('{');
+        for (i, (key, value)) in labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!("{}=\"{}\"", key, escape_label(value)));
+        }
+        out.push('}');
+    }
+    out.push_str(&format!(" {}\n", value));
+}
+
+/// Escape a label value (backslash, double quote and newline)
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::{VolumeControl, VolumeScale, ZoneCapabilities};
+
+    fn zone(id: &str, name: &str, state: PlaybackState) -> Zone {
+        Zone {
+            zone_id: id.to_string(),
+            zone_name: name.to_string(),
+            state,
+            volume_control: Some(VolumeControl {
+                value: -20.0,
+                min: -64.0,
+                max: 0.0,
+                step: 1.0,
+                is_muted: false,
+                scale: VolumeScale::Decibel,
+                output_id: None,
+            }),
+            now_playing: None,
+            source: "roon".to_string(),
+            is_controllable: true,
+            is_seekable: true,
+            capabilities: ZoneCapabilities::default(),
+            volume_leveling: None,
+            last_updated: 0,
+            is_play_allowed: true,
+            is_pause_allowed: true,
+            is_next_allowed: true,
+            is_previous_allowed: true,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_tracks_played_ignores_repeats() {
+        let metrics = ZoneMetrics::default();
+        metrics.record_now_playing("roon:1", Some("Song"), Some("Artist"));
+        metrics.record_now_playing("roon:1", Some("Song"), Some("Artist"));
+        metrics.record_now_playing("roon:1", None, None);
+        metrics.record_now_playing("roon:1", Some("Other"), Some("Artist"));
+
+        let out = metrics.render(&[], &BridgeMetrics::default());
+        assert!(out.contains("uhc_zone_tracks_played_total{zone_id=\"roon:1\",zone_name=\"\"} 2\n"));
+    }
+
+    #[test]
+    fn test_render_zone_gauges_and_commands() {
+        let metrics = ZoneMetrics::default();
+        let zone_id = PrefixedZoneId::roon("1");
+        metrics.record_command(&zone_id, "play_pause");
+        metrics.record_command(&zone_id, "play_pause");
+
+        let zones = [zone("roon:1", "Living \"Room\"", PlaybackState::Playing)];
+        let out = metrics.render(&zones, &BridgeMetrics::default());
+
+        let labels = "zone_id=\"roon:1\",zone_name=\"Living \\\"Room\\\"\"";
+        assert!(out.contains(&format!(
+            "uhc_zone_playing{{{},source=\"roon\",room=\"\"}} 1\n",
+            labels
+        )));
+        assert!(out.contains(&format!(
+            "uhc_zone_volume{{{},source=\"roon\",room=\"\"}} -20\n",
+            labels
+        )));
+        assert!(out.contains(&format!(
+            "uhc_zone_commands_total{{{},action=\"play_pause\"}} 2\n",
+            labels
+        )));
+        assert!(out.contains("# TYPE uhc_zone_commands_total counter\n"));
+    }
+}
@@ -0,0 +1,373 @@
+//! Import configs from a Node.js installation (`/api/migrate/nodejs`)
+//!
+//! The startup migration in [`crate::config::migrate_nodejs_configs`] only
+//! covers in-place image swaps, where the old files sit in the current config
+//! directory. Users moving a Docker volume point this at the old `CONFIG_DIR`
+//! instead: a preview lists what would be imported, and applying it writes the
+//! files into the config subdirectory. Adapters pick them up after a restart.
+//!
+//! MQTT broker settings are read from the config file or `UHC_MQTT__*`
+//! variables, so they aren't written; the preview lists the variables to set.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::{get_config_file_path, hqp_config_from_nodejs, read_config_file};
+
+/// What happens (or happened) to one imported item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// Nothing there yet; imported on apply
+    New,
+    /// The bridge already has this file; imported only with `overwrite`
+    Replace,
+    /// Unreadable or unparseable; never imported
+    Invalid,
+    /// Set by hand (see `env`)
+    Manual,
+    Imported,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportItem {
+    /// `roon`, `hqplayer`, `knobs`, `app_settings` or `mqtt`
+    pub kind: &'static str,
+    /// Node.js file the item comes from
+    pub source: String,
+    /// Config file written; absent for MQTT
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<&'static str>,
+    pub status: ImportStatus,
+    /// Short summary of the contents, or why it can't be imported
+    pub detail: String,
+    /// For MQTT: `UHC_MQTT__*` variables to set (password left out)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+/// Importable items found in an old config directory
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPlan {
+    pub dir: String,
+    pub items: Vec<ImportItem>,
+}
+
+/// A file item: Node.js file name, kind and the file it becomes
+struct FileSource {
+    file: &'static str,
+    kind: &'static str,
+    target: &'static str,
+    /// Rust-format contents and a summary, or why it can't be used
+    convert: fn(Value) -> Result<(Value, String)>,
+}
+
+const FILE_SOURCES: &[FileSource] = &[
+    FileSource {
+        file: "roon-config.json",
+        kind: "roon",
+        target: "roon_state.json",
+        convert: convert_roon,
+    },
+    FileSource {
+        file: "hqp-config.json",
+        kind: "hqplayer",
+        target: "hqp-config.json",
+        convert: convert_hqp,
+    },
+    FileSource {
+        file: "knobs.json",
+        kind: "knobs",
+        target: "knobs.json",
+        convert: convert_knobs,
+    },
+    FileSource {
+        file: "app-settings.json",
+        kind: "app_settings",
+        target: "app-settings.json",
+        convert: convert_app_settings,
+    },
+];
+
+/// Files that may hold Node.js MQTT settings, in order of preference
+const MQTT_SOURCES: &[&str] = &["mqtt-config.json", "app-settings.json"];
+
+/// List what an import from `dir` would do, without writing anything
+pub fn plan_import(dir: &Path) -> Result<ImportPlan> {
+    Ok(scan(dir)?.0)
+}
+
+/// Import everything importable from `dir`. Files the bridge already has are
+/// kept unless `overwrite` is set. The current config is backed up first.
+pub fn apply_import(dir: &Path, overwrite: bool, backup_dir: &Path) -> Result<ImportPlan> {
+    let (mut plan, contents) = scan(dir)?;
+    if plan.items.iter().any(|i| importable(i.status, overwrite)) {
+        crate::backup::write_backup(backup_dir)?;
+    }
+    for (item, content) in plan.items.iter_mut().zip(contents) {
+        let (Some(target), Some(content)) = (item.target, content) else {
+            continue;
+        };
+        if !importable(item.status, overwrite) {
+            item.status = ImportStatus::Skipped;
+            continue;
+        }
+        let path = get_config_file_path(target);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(&content)?)?;
+        tracing::info!("Imported Node.js {} from {}", target, item.source);
+        item.status = ImportStatus::Imported;
+    }
+    Ok(plan)
+}
+
+fn importable(status: ImportStatus, overwrite: bool) -> bool {
+    status == ImportStatus::New || (overwrite && status == ImportStatus::Replace)
+}
+
+/// Items found in `dir`, each with its converted contents (`None` for items
+/// that can't be written)
+fn scan(dir: &Path) -> Result<(ImportPlan, Vec<Option<Value>>)> {
+    if !dir.is_dir() {
+        return Err(anyhow!("Not a directory: {}", dir.display()));
+    }
+    let mut items = Vec::new();
+    let mut contents = Vec::new();
+
+    for source in FILE_SOURCES {
+        let path = dir.join(source.file);
+        if !path.is_file() {
+            continue;
+        }
+        let converted = read_json(&path).and_then(source.convert);
+        let (status, detail, content) = match converted {
+            Ok((content, detail)) => {
+                let status = if read_config_file(source.target).is_some() {
+                    ImportStatus::Replace
+                } else {
+                    ImportStatus::New
+                };
+                (status, detail, Some(content))
+            }
+            Err(e) => (ImportStatus::Invalid, e.to_string(), None),
+        };
+        items.push(ImportItem {
+            kind: source.kind,
+            source: path.display().to_string(),
+            target: Some(source.target),
+            status,
+            detail,
+            env: Vec::new(),
+        });
+        contents.push(content);
+    }
+
+    if let Some(item) = scan_mqtt(dir) {
+        items.push(item);
+        contents.push(None);
+    }
+
+    let plan = ImportPlan {
+        dir: dir.display().to_string(),
+        items,
+    };
+    Ok((plan, contents))
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn convert_roon(value: Value) -> Result<(Value, String)> {
+    // Same Roon API state structure in both; only the file name changed
+    if !value.is_object() {
+        return Err(anyhow!("Not a Roon pairing state"));
+    }
+    let paired = value
+        .pointer("/roonstate/paired_core_id")
+        .or_else(|| value.get("paired_core_id"));
+    let detail = if paired.is_some() {
+        "Roon pairing"
+    } else {
+        "Roon extension state (no paired core)"
+    };
+    Ok((value, detail.to_string()))
+}
+
+fn convert_hqp(value: Value) -> Result<(Value, String)> {
+    let value = hqp_config_from_nodejs(&value).unwrap_or(value);
+    let instances = match &value {
+        Value::Array(instances) => instances.clone(),
+        Value::Object(_) => vec![value.clone()],
+        _ => return Err(anyhow!("Not an HQPlayer config")),
+    };
+    let hosts: Vec<&str> = instances
+        .iter()
+        .filter_map(|i| i.get("host").and_then(Value::as_str))
+        .filter(|h| !h.is_empty())
+        .collect();
+    if hosts.is_empty() {
+        return Err(anyhow!("No HQPlayer host set"));
+    }
+    Ok((value, format!("HQPlayer at {}", hosts.join(", "))))
+}
+
+fn convert_knobs(value: Value) -> Result<(Value, String)> {
+    let knobs: std::collections::HashMap<String, crate::knobs::store::Knob> =
+        serde_json::from_value(value)?;
+    let value = serde_json::to_value(&knobs)?;
+    Ok((value, format!("{} knobs", knobs.len())))
+}
+
+fn convert_app_settings(value: Value) -> Result<(Value, String)> {
+    // camelCase Node.js fields are accepted through serde aliases
+    let settings: crate::api::AppSettings = serde_json::from_value(value)?;
+    let adapters = &settings.adapters;
+    let enabled: Vec<&str> = [
+        ("roon", adapters.roon),
+        ("lms", adapters.lms),
+        ("openhome", adapters.openhome),
+        ("upnp", adapters.upnp),
+        ("hqplayer", adapters.hqplayer),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect();
+    let detail = format!("Enabled adapters: {}", enabled.join(", "));
+    Ok((serde_json::to_value(&settings)?, detail))
+}
+
+/// MQTT settings from `mqtt-config.json` or the `mqtt` object of
+/// `app-settings.json`: `broker` (`mqtt://host:port`) or `host`/`port`,
+/// `username`, `password` and `topicPrefix`
+fn scan_mqtt(dir: &Path) -> Option<ImportItem> {
+    MQTT_SOURCES.iter().find_map(|file| {
+        let path = dir.join(file);
+        let value = read_json(&path).ok()?;
+        let mqtt = if *file == "app-settings.json" {
+            value.get("mqtt")?.clone()
+        } else {
+            value
+        };
+        let env = mqtt_env(&mqtt)?;
+        Some(ImportItem {
+            kind: "mqtt",
+            source: path.display().to_string(),
+            target: None,
+            status: ImportStatus::Manual,
+            detail:
+                "Set these variables (and UHC_MQTT__PASSWORD if the broker needs one), then restart"
+                    .to_string(),
+            env,
+        })
+    })
+}
+
+fn mqtt_env(mqtt: &Value) -> Option<Vec<String>> {
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| mqtt.get(*k).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let (host, port) = match text(&["broker", "url"]) {
+        Some(url) => {
+            let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+            let rest = rest.trim_end_matches('/');
+            match rest.rsplit_once(':') {
+                Some((host, port)) => (host.to_string(), port.parse::<u16>().ok()),
+                None => (rest.to_string(), None),
+            }
+        }
+        None => (
+            text(&["host"])?.to_string(),
+            mqtt.get("port").and_then(Value::as_u64).map(|p| p as u16),
+        ),
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    let mut env = vec![format!("UHC_MQTT__HOST={}", host)];
+    if let Some(port) = port {
+        env.push(format!("UHC_MQTT__PORT={}", port));
+    }
+    if let Some(username) = text(&["username", "user"]) {
+        env.push(format!("UHC_MQTT__USERNAME={}", username));
+    }
+    if let Some(prefix) = text(&["topicPrefix", "topic_prefix", "base_topic"]) {
+        env.push(format!("UHC_MQTT__BASE_TOPIC={}", prefix));
+    }
+    Some(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_plan_and_apply() {
+        let old = tempfile::tempdir().unwrap();
+        let current = tempfile::tempdir().unwrap();
+        std::env::set_var("UHC_CONFIG_DIR", current.path());
+
+        std::fs::write(
+            old.path().join("hqp-config.json"),
+            r#"{"host": "10.0.0.5", "port": 8088, "username": "u", "password": "p"}"#,
+        )
+        .unwrap();
+        std::fs::write(old.path().join("knobs.json"), "not json").unwrap();
+        std::fs::write(
+            old.path().join("app-settings.json"),
+            r#"{"hideKnobsPage": true, "mqtt": {"broker": "mqtt://broker.lan:1884", "topicPrefix": "hifi"}}"#,
+        )
+        .unwrap();
+
+        let plan = plan_import(old.path()).unwrap();
+        let status = |kind: &str| plan.items.iter().find(|i| i.kind == kind).unwrap().status;
+        assert_eq!(status("hqplayer"), ImportStatus::New);
+        assert_eq!(status("knobs"), ImportStatus::Invalid);
+        assert_eq!(status("mqtt"), ImportStatus::Manual);
+        let mqtt = plan.items.iter().find(|i| i.kind == "mqtt").unwrap();
+        assert_eq!(
+            mqtt.env,
+            vec![
+                "UHC_MQTT__HOST=broker.lan",
+                "UHC_MQTT__PORT=1884",
+                "UHC_MQTT__BASE_TOPIC=hifi"
+            ]
+        );
+        assert!(read_config_file("hqp-config.json").is_none());
+
+        let applied = apply_import(old.path(), false, &current.path().join("backups")).unwrap();
+        let hqp = read_config_file("hqp-config.json").unwrap();
+        let hqp: Value = serde_json::from_str(&hqp).unwrap();
+        assert_eq!(hqp[0]["web_port"], 8088);
+        let settings = read_config_file("app-settings.json").unwrap();
+        assert!(settings.contains("\"hide_knobs_page\": true"));
+        assert!(applied
+            .items
+            .iter()
+            .filter(|i| i.target.is_some() && i.kind != "knobs")
+            .all(|i| i.status == ImportStatus::Imported));
+
+        // Second run: the files exist now and are kept without overwrite
+        let again = apply_import(old.path(), false, &current.path().join("backups")).unwrap();
+        assert!(again
+            .items
+            .iter()
+            .filter(|i| i.kind == "hqplayer")
+            .all(|i| i.status == ImportStatus::Skipped));
+
+        std::env::remove_var("UHC_CONFIG_DIR");
+    }
+}
@@ -7,7 +7,7 @@ use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::RwLock;
-use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 /// Mock HQPlayer state
 #[derive(Debug, Clone)]
@@ -47,36 +47,41 @@ impl Default for MockHqpState {
 pub struct MockHqpServer {
     addr: SocketAddr,
     state: Arc<RwLock<MockHqpState>>,
-    handle: JoinHandle<()>,
+    shutdown: CancellationToken,
 }
 
 impl MockHqpServer {
     /// Start a mock HQPlayer server on a random port
-    pub async fn start() -> Self {
+    pub async fn start() -> std::io::Result<Self> {
         let state = Arc::new(RwLock::new(MockHqpState::default()));
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let shutdown = CancellationToken::new();
 
         let state_clone = state.clone();
-        let handle = tokio::spawn(async move {
+        let shutdown_clone = shutdown.clone();
+        tokio::spawn(async move {
             loop {
-                match listener.accept().await {
-                    Ok((stream, _)) => {
-                        let state = state_clone.clone();
-                        tokio::spawn(async move {
-                            handle_connection(stream, state).await;
-                        });
-                    }
-                    Err(_) => break,
+                tokio::select! {
+                    _ = shutdown_clone.cancelled() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            let state = state_clone.clone();
+                            tokio::spawn(async move {
+                                handle_connection(stream, state).await;
+                            });
+                        }
+                        Err(_) => break,
+                    },
                 }
             }
         });
 
-        Self {
+        Ok(Self {
             addr,
             state,
-            handle,
-        }
+            shutdown,
+        })
     }
 
     /// Get the server address
@@ -89,6 +94,11 @@ impl MockHqpServer {
         self.state.write().await.state = state;
     }
 
+    /// Set filter index (simulates a change in HQPlayer's own UI)
+    pub async fn set_filter(&self, filter: u32) {
+        self.state.write().await.filter = filter;
+    }
+
     /// Set volume (dB)
     pub async fn set_volume(&self, volume: i32) {
         self.state.write().await.volume = volume;
@@ -105,7 +115,7 @@ impl MockHqpServer {
 
     /// Stop the mock server
     pub async fn stop(self) {
-        self.handle.abort();
+        self.shutdown.cancel();
     }
 }
 
@@ -132,10 +142,13 @@ async fn handle_connection(stream: TcpStream, state: Arc<RwLock<MockHqpState>>)
 
 /// Process an XML command and return a response
 async fn process_command(command: &str, state: &Arc<RwLock<MockHqpState>>) -> String {
-    // Skip XML declaration
-    let command = command.trim();
+    // Skip XML declaration (sent alone or in front of the command)
+    let mut command = command.trim();
     if command.starts_with("<?xml") {
-        return String::new(); // Ignore declaration line
+        command = command.split_once("?>").map_or("", |(_, rest)| rest.trim());
+        if command.is_empty() {
+            return String::new(); // Ignore declaration line
+        }
     }
 
     // Parse command name from XML
@@ -144,9 +157,9 @@ async fn process_command(command: &str, state: &Arc<RwLock<MockHqpState>>) -> St
     let state = state.read().await;
 
     match cmd_name.as_str() {
-        "GetInfo" => format!(
-            "<?xml version=\"1.0\"?>\n<GetInfo name=\"MockHQPlayer\" product=\"HQPlayer\" version=\"5.0.0\" platform=\"mock\" engine=\"mock\"/>\n"
-        ),
+        "GetInfo" => {
+            "<?xml version=\"1.0\"?>\n<GetInfo name=\"MockHQPlayer\" product=\"HQPlayer\" version=\"5.0.0\" platform=\"mock\" engine=\"mock\"/>\n".to_string()
+        }
         "State" => format!(
             "<?xml version=\"1.0\"?>\n<State state=\"{}\" mode=\"{}\" filter=\"{}\" shaper=\"{}\" rate=\"{}\" volume=\"{}\"/>\n",
             state.state, state.mode, state.filter, state.shaper, state.rate, state.volume
@@ -208,7 +221,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_hqp_starts_and_stops() {
-        let server = MockHqpServer::start().await;
+        let server = MockHqpServer::start().await.unwrap();
         let addr = server.addr();
         assert!(addr.port() > 0);
         server.stop().await;
@@ -216,7 +229,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_hqp_responds_to_getinfo() {
-        let server = MockHqpServer::start().await;
+        let server = MockHqpServer::start().await.unwrap();
 
         let mut stream = TcpStream::connect(server.addr()).await.unwrap();
         stream
@@ -62,7 +62,7 @@ pub struct MockLmsServer {
 
 impl MockLmsServer {
     /// Start a mock LMS server on a random port
-    pub async fn start() -> Self {
+    pub async fn start() -> std::io::Result<Self> {
         let state = Arc::new(RwLock::new(MockLmsState {
             players: HashMap::new(),
         }));
@@ -71,18 +71,20 @@ impl MockLmsServer {
             .route("/jsonrpc.js", post(handle_jsonrpc))
             .with_state(state.clone());
 
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
 
         let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::warn!("Mock LMS server stopped: {}", e);
+            }
         });
 
-        Self {
+        Ok(Self {
             addr,
             state,
             handle,
-        }
+        })
     }
 
     /// Get the server address
@@ -308,7 +310,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_lms_starts_and_stops() {
-        let server = MockLmsServer::start().await;
+        let server = MockLmsServer::start().await.unwrap();
         let addr = server.addr();
         assert!(addr.port() > 0);
         server.stop().await;
@@ -316,7 +318,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_lms_returns_players() {
-        let server = MockLmsServer::start().await;
+        let server = MockLmsServer::start().await.unwrap();
         server.add_player("aa:bb:cc:dd:ee:ff", "Test Player").await;
 
         let client = reqwest::Client::new();
@@ -382,7 +384,7 @@ mod tests {
     async fn mock_lms_pause_0_resumes_playback() {
         // This test verifies the correct LMS behavior:
         // - "pause 0" (unpause) resumes playback from pause
-        let server = MockLmsServer::start().await;
+        let server = MockLmsServer::start().await.unwrap();
         let player_id = "aa:bb:cc:dd:ee:ff";
         server.add_player(player_id, "Test Player").await;
         server.set_mode(player_id, "pause").await;
@@ -406,7 +408,7 @@ mod tests {
     async fn mock_lms_play_resumes_from_pause() {
         // Per real-world testing (issue #68), "play" command resumes from pause
         // This matches actual LMS behavior - a single command handles both start and resume
-        let server = MockLmsServer::start().await;
+        let server = MockLmsServer::start().await.unwrap();
         let player_id = "aa:bb:cc:dd:ee:ff";
         server.add_player(player_id, "Test Player").await;
         server.set_mode(player_id, "pause").await;
@@ -429,7 +431,7 @@ mod tests {
     #[tokio::test]
     async fn mock_lms_pause_toggle() {
         // Test that "pause" with no args toggles
-        let server = MockLmsServer::start().await;
+        let server = MockLmsServer::start().await.unwrap();
         let player_id = "aa:bb:cc:dd:ee:ff";
         server.add_player(player_id, "Test Player").await;
         server.set_mode(player_id, "play").await;
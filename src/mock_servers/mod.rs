@@ -0,0 +1,33 @@
+//! Mock backend servers (Roon, LMS, HQPlayer, UPnP, OpenHome)
+//!
+//! These mock servers simulate real backend services in-process, allowing full
+//! integration testing and the `--demo` mode without real hardware.
+//!
+//! Requires the `test-util` feature. Client authors (iOS app, knob firmware CI) can
+//! depend on this crate with that feature to run a fake bridge backend in their own
+//! integration tests:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! unified-hifi-control = { git = "https://github.com/open-horizon-labs/unified-hifi-control", features = ["test-util"] }
+//! ```
+//!
+//! ```ignore
+//! use unified_hifi_control::mock_servers::MockLmsServer;
+//!
+//! let lms = MockLmsServer::start().await?;
+//! lms.add_player("aa:bb:cc:dd:ee:ff", "Living Room").await;
+//! // point the bridge (or your client) at lms.addr()
+//! ```
+
+pub mod hqplayer;
+pub mod lms;
+pub mod openhome;
+pub mod roon;
+pub mod upnp;
+
+pub use hqplayer::MockHqpServer;
+pub use lms::MockLmsServer;
+pub use openhome::MockOpenHomeDevice;
+pub use roon::MockRoonCore;
+pub use upnp::MockUpnpRenderer;
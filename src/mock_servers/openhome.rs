@@ -4,10 +4,9 @@
 //! OpenHome extends UPnP with richer metadata and transport controls.
 
 use axum::{
-    body::Body,
     extract::State,
     http::{header, HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
+    response::IntoResponse,
     routing::{get, post},
     Router,
 };
@@ -60,12 +59,12 @@ pub struct MockOpenHomeDevice {
 
 impl MockOpenHomeDevice {
     /// Start a mock OpenHome device on a random port
-    pub async fn start() -> Self {
+    pub async fn start() -> std::io::Result<Self> {
         Self::start_with_state(MockOpenHomeState::default()).await
     }
 
     /// Start with custom initial state
-    pub async fn start_with_state(initial_state: MockOpenHomeState) -> Self {
+    pub async fn start_with_state(initial_state: MockOpenHomeState) -> std::io::Result<Self> {
         let state = Arc::new(RwLock::new(initial_state));
 
         let app = Router::new()
@@ -75,18 +74,20 @@ impl MockOpenHomeDevice {
             .route("/Info/control", post(handle_info))
             .with_state(state.clone());
 
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
 
         let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::warn!("Mock OpenHome server stopped: {}", e);
+            }
         });
 
-        Self {
+        Ok(Self {
             addr,
             state,
             handle,
-        }
+        })
     }
 
     /// Get the server address
@@ -178,10 +179,7 @@ async fn handle_description(
         state.name, state.manufacturer, state.model, state.uuid
     );
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(xml))
-        .unwrap()
+    ([(header::CONTENT_TYPE, "text/xml; charset=utf-8")], xml).into_response()
 }
 
 /// Handle Transport SOAP requests
@@ -238,16 +236,14 @@ async fn handle_transport(
             action_name, action_name
         )
     } else {
-        return Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::from("Unknown action"))
-            .unwrap();
+        return (StatusCode::BAD_REQUEST, "Unknown action").into_response();
     };
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(response_body))
-        .unwrap()
+    (
+        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
+        response_body,
+    )
+        .into_response()
 }
 
 /// Handle Volume SOAP requests
@@ -305,16 +301,14 @@ async fn handle_volume(
             action_name, action_name
         )
     } else {
-        return Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::from("Unknown action"))
-            .unwrap();
+        return (StatusCode::BAD_REQUEST, "Unknown action").into_response();
     };
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(response_body))
-        .unwrap()
+    (
+        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
+        response_body,
+    )
+        .into_response()
 }
 
 /// Handle Info SOAP requests (track metadata)
@@ -362,16 +356,14 @@ async fn handle_info(
             metadata
         )
     } else {
-        return Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::from("Unknown action"))
-            .unwrap();
+        return (StatusCode::BAD_REQUEST, "Unknown action").into_response();
     };
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(response_body))
-        .unwrap()
+    (
+        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
+        response_body,
+    )
+        .into_response()
 }
 
 #[cfg(test)]
@@ -380,7 +372,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_openhome_starts_and_stops() {
-        let server = MockOpenHomeDevice::start().await;
+        let server = MockOpenHomeDevice::start().await.unwrap();
         let addr = server.addr();
         assert!(addr.port() > 0);
         server.stop().await;
@@ -388,7 +380,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_openhome_returns_description() {
-        let server = MockOpenHomeDevice::start().await;
+        let server = MockOpenHomeDevice::start().await.unwrap();
 
         let client = reqwest::Client::new();
         let response = client
@@ -408,7 +400,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_openhome_returns_transport_state() {
-        let server = MockOpenHomeDevice::start().await;
+        let server = MockOpenHomeDevice::start().await.unwrap();
         server.set_state("Playing").await;
 
         let client = reqwest::Client::new();
@@ -102,6 +102,7 @@ impl Default for MockRoonState {
 ///
 /// Instead, this mock provides a controllable state object that can be
 /// used to inject zones and test the adapter's behavior with known data.
+#[derive(Clone)]
 pub struct MockRoonCore {
     state: Arc<RwLock<MockRoonState>>,
 }
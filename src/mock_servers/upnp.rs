@@ -4,10 +4,9 @@
 //! Note: Does not implement SSDP discovery - tests should directly configure adapter.
 
 use axum::{
-    body::Body,
     extract::State,
     http::{header, HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
+    response::IntoResponse,
     routing::{get, post},
     Router,
 };
@@ -52,12 +51,12 @@ pub struct MockUpnpRenderer {
 
 impl MockUpnpRenderer {
     /// Start a mock UPnP renderer on a random port
-    pub async fn start() -> Self {
+    pub async fn start() -> std::io::Result<Self> {
         Self::start_with_state(MockUpnpState::default()).await
     }
 
     /// Start with custom initial state
-    pub async fn start_with_state(initial_state: MockUpnpState) -> Self {
+    pub async fn start_with_state(initial_state: MockUpnpState) -> std::io::Result<Self> {
         let state = Arc::new(RwLock::new(initial_state));
 
         let app = Router::new()
@@ -66,18 +65,20 @@ impl MockUpnpRenderer {
             .route("/RenderingControl/control", post(handle_rendering_control))
             .with_state(state.clone());
 
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
 
         let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::warn!("Mock UPnP server stopped: {}", e);
+            }
         });
 
-        Self {
+        Ok(Self {
             addr,
             state,
             handle,
-        }
+        })
     }
 
     /// Get the server address
@@ -151,17 +152,14 @@ async fn handle_description(State(state): State<Arc<RwLock<MockUpnpState>>>) ->
         state.name, state.manufacturer, state.model, state.uuid
     );
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(xml))
-        .unwrap()
+    ([(header::CONTENT_TYPE, "text/xml; charset=utf-8")], xml).into_response()
 }
 
 /// Handle AVTransport SOAP requests
 async fn handle_av_transport(
     State(state): State<Arc<RwLock<MockUpnpState>>>,
     headers: HeaderMap,
-    body: String,
+    _body: String,
 ) -> impl IntoResponse {
     let action = headers
         .get("soapaction")
@@ -206,23 +204,21 @@ async fn handle_av_transport(
         )
     } else {
         // Unknown action
-        return Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::from("Unknown action"))
-            .unwrap();
+        return (StatusCode::BAD_REQUEST, "Unknown action").into_response();
     };
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(response_body))
-        .unwrap()
+    (
+        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
+        response_body,
+    )
+        .into_response()
 }
 
 /// Handle RenderingControl SOAP requests
 async fn handle_rendering_control(
     State(state): State<Arc<RwLock<MockUpnpState>>>,
     headers: HeaderMap,
-    body: String,
+    _body: String,
 ) -> impl IntoResponse {
     let action = headers
         .get("soapaction")
@@ -274,16 +270,14 @@ async fn handle_rendering_control(
             action_name, action_name
         )
     } else {
-        return Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body(Body::from("Unknown action"))
-            .unwrap();
+        return (StatusCode::BAD_REQUEST, "Unknown action").into_response();
     };
 
-    Response::builder()
-        .header(header::CONTENT_TYPE, "text/xml; charset=utf-8")
-        .body(Body::from(response_body))
-        .unwrap()
+    (
+        [(header::CONTENT_TYPE, "text/xml; charset=utf-8")],
+        response_body,
+    )
+        .into_response()
 }
 
 #[cfg(test)]
@@ -292,7 +286,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_upnp_starts_and_stops() {
-        let server = MockUpnpRenderer::start().await;
+        let server = MockUpnpRenderer::start().await.unwrap();
         let addr = server.addr();
         assert!(addr.port() > 0);
         server.stop().await;
@@ -300,7 +294,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_upnp_returns_description() {
-        let server = MockUpnpRenderer::start().await;
+        let server = MockUpnpRenderer::start().await.unwrap();
 
         let client = reqwest::Client::new();
         let response = client
@@ -320,7 +314,7 @@ mod tests {
 
     #[tokio::test]
     async fn mock_upnp_returns_transport_state() {
-        let server = MockUpnpRenderer::start().await;
+        let server = MockUpnpRenderer::start().await.unwrap();
         server.set_state("PLAYING").await;
 
         let client = reqwest::Client::new();
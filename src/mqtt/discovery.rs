@@ -0,0 +1,440 @@
+//! Topics and Home Assistant discovery payloads
+//!
+//! Home Assistant's built-in MQTT integration has no `media_player` platform,
+//! so zones are announced in the format of the "MQTT Media Player" custom
+//! integration (HACS): one retained state topic per attribute, and command
+//! topics whose payloads are unified `/control` actions.
+//!
+//! Knob batteries and adapter connections use the built-in `sensor` and
+//! `binary_sensor` platforms: each knob is a device with a battery level and
+//! a charging sensor, and each adapter a connectivity sensor on the bridge's
+//! own device.
+
+use serde_json::{json, Value};
+
+use crate::bus::{PlaybackState, Zone};
+use crate::text::TextNormalizer;
+
+/// Availability payloads, for the bridge and for each zone
+pub const ONLINE: &str = "online";
+pub const OFFLINE: &str = "offline";
+
+/// `binary_sensor` payloads
+pub const ON: &str = "ON";
+pub const OFF: &str = "OFF";
+
+/// Zone attributes published as their own retained topics
+pub const STATE_ATTRIBUTES: &[&str] = &[
+    "state",
+    "title",
+    "artist",
+    "album",
+    "duration",
+    "position",
+    "volume",
+    "mediatype",
+];
+
+/// A zone id as a topic segment and HA object id: `roon:1601e0a5` becomes
+/// `roon_1601e0a5`
+pub fn slug(zone_id: &str) -> String {
+    zone_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Topic layout under the configured prefixes
+#[derive(Debug, Clone)]
+pub struct Topics {
+    pub base: String,
+    pub discovery_prefix: String,
+}
+
+impl Topics {
+    pub fn new(base: &str, discovery_prefix: &str) -> Self {
+        Self {
+            base: base.trim_matches('/').to_string(),
+            discovery_prefix: discovery_prefix.trim_matches('/').to_string(),
+        }
+    }
+
+    /// Bridge availability; the broker publishes `offline` here as our last will
+    pub fn status(&self) -> String {
+        format!("{}/status", self.base)
+    }
+
+    pub fn zone(&self, zone_id: &str) -> String {
+        format!("{}/zones/{}", self.base, slug(zone_id))
+    }
+
+    pub fn zone_attribute(&self, zone_id: &str, attribute: &str) -> String {
+        format!("{}/{}", self.zone(zone_id), attribute)
+    }
+
+    /// Action names (or `{"action", "value"}` JSON) for `/control`
+    pub fn zone_command(&self, zone_id: &str) -> String {
+        self.zone_attribute(zone_id, "command")
+    }
+
+    /// Volume from Home Assistant, 0.0 to 1.0
+    pub fn zone_volume_set(&self, zone_id: &str) -> String {
+        self.zone_attribute(zone_id, "volume/set")
+    }
+
+    pub fn zone_availability(&self, zone_id: &str) -> String {
+        self.zone_attribute(zone_id, "availability")
+    }
+
+    /// Subscriptions covering every zone's command topics
+    pub fn command_filters(&self) -> [String; 2] {
+        [
+            format!("{}/zones/+/command", self.base),
+            format!("{}/zones/+/volume/set", self.base),
+        ]
+    }
+
+    pub fn discovery(&self, zone_id: &str) -> String {
+        format!(
+            "{}/media_player/{}/config",
+            self.discovery_prefix,
+            slug(zone_id)
+        )
+    }
+
+    /// Battery level (percent) of a knob
+    pub fn knob_battery(&self, knob_id: &str) -> String {
+        format!("{}/knobs/{}/battery", self.base, slug(knob_id))
+    }
+
+    /// `ON` while a knob is charging
+    pub fn knob_charging(&self, knob_id: &str) -> String {
+        format!("{}/knobs/{}/charging", self.base, slug(knob_id))
+    }
+
+    /// `ON` while an adapter is connected to its backend
+    pub fn adapter_connected(&self, adapter: &str) -> String {
+        format!("{}/adapters/{}/connected", self.base, slug(adapter))
+    }
+
+    /// Discovery topic of a `sensor` or `binary_sensor` entity
+    pub fn sensor_discovery(&self, component: &str, object_id: &str) -> String {
+        format!(
+            "{}/{}/{}/config",
+            self.discovery_prefix, component, object_id
+        )
+    }
+
+    /// The zone slug and command of a topic from [`Self::command_filters`]
+    pub fn parse_command<'a>(&self, topic: &'a str) -> Option<(&'a str, ZoneCommand)> {
+        let rest = topic
+            .strip_prefix(self.base.as_str())?
+            .strip_prefix("/zones/")?;
+        let (slug, command) = rest.split_once('/')?;
+        match command {
+            "command" => Some((slug, ZoneCommand::Action)),
+            "volume/set" => Some((slug, ZoneCommand::Volume)),
+            _ => None,
+        }
+    }
+}
+
+/// What a message on a zone's command topics asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneCommand {
+    Action,
+    Volume,
+}
+
+/// Retained discovery payload announcing a zone as a media player
+pub fn media_player_config(topics: &Topics, zone: &Zone) -> Value {
+    let id = &zone.zone_id;
+    let command = topics.zone_command(id);
+    let mut config = json!({
+        "name": zone.zone_name,
+        "unique_id": format!("uhc_{}", slug(id)),
+        "availability": [
+            { "topic": topics.status() },
+            { "topic": topics.zone_availability(id) },
+        ],
+        "availability_mode": "all",
+        "device": {
+            "identifiers": [format!("uhc_{}", slug(id))],
+            "name": zone.zone_name,
+            "manufacturer": "Unified Hi-Fi Control",
+            "model": zone.source,
+        },
+        "command_play_topic": command,
+        "command_play_payload": "play",
+        "command_pause_topic": command,
+        "command_pause_payload": "pause",
+        "command_playpause_topic": command,
+        "command_playpause_payload": "play_pause",
+        "command_next_topic": command,
+        "command_next_payload": "next",
+        "command_previous_topic": command,
+        "command_previous_payload": "previous",
+    });
+    for attribute in STATE_ATTRIBUTES {
+        config[format!("state_{}_topic", attribute)] = json!(topics.zone_attribute(id, attribute));
+    }
+    if zone.volume_control.is_some() {
+        config["command_volume_topic"] = json!(topics.zone_volume_set(id));
+    }
+    config
+}
+
+/// Retained discovery `(topic, payload)` pairs announcing a knob's battery
+/// level and charging sensors
+pub fn knob_sensor_configs(topics: &Topics, knob_id: &str, name: &str) -> [(String, Value); 2] {
+    let id = format!("uhc_knob_{}", slug(knob_id));
+    let device = json!({
+        "identifiers": [id],
+        "name": name,
+        "manufacturer": "Unified Hi-Fi Control",
+        "model": "Knob",
+    });
+    let availability = json!([{ "topic": topics.status() }]);
+    let battery_id = format!("{}_battery", id);
+    let charging_id = format!("{}_charging", id);
+    [
+        (
+            topics.sensor_discovery("sensor", &battery_id),
+            json!({
+                "name": "Battery",
+                "unique_id": battery_id,
+                "device_class": "battery",
+                "state_class": "measurement",
+                "unit_of_measurement": "%",
+                "state_topic": topics.knob_battery(knob_id),
+                "availability": availability,
+                "device": device,
+            }),
+        ),
+        (
+            topics.sensor_discovery("binary_sensor", &charging_id),
+            json!({
+                "name": "Charging",
+                "unique_id": charging_id,
+                "device_class": "battery_charging",
+                "state_topic": topics.knob_charging(knob_id),
+                "payload_on": ON,
+                "payload_off": OFF,
+                "availability": availability,
+                "device": device,
+            }),
+        ),
+    ]
+}
+
+/// Retained discovery `(topic, payload)` announcing an adapter's
+/// connectivity sensor on the bridge device
+pub fn adapter_sensor_config(topics: &Topics, adapter: &str) -> (String, Value) {
+    let id = format!("uhc_adapter_{}", slug(adapter));
+    (
+        topics.sensor_discovery("binary_sensor", &id),
+        json!({
+            "name": format!("{} connection", adapter_label(adapter)),
+            "unique_id": id,
+            "device_class": "connectivity",
+            "state_topic": topics.adapter_connected(adapter),
+            "payload_on": ON,
+            "payload_off": OFF,
+            "availability": [{ "topic": topics.status() }],
+            "device": {
+                "identifiers": ["uhc_bridge"],
+                "name": "Unified Hi-Fi Control",
+                "manufacturer": "Unified Hi-Fi Control",
+                "model": "Bridge",
+            },
+        }),
+    )
+}
+
+/// Display name of an adapter id (`lms` is `LMS`, `roon` is `Roon`)
+fn adapter_label(adapter: &str) -> String {
+    match adapter {
+        "lms" => "LMS".to_string(),
+        "hqplayer" => "HQPlayer".to_string(),
+        "upnp" => "UPnP".to_string(),
+        "openhome" => "OpenHome".to_string(),
+        "heos" => "HEOS".to_string(),
+        "http" => "HTTP".to_string(),
+        _ => {
+            let mut chars = adapter.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Retained `(attribute, payload)` pairs for a zone's state topics, with
+/// title, artist and album passed through `text`
+pub fn zone_state(zone: &Zone, text: &TextNormalizer) -> Vec<(&'static str, String)> {
+    let state = match zone.state {
+        PlaybackState::Playing => "playing",
+        PlaybackState::Paused => "paused",
+        _ => "idle",
+    };
+    let np = zone.now_playing.as_ref();
+    let (title, artist, album) = np
+        .map(|n| {
+            (
+                text.apply(&n.title).into_owned(),
+                text.apply(&n.artist).into_owned(),
+                text.apply(&n.album).into_owned(),
+            )
+        })
+        .unwrap_or_default();
+    let seconds = |v: Option<f64>| {
+        v.map(|s| (s.round() as i64).to_string())
+            .unwrap_or_default()
+    };
+    let volume = zone
+        .volume_control
+        .as_ref()
+        .filter(|vc| vc.max > vc.min)
+        .map(|vc| {
+            format!(
+                "{:.2}",
+                ((vc.value - vc.min) / (vc.max - vc.min)).clamp(0.0, 1.0)
+            )
+        })
+        .unwrap_or_default();
+    vec![
+        ("state", state.to_string()),
+        ("title", title),
+        ("artist", artist),
+        ("album", album),
+        ("duration", seconds(np.and_then(|n| n.duration))),
+        ("position", seconds(np.and_then(|n| n.seek_position))),
+        ("volume", volume),
+        ("mediatype", "music".to_string()),
+    ]
+}
+
+/// Home Assistant's 0.0 to 1.0 volume in the zone's own units
+pub fn volume_from_level(zone: &Zone, level: f32) -> Option<f32> {
+    let vc = zone.volume_control.as_ref()?;
+    Some(vc.min + level.clamp(0.0, 1.0) * (vc.max - vc.min))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::{VolumeControl, VolumeScale};
+
+    fn zone() -> Zone {
+        serde_json::from_value(json!({
+            "zone_id": "lms:00:04:20:aa",
+            "zone_name": "Kitchen",
+            "state": "playing",
+            "volume_control": null,
+            "now_playing": { "title": "So What", "artist": "Miles Davis", "album": "Kind of Blue",
+                             "image_key": null, "seek_position": 12.4, "duration": 545.0, "metadata": null },
+            "source": "lms",
+            "is_controllable": true,
+            "is_seekable": true,
+            "last_updated": 0,
+            "is_play_allowed": true,
+            "is_pause_allowed": true,
+            "is_next_allowed": true,
+            "is_previous_allowed": true
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_topics() {
+        let topics = Topics::new("unified-hifi/", "homeassistant");
+        assert_eq!(
+            topics.zone_command("lms:00:04:20:aa"),
+            "unified-hifi/zones/lms_00_04_20_aa/command"
+        );
+        assert_eq!(
+            topics.discovery("lms:00:04:20:aa"),
+            "homeassistant/media_player/lms_00_04_20_aa/config"
+        );
+        assert_eq!(
+            topics.parse_command("unified-hifi/zones/lms_00_04_20_aa/volume/set"),
+            Some(("lms_00_04_20_aa", ZoneCommand::Volume))
+        );
+        assert_eq!(
+            topics.parse_command("unified-hifi/zones/lms_00_04_20_aa/title"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_zone_payloads() {
+        let topics = Topics::new("unified-hifi", "homeassistant");
+        let mut zone = zone();
+        let config = media_player_config(&topics, &zone);
+        assert_eq!(config["unique_id"], "uhc_lms_00_04_20_aa");
+        assert_eq!(
+            config["state_title_topic"],
+            "unified-hifi/zones/lms_00_04_20_aa/title"
+        );
+        assert!(config.get("command_volume_topic").is_none());
+
+        let state = zone_state(&zone, &TextNormalizer::default());
+        assert!(state.contains(&("state", "playing".to_string())));
+        assert!(state.contains(&("position", "12".to_string())));
+
+        zone.volume_control = Some(VolumeControl {
+            value: -20.0,
+            min: -80.0,
+            max: 0.0,
+            step: 1.0,
+            is_muted: false,
+            scale: VolumeScale::Decibel,
+            output_id: None,
+        });
+        assert!(
+            zone_state(&zone, &TextNormalizer::default()).contains(&("volume", "0.75".to_string()))
+        );
+        assert_eq!(volume_from_level(&zone, 0.5), Some(-40.0));
+    }
+
+    #[test]
+    fn test_sensor_payloads() {
+        let topics = Topics::new("unified-hifi", "homeassistant");
+        let [(battery_topic, battery), (charging_topic, charging)] =
+            knob_sensor_configs(&topics, "a1:b2", "Desk Knob");
+        assert_eq!(
+            battery_topic,
+            "homeassistant/sensor/uhc_knob_a1_b2_battery/config"
+        );
+        assert_eq!(battery["device_class"], "battery");
+        assert_eq!(battery["state_topic"], "unified-hifi/knobs/a1_b2/battery");
+        assert_eq!(
+            charging_topic,
+            "homeassistant/binary_sensor/uhc_knob_a1_b2_charging/config"
+        );
+        assert_eq!(charging["device"]["name"], "Desk Knob");
+
+        let (topic, adapter) = adapter_sensor_config(&topics, "roon");
+        assert_eq!(topic, "homeassistant/binary_sensor/uhc_adapter_roon/config");
+        assert_eq!(adapter["name"], "Roon connection");
+        assert_eq!(adapter["device_class"], "connectivity");
+        assert_eq!(adapter_label("lms"), "LMS");
+    }
+
+    #[test]
+    fn test_zone_state_normalizes_text() {
+        let mut zone = zone();
+        if let Some(np) = zone.now_playing.as_mut() {
+            np.artist = "Björk".to_string();
+        }
+        let text = TextNormalizer::new(crate::config::TextNormalization {
+            strip_diacritics: true,
+            max_length: Some(6),
+            ..Default::default()
+        });
+        let state = zone_state(&zone, &text);
+        assert!(state.contains(&("title", "So Wh…".to_string())));
+        assert!(state.contains(&("artist", "Bjork".to_string())));
+    }
+}
@@ -0,0 +1,514 @@
+//! MQTT and Home Assistant
+//!
+//! Publishes every Roon, LMS, OpenHome and UPnP zone to an MQTT broker as a
+//! Home Assistant media player (see [`discovery`]) and runs the commands Home
+//! Assistant sends back through the unified control path, like `/control`.
+//! State topics follow the event bus and are retained, so Home Assistant gets
+//! the current state when it (re)connects.
+//!
+//! The bridge's `status` topic is `online` while it is connected and
+//! `offline` once it is gone (the broker publishes it as our last will), and
+//! each zone has its own availability topic, so entities show as unavailable
+//! instead of stale. A zone is also unavailable while its adapter is
+//! disconnected from its backend (e.g. the Roon Core is down). Commands count
+//! as non-admin: locked zones reject them.
+//!
+//! Knob batteries (level and charging) and adapter connections are published
+//! as Home Assistant sensors too, so automations can warn about a low knob or
+//! a Roon outage.
+//!
+//! `qos` in `[mqtt]` applies to every publish and subscription; `retain`
+//! covers the zone state topics. Discovery, status and availability are
+//! always retained, since Home Assistant needs them when it restarts.
+
+pub mod discovery;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use rumqttc::{AsyncClient, ClientError, Event, LastWill, MqttOptions, Packet, QoS};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::api::{AppState, Caller};
+use crate::bus::{BusEvent, Zone};
+use crate::config::MqttConfig;
+use crate::knobs::dispatch_control;
+use crate::text::TextNormalizer;
+use discovery::{
+    adapter_sensor_config, knob_sensor_configs, media_player_config, slug, volume_from_level,
+    zone_state, Topics, ZoneCommand, OFF, OFFLINE, ON, ONLINE,
+};
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Wait before reconnecting after the broker connection fails
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How often knob battery levels are checked for changes; the bus only
+/// reports charging and low-battery transitions
+const KNOB_REFRESH: Duration = Duration::from_secs(60);
+
+/// Requests queued for the event loop before publishing waits
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Zone sources announced to Home Assistant; HQPlayer zones follow the zone
+/// they are linked to
+const ANNOUNCED_SOURCES: &[&str] = &[
+    "roon", "lms", "openhome", "upnp", "snapcast", "heos", "kodi", "spotify", "http", "plexamp",
+];
+
+/// JSON form of a command: `{"action": "vol_abs", "value": 30}`
+#[derive(Debug, Deserialize)]
+struct CommandPayload {
+    action: String,
+    #[serde(default)]
+    value: Option<serde_json::Value>,
+}
+
+/// Connect to the configured broker and keep zones published until `shutdown`
+pub fn start(config: &MqttConfig, state: AppState, shutdown: CancellationToken) -> Result<()> {
+    let host = config.host.trim();
+    if host.is_empty() {
+        return Err(anyhow!("no MQTT broker host configured"));
+    }
+    let qos = match config.qos {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        2 => QoS::ExactlyOnce,
+        other => return Err(anyhow!("invalid MQTT qos {} (use 0, 1 or 2)", other)),
+    };
+    let topics = Topics::new(&config.base_topic, &config.discovery_prefix);
+    let client_id = config.client_id.clone().unwrap_or_else(|| {
+        format!(
+            "unified-hifi-control-{}",
+            gethostname::gethostname().to_string_lossy()
+        )
+    });
+
+    let mut options = MqttOptions::new(client_id, host, config.port);
+    options.set_keep_alive(KEEP_ALIVE);
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+    options.set_last_will(LastWill::new(topics.status(), OFFLINE, qos, true));
+    let (client, mut eventloop) = AsyncClient::new(options, CHANNEL_CAPACITY);
+    tracing::info!("MQTT enabled for {}:{}", host, config.port);
+    let publisher = Publisher {
+        client,
+        topics,
+        qos,
+        retain: config.retain,
+        adapters: Arc::default(),
+        knobs: Arc::default(),
+    };
+
+    // Connection: announce everything on each (re)connect and run commands.
+    // Publishing goes through this loop, so both happen on their own tasks.
+    // Stopping drops the connection without a DISCONNECT, which makes the
+    // broker publish the last will.
+    {
+        let publisher = publisher.clone();
+        let state = state.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    event = eventloop.poll() => event,
+                };
+                match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        tracing::info!("MQTT connected");
+                        tokio::spawn(announce(publisher.clone(), state.clone()));
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        let payload = String::from_utf8_lossy(&publish.payload).into_owned();
+                        tokio::spawn(run_command(
+                            state.clone(),
+                            publisher.topics.clone(),
+                            publish.topic,
+                            payload,
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("MQTT connection failed: {}", e);
+                        tokio::select! {
+                            _ = shutdown.cancelled() => break,
+                            _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    // Bus: keep state topics current
+    let mut rx = state.bus.subscribe();
+    tokio::spawn(async move {
+        let mut knob_refresh = tokio::time::interval(KNOB_REFRESH);
+        loop {
+            let event = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = knob_refresh.tick() => {
+                    if let Err(e) = publish_knobs(&publisher, &state).await {
+                        tracing::debug!("MQTT knob battery publish failed: {}", e);
+                    }
+                    continue;
+                }
+                result = rx.recv() => match result {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("MQTT lagged, dropped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if let Err(e) = publish_event(&publisher, &state, &event).await {
+                tracing::debug!("MQTT publish for {} failed: {}", event.event_type(), e);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn is_announced(zone: &Zone) -> bool {
+    ANNOUNCED_SOURCES.contains(&zone.source.as_str())
+}
+
+/// The broker client with the `[mqtt]` publish settings
+#[derive(Clone)]
+struct Publisher {
+    client: AsyncClient,
+    topics: Topics,
+    qos: QoS,
+    /// Retain zone state topics
+    retain: bool,
+    /// Last connection state seen per adapter; adapters not in here count
+    /// as connected
+    adapters: Arc<Mutex<HashMap<String, bool>>>,
+    /// Battery level and charging last published per knob
+    knobs: Arc<Mutex<HashMap<String, (u8, bool)>>>,
+}
+
+impl Publisher {
+    async fn publish(
+        &self,
+        topic: String,
+        retain: bool,
+        payload: impl Into<Vec<u8>>,
+    ) -> Result<(), ClientError> {
+        self.client.publish(topic, self.qos, retain, payload).await
+    }
+
+    fn adapter_state(&self, adapter: &str) -> Option<bool> {
+        self.adapters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(adapter)
+            .copied()
+    }
+
+    fn adapter_connected(&self, adapter: &str) -> bool {
+        self.adapter_state(adapter).unwrap_or(true)
+    }
+
+    /// An adapter's connectivity sensor: discovery and state
+    async fn adapter_sensor(&self, adapter: &str, connected: bool) -> Result<(), ClientError> {
+        let (topic, config) = adapter_sensor_config(&self.topics, adapter);
+        self.publish(topic, true, config.to_string()).await?;
+        self.publish(
+            self.topics.adapter_connected(adapter),
+            self.retain,
+            if connected { ON } else { OFF },
+        )
+        .await
+    }
+
+    /// A knob's battery sensors, when its level or charging changed since the
+    /// last publish; knobs that never reported a level get none
+    async fn knob(
+        &self,
+        knob_id: &str,
+        name: &str,
+        level: Option<u8>,
+        charging: bool,
+    ) -> Result<(), ClientError> {
+        let Some(level) = level else {
+            return Ok(());
+        };
+        let previous = self
+            .knobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(knob_id.to_string(), (level, charging));
+        if previous == Some((level, charging)) {
+            return Ok(());
+        }
+        if previous.is_none() {
+            for (topic, config) in knob_sensor_configs(&self.topics, knob_id, name) {
+                self.publish(topic, true, config.to_string()).await?;
+            }
+        }
+        self.publish(
+            self.topics.knob_battery(knob_id),
+            self.retain,
+            level.to_string(),
+        )
+        .await?;
+        self.publish(
+            self.topics.knob_charging(knob_id),
+            self.retain,
+            if charging { ON } else { OFF },
+        )
+        .await
+    }
+
+    /// Discovery config and availability of a zone
+    async fn discovery(&self, zone: &Zone) -> Result<(), ClientError> {
+        let config = media_player_config(&self.topics, zone).to_string();
+        self.publish(self.topics.discovery(&zone.zone_id), true, config)
+            .await?;
+        let availability = if self.adapter_connected(&zone.source) {
+            ONLINE
+        } else {
+            OFFLINE
+        };
+        self.availability(&zone.zone_id, availability).await
+    }
+
+    async fn state(&self, zone: &Zone, text: &TextNormalizer) -> Result<(), ClientError> {
+        for (attribute, payload) in zone_state(zone, text) {
+            self.publish(
+                self.topics.zone_attribute(&zone.zone_id, attribute),
+                self.retain,
+                payload,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn availability(&self, zone_id: &str, availability: &str) -> Result<(), ClientError> {
+        self.publish(self.topics.zone_availability(zone_id), true, availability)
+            .await
+    }
+
+    /// Record an adapter's connection state and mark its zones available or not
+    async fn adapter_changed(
+        &self,
+        state: &AppState,
+        adapter: &str,
+        connected: bool,
+    ) -> Result<(), ClientError> {
+        self.adapters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(adapter.to_string(), connected);
+        self.adapter_sensor(adapter, connected).await?;
+        let availability = if connected { ONLINE } else { OFFLINE };
+        for zone in state.aggregator.get_zones().await {
+            if is_announced(&zone) && zone.source == adapter {
+                self.availability(&zone.zone_id, availability).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bridge status, command subscriptions and every zone
+async fn announce(publisher: Publisher, state: AppState) {
+    let result = async {
+        publisher
+            .publish(publisher.topics.status(), true, ONLINE)
+            .await?;
+        for filter in publisher.topics.command_filters() {
+            publisher.client.subscribe(filter, publisher.qos).await?;
+        }
+        for zone in state.aggregator.get_zones().await {
+            if is_announced(&zone) {
+                publisher.discovery(&zone).await?;
+                publisher.state(&zone, &state.text.mqtt).await?;
+            }
+        }
+        let adapters: Vec<(String, bool)> = publisher
+            .adapters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(adapter, connected)| (adapter.clone(), *connected))
+            .collect();
+        for (adapter, connected) in adapters {
+            publisher.adapter_sensor(&adapter, connected).await?;
+        }
+        publisher
+            .knobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        publish_knobs(&publisher, &state).await?;
+        Ok::<_, ClientError>(())
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("MQTT announce failed: {}", e);
+    }
+}
+
+/// Battery sensors of every knob whose level or charging changed
+async fn publish_knobs(publisher: &Publisher, state: &AppState) -> Result<(), ClientError> {
+    for knob in state.knobs.list().await {
+        let name = if knob.name.is_empty() {
+            knob.knob_id.as_str()
+        } else {
+            knob.name.as_str()
+        };
+        publisher
+            .knob(
+                &knob.knob_id,
+                name,
+                knob.status.battery_level,
+                knob.status.battery_charging == Some(true),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Publish whatever a bus event changed
+async fn publish_event(
+    publisher: &Publisher,
+    state: &AppState,
+    event: &BusEvent,
+) -> Result<(), ClientError> {
+    match event {
+        BusEvent::ZoneDiscovered { zone } if is_announced(zone) => {
+            // A zone coming in means its adapter has reconnected
+            if publisher.adapter_state(&zone.source) == Some(false) {
+                publisher.adapter_changed(state, &zone.source, true).await?;
+            }
+            publisher.discovery(zone).await?;
+            publisher.state(zone, &state.text.mqtt).await
+        }
+        // Names come from metadata too
+        BusEvent::ZoneMetadataChanged { zone_id } => {
+            match state.aggregator.get_zone(zone_id).await {
+                Some(zone) if is_announced(&zone) => publisher.discovery(&zone).await,
+                _ => Ok(()),
+            }
+        }
+        BusEvent::ZoneUpdated { zone_id, .. } | BusEvent::NowPlayingChanged { zone_id, .. } => {
+            match state.aggregator.get_zone(zone_id.as_str()).await {
+                Some(zone) if is_announced(&zone) => publisher.state(&zone, &state.text.mqtt).await,
+                _ => Ok(()),
+            }
+        }
+        BusEvent::VolumeChanged { output_id, .. } => {
+            for zone in state.aggregator.get_zones().await {
+                let output = zone
+                    .volume_control
+                    .as_ref()
+                    .and_then(|vc| vc.output_id.as_deref());
+                if is_announced(&zone) && output == Some(output_id.as_str()) {
+                    publisher.state(&zone, &state.text.mqtt).await?;
+                }
+            }
+            Ok(())
+        }
+        BusEvent::ZoneRemoved { zone_id } => {
+            publisher.availability(zone_id.as_str(), OFFLINE).await
+        }
+        BusEvent::ZonesFlushed { zone_ids, .. } => {
+            for zone_id in zone_ids {
+                publisher.availability(zone_id, OFFLINE).await?;
+            }
+            Ok(())
+        }
+        // Zones an adapter still lists go unavailable while it's disconnected
+        BusEvent::RoonConnected { .. } => publisher.adapter_changed(state, "roon", true).await,
+        BusEvent::RoonDisconnected => publisher.adapter_changed(state, "roon", false).await,
+        BusEvent::LmsConnected { .. } => publisher.adapter_changed(state, "lms", true).await,
+        BusEvent::LmsDisconnected { .. } => publisher.adapter_changed(state, "lms", false).await,
+        BusEvent::AdapterConnected { adapter, .. } => {
+            publisher.adapter_changed(state, adapter, true).await
+        }
+        BusEvent::AdapterDisconnected { adapter, .. } => {
+            publisher.adapter_changed(state, adapter, false).await
+        }
+        BusEvent::HqpConnected { .. } => publisher.adapter_changed(state, "hqplayer", true).await,
+        BusEvent::HqpDisconnected { .. } => {
+            publisher.adapter_changed(state, "hqplayer", false).await
+        }
+        BusEvent::KnobBatteryChanged {
+            knob_id,
+            knob_name,
+            battery_level,
+            charging,
+            ..
+        } => {
+            publisher
+                .knob(
+                    knob_id,
+                    knob_name.as_deref().unwrap_or(knob_id),
+                    *battery_level,
+                    *charging,
+                )
+                .await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Run a message from a zone's command topics through the control path
+async fn run_command(state: AppState, topics: Topics, topic: String, payload: String) {
+    let Some((zone_slug, command)) = topics.parse_command(&topic) else {
+        return;
+    };
+    let zone = state
+        .aggregator
+        .get_zones()
+        .await
+        .into_iter()
+        .find(|z| is_announced(z) && slug(&z.zone_id) == zone_slug);
+    let Some(zone) = zone else {
+        tracing::debug!("MQTT command for unknown zone {}", zone_slug);
+        return;
+    };
+
+    let payload = payload.trim();
+    let (action, value) = match command {
+        ZoneCommand::Volume => {
+            match payload
+                .parse::<f32>()
+                .ok()
+                .and_then(|level| volume_from_level(&zone, level))
+            {
+                Some(volume) => ("vol_abs".to_string(), Some(serde_json::json!(volume))),
+                None => {
+                    tracing::debug!("MQTT volume for {} ignored: {:?}", zone.zone_id, payload);
+                    return;
+                }
+            }
+        }
+        ZoneCommand::Action => match serde_json::from_str::<CommandPayload>(payload) {
+            Ok(command) => (command.action, command.value),
+            Err(_) => (payload.to_string(), None),
+        },
+    };
+    if let Err(e) = dispatch_control(
+        &state,
+        &zone.zone_id,
+        &action,
+        value.as_ref(),
+        Caller::Restricted,
+    )
+    .await
+    {
+        tracing::warn!("MQTT {} on {} failed: {}", action, zone.zone_id, e.message);
+    }
+}
@@ -0,0 +1,307 @@
+//! Remote access listener
+//!
+//! Serves the same router as the LAN listener on a second address meant to be
+//! reachable from outside the home network, typically the host's Tailscale or
+//! WireGuard address, so the iOS app can connect without a reverse proxy.
+//! Every request on this listener needs the access token, either as
+//! `Authorization: Bearer <token>` or as a `?token=` query parameter (for
+//! EventSource and image URLs, which can't set headers).
+//!
+//! Named tokens from `/api/tokens` are accepted too, limited to their scope.
+//! Hooks (`/hooks/{name}`) called with a hook secret need no token; the
+//! secret is checked by the hook itself.
+//!
+//! With remote access on, the LAN listener and gRPC also ask for the token
+//! from peers that aren't on the local network, so the remote address can't
+//! be used to reach them without one. Local peers (loopback, private and
+//! link-local addresses) need no token there.
+
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{ApiError, ErrorCode, HOOK_SECRET_HEADER};
+use crate::config::{get_config_file_path, read_config_file};
+use crate::tokens::TokenStore;
+
+const REMOTE_ACCESS_FILE: &str = "remote-access.json";
+
+/// Bytes of randomness in a generated token (hex encoded)
+const TOKEN_BYTES: usize = 32;
+
+/// Persisted remote access state
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RemoteAccessFile {
+    token: Option<String>,
+}
+
+/// Resolve the configured bind to an address.
+///
+/// `tailscale` picks the first interface address in Tailscale's ranges
+/// (100.64.0.0/10 or fd7a:115c:a1e0::/48); an IP address is used as-is;
+/// anything else is treated as an interface name (e.g. `wg0`), preferring IPv4.
+pub fn resolve_bind(bind: &str) -> Result<IpAddr> {
+    if let Ok(ip) = bind.parse::<IpAddr>() {
+        return Ok(ip);
+    }
+
+    let interfaces = if_addrs::get_if_addrs()?;
+    if bind.eq_ignore_ascii_case("tailscale") {
+        return interfaces
+            .iter()
+            .map(|iface| iface.ip())
+            .find(is_tailscale_addr)
+            .ok_or_else(|| anyhow!("no Tailscale address found (is tailscaled up?)"));
+    }
+
+    let mut addrs: Vec<IpAddr> = interfaces
+        .iter()
+        .filter(|iface| iface.name == bind)
+        .map(|iface| iface.ip())
+        .collect();
+    addrs.sort_by_key(|ip| ip.is_ipv6());
+    addrs
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("interface {} not found or has no address", bind))
+}
+
+/// Whether an address is in Tailscale's CGNAT or ULA range
+pub fn is_tailscale_addr(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_tailscale_v4(v4),
+        IpAddr::V6(v6) => is_tailscale_v6(v6),
+    }
+}
+
+fn is_tailscale_v4(ip: &Ipv4Addr) -> bool {
+    let [a, b, _, _] = ip.octets();
+    a == 100 && (b & 0xc0) == 64
+}
+
+fn is_tailscale_v6(ip: &Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    segments[0] == 0xfd7a && segments[1] == 0x115c && segments[2] == 0xa1e0
+}
+
+/// Whether a peer is on the local network: loopback, private or link-local,
+/// but not Tailscale's ranges
+pub fn is_lan_peer(ip: &IpAddr) -> bool {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(*ip),
+        IpAddr::V4(_) => *ip,
+    };
+    if is_tailscale_addr(&ip) {
+        return false;
+    }
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            // fc00::/7 unique local, fe80::/10 link-local
+            v6.is_loopback() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// The configured token, or the saved one, generating and saving a new token if
+/// there is neither
+pub fn load_or_create_token(configured: Option<String>) -> Result<String> {
+    if let Some(token) = configured.filter(|t| !t.trim().is_empty()) {
+        return Ok(token.trim().to_string());
+    }
+
+    let saved = read_config_file(REMOTE_ACCESS_FILE)
+        .and_then(|content| serde_json::from_str::<RemoteAccessFile>(&content).ok())
+        .and_then(|file| file.token)
+        .filter(|t| !t.is_empty());
+    if let Some(token) = saved {
+        return Ok(token);
+    }
+
+    let token = hex::encode(rand::random::<[u8; TOKEN_BYTES]>());
+    let path = get_config_file_path(REMOTE_ACCESS_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = RemoteAccessFile {
+        token: Some(token.clone()),
+    };
+    // The token grants full access, so only the service user may read it
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(&path)?
+        .write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
+    tracing::info!("Generated remote access token in {}", path.display());
+    Ok(token)
+}
+
+/// Tokens accepted by the remote listener
+pub struct RemoteAuth {
+    /// The listener's own token (full access)
+    pub token: String,
+    /// Named tokens, limited to their scope
+    pub tokens: Arc<TokenStore>,
+}
+
+/// Middleware rejecting requests without the access token or a named token
+/// whose scope covers the request
+pub async fn require_token(
+    State(auth): State<Arc<RemoteAuth>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Pairing is how a client without a token gets one; the code is the credential
+    if request.method() == Method::POST && request.uri().path() == "/api/pair" {
+        return next.run(request).await;
+    }
+    // The hook secret is the credential; a hook without one rejects it
+    if request.method() == Method::POST
+        && request.uri().path().starts_with("/hooks/")
+        && (request.headers().contains_key(HOOK_SECRET_HEADER)
+            || request
+                .uri()
+                .query()
+                .and_then(|q| query_param(q, "secret"))
+                .is_some())
+    {
+        return next.run(request).await;
+    }
+
+    let header_token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let query_token = request.uri().query().and_then(|q| query_param(q, "token"));
+    let candidates: Vec<&str> = header_token
+        .into_iter()
+        .chain(query_token.as_deref())
+        .collect();
+
+    if candidates
+        .iter()
+        .any(|candidate| tokens_match(candidate, &auth.token))
+    {
+        return next.run(request).await;
+    }
+
+    let scope = candidates
+        .iter()
+        .find_map(|candidate| auth.tokens.authenticate(candidate));
+    match scope {
+        Some(scope) if scope.allows(request.method(), request.uri().path()) => {
+            next.run(request).await
+        }
+        Some(_) => ApiError::new(
+            ErrorCode::Forbidden,
+            "Access token scope does not allow this request",
+        )
+        .into_response(),
+        None => ApiError::new(ErrorCode::Unauthorized, "Missing or invalid access token")
+            .into_response(),
+    }
+}
+
+/// Middleware for the LAN listener: local peers pass, anyone else needs a
+/// token as on the remote listener
+pub async fn require_token_off_lan(
+    State(auth): State<Arc<RemoteAuth>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let local = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .is_some_and(|ConnectInfo(peer)| is_lan_peer(&peer.ip()));
+    if local {
+        return next.run(request).await;
+    }
+    require_token(State(auth), request, next).await
+}
+
+/// Value of a query parameter, e.g. `token`
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name)
+            .then(|| urlencoding::decode(value).ok().map(|v| v.into_owned()))
+            .flatten()
+    })
+}
+
+/// Constant-time comparison so response timing doesn't leak the token
+pub fn tokens_match(candidate: &str, token: &str) -> bool {
+    let (a, b) = (candidate.as_bytes(), token.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tailscale_ranges() {
+        assert!(is_tailscale_addr(&"100.101.102.103".parse().unwrap()));
+        assert!(is_tailscale_addr(&"100.64.0.1".parse().unwrap()));
+        assert!(!is_tailscale_addr(&"100.128.0.1".parse().unwrap()));
+        assert!(!is_tailscale_addr(&"192.168.1.10".parse().unwrap()));
+        assert!(is_tailscale_addr(&"fd7a:115c:a1e0::1".parse().unwrap()));
+        assert!(!is_tailscale_addr(&"fd00::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_lan_peers() {
+        for ip in [
+            "127.0.0.1",
+            "192.168.1.10",
+            "10.0.0.2",
+            "169.254.1.1",
+            "::1",
+            "fd00::1",
+        ] {
+            assert!(is_lan_peer(&ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_lan_peer(&"::ffff:192.168.1.10".parse().unwrap()));
+        for ip in [
+            "100.101.102.103",
+            "fd7a:115c:a1e0::1",
+            "203.0.113.5",
+            "2001:db8::1",
+        ] {
+            assert!(!is_lan_peer(&ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_resolve_bind_accepts_ip() {
+        assert_eq!(
+            resolve_bind("10.0.0.2").unwrap(),
+            "10.0.0.2".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_query_token_and_match() {
+        assert_eq!(
+            query_param("zone_id=roon%3A1&token=abc%2B1", "token").as_deref(),
+            Some("abc+1")
+        );
+        assert_eq!(query_param("zone_id=1", "token"), None);
+        assert_eq!(query_param("secret=s", "secret").as_deref(), Some("s"));
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secre", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Scene presets
+//!
+//! A scene is a stored multi-zone setup applied in one call. The party scene
+//! lists the zones to group (every zone when empty), a target volume per zone
+//! and an optional playlist, and is persisted to `scenes.json` in the config
+//! directory.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{PoisonError, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{get_config_file_path, read_config_file};
+
+const SCENES_FILE: &str = "scenes.json";
+
+/// Party mode preset
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PartyScene {
+    /// Zones to include (prefixed zone_ids); every zone when empty
+    #[serde(default)]
+    pub zones: Vec<String>,
+    /// Target volume per zone_id, in the zone's own scale
+    #[serde(default)]
+    pub volumes: HashMap<String, f64>,
+    /// Started on the LMS group leader: a URL, file path or playlist file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub playlist: Option<String>,
+}
+
+impl PartyScene {
+    /// Whether `zone_id` takes part
+    pub fn includes(&self, zone_id: &str) -> bool {
+        self.zones.is_empty() || self.zones.iter().any(|z| z == zone_id)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScenesFile {
+    #[serde(default)]
+    party: PartyScene,
+}
+
+/// Stored scenes, optionally persisted to disk
+#[derive(Default)]
+pub struct SceneStore {
+    scenes: RwLock<ScenesFile>,
+    /// `None` keeps the store in memory only (tests, demo)
+    path: Option<PathBuf>,
+}
+
+impl SceneStore {
+    /// Load scenes from the config directory; changes are written back
+    pub fn load() -> Self {
+        let scenes = read_config_file(SCENES_FILE)
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(scenes) => Some(scenes),
+                Err(e) => {
+                    tracing::warn!("Failed to parse scenes: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            scenes: RwLock::new(scenes),
+            path: Some(get_config_file_path(SCENES_FILE)),
+        }
+    }
+
+    pub fn party(&self) -> PartyScene {
+        self.scenes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .party
+            .clone()
+    }
+
+    pub fn set_party(&self, party: PartyScene) {
+        let mut scenes = self.scenes.write().unwrap_or_else(PoisonError::into_inner);
+        scenes.party = party;
+        self.save(&scenes);
+    }
+
+    fn save(&self, scenes: &ScenesFile) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(scenes) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(path, json) {
+                    tracing::error!("Failed to save scenes: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize scenes: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_party_includes_all_zones_when_empty() {
+        let all = PartyScene::default();
+        assert!(all.includes("roon:1"));
+
+        let some = PartyScene {
+            zones: vec!["lms:aa".to_string()],
+            ..Default::default()
+        };
+        assert!(some.includes("lms:aa"));
+        assert!(!some.includes("roon:1"));
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let store = SceneStore::default();
+        let party = PartyScene {
+            zones: vec!["lms:aa".to_string()],
+            volumes: HashMap::from([("lms:aa".to_string(), 35.0)]),
+            playlist: Some("party.m3u".to_string()),
+        };
+        store.set_party(party.clone());
+        assert_eq!(store.party(), party);
+    }
+}
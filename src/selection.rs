@@ -0,0 +1,176 @@
+//! Selected zone per client
+//!
+//! Remembers which zone each client last focused on, so a phone, a watch and
+//! a wall tablet can pick up where another left off instead of each keeping
+//! its own choice in local storage. A client is whatever key it asks for
+//! (`?client=`), otherwise its named token or knob id; clients sharing a key
+//! share a selection. Persisted to `selection.json` in the config directory.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{PoisonError, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::unix_secs;
+use crate::config::{get_config_file_path, read_config_file};
+
+const SELECTION_FILE: &str = "selection.json";
+
+/// Key of clients that don't identify themselves (the LAN web UI)
+pub const DEFAULT_CLIENT: &str = "default";
+
+/// Client key of a knob, which selects the zone it reports
+pub fn knob_client(knob_id: &str) -> String {
+    format!("knob:{}", knob_id)
+}
+
+/// Longest client key accepted
+const MAX_CLIENT_LEN: usize = 64;
+
+/// A client's selected zone
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Selection {
+    pub zone_id: String,
+    /// Unix seconds
+    pub updated_at: u64,
+}
+
+/// Client keys are short and printable; they end up in events and logs
+pub fn is_valid_client(client: &str) -> bool {
+    !client.is_empty()
+        && client.len() <= MAX_CLIENT_LEN
+        && client
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SelectionFile {
+    #[serde(default)]
+    clients: BTreeMap<String, Selection>,
+}
+
+/// Selected zones by client, optionally persisted to disk
+#[derive(Default)]
+pub struct SelectionStore {
+    selections: RwLock<SelectionFile>,
+    /// `None` keeps the store in memory only (tests, demo)
+    path: Option<PathBuf>,
+}
+
+impl SelectionStore {
+    /// Load selections from the config directory; changes are written back
+    pub fn load() -> Self {
+        let selections = read_config_file(SELECTION_FILE)
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(selections) => Some(selections),
+                Err(e) => {
+                    tracing::warn!("Failed to parse zone selections: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            selections: RwLock::new(selections),
+            path: Some(get_config_file_path(SELECTION_FILE)),
+        }
+    }
+
+    /// All selections by client
+    pub fn list(&self) -> BTreeMap<String, Selection> {
+        self.selections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clients
+            .clone()
+    }
+
+    pub fn get(&self, client: &str) -> Option<Selection> {
+        self.selections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clients
+            .get(client)
+            .cloned()
+    }
+
+    /// Select a zone for a client; `false` if it was already selected
+    pub fn set(&self, client: &str, zone_id: &str) -> bool {
+        let mut selections = self
+            .selections
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if selections
+            .clients
+            .get(client)
+            .is_some_and(|s| s.zone_id == zone_id)
+        {
+            return false;
+        }
+        selections.clients.insert(
+            client.to_string(),
+            Selection {
+                zone_id: zone_id.to_string(),
+                updated_at: unix_secs(),
+            },
+        );
+        self.save(&selections);
+        true
+    }
+
+    /// Forget a client's selection; `false` if it had none
+    pub fn clear(&self, client: &str) -> bool {
+        let mut selections = self
+            .selections
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let removed = selections.clients.remove(client).is_some();
+        if removed {
+            self.save(&selections);
+        }
+        removed
+    }
+
+    fn save(&self, selections: &SelectionFile) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(selections) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(path, json) {
+                    tracing::error!("Failed to save zone selections: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize zone selections: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_store() {
+        assert!(is_valid_client("knob:aa-bb"));
+        assert!(!is_valid_client(""));
+        assert!(!is_valid_client("a b"));
+
+        let store = SelectionStore::default();
+        assert!(store.set("phone", "roon:1"));
+        assert!(!store.set("phone", "roon:1"));
+        assert_eq!(
+            store.get("phone").map(|s| s.zone_id).as_deref(),
+            Some("roon:1")
+        );
+        assert!(store.set("phone", "lms:aa"));
+        assert_eq!(store.list().len(), 1);
+        assert!(store.clear("phone"));
+        assert!(!store.clear("phone"));
+        assert_eq!(store.get("phone"), None);
+    }
+}
@@ -0,0 +1,469 @@
+//! Load/soak test mode (`--soak`)
+//!
+//! Runs against an already running bridge (usually one started with `--demo`)
+//! and simulates the clients it sees in a busy house:
+//! - web clients that fetch `/zones` and then hold `/events` open
+//! - knobs polling `/knob/now_playing` and their RGB565 artwork
+//! - with `--bursts`, rapid volume steps on `/control` (paired up/down, but
+//!   they do reach real zones)
+//!
+//! At the end it prints latency percentiles per request kind. `--max-p99-ms`
+//! fails the run when any kind is slower, so a release check can catch
+//! regressions in the aggregator or image pipeline.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use tokio_util::sync::CancellationToken;
+
+/// How often each simulated knob polls now playing
+const KNOB_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Knobs fetch artwork on every this many polls
+const KNOB_IMAGE_EVERY: u64 = 5;
+
+/// Pause between control bursts
+const BURST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Volume steps per burst (half up, half down)
+const BURST_STEPS: usize = 10;
+
+/// Per-request timeout; a timed out request counts as an error
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings for a soak run
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoakOptions {
+    /// Base URL of the bridge, e.g. `http://127.0.0.1:8088`
+    pub url: String,
+    pub duration: Duration,
+    pub sse_clients: usize,
+    pub knobs: usize,
+    /// Send control bursts
+    pub bursts: bool,
+    /// Bearer token, for bridges that require one
+    pub token: Option<String>,
+    /// Fail when a request kind's p99 exceeds this
+    pub max_p99: Option<Duration>,
+}
+
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn parse_flag<T: std::str::FromStr>(args: &[String], name: &str) -> Result<Option<T>> {
+    flag_value(args, name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| anyhow!("invalid value for {}: {}", name, value))
+        })
+        .transpose()
+}
+
+impl SoakOptions {
+    /// Options from the command line; the bridge on `port` of this host by default
+    pub fn from_args(args: &[String], port: u16) -> Result<Self> {
+        let url = flag_value(args, "--url")
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| format!("http://127.0.0.1:{}", port));
+        Ok(Self {
+            url,
+            duration: Duration::from_secs(parse_flag(args, "--duration")?.unwrap_or(60)),
+            sse_clients: parse_flag(args, "--clients")?.unwrap_or(50),
+            knobs: parse_flag(args, "--knobs")?.unwrap_or(20),
+            bursts: args.iter().any(|a| a == "--bursts"),
+            token: flag_value(args, "--token").map(String::from),
+            max_p99: parse_flag(args, "--max-p99-ms")?.map(Duration::from_millis),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    errors: u64,
+}
+
+/// Latency summary of one request kind
+#[derive(Debug, PartialEq)]
+struct Summary {
+    kind: &'static str,
+    count: usize,
+    errors: u64,
+    p50: Duration,
+    p90: Duration,
+    p99: Duration,
+    max: Duration,
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Latencies and errors by request kind, shared by all simulated clients
+#[derive(Debug, Default)]
+struct Recorder {
+    kinds: Mutex<BTreeMap<&'static str, Samples>>,
+    sse_events: AtomicU64,
+}
+
+impl Recorder {
+    /// Record a request's latency, or an error when `None`
+    fn record(&self, kind: &'static str, latency: Option<Duration>) {
+        let mut kinds = self.kinds.lock().unwrap_or_else(PoisonError::into_inner);
+        let samples = kinds.entry(kind).or_default();
+        match latency {
+            Some(latency) => samples.latencies.push(latency),
+            None => samples.errors += 1,
+        }
+    }
+
+    fn summaries(&self) -> Vec<Summary> {
+        let kinds = self.kinds.lock().unwrap_or_else(PoisonError::into_inner);
+        kinds
+            .iter()
+            .map(|(kind, samples)| {
+                let mut sorted = samples.latencies.clone();
+                sorted.sort();
+                Summary {
+                    kind: *kind,
+                    count: sorted.len(),
+                    errors: samples.errors,
+                    p50: percentile(&sorted, 50.0),
+                    p90: percentile(&sorted, 90.0),
+                    p99: percentile(&sorted, 99.0),
+                    max: sorted.last().copied().unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// HTTP client for one soak run
+#[derive(Clone)]
+struct Target {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    recorder: Arc<Recorder>,
+}
+
+impl Target {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}{}", self.url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send a request and read the whole body, recording the latency under `kind`
+    async fn timed(&self, kind: &'static str, request: reqwest::RequestBuilder) -> Option<Vec<u8>> {
+        let started = Instant::now();
+        let body = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                response.bytes().await.ok().map(|body| body.to_vec())
+            }
+            _ => None,
+        };
+        self.recorder
+            .record(kind, body.as_ref().map(|_| started.elapsed()));
+        body
+    }
+
+    async fn get(&self, kind: &'static str, path: &str) -> Option<Vec<u8>> {
+        self.timed(kind, self.request(reqwest::Method::GET, path))
+            .await
+    }
+
+    async fn zone_ids(&self) -> Result<Vec<String>> {
+        let body = self
+            .get("zones", "/zones")
+            .await
+            .ok_or_else(|| anyhow!("GET {}/zones failed", self.url))?;
+        let zones: Value = serde_json::from_slice(&body).context("invalid /zones response")?;
+        Ok(zones["zones"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|zone| zone["zone_id"].as_str().map(String::from))
+            .collect())
+    }
+}
+
+/// A web client: fetch zones, then read `/events` until cancelled (reconnecting)
+async fn sse_client(target: Target, cancel: CancellationToken) {
+    loop {
+        let connected = tokio::select! {
+            _ = cancel.cancelled() => break,
+            connected = async {
+                target.get("zones", "/zones").await;
+                let started = Instant::now();
+                let response = target
+                    .request(reqwest::Method::GET, "/events")
+                    .send()
+                    .await
+                    .ok()
+                    .filter(|r| r.status().is_success());
+                target
+                    .recorder
+                    .record("sse_connect", response.as_ref().map(|_| started.elapsed()));
+                response
+            } => connected,
+        };
+        let Some(mut response) = connected else {
+            // Don't spin on a server that refuses connections
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+            }
+            continue;
+        };
+        loop {
+            let chunk = tokio::select! {
+                _ = cancel.cancelled() => return,
+                chunk = response.chunk() => chunk,
+            };
+            match chunk {
+                Ok(Some(chunk)) => {
+                    let events = chunk.windows(5).filter(|w| *w == b"data:").count();
+                    target
+                        .recorder
+                        .sse_events
+                        .fetch_add(events as u64, Ordering::Relaxed);
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+/// A knob polling now playing on one zone, fetching artwork every few polls
+async fn knob_poller(target: Target, zone_id: String, cancel: CancellationToken) {
+    let zone = urlencoding::encode(&zone_id).into_owned();
+    let mut interval = tokio::time::interval(KNOB_POLL_INTERVAL);
+    let mut polls = 0u64;
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        target
+            .get(
+                "knob_now_playing",
+                &format!("/knob/now_playing?zone_id={}", zone),
+            )
+            .await;
+        if polls % KNOB_IMAGE_EVERY == 0 {
+            target
+                .get(
+                    "knob_image",
+                    &format!(
+                        "/knob/now_playing/image?zone_id={}&width=240&height=240&format=rgb565",
+                        zone
+                    ),
+                )
+                .await;
+        }
+        polls += 1;
+    }
+}
+
+/// Bursts of volume steps, alternating up and down, across the zones in turn
+async fn control_bursts(target: Target, zone_ids: Vec<String>, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(BURST_INTERVAL);
+    for zone_id in zone_ids.iter().cycle() {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        for step in 0..BURST_STEPS {
+            let action = if step % 2 == 0 { "vol_up" } else { "vol_down" };
+            let request = target
+                .request(reqwest::Method::POST, "/control")
+                .json(&json!({ "zone_id": zone_id, "action": action }));
+            target.timed("control", request).await;
+        }
+    }
+}
+
+fn print_report(summaries: &[Summary], sse_events: u64, elapsed: Duration) {
+    let ms = |d: Duration| format!("{:.1}", d.as_secs_f64() * 1000.0);
+    println!();
+    println!("Soak test finished after {}s", elapsed.as_secs());
+    println!(
+        "{:<18} {:>8} {:>7} {:>9} {:>9} {:>9} {:>9}",
+        "kind", "count", "errors", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    for s in summaries {
+        println!(
+            "{:<18} {:>8} {:>7} {:>9} {:>9} {:>9} {:>9}",
+            s.kind,
+            s.count,
+            s.errors,
+            ms(s.p50),
+            ms(s.p90),
+            ms(s.p99),
+            ms(s.max)
+        );
+    }
+    println!("SSE events received: {}", sse_events);
+}
+
+/// Kinds whose p99 exceeds `max`
+fn over_budget(summaries: &[Summary], max: Duration) -> Vec<&'static str> {
+    summaries
+        .iter()
+        .filter(|s| s.p99 > max)
+        .map(|s| s.kind)
+        .collect()
+}
+
+/// Run the soak test and print the report; fails when over the p99 budget
+pub async fn run(options: SoakOptions) -> Result<()> {
+    let target = Target {
+        client: reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?,
+        url: options.url.clone(),
+        token: options.token.clone(),
+        recorder: Arc::new(Recorder::default()),
+    };
+    // The SSE client has no overall timeout, as streams stay open
+    let sse_target = Target {
+        client: reqwest::Client::new(),
+        ..target.clone()
+    };
+
+    let zone_ids = target.zone_ids().await?;
+    println!(
+        "Soaking {} for {}s: {} SSE clients, {} knobs on {} zones{}",
+        options.url,
+        options.duration.as_secs(),
+        options.sse_clients,
+        options.knobs,
+        zone_ids.len(),
+        if options.bursts {
+            ", control bursts"
+        } else {
+            ""
+        }
+    );
+    if zone_ids.is_empty() && (options.knobs > 0 || options.bursts) {
+        println!("No zones found; knobs and control bursts are skipped");
+    }
+
+    let cancel = CancellationToken::new();
+    let mut workers = Vec::new();
+    for _ in 0..options.sse_clients {
+        workers.push(tokio::spawn(sse_client(sse_target.clone(), cancel.clone())));
+    }
+    if !zone_ids.is_empty() {
+        for knob in 0..options.knobs {
+            let zone_id = zone_ids[knob % zone_ids.len()].clone();
+            workers.push(tokio::spawn(knob_poller(
+                target.clone(),
+                zone_id,
+                cancel.clone(),
+            )));
+        }
+        if options.bursts {
+            workers.push(tokio::spawn(control_bursts(
+                target.clone(),
+                zone_ids,
+                cancel.clone(),
+            )));
+        }
+    }
+
+    let started = Instant::now();
+    tokio::select! {
+        _ = tokio::time::sleep(options.duration) => {}
+        _ = tokio::signal::ctrl_c() => println!("Interrupted, reporting early"),
+    }
+    cancel.cancel();
+    futures::future::join_all(workers).await;
+
+    let summaries = target.recorder.summaries();
+    print_report(
+        &summaries,
+        target.recorder.sse_events.load(Ordering::Relaxed),
+        started.elapsed(),
+    );
+    if let Some(max) = options.max_p99 {
+        let slow = over_budget(&summaries, max);
+        if !slow.is_empty() {
+            anyhow::bail!("p99 over {}ms for: {}", max.as_millis(), slow.join(", "));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn options_from_args() {
+        let defaults = SoakOptions::from_args(&args("uhc --soak"), 8088).unwrap();
+        assert_eq!(defaults.url, "http://127.0.0.1:8088");
+        assert_eq!(defaults.duration, Duration::from_secs(60));
+        assert!(!defaults.bursts);
+        assert_eq!(defaults.max_p99, None);
+
+        let options = SoakOptions::from_args(
+            &args("uhc --soak --url http://nas:8088/ --clients 5 --bursts --max-p99-ms 250"),
+            8088,
+        )
+        .unwrap();
+        assert_eq!(options.url, "http://nas:8088");
+        assert_eq!(options.sse_clients, 5);
+        assert!(options.bursts);
+        assert_eq!(options.max_p99, Some(Duration::from_millis(250)));
+
+        assert!(SoakOptions::from_args(&args("uhc --soak --knobs many"), 8088).is_err());
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let sorted: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&sorted[..1], 99.0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn summaries_flag_slow_kinds() {
+        let recorder = Recorder::default();
+        recorder.record("zones", Some(Duration::from_millis(5)));
+        recorder.record("knob_image", Some(Duration::from_millis(400)));
+        recorder.record("knob_image", None);
+
+        let summaries = recorder.summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].kind, "knob_image");
+        assert_eq!((summaries[0].count, summaries[0].errors), (1, 1));
+        assert_eq!(
+            over_budget(&summaries, Duration::from_millis(100)),
+            vec!["knob_image"]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Startup report (`GET /api/startup-report`)
+//!
+//! Boot steps record what they did as they run: config files migrated, how
+//! the web UI is served, which listeners bound, and how each adapter's first
+//! start went. The report answers "it started but nothing works" without
+//! digging through logs: a failed bind, an adapter that was never enabled and
+//! one that failed to connect look the same from the UI but not here.
+
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use serde::Serialize;
+
+use crate::clock::unix_millis;
+
+/// A config file moved or rewritten at startup
+#[derive(Debug, Clone, Serialize)]
+pub struct Migration {
+    pub from: String,
+    pub to: String,
+}
+
+/// A listening socket the bridge tried to open
+#[derive(Debug, Clone, Serialize)]
+pub struct Listener {
+    /// `http`, `remote` or `grpc`
+    pub name: String,
+    pub addr: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How an adapter's first start went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterOutcome {
+    Started,
+    Failed,
+    Disabled,
+    /// Enabled but missing settings it needs (e.g. no LMS host)
+    NotConfigured,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdapterStart {
+    pub name: String,
+    pub outcome: AdapterOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+    pub version: String,
+    pub git_sha: String,
+    /// Process start (milliseconds since epoch)
+    pub started_at: u64,
+    /// `embedded` (single binary), `embedded_spa` (embedded without bootstrap
+    /// scripts) or `dev` (no embedded assets; UI served by `dx serve`)
+    pub assets_mode: String,
+    pub embedded_files: usize,
+    pub migrations: Vec<Migration>,
+    pub listeners: Vec<Listener>,
+    /// False until every enabled adapter has been started once
+    pub adapters_done: bool,
+    pub adapters: Vec<AdapterStart>,
+    /// Optional features left off, with why (bad config, missing build feature)
+    pub warnings: Vec<String>,
+}
+
+static REPORT: LazyLock<Mutex<StartupReport>> = LazyLock::new(|| {
+    Mutex::new(StartupReport {
+        version: env!("UHC_VERSION").to_string(),
+        git_sha: env!("UHC_GIT_SHA").to_string(),
+        started_at: unix_millis(),
+        ..Default::default()
+    })
+});
+
+fn update(f: impl FnOnce(&mut StartupReport)) {
+    f(&mut REPORT.lock().unwrap_or_else(PoisonError::into_inner));
+}
+
+/// Mark the process start; call first thing so `started_at` is accurate
+pub fn begin() {
+    LazyLock::force(&REPORT);
+}
+
+pub fn record_assets(mode: &str, embedded_files: usize) {
+    update(|r| {
+        r.assets_mode = mode.to_string();
+        r.embedded_files = embedded_files;
+    });
+}
+
+pub fn record_migration(from: impl Into<String>, to: impl Into<String>) {
+    let migration = Migration {
+        from: from.into(),
+        to: to.into(),
+    };
+    update(|r| r.migrations.push(migration));
+}
+
+pub fn record_listener(name: &str, addr: impl ToString, error: Option<String>) {
+    let listener = Listener {
+        name: name.to_string(),
+        addr: addr.to_string(),
+        ok: error.is_none(),
+        error,
+    };
+    update(|r| r.listeners.push(listener));
+}
+
+pub fn record_adapter(name: &str, outcome: AdapterOutcome, error: Option<String>) {
+    let start = AdapterStart {
+        name: name.to_string(),
+        outcome,
+        error,
+    };
+    update(|r| r.adapters.push(start));
+}
+
+/// All adapters have had their first start
+pub fn finish_adapters() {
+    update(|r| {
+        r.adapters.sort_by(|a, b| a.name.cmp(&b.name));
+        r.adapters_done = true;
+    });
+}
+
+pub fn record_warning(warning: impl Into<String>) {
+    let warning = warning.into();
+    update(|r| r.warnings.push(warning));
+}
+
+/// The report so far
+pub fn snapshot() -> StartupReport {
+    REPORT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_show_in_snapshot() {
+        record_adapter("zz-test", AdapterOutcome::Failed, Some("refused".into()));
+        record_migration("roon-config.json", "roon_state.json");
+        let report = snapshot();
+        let adapter = report
+            .adapters
+            .iter()
+            .find(|a| a.name == "zz-test")
+            .unwrap();
+        assert_eq!(adapter.outcome, AdapterOutcome::Failed);
+        assert!(report.migrations.iter().any(|m| m.to == "roon_state.json"));
+        assert!(report.started_at > 0);
+
+        let json = serde_json::to_value(adapter).unwrap();
+        assert_eq!(json["outcome"], "failed");
+    }
+}
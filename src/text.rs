@@ -0,0 +1,150 @@
+//! Now-playing text for constrained consumers
+//!
+//! Knob displays only carry a Latin font, and some MQTT consumers (LED
+//! matrices, old Home Assistant cards) cope badly with long or non-ASCII
+//! strings. Each consumer gets its own [`TextNormalization`] from `[text]`:
+//! transliteration to ASCII, accent stripping and a length limit. The main
+//! API, SSE and the web UI always get the raw strings.
+
+use std::borrow::Cow;
+
+use crate::config::{TextConfig, TextNormalization};
+
+/// Normalizers for each constrained consumer
+#[derive(Debug, Clone, Default)]
+pub struct TextService {
+    /// `/knob/now_playing` lines
+    pub knob: TextNormalizer,
+    /// MQTT title, artist and album state topics
+    pub mqtt: TextNormalizer,
+}
+
+impl TextService {
+    pub fn new(config: &TextConfig) -> Self {
+        Self {
+            knob: TextNormalizer::new(config.knob.clone()),
+            mqtt: TextNormalizer::new(config.mqtt.clone()),
+        }
+    }
+}
+
+/// Applies one consumer's [`TextNormalization`]
+#[derive(Debug, Clone, Default)]
+pub struct TextNormalizer {
+    rules: TextNormalization,
+}
+
+impl TextNormalizer {
+    pub fn new(rules: TextNormalization) -> Self {
+        Self { rules }
+    }
+
+    /// The text as this consumer should get it (borrowed when unchanged)
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = if self.rules.transliterate {
+            Cow::Owned(deunicode::deunicode_with_tofu(text, "?").trim().to_string())
+        } else if self.rules.strip_diacritics && !text.is_ascii() {
+            Cow::Owned(strip_diacritics(text))
+        } else {
+            Cow::Borrowed(text)
+        };
+        if let Some(max) = self.rules.max_length {
+            if text.chars().count() > max {
+                let ellipsis = if self.rules.transliterate {
+                    "..."
+                } else {
+                    "…"
+                };
+                text = Cow::Owned(truncate(&text, max, ellipsis));
+            }
+        }
+        text
+    }
+}
+
+/// Latin letters with accents, in precomposed or decomposed form
+fn is_accented_latin(c: char) -> bool {
+    matches!(c, '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}')
+}
+
+/// Combining accents left after a decomposed letter
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+/// Replace accented Latin letters with their plain spelling (`ø` to `o`,
+/// `ß` to `ss`); other scripts are kept
+fn strip_diacritics(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if is_combining_mark(c) {
+            continue;
+        }
+        match deunicode::deunicode_char(c).filter(|_| is_accented_latin(c)) {
+            Some(plain) => out.push_str(plain),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// The first `max` characters, ending in `ellipsis` when there is room for it
+fn truncate(text: &str, max: usize, ellipsis: &str) -> String {
+    let ellipsis_len = ellipsis.chars().count();
+    if max <= ellipsis_len {
+        return text.chars().take(max).collect();
+    }
+    let kept: String = text.chars().take(max - ellipsis_len).collect();
+    format!("{}{}", kept.trim_end(), ellipsis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalizer(
+        transliterate: bool,
+        strip_diacritics: bool,
+        max: Option<usize>,
+    ) -> TextNormalizer {
+        TextNormalizer::new(TextNormalization {
+            transliterate,
+            strip_diacritics,
+            max_length: max,
+        })
+    }
+
+    #[test]
+    fn default_passes_text_through() {
+        let text = normalizer(false, false, None);
+        assert!(matches!(text.apply("Dvořák – Русалка"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn transliterates_other_scripts() {
+        let text = normalizer(true, false, None);
+        assert_eq!(text.apply("Кино"), "Kino");
+        assert_eq!(text.apply("Sigur Rós"), "Sigur Ros");
+        assert!(text.apply("坂本龍一").is_ascii());
+    }
+
+    #[test]
+    fn strip_diacritics_keeps_other_scripts() {
+        let text = normalizer(false, true, None);
+        assert_eq!(text.apply("Dvořák: Rusalka"), "Dvorak: Rusalka");
+        assert_eq!(text.apply("Mötley Crüe"), "Motley Crue");
+        // Decomposed e + combining acute
+        assert_eq!(text.apply("Beyonce\u{0301}"), "Beyonce");
+        assert_eq!(text.apply("Кино"), "Кино");
+    }
+
+    #[test]
+    fn max_length_counts_characters() {
+        let text = normalizer(false, false, Some(10));
+        assert_eq!(text.apply("Short"), "Short");
+        assert_eq!(text.apply("Симфония № 5 до минор"), "Симфония…");
+        let ascii = normalizer(true, false, Some(10));
+        assert_eq!(ascii.apply("Symphony No. 5"), "Symphon...");
+        assert_eq!(normalizer(false, false, Some(1)).apply("Abc"), "A");
+    }
+}
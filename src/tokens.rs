@@ -0,0 +1,696 @@
+//! Named API tokens
+//!
+//! Tokens are created from Settings or `/api/tokens` with a scope and saved to
+//! `tokens.json` as SHA-256 hashes; the plaintext is only returned once, at
+//! creation. The remote access listener accepts them alongside its own token,
+//! limited to what their scope allows.
+//!
+//! Knob device tokens are `knob`-scoped tokens bound to one knob. They are
+//! issued when a knob registers and handed to it in its config response until
+//! it first uses them; rotating issues a new one the same way, and the old
+//! token stays valid until the knob switches over.
+//!
+//! Pairing codes let a new client get a token without copying one around: an
+//! admin creates a short-lived six-digit code with a scope, and the client
+//! exchanges it once at `POST /api/pair` for a token with that scope.
+//!
+//! Managing tokens needs an admin credential: an admin-scoped token or the
+//! remote access token. Until either exists, token management is open so the
+//! first admin token can be created.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use anyhow::Result;
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Notify;
+
+use crate::clock::unix_secs;
+use crate::config::{get_config_file_path, read_config_file};
+use crate::remote::tokens_match;
+
+const TOKENS_FILE: &str = "tokens.json";
+
+/// Prefix of every issued token, so they're recognizable in configs and logs
+const TOKEN_PREFIX: &str = "uhc_";
+
+/// Bytes of randomness in a generated token (hex encoded)
+const TOKEN_BYTES: usize = 32;
+
+/// How long a pairing code can be redeemed
+pub const PAIRING_CODE_TTL_SECS: u64 = 300;
+
+/// Wrong pairing codes tolerated before all outstanding codes are invalidated
+const PAIRING_MAX_FAILURES: u32 = 10;
+
+/// What a token may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// GET requests, except token management, backups and `/simple` controls
+    ReadOnly,
+    /// Read-only plus playback control; no configuration changes
+    Control,
+    /// Everything
+    Admin,
+    /// The knob protocol routes (`/knob/*` and the legacy `/now_playing`, `/control`, `/zones`)
+    Knob,
+}
+
+impl TokenScope {
+    /// Whether a request with this scope may call `method path`
+    pub fn allows(self, method: &Method, path: &str) -> bool {
+        let read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        match self {
+            Self::Admin => true,
+            Self::Knob => {
+                path == "/knob"
+                    || path.starts_with("/knob/")
+                    || path == "/now_playing"
+                    || path.starts_with("/now_playing/")
+                    || path == "/control"
+                    || path == "/zones"
+                    || path.starts_with("/config/")
+                    || path.starts_with("/firmware/")
+            }
+            Self::ReadOnly => read && !is_sensitive(path) && !path.starts_with("/simple/"),
+            Self::Control => !is_sensitive(path) && (read || !is_admin_write(path)),
+        }
+    }
+}
+
+/// Paths that expose secrets (or probe the host's files) even on GET
+fn is_sensitive(path: &str) -> bool {
+    path.starts_with("/api/tokens")
+        || path.starts_with("/api/backups")
+        || path.starts_with("/api/hooks")
+        || path.starts_with("/api/migrate")
+}
+
+/// Writes that change configuration rather than playback
+fn is_admin_write(path: &str) -> bool {
+    (path.starts_with("/api/") && path != "/api/selection")
+        || path.starts_with("/debug/")
+        || path.starts_with("/hqp/instances")
+        || path.starts_with("/hqp/zones/")
+        || path == "/hqp/detect"
+        || path == "/knob/config"
+        || path == "/roon/core/power/wol"
+        || path.ends_with("/configure")
+        || path.ends_with("/metadata")
+}
+
+/// A token as listed by the API (no secret or hash)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    /// Unix seconds
+    pub created_at: u64,
+    /// Unix seconds of the last accepted request
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// Knob this device token belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub knob_id: Option<String>,
+}
+
+impl ApiToken {
+    /// Whether this token may act as the given knob (any knob if `None`)
+    pub fn allows_knob(&self, knob_id: Option<&str>) -> bool {
+        match self.scope {
+            TokenScope::Admin => true,
+            TokenScope::Knob => match (self.knob_id.as_deref(), knob_id) {
+                (Some(bound), Some(requested)) => bound == requested,
+                _ => true,
+            },
+            TokenScope::ReadOnly | TokenScope::Control => false,
+        }
+    }
+}
+
+/// On-disk form: the token plus the SHA-256 of its secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredToken {
+    #[serde(flatten)]
+    token: ApiToken,
+    hash: String,
+    /// Knob device token not yet picked up by the knob
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pending_secret: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokensFile {
+    tokens: Vec<StoredToken>,
+    #[serde(default)]
+    require_knob_tokens: bool,
+}
+
+/// Device token state for one knob (Knobs page)
+#[derive(Debug, Clone, Serialize)]
+pub struct KnobTokenStatus {
+    /// Unix seconds the current token was issued
+    pub issued_at: u64,
+    pub last_used_at: Option<u64>,
+    /// A new token is waiting for the knob to pick it up
+    pub pending: bool,
+}
+
+/// Whether a presented credential looks like a named token (as opposed to
+/// the remote access token)
+pub fn is_token(presented: &str) -> bool {
+    presented.starts_with(TOKEN_PREFIX)
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// A short-lived code exchanged once for a token
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingCode {
+    pub code: String,
+    pub scope: TokenScope,
+    /// Name for the token; the client may supply its own instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Unix seconds
+    pub expires_at: u64,
+}
+
+/// Outstanding pairing codes (memory only)
+#[derive(Debug, Default)]
+struct Pairing {
+    codes: Vec<PairingCode>,
+    failures: u32,
+}
+
+/// Named tokens persisted in `tokens.json`
+#[derive(Debug, Default)]
+pub struct TokenStore {
+    tokens: Mutex<Vec<StoredToken>>,
+    pairing: Mutex<Pairing>,
+    /// Knob protocol requests must carry a device token
+    require_knob_tokens: AtomicBool,
+    /// Signalled when `require_knob_tokens` changes
+    knob_auth_changed: Notify,
+    /// Save changes to disk (off for in-memory test stores)
+    persist: bool,
+    /// The remote access token, when remote access is on
+    remote_token: OnceLock<String>,
+}
+
+impl TokenStore {
+    /// Load saved tokens (empty if the file is missing or unreadable)
+    pub fn load() -> Self {
+        let file = read_config_file(TOKENS_FILE)
+            .and_then(
+                |content| match serde_json::from_str::<TokensFile>(&content) {
+                    Ok(file) => Some(file),
+                    Err(e) => {
+                        tracing::warn!("Ignoring invalid {}: {}", TOKENS_FILE, e);
+                        None
+                    }
+                },
+            )
+            .unwrap_or_default();
+        Self {
+            tokens: Mutex::new(file.tokens),
+            pairing: Mutex::default(),
+            require_knob_tokens: AtomicBool::new(file.require_knob_tokens),
+            knob_auth_changed: Notify::new(),
+            persist: true,
+            remote_token: OnceLock::new(),
+        }
+    }
+
+    /// All tokens, oldest first
+    pub fn list(&self) -> Vec<ApiToken> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|t| t.token.clone())
+            .collect()
+    }
+
+    /// Create a token, returning its entry and the plaintext (shown once)
+    pub fn create(&self, name: &str, scope: TokenScope) -> Result<(ApiToken, String)> {
+        self.insert(name, scope, None)
+    }
+
+    fn insert(
+        &self,
+        name: &str,
+        scope: TokenScope,
+        knob_id: Option<&str>,
+    ) -> Result<(ApiToken, String)> {
+        let secret = format!(
+            "{}{}",
+            TOKEN_PREFIX,
+            hex::encode(rand::random::<[u8; TOKEN_BYTES]>())
+        );
+        let token = ApiToken {
+            id: hex::encode(rand::random::<[u8; 8]>()),
+            name: name.trim().to_string(),
+            scope,
+            created_at: unix_secs(),
+            last_used_at: None,
+            knob_id: knob_id.map(String::from),
+        };
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        tokens.push(StoredToken {
+            token: token.clone(),
+            hash: hash_token(&secret),
+            pending_secret: None,
+        });
+        self.save(&tokens)?;
+        Ok((token, secret))
+    }
+
+    /// Rename a token or change its scope; `None` if there is no such token
+    pub fn update(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        scope: Option<TokenScope>,
+    ) -> Result<Option<ApiToken>> {
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(StoredToken { token, .. }) = tokens.iter_mut().find(|t| t.token.id == id) else {
+            return Ok(None);
+        };
+        if let Some(name) = name {
+            token.name = name.trim().to_string();
+        }
+        if let Some(scope) = scope {
+            token.scope = scope;
+        }
+        let updated = token.clone();
+        self.save(&tokens)?;
+        Ok(Some(updated))
+    }
+
+    /// Revoke a token; `false` if there is no such token
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        let before = tokens.len();
+        tokens.retain(|t| t.token.id != id);
+        if tokens.len() == before {
+            return Ok(false);
+        }
+        self.save(&tokens)?;
+        Ok(true)
+    }
+
+    /// Scope of a presented token, recording its use
+    pub fn authenticate(&self, presented: &str) -> Option<TokenScope> {
+        self.authenticate_token(presented).map(|t| t.scope)
+    }
+
+    /// The token matching a presented secret, recording its use. A knob's
+    /// first use of a pending device token completes its rotation.
+    pub fn authenticate_token(&self, presented: &str) -> Option<ApiToken> {
+        if !is_token(presented) {
+            return None;
+        }
+        let hash = hash_token(presented);
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        let stored = tokens.iter_mut().find(|t| t.hash == hash)?;
+        stored.token.last_used_at = Some(unix_secs());
+        let token = stored.token.clone();
+
+        if stored.pending_secret.take().is_some() {
+            if let Some(knob_id) = token.knob_id.as_deref() {
+                tokens.retain(|t| {
+                    t.token.id == token.id || t.token.knob_id.as_deref() != Some(knob_id)
+                });
+                tracing::info!("Knob {} switched to its new device token", knob_id);
+            }
+            if let Err(e) = self.save(&tokens) {
+                tracing::warn!("Failed to save {}: {}", TOKENS_FILE, e);
+            }
+        }
+        Some(token)
+    }
+
+    /// Record the remote access token, which is also an admin credential
+    pub fn set_remote_token(&self, token: &str) {
+        let _ = self.remote_token.set(token.to_string());
+    }
+
+    /// Whether a presented secret is the remote access token or an
+    /// admin-scoped token
+    pub fn is_admin(&self, presented: &str) -> bool {
+        if self
+            .remote_token
+            .get()
+            .is_some_and(|token| tokens_match(presented, token))
+        {
+            return true;
+        }
+        self.authenticate(presented) == Some(TokenScope::Admin)
+    }
+
+    /// Whether there is any admin credential yet
+    pub fn has_admin_credential(&self) -> bool {
+        self.remote_token.get().is_some()
+            || self
+                .tokens
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .iter()
+                .any(|t| t.token.scope == TokenScope::Admin)
+    }
+
+    /// Create a pairing code for a token with `scope`, valid for
+    /// [`PAIRING_CODE_TTL_SECS`]
+    pub fn create_pairing_code(&self, scope: TokenScope, name: Option<&str>) -> PairingCode {
+        let now = unix_secs();
+        let mut pairing = self.pairing.lock().unwrap_or_else(PoisonError::into_inner);
+        pairing.codes.retain(|c| c.expires_at > now);
+        let code = loop {
+            let candidate = format!("{:06}", rand::random::<u32>() % 1_000_000);
+            if !pairing.codes.iter().any(|c| c.code == candidate) {
+                break candidate;
+            }
+        };
+        let pairing_code = PairingCode {
+            code,
+            scope,
+            name: name
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map(String::from),
+            expires_at: now + PAIRING_CODE_TTL_SECS,
+        };
+        pairing.codes.push(pairing_code.clone());
+        pairing.failures = 0;
+        pairing_code
+    }
+
+    /// Exchange a pairing code for a new token; `None` if the code is wrong,
+    /// expired or already used. A knob-scoped token is bound to `knob_id`.
+    pub fn redeem_pairing_code(
+        &self,
+        code: &str,
+        name: Option<&str>,
+        knob_id: Option<&str>,
+    ) -> Result<Option<(ApiToken, String)>> {
+        let now = unix_secs();
+        let pairing_code = {
+            let mut pairing = self.pairing.lock().unwrap_or_else(PoisonError::into_inner);
+            pairing.codes.retain(|c| c.expires_at > now);
+            match pairing.codes.iter().position(|c| c.code == code.trim()) {
+                Some(index) => pairing.codes.remove(index),
+                None => {
+                    pairing.failures += 1;
+                    if pairing.failures >= PAIRING_MAX_FAILURES && !pairing.codes.is_empty() {
+                        tracing::warn!(
+                            "Too many wrong pairing codes, invalidating outstanding codes"
+                        );
+                        pairing.codes.clear();
+                    }
+                    return Ok(None);
+                }
+            }
+        };
+
+        let name = name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(String::from)
+            .or(pairing_code.name)
+            .unwrap_or_else(|| match knob_id {
+                Some(id) => format!("Knob {}", id),
+                None => "Paired device".to_string(),
+            });
+        let knob_id = knob_id.filter(|_| pairing_code.scope == TokenScope::Knob);
+        let created = self.insert(&name, pairing_code.scope, knob_id)?;
+        tracing::info!("Paired {} with scope {:?}", name, pairing_code.scope);
+        Ok(Some(created))
+    }
+
+    /// Whether knob protocol requests must carry a device token
+    pub fn knob_tokens_required(&self) -> bool {
+        self.require_knob_tokens.load(Ordering::Relaxed)
+    }
+
+    pub fn set_knob_tokens_required(&self, required: bool) -> Result<()> {
+        if self.require_knob_tokens.swap(required, Ordering::Relaxed) != required {
+            self.knob_auth_changed.notify_one();
+        }
+        let tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        self.save(&tokens)
+    }
+
+    /// Wait until device tokens are switched on or off (e.g. to update the
+    /// `auth` mDNS record)
+    pub async fn knob_auth_changed(&self) {
+        self.knob_auth_changed.notified().await
+    }
+
+    /// Issue a device token for a knob that has none (registration)
+    pub fn ensure_knob_token(&self, knob_id: &str) -> Result<()> {
+        let has_token = self
+            .tokens
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .any(|t| t.token.knob_id.as_deref() == Some(knob_id));
+        if has_token {
+            return Ok(());
+        }
+        self.issue_knob_token(knob_id)
+    }
+
+    /// Issue a new device token for a knob, replacing any not yet picked up.
+    /// The knob's current token stays valid until it uses the new one.
+    pub fn issue_knob_token(&self, knob_id: &str) -> Result<()> {
+        let secret = format!(
+            "{}{}",
+            TOKEN_PREFIX,
+            hex::encode(rand::random::<[u8; TOKEN_BYTES]>())
+        );
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        tokens
+            .retain(|t| t.pending_secret.is_none() || t.token.knob_id.as_deref() != Some(knob_id));
+        tokens.push(StoredToken {
+            token: ApiToken {
+                id: hex::encode(rand::random::<[u8; 8]>()),
+                name: format!("Knob {}", knob_id),
+                scope: TokenScope::Knob,
+                created_at: unix_secs(),
+                last_used_at: None,
+                knob_id: Some(knob_id.to_string()),
+            },
+            hash: hash_token(&secret),
+            pending_secret: Some(secret),
+        });
+        self.save(&tokens)?;
+        tracing::info!("Issued device token for knob {}", knob_id);
+        Ok(())
+    }
+
+    /// Device token waiting to be handed to a knob
+    pub fn pending_knob_token(&self, knob_id: &str) -> Option<String> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|t| t.token.knob_id.as_deref() == Some(knob_id))
+            .find_map(|t| t.pending_secret.clone())
+    }
+
+    /// Current device token state for a knob
+    pub fn knob_token_status(&self, knob_id: &str) -> Option<KnobTokenStatus> {
+        let tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        let knob_tokens: Vec<&StoredToken> = tokens
+            .iter()
+            .filter(|t| t.token.knob_id.as_deref() == Some(knob_id))
+            .collect();
+        let current = knob_tokens
+            .iter()
+            .find(|t| t.pending_secret.is_none())
+            .or_else(|| knob_tokens.first())?;
+        Some(KnobTokenStatus {
+            issued_at: current.token.created_at,
+            last_used_at: current.token.last_used_at,
+            pending: knob_tokens.iter().any(|t| t.pending_secret.is_some()),
+        })
+    }
+
+    /// Revoke every device token of a knob; `false` if it had none
+    pub fn revoke_knob_tokens(&self, knob_id: &str) -> Result<bool> {
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        let before = tokens.len();
+        tokens.retain(|t| t.token.knob_id.as_deref() != Some(knob_id));
+        if tokens.len() == before {
+            return Ok(false);
+        }
+        self.save(&tokens)?;
+        tracing::info!("Revoked device tokens for knob {}", knob_id);
+        Ok(true)
+    }
+
+    fn save(&self, tokens: &[StoredToken]) -> Result<()> {
+        if !self.persist {
+            return Ok(());
+        }
+        let path = get_config_file_path(TOKENS_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = TokensFile {
+            tokens: tokens.to_vec(),
+            require_knob_tokens: self.knob_tokens_required(),
+        };
+        std::fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_authenticate_revoke() {
+        let store = TokenStore::default();
+        let (token, secret) = store.create(" Kitchen iPad ", TokenScope::Control).unwrap();
+        assert_eq!(token.name, "Kitchen iPad");
+        assert!(secret.starts_with(TOKEN_PREFIX));
+
+        assert_eq!(store.authenticate(&secret), Some(TokenScope::Control));
+        assert!(store.list()[0].last_used_at.is_some());
+        assert_eq!(store.authenticate("uhc_wrong"), None);
+
+        assert!(store.delete(&token.id).unwrap());
+        assert!(!store.delete(&token.id).unwrap());
+        assert_eq!(store.authenticate(&secret), None);
+    }
+
+    #[test]
+    fn test_admin_credentials() {
+        let store = TokenStore::default();
+        assert!(!store.has_admin_credential());
+        let (_, control) = store.create("app", TokenScope::Control).unwrap();
+        assert!(!store.has_admin_credential());
+        assert!(!store.is_admin(&control));
+
+        let (_, admin) = store.create("owner", TokenScope::Admin).unwrap();
+        assert!(store.has_admin_credential());
+        assert!(store.is_admin(&admin));
+
+        store.set_remote_token("remote-secret");
+        assert!(store.is_admin("remote-secret"));
+        assert!(!store.is_admin("remote-secreT"));
+    }
+
+    #[test]
+    fn test_list_never_serializes_hash() {
+        let store = TokenStore::default();
+        store.create("knob", TokenScope::Knob).unwrap();
+        let json = serde_json::to_string(&store.list()).unwrap();
+        assert!(!json.contains("hash"));
+        assert!(json.contains("\"scope\":\"knob\""));
+    }
+
+    #[test]
+    fn test_pairing_code_single_use() {
+        let store = TokenStore::default();
+        let pairing = store.create_pairing_code(TokenScope::Knob, None);
+        assert_eq!(pairing.code.len(), 6);
+
+        let (token, secret) = store
+            .redeem_pairing_code(&pairing.code, None, Some("knob-1"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.name, "Knob knob-1");
+        assert_eq!(token.knob_id.as_deref(), Some("knob-1"));
+        assert_eq!(store.authenticate(&secret), Some(TokenScope::Knob));
+
+        assert!(store
+            .redeem_pairing_code(&pairing.code, None, None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_pairing_failures_invalidate_codes() {
+        let store = TokenStore::default();
+        let pairing = store.create_pairing_code(TokenScope::Control, Some("iPad"));
+        let wrong = if pairing.code == "000000" {
+            "000001"
+        } else {
+            "000000"
+        };
+        for _ in 0..PAIRING_MAX_FAILURES {
+            assert!(store
+                .redeem_pairing_code(wrong, None, None)
+                .unwrap()
+                .is_none());
+        }
+        assert!(store
+            .redeem_pairing_code(&pairing.code, None, None)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_knob_token_rotation() {
+        let store = TokenStore::default();
+        store.ensure_knob_token("knob-1").unwrap();
+        let first = store.pending_knob_token("knob-1").unwrap();
+        let token = store.authenticate_token(&first).unwrap();
+        assert!(token.allows_knob(Some("knob-1")));
+        assert!(!token.allows_knob(Some("knob-2")));
+        assert!(store.pending_knob_token("knob-1").is_none());
+
+        // Registration doesn't reissue; rotation keeps the old token until the new one is used
+        store.ensure_knob_token("knob-1").unwrap();
+        assert!(store.pending_knob_token("knob-1").is_none());
+        store.issue_knob_token("knob-1").unwrap();
+        let second = store.pending_knob_token("knob-1").unwrap();
+        assert!(store.knob_token_status("knob-1").unwrap().pending);
+        assert!(store.authenticate_token(&first).is_some());
+        assert!(store.authenticate_token(&second).is_some());
+        assert!(store.authenticate_token(&first).is_none());
+        assert!(!store.knob_token_status("knob-1").unwrap().pending);
+
+        assert!(store.revoke_knob_tokens("knob-1").unwrap());
+        assert!(store.authenticate_token(&second).is_none());
+        assert!(store.knob_token_status("knob-1").is_none());
+    }
+
+    #[test]
+    fn test_scopes() {
+        use TokenScope::*;
+        assert!(ReadOnly.allows(&Method::GET, "/zones"));
+        assert!(!ReadOnly.allows(&Method::POST, "/roon/control"));
+        assert!(!ReadOnly.allows(&Method::GET, "/api/tokens"));
+        assert!(!ReadOnly.allows(&Method::GET, "/api/hooks"));
+        assert!(!Control.allows(&Method::GET, "/api/migrate/nodejs"));
+        assert!(!ReadOnly.allows(&Method::GET, "/simple/pause"));
+        assert!(Control.allows(&Method::GET, "/simple/pause"));
+
+        assert!(Control.allows(&Method::POST, "/roon/control"));
+        assert!(Control.allows(&Method::POST, "/knob/control"));
+        assert!(!Control.allows(&Method::POST, "/api/settings"));
+        assert!(Control.allows(&Method::PUT, "/api/selection"));
+        assert!(!Control.allows(&Method::PUT, "/zones/roon:1/metadata"));
+        assert!(!Control.allows(&Method::POST, "/lms/configure"));
+        assert!(Control.allows(&Method::POST, "/roon/core/power"));
+        assert!(!Control.allows(&Method::PUT, "/roon/core/power/wol"));
+        assert!(!Control.allows(&Method::GET, "/api/backups"));
+
+        assert!(Knob.allows(&Method::GET, "/knob/now_playing"));
+        assert!(Knob.allows(&Method::POST, "/control"));
+        assert!(!Knob.allows(&Method::GET, "/api/settings"));
+
+        assert!(Admin.allows(&Method::DELETE, "/api/tokens/abc"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Outbound webhooks
+//!
+//! Bus events are POSTed as JSON to each configured URL. Every delivery is
+//! signed so receivers on the LAN or the internet can check it came from the
+//! bridge and reject replays:
+//!
+//! - `X-UHC-Timestamp`: Unix seconds when the delivery was signed
+//! - `X-UHC-Delivery`: random id, unique per delivery
+//! - `X-UHC-Signature`: `sha256=<hex>`, the HMAC-SHA256 of
+//!   `"{timestamp}.{body}"` keyed with the shared secret
+//!
+//! Receivers recompute the HMAC over the raw body, compare in constant time,
+//! reject timestamps more than [`REPLAY_TOLERANCE_SECS`] from their clock and
+//! drop delivery ids they have already seen within that window.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::bus::{BusEvent, SharedBus};
+use crate::clock::unix_secs;
+use crate::config::{get_config_file_path, read_config_file, WebhooksConfig};
+
+const WEBHOOKS_FILE: &str = "webhooks.json";
+
+pub const SIGNATURE_HEADER: &str = "X-UHC-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-UHC-Timestamp";
+pub const DELIVERY_HEADER: &str = "X-UHC-Delivery";
+pub const EVENT_HEADER: &str = "X-UHC-Event";
+
+/// Largest clock difference a receiver should accept
+pub const REPLAY_TOLERANCE_SECS: u64 = 300;
+
+/// Bytes of randomness in a generated secret (hex encoded)
+const SECRET_BYTES: usize = 32;
+
+/// Per-request timeout; a slow receiver delays later deliveries by at most this
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delivered when no `events` filter is configured: everything except
+/// per-second seek updates and internal health checks
+const DEFAULT_EXCLUDED_EVENTS: &[&str] = &["seek_position_changed", "health_check"];
+
+/// Persisted webhook state
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WebhooksFile {
+    secret: Option<String>,
+}
+
+/// Signed request body
+#[derive(Debug, Serialize)]
+struct Delivery<'a> {
+    delivery: &'a str,
+    timestamp: u64,
+    event: &'a BusEvent,
+}
+
+/// The configured secret, or the saved one, generating and saving a new secret
+/// if there is neither
+pub fn load_or_create_secret(configured: Option<String>) -> Result<String> {
+    if let Some(secret) = configured.filter(|s| !s.trim().is_empty()) {
+        return Ok(secret.trim().to_string());
+    }
+
+    let saved = read_config_file(WEBHOOKS_FILE)
+        .and_then(|content| serde_json::from_str::<WebhooksFile>(&content).ok())
+        .and_then(|file| file.secret)
+        .filter(|s| !s.is_empty());
+    if let Some(secret) = saved {
+        return Ok(secret);
+    }
+
+    let secret = hex::encode(rand::random::<[u8; SECRET_BYTES]>());
+    let path = get_config_file_path(WEBHOOKS_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = WebhooksFile {
+        secret: Some(secret.clone()),
+    };
+    std::fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+    tracing::info!("Generated webhook signing secret in {}", path.display());
+    Ok(secret)
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// `X-UHC-Signature` value for a body signed at `timestamp`
+pub fn sign(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut message = format!("{}.", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    format!(
+        "sha256={}",
+        hex::encode(hmac_sha256(secret.as_bytes(), &message))
+    )
+}
+
+/// Check a delivery's signature and timestamp (receiver side, as documented)
+pub fn verify(secret: &str, signature: &str, timestamp: u64, body: &[u8], now: u64) -> bool {
+    if now.abs_diff(timestamp) > REPLAY_TOLERANCE_SECS {
+        return false;
+    }
+    let expected = sign(secret, timestamp, body);
+    let (a, b) = (signature.as_bytes(), expected.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Which event types to deliver
+#[derive(Debug, Clone)]
+enum EventFilter {
+    Default,
+    Only(HashSet<String>),
+}
+
+impl EventFilter {
+    fn parse(spec: Option<&str>) -> Self {
+        let types: HashSet<String> = spec
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect();
+        if types.is_empty() {
+            Self::Default
+        } else {
+            Self::Only(types)
+        }
+    }
+
+    fn matches(&self, event_type: &str) -> bool {
+        match self {
+            Self::Default => !DEFAULT_EXCLUDED_EVENTS.contains(&event_type),
+            Self::Only(types) => types.contains(event_type),
+        }
+    }
+}
+
+/// Start delivering bus events to the configured URLs until `shutdown`
+pub fn start(config: &WebhooksConfig, bus: SharedBus, shutdown: CancellationToken) -> Result<()> {
+    let urls: Vec<String> = config
+        .urls
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect();
+    if urls.is_empty() {
+        return Err(anyhow!("no webhook URLs configured"));
+    }
+    let secret = load_or_create_secret(config.secret.clone())?;
+    let filter = EventFilter::parse(config.events.as_deref());
+    let client = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()?;
+
+    tracing::info!("Webhooks enabled for {} URL(s)", urls.len());
+    let mut rx = bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                result = rx.recv() => match result {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Webhooks lagged, dropped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if !filter.matches(event.event_type()) {
+                continue;
+            }
+            deliver(&client, &urls, &secret, &event).await;
+        }
+    });
+    Ok(())
+}
+
+async fn deliver(client: &reqwest::Client, urls: &[String], secret: &str, event: &BusEvent) {
+    let delivery = hex::encode(rand::random::<[u8; 16]>());
+    let timestamp = unix_secs();
+    let body = match serde_json::to_vec(&Delivery {
+        delivery: &delivery,
+        timestamp,
+        event,
+    }) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::warn!("Failed to serialize webhook payload: {}", e);
+            return;
+        }
+    };
+    let signature = sign(secret, timestamp, &body);
+
+    for url in urls {
+        let result = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event.event_type())
+            .header(DELIVERY_HEADER, &delivery)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, &signature)
+            .body(body.clone())
+            .send()
+            .await;
+        match result {
+            Ok(response) if !response.status().is_success() => tracing::debug!(
+                "Webhook {} returned {} for {}",
+                url,
+                response.status(),
+                event.event_type()
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Webhook {} failed: {}", url, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_rfc4231_vector() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            hex::encode(mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let body = br#"{"delivery":"ab","timestamp":1700000000}"#;
+        let signature = sign("secret", 1_700_000_000, body);
+        assert!(signature.starts_with("sha256="));
+        assert!(verify(
+            "secret",
+            &signature,
+            1_700_000_000,
+            body,
+            1_700_000_060
+        ));
+        assert!(!verify(
+            "other",
+            &signature,
+            1_700_000_000,
+            body,
+            1_700_000_060
+        ));
+        assert!(!verify(
+            "secret",
+            &signature,
+            1_700_000_001,
+            body,
+            1_700_000_060
+        ));
+        assert!(!verify(
+            "secret",
+            &signature,
+            1_700_000_000,
+            b"{}",
+            1_700_000_060
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_stale_timestamp() {
+        let body = b"{}";
+        let signature = sign("secret", 1_700_000_000, body);
+        let late = 1_700_000_000 + REPLAY_TOLERANCE_SECS + 1;
+        assert!(!verify("secret", &signature, 1_700_000_000, body, late));
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let default = EventFilter::parse(None);
+        assert!(default.matches("volume_changed"));
+        assert!(!default.matches("seek_position_changed"));
+
+        let only = EventFilter::parse(Some("volume_changed, now_playing_changed"));
+        assert!(only.matches("now_playing_changed"));
+        assert!(!only.matches("zone_updated"));
+    }
+}
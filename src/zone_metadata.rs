@@ -0,0 +1,331 @@
+//! Zone metadata store (rooms, icons, sort order, colors, volume steps, aliases)
+//!
+//! Adapters only know what their backend reports, so room assignment and
+//! ordering are user data kept here, keyed by prefixed zone_id, and persisted to
+//! `zone-metadata.json` in the config directory. The aggregator merges entries
+//! into the zones it returns. Entries survive a zone going offline.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::bus::{Zone, ZoneMetadata};
+use crate::config::{get_config_file_path, read_config_file};
+
+const ZONE_METADATA_FILE: &str = "zone-metadata.json";
+
+/// Longest accepted room name
+const MAX_ROOM_LEN: usize = 64;
+/// Longest accepted icon name (emoji or icon identifier)
+const MAX_ICON_LEN: usize = 32;
+/// Most aliases per zone
+const MAX_ALIASES: usize = 10;
+/// Largest accepted volume step (Roon clamps relative changes to 10 as well)
+const MAX_VOLUME_STEP: f32 = 10.0;
+
+/// Per-zone metadata, optionally persisted to disk
+#[derive(Default)]
+pub struct ZoneMetadataStore {
+    entries: RwLock<HashMap<String, ZoneMetadata>>,
+    /// `None` keeps the store in memory only (tests, demo)
+    path: Option<PathBuf>,
+}
+
+impl ZoneMetadataStore {
+    /// Load the store from the config directory; changes are written back
+    pub fn load() -> Self {
+        let entries = match read_config_file(ZONE_METADATA_FILE) {
+            Some(content) => {
+                match serde_json::from_str::<HashMap<String, ZoneMetadata>>(&content) {
+                    Ok(entries) => {
+                        tracing::info!("Loaded metadata for {} zones", entries.len());
+                        entries
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to parse zone metadata: {}", e);
+                        HashMap::new()
+                    }
+                }
+            }
+            None => HashMap::new(),
+        };
+        Self {
+            entries: RwLock::new(entries),
+            path: Some(get_config_file_path(ZONE_METADATA_FILE)),
+        }
+    }
+
+    /// All entries, keyed by zone_id
+    pub fn all(&self) -> HashMap<String, ZoneMetadata> {
+        self.entries
+            .read()
+            .map(|entries| entries.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, zone_id: &str) -> Option<ZoneMetadata> {
+        self.entries
+            .read()
+            .ok()
+            .and_then(|entries| entries.get(zone_id).cloned())
+    }
+
+    /// Replace a zone's metadata (an empty value removes the entry)
+    pub fn set(&self, zone_id: &str, metadata: ZoneMetadata) {
+        if let Ok(mut entries) = self.entries.write() {
+            if metadata.is_empty() {
+                entries.remove(zone_id);
+            } else {
+                entries.insert(zone_id.to_string(), metadata);
+            }
+            self.save(&entries);
+        }
+    }
+
+    /// Remove a zone's metadata. Returns false if it had none.
+    pub fn remove(&self, zone_id: &str) -> bool {
+        let Ok(mut entries) = self.entries.write() else {
+            return false;
+        };
+        let removed = entries.remove(zone_id).is_some();
+        if removed {
+            self.save(&entries);
+        }
+        removed
+    }
+
+    /// Attach stored metadata to a zone, including its volume step override
+    pub fn apply(&self, zone: &mut Zone) {
+        zone.metadata = self.get(&zone.zone_id);
+        let step = zone.metadata.as_ref().and_then(|m| m.volume_step);
+        if let (Some(step), Some(vc)) = (step, zone.volume_control.as_mut()) {
+            vc.step = step;
+        }
+    }
+
+    fn save(&self, entries: &HashMap<String, ZoneMetadata>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(entries) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(path, json) {
+                    tracing::error!("Failed to save zone metadata: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize zone metadata: {}", e),
+        }
+    }
+}
+
+/// Trim fields, drop empty strings and check lengths and color format
+pub fn normalize(metadata: ZoneMetadata) -> Result<ZoneMetadata, String> {
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let room = trimmed(metadata.room);
+    let icon = trimmed(metadata.icon);
+    let color = trimmed(metadata.color).map(|c| c.to_ascii_lowercase());
+
+    if room
+        .as_ref()
+        .is_some_and(|r| r.chars().count() > MAX_ROOM_LEN)
+    {
+        return Err(format!("room must be at most {} characters", MAX_ROOM_LEN));
+    }
+    if icon
+        .as_ref()
+        .is_some_and(|i| i.chars().count() > MAX_ICON_LEN)
+    {
+        return Err(format!("icon must be at most {} characters", MAX_ICON_LEN));
+    }
+    if let Some(color) = &color {
+        let valid = color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            return Err(format!("color must be #rrggbb, got {}", color));
+        }
+    }
+    if let Some(step) = metadata.volume_step {
+        if !(step > 0.0 && step <= MAX_VOLUME_STEP) {
+            return Err(format!(
+                "volume_step must be above 0 and at most {}",
+                MAX_VOLUME_STEP
+            ));
+        }
+    }
+
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in metadata.aliases {
+        let alias = alias.trim().to_string();
+        if alias.chars().count() > MAX_ROOM_LEN {
+            return Err(format!(
+                "aliases must be at most {} characters",
+                MAX_ROOM_LEN
+            ));
+        }
+        if !alias.is_empty() && !aliases.iter().any(|a| a.eq_ignore_ascii_case(&alias)) {
+            aliases.push(alias);
+        }
+    }
+    if aliases.len() > MAX_ALIASES {
+        return Err(format!("at most {} aliases per zone", MAX_ALIASES));
+    }
+
+    Ok(ZoneMetadata {
+        room,
+        icon,
+        sort_weight: metadata.sort_weight,
+        color,
+        volume_step: metadata.volume_step,
+        aliases,
+        locked: metadata.locked,
+    })
+}
+
+/// Order two zones by sort weight (lower first, unset = 0), then name
+pub fn compare_zones(
+    a: (Option<&ZoneMetadata>, &str),
+    b: (Option<&ZoneMetadata>, &str),
+) -> Ordering {
+    weight(a.0).cmp(&weight(b.0)).then_with(|| a.1.cmp(b.1))
+}
+
+fn weight(metadata: Option<&ZoneMetadata>) -> i32 {
+    metadata.and_then(|m| m.sort_weight).unwrap_or(0)
+}
+
+/// Zone ordering shared by all zone lists: zones with a room come first, grouped
+/// by room (rooms ordered by their lowest zone sort weight, then name), followed
+/// by unassigned zones. Within a group, [`compare_zones`] applies.
+///
+/// `key` returns each item's metadata and zone name.
+pub fn sort_by_room<T>(items: &mut [T], key: impl Fn(&T) -> (Option<&ZoneMetadata>, &str)) {
+    let mut room_weights: HashMap<String, i32> = HashMap::new();
+    for item in items.iter() {
+        let (metadata, _) = key(item);
+        if let Some(room) = metadata.and_then(|m| m.room.as_ref()) {
+            let w = weight(metadata);
+            room_weights
+                .entry(room.clone())
+                .and_modify(|lowest| *lowest = (*lowest).min(w))
+                .or_insert(w);
+        }
+    }
+
+    // (unassigned, room weight, room name); unassigned sorts after every room
+    let room_rank = |metadata: Option<&ZoneMetadata>| match metadata.and_then(|m| m.room.clone()) {
+        Some(room) => (false, room_weights.get(&room).copied().unwrap_or(0), room),
+        None => (true, 0, String::new()),
+    };
+    items.sort_by(|a, b| {
+        let (a_key, b_key) = (key(a), key(b));
+        room_rank(a_key.0)
+            .cmp(&room_rank(b_key.0))
+            .then_with(|| compare_zones(a_key, b_key))
+    });
+}
+
+/// Sort zones in the shared order
+pub fn sort_zones(zones: &mut [Zone]) {
+    sort_by_room(zones, |z| (z.metadata.as_ref(), z.zone_name.as_str()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_trims_and_validates() {
+        let metadata = normalize(ZoneMetadata {
+            room: Some("  Living Room ".to_string()),
+            icon: Some("".to_string()),
+            sort_weight: Some(-1),
+            color: Some("#AABBCC".to_string()),
+            volume_step: Some(0.5),
+            aliases: vec![" Lounge".to_string(), "lounge".to_string(), "".to_string()],
+            locked: true,
+        })
+        .unwrap();
+        assert!(metadata.locked);
+        assert_eq!(metadata.room.as_deref(), Some("Living Room"));
+        assert_eq!(metadata.aliases, ["Lounge"]);
+        assert_eq!(metadata.icon, None);
+        assert_eq!(metadata.color.as_deref(), Some("#aabbcc"));
+
+        assert!(normalize(ZoneMetadata {
+            color: Some("red".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(normalize(ZoneMetadata {
+            volume_step: Some(0.0),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_store_set_empty_removes_entry() {
+        let store = ZoneMetadataStore::default();
+        store.set(
+            "roon:1",
+            ZoneMetadata {
+                room: Some("Kitchen".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            store.get("roon:1").unwrap().room.as_deref(),
+            Some("Kitchen")
+        );
+
+        store.set("roon:1", ZoneMetadata::default());
+        assert!(store.get("roon:1").is_none());
+        assert!(store.all().is_empty());
+    }
+
+    #[test]
+    fn test_compare_zones_by_weight_then_name() {
+        let first = ZoneMetadata {
+            sort_weight: Some(-5),
+            ..Default::default()
+        };
+        assert_eq!(
+            compare_zones((Some(&first), "Zeta"), (None, "Alpha")),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_zones((None, "Alpha"), (None, "Beta")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_by_room_groups_rooms_before_unassigned() {
+        let meta = |room: Option<&str>, sort_weight: Option<i32>| {
+            Some(ZoneMetadata {
+                room: room.map(str::to_string),
+                sort_weight,
+                ..Default::default()
+            })
+        };
+        let mut zones = vec![
+            (None, "Garage"),
+            (meta(Some("Kitchen"), None), "Pantry"),
+            (meta(Some("Lounge"), Some(-1)), "TV"),
+            (meta(Some("Kitchen"), None), "Island"),
+            (meta(None, Some(-10)), "Office"),
+        ];
+        sort_by_room(&mut zones, |(m, name)| (m.as_ref(), *name));
+        let names: Vec<&str> = zones.iter().map(|(_, name)| *name).collect();
+        assert_eq!(names, ["TV", "Island", "Pantry", "Office", "Garage"]);
+    }
+}
@@ -0,0 +1,185 @@
+//! Zone lookup by spoken or typed name
+//!
+//! Finds the zone meant by loose phrases like "the kitchen" or "living room
+//! speakers", for the simple endpoints, `/zones/resolve` and other callers
+//! that take a name instead of a zone_id. A zone answers to its display name,
+//! its room and any aliases in its metadata.
+
+use serde::Serialize;
+
+use crate::bus::Zone;
+
+/// Words that don't help tell zones apart ("the kitchen speaker")
+const FILLER_WORDS: &[&str] = &[
+    "the", "a", "an", "my", "our", "in", "on", "at", "zone", "speaker", "speakers", "player",
+];
+
+/// How well a name matched, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchQuality {
+    /// The query is the zone_id
+    Id,
+    /// Same words as a name
+    Exact,
+    /// Every query word appears in a name, in any order
+    Words,
+    /// A name starts with the query
+    Prefix,
+    /// A name contains the query
+    Contains,
+}
+
+/// A zone and the names it answers to; the first is its display name
+#[derive(Debug, Clone)]
+pub struct ZoneNames {
+    pub zone_id: String,
+    pub names: Vec<String>,
+}
+
+impl ZoneNames {
+    pub fn from_zone(zone: &Zone) -> Self {
+        let mut names = vec![zone.zone_name.clone()];
+        if let Some(metadata) = &zone.metadata {
+            names.extend(metadata.room.clone());
+            names.extend(metadata.aliases.iter().cloned());
+        }
+        Self {
+            zone_id: zone.zone_id.clone(),
+            names,
+        }
+    }
+}
+
+/// A zone matching a query
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZoneMatch {
+    pub zone_id: String,
+    pub zone_name: String,
+    /// The name that matched
+    pub matched: String,
+    pub quality: MatchQuality,
+}
+
+/// Lowercase words without filler; all words if nothing else is left
+fn words(s: &str) -> Vec<String> {
+    let all: Vec<String> = s
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let meaningful: Vec<String> = all
+        .iter()
+        .filter(|w| !FILLER_WORDS.contains(&w.as_str()))
+        .cloned()
+        .collect();
+    if meaningful.is_empty() {
+        all
+    } else {
+        meaningful
+    }
+}
+
+fn quality(query: &[String], name: &str) -> Option<MatchQuality> {
+    let name = words(name);
+    let (query_joined, name_joined) = (query.concat(), name.concat());
+    if query_joined == name_joined {
+        Some(MatchQuality::Exact)
+    } else if query.iter().all(|w| name.contains(w)) {
+        Some(MatchQuality::Words)
+    } else if name_joined.starts_with(&query_joined) {
+        Some(MatchQuality::Prefix)
+    } else if name_joined.contains(&query_joined) {
+        Some(MatchQuality::Contains)
+    } else {
+        None
+    }
+}
+
+/// Zones matching `query`, best first, each with its best matching name
+pub fn resolve(zones: &[ZoneNames], query: &str) -> Vec<ZoneMatch> {
+    let query = query.trim();
+    let query_words = words(query);
+    let mut matches: Vec<ZoneMatch> = zones
+        .iter()
+        .filter_map(|zone| {
+            let zone_name = zone.names.first().cloned().unwrap_or_default();
+            if zone.zone_id == query {
+                return Some(ZoneMatch {
+                    zone_id: zone.zone_id.clone(),
+                    matched: zone.zone_id.clone(),
+                    zone_name,
+                    quality: MatchQuality::Id,
+                });
+            }
+            if query_words.is_empty() {
+                return None;
+            }
+            zone.names
+                .iter()
+                .filter_map(|name| Some((quality(&query_words, name)?, name)))
+                .min_by_key(|(quality, _)| *quality)
+                .map(|(quality, name)| ZoneMatch {
+                    zone_id: zone.zone_id.clone(),
+                    zone_name,
+                    matched: name.clone(),
+                    quality,
+                })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        a.quality
+            .cmp(&b.quality)
+            .then_with(|| a.zone_name.cmp(&b.zone_name))
+    });
+    matches
+}
+
+/// The match to act on: the best one, if no other zone matched as well
+pub fn best(matches: &[ZoneMatch]) -> Option<&ZoneMatch> {
+    match matches {
+        [first, second, ..] if second.quality == first.quality => None,
+        [first, ..] => Some(first),
+        [] => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zones() -> Vec<ZoneNames> {
+        let zone = |id: &str, names: &[&str]| ZoneNames {
+            zone_id: id.to_string(),
+            names: names.iter().map(|n| n.to_string()).collect(),
+        };
+        vec![
+            zone("roon:1", &["Kitchen"]),
+            zone("lms:aa", &["Squeezebox Touch", "Living Room", "Lounge"]),
+            zone("upnp:x", &["Living Room TV"]),
+        ]
+    }
+
+    fn resolved(query: &str) -> Option<String> {
+        best(&resolve(&zones(), query)).map(|m| m.zone_id.clone())
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolved("kitchen").as_deref(), Some("roon:1"));
+        assert_eq!(resolved("the kitchen speaker").as_deref(), Some("roon:1"));
+        assert_eq!(resolved("lms:aa").as_deref(), Some("lms:aa"));
+        // The exact room name wins over the longer name containing it
+        assert_eq!(resolved("living-room").as_deref(), Some("lms:aa"));
+        assert_eq!(resolved("lounge").as_deref(), Some("lms:aa"));
+        assert_eq!(resolved("tv in the living room").as_deref(), Some("upnp:x"));
+        assert_eq!(resolved("squeeze").as_deref(), Some("lms:aa"));
+        assert_eq!(resolved("garage"), None);
+        assert_eq!(resolved("  "), None);
+
+        // Equally good matches are ambiguous
+        let matches = resolve(&zones(), "liv");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(best(&matches), None);
+    }
+}
@@ -7,8 +7,6 @@
 //! - Event bus integration
 //! - State consistency
 
-mod mock_servers;
-
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
@@ -130,6 +128,107 @@ mod hqplayer_integration {
         let result = adapter.set_volume(150).await;
         assert!(result.is_err()); // Can't set volume when disconnected
     }
+
+    /// Pipeline changes made outside the bridge publish HqpPipelineChanged
+    #[tokio::test]
+    async fn pipeline_check_publishes_external_changes() {
+        use unified_hifi_control::mock_servers::MockHqpServer;
+
+        let mock = MockHqpServer::start()
+            .await
+            .expect("mock HQPlayer should start");
+        let (bus, mut rx) = test_bus();
+        let adapter = HqpAdapter::new(bus);
+        adapter
+            .configure(
+                mock.addr().ip().to_string(),
+                Some(mock.addr().port()),
+                None,
+                None,
+                None,
+            )
+            .await;
+        adapter.connect().await.expect("connect to mock");
+
+        // First check records the baseline only
+        adapter.check_pipeline().await.expect("baseline check");
+        adapter.check_pipeline().await.expect("unchanged check");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut unexpected = false;
+        while let Ok(event) = rx.try_recv() {
+            unexpected |= matches!(event, BusEvent::HqpPipelineChanged { .. });
+        }
+        assert!(!unexpected, "No HqpPipelineChanged without a change");
+
+        mock.set_filter(1).await;
+        adapter.check_pipeline().await.expect("check after change");
+
+        let event = expect_event(
+            &mut rx,
+            |e| matches!(e, BusEvent::HqpPipelineChanged { .. }),
+            1000,
+        )
+        .await;
+        match event {
+            Some(BusEvent::HqpPipelineChanged { filter, .. }) => {
+                assert_eq!(filter.as_deref(), Some("closed-form"));
+            }
+            other => panic!("Expected HqpPipelineChanged, got {:?}", other),
+        }
+
+        adapter.disconnect().await;
+        mock.stop().await;
+    }
+
+    /// Pipeline status reads State and VolumeRange in one round trip and
+    /// reuses the option lists fetched while connecting
+    #[tokio::test]
+    async fn pipeline_status_batches_queries() {
+        use unified_hifi_control::adapters::ops;
+        use unified_hifi_control::mock_servers::MockHqpServer;
+
+        let mock = MockHqpServer::start()
+            .await
+            .expect("mock HQPlayer should start");
+        let (bus, _rx) = test_bus();
+        let adapter = HqpAdapter::new(bus);
+        adapter
+            .configure(
+                mock.addr().ip().to_string(),
+                Some(mock.addr().port()),
+                None,
+                None,
+                None,
+            )
+            .await;
+        adapter.connect().await.expect("connect to mock");
+
+        mock.set_filter(1).await;
+        let pipeline = adapter
+            .get_pipeline_status()
+            .await
+            .expect("pipeline status");
+        assert_eq!(pipeline.status.active_filter, "closed-form");
+        assert_eq!(pipeline.volume.min, -60);
+        assert_eq!(pipeline.settings.mode.options.len(), 2);
+        assert_eq!(pipeline.settings.samplerate.options.len(), 2);
+
+        let target = mock.addr().ip().to_string();
+        let operations: Vec<String> = ops::recent("hqplayer")
+            .into_iter()
+            .filter(|op| op.target.as_deref() == Some(target.as_str()))
+            .map(|op| op.operation)
+            .collect();
+        assert!(operations.iter().any(|op| op == "State+VolumeRange"));
+        assert!(
+            !operations.iter().any(|op| op.starts_with("Get")),
+            "Option lists should come from the cache, got {:?}",
+            operations
+        );
+
+        adapter.disconnect().await;
+        mock.stop().await;
+    }
 }
 
 // =============================================================================
@@ -201,10 +300,10 @@ mod lms_integration {
     /// when metadata changes, including when metadata is cleared.
     #[tokio::test]
     async fn polling_emits_now_playing_changed_including_cleared() {
-        use mock_servers::lms::MockLmsServer;
+        use unified_hifi_control::mock_servers::lms::MockLmsServer;
 
         // Start mock LMS server
-        let server = MockLmsServer::start().await;
+        let server = MockLmsServer::start().await.expect("mock LMS should start");
         let player_id = "aa:bb:cc:dd:ee:ff";
         server.add_player(player_id, "Test Player").await;
 
@@ -280,6 +379,58 @@ mod lms_integration {
 
         server.stop().await;
     }
+
+    /// Resync after a CLI reconnect publishes full state even when the cache
+    /// already matches the server (events missed while disconnected)
+    #[tokio::test]
+    async fn resync_publishes_state_for_unchanged_players() {
+        use unified_hifi_control::adapters::lms::create_lms_adapters;
+        use unified_hifi_control::mock_servers::lms::MockLmsServer;
+
+        let server = MockLmsServer::start().await.expect("mock LMS should start");
+        let player_id = "11:22:33:44:55:66";
+        server.add_player(player_id, "Kitchen").await;
+        server
+            .set_now_playing(player_id, "Resync Song", "Artist", "Album")
+            .await;
+        server.set_mode(player_id, "play").await;
+
+        let (bus, mut rx) = test_bus();
+        let (lms, cli) = create_lms_adapters(bus.clone());
+        let addr = server.addr();
+        lms.configure(addr.ip().to_string(), Some(addr.port()), None, None)
+            .await;
+
+        // Baseline poll fills the cache; a second poll has nothing to report
+        lms.update_players().await.expect("initial update");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        while rx.try_recv().is_ok() {}
+
+        cli.resync().await.expect("resync");
+
+        // Published in order: now playing, then state
+        let now_playing = expect_event(
+            &mut rx,
+            |e| matches!(e, BusEvent::NowPlayingChanged { title: Some(t), .. } if t == "Resync Song"),
+            1000,
+        )
+        .await;
+        assert!(
+            now_playing.is_some(),
+            "Expected NowPlayingChanged after resync"
+        );
+
+        let zone_id = PrefixedZoneId::lms(player_id);
+        let updated = expect_event(
+            &mut rx,
+            |e| matches!(e, BusEvent::ZoneUpdated { zone_id: z, .. } if *z == zone_id),
+            1000,
+        )
+        .await;
+        assert!(updated.is_some(), "Expected ZoneUpdated after resync");
+
+        server.stop().await;
+    }
 }
 
 // =============================================================================
@@ -508,12 +659,14 @@ mod error_handling {
 
 mod mock_server_tests {
     use super::*;
-    use crate::mock_servers::{MockHqpServer, MockLmsServer, MockOpenHomeDevice, MockUpnpRenderer};
+    use unified_hifi_control::mock_servers::{
+        MockHqpServer, MockLmsServer, MockOpenHomeDevice, MockUpnpRenderer,
+    };
 
     #[tokio::test]
     async fn lms_connects_to_mock_server() {
         // Start mock LMS server
-        let mock = MockLmsServer::start().await;
+        let mock = MockLmsServer::start().await.expect("mock LMS should start");
         mock.add_player("aa:bb:cc:dd:ee:ff", "Living Room").await;
         mock.add_player("11:22:33:44:55:66", "Kitchen").await;
 
@@ -558,7 +711,7 @@ mod mock_server_tests {
 
     #[tokio::test]
     async fn lms_mock_responds_to_status_query() {
-        let mock = MockLmsServer::start().await;
+        let mock = MockLmsServer::start().await.expect("mock LMS should start");
         mock.add_player("aa:bb:cc:dd:ee:ff", "Test Player").await;
         mock.set_mode("aa:bb:cc:dd:ee:ff", "play").await;
         mock.set_volume("aa:bb:cc:dd:ee:ff", 75).await;
@@ -598,7 +751,9 @@ mod mock_server_tests {
 
     #[tokio::test]
     async fn hqp_mock_responds_to_getinfo() {
-        let mock = MockHqpServer::start().await;
+        let mock = MockHqpServer::start()
+            .await
+            .expect("mock HQPlayer should start");
 
         // Use reqwest to test the mock directly (not through adapter)
         // because HQP adapter uses a complex TCP protocol
@@ -623,7 +778,9 @@ mod mock_server_tests {
 
     #[tokio::test]
     async fn upnp_mock_serves_description() {
-        let mock = MockUpnpRenderer::start().await;
+        let mock = MockUpnpRenderer::start()
+            .await
+            .expect("mock UPnP renderer should start");
 
         let client = reqwest::Client::new();
         let response = client
@@ -644,7 +801,9 @@ mod mock_server_tests {
 
     #[tokio::test]
     async fn openhome_mock_serves_metadata() {
-        let mock = MockOpenHomeDevice::start().await;
+        let mock = MockOpenHomeDevice::start()
+            .await
+            .expect("mock OpenHome device should start");
         mock.set_state("Playing").await;
         mock.set_volume(65).await;
         mock.set_track(
@@ -679,7 +838,7 @@ mod mock_server_tests {
     #[tokio::test]
     async fn lms_adapter_play_resumes_from_pause() {
         // Start mock server with player in paused state
-        let mock = MockLmsServer::start().await;
+        let mock = MockLmsServer::start().await.expect("mock LMS should start");
         let player_id = "aa:bb:cc:dd:ee:ff";
         mock.add_player(player_id, "Test Player").await;
         mock.set_mode(player_id, "pause").await;
@@ -725,7 +884,7 @@ mod mock_server_tests {
     #[tokio::test]
     async fn lms_adapter_play_starts_from_stopped() {
         // Start mock server with player in stopped state
-        let mock = MockLmsServer::start().await;
+        let mock = MockLmsServer::start().await.expect("mock LMS should start");
         let player_id = "aa:bb:cc:dd:ee:ff";
         mock.add_player(player_id, "Test Player").await;
         mock.set_mode(player_id, "stop").await;
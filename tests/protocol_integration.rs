@@ -267,6 +267,50 @@ mod protocol_endpoints {
         assert_eq!(status, StatusCode::OK);
         assert_json("/knob/devices", &body);
     }
+
+    #[tokio::test]
+    async fn zones_paged_reports_total() {
+        let app = create_test_app().await;
+        let (status, body) = get_body(&app, "/zones?limit=10&offset=0&fields=zone_name").await;
+        assert_eq!(status, StatusCode::OK);
+        let json: Value = serde_json::from_str(&body).unwrap();
+        assert!(json["zones"].is_array());
+        assert!(json["total"].is_u64());
+    }
+
+    #[tokio::test]
+    async fn zones_honours_if_none_match() {
+        let app = create_test_app().await;
+        let first = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/zones")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first
+            .headers()
+            .get("etag")
+            .expect("/zones should carry an ETag")
+            .clone();
+
+        let second = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/zones")
+                    .header("if-none-match", etag)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+    }
 }
 
 // =============================================================================
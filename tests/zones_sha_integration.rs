@@ -3,8 +3,6 @@
 //! This test verifies that the bridge emits zones_sha for dynamic zone detection.
 //! Issue #148: zones appearing after client starts should be detected.
 
-mod mock_servers;
-
 use serial_test::serial;
 
 use axum::{
@@ -19,7 +17,6 @@ use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 use tower::ServiceExt;
 
-use mock_servers::lms::MockLmsServer;
 use unified_hifi_control::adapters::hqplayer::{HqpInstanceManager, HqpZoneLinkService};
 use unified_hifi_control::adapters::lms::LmsAdapter;
 use unified_hifi_control::adapters::openhome::OpenHomeAdapter;
@@ -30,7 +27,9 @@ use unified_hifi_control::aggregator::ZoneAggregator;
 use unified_hifi_control::api::AppState;
 use unified_hifi_control::bus::create_bus;
 use unified_hifi_control::coordinator::AdapterCoordinator;
+use unified_hifi_control::knobs::store::KnobConfigUpdate;
 use unified_hifi_control::knobs::{self, KnobStore};
+use unified_hifi_control::mock_servers::lms::MockLmsServer;
 
 /// Response from /knob/now_playing - must include zones_sha
 #[derive(Debug, Deserialize)]
@@ -47,6 +46,13 @@ struct NowPlayingResponse {
 
 /// Create test app with LMS adapter connected to mock server
 async fn create_test_app_with_lms(mock_addr: std::net::SocketAddr) -> Router {
+    create_test_app_with_lms_and_knobs(mock_addr, KnobStore::new()).await
+}
+
+async fn create_test_app_with_lms_and_knobs(
+    mock_addr: std::net::SocketAddr,
+    knob_store: KnobStore,
+) -> Router {
     let bus = create_bus();
     let coordinator = Arc::new(AdapterCoordinator::new(bus.clone()));
 
@@ -67,7 +73,6 @@ async fn create_test_app_with_lms(mock_addr: std::net::SocketAddr) -> Router {
     let lms = Arc::new(LmsAdapter::new(bus.clone()));
     let openhome = Arc::new(OpenHomeAdapter::new(bus.clone()));
     let upnp = Arc::new(UPnPAdapter::new(bus.clone()));
-    let knob_store = KnobStore::new();
 
     // Configure and start LMS adapter with mock server
     lms.configure(
@@ -117,7 +122,7 @@ async fn now_playing_includes_zones_sha() {
     std::env::set_var("LMS_UNIFIEDHIFI_STARTED", "true");
 
     // Start mock LMS with a player
-    let mock = MockLmsServer::start().await;
+    let mock = MockLmsServer::start().await.expect("mock LMS should start");
     mock.add_player("aa:bb:cc:dd:ee:ff", "Test Player").await;
     mock.set_mode("aa:bb:cc:dd:ee:ff", "stop").await;
 
@@ -170,6 +175,59 @@ async fn now_playing_includes_zones_sha() {
     mock.stop().await;
 }
 
+/// Low bandwidth knobs get a poll hint and no optional fields, but keep zones_sha
+#[tokio::test]
+#[serial]
+async fn now_playing_trims_payload_for_low_bandwidth_knob() {
+    std::env::set_var("LMS_UNIFIEDHIFI_STARTED", "true");
+    let config_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("UHC_CONFIG_DIR", config_dir.path());
+
+    let mock = MockLmsServer::start().await.expect("mock LMS should start");
+    mock.add_player("aa:bb:cc:dd:ee:ff", "Test Player").await;
+
+    let knob_store = KnobStore::new();
+    knob_store.get_or_create("knob-1", None).await;
+    knob_store
+        .update_config(
+            "knob-1",
+            KnobConfigUpdate {
+                low_bandwidth: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let app = create_test_app_with_lms_and_knobs(mock.addr(), knob_store).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/knob/now_playing?zone_id=lms:aa:bb:cc:dd:ee:ff")
+                .header("x-knob-id", "knob-1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1024 * 1024)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert!(
+        json.get("zones").is_none(),
+        "zones should be stripped: {json}"
+    );
+    assert!(json.get("image_key").is_none());
+    assert_eq!(json["poll_interval_ms"], 3000);
+    assert!(json["zones_sha"].is_string());
+
+    mock.stop().await;
+    std::env::remove_var("UHC_CONFIG_DIR");
+}
+
 // Note: A test for "zones_sha changes when zones change" would require
 // enabling the LMS adapter in settings, which needs env var configuration.
 // The key regression test (zones_sha is present) is covered above.